@@ -0,0 +1,591 @@
+// 命令行模式: 第一个参数是已知的子命令时不启动界面, 直接打开应用数据库执行后退出.
+// 查询与导出以只读方式打开数据库, 可与正在运行的应用同时使用; 工作流执行引擎运行在前端界面中,
+// `run` 把执行加入执行队列, 由正在运行的应用执行, 命令行输出节点进度并在执行结束后写出最终输出
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use sqlx::SqlitePool;
+
+use crate::commands::chapter::{self, FullDraft};
+use crate::commands::{budget, daily_limit, queue, watchdog};
+use crate::db;
+
+/// 应用标识, 需与 `tauri.conf.json` 中的 identifier 保持一致 (数据库位于其配置目录下)
+const APP_IDENTIFIER: &str = "com.chouann.chouannnovel";
+
+/// 支持的书稿导出格式
+const MANUSCRIPT_FORMATS: &[&str] = &["txt"];
+
+/// `run` 检查执行状态的间隔
+const RUN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `run` 默认的等待时长 (秒), 超过后命令行退出, 执行仍由应用继续
+const DEFAULT_RUN_TIMEOUT_SECS: u64 = 1800;
+
+const USAGE: &str = "用法:
+  chouann-novel --list-workflows <项目 ID 或名称>
+  chouann-novel --export-manuscript <项目 ID 或名称> --format txt [--output <文件>]
+  chouann-novel run --workflow <工作流 ID> --input-file <文件> --output <文件> [--timeout <秒, 默认 1800>]";
+
+// 命令行子命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    ListWorkflows {
+        project: String,
+    },
+    ExportManuscript {
+        project: String,
+        format: String,
+        /// 不提供时输出到标准输出
+        output: Option<PathBuf>,
+    },
+    Run {
+        workflow_id: String,
+        input_file: PathBuf,
+        output: PathBuf,
+        /// 等待执行结束的最长时间
+        timeout: Duration,
+    },
+}
+
+// 读取 `--name value` 形式的选项, 重复或缺少值时报错
+fn options(args: &[String], allowed: &[&str]) -> Result<Vec<(String, String)>, String> {
+    let mut parsed: Vec<(String, String)> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !allowed.contains(&arg.as_str()) {
+            return Err(format!("未知参数: {}", arg));
+        }
+        let value = iter
+            .next()
+            .filter(|v| !v.starts_with("--"))
+            .ok_or_else(|| format!("{} 缺少参数值", arg))?;
+        if parsed.iter().any(|(name, _)| name == arg) {
+            return Err(format!("重复的参数: {}", arg));
+        }
+        parsed.push((arg.clone(), value.clone()));
+    }
+    Ok(parsed)
+}
+
+fn take(options: &mut Vec<(String, String)>, name: &str) -> Option<String> {
+    let index = options.iter().position(|(n, _)| n == name)?;
+    Some(options.remove(index).1)
+}
+
+fn required(options: &mut Vec<(String, String)>, name: &str) -> Result<String, String> {
+    take(options, name).ok_or_else(|| format!("缺少参数 {}", name))
+}
+
+/// 解析启动参数 (不含程序名); 第一个参数不是命令行子命令时返回 None, 按界面模式启动
+pub fn parse(args: &[String]) -> Option<Result<CliCommand, String>> {
+    let (first, rest) = args.split_first()?;
+    let result = match first.as_str() {
+        "--list-workflows" => match rest {
+            [project] if !project.starts_with("--") => Ok(CliCommand::ListWorkflows {
+                project: project.clone(),
+            }),
+            _ => Err("--list-workflows 需要且只接受一个项目参数".to_string()),
+        },
+        "--export-manuscript" => match rest.split_first() {
+            Some((project, rest)) if !project.starts_with("--") => {
+                options(rest, &["--format", "--output"]).and_then(|mut options| {
+                    let format = required(&mut options, "--format")?;
+                    if !MANUSCRIPT_FORMATS.contains(&format.as_str()) {
+                        return Err(format!("不支持的导出格式: {}", format));
+                    }
+                    Ok(CliCommand::ExportManuscript {
+                        project: project.clone(),
+                        format,
+                        output: take(&mut options, "--output").map(PathBuf::from),
+                    })
+                })
+            }
+            _ => Err("--export-manuscript 缺少项目参数".to_string()),
+        },
+        "run" => options(
+            rest,
+            &["--workflow", "--input-file", "--output", "--timeout"],
+        )
+        .and_then(|mut options| {
+            let timeout = match take(&mut options, "--timeout") {
+                Some(value) => value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| format!("--timeout 须为正整数秒数: {}", value))?,
+                None => DEFAULT_RUN_TIMEOUT_SECS,
+            };
+            Ok(CliCommand::Run {
+                workflow_id: required(&mut options, "--workflow")?,
+                input_file: required(&mut options, "--input-file")?.into(),
+                output: required(&mut options, "--output")?.into(),
+                timeout: Duration::from_secs(timeout),
+            })
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+// 应用数据库文件: tauri-plugin-sql 把相对路径的数据库放在应用配置目录下
+fn database_path() -> Result<PathBuf, String> {
+    let file = db::DB_URL.trim_start_matches("sqlite:");
+    let path = dirs::config_dir()
+        .ok_or_else(|| "无法确定应用配置目录".to_string())?
+        .join(APP_IDENTIFIER)
+        .join(file);
+    if !path.exists() {
+        return Err(format!(
+            "未找到应用数据库 {}, 请先启动一次应用",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+// `run` 需要写入执行队列, 其余子命令只读
+async fn open(writable: bool) -> Result<SqlitePool, String> {
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(database_path()?)
+        .read_only(!writable);
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("打开数据库失败: {}", e))
+}
+
+// 按 ID 或名称查找项目
+async fn resolve_project(pool: &SqlitePool, project: &str) -> Result<String, String> {
+    sqlx::query_scalar(
+        "SELECT id FROM projects WHERE id = ? OR name = ? ORDER BY id = ? DESC LIMIT 1",
+    )
+    .bind(project)
+    .bind(project)
+    .bind(project)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("项目不存在: {}", project))
+}
+
+/// 项目的工作流列表, 每行 `<ID>\t<名称>`, 按创建时间排序
+pub async fn list_workflows(pool: &SqlitePool, project: &str) -> Result<String, String> {
+    let project_id = resolve_project(pool, project).await?;
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, name FROM workflows WHERE project_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(&project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name)| format!("{}\t{}\n", id, name))
+        .collect())
+}
+
+/// 把全书草稿排版为纯文本: 书名与作者, 然后依次为各章标题与正文
+pub fn manuscript_txt(draft: &FullDraft) -> String {
+    let mut text = draft.title.clone();
+    if let Some(author) = draft.author.as_deref().filter(|a| !a.trim().is_empty()) {
+        text.push_str(&format!("\n作者: {}", author));
+    }
+    text.push('\n');
+    for chapter in &draft.chapters {
+        text.push_str(&format!("\n\n{}\n", chapter.title));
+        let content = chapter.content.trim_end();
+        if !content.is_empty() {
+            text.push_str(&format!("\n{}\n", content));
+        }
+    }
+    text
+}
+
+/// 导出项目书稿
+pub async fn export_manuscript(pool: &SqlitePool, project: &str) -> Result<String, String> {
+    let project_id = resolve_project(pool, project).await?;
+    let draft = chapter::full_draft(pool, &project_id).await?;
+    Ok(manuscript_txt(&draft))
+}
+
+/// 以输入文件的内容作为输入, 把工作流执行加入执行队列 (检查项目预算与每日执行上限), 返回执行 ID.
+/// 没有正在运行的应用实例时不加入队列, 否则命令行会一直等待
+pub async fn enqueue_run(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    input_file: &Path,
+) -> Result<String, String> {
+    let input = std::fs::read_to_string(input_file)
+        .map_err(|e| format!("无法读取输入文件 {}: {}", input_file.display(), e))?;
+    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM workflows WHERE id = ?")
+        .bind(workflow_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+    budget::ensure_workflow_within_budget(pool, workflow_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    daily_limit::ensure_within_daily_limit(pool, workflow_id, 1)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !watchdog::app_running(pool, chrono::Utc::now()).await? {
+        return Err("未检测到正在运行的应用, 请先启动应用再执行 run".to_string());
+    }
+    let execution = queue::enqueue(pool, workflow_id, Some(&input), "normal").await?;
+    Ok(execution.id)
+}
+
+// 节点结果状态的进度文字
+fn progress_label(status: &str) -> &str {
+    match status {
+        "running" => "开始",
+        "completed" => "完成",
+        "failed" => "失败",
+        other => other,
+    }
+}
+
+/// 等待执行结束, 节点开始、完成或失败时通过 progress 报告进度: 完成时返回最终输出;
+/// 执行失败或取消、应用在执行结束前退出, 以及超过 timeout 时报错
+pub async fn wait_for_execution(
+    pool: &SqlitePool,
+    execution_id: &str,
+    interval: Duration,
+    timeout: Duration,
+    mut progress: impl FnMut(&str),
+) -> Result<String, String> {
+    let deadline = Instant::now() + timeout;
+    let mut reported: HashMap<String, String> = HashMap::new();
+    loop {
+        let row: Option<(String, Option<String>)> =
+            sqlx::query_as("SELECT status, final_output FROM executions WHERE id = ?")
+                .bind(execution_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        let (status, output) = row.ok_or_else(|| format!("执行记录已被删除: {}", execution_id))?;
+
+        let nodes: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT r.id, COALESCE(n.name, r.node_id), r.status FROM node_results r
+             LEFT JOIN nodes n ON n.id = r.node_id
+             WHERE r.execution_id = ? ORDER BY r.started_at, r.rowid",
+        )
+        .bind(execution_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (id, name, node_status) in nodes {
+            if reported.get(&id) != Some(&node_status) {
+                progress(&format!("{} {}", name, progress_label(&node_status)));
+                reported.insert(id, node_status);
+            }
+        }
+
+        match status.as_str() {
+            "completed" => return Ok(output.unwrap_or_default()),
+            "queued" | "running" | "paused" => {
+                if !watchdog::app_running(pool, chrono::Utc::now()).await? {
+                    return Err(format!("应用已退出, 执行尚未结束 (状态: {})", status));
+                }
+                if Instant::now() >= deadline {
+                    return Err(format!(
+                        "等待超过 {} 秒, 执行仍在进行 (状态: {}), 结果可稍后在应用中查看",
+                        timeout.as_secs(),
+                        status
+                    ));
+                }
+                tokio::time::sleep(interval).await;
+            }
+            other => return Err(format!("执行未成功完成 (状态: {})", other)),
+        }
+    }
+}
+
+async fn execute(command: CliCommand) -> Result<(), String> {
+    let pool = open(matches!(command, CliCommand::Run { .. })).await?;
+    match command {
+        CliCommand::ListWorkflows { project } => {
+            print!("{}", list_workflows(&pool, &project).await?);
+        }
+        CliCommand::ExportManuscript {
+            project, output, ..
+        } => {
+            let text = export_manuscript(&pool, &project).await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text).map_err(|e| format!("写入文件失败: {}", e))?;
+                    println!("已导出到 {}", path.display());
+                }
+                None => print!("{}", text),
+            }
+        }
+        CliCommand::Run {
+            workflow_id,
+            input_file,
+            output,
+            timeout,
+        } => {
+            let execution_id = enqueue_run(&pool, &workflow_id, &input_file).await?;
+            println!(
+                "已加入执行队列 (执行 ID: {}), 等待应用运行; 请保持应用打开",
+                execution_id
+            );
+            let text =
+                wait_for_execution(&pool, &execution_id, RUN_POLL_INTERVAL, timeout, |line| {
+                    println!("{}", line)
+                })
+                .await?;
+            std::fs::write(&output, text).map_err(|e| format!("写入文件失败: {}", e))?;
+            println!("已写入 {}", output.display());
+        }
+    }
+    Ok(())
+}
+
+// Windows 发布版使用 GUI 子系统, 进程没有控制台; 命令行模式下附加到启动它的终端, 使输出可见.
+// 不是从终端启动 (没有父控制台) 时附加失败, 忽略即可
+#[cfg(windows)]
+fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// 命令行入口: 参数是命令行子命令时执行并返回退出码 (0 成功, 1 失败, 2 参数错误), 否则返回 None
+pub fn run(args: &[String]) -> Option<i32> {
+    let parsed = parse(args)?;
+    attach_parent_console();
+    let command = match parsed {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+    match tauri::async_runtime::block_on(execute(command)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_cli_commands() {
+        // 非子命令 (如双击关联文件传入的路径) 按界面模式启动
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&args(&["/tmp/备份.chouann"])), None);
+
+        assert_eq!(
+            parse(&args(&["--list-workflows", "雾城"])),
+            Some(Ok(CliCommand::ListWorkflows {
+                project: "雾城".to_string()
+            }))
+        );
+        assert!(matches!(parse(&args(&["--list-workflows"])), Some(Err(_))));
+        assert_eq!(
+            parse(&args(&["--export-manuscript", "p1", "--format", "txt"])),
+            Some(Ok(CliCommand::ExportManuscript {
+                project: "p1".to_string(),
+                format: "txt".to_string(),
+                output: None
+            }))
+        );
+        assert!(matches!(
+            parse(&args(&["--export-manuscript", "p1", "--format", "epub"])),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse(&args(&["--export-manuscript", "--format", "txt"])),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse(&args(&[
+                "run",
+                "--input-file",
+                "chapter12_outline.txt",
+                "--workflow",
+                "w1",
+                "--output",
+                "out.txt"
+            ])),
+            Some(Ok(CliCommand::Run {
+                workflow_id: "w1".to_string(),
+                input_file: "chapter12_outline.txt".into(),
+                output: "out.txt".into(),
+                timeout: Duration::from_secs(DEFAULT_RUN_TIMEOUT_SECS)
+            }))
+        );
+        assert!(matches!(
+            parse(&args(&[
+                "run", "--workflow", "w1", "--input-file", "in.txt", "--output", "out.txt",
+                "--timeout", "60"
+            ])),
+            Some(Ok(CliCommand::Run { timeout, .. })) if timeout == Duration::from_secs(60)
+        ));
+        assert!(matches!(
+            parse(&args(&[
+                "run",
+                "--workflow",
+                "w1",
+                "--input-file",
+                "in.txt",
+                "--output",
+                "out.txt",
+                "--timeout",
+                "0"
+            ])),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse(&args(&["run", "--workflow", "w1", "--output"])),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse(&args(&["run", "--workflow", "w1", "--workflow", "w2"])),
+            Some(Err(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn lists_workflows_and_exports_manuscript() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name, author) VALUES ('p1', '雾城', '林雪');
+             INSERT INTO workflows (id, project_id, name, created_at) VALUES
+                 ('w2', 'p1', '润色', '2026-01-02T00:00:00.000Z'),
+                 ('w1', 'p1', '正文生成', '2026-01-01T00:00:00.000Z');
+             INSERT INTO chapters (id, project_id, title, order_index) VALUES
+                 ('c1', 'p1', '第一章', 0), ('c2', 'p1', '第二章', 1);
+             INSERT INTO executions (id, workflow_id, status, final_output, chapter_id, started_at) VALUES
+                 ('e1', 'w1', 'completed', '雨夜。\n', 'c1', '2026-01-01T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let by_name = list_workflows(&pool, "雾城").await.unwrap();
+        assert_eq!(by_name, "w1\t正文生成\nw2\t润色\n");
+        assert_eq!(list_workflows(&pool, "p1").await.unwrap(), by_name);
+        assert!(list_workflows(&pool, "missing").await.is_err());
+
+        assert_eq!(
+            export_manuscript(&pool, "p1").await.unwrap(),
+            "雾城\n作者: 林雪\n\n\n第一章\n\n雨夜。\n\n\n第二章\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_enqueues_and_waits_for_the_app() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '雾城');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO nodes (id, workflow_id, type, name, order_index) VALUES
+                 ('n1', 'w1', 'ai_chat', '撰写正文', 0);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let input_file = std::env::temp_dir().join(format!("cli-run-{}.txt", db::new_id()));
+        std::fs::write(&input_file, "第十二章大纲").unwrap();
+        let wait = Duration::from_millis(10);
+        let timeout = Duration::from_secs(5);
+
+        // 应用未运行时不加入队列
+        assert!(enqueue_run(&pool, "w1", &input_file).await.is_err());
+        watchdog::record_app_alive(&pool).await.unwrap();
+
+        let execution_id = enqueue_run(&pool, "w1", &input_file).await.unwrap();
+        assert!(enqueue_run(&pool, "missing", &input_file).await.is_err());
+        assert!(
+            enqueue_run(&pool, "w1", Path::new("/nonexistent/input.txt"))
+                .await
+                .is_err()
+        );
+        std::fs::remove_file(&input_file).unwrap();
+
+        // 模拟应用取出并运行排队的执行
+        let app = {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                let claimed = queue::claim_next(&pool).await.unwrap().unwrap();
+                assert_eq!(claimed.input.as_deref(), Some("第十二章大纲"));
+                sqlx::query(
+                    "INSERT INTO node_results (id, execution_id, node_id, status)
+                     VALUES ('r1', ?, 'n1', 'completed')",
+                )
+                .bind(&claimed.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+                sqlx::query(
+                    "UPDATE executions SET status = 'completed', final_output = '雨夜。' WHERE id = ?",
+                )
+                .bind(&claimed.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            })
+        };
+        let mut progress = Vec::new();
+        let output = wait_for_execution(&pool, &execution_id, wait, timeout, |line| {
+            progress.push(line.to_string())
+        })
+        .await
+        .unwrap();
+        app.await.unwrap();
+        assert_eq!(output, "雨夜。");
+        assert_eq!(progress, ["撰写正文 完成"]);
+
+        sqlx::query("UPDATE executions SET status = 'failed' WHERE id = ?")
+            .bind(&execution_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(
+            wait_for_execution(&pool, &execution_id, wait, timeout, |_| {})
+                .await
+                .is_err()
+        );
+        assert!(wait_for_execution(&pool, "missing", wait, timeout, |_| {})
+            .await
+            .is_err());
+
+        // 执行未结束: 超过等待时长, 或应用已退出时报错
+        let queued = queue::enqueue(&pool, "w1", None, "normal").await.unwrap();
+        assert!(
+            wait_for_execution(&pool, &queued.id, wait, Duration::from_millis(30), |_| {})
+                .await
+                .unwrap_err()
+                .contains("等待超过")
+        );
+        sqlx::query("UPDATE global_config SET app_heartbeat_at = '2026-01-01T00:00:00.000Z'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(wait_for_execution(&pool, &queued.id, wait, timeout, |_| {})
+            .await
+            .unwrap_err()
+            .contains("应用已退出"));
+    }
+}
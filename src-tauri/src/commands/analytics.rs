@@ -0,0 +1,226 @@
+// 本地使用统计: 仅写入本地数据库, 不会上传到任何地方, 需用户主动开启
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 允许记录的事件类型
+pub const EVENT_KINDS: &[&str] = &[
+    "execution_started",
+    "execution_finished",
+    "chapter_accepted",
+    "import_used",
+    "export_used",
+];
+
+// 按事件类型汇总
+#[derive(Debug, Serialize)]
+pub struct EventKindCount {
+    pub kind: String,
+    pub count: i64,
+}
+
+// 按日期汇总
+#[derive(Debug, Serialize)]
+pub struct EventDayCount {
+    pub date: String,
+    pub count: i64,
+}
+
+// 使用统计汇总
+#[derive(Debug, Serialize)]
+pub struct AppActivitySummary {
+    pub period: String,
+    pub total_events: i64,
+    pub by_kind: Vec<EventKindCount>,
+    pub by_day: Vec<EventDayCount>,
+}
+
+/// 本地统计是否已开启 (默认关闭)
+pub async fn is_enabled(pool: &SqlitePool) -> Result<bool, String> {
+    let enabled: Option<Option<bool>> =
+        sqlx::query_scalar("SELECT local_analytics_enabled FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(enabled.flatten().unwrap_or(false))
+}
+
+/// 记录一条事件, 未开启统计时直接忽略; 返回是否实际写入
+pub async fn record(
+    pool: &SqlitePool,
+    kind: &str,
+    payload: Option<&serde_json::Value>,
+) -> Result<bool, String> {
+    if !EVENT_KINDS.contains(&kind) {
+        return Err(format!("未知的事件类型: {}", kind));
+    }
+    if !is_enabled(pool).await? {
+        return Ok(false);
+    }
+
+    let payload = payload.map(|p| p.to_string());
+    sqlx::query("INSERT INTO app_events (id, kind, payload, created_at) VALUES (?, ?, ?, ?)")
+        .bind(db::new_id())
+        .bind(kind)
+        .bind(payload)
+        .bind(db::now())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 开启或关闭本地统计
+pub async fn set_enabled(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    sqlx::query("UPDATE global_config SET local_analytics_enabled = ? WHERE id = 1")
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 统计周期对应的起始时间, None 表示全部
+fn period_start(period: &str) -> Result<Option<String>, String> {
+    let days = match period {
+        "day" => 1,
+        "week" => 7,
+        "month" => 30,
+        "all" => return Ok(None),
+        _ => return Err(format!("不支持的统计周期: {}", period)),
+    };
+    Ok(Some(db::to_iso(
+        chrono::Utc::now() - chrono::Duration::days(days),
+    )))
+}
+
+/// 汇总指定周期内的事件
+pub async fn summarize(pool: &SqlitePool, period: &str) -> Result<AppActivitySummary, String> {
+    // 全部周期使用最小字符串作为下界, 统一查询语句
+    let since = period_start(period)?.unwrap_or_default();
+
+    let by_kind: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT kind, COUNT(*) FROM app_events WHERE created_at >= ?
+         GROUP BY kind ORDER BY COUNT(*) DESC, kind",
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let by_day: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT substr(created_at, 1, 10) AS day, COUNT(*) FROM app_events
+         WHERE created_at >= ? GROUP BY day ORDER BY day",
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(AppActivitySummary {
+        period: period.to_string(),
+        total_events: by_kind.iter().map(|(_, count)| count).sum(),
+        by_kind: by_kind
+            .into_iter()
+            .map(|(kind, count)| EventKindCount { kind, count })
+            .collect(),
+        by_day: by_day
+            .into_iter()
+            .map(|(date, count)| EventDayCount { date, count })
+            .collect(),
+    })
+}
+
+/// 清空全部事件, 返回删除条数
+pub async fn purge(pool: &SqlitePool) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM app_events")
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected())
+}
+
+#[tauri::command]
+pub async fn set_local_analytics_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_enabled(&pool, enabled).await
+}
+
+#[tauri::command]
+pub async fn record_app_event(
+    app: AppHandle,
+    kind: String,
+    payload: Option<serde_json::Value>,
+) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    record(&pool, &kind, payload.as_ref()).await
+}
+
+#[tauri::command]
+pub async fn get_app_activity_summary(
+    app: AppHandle,
+    period: String,
+) -> Result<AppActivitySummary, String> {
+    let pool = db::pool(&app).await?;
+    summarize(&pool, &period).await
+}
+
+#[tauri::command]
+pub async fn purge_app_events(app: AppHandle) -> Result<u64, String> {
+    let pool = db::pool(&app).await?;
+    purge(&pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn event_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM app_events")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_nothing_when_disabled() {
+        let pool = db::test_pool().await;
+        assert!(!is_enabled(&pool).await.unwrap());
+
+        for kind in EVENT_KINDS {
+            assert!(!record(&pool, kind, None).await.unwrap());
+        }
+        assert_eq!(event_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn summarizes_recorded_events() {
+        let pool = db::test_pool().await;
+        set_enabled(&pool, true).await.unwrap();
+
+        let payload = serde_json::json!({ "workflow_id": "w1" });
+        record(&pool, "execution_started", Some(&payload))
+            .await
+            .unwrap();
+        record(&pool, "execution_started", None).await.unwrap();
+        record(&pool, "export_used", None).await.unwrap();
+
+        let summary = summarize(&pool, "week").await.unwrap();
+        assert_eq!(summary.total_events, 3);
+        assert_eq!(summary.by_kind[0].kind, "execution_started");
+        assert_eq!(summary.by_kind[0].count, 2);
+        assert_eq!(summary.by_day.len(), 1);
+
+        assert_eq!(purge(&pool).await.unwrap(), 3);
+        assert_eq!(summarize(&pool, "all").await.unwrap().total_events, 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_kind_and_period() {
+        let pool = db::test_pool().await;
+        assert!(record(&pool, "keystroke", None).await.is_err());
+        assert!(summarize(&pool, "year").await.is_err());
+    }
+}
@@ -0,0 +1,439 @@
+// 输出批注: 锚定在执行最终输出或章节正文某个文本区间上的审阅意见. 偏移量按字符 (Unicode 标量) 计,
+// 创建时记录区间内的文本, 之后正文变化时据此重新定位, 找不到时标记为孤立批注
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::chapter;
+use crate::db;
+
+/// 批注类型: 批注、问题、建议
+pub const ANNOTATION_KINDS: &[&str] = &["note", "issue", "suggestion"];
+
+// 批注对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnnotationTarget {
+    Execution { id: String },
+    Chapter { id: String },
+}
+
+// 文本区间上的批注
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct Annotation {
+    pub id: String,
+    pub execution_id: Option<String>,
+    pub chapter_id: Option<String>,
+    pub start_offset: i64,
+    pub end_offset: i64,
+    /// 创建时区间内的文本
+    pub anchor_text: String,
+    pub note: String,
+    pub kind: String,
+    /// 正文中已找不到 anchor_text, 偏移量为最后一次定位的位置
+    pub orphaned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 按当前正文重新定位的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anchor {
+    /// 原区间内的文本未变
+    Unchanged,
+    /// 文本移动到了新的区间
+    Moved(i64, i64),
+    Orphaned,
+}
+
+fn char_slice(text: &str, start: i64, end: i64) -> Option<&str> {
+    if start < 0 || end < start {
+        return None;
+    }
+    let mut indices = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()));
+    let start_byte = indices.nth(start as usize)?;
+    let end_byte = if end == start {
+        start_byte
+    } else {
+        indices.nth((end - start - 1) as usize)?
+    };
+    Some(&text[start_byte..end_byte])
+}
+
+/// 在当前正文中定位批注: 原区间文本未变时保持不动, 否则取与原位置最近的相同文本
+pub fn reanchor(text: &str, start: i64, end: i64, anchor_text: &str) -> Anchor {
+    if char_slice(text, start, end) == Some(anchor_text) {
+        return Anchor::Unchanged;
+    }
+    let len = anchor_text.chars().count() as i64;
+    text.match_indices(anchor_text)
+        .map(|(byte, _)| text[..byte].chars().count() as i64)
+        .min_by_key(|candidate| (candidate - start).abs())
+        .map_or(Anchor::Orphaned, |new_start| {
+            Anchor::Moved(new_start, new_start + len)
+        })
+}
+
+fn target_columns(target: &AnnotationTarget) -> (&'static str, &str) {
+    match target {
+        AnnotationTarget::Execution { id } => ("execution_id", id),
+        AnnotationTarget::Chapter { id } => ("chapter_id", id),
+    }
+}
+
+/// 批注对象的当前文本: 执行的最终输出, 或章节正文 (与全书草稿导出一致)
+pub async fn target_text(pool: &SqlitePool, target: &AnnotationTarget) -> Result<String, String> {
+    match target {
+        AnnotationTarget::Execution { id } => {
+            let output: Option<Option<String>> =
+                sqlx::query_scalar("SELECT final_output FROM executions WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let output = output.ok_or_else(|| format!("执行记录不存在: {}", id))?;
+            Ok(output.unwrap_or_default())
+        }
+        AnnotationTarget::Chapter { id } => chapter::content(pool, id).await,
+    }
+}
+
+fn validate_note(note: &str, kind: &str) -> Result<(), String> {
+    if note.trim().is_empty() {
+        return Err("批注内容不能为空".to_string());
+    }
+    if !ANNOTATION_KINDS.contains(&kind) {
+        return Err(format!("不支持的批注类型: {}", kind));
+    }
+    Ok(())
+}
+
+/// 获取单条批注
+pub async fn get(pool: &SqlitePool, annotation_id: &str) -> Result<Annotation, String> {
+    sqlx::query_as::<_, Annotation>("SELECT * FROM output_annotations WHERE id = ?")
+        .bind(annotation_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("批注不存在: {}", annotation_id))
+}
+
+/// 创建批注, 区间须在当前文本长度之内且不为空
+pub async fn create(
+    pool: &SqlitePool,
+    target: &AnnotationTarget,
+    start_offset: i64,
+    end_offset: i64,
+    note: &str,
+    kind: &str,
+) -> Result<Annotation, String> {
+    validate_note(note, kind)?;
+    let text = target_text(pool, target).await?;
+    let length = text.chars().count() as i64;
+    if start_offset < 0 || start_offset >= end_offset || end_offset > length {
+        return Err(format!(
+            "批注区间无效: {}..{}, 文本长度为 {}",
+            start_offset, end_offset, length
+        ));
+    }
+    let anchor_text = char_slice(&text, start_offset, end_offset)
+        .unwrap_or_default()
+        .to_string();
+
+    let now = db::now();
+    let (execution_id, chapter_id) = match target {
+        AnnotationTarget::Execution { id } => (Some(id.clone()), None),
+        AnnotationTarget::Chapter { id } => (None, Some(id.clone())),
+    };
+    let annotation = Annotation {
+        id: db::new_id(),
+        execution_id,
+        chapter_id,
+        start_offset,
+        end_offset,
+        anchor_text,
+        note: note.trim().to_string(),
+        kind: kind.to_string(),
+        orphaned: false,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO output_annotations
+             (id, execution_id, chapter_id, start_offset, end_offset, anchor_text, note, kind,
+              orphaned, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?)",
+    )
+    .bind(&annotation.id)
+    .bind(&annotation.execution_id)
+    .bind(&annotation.chapter_id)
+    .bind(annotation.start_offset)
+    .bind(annotation.end_offset)
+    .bind(&annotation.anchor_text)
+    .bind(&annotation.note)
+    .bind(&annotation.kind)
+    .bind(&annotation.created_at)
+    .bind(&annotation.updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(annotation)
+}
+
+/// 修改批注内容与类型, 区间不变
+pub async fn update(
+    pool: &SqlitePool,
+    annotation_id: &str,
+    note: &str,
+    kind: &str,
+) -> Result<Annotation, String> {
+    validate_note(note, kind)?;
+    let mut annotation = get(pool, annotation_id).await?;
+    annotation.note = note.trim().to_string();
+    annotation.kind = kind.to_string();
+    annotation.updated_at = db::now();
+    sqlx::query("UPDATE output_annotations SET note = ?, kind = ?, updated_at = ? WHERE id = ?")
+        .bind(&annotation.note)
+        .bind(&annotation.kind)
+        .bind(&annotation.updated_at)
+        .bind(annotation_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(annotation)
+}
+
+/// 删除批注, 返回是否存在
+pub async fn delete(pool: &SqlitePool, annotation_id: &str) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM output_annotations WHERE id = ?")
+        .bind(annotation_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 列出批注: 先按当前文本重新定位并保存变化, 已定位的按区间排序在前, 孤立批注在后
+pub async fn list(pool: &SqlitePool, target: &AnnotationTarget) -> Result<Vec<Annotation>, String> {
+    let text = target_text(pool, target).await?;
+    let (column, id) = target_columns(target);
+    let mut annotations = sqlx::query_as::<_, Annotation>(&format!(
+        "SELECT * FROM output_annotations WHERE {} = ? ORDER BY created_at, rowid",
+        column
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for annotation in &mut annotations {
+        let anchor = reanchor(
+            &text,
+            annotation.start_offset,
+            annotation.end_offset,
+            &annotation.anchor_text,
+        );
+        let (start, end, orphaned) = match anchor {
+            Anchor::Unchanged => (annotation.start_offset, annotation.end_offset, false),
+            Anchor::Moved(start, end) => (start, end, false),
+            Anchor::Orphaned => (annotation.start_offset, annotation.end_offset, true),
+        };
+        if (start, end, orphaned)
+            == (
+                annotation.start_offset,
+                annotation.end_offset,
+                annotation.orphaned,
+            )
+        {
+            continue;
+        }
+        annotation.start_offset = start;
+        annotation.end_offset = end;
+        annotation.orphaned = orphaned;
+        sqlx::query(
+            "UPDATE output_annotations SET start_offset = ?, end_offset = ?, orphaned = ? WHERE id = ?",
+        )
+        .bind(start)
+        .bind(end)
+        .bind(orphaned)
+        .bind(&annotation.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    annotations.sort_by_key(|a| (a.orphaned, a.start_offset, a.end_offset));
+    Ok(annotations)
+}
+
+/// 在正文中插入 Markdown 脚注标记 `[^n]`, 编号从 `first_number` 开始; 返回插入后的正文与脚注定义.
+/// 孤立批注没有位置, 不输出
+pub fn with_footnotes(
+    text: &str,
+    annotations: &[Annotation],
+    first_number: usize,
+) -> (String, Vec<String>) {
+    let mut anchored: Vec<&Annotation> = annotations.iter().filter(|a| !a.orphaned).collect();
+    anchored.sort_by_key(|a| (a.end_offset, a.start_offset));
+
+    let mut output = String::with_capacity(text.len());
+    let mut definitions = Vec::with_capacity(anchored.len());
+    let mut pending = anchored.iter().peekable();
+    let mut emit = |output: &mut String, annotation: &Annotation| {
+        let number = first_number + definitions.len();
+        output.push_str(&format!("[^{}]", number));
+        let label = match annotation.kind.as_str() {
+            "issue" => "问题",
+            "suggestion" => "建议",
+            _ => "批注",
+        };
+        let note = annotation
+            .note
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        definitions.push(format!("[^{}]: [{}] {}", number, label, note));
+    };
+    for (index, ch) in text.chars().enumerate() {
+        while let Some(annotation) = pending.next_if(|a| a.end_offset <= index as i64) {
+            emit(&mut output, annotation);
+        }
+        output.push(ch);
+    }
+    for annotation in pending {
+        emit(&mut output, annotation);
+    }
+    (output, definitions)
+}
+
+#[tauri::command]
+pub async fn create_annotation(
+    app: AppHandle,
+    target: AnnotationTarget,
+    start_offset: i64,
+    end_offset: i64,
+    note: String,
+    kind: Option<String>,
+) -> Result<Annotation, String> {
+    let pool = db::pool(&app).await?;
+    let kind = kind.unwrap_or_else(|| "note".to_string());
+    create(&pool, &target, start_offset, end_offset, &note, &kind).await
+}
+
+#[tauri::command]
+pub async fn update_annotation(
+    app: AppHandle,
+    annotation_id: String,
+    note: String,
+    kind: String,
+) -> Result<Annotation, String> {
+    let pool = db::pool(&app).await?;
+    update(&pool, &annotation_id, &note, &kind).await
+}
+
+#[tauri::command]
+pub async fn delete_annotation(app: AppHandle, annotation_id: String) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    delete(&pool, &annotation_id).await
+}
+
+#[tauri::command]
+pub async fn list_annotations(
+    app: AppHandle,
+    target: AnnotationTarget,
+) -> Result<Vec<Annotation>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &target).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reanchors_by_anchor_text() {
+        let text = "林雪推开门。林雪笑了。";
+        assert_eq!(char_slice(text, 6, 8), Some("林雪"));
+        assert_eq!(char_slice(text, 0, 12), None);
+        assert_eq!(reanchor(text, 6, 8, "林雪"), Anchor::Unchanged);
+        // 前面插入文字后取与原位置最近的一处
+        assert_eq!(
+            reanchor("雨，林雪推开门。林雪笑了。", 6, 8, "林雪"),
+            Anchor::Moved(8, 10)
+        );
+        assert_eq!(reanchor(text, 2, 5, "推开窗"), Anchor::Orphaned);
+    }
+
+    #[tokio::test]
+    async fn annotates_and_reanchors_chapter_text() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO chapters (id, project_id, title, order_index) VALUES ('c1', 'p1', '第一章', 0);
+            INSERT INTO executions (id, workflow_id, chapter_id, status, final_output, started_at)
+                VALUES ('e1', 'w1', 'c1', 'completed', '林雪推开门。她笑了。', '2026-01-02T00:00:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let chapter = AnnotationTarget::Chapter {
+            id: "c1".to_string(),
+        };
+
+        let issue = create(&pool, &chapter, 6, 9, "这里人设崩了", "issue")
+            .await
+            .unwrap();
+        assert_eq!(issue.anchor_text, "她笑了");
+        let note = create(&pool, &chapter, 0, 2, "主角", "note").await.unwrap();
+        assert!(create(&pool, &chapter, 5, 11, "越界", "note")
+            .await
+            .is_err());
+        assert!(create(&pool, &chapter, 3, 3, "空区间", "note")
+            .await
+            .is_err());
+        assert!(create(&pool, &chapter, 0, 2, " ", "note").await.is_err());
+        assert!(create(&pool, &chapter, 0, 2, "主角", "praise")
+            .await
+            .is_err());
+
+        // 前面多出一段输出: 批注随文本移动; 原文本被改写: 标记为孤立
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO executions (id, workflow_id, chapter_id, status, final_output, started_at)
+                VALUES ('e0', 'w1', 'c1', 'completed', '雨夜。', '2026-01-01T00:00:00.000Z');
+            UPDATE executions SET final_output = '林雪推开门。她转身离开。' WHERE id = 'e1';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let found = list(&pool, &chapter).await.unwrap();
+        assert_eq!(found[0].id, note.id);
+        assert_eq!((found[0].start_offset, found[0].end_offset), (5, 7));
+        assert!(found[1].orphaned && found[1].id == issue.id);
+
+        let updated = update(&pool, &issue.id, "她不会这样离开", "suggestion")
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &issue.id).await.unwrap(), updated);
+
+        let (text, definitions) = with_footnotes("雨夜。\n\n林雪推开门。", &found, 3);
+        assert_eq!(text, "雨夜。\n\n林雪[^3]推开门。");
+        assert_eq!(definitions, ["[^3]: [批注] 主角"]);
+
+        assert!(delete(&pool, &note.id).await.unwrap());
+        assert!(!delete(&pool, &note.id).await.unwrap());
+        let missing = AnnotationTarget::Execution {
+            id: "missing".to_string(),
+        };
+        assert!(list(&pool, &missing).await.is_err());
+    }
+}
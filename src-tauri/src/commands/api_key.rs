@@ -0,0 +1,262 @@
+// API Key: 为之后的本地 HTTP API 准备的访问凭据. 原始密钥只在创建时返回一次,
+// 数据库中只保存其 SHA-256 摘要; 校验时按摘要查找并返回授权范围
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 原始密钥前缀, 便于识别
+const KEY_PREFIX: &str = "chn_";
+
+/// 可授予的访问范围
+pub const SCOPES: &[&str] = &[
+    "projects:read",
+    "projects:write",
+    "executions:read",
+    "executions:write",
+    "settings:read",
+    "settings:write",
+];
+
+// API Key (不含摘要)
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    #[sqlx(json)]
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+// 创建 API Key 的结果; 原始密钥只在创建时返回
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyCreated {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+fn hash_key(raw_key: &str) -> String {
+    hex::encode(Sha256::digest(raw_key.as_bytes()))
+}
+
+fn new_key() -> String {
+    format!(
+        "{}{}{}",
+        KEY_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+async fn get(pool: &SqlitePool, id: &str) -> Result<ApiKey, String> {
+    sqlx::query_as::<_, ApiKey>(
+        "SELECT id, name, scopes, created_at, last_used_at, expires_at FROM api_keys WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("API Key 不存在: {}", id))
+}
+
+/// 创建 API Key; 过期时间为 ISO 8601 时间, 须晚于当前时间, 不设置表示永不过期
+pub async fn create(
+    pool: &SqlitePool,
+    name: &str,
+    scopes: &[String],
+    expires_at: Option<&str>,
+) -> Result<ApiKeyCreated, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("名称不能为空".to_string());
+    }
+    if scopes.is_empty() {
+        return Err("至少授予一种访问范围".to_string());
+    }
+    if let Some(unknown) = scopes.iter().find(|s| !SCOPES.contains(&s.as_str())) {
+        return Err(format!("不支持的访问范围: {}", unknown));
+    }
+    let expires_at = expires_at
+        .map(|t| {
+            chrono::DateTime::parse_from_rfc3339(t)
+                .map(|t| db::to_iso(t.to_utc()))
+                .map_err(|_| format!("无效的过期时间: {}", t))
+        })
+        .transpose()?;
+    if expires_at.as_ref().is_some_and(|t| *t <= db::now()) {
+        return Err("过期时间须晚于当前时间".to_string());
+    }
+
+    let mut scopes = scopes.to_vec();
+    scopes.sort_by_key(|s| SCOPES.iter().position(|e| e == s));
+    scopes.dedup();
+    let key = new_key();
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO api_keys (id, name, key_hash, scopes, created_at, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(hash_key(&key))
+    .bind(serde_json::to_string(&scopes).map_err(|e| e.to_string())?)
+    .bind(db::now())
+    .bind(&expires_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(ApiKeyCreated {
+        api_key: get(pool, &id).await?,
+        key,
+    })
+}
+
+/// 列出 API Key, 按创建时间排序
+pub async fn list(pool: &SqlitePool) -> Result<Vec<ApiKey>, String> {
+    sqlx::query_as::<_, ApiKey>(
+        "SELECT id, name, scopes, created_at, last_used_at, expires_at FROM api_keys
+         ORDER BY created_at, rowid",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 吊销 (删除) API Key, 返回是否存在
+pub async fn revoke(pool: &SqlitePool, id: &str) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM api_keys WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 校验原始密钥, 返回授权范围并记录最近使用时间; 密钥不存在或已过期时报错
+pub async fn validate(pool: &SqlitePool, raw_key: &str) -> Result<Vec<String>, String> {
+    let now = db::now();
+    let row: Option<(String, String)> = sqlx::query_as(
+        "SELECT id, scopes FROM api_keys
+         WHERE key_hash = ? AND (expires_at IS NULL OR expires_at > ?)",
+    )
+    .bind(hash_key(raw_key.trim()))
+    .bind(&now)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (id, scopes) = row.ok_or_else(|| "API Key 无效或已过期".to_string())?;
+    sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(&id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&scopes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_api_key(
+    app: AppHandle,
+    name: String,
+    scopes: Vec<String>,
+    expires_at: Option<String>,
+) -> Result<ApiKeyCreated, String> {
+    let pool = db::pool(&app).await?;
+    create(&pool, &name, &scopes, expires_at.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn list_api_keys(app: AppHandle) -> Result<Vec<ApiKey>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool).await
+}
+
+#[tauri::command]
+pub async fn revoke_api_key(app: AppHandle, id: String) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    revoke(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn validate_api_key(app: AppHandle, raw_key: String) -> Result<Vec<String>, String> {
+    let pool = db::pool(&app).await?;
+    validate(&pool, &raw_key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn stores_only_key_hash_and_validates_scopes() {
+        let pool = db::test_pool().await;
+
+        assert!(create(&pool, " ", &scopes(&["projects:read"]), None)
+            .await
+            .is_err());
+        assert!(create(&pool, "脚本", &[], None).await.is_err());
+        assert!(create(&pool, "脚本", &scopes(&["admin"]), None)
+            .await
+            .is_err());
+        assert!(
+            create(&pool, "脚本", &scopes(&["projects:read"]), Some("明天"))
+                .await
+                .is_err()
+        );
+        assert!(create(
+            &pool,
+            "脚本",
+            &scopes(&["projects:read"]),
+            Some("2000-01-01T00:00:00Z")
+        )
+        .await
+        .is_err());
+
+        let created = create(
+            &pool,
+            "导出脚本",
+            &scopes(&["executions:read", "projects:read", "projects:read"]),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(created.key.starts_with(KEY_PREFIX));
+        assert_eq!(created.api_key.scopes, ["projects:read", "executions:read"]);
+        let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = ?")
+            .bind(&created.api_key.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, created.key);
+        assert_eq!(stored, hash_key(&created.key));
+
+        assert_eq!(
+            validate(&pool, &created.key).await.unwrap(),
+            ["projects:read", "executions:read"]
+        );
+        assert!(validate(&pool, "chn_wrong").await.is_err());
+        let keys = list(&pool).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].last_used_at.is_some());
+
+        // 过期的密钥不再通过校验
+        sqlx::query("UPDATE api_keys SET expires_at = '2000-01-01T00:00:00.000Z'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(validate(&pool, &created.key).await.is_err());
+
+        assert!(revoke(&pool, &created.api_key.id).await.unwrap());
+        assert!(!revoke(&pool, &created.api_key.id).await.unwrap());
+        assert!(list(&pool).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+// 执行附件: 记录与执行结果关联的本地文件路径, 文件本身不复制
+use std::io::Read;
+use std::path::PathBuf;
+
+use base64::Engine;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+use tauri_plugin_fs::{FsExt, OpenOptions};
+
+use crate::db;
+use crate::models::Attachment;
+
+// 附件内联预览: 文件开头至多 max_bytes 字节的 Base64
+#[derive(Debug, Serialize)]
+pub struct AttachmentPreview {
+    pub mime_type: Option<String>,
+    pub data_base64: String,
+    pub truncated: bool,
+}
+
+/// 通过 fs 插件确认文件存在且可读
+pub fn verify_file(app: &AppHandle, file_path: &str) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.read(true);
+    app.fs()
+        .open(PathBuf::from(file_path), options)
+        .map(|_| ())
+        .map_err(|e| format!("无法读取附件文件 {}: {}", file_path, e))
+}
+
+/// 为执行记录添加附件, 返回附件 ID
+pub async fn add(
+    pool: &SqlitePool,
+    execution_id: &str,
+    file_path: &str,
+    mime_type: &str,
+    description: &str,
+) -> Result<String, String> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM executions WHERE id = ?)")
+        .bind(execution_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("执行记录不存在: {}", execution_id));
+    }
+
+    let mime_type = mime_type.trim();
+    let description = description.trim();
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO execution_attachments (id, execution_id, file_path, mime_type, description, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(execution_id)
+    .bind(file_path)
+    .bind((!mime_type.is_empty()).then_some(mime_type))
+    .bind((!description.is_empty()).then_some(description))
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// 列出执行记录的附件, 按添加时间排序
+pub async fn list(pool: &SqlitePool, execution_id: &str) -> Result<Vec<Attachment>, String> {
+    sqlx::query_as::<_, Attachment>(
+        "SELECT * FROM execution_attachments WHERE execution_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(execution_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 获取单个附件
+pub async fn get(pool: &SqlitePool, attachment_id: &str) -> Result<Attachment, String> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM execution_attachments WHERE id = ?")
+        .bind(attachment_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("附件不存在: {}", attachment_id))
+}
+
+/// 读取至多 max_bytes 字节, 多读一个字节用于判断是否截断
+pub fn read_prefix(reader: impl Read, max_bytes: u64) -> Result<(Vec<u8>, bool), String> {
+    let mut data = Vec::new();
+    reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut data)
+        .map_err(|e| format!("读取附件文件失败: {}", e))?;
+    let truncated = data.len() as u64 > max_bytes;
+    data.truncate(max_bytes as usize);
+    Ok((data, truncated))
+}
+
+#[tauri::command]
+pub async fn add_execution_attachment(
+    app: AppHandle,
+    execution_id: String,
+    file_path: String,
+    mime_type: String,
+    description: String,
+) -> Result<String, String> {
+    verify_file(&app, &file_path)?;
+    let pool = db::pool(&app).await?;
+    add(&pool, &execution_id, &file_path, &mime_type, &description).await
+}
+
+#[tauri::command]
+pub async fn get_execution_attachments(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<Vec<Attachment>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &execution_id).await
+}
+
+#[tauri::command]
+pub async fn get_execution_attachment_preview(
+    app: AppHandle,
+    attachment_id: String,
+    max_bytes: u64,
+) -> Result<AttachmentPreview, String> {
+    let pool = db::pool(&app).await?;
+    let attachment = get(&pool, &attachment_id).await?;
+    let mut options = OpenOptions::new();
+    options.read(true);
+    let file = app
+        .fs()
+        .open(PathBuf::from(&attachment.file_path), options)
+        .map_err(|e| format!("无法读取附件文件 {}: {}", attachment.file_path, e))?;
+    let (data, truncated) = read_prefix(file, max_bytes)?;
+    Ok(AttachmentPreview {
+        mime_type: attachment.mime_type,
+        data_base64: base64::engine::general_purpose::STANDARD.encode(data),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn adds_and_lists_attachments() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO executions (id, workflow_id, status) VALUES ('e1', 'w1', 'completed');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let cover = add(&pool, "e1", "/tmp/cover.png", "image/png", "封面")
+            .await
+            .unwrap();
+        add(&pool, "e1", "/tmp/outline.md", "", " ").await.unwrap();
+
+        let attachments = list(&pool, "e1").await.unwrap();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].id, cover);
+        assert_eq!(attachments[0].description.as_deref(), Some("封面"));
+        assert!(attachments[1].mime_type.is_none());
+        assert!(attachments[1].description.is_none());
+
+        assert!(add(&pool, "missing", "/tmp/a.png", "image/png", "")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn reads_prefix_and_flags_truncation() {
+        let (data, truncated) = read_prefix(&b"chapter one"[..], 7).unwrap();
+        assert_eq!(data, b"chapter");
+        assert!(truncated);
+
+        let (data, truncated) = read_prefix(&b"short"[..], 5).unwrap();
+        assert_eq!(data, b"short");
+        assert!(!truncated);
+    }
+}
@@ -0,0 +1,385 @@
+// 后台任务: 数据量大的迁移只在迁移中修改表结构, 数据回填作为后台任务分批执行, 避免启动时界面长时间无响应.
+// 任务记录在 background_jobs 表中, 每批处理与进度、游标在同一事务中提交, 应用重启后从游标继续;
+// 进度通过 `job:progress` 事件通知前端
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+
+/// 进度事件名
+pub const JOB_PROGRESS_EVENT: &str = "job:progress";
+
+/// 回填 v48 之前执行记录的 workflow_version
+pub const BACKFILL_EXECUTION_WORKFLOW_VERSION: &str = "backfill_execution_workflow_version";
+
+/// 每批处理的行数
+const BATCH_SIZE: i64 = 500;
+
+// 后台任务
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct BackgroundJob {
+    pub id: String,
+    pub kind: String,
+    pub progress: i64,
+    pub total: i64,
+    /// pending / running / completed / failed / cancelled
+    pub status: String,
+    /// 是否可以中途取消 (已处理的批次保留)
+    pub cancellable: bool,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 按 rowid 分批回填的游标: 已处理到的 rowid 与任务开始时的最大 rowid (之后新增的行不在回填范围内)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct RowidCursor {
+    after: i64,
+    until: i64,
+}
+
+const JOB_COLUMNS: &str =
+    "id, kind, progress, total, status, cancellable, error, created_at, updated_at";
+
+/// 全部后台任务, 按创建顺序排列
+pub async fn list(pool: &SqlitePool) -> Result<Vec<BackgroundJob>, String> {
+    sqlx::query_as::<_, BackgroundJob>(&format!(
+        "SELECT {} FROM background_jobs ORDER BY created_at, rowid",
+        JOB_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn get(pool: &SqlitePool, job_id: &str) -> Result<BackgroundJob, String> {
+    sqlx::query_as::<_, BackgroundJob>(&format!(
+        "SELECT {} FROM background_jobs WHERE id = ?",
+        JOB_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("后台任务不存在: {}", job_id))
+}
+
+/// 取消未结束的任务; 已提交的批次保留
+pub async fn cancel(pool: &SqlitePool, job_id: &str) -> Result<BackgroundJob, String> {
+    let job = get(pool, job_id).await?;
+    if !job.cancellable {
+        return Err("该任务不能取消".to_string());
+    }
+    if job.status != "pending" && job.status != "running" {
+        return Err(format!("任务已结束: {}", job.status));
+    }
+    sqlx::query(
+        "UPDATE background_jobs SET status = 'cancelled', updated_at = ?
+         WHERE id = ? AND status IN ('pending', 'running')",
+    )
+    .bind(db::now())
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    get(pool, job_id).await
+}
+
+// 开始任务: 统计总量并生成初始游标
+async fn start(pool: &SqlitePool, kind: &str) -> Result<(i64, String), String> {
+    match kind {
+        BACKFILL_EXECUTION_WORKFLOW_VERSION => {
+            let until: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(rowid), 0) FROM executions")
+                .fetch_one(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let total: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM executions WHERE workflow_version IS NULL AND rowid <= ?",
+            )
+            .bind(until)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            let cursor = RowidCursor { after: 0, until };
+            Ok((
+                total,
+                serde_json::to_string(&cursor).map_err(|e| e.to_string())?,
+            ))
+        }
+        other => Err(format!("未知的后台任务类型: {}", other)),
+    }
+}
+
+// 处理一批, 返回处理的行数与下一批的游标 (None 表示已完成)
+async fn step(
+    conn: &mut SqliteConnection,
+    kind: &str,
+    cursor: &str,
+    batch_size: i64,
+) -> Result<(i64, Option<String>), String> {
+    match kind {
+        BACKFILL_EXECUTION_WORKFLOW_VERSION => {
+            let cursor: RowidCursor =
+                serde_json::from_str(cursor).map_err(|e| format!("无效的任务游标: {}", e))?;
+            let rowids: Vec<i64> = sqlx::query_scalar(
+                "SELECT rowid FROM executions
+                 WHERE workflow_version IS NULL AND rowid > ? AND rowid <= ?
+                 ORDER BY rowid LIMIT ?",
+            )
+            .bind(cursor.after)
+            .bind(cursor.until)
+            .bind(batch_size)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+            let Some(&last) = rowids.last() else {
+                return Ok((0, None));
+            };
+            // 取执行开始时工作流已保存的最新版本; 当时尚无版本的保持 NULL
+            sqlx::query(
+                "UPDATE executions SET workflow_version = (
+                     SELECT MAX(v.version_number) FROM workflow_versions v
+                     WHERE v.workflow_id = executions.workflow_id
+                       AND datetime(v.created_at) <= datetime(executions.started_at)
+                 )
+                 WHERE workflow_version IS NULL AND rowid > ? AND rowid <= ?",
+            )
+            .bind(cursor.after)
+            .bind(last)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+            let next = if rowids.len() as i64 == batch_size && last < cursor.until {
+                let next = RowidCursor {
+                    after: last,
+                    until: cursor.until,
+                };
+                Some(serde_json::to_string(&next).map_err(|e| e.to_string())?)
+            } else {
+                None
+            };
+            Ok((rowids.len() as i64, next))
+        }
+        other => Err(format!("未知的后台任务类型: {}", other)),
+    }
+}
+
+/// 执行任务的一批; 任务已结束或已取消时不做处理. 返回处理后的任务
+pub async fn run_step(
+    pool: &SqlitePool,
+    job_id: &str,
+    batch_size: i64,
+) -> Result<BackgroundJob, String> {
+    let job = get(pool, job_id).await?;
+    if job.status == "pending" {
+        let (total, cursor) = start(pool, &job.kind).await?;
+        sqlx::query(
+            "UPDATE background_jobs SET status = 'running', total = ?, progress = 0, cursor = ?, updated_at = ?
+             WHERE id = ? AND status = 'pending'",
+        )
+        .bind(total)
+        .bind(cursor)
+        .bind(db::now())
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    } else if job.status != "running" {
+        return Ok(job);
+    }
+
+    let cursor: Option<String> =
+        sqlx::query_scalar("SELECT cursor FROM background_jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let (processed, next) = step(
+        &mut tx,
+        &job.kind,
+        cursor.as_deref().unwrap_or(""),
+        batch_size,
+    )
+    .await?;
+    // 期间被取消时放弃这一批
+    let result = sqlx::query(
+        "UPDATE background_jobs
+         SET progress = MIN(progress + ?, total), cursor = ?,
+             status = CASE WHEN ? THEN 'completed' ELSE status END, updated_at = ?
+         WHERE id = ? AND status = 'running'",
+    )
+    .bind(processed)
+    .bind(&next)
+    .bind(next.is_none())
+    .bind(db::now())
+    .bind(job_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 1 {
+        tx.commit().await.map_err(|e| e.to_string())?;
+    } else {
+        tx.rollback().await.map_err(|e| e.to_string())?;
+    }
+    get(pool, job_id).await
+}
+
+async fn fail(pool: &SqlitePool, job_id: &str, error: &str) -> Result<BackgroundJob, String> {
+    sqlx::query(
+        "UPDATE background_jobs SET status = 'failed', error = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(error)
+    .bind(db::now())
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    get(pool, job_id).await
+}
+
+/// 执行任务直到结束, 每批后调用 on_progress; 出错时把任务标记为 failed
+pub async fn run(
+    pool: &SqlitePool,
+    job_id: &str,
+    on_progress: impl Fn(&BackgroundJob),
+) -> Result<BackgroundJob, String> {
+    loop {
+        let job = match run_step(pool, job_id, BATCH_SIZE).await {
+            Ok(job) => job,
+            Err(e) => fail(pool, job_id, &e).await?,
+        };
+        on_progress(&job);
+        if job.status != "running" {
+            return Ok(job);
+        }
+        // 让出运行时, 避免长时间占用数据库连接
+        tokio::task::yield_now().await;
+    }
+}
+
+/// 启动时在后台依次执行未完成的任务 (含上次运行中断的任务)
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let pool = match db::pool(&app).await {
+            Ok(pool) => pool,
+            Err(e) => return eprintln!("后台任务启动失败: {}", e),
+        };
+        let jobs = match list(&pool).await {
+            Ok(jobs) => jobs,
+            Err(e) => return eprintln!("读取后台任务失败: {}", e),
+        };
+        for job in jobs
+            .iter()
+            .filter(|j| j.status == "pending" || j.status == "running")
+        {
+            let emit = |job: &BackgroundJob| {
+                let _ = app.emit(JOB_PROGRESS_EVENT, job);
+            };
+            if let Err(e) = run(&pool, &job.id, emit).await {
+                eprintln!("后台任务 {} 失败: {}", job.kind, e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_background_jobs(app: AppHandle) -> Result<Vec<BackgroundJob>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool).await
+}
+
+#[tauri::command]
+pub async fn cancel_background_job(
+    app: AppHandle,
+    job_id: String,
+) -> Result<BackgroundJob, String> {
+    let pool = db::pool(&app).await?;
+    let job = cancel(&pool, &job_id).await?;
+    app.emit(JOB_PROGRESS_EVENT, &job)
+        .map_err(|e| e.to_string())?;
+    Ok(job)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB_ID: &str = "v50-backfill_execution_workflow_version";
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO workflow_versions (id, workflow_id, version_number, snapshot, created_at) VALUES
+                 ('v1', 'w1', 1, '{}', '2026-01-01 00:00:00'),
+                 ('v2', 'w1', 2, '{}', '2026-02-01T00:00:00.000Z');
+             INSERT INTO executions (id, workflow_id, status, started_at) VALUES
+                 ('e0', 'w1', 'completed', '2025-12-01T00:00:00.000Z'),
+                 ('e1', 'w1', 'completed', '2026-01-15T00:00:00.000Z'),
+                 ('e2', 'w1', 'completed', '2026-03-01T00:00:00.000Z');",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn versions(pool: &SqlitePool) -> Vec<Option<i64>> {
+        sqlx::query_scalar("SELECT workflow_version FROM executions ORDER BY rowid")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn backfills_in_resumable_batches() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let job = &list(&pool).await.unwrap()[0];
+        assert_eq!((job.id.as_str(), job.status.as_str()), (JOB_ID, "pending"));
+
+        let job = run_step(&pool, JOB_ID, 2).await.unwrap();
+        assert_eq!(
+            (job.status.as_str(), job.progress, job.total),
+            ("running", 2, 3)
+        );
+        assert_eq!(versions(&pool).await, [None, Some(1), None]);
+
+        // 开始后新增的执行不在回填范围内
+        sqlx::query("INSERT INTO executions (id, workflow_id, status, started_at) VALUES ('e3', 'w1', 'completed', '2026-03-02T00:00:00.000Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // 重启后从游标继续
+        let job = run(&pool, JOB_ID, |_| {}).await.unwrap();
+        assert_eq!((job.status.as_str(), job.progress), ("completed", 3));
+        assert_eq!(versions(&pool).await, [None, Some(1), Some(2), None]);
+
+        // 已结束的任务不再处理, 也不能取消
+        assert_eq!(run_step(&pool, JOB_ID, 2).await.unwrap(), job);
+        assert!(cancel(&pool, JOB_ID).await.is_err());
+        assert!(cancel(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancels_between_batches() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        run_step(&pool, JOB_ID, 1).await.unwrap();
+        let job = cancel(&pool, JOB_ID).await.unwrap();
+        assert_eq!((job.status.as_str(), job.progress), ("cancelled", 1));
+
+        // 取消后保留已提交的批次, 不再继续
+        let job = run(&pool, JOB_ID, |_| {}).await.unwrap();
+        assert_eq!(job.status, "cancelled");
+        assert_eq!(versions(&pool).await, [None, None, None]);
+
+        sqlx::query("UPDATE background_jobs SET cancellable = 0, status = 'running' WHERE id = ?")
+            .bind(JOB_ID)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(cancel(&pool, JOB_ID).await.is_err());
+    }
+}
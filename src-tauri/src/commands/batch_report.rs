@@ -0,0 +1,520 @@
+// 批量执行报告: 把同一批次的执行导出为单个自包含 HTML 文件 (内联样式, 无外部资源).
+// 先输出汇总统计与每条执行的摘要表 (输出前 PREVIEW_CHARS 字, 链接到下方全文), 再输出全文.
+// 执行按 PAGE_SIZE 分页读取并直接写入文件, 批次包含上百条执行时也不会整体载入内存;
+// 已知的密钥 (AI 服务商 API Key、Webhook 签名密钥) 出现在文本中时替换为 [已移除]
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::execution::token_cost;
+use crate::commands::export::{duration_ms, SCRUBBED, SECRET_KEYS};
+use crate::commands::webhook;
+use crate::db;
+use crate::models::Execution;
+
+/// 摘要表中输出预览的字符数
+pub const PREVIEW_CHARS: usize = 500;
+
+/// 每次从数据库读取的执行数
+const PAGE_SIZE: i64 = 50;
+
+/// 节点失败时 output 的前缀
+const ERROR_PREFIX: &str = "错误: ";
+
+// 报告汇总, 导出完成后返回给前端
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchReportSummary {
+    pub batch_id: String,
+    pub workflow_name: Option<String>,
+    pub total: i64,
+    pub completed: i64,
+    pub failed: i64,
+    /// 排队中、运行中、已取消等其他状态
+    pub other: i64,
+    /// 已结束执行的耗时之和
+    pub total_duration_ms: i64,
+    pub total_cost_usd: f64,
+}
+
+// 执行的报告行数据
+struct ReportRow {
+    index: i64,
+    execution: Execution,
+    cost_usd: Option<f64>,
+    error: Option<String>,
+}
+
+/// HTML 转义
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn preview(text: &str) -> (String, bool) {
+    let mut chars = text.chars();
+    let head: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    (head, chars.next().is_some())
+}
+
+fn format_duration(ms: Option<i64>) -> String {
+    match ms {
+        Some(ms) if ms >= 60_000 => format!("{}分{}秒", ms / 60_000, ms % 60_000 / 1000),
+        Some(ms) => format!("{:.1}秒", ms as f64 / 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map_or("-".to_string(), |c| format!("${:.4}", c))
+}
+
+fn collect_secret_values(value: &Value, secrets: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let normalized = key.to_lowercase().replace('-', "_");
+                match child {
+                    Value::String(s) if SECRET_KEYS.contains(&normalized.as_str()) => {
+                        secrets.push(s.clone())
+                    }
+                    _ => collect_secret_values(child, secrets),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_secret_values(v, secrets)),
+        _ => {}
+    }
+}
+
+/// 已配置的密钥原文, 按长度降序 (先替换较长的, 避免留下残片)
+async fn known_secrets(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    let providers: Option<String> =
+        sqlx::query_scalar("SELECT ai_providers FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let mut secrets = Vec::new();
+    if let Some(value) = providers.and_then(|p| serde_json::from_str::<Value>(&p).ok()) {
+        collect_secret_values(&value, &mut secrets);
+    }
+    secrets.extend(webhook::workflow_secrets(pool).await?);
+    let webhook_secrets: Vec<String> =
+        sqlx::query_scalar("SELECT secret FROM webhooks WHERE secret IS NOT NULL")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    secrets.extend(webhook_secrets);
+    // 过短的值可能误伤正文
+    secrets.retain(|s| s.chars().count() >= 8);
+    secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    secrets.dedup();
+    Ok(secrets)
+}
+
+fn redact(text: &str, secrets: &[String]) -> String {
+    secrets.iter().fold(text.to_string(), |text, secret| {
+        text.replace(secret, SCRUBBED)
+    })
+}
+
+/// 批次中每条执行的 AI 费用, 未知模型不计费
+async fn costs(pool: &SqlitePool, batch_id: &str) -> Result<HashMap<String, f64>, String> {
+    let rows: Vec<(String, i64, i64, Option<String>)> = sqlx::query_as(
+        "SELECT r.execution_id,
+                COALESCE(json_extract(r.token_usage, '$.promptTokens'), 0),
+                COALESCE(json_extract(r.token_usage, '$.completionTokens'), 0),
+                CASE WHEN json_valid(r.resolved_config)
+                     THEN json_extract(r.resolved_config, '$.model') END
+         FROM node_results r
+         JOIN executions e ON e.id = r.execution_id
+         WHERE e.batch_id = ? AND r.token_usage IS NOT NULL AND json_valid(r.token_usage)",
+    )
+    .bind(batch_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut costs = HashMap::new();
+    for (execution_id, prompt_tokens, completion_tokens, model) in rows {
+        if let Some(cost) = model
+            .as_deref()
+            .and_then(|m| token_cost(m, prompt_tokens, completion_tokens))
+        {
+            *costs.entry(execution_id).or_insert(0.0) += cost;
+        }
+    }
+    Ok(costs)
+}
+
+/// 统计批次并生成汇总; 批次不存在时报错
+async fn summarize(
+    pool: &SqlitePool,
+    batch_id: &str,
+    costs: &HashMap<String, f64>,
+) -> Result<BatchReportSummary, String> {
+    let rows: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT status, started_at, finished_at FROM executions WHERE batch_id = ?")
+            .bind(batch_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if rows.is_empty() {
+        return Err(format!("批次不存在: {}", batch_id));
+    }
+    let workflow_name: Option<String> = sqlx::query_scalar(
+        "SELECT w.name FROM executions e JOIN workflows w ON w.id = e.workflow_id
+         WHERE e.batch_id = ? LIMIT 1",
+    )
+    .bind(batch_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let count = |status: &str| rows.iter().filter(|(s, _, _)| s == status).count() as i64;
+    let total = rows.len() as i64;
+    let completed = count("completed");
+    let failed = count("failed") + count("timeout");
+    Ok(BatchReportSummary {
+        batch_id: batch_id.to_string(),
+        workflow_name,
+        total,
+        completed,
+        failed,
+        other: total - completed - failed,
+        total_duration_ms: rows
+            .iter()
+            .filter_map(|(_, started, finished)| duration_ms(started, finished.as_deref()))
+            .sum(),
+        total_cost_usd: costs.values().sum(),
+    })
+}
+
+/// 失败执行的错误信息: 取最后一个失败节点的输出
+async fn error_message(pool: &SqlitePool, execution_id: &str) -> Result<Option<String>, String> {
+    let output: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT output FROM node_results WHERE execution_id = ? AND status = 'failed'
+         ORDER BY started_at DESC, rowid DESC LIMIT 1",
+    )
+    .bind(execution_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(output
+        .flatten()
+        .map(|o| o.strip_prefix(ERROR_PREFIX).unwrap_or(&o).to_string()))
+}
+
+async fn page(
+    pool: &SqlitePool,
+    batch_id: &str,
+    offset: i64,
+    costs: &HashMap<String, f64>,
+) -> Result<Vec<ReportRow>, String> {
+    let executions: Vec<Execution> = sqlx::query_as(
+        "SELECT * FROM executions WHERE batch_id = ?
+         ORDER BY queued_at, rowid LIMIT ? OFFSET ?",
+    )
+    .bind(batch_id)
+    .bind(PAGE_SIZE)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut rows = Vec::with_capacity(executions.len());
+    for (i, execution) in executions.into_iter().enumerate() {
+        let error = match execution.status.as_str() {
+            "failed" | "timeout" => error_message(pool, &execution.id).await?,
+            _ => None,
+        };
+        rows.push(ReportRow {
+            index: offset + i as i64 + 1,
+            cost_usd: costs.get(&execution.id).copied(),
+            error,
+            execution,
+        });
+    }
+    Ok(rows)
+}
+
+fn status_label(status: &str) -> &str {
+    match status {
+        "queued" => "排队中",
+        "running" => "运行中",
+        "paused" => "已暂停",
+        "completed" => "已完成",
+        "failed" => "失败",
+        "timeout" => "超时",
+        "cancelled" => "已取消",
+        other => other,
+    }
+}
+
+const STYLE: &str =
+    "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:960px;color:#222}
+table{border-collapse:collapse;width:100%;margin:1rem 0}
+th,td{border:1px solid #ddd;padding:.4rem .6rem;text-align:left;vertical-align:top}
+th{background:#f5f5f5}
+.completed{color:#1a7f37}.failed,.timeout{color:#cf222e}
+.preview,.output{white-space:pre-wrap}
+.error{color:#cf222e;white-space:pre-wrap}
+article{border-top:1px solid #ddd;padding-top:1rem;margin-top:1.5rem}";
+
+fn io(e: std::io::Error) -> String {
+    format!("写入文件失败: {}", e)
+}
+
+fn write_header(out: &mut impl Write, summary: &BatchReportSummary) -> Result<(), String> {
+    let title = format!(
+        "批量执行报告 - {}",
+        summary.workflow_name.as_deref().unwrap_or("未知工作流")
+    );
+    write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>批次 {batch} · 生成于 {now}</p>\n",
+        title = escape(&title),
+        batch = escape(&summary.batch_id),
+        now = db::now(),
+    )
+    .map_err(io)?;
+    write!(
+        out,
+        "<h2>汇总</h2>\n<table>\n<tr><th>执行数</th><th>已完成</th><th>失败</th><th>其他</th><th>总耗时</th><th>总费用</th></tr>\n<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n</table>\n",
+        summary.total,
+        summary.completed,
+        summary.failed,
+        summary.other,
+        format_duration(Some(summary.total_duration_ms)),
+        format_cost(Some(summary.total_cost_usd)),
+    )
+    .map_err(io)
+}
+
+fn write_summary_row(
+    out: &mut impl Write,
+    row: &ReportRow,
+    secrets: &[String],
+) -> Result<(), String> {
+    let execution = &row.execution;
+    let output = redact(execution.final_output.as_deref().unwrap_or(""), secrets);
+    let (head, truncated) = preview(&output);
+    let mut detail = format!("<div class=\"preview\">{}", escape(&head));
+    if truncated {
+        detail.push('…');
+    }
+    detail.push_str("</div>");
+    if !output.is_empty() {
+        detail.push_str(&format!("<a href=\"#output-{}\">查看全文</a>", row.index));
+    }
+    if let Some(error) = &row.error {
+        detail.push_str(&format!(
+            "<div class=\"error\">{}</div>",
+            escape(&redact(error, secrets))
+        ));
+    }
+    writeln!(
+        out,
+        "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        row.index,
+        escape(&redact(execution.title.as_deref().unwrap_or("-"), secrets)),
+        escape(&execution.status),
+        status_label(&execution.status),
+        execution
+            .workflow_version
+            .map_or("-".to_string(), |v| format!("v{}", v)),
+        format_duration(duration_ms(
+            &execution.started_at,
+            execution.finished_at.as_deref()
+        )),
+        format_cost(row.cost_usd),
+        detail,
+    )
+    .map_err(io)
+}
+
+fn write_full_output(
+    out: &mut impl Write,
+    row: &ReportRow,
+    secrets: &[String],
+) -> Result<(), String> {
+    let Some(output) = row
+        .execution
+        .final_output
+        .as_deref()
+        .filter(|o| !o.is_empty())
+    else {
+        return Ok(());
+    };
+    writeln!(
+        out,
+        "<article id=\"output-{index}\">\n<h3>#{index} {title}</h3>\n<div class=\"output\">{output}</div>\n</article>",
+        index = row.index,
+        title = escape(&redact(row.execution.title.as_deref().unwrap_or(""), secrets)),
+        output = escape(&redact(output, secrets)),
+    )
+    .map_err(io)
+}
+
+/// 生成批次报告并写入 out; 执行按加入队列的顺序编号
+pub async fn write_report(
+    pool: &SqlitePool,
+    batch_id: &str,
+    out: &mut impl Write,
+) -> Result<BatchReportSummary, String> {
+    let costs = costs(pool, batch_id).await?;
+    let summary = summarize(pool, batch_id, &costs).await?;
+    let secrets = known_secrets(pool).await?;
+
+    write_header(out, &summary)?;
+    out.write_all(
+        "<h2>执行</h2>\n<table>\n<tr><th>#</th><th>标题</th><th>状态</th><th>工作流版本</th><th>耗时</th><th>费用</th><th>输出预览</th></tr>\n"
+            .as_bytes(),
+    )
+    .map_err(io)?;
+    let mut offset = 0;
+    loop {
+        let rows = page(pool, batch_id, offset, &costs).await?;
+        for row in &rows {
+            write_summary_row(out, row, &secrets)?;
+        }
+        if (rows.len() as i64) < PAGE_SIZE {
+            break;
+        }
+        offset += PAGE_SIZE;
+    }
+    out.write_all("</table>\n<h2>全文</h2>\n".as_bytes())
+        .map_err(io)?;
+
+    let mut offset = 0;
+    loop {
+        let rows = page(pool, batch_id, offset, &costs).await?;
+        for row in &rows {
+            write_full_output(out, row, &secrets)?;
+        }
+        if (rows.len() as i64) < PAGE_SIZE {
+            break;
+        }
+        offset += PAGE_SIZE;
+    }
+    out.write_all(b"</body>\n</html>\n").map_err(io)?;
+    out.flush().map_err(io)?;
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn export_batch_report(
+    app: AppHandle,
+    batch_id: String,
+    path: String,
+) -> Result<BatchReportSummary, String> {
+    let pool = db::pool(&app).await?;
+    let file = File::create(&path).map_err(|e| format!("无法创建文件 {}: {}", path, e))?;
+    let mut out = BufWriter::new(file);
+    write_report(&pool, &batch_id, &mut out).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn writes_batch_report() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            UPDATE global_config SET ai_providers = '{"openai":{"apiKey":"sk-live-123456789"}}' WHERE id = 1;
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '<正文>生成');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'ai_chat', '撰写正文', '{}', 0);
+            INSERT INTO workflow_versions (id, workflow_id, version_number, snapshot) VALUES
+                ('v1', 'w1', 1, '{}'), ('v2', 'w1', 2, '{}');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let long = format!("{}结尾", "雨".repeat(PREVIEW_CHARS));
+        let inputs: Vec<String> = (0..120).map(|i| format!("第{}章", i + 1)).collect();
+        let batch = crate::commands::queue::enqueue_batch(&pool, "w1", &inputs, "normal")
+            .await
+            .unwrap();
+        assert_eq!(batch.executions.len(), 120);
+        assert!(batch
+            .executions
+            .iter()
+            .all(|e| e.workflow_version == Some(2)));
+        let first = &batch.executions[0].id;
+        let second = &batch.executions[1].id;
+        sqlx::query(
+            "UPDATE executions SET status = 'completed', final_output = ?, title = '雨夜',
+                 started_at = '2026-01-01T00:00:00.000Z', finished_at = '2026-01-01T00:00:02.000Z'
+             WHERE id = ?",
+        )
+        .bind(&long)
+        .bind(first)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE executions SET status = 'failed' WHERE id = ?")
+            .bind(second)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO node_results (id, execution_id, node_id, status, output, token_usage, resolved_config) VALUES
+                ('r1', ?1, 'n1', 'completed', 'ok', '{"promptTokens":1000,"completionTokens":2000}', '{"model":"gpt-5"}'),
+                ('r2', ?2, 'n1', 'failed', '错误: 401 invalid key sk-live-123456789', NULL, NULL)"#,
+        )
+        .bind(first)
+        .bind(second)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut out = Vec::new();
+        let summary = write_report(&pool, &batch.batch_id, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(
+            (
+                summary.total,
+                summary.completed,
+                summary.failed,
+                summary.other
+            ),
+            (120, 1, 1, 118)
+        );
+        assert_eq!(summary.total_duration_ms, 2000);
+        assert!((summary.total_cost_usd - 0.027).abs() < 1e-9);
+
+        let html = String::from_utf8(out).unwrap();
+        assert!(html.contains("&lt;正文&gt;生成"));
+        assert!(html.contains("<a href=\"#output-1\">"));
+        assert!(html.contains("<article id=\"output-1\">"));
+        assert!(html.contains("401 invalid key [已移除]"));
+        assert!(!html.contains("sk-live-123456789"));
+        assert!(html.contains("<td>v2</td>"));
+        assert!(html.contains("<td>120</td>"));
+        // 摘要表只含预览, 全文在下方
+        assert_eq!(html.matches("结尾").count(), 1);
+
+        assert!(write_report(&pool, "missing", &mut Vec::new())
+            .await
+            .is_err());
+    }
+}
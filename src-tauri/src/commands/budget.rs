@@ -0,0 +1,229 @@
+// 项目预算: 为项目设置 AI 费用上限, 已花费达到预算后不能开始新的执行.
+// 费用按节点结果记录的 token 用量与模型定价 (execution::MODEL_PRICING) 计算, 未知模型不计费
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::execution::token_cost;
+use crate::db;
+use crate::error::CommandError;
+
+// 预算使用情况, 未设置预算时相关字段为 None
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    pub budget_usd: Option<f64>,
+    pub spent_usd: f64,
+    /// 超支时为 0
+    pub remaining_usd: Option<f64>,
+    /// 已花费占预算的百分比, 超支时可超过 100
+    pub percentage_used: Option<f64>,
+}
+
+async fn budget(pool: &SqlitePool, project_id: &str) -> Result<Option<f64>, String> {
+    let budget: Option<Option<f64>> =
+        sqlx::query_scalar("SELECT budget_usd FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    budget.ok_or_else(|| format!("项目不存在: {}", project_id))
+}
+
+/// 设置项目预算 (USD), 须大于 0; None 清除预算, 不再限制
+pub async fn set_budget(
+    pool: &SqlitePool,
+    project_id: &str,
+    budget_usd: Option<f64>,
+) -> Result<(), String> {
+    if budget_usd.is_some_and(|b| !b.is_finite() || b <= 0.0) {
+        return Err("预算须大于 0".to_string());
+    }
+    let result = sqlx::query("UPDATE projects SET budget_usd = ? WHERE id = ?")
+        .bind(budget_usd)
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+    Ok(())
+}
+
+/// 项目全部执行已花费的 AI 费用 (USD)
+pub async fn spend(pool: &SqlitePool, project_id: &str) -> Result<f64, String> {
+    budget(pool, project_id).await?;
+    let rows: Vec<(i64, i64, Option<String>)> = sqlx::query_as(
+        "SELECT COALESCE(json_extract(r.token_usage, '$.promptTokens'), 0),
+                COALESCE(json_extract(r.token_usage, '$.completionTokens'), 0),
+                CASE WHEN json_valid(r.resolved_config)
+                     THEN json_extract(r.resolved_config, '$.model') END
+         FROM node_results r
+         JOIN executions e ON e.id = r.execution_id
+         JOIN workflows w ON w.id = e.workflow_id
+         WHERE w.project_id = ? AND r.token_usage IS NOT NULL AND json_valid(r.token_usage)",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(prompt_tokens, completion_tokens, model)| {
+            token_cost(model.as_deref()?, prompt_tokens, completion_tokens)
+        })
+        .sum())
+}
+
+/// 项目预算使用情况
+pub async fn status(pool: &SqlitePool, project_id: &str) -> Result<BudgetStatus, String> {
+    let budget_usd = budget(pool, project_id).await?;
+    let spent_usd = spend(pool, project_id).await?;
+    Ok(BudgetStatus {
+        budget_usd,
+        spent_usd,
+        remaining_usd: budget_usd.map(|b| (b - spent_usd).max(0.0)),
+        percentage_used: budget_usd.map(|b| spent_usd / b * 100.0),
+    })
+}
+
+/// 开始新的执行前调用: 项目设置了预算且已花费达到预算时返回 BudgetExceeded
+pub async fn ensure_within_budget(pool: &SqlitePool, project_id: &str) -> Result<(), CommandError> {
+    let Some(budget_usd) = budget(pool, project_id).await? else {
+        return Ok(());
+    };
+    let spend_usd = spend(pool, project_id).await?;
+    if spend_usd >= budget_usd {
+        return Err(CommandError::BudgetExceeded {
+            budget_usd,
+            spend_usd,
+        });
+    }
+    Ok(())
+}
+
+/// 按工作流所属项目检查预算
+pub async fn ensure_workflow_within_budget(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<(), CommandError> {
+    let project_id: Option<String> =
+        sqlx::query_scalar("SELECT project_id FROM workflows WHERE id = ?")
+            .bind(workflow_id)
+            .fetch_optional(pool)
+            .await?;
+    let project_id = project_id.ok_or_else(|| format!("工作流不存在: {}", workflow_id))?;
+    ensure_within_budget(pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn set_project_budget(
+    app: AppHandle,
+    project_id: String,
+    budget_usd: Option<f64>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_budget(&pool, &project_id, budget_usd).await
+}
+
+#[tauri::command]
+pub async fn get_project_spend(app: AppHandle, project_id: String) -> Result<f64, String> {
+    let pool = db::pool(&app).await?;
+    spend(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn get_budget_status(app: AppHandle, project_id: String) -> Result<BudgetStatus, String> {
+    let pool = db::pool(&app).await?;
+    status(&pool, &project_id).await
+}
+
+/// 前端执行引擎创建执行记录前调用
+#[tauri::command]
+pub async fn ensure_project_within_budget(
+    app: AppHandle,
+    project_id: String,
+) -> Result<(), CommandError> {
+    let pool = db::pool(&app).await?;
+    ensure_within_budget(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn blocks_executions_over_budget() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '其他项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文'), ('w2', 'p2', '其他');
+            INSERT INTO executions (id, workflow_id, status) VALUES
+                ('e1', 'w1', 'completed'), ('e2', 'w1', 'completed'), ('e3', 'w2', 'completed');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'ai_chat', '撰写正文', '{}', 0), ('n2', 'w2', 'ai_chat', '撰写', '{}', 0);
+            INSERT INTO node_results (id, execution_id, node_id, status, token_usage, resolved_config) VALUES
+                ('r1', 'e1', 'n1', 'completed', '{"promptTokens":1000,"completionTokens":2000}', '{"model":"gpt-5"}'),
+                ('r2', 'e2', 'n1', 'completed', '{"promptTokens":1000,"completionTokens":0}', '{"model":"local-model"}'),
+                ('r3', 'e2', 'n1', 'completed', '{"promptTokens":0,"completionTokens":1000}', '{"model":"gpt-5"}'),
+                ('r4', 'e3', 'n2', 'completed', '{"promptTokens":1000000,"completionTokens":0}', '{"model":"gpt-5"}');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // (1000 * 3 + 3000 * 12) / 1M; 未知模型不计费
+        let spent = spend(&pool, "p1").await.unwrap();
+        assert!((spent - 0.039).abs() < 1e-9);
+        assert!(spend(&pool, "missing").await.is_err());
+
+        // 未设置预算时不限制
+        ensure_within_budget(&pool, "p1").await.unwrap();
+        let unset = status(&pool, "p1").await.unwrap();
+        assert_eq!(unset.budget_usd, None);
+        assert_eq!(unset.remaining_usd, None);
+        assert_eq!(unset.percentage_used, None);
+        assert!(set_budget(&pool, "p1", Some(0.0)).await.is_err());
+        assert!(set_budget(&pool, "p1", Some(f64::NAN)).await.is_err());
+        assert!(set_budget(&pool, "missing", Some(1.0)).await.is_err());
+
+        set_budget(&pool, "p1", Some(1.0)).await.unwrap();
+        ensure_workflow_within_budget(&pool, "w1").await.unwrap();
+        set_budget(&pool, "p1", Some(0.03)).await.unwrap();
+        assert_eq!(
+            ensure_workflow_within_budget(&pool, "w1").await,
+            Err(CommandError::BudgetExceeded {
+                budget_usd: 0.03,
+                spend_usd: spent
+            })
+        );
+        let over = status(&pool, "p1").await.unwrap();
+        assert_eq!(over.remaining_usd, Some(0.0));
+        assert!((over.percentage_used.unwrap() - 130.0).abs() < 1e-6);
+
+        // 其他项目的费用不计入
+        set_budget(&pool, "p2", Some(5.0)).await.unwrap();
+        ensure_within_budget(&pool, "p2").await.unwrap();
+        let p2 = status(&pool, "p2").await.unwrap();
+        assert_eq!(p2.remaining_usd, Some(2.0));
+        assert_eq!(p2.percentage_used, Some(60.0));
+    }
+
+    #[tokio::test]
+    async fn clears_budget_with_none() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql("INSERT INTO projects (id, name) VALUES ('p1', '测试项目');")
+            .execute(&pool)
+            .await
+            .unwrap();
+        set_budget(&pool, "p1", Some(2.0)).await.unwrap();
+        assert_eq!(budget(&pool, "p1").await.unwrap(), Some(2.0));
+
+        set_budget(&pool, "p1", None).await.unwrap();
+        assert_eq!(status(&pool, "p1").await.unwrap().budget_usd, None);
+        ensure_within_budget(&pool, "p1").await.unwrap();
+        assert!(set_budget(&pool, "missing", None).await.is_err());
+    }
+}
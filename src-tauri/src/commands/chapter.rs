@@ -0,0 +1,1418 @@
+// 章节规划: 按 order_index 排列的章节大纲, 记录梗概、目标字数与写作状态
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::commands::chapter_length::{self, ChapterListItem};
+use crate::commands::chapter_lock::{self, ChapterLocks};
+use crate::commands::{analytics, synopsis, tts};
+use crate::db;
+use crate::error::CommandError;
+use crate::models::{Chapter, Execution};
+
+/// 章节状态, 按写作进度排列 (看板的列)
+pub const CHAPTER_STATUSES: &[&str] = &["draft", "in_progress", "review", "complete"];
+
+/// 生成梗概时每条执行输出取用的句数
+const SYNOPSIS_SENTENCES: usize = 2;
+
+const CHINESE_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+// 重新编号前后的章节标题
+#[derive(Debug, Clone, Serialize)]
+pub struct RenumberedChapter {
+    pub chapter_id: String,
+    pub before: String,
+    pub after: String,
+}
+
+// 删除章节的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteChapterReport {
+    /// 解除关联的执行记录数 (执行记录本身保留)
+    pub executions_unlinked: u64,
+}
+
+// 单个章节的字数
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ChapterWords {
+    pub chapter_id: String,
+    pub title: String,
+    pub words: i64,
+}
+
+// 单章写作进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterWordCount {
+    pub chapter_id: String,
+    pub target_word_count: Option<i64>,
+    pub written_words: i64,
+    // 相对目标字数的百分比, 未设置目标时为 0; 超出目标时大于 100
+    pub percentage: f64,
+    pub last_execution_at: Option<String>,
+}
+
+// 项目仪表盘中的章节进度行
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterProgress {
+    #[serde(flatten)]
+    pub word_count: ChapterWordCount,
+    pub title: String,
+    pub status: String,
+    pub order_index: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ChapterProgressRow {
+    id: String,
+    title: String,
+    status: String,
+    order_index: i64,
+    target_word_count: Option<i64>,
+    written_words: i64,
+    last_execution_at: Option<String>,
+}
+
+// 汇编草稿中的一章
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterDraft {
+    pub chapter_id: String,
+    pub title: String,
+    pub content: String,
+}
+
+// 全书草稿: 按章节顺序汇编的正文
+#[derive(Debug, Clone, Serialize)]
+pub struct FullDraft {
+    pub title: String,
+    pub author: Option<String>,
+    pub chapters: Vec<ChapterDraft>,
+}
+
+// 项目字数: 按章节汇总执行记录的最终输出, 未关联章节的单独计入
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectWordCount {
+    pub project_id: String,
+    pub total_words: i64,
+    pub unassigned_words: i64,
+    pub chapters: Vec<ChapterWords>,
+}
+
+fn validate(title: &str, target_word_count: Option<i64>, status: &str) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("章节标题不能为空".to_string());
+    }
+    if target_word_count.is_some_and(|n| n < 0) {
+        return Err("目标字数不能为负数".to_string());
+    }
+    if !CHAPTER_STATUSES.contains(&status) {
+        return Err(format!("不支持的章节状态: {}", status));
+    }
+    Ok(())
+}
+
+/// 获取单个章节
+pub async fn get(pool: &SqlitePool, chapter_id: &str) -> Result<Chapter, String> {
+    sqlx::query_as::<_, Chapter>("SELECT * FROM chapters WHERE id = ?")
+        .bind(chapter_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("章节不存在: {}", chapter_id))
+}
+
+/// 按顺序列出项目的章节
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<Chapter>, String> {
+    sqlx::query_as::<_, Chapter>(
+        "SELECT * FROM chapters WHERE project_id = ? ORDER BY order_index, created_at",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 列出项目中处于某一状态的章节, 按章节顺序排列
+pub async fn by_status(
+    pool: &SqlitePool,
+    project_id: &str,
+    status: &str,
+) -> Result<Vec<Chapter>, String> {
+    if !CHAPTER_STATUSES.contains(&status) {
+        return Err(format!("不支持的章节状态: {}", status));
+    }
+    sqlx::query_as::<_, Chapter>(
+        "SELECT * FROM chapters WHERE project_id = ? AND status = ? ORDER BY order_index, created_at",
+    )
+    .bind(project_id)
+    .bind(status)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 同一项目中按章节顺序相邻的章节; `forward` 为 true 取下一章, 否则取上一章
+pub async fn adjacent(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    forward: bool,
+) -> Result<Option<Chapter>, String> {
+    let chapter = get(pool, chapter_id).await?;
+    let sql = if forward {
+        "SELECT * FROM chapters WHERE project_id = ? AND (order_index, created_at, id) > (?, ?, ?)
+         ORDER BY order_index, created_at, id LIMIT 1"
+    } else {
+        "SELECT * FROM chapters WHERE project_id = ? AND (order_index, created_at, id) < (?, ?, ?)
+         ORDER BY order_index DESC, created_at DESC, id DESC LIMIT 1"
+    };
+    sqlx::query_as::<_, Chapter>(sql)
+        .bind(&chapter.project_id)
+        .bind(chapter.order_index)
+        .bind(&chapter.created_at)
+        .bind(&chapter.id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按排序号查找章节
+pub async fn get_by_index(
+    pool: &SqlitePool,
+    project_id: &str,
+    order_index: i64,
+) -> Result<Option<Chapter>, String> {
+    sqlx::query_as::<_, Chapter>(
+        "SELECT * FROM chapters WHERE project_id = ? AND order_index = ? LIMIT 1",
+    )
+    .bind(project_id)
+    .bind(order_index)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 创建章节, 排在项目末尾, 初始状态为 draft
+pub async fn create(
+    pool: &SqlitePool,
+    project_id: &str,
+    title: &str,
+    synopsis: Option<&str>,
+    target_word_count: Option<i64>,
+) -> Result<Chapter, String> {
+    validate(title, target_word_count, CHAPTER_STATUSES[0])?;
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+
+    let order_index: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(order_index) + 1, 0) FROM chapters WHERE project_id = ?",
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let now = db::now();
+    let chapter = Chapter {
+        id: db::new_id(),
+        project_id: project_id.to_string(),
+        title: title.trim().to_string(),
+        synopsis: synopsis.map(str::to_string),
+        target_word_count,
+        order_index,
+        status: CHAPTER_STATUSES[0].to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO chapters (id, project_id, title, synopsis, target_word_count, order_index, status, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&chapter.id)
+    .bind(&chapter.project_id)
+    .bind(&chapter.title)
+    .bind(&chapter.synopsis)
+    .bind(chapter.target_word_count)
+    .bind(chapter.order_index)
+    .bind(&chapter.status)
+    .bind(&chapter.created_at)
+    .bind(&chapter.updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(chapter)
+}
+
+/// 复制章节的标题、梗概、目标字数与状态到项目末尾, 不复制关联的执行记录;
+/// 未指定标题时为 "原标题 (副本)", 返回新章节 ID
+pub async fn duplicate(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    new_title: Option<&str>,
+) -> Result<String, String> {
+    let source = get(pool, chapter_id).await?;
+    let title = new_title
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} (副本)", source.title));
+    let copy = create(
+        pool,
+        &source.project_id,
+        &title,
+        source.synopsis.as_deref(),
+        source.target_word_count,
+    )
+    .await?;
+    if copy.status != source.status {
+        set_status(pool, &copy.id, &source.status).await?;
+    }
+    Ok(copy.id)
+}
+
+/// 只更新章节状态
+pub async fn set_status(pool: &SqlitePool, chapter_id: &str, status: &str) -> Result<(), String> {
+    if !CHAPTER_STATUSES.contains(&status) {
+        return Err(format!("不支持的章节状态: {}", status));
+    }
+    let result = sqlx::query("UPDATE chapters SET status = ?, updated_at = ? WHERE id = ?")
+        .bind(status)
+        .bind(db::now())
+        .bind(chapter_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("章节不存在: {}", chapter_id));
+    }
+    Ok(())
+}
+
+/// 更新章节标题、梗概、目标字数与状态
+pub async fn update(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    title: &str,
+    synopsis: Option<&str>,
+    target_word_count: Option<i64>,
+    status: &str,
+) -> Result<Chapter, String> {
+    validate(title, target_word_count, status)?;
+    let mut chapter = get(pool, chapter_id).await?;
+    chapter.title = title.trim().to_string();
+    chapter.synopsis = synopsis.map(str::to_string);
+    chapter.target_word_count = target_word_count;
+    chapter.status = status.to_string();
+    chapter.updated_at = db::now();
+    sqlx::query(
+        "UPDATE chapters SET title = ?, synopsis = ?, target_word_count = ?, status = ?, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(&chapter.title)
+    .bind(&chapter.synopsis)
+    .bind(chapter.target_word_count)
+    .bind(&chapter.status)
+    .bind(&chapter.updated_at)
+    .bind(chapter_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(chapter)
+}
+
+/// 删除章节: 关联的执行记录只解除章节与场景关联不删除, 章节的场景随之删除,
+/// 其余章节的排序号重新编为连续的 0..n
+pub async fn delete(pool: &SqlitePool, chapter_id: &str) -> Result<DeleteChapterReport, String> {
+    let chapter = get(pool, chapter_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let executions_unlinked = sqlx::query(
+        "UPDATE executions SET chapter_id = NULL, scene_id = NULL WHERE chapter_id = ?",
+    )
+    .bind(chapter_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    sqlx::query("DELETE FROM chapters WHERE id = ?")
+        .bind(chapter_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let remaining: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT id, order_index FROM chapters WHERE project_id = ? ORDER BY order_index, created_at, id",
+    )
+    .bind(&chapter.project_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let now = db::now();
+    for (index, (id, order_index)) in remaining.iter().enumerate() {
+        if *order_index == index as i64 {
+            continue;
+        }
+        sqlx::query("UPDATE chapters SET order_index = ?, updated_at = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(DeleteChapterReport {
+        executions_unlinked,
+    })
+}
+
+/// 按给定顺序重排章节; 列表须恰好包含项目的全部章节
+pub async fn reorder(
+    pool: &SqlitePool,
+    project_id: &str,
+    ordered_ids: &[String],
+) -> Result<Vec<Chapter>, String> {
+    let existing: HashSet<String> = list(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let foreign: Vec<&str> = ordered_ids
+        .iter()
+        .filter(|id| !existing.contains(*id))
+        .map(String::as_str)
+        .collect();
+    if !foreign.is_empty() {
+        return Err(format!("章节不属于该项目: {}", foreign.join(", ")));
+    }
+    let requested: HashSet<String> = ordered_ids.iter().cloned().collect();
+    if requested.len() != ordered_ids.len() {
+        return Err("章节列表中有重复的章节".to_string());
+    }
+    if requested != existing {
+        return Err("章节列表须包含项目的全部章节".to_string());
+    }
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (index, id) in ordered_ids.iter().enumerate() {
+        sqlx::query("UPDATE chapters SET order_index = ?, updated_at = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    list(pool, project_id).await
+}
+
+// 四位以内的中文数字 (不含万), 如 1010 → 一千零一十
+fn chinese_below_10000(n: u64) -> String {
+    const UNITS: [&str; 4] = ["千", "百", "十", ""];
+    let digits = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10];
+    let mut out = String::new();
+    let mut pending_zero = false;
+    for (digit, unit) in digits.iter().zip(UNITS) {
+        if *digit == 0 {
+            pending_zero = !out.is_empty();
+            continue;
+        }
+        if pending_zero {
+            out.push('零');
+            pending_zero = false;
+        }
+        out.push(CHINESE_DIGITS[*digit as usize]);
+        out.push_str(unit);
+    }
+    out
+}
+
+/// 阿拉伯数字转中文数字, 如 12 → 十二, 105 → 一百零五, 20001 → 二万零一
+pub fn to_chinese_numeral(n: u64) -> String {
+    if n == 0 {
+        return CHINESE_DIGITS[0].to_string();
+    }
+    let (high, low) = (n / 10000, n % 10000);
+    let mut out = if high > 0 {
+        format!("{}万", to_chinese_numeral(high))
+    } else {
+        String::new()
+    };
+    if low > 0 {
+        if high > 0 && low < 1000 {
+            out.push('零');
+        }
+        out.push_str(&chinese_below_10000(low));
+    }
+    // 十到十九读作 "十X" 而不是 "一十X"
+    if (10..20).contains(&n) {
+        out.remove(0);
+    }
+    out
+}
+
+/// 去掉标题开头的 "第…章" 编号 (到第一个 "章" 为止) 及其后的空白
+pub fn strip_chapter_prefix(title: &str) -> &str {
+    let trimmed = title.trim_start();
+    let Some(rest) = trimmed.strip_prefix('第') else {
+        return trimmed;
+    };
+    match rest.char_indices().find(|(i, c)| *c == '章' && *i > 0) {
+        Some((i, c)) => rest[i + c.len_utf8()..].trim_start(),
+        None => trimmed,
+    }
+}
+
+/// 按排序号重新编号章节标题; `pattern` 中 `{n}` 为序号 (从 1 开始), `{title}` 为去掉旧编号后的标题.
+/// dry_run 时只返回前后对照, 不写入数据库
+pub async fn renumber(
+    pool: &SqlitePool,
+    project_id: &str,
+    pattern: &str,
+    chinese_numerals: bool,
+    dry_run: bool,
+) -> Result<Vec<RenumberedChapter>, String> {
+    if !pattern.contains("{n}") {
+        return Err("编号格式须包含 {n}".to_string());
+    }
+    let changes: Vec<RenumberedChapter> = list(pool, project_id)
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(index, chapter)| {
+            let n = index as u64 + 1;
+            let number = if chinese_numerals {
+                to_chinese_numeral(n)
+            } else {
+                n.to_string()
+            };
+            let after = pattern
+                .replace("{n}", &number)
+                .replace("{title}", strip_chapter_prefix(&chapter.title))
+                .trim()
+                .to_string();
+            RenumberedChapter {
+                chapter_id: chapter.id,
+                before: chapter.title,
+                after,
+            }
+        })
+        .collect();
+    if dry_run {
+        return Ok(changes);
+    }
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for change in changes.iter().filter(|c| c.before != c.after) {
+        sqlx::query("UPDATE chapters SET title = ?, updated_at = ? WHERE id = ?")
+            .bind(&change.after)
+            .bind(&now)
+            .bind(&change.chapter_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(changes)
+}
+
+/// 把执行记录关联到章节, 两者须属于同一项目
+pub async fn link_execution(
+    pool: &SqlitePool,
+    execution_id: &str,
+    chapter_id: &str,
+) -> Result<(), String> {
+    let chapter = get(pool, chapter_id).await?;
+    let project_id: Option<String> = sqlx::query_scalar(
+        "SELECT w.project_id FROM executions e JOIN workflows w ON w.id = e.workflow_id WHERE e.id = ?",
+    )
+    .bind(execution_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    match project_id {
+        None => return Err(format!("执行记录不存在: {}", execution_id)),
+        Some(project_id) if project_id != chapter.project_id => {
+            return Err("执行记录与章节不属于同一项目".to_string())
+        }
+        Some(_) => {}
+    }
+    // 换到其他章节时解除原场景关联
+    sqlx::query(
+        "UPDATE executions SET scene_id = CASE WHEN chapter_id = ? THEN scene_id END, chapter_id = ?
+         WHERE id = ?",
+    )
+    .bind(chapter_id)
+    .bind(chapter_id)
+    .bind(execution_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 章节关联的执行记录, 按开始时间倒序
+pub async fn executions(pool: &SqlitePool, chapter_id: &str) -> Result<Vec<Execution>, String> {
+    sqlx::query_as::<_, Execution>(
+        "SELECT * FROM executions WHERE chapter_id = ? ORDER BY started_at DESC",
+    )
+    .bind(chapter_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 由执行输出拼出梗概: 每条输出按段落切分后取前两句 (跳过 Markdown 标题行), 各条之间换行
+pub fn synopsis_from_outputs(outputs: &[String]) -> String {
+    outputs
+        .iter()
+        .map(|output| {
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|paragraph| !paragraph.is_empty() && !paragraph.starts_with('#'))
+                .flat_map(tts::split_sentences)
+                .map(|sentence| sentence.trim().to_string())
+                .filter(|sentence| !sentence.is_empty())
+                .take(SYNOPSIS_SENTENCES)
+                .collect::<String>()
+        })
+        .filter(|excerpt| !excerpt.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 按开始时间顺序取章节已完成执行的最终输出生成梗概, 纯文本处理, 不调用 AI
+pub async fn generate_synopsis(pool: &SqlitePool, chapter_id: &str) -> Result<String, String> {
+    get(pool, chapter_id).await?;
+    let outputs: Vec<String> = sqlx::query_scalar(
+        "SELECT final_output FROM executions
+         WHERE chapter_id = ? AND status = 'completed' AND final_output IS NOT NULL
+         ORDER BY started_at",
+    )
+    .bind(chapter_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(synopsis_from_outputs(&outputs))
+}
+
+// 相对目标字数的百分比, 未设置目标时为 0
+fn percentage(written_words: i64, target_word_count: Option<i64>) -> f64 {
+    match target_word_count {
+        Some(target) if target > 0 => written_words as f64 / target as f64 * 100.0,
+        _ => 0.0,
+    }
+}
+
+/// 单章字数: 与章节正文口径一致, 按已完成执行记录最终输出的字符数累计
+pub async fn word_count(pool: &SqlitePool, chapter_id: &str) -> Result<ChapterWordCount, String> {
+    let chapter = get(pool, chapter_id).await?;
+    let (written_words, last_execution_at): (i64, Option<String>) = sqlx::query_as(
+        "SELECT COALESCE(SUM(LENGTH(final_output)), 0), MAX(started_at)
+         FROM executions
+         WHERE chapter_id = ? AND status = 'completed' AND final_output IS NOT NULL",
+    )
+    .bind(chapter_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(ChapterWordCount {
+        chapter_id: chapter.id,
+        target_word_count: chapter.target_word_count,
+        written_words,
+        percentage: percentage(written_words, chapter.target_word_count),
+        last_execution_at,
+    })
+}
+
+/// 项目全部章节的写作进度, 一次查询取得, 按章节顺序排列
+pub async fn all_chapters_progress(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<ChapterProgress>, String> {
+    let rows = sqlx::query_as::<_, ChapterProgressRow>(
+        "SELECT c.id, c.title, c.status, c.order_index, c.target_word_count,
+                COALESCE(SUM(LENGTH(e.final_output)), 0) AS written_words,
+                MAX(e.started_at) AS last_execution_at
+         FROM chapters c
+         LEFT JOIN executions e
+             ON e.chapter_id = c.id AND e.status = 'completed' AND e.final_output IS NOT NULL
+         WHERE c.project_id = ?
+         GROUP BY c.id
+         ORDER BY c.order_index, c.created_at",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ChapterProgress {
+            word_count: ChapterWordCount {
+                chapter_id: row.id,
+                target_word_count: row.target_word_count,
+                written_words: row.written_words,
+                percentage: percentage(row.written_words, row.target_word_count),
+                last_execution_at: row.last_execution_at,
+            },
+            title: row.title,
+            status: row.status,
+            order_index: row.order_index,
+        })
+        .collect())
+}
+
+/// 统计项目字数 (已完成执行最终输出的字符数), 按章节顺序列出各章字数
+pub async fn project_word_count(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<ProjectWordCount, String> {
+    let chapters = sqlx::query_as::<_, ChapterWords>(
+        "SELECT c.id AS chapter_id, c.title, COALESCE(SUM(LENGTH(e.final_output)), 0) AS words
+         FROM chapters c
+         LEFT JOIN executions e
+             ON e.chapter_id = c.id AND e.status = 'completed' AND e.final_output IS NOT NULL
+         WHERE c.project_id = ?
+         GROUP BY c.id
+         ORDER BY c.order_index, c.created_at",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let unassigned_words: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(LENGTH(e.final_output)), 0)
+         FROM executions e
+         JOIN workflows w ON w.id = e.workflow_id
+         WHERE w.project_id = ? AND e.status = 'completed'
+           AND (e.chapter_id IS NULL OR e.chapter_id NOT IN (SELECT id FROM chapters))",
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(ProjectWordCount {
+        project_id: project_id.to_string(),
+        total_words: unassigned_words + chapters.iter().map(|c| c.words).sum::<i64>(),
+        unassigned_words,
+        chapters,
+    })
+}
+
+// 章节正文中的各段执行输出以空行分隔
+fn append_output(content: &mut String, output: &str) {
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    content.push_str(output.trim());
+}
+
+/// 单章正文, 与全书草稿中该章的正文一致
+pub async fn content(pool: &SqlitePool, chapter_id: &str) -> Result<String, String> {
+    get(pool, chapter_id).await?;
+    let outputs: Vec<String> = sqlx::query_scalar(
+        "SELECT final_output FROM executions
+         WHERE chapter_id = ? AND status = 'completed' AND final_output IS NOT NULL
+         ORDER BY started_at",
+    )
+    .bind(chapter_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut content = String::new();
+    for output in outputs {
+        append_output(&mut content, &output);
+    }
+    Ok(content)
+}
+
+/// 汇编全书草稿: 章节按 order_index 排列, 正文为该章已完成执行的最终输出按开始时间拼接,
+/// 没有已完成执行的章节正文为空
+pub async fn full_draft(pool: &SqlitePool, project_id: &str) -> Result<FullDraft, String> {
+    let (title, author): (String, Option<String>) =
+        sqlx::query_as("SELECT name, author FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("项目不存在: {}", project_id))?;
+
+    let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT c.id, c.title, e.final_output
+         FROM chapters c
+         LEFT JOIN executions e
+             ON e.chapter_id = c.id AND e.status = 'completed' AND e.final_output IS NOT NULL
+         WHERE c.project_id = ?
+         ORDER BY c.order_index, c.created_at, e.started_at",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut chapters: Vec<(String, ChapterDraft)> = Vec::new();
+    for (chapter_id, chapter_title, output) in rows {
+        if chapters.last().is_none_or(|(id, _)| *id != chapter_id) {
+            let draft = ChapterDraft {
+                chapter_id: chapter_id.clone(),
+                title: chapter_title,
+                content: String::new(),
+            };
+            chapters.push((chapter_id, draft));
+        }
+        if let (Some(output), Some((_, draft))) = (output, chapters.last_mut()) {
+            append_output(&mut draft.content, &output);
+        }
+    }
+    Ok(FullDraft {
+        title,
+        author,
+        chapters: chapters.into_iter().map(|(_, draft)| draft).collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn create_chapter_outline(
+    app: AppHandle,
+    project_id: String,
+    title: String,
+    synopsis: Option<String>,
+    target_word_count: Option<i64>,
+) -> Result<Chapter, String> {
+    let pool = db::pool(&app).await?;
+    create(
+        &pool,
+        &project_id,
+        &title,
+        synopsis.as_deref(),
+        target_word_count,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_chapter(app: AppHandle, chapter_id: String) -> Result<Chapter, String> {
+    let pool = db::pool(&app).await?;
+    get(&pool, &chapter_id).await
+}
+
+#[tauri::command]
+pub async fn update_chapter(
+    app: AppHandle,
+    window: Window,
+    chapter_id: String,
+    title: String,
+    synopsis: Option<String>,
+    target_word_count: Option<i64>,
+    status: String,
+) -> Result<Chapter, CommandError> {
+    let pool = db::pool(&app).await?;
+    chapter_lock::ensure_unlocked(&pool, &window, std::slice::from_ref(&chapter_id)).await?;
+    let chapter = update(
+        &pool,
+        &chapter_id,
+        &title,
+        synopsis.as_deref(),
+        target_word_count,
+        &status,
+    )
+    .await?;
+    Ok(chapter)
+}
+
+#[tauri::command]
+pub async fn set_chapter_status(
+    app: AppHandle,
+    window: Window,
+    chapter_id: String,
+    status: String,
+) -> Result<(), CommandError> {
+    let pool = db::pool(&app).await?;
+    chapter_lock::ensure_unlocked(&pool, &window, std::slice::from_ref(&chapter_id)).await?;
+    Ok(set_status(&pool, &chapter_id, &status).await?)
+}
+
+#[tauri::command]
+pub async fn get_chapters_by_status(
+    app: AppHandle,
+    project_id: String,
+    status: String,
+) -> Result<Vec<Chapter>, String> {
+    let pool = db::pool(&app).await?;
+    by_status(&pool, &project_id, &status).await
+}
+
+#[tauri::command]
+pub async fn delete_chapter(
+    app: AppHandle,
+    window: Window,
+    chapter_id: String,
+) -> Result<DeleteChapterReport, CommandError> {
+    let pool = db::pool(&app).await?;
+    chapter_lock::ensure_unlocked(&pool, &window, std::slice::from_ref(&chapter_id)).await?;
+    let report = delete(&pool, &chapter_id).await?;
+    window.state::<ChapterLocks>().forget(&chapter_id);
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn duplicate_chapter(
+    app: AppHandle,
+    chapter_id: String,
+    new_title: Option<String>,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    duplicate(&pool, &chapter_id, new_title.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn reorder_chapters(
+    app: AppHandle,
+    project_id: String,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<Chapter>, String> {
+    let pool = db::pool(&app).await?;
+    reorder(&pool, &project_id, &ordered_ids).await
+}
+
+#[tauri::command]
+pub async fn get_chapter_by_index(
+    app: AppHandle,
+    project_id: String,
+    order_index: i64,
+) -> Result<Option<Chapter>, String> {
+    let pool = db::pool(&app).await?;
+    get_by_index(&pool, &project_id, order_index).await
+}
+
+#[tauri::command]
+pub async fn get_next_chapter(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<Option<Chapter>, String> {
+    let pool = db::pool(&app).await?;
+    adjacent(&pool, &chapter_id, true).await
+}
+
+#[tauri::command]
+pub async fn get_previous_chapter(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<Option<Chapter>, String> {
+    let pool = db::pool(&app).await?;
+    adjacent(&pool, &chapter_id, false).await
+}
+
+#[tauri::command]
+pub async fn get_project_chapter_list(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<ChapterListItem>, String> {
+    let pool = db::pool(&app).await?;
+    chapter_length::list(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn renumber_chapters(
+    app: AppHandle,
+    window: Window,
+    project_id: String,
+    pattern: String,
+    chinese_numerals: bool,
+    dry_run: bool,
+) -> Result<Vec<RenumberedChapter>, CommandError> {
+    let pool = db::pool(&app).await?;
+    if !dry_run {
+        let ids: Vec<String> = list(&pool, &project_id)
+            .await?
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        chapter_lock::ensure_unlocked(&pool, &window, &ids).await?;
+    }
+    Ok(renumber(&pool, &project_id, &pattern, chinese_numerals, dry_run).await?)
+}
+
+#[tauri::command]
+pub async fn link_execution_to_chapter(
+    app: AppHandle,
+    execution_id: String,
+    chapter_id: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    link_execution(&pool, &execution_id, &chapter_id).await?;
+    let payload = serde_json::json!({ "chapter_id": chapter_id, "execution_id": execution_id });
+    if let Err(e) = analytics::record(&pool, "chapter_accepted", Some(&payload)).await {
+        eprintln!("记录使用统计失败: {}", e);
+    }
+    synopsis::on_chapter_accepted(&app, &pool, &chapter_id).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_chapter_executions(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<Vec<Execution>, String> {
+    let pool = db::pool(&app).await?;
+    executions(&pool, &chapter_id).await
+}
+
+#[tauri::command]
+pub async fn generate_chapter_synopsis(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    generate_synopsis(&pool, &chapter_id).await
+}
+
+#[tauri::command]
+pub async fn get_project_full_draft(
+    app: AppHandle,
+    project_id: String,
+) -> Result<FullDraft, String> {
+    let pool = db::pool(&app).await?;
+    full_draft(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn get_chapter_word_count(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<ChapterWordCount, String> {
+    let pool = db::pool(&app).await?;
+    word_count(&pool, &chapter_id).await
+}
+
+#[tauri::command]
+pub async fn compute_project_word_count(
+    app: AppHandle,
+    project_id: String,
+) -> Result<ProjectWordCount, String> {
+    let pool = db::pool(&app).await?;
+    project_word_count(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn get_all_chapters_progress(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<ChapterProgress>, String> {
+    let pool = db::pool(&app).await?;
+    all_chapters_progress(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql("INSERT INTO projects (id, name) VALUES ('p1', '测试项目');")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn creates_and_updates_chapters() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let first = create(&pool, "p1", "雨夜", Some("林雪救下沈舟"), Some(3000))
+            .await
+            .unwrap();
+        let second = create(&pool, "p1", "玉佩", None, None).await.unwrap();
+        assert_eq!((first.order_index, second.order_index), (0, 1));
+        assert_eq!(first.status, "draft");
+        assert!(create(&pool, "p1", " ", None, None).await.is_err());
+        assert!(create(&pool, "p1", "负数", None, Some(-1)).await.is_err());
+        assert!(create(&pool, "missing", "雨夜", None, None).await.is_err());
+
+        let updated = update(&pool, &second.id, "古玉", None, Some(2500), "in_progress")
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &second.id).await.unwrap().title, updated.title);
+        assert!(update(&pool, &second.id, "古玉", None, None, "published")
+            .await
+            .is_err());
+
+        let found = get_by_index(&pool, "p1", 1).await.unwrap().unwrap();
+        assert_eq!(found.id, second.id);
+        assert!(get_by_index(&pool, "p1", 5).await.unwrap().is_none());
+
+        delete(&pool, &first.id).await.unwrap();
+        assert_eq!(list(&pool, "p1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn tracks_chapters_by_status() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let first = create(&pool, "p1", "雨夜", None, None).await.unwrap();
+        let second = create(&pool, "p1", "玉佩", None, None).await.unwrap();
+        let third = create(&pool, "p1", "进城", None, None).await.unwrap();
+
+        set_status(&pool, &third.id, "review").await.unwrap();
+        set_status(&pool, &first.id, "review").await.unwrap();
+        assert!(set_status(&pool, &second.id, "done").await.is_err());
+        assert!(set_status(&pool, "missing", "review").await.is_err());
+
+        let review = by_status(&pool, "p1", "review").await.unwrap();
+        let ids: Vec<&str> = review.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec![first.id.as_str(), third.id.as_str()]);
+        assert_eq!(
+            by_status(&pool, "p1", "draft").await.unwrap()[0].id,
+            second.id
+        );
+        assert!(by_status(&pool, "p1", "complete").await.unwrap().is_empty());
+        assert!(by_status(&pool, "p1", "archived").await.is_err());
+
+        // 未指定状态直接写入的章节 (如导入) 默认为 draft
+        sqlx::query("INSERT INTO chapters (id, project_id, title) VALUES ('c9', 'p1', '补录')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, "c9").await.unwrap().status, "draft");
+    }
+
+    #[tokio::test]
+    async fn duplicates_chapter_to_the_end_without_executions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            "INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status) VALUES ('e1', 'w1', 'completed');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let first = create(&pool, "p1", "雨夜", Some("林雪推门"), Some(3000))
+            .await
+            .unwrap();
+        create(&pool, "p1", "玉佩", None, None).await.unwrap();
+        set_status(&pool, &first.id, "review").await.unwrap();
+        link_execution(&pool, "e1", &first.id).await.unwrap();
+
+        let copy = get(&pool, &duplicate(&pool, &first.id, None).await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(copy.title, "雨夜 (副本)");
+        assert_eq!(copy.synopsis.as_deref(), Some("林雪推门"));
+        assert_eq!(copy.target_word_count, Some(3000));
+        assert_eq!(copy.status, "review");
+        assert_eq!(copy.order_index, 2);
+        assert!(executions(&pool, &copy.id).await.unwrap().is_empty());
+
+        let named = duplicate(&pool, &first.id, Some("雨夜 (改)"))
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &named).await.unwrap().order_index, 3);
+        assert!(duplicate(&pool, &first.id, Some(" ")).await.is_err());
+        assert!(duplicate(&pool, "missing", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reorders_only_complete_chapter_lists() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let a = create(&pool, "p1", "一", None, None).await.unwrap().id;
+        let b = create(&pool, "p1", "二", None, None).await.unwrap().id;
+        let c = create(&pool, "p1", "三", None, None).await.unwrap().id;
+
+        let chapters = reorder(&pool, "p1", &[c.clone(), a.clone(), b.clone()])
+            .await
+            .unwrap();
+        let ids: Vec<&str> = chapters.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec![c.as_str(), a.as_str(), b.as_str()]);
+
+        assert!(reorder(&pool, "p1", &[a.clone(), b.clone()]).await.is_err());
+        assert!(reorder(&pool, "p1", &[a.clone(), a.clone(), b.clone()])
+            .await
+            .is_err());
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p2', '其他项目');
+             INSERT INTO chapters (id, project_id, title) VALUES ('x1', 'p2', '别处');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let foreign = reorder(
+            &pool,
+            "p1",
+            &[c.clone(), a.clone(), b.clone(), "x1".to_string()],
+        )
+        .await
+        .unwrap_err();
+        assert!(foreign.contains("x1"), "{}", foreign);
+        assert_eq!(list(&pool, "p1").await.unwrap()[0].id, c);
+
+        // 导航跟随新的顺序: 三 → 一 → 二
+        let next = adjacent(&pool, &c, true).await.unwrap().unwrap();
+        assert_eq!(next.id, a);
+        let previous = adjacent(&pool, &b, false).await.unwrap().unwrap();
+        assert_eq!(previous.id, a);
+        assert!(adjacent(&pool, &c, false).await.unwrap().is_none());
+        assert!(adjacent(&pool, &b, true).await.unwrap().is_none());
+        assert!(adjacent(&pool, "missing", true).await.is_err());
+    }
+
+    #[test]
+    fn converts_chinese_numerals() {
+        let cases = [
+            (1, "一"),
+            (10, "十"),
+            (12, "十二"),
+            (20, "二十"),
+            (105, "一百零五"),
+            (110, "一百一十"),
+            (1010, "一千零一十"),
+            (10000, "一万"),
+            (20001, "二万零一"),
+            (101100, "十万一千一百"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(to_chinese_numeral(n), expected, "{}", n);
+        }
+    }
+
+    #[tokio::test]
+    async fn renumbers_titles_in_order() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let a = create(&pool, "p1", "第12章 雨夜", None, None)
+            .await
+            .unwrap();
+        let b = create(&pool, "p1", "第三章玉佩", None, None).await.unwrap();
+        let c = create(&pool, "p1", "章鱼", None, None).await.unwrap();
+        reorder(&pool, "p1", &[b.id, a.id.clone(), c.id])
+            .await
+            .unwrap();
+
+        let preview = renumber(&pool, "p1", "第{n}章 {title}", true, true)
+            .await
+            .unwrap();
+        let after: Vec<&str> = preview.iter().map(|c| c.after.as_str()).collect();
+        assert_eq!(after, vec!["第一章 玉佩", "第二章 雨夜", "第三章 章鱼"]);
+        assert_eq!(get(&pool, &a.id).await.unwrap().title, "第12章 雨夜");
+
+        renumber(&pool, "p1", "第{n}章 {title}", false, false)
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &a.id).await.unwrap().title, "第2章 雨夜");
+        assert!(renumber(&pool, "p1", "{title}", false, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn links_executions_and_counts_words_per_chapter() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '其他项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p2', '正文生成');
+            INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                ('e1', 'w1', 'completed', '雨下得很急。', '2026-01-01T00:00:00Z'),
+                ('e2', 'w1', 'completed', '林雪推开木门', '2026-01-02T00:00:00Z'),
+                ('e3', 'w1', 'completed', '未归档', '2026-01-03T00:00:00Z'),
+                ('e4', 'w2', 'completed', '别的项目', '2026-01-03T00:00:00Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let first = create(&pool, "p1", "雨夜", None, None).await.unwrap();
+        let second = create(&pool, "p1", "玉佩", None, None).await.unwrap();
+
+        link_execution(&pool, "e1", &first.id).await.unwrap();
+        link_execution(&pool, "e2", &first.id).await.unwrap();
+        assert!(link_execution(&pool, "e4", &first.id).await.is_err());
+        assert!(link_execution(&pool, "missing", &first.id).await.is_err());
+
+        let linked = executions(&pool, &first.id).await.unwrap();
+        let ids: Vec<&str> = linked.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["e2", "e1"]);
+
+        let progress = word_count(&pool, &first.id).await.unwrap();
+        assert_eq!(progress.written_words, 12);
+        assert_eq!(
+            progress.last_execution_at.as_deref(),
+            Some("2026-01-02T00:00:00Z")
+        );
+        assert_eq!(progress.percentage, 0.0);
+        update(&pool, &first.id, "雨夜", None, Some(48), "in_progress")
+            .await
+            .unwrap();
+        assert_eq!(word_count(&pool, &first.id).await.unwrap().percentage, 25.0);
+        assert!(word_count(&pool, &second.id)
+            .await
+            .unwrap()
+            .last_execution_at
+            .is_none());
+
+        let count = project_word_count(&pool, "p1").await.unwrap();
+        assert_eq!(count.chapters[0].words, 12);
+        assert_eq!(count.chapters[1].chapter_id, second.id);
+        assert_eq!(count.chapters[1].words, 0);
+        assert_eq!(count.unassigned_words, 3);
+        assert_eq!(count.total_words, 15);
+
+        let progress = all_chapters_progress(&pool, "p1").await.unwrap();
+        assert_eq!(progress.len(), 2);
+        assert_eq!(progress[0].title, "雨夜");
+        assert_eq!(progress[0].status, "in_progress");
+        assert_eq!(progress[0].word_count.written_words, 12);
+        assert_eq!(progress[0].word_count.percentage, 25.0);
+        assert_eq!(progress[1].order_index, 1);
+        assert!(progress[1].word_count.last_execution_at.is_none());
+        assert!(all_chapters_progress(&pool, "p2").await.unwrap().is_empty());
+
+        let third = create(&pool, "p1", "鬼市", None, None).await.unwrap();
+        let report = delete(&pool, &first.id).await.unwrap();
+        assert_eq!(report.executions_unlinked, 2);
+        let unlinked: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM executions WHERE chapter_id IS NULL")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(unlinked, 4);
+        let remaining: Vec<(String, i64)> = list(&pool, "p1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.id, c.order_index))
+            .collect();
+        assert_eq!(remaining, vec![(second.id, 0), (third.id, 1)]);
+        assert!(delete(&pool, &first.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn word_counts_ignore_unfinished_executions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let chapter = create(&pool, "p1", "雨夜", None, Some(10)).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                 ('e1', 'w1', 'completed', '雨下得很急。', '2026-01-01T00:00:00.000Z'),
+                 ('e2', 'w1', 'failed', '半途而废。', '2026-01-02T00:00:00.000Z'),
+                 ('e3', 'w1', 'failed', '未归档', '2026-01-03T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        link_execution(&pool, "e1", &chapter.id).await.unwrap();
+        link_execution(&pool, "e2", &chapter.id).await.unwrap();
+
+        let single = word_count(&pool, &chapter.id).await.unwrap();
+        assert_eq!(single.written_words, 6);
+        assert_eq!(single.percentage, 60.0);
+        assert_eq!(
+            single.last_execution_at.as_deref(),
+            Some("2026-01-01T00:00:00.000Z")
+        );
+        let progress = all_chapters_progress(&pool, "p1").await.unwrap();
+        assert_eq!(progress[0].word_count.written_words, 6);
+        assert_eq!(
+            progress[0].word_count.last_execution_at,
+            single.last_execution_at
+        );
+        let count = project_word_count(&pool, "p1").await.unwrap();
+        assert_eq!(count.chapters[0].words, 6);
+        assert_eq!(count.unassigned_words, 0);
+        assert_eq!(count.total_words, 6);
+        assert_eq!(
+            content(&pool, &chapter.id).await.unwrap().chars().count(),
+            6
+        );
+    }
+
+    #[tokio::test]
+    async fn builds_synopsis_from_completed_outputs() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let chapter = create(&pool, "p1", "雨夜", None, None).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                 ('e1', 'w1', 'completed', '# 第一章\n\n雨下得很急。林雪推开木门。“可否借个火？”他问。', '2026-01-01T00:00:00.000Z'),
+                 ('e2', 'w1', 'failed', '半途而废。', '2026-01-02T00:00:00.000Z'),
+                 ('e3', 'w1', 'completed', '沈舟醒来\n玉佩不见了！', '2026-01-03T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for id in ["e3", "e1", "e2"] {
+            link_execution(&pool, id, &chapter.id).await.unwrap();
+        }
+
+        assert_eq!(
+            generate_synopsis(&pool, &chapter.id).await.unwrap(),
+            "雨下得很急。林雪推开木门。\n沈舟醒来玉佩不见了！"
+        );
+        assert!(generate_synopsis(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn compiles_full_draft_in_chapter_order() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::query("UPDATE projects SET author = '沈舟' WHERE id = 'p1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let first = create(&pool, "p1", "雨夜", None, None).await.unwrap();
+        let second = create(&pool, "p1", "玉佩", None, None).await.unwrap();
+        let empty = create(&pool, "p1", "鬼市", None, None).await.unwrap();
+        reorder(
+            &pool,
+            "p1",
+            &[second.id.clone(), first.id.clone(), empty.id.clone()],
+        )
+        .await
+        .unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                 ('e1', 'w1', 'completed', '雨下得很急。\n', '2026-01-02T00:00:00.000Z'),
+                 ('e2', 'w1', 'completed', '林雪推开木门。', '2026-01-03T00:00:00.000Z'),
+                 ('e3', 'w1', 'failed', '半途而废。', '2026-01-04T00:00:00.000Z'),
+                 ('e4', 'w1', 'completed', '玉佩碎了。', '2026-01-01T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (execution, chapter) in [
+            ("e2", &first),
+            ("e1", &first),
+            ("e3", &first),
+            ("e4", &second),
+        ] {
+            link_execution(&pool, execution, &chapter.id).await.unwrap();
+        }
+
+        let draft = full_draft(&pool, "p1").await.unwrap();
+        assert_eq!(draft.title, "测试项目");
+        assert_eq!(draft.author.as_deref(), Some("沈舟"));
+        let chapters: Vec<(&str, &str)> = draft
+            .chapters
+            .iter()
+            .map(|c| (c.title.as_str(), c.content.as_str()))
+            .collect();
+        assert_eq!(
+            chapters,
+            vec![
+                ("玉佩", "玉佩碎了。"),
+                ("雨夜", "雨下得很急。\n\n林雪推开木门。"),
+                ("鬼市", ""),
+            ]
+        );
+        assert!(full_draft(&pool, "missing").await.is_err());
+    }
+}
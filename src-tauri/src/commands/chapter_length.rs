@@ -0,0 +1,254 @@
+// 章节字数范围: 项目统一要求每章正文在 min_chapter_chars..=max_chapter_chars 字符之间 (如连载平台的单章字数要求).
+// 字数按全书草稿中的章节正文计算; 尚未写入正文的章节不算超出范围
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::chapter::{self, FullDraft};
+use crate::db;
+use crate::models::Chapter;
+
+/// 超出范围时执行引擎的处理方式: 不检查 / 在最后一个 AI 节点追加扩写或压缩提示重试 / 接受并警告
+pub const ENFORCEMENT_MODES: &[&str] = &["off", "retry", "warn"];
+
+// 项目的章节字数范围, 未设置的一端不限制
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChapterLengthRange {
+    pub min_chapter_chars: Option<i64>,
+    pub max_chapter_chars: Option<i64>,
+    pub enforcement: String,
+}
+
+impl ChapterLengthRange {
+    /// 正文字数是否超出范围; 空章节不算
+    pub fn out_of_range(&self, chars: i64) -> bool {
+        chars > 0
+            && (self.min_chapter_chars.is_some_and(|min| chars < min)
+                || self.max_chapter_chars.is_some_and(|max| chars > max))
+    }
+}
+
+// 章节列表项: 章节及其正文字数
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterListItem {
+    #[serde(flatten)]
+    pub chapter: Chapter,
+    pub chars: i64,
+    pub out_of_range: bool,
+}
+
+// 正文字数超出范围的章节
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChapterLengthIssue {
+    pub chapter_id: String,
+    pub title: String,
+    pub chars: i64,
+}
+
+/// 获取项目的章节字数范围
+pub async fn get_range(pool: &SqlitePool, project_id: &str) -> Result<ChapterLengthRange, String> {
+    let row: Option<(Option<i64>, Option<i64>, String)> = sqlx::query_as(
+        "SELECT min_chapter_chars, max_chapter_chars, chapter_length_enforcement
+         FROM projects WHERE id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (min_chapter_chars, max_chapter_chars, enforcement) =
+        row.ok_or_else(|| format!("项目不存在: {}", project_id))?;
+    Ok(ChapterLengthRange {
+        min_chapter_chars,
+        max_chapter_chars,
+        enforcement,
+    })
+}
+
+/// 设置项目的章节字数范围; 两端均须大于 0 且下限不大于上限
+pub async fn set_range(
+    pool: &SqlitePool,
+    project_id: &str,
+    range: &ChapterLengthRange,
+) -> Result<ChapterLengthRange, String> {
+    if !ENFORCEMENT_MODES.contains(&range.enforcement.as_str()) {
+        return Err(format!("不支持的处理方式: {}", range.enforcement));
+    }
+    if range
+        .min_chapter_chars
+        .into_iter()
+        .chain(range.max_chapter_chars)
+        .any(|chars| chars <= 0)
+    {
+        return Err("章节字数须大于 0".to_string());
+    }
+    if let (Some(min), Some(max)) = (range.min_chapter_chars, range.max_chapter_chars) {
+        if min > max {
+            return Err("最少字数不能大于最多字数".to_string());
+        }
+    }
+
+    let result = sqlx::query(
+        "UPDATE projects
+         SET min_chapter_chars = ?, max_chapter_chars = ?, chapter_length_enforcement = ?
+         WHERE id = ?",
+    )
+    .bind(range.min_chapter_chars)
+    .bind(range.max_chapter_chars)
+    .bind(&range.enforcement)
+    .bind(project_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+    get_range(pool, project_id).await
+}
+
+/// 全书草稿中超出范围的章节, 按章节顺序排列
+pub fn issues(draft: &FullDraft, range: &ChapterLengthRange) -> Vec<ChapterLengthIssue> {
+    draft
+        .chapters
+        .iter()
+        .map(|c| (c, c.content.chars().count() as i64))
+        .filter(|(_, chars)| range.out_of_range(*chars))
+        .map(|(c, chars)| ChapterLengthIssue {
+            chapter_id: c.chapter_id.clone(),
+            title: c.title.clone(),
+            chars,
+        })
+        .collect()
+}
+
+/// 按顺序列出项目章节, 附带正文字数与是否超出范围
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<ChapterListItem>, String> {
+    let range = get_range(pool, project_id).await?;
+    let draft = chapter::full_draft(pool, project_id).await?;
+    let chapters = chapter::list(pool, project_id).await?;
+    Ok(chapters
+        .into_iter()
+        .map(|chapter| {
+            let chars = draft
+                .chapters
+                .iter()
+                .find(|c| c.chapter_id == chapter.id)
+                .map_or(0, |c| c.content.chars().count() as i64);
+            ChapterListItem {
+                chapter,
+                chars,
+                out_of_range: range.out_of_range(chars),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_chapter_length_range(
+    app: AppHandle,
+    project_id: String,
+) -> Result<ChapterLengthRange, String> {
+    let pool = db::pool(&app).await?;
+    get_range(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn set_chapter_length_range(
+    app: AppHandle,
+    project_id: String,
+    min_chapter_chars: Option<i64>,
+    max_chapter_chars: Option<i64>,
+    enforcement: String,
+) -> Result<ChapterLengthRange, String> {
+    let pool = db::pool(&app).await?;
+    let range = ChapterLengthRange {
+        min_chapter_chars,
+        max_chapter_chars,
+        enforcement,
+    };
+    set_range(&pool, &project_id, &range).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(min: Option<i64>, max: Option<i64>) -> ChapterLengthRange {
+        ChapterLengthRange {
+            min_chapter_chars: min,
+            max_chapter_chars: max,
+            enforcement: "warn".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn flags_chapters_out_of_range() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO chapters (id, project_id, title, order_index) VALUES
+                 ('c1', 'p1', '第一章', 0), ('c2', 'p1', '第二章', 1),
+                 ('c3', 'p1', '第三章', 2), ('c4', 'p1', '第四章', 3);
+             INSERT INTO executions (id, workflow_id, status, final_output, chapter_id, started_at) VALUES
+                 ('e1', 'w1', 'completed', '雨夜', 'c1', '2026-01-01T00:00:00.000Z'),
+                 ('e2', 'w1', 'completed', '林雪推开了门', 'c2', '2026-01-01T00:00:00.000Z'),
+                 ('e3', 'w1', 'completed', '林雪推开了门，外面在下雨', 'c3', '2026-01-01T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let default = get_range(&pool, "p1").await.unwrap();
+        assert_eq!(
+            default,
+            ChapterLengthRange {
+                enforcement: "off".to_string(),
+                ..range(None, None)
+            }
+        );
+        assert!(list(&pool, "p1")
+            .await
+            .unwrap()
+            .iter()
+            .all(|c| !c.out_of_range));
+
+        assert!(set_range(&pool, "p1", &range(Some(10), Some(5)))
+            .await
+            .is_err());
+        assert!(set_range(&pool, "p1", &range(Some(0), None)).await.is_err());
+        let invalid = ChapterLengthRange {
+            enforcement: "block".to_string(),
+            ..range(None, None)
+        };
+        assert!(set_range(&pool, "p1", &invalid).await.is_err());
+        assert!(set_range(&pool, "missing", &range(None, None))
+            .await
+            .is_err());
+
+        set_range(&pool, "p1", &range(Some(3), Some(10)))
+            .await
+            .unwrap();
+        let flags: Vec<(String, i64, bool)> = list(&pool, "p1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.chapter.id, c.chars, c.out_of_range))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                ("c1".to_string(), 2, true),
+                ("c2".to_string(), 6, false),
+                ("c3".to_string(), 12, true),
+                ("c4".to_string(), 0, false)
+            ]
+        );
+
+        let draft = chapter::full_draft(&pool, "p1").await.unwrap();
+        let ids: Vec<String> = issues(&draft, &range(None, Some(10)))
+            .into_iter()
+            .map(|i| i.chapter_id)
+            .collect();
+        assert_eq!(ids, ["c3"]);
+    }
+}
@@ -0,0 +1,325 @@
+// 章节锁: 防止多个窗口同时修改同一章节. 锁保存在内存中, 以获取锁的窗口为持有方,
+// 窗口关闭时释放, 空闲超过设定时长 (global_config.chapter_lock_idle_seconds) 自动失效
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::db;
+use crate::error::CommandError;
+
+/// 默认空闲失效秒数
+const DEFAULT_IDLE_SECONDS: i64 = 300;
+
+// 章节锁
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChapterLock {
+    pub chapter_id: String,
+    /// 显示给其他窗口的持有者名称
+    pub holder_label: String,
+    /// 持有锁的窗口
+    pub window_label: String,
+    pub acquired_at: String,
+    pub last_active_at: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    holder_label: String,
+    window_label: String,
+    acquired_at: DateTime<Utc>,
+    last_active_at: DateTime<Utc>,
+}
+
+impl Entry {
+    fn expired(&self, now: DateTime<Utc>, idle: Duration) -> bool {
+        now - self.last_active_at >= idle
+    }
+
+    fn to_lock(&self, chapter_id: &str, idle: Duration) -> ChapterLock {
+        ChapterLock {
+            chapter_id: chapter_id.to_string(),
+            holder_label: self.holder_label.clone(),
+            window_label: self.window_label.clone(),
+            acquired_at: db::to_iso(self.acquired_at),
+            last_active_at: db::to_iso(self.last_active_at),
+            expires_at: db::to_iso(self.last_active_at + idle),
+        }
+    }
+}
+
+/// 章节锁表, 作为应用状态管理
+#[derive(Default)]
+pub struct ChapterLocks(Mutex<HashMap<String, Entry>>);
+
+impl ChapterLocks {
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 获取锁; 同一窗口重复获取时刷新持有者名称与活动时间, 其他窗口持有未失效的锁时返回 Locked
+    pub fn acquire(
+        &self,
+        chapter_id: &str,
+        holder_label: &str,
+        window_label: &str,
+        now: DateTime<Utc>,
+        idle: Duration,
+    ) -> Result<ChapterLock, CommandError> {
+        let holder_label = holder_label.trim();
+        if holder_label.is_empty() {
+            return Err(CommandError::Other("持有者名称不能为空".to_string()));
+        }
+        let mut entries = self.entries();
+        if let Some(entry) = entries.get(chapter_id) {
+            if entry.window_label != window_label && !entry.expired(now, idle) {
+                return Err(CommandError::Locked(entry.holder_label.clone()));
+            }
+        }
+        let acquired_at = entries
+            .get(chapter_id)
+            .filter(|e| e.window_label == window_label && !e.expired(now, idle))
+            .map_or(now, |e| e.acquired_at);
+        let entry = Entry {
+            holder_label: holder_label.to_string(),
+            window_label: window_label.to_string(),
+            acquired_at,
+            last_active_at: now,
+        };
+        let lock = entry.to_lock(chapter_id, idle);
+        entries.insert(chapter_id.to_string(), entry);
+        Ok(lock)
+    }
+
+    /// 释放锁, 返回是否持有; 不能释放其他窗口持有的未失效的锁
+    pub fn release(
+        &self,
+        chapter_id: &str,
+        window_label: &str,
+        now: DateTime<Utc>,
+        idle: Duration,
+    ) -> Result<bool, CommandError> {
+        let mut entries = self.entries();
+        match entries.get(chapter_id) {
+            None => Ok(false),
+            Some(entry) if entry.window_label != window_label && !entry.expired(now, idle) => {
+                Err(CommandError::Locked(entry.holder_label.clone()))
+            }
+            Some(entry) => {
+                let held = entry.window_label == window_label && !entry.expired(now, idle);
+                entries.remove(chapter_id);
+                Ok(held)
+            }
+        }
+    }
+
+    /// 释放窗口持有的全部锁, 窗口关闭时调用
+    pub fn release_window(&self, window_label: &str) -> usize {
+        let mut entries = self.entries();
+        let before = entries.len();
+        entries.retain(|_, entry| entry.window_label != window_label);
+        before - entries.len()
+    }
+
+    /// 移除章节的锁, 章节删除后调用
+    pub fn forget(&self, chapter_id: &str) {
+        self.entries().remove(chapter_id);
+    }
+
+    /// 修改章节前检查: 其他窗口持有未失效的锁时返回 Locked, 本窗口持有时刷新活动时间
+    pub fn check(
+        &self,
+        chapter_ids: &[String],
+        window_label: &str,
+        now: DateTime<Utc>,
+        idle: Duration,
+    ) -> Result<(), CommandError> {
+        let mut entries = self.entries();
+        for chapter_id in chapter_ids {
+            if let Some(entry) = entries.get(chapter_id) {
+                if entry.window_label != window_label && !entry.expired(now, idle) {
+                    return Err(CommandError::Locked(entry.holder_label.clone()));
+                }
+            }
+        }
+        for chapter_id in chapter_ids {
+            if let Some(entry) = entries.get_mut(chapter_id) {
+                if entry.window_label == window_label {
+                    entry.last_active_at = now;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 未失效的锁, 按获取时间排序; 同时清理已失效的锁
+    pub fn active(&self, now: DateTime<Utc>, idle: Duration) -> Vec<ChapterLock> {
+        let mut entries = self.entries();
+        entries.retain(|_, entry| !entry.expired(now, idle));
+        let mut locks: Vec<ChapterLock> = entries
+            .iter()
+            .map(|(chapter_id, entry)| entry.to_lock(chapter_id, idle))
+            .collect();
+        locks.sort_by(|a, b| {
+            a.acquired_at
+                .cmp(&b.acquired_at)
+                .then_with(|| a.chapter_id.cmp(&b.chapter_id))
+        });
+        locks
+    }
+}
+
+/// 锁空闲失效秒数
+pub async fn idle_seconds(pool: &SqlitePool) -> Result<i64, String> {
+    let seconds: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT chapter_lock_idle_seconds FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(seconds.flatten().unwrap_or(DEFAULT_IDLE_SECONDS))
+}
+
+/// 设置锁空闲失效秒数
+pub async fn set_idle_seconds(pool: &SqlitePool, seconds: i64) -> Result<(), String> {
+    if seconds <= 0 {
+        return Err("空闲时间须大于 0".to_string());
+    }
+    sqlx::query("UPDATE global_config SET chapter_lock_idle_seconds = ? WHERE id = 1")
+        .bind(seconds)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn idle(pool: &SqlitePool) -> Result<Duration, String> {
+    Ok(Duration::seconds(idle_seconds(pool).await?))
+}
+
+/// 章节修改命令调用: 其他窗口持有任一章节的锁时返回 Locked
+pub async fn ensure_unlocked(
+    pool: &SqlitePool,
+    window: &Window,
+    chapter_ids: &[String],
+) -> Result<(), CommandError> {
+    let idle = idle(pool).await?;
+    window
+        .state::<ChapterLocks>()
+        .check(chapter_ids, window.label(), Utc::now(), idle)
+}
+
+#[tauri::command]
+pub async fn acquire_chapter_lock(
+    app: AppHandle,
+    window: Window,
+    locks: State<'_, ChapterLocks>,
+    chapter_id: String,
+    holder_label: String,
+) -> Result<ChapterLock, CommandError> {
+    let pool = db::pool(&app).await?;
+    crate::commands::chapter::get(&pool, &chapter_id).await?;
+    let idle = idle(&pool).await?;
+    locks.acquire(&chapter_id, &holder_label, window.label(), Utc::now(), idle)
+}
+
+#[tauri::command]
+pub async fn release_chapter_lock(
+    app: AppHandle,
+    window: Window,
+    locks: State<'_, ChapterLocks>,
+    chapter_id: String,
+) -> Result<bool, CommandError> {
+    let pool = db::pool(&app).await?;
+    let idle = idle(&pool).await?;
+    locks.release(&chapter_id, window.label(), Utc::now(), idle)
+}
+
+#[tauri::command]
+pub async fn list_active_locks(
+    app: AppHandle,
+    locks: State<'_, ChapterLocks>,
+) -> Result<Vec<ChapterLock>, String> {
+    let pool = db::pool(&app).await?;
+    Ok(locks.active(Utc::now(), idle(&pool).await?))
+}
+
+#[tauri::command]
+pub async fn set_chapter_lock_idle_timeout(app: AppHandle, seconds: i64) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_idle_seconds(&pool, seconds).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn locks_chapters_per_window() {
+        let locks = ChapterLocks::default();
+        let idle = Duration::seconds(300);
+        let t0: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+
+        let lock = locks.acquire("c1", "主窗口", "main", t0, idle).unwrap();
+        assert_eq!(lock.expires_at, "2026-01-01T00:05:00.000Z");
+        assert!(locks.acquire("c1", " ", "main", t0, idle).is_err());
+        assert_eq!(
+            locks.acquire("c1", "第二窗口", "second", t0, idle),
+            Err(CommandError::Locked("主窗口".to_string()))
+        );
+
+        // 持有方可以修改并刷新活动时间, 其他窗口修改失败
+        let t1 = t0 + Duration::seconds(200);
+        locks.check(&ids(&["c1", "c2"]), "main", t1, idle).unwrap();
+        assert_eq!(
+            locks.check(&ids(&["c2", "c1"]), "second", t1, idle),
+            Err(CommandError::Locked("主窗口".to_string()))
+        );
+        assert!(locks.release("c1", "second", t1, idle).is_err());
+
+        // 空闲超时后失效, 其他窗口可以获取
+        let t2 = t1 + Duration::seconds(300);
+        assert!(locks.active(t1, idle).len() == 1);
+        locks.check(&ids(&["c1"]), "second", t2, idle).unwrap();
+        let lock = locks.acquire("c1", "第二窗口", "second", t2, idle).unwrap();
+        assert_eq!(lock.acquired_at, db::to_iso(t2));
+        assert!(locks.release("c1", "main", t2, idle).is_err());
+
+        locks.acquire("c2", "第二窗口", "second", t2, idle).unwrap();
+        locks.acquire("c3", "主窗口", "main", t2, idle).unwrap();
+        let active: Vec<(String, String)> = locks
+            .active(t2, idle)
+            .into_iter()
+            .map(|l| (l.chapter_id, l.window_label))
+            .collect();
+        assert_eq!(
+            active,
+            [
+                ("c1".to_string(), "second".to_string()),
+                ("c2".to_string(), "second".to_string()),
+                ("c3".to_string(), "main".to_string())
+            ]
+        );
+
+        assert_eq!(locks.release_window("second"), 2);
+        assert!(locks.release("c3", "main", t2, idle).unwrap());
+        assert!(!locks.release("c3", "main", t2, idle).unwrap());
+        assert!(locks.active(t2, idle).is_empty());
+    }
+
+    #[tokio::test]
+    async fn configures_idle_timeout() {
+        let pool = db::test_pool().await;
+        assert_eq!(idle_seconds(&pool).await.unwrap(), DEFAULT_IDLE_SECONDS);
+        set_idle_seconds(&pool, 60).await.unwrap();
+        assert_eq!(idle_seconds(&pool).await.unwrap(), 60);
+        assert!(set_idle_seconds(&pool, 0).await.is_err());
+    }
+}
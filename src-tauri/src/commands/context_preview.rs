@@ -0,0 +1,246 @@
+// 上下文预览: 按执行引擎的规则为工作流的第一个 AI 节点组装提示词 (设定注入、事实引用与变量插值),
+// 只读取数据, 不调用 AI. 规则与前端 `src/lib/engine/executor.ts` 保持一致
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::synopsis;
+use crate::models::{Node, Setting, SettingPrompt};
+use crate::{db, injection, tokens};
+
+/// 执行开始时保存用户输入的变量
+const INPUT_VARIABLE: &str = "用户问题";
+
+// 第一个 AI 节点将收到的提示词
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowContextPreview {
+    pub system_prompt: String,
+    pub user_prompt: String,
+    /// 系统提示词与用户问题的估算 token 数
+    pub estimated_tokens: i64,
+    /// 注入的设定名称
+    pub settings_used: Vec<String>,
+}
+
+fn config_str<'a>(config: &'a Value, key: &str) -> Option<&'a str> {
+    config.get(key).and_then(Value::as_str)
+}
+
+// 替换 `{{变量名}}`; 预览时没有节点输出, `{{@节点}}` 与未定义的变量原样保留
+fn interpolate(template: &str, variables: &HashMap<String, String>) -> String {
+    Regex::new(r"\{\{([^}]+)\}\}")
+        .expect("变量模式有效")
+        .replace_all(template, |captures: &regex::Captures| {
+            variables
+                .get(captures[1].trim())
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+// 执行开始时的变量: 梗概、传入的变量, 以及开始节点的输入默认值与自定义变量 (传入时以传入值为准)
+fn initial_variables(
+    input_variables: &Value,
+    start: Option<&Node>,
+    synopsis: String,
+) -> Result<HashMap<String, String>, String> {
+    let mut variables = HashMap::from([("synopsis".to_string(), synopsis)]);
+    match input_variables {
+        Value::Null => {}
+        Value::Object(values) => {
+            for (name, value) in values {
+                let value = match value {
+                    Value::String(text) => text.clone(),
+                    Value::Null => continue,
+                    other => other.to_string(),
+                };
+                variables.insert(name.clone(), value);
+            }
+        }
+        _ => return Err("输入变量须为对象".to_string()),
+    }
+    let Some(start) = start else {
+        return Ok(variables);
+    };
+
+    let input = variables.get(INPUT_VARIABLE).cloned().unwrap_or_default();
+    if input.is_empty() {
+        let default = config_str(&start.config, "default_value").unwrap_or_default();
+        variables.insert(INPUT_VARIABLE.to_string(), default.to_string());
+    }
+    let custom = start
+        .config
+        .get("custom_variables")
+        .and_then(Value::as_array);
+    for variable in custom.into_iter().flatten() {
+        let Some(name) = config_str(variable, "name") else {
+            continue;
+        };
+        if !variables.contains_key(name) {
+            let default = config_str(variable, "default_value").unwrap_or_default();
+            let value = interpolate(default, &variables);
+            variables.insert(name.to_string(), value);
+        }
+    }
+    Ok(variables)
+}
+
+/// 组装工作流第一个 AI 节点的系统提示词与用户问题: 注入的设定置于系统提示词之前,
+/// 提示词中的设定事实引用与变量按执行时的规则替换
+pub async fn preview(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    input_variables: &Value,
+) -> Result<WorkflowContextPreview, String> {
+    let project_id: Option<String> =
+        sqlx::query_scalar("SELECT project_id FROM workflows WHERE id = ?")
+            .bind(workflow_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let project_id = project_id.ok_or_else(|| format!("工作流不存在: {}", workflow_id))?;
+
+    let nodes = sqlx::query_as::<_, Node>(
+        "SELECT * FROM nodes WHERE workflow_id = ? AND type IN ('start', 'ai_chat')
+         ORDER BY order_index",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let node = nodes
+        .iter()
+        .find(|n| n.node_type == "ai_chat")
+        .ok_or("工作流中没有 AI 对话节点")?;
+    let start = nodes.iter().find(|n| n.node_type == "start");
+
+    let settings = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? ORDER BY category, order_index, name",
+    )
+    .bind(&project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let prompts =
+        sqlx::query_as::<_, SettingPrompt>("SELECT * FROM setting_prompts WHERE project_id = ?")
+            .bind(&project_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let synopsis = synopsis::get(pool, &project_id).await?.content;
+    let variables = initial_variables(input_variables, start, synopsis)?;
+
+    let render = |template: &str| {
+        interpolate(
+            &injection::render_setting_refs(template, &settings),
+            &variables,
+        )
+    };
+    let config = &node.config;
+    // 兼容旧版配置的 prompt 字段
+    let mut system_prompt = config_str(config, "system_prompt")
+        .or_else(|| config_str(config, "prompt"))
+        .map(render)
+        .unwrap_or_default();
+    let user_prompt = config_str(config, "user_prompt")
+        .map(render)
+        .unwrap_or_default();
+
+    let manual_ids: Vec<String> = config
+        .get("setting_ids")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|id| id.as_str().map(str::to_string))
+        .collect();
+    let injected = injection::select_settings(
+        &settings,
+        &manual_ids,
+        config_str(config, "setting_injection_level"),
+    );
+    let injection_text = injection::render_injection(&injected, &prompts);
+    if !injection_text.is_empty() {
+        system_prompt = if system_prompt.is_empty() {
+            injection_text
+        } else {
+            format!("{}\n\n{}", injection_text, system_prompt)
+        };
+    }
+
+    Ok(WorkflowContextPreview {
+        estimated_tokens: (tokens::estimate_tokens(&system_prompt)
+            + tokens::estimate_tokens(&user_prompt)) as i64,
+        system_prompt,
+        user_prompt,
+        settings_used: injected.into_iter().map(|s| s.name).collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_workflow_settings_context_preview(
+    app: AppHandle,
+    workflow_id: String,
+    input_variables: Value,
+) -> Result<WorkflowContextPreview, String> {
+    let pool = db::pool(&app).await?;
+    preview(&pool, &workflow_id, &input_variables).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn assembles_first_ai_node_prompts() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '雾城');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p1', '空');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n0', 'w1', 'start', '开始', '{"default_value":"驿站来客","custom_variables":[{"name":"视角","default_value":"第三人称"},{"name":"基调","default_value":"阴郁"}]}', 0),
+                ('n1', 'w1', 'ai_chat', '大纲', '{"provider":"openai","system_prompt":"以{{视角}}写作, 基调{{基调}}。林雪瞳色: {{setting:林雪.facts.瞳色}}","user_prompt":"情节: {{用户问题}} {{@n0 > 输出}}","setting_ids":["s2","s3"]}', 1),
+                ('n2', 'w1', 'ai_chat', '正文', '{"system_prompt":"不会被预览"}', 2);
+            INSERT INTO settings (id, project_id, category, name, content, injection_mode, priority, facts, order_index) VALUES
+                ('s1', 'p1', 'worldview', '雾城', '常年大雾', 'auto', 'medium', NULL, 0),
+                ('s2', 'p1', 'character', '林雪', '驿站掌柜的女儿', 'manual', 'high', '{"瞳色":"蓝"}', 0),
+                ('s3', 'p1', 'character', '陌生人', '左臂有伤', 'manual', 'low', NULL, 1);
+            UPDATE settings SET enabled = 0 WHERE id = 's3';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let assembled = preview(&pool, "w1", &json!({ "视角": "第一人称" }))
+            .await
+            .unwrap();
+        assert_eq!(
+            assembled.system_prompt,
+            "【世界观设定】\n雾城：常年大雾\n\n【角色设定】\n林雪：驿站掌柜的女儿\n\n以第一人称写作, 基调阴郁。林雪瞳色: 蓝"
+        );
+        assert_eq!(assembled.user_prompt, "情节: 驿站来客 {{@n0 > 输出}}");
+        assert_eq!(assembled.settings_used, ["雾城", "林雪"]);
+        assert_eq!(
+            assembled.estimated_tokens,
+            (tokens::estimate_tokens(&assembled.system_prompt)
+                + tokens::estimate_tokens(&assembled.user_prompt)) as i64
+        );
+
+        let with_input = preview(&pool, "w1", &json!({ "用户问题": "雨夜" }))
+            .await
+            .unwrap();
+        assert_eq!(with_input.user_prompt, "情节: 雨夜 {{@n0 > 输出}}");
+        assert!(with_input.system_prompt.contains("以第三人称写作"));
+
+        assert!(preview(&pool, "w1", &json!("雨夜")).await.is_err());
+        assert!(preview(&pool, "w2", &Value::Null).await.is_err());
+        assert!(preview(&pool, "missing", &Value::Null).await.is_err());
+    }
+}
@@ -0,0 +1,191 @@
+// 工作流每日执行上限: 统计工作流当天 (UTC) 开始的执行次数, 达到上限后不能开始新的执行.
+// 上限保存在 workflows.daily_execution_limit, NULL 表示不限制; 导入的模拟执行不计入
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::fine_tune::SIMULATED_STATUS;
+use crate::db;
+use crate::error::CommandError;
+
+// 当天执行次数与上限, 未设置上限时相关字段为 None
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InvocationStats {
+    pub count_today: i64,
+    pub daily_limit: Option<i64>,
+    /// 已达上限时为 0
+    pub limit_remaining: Option<i64>,
+}
+
+async fn daily_limit(pool: &SqlitePool, workflow_id: &str) -> Result<Option<i64>, String> {
+    let limit: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT daily_execution_limit FROM workflows WHERE id = ?")
+            .bind(workflow_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    limit.ok_or_else(|| format!("工作流不存在: {}", workflow_id))
+}
+
+/// 设置工作流每日执行上限, None 表示不限制; 上限须大于 0
+pub async fn set_daily_limit(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    limit: Option<i64>,
+) -> Result<(), String> {
+    if limit.is_some_and(|limit| limit <= 0) {
+        return Err("每日执行上限须大于 0".to_string());
+    }
+    let result =
+        sqlx::query("UPDATE workflows SET daily_execution_limit = ?, updated_at = ? WHERE id = ?")
+            .bind(limit)
+            .bind(db::now())
+            .bind(workflow_id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+    Ok(())
+}
+
+/// 工作流当天已开始 (含排队) 的执行次数
+pub async fn count_today(pool: &SqlitePool, workflow_id: &str) -> Result<i64, String> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM executions
+         WHERE workflow_id = ? AND status != ? AND DATE(started_at) = DATE('now')",
+    )
+    .bind(workflow_id)
+    .bind(SIMULATED_STATUS)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 当天执行次数与剩余次数
+pub async fn stats(pool: &SqlitePool, workflow_id: &str) -> Result<InvocationStats, String> {
+    let daily_limit = daily_limit(pool, workflow_id).await?;
+    let count_today = count_today(pool, workflow_id).await?;
+    Ok(InvocationStats {
+        count_today,
+        daily_limit,
+        limit_remaining: daily_limit.map(|limit| (limit - count_today).max(0)),
+    })
+}
+
+/// 开始新的执行前调用: 工作流设置了上限且当天次数加上即将开始的 `runs` 次超出上限时
+/// 返回 DailyLimitExceeded
+pub async fn ensure_within_daily_limit(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    runs: i64,
+) -> Result<(), CommandError> {
+    let Some(daily_limit) = daily_limit(pool, workflow_id).await? else {
+        return Ok(());
+    };
+    let count_today = count_today(pool, workflow_id).await?;
+    if count_today + runs > daily_limit {
+        return Err(CommandError::DailyLimitExceeded {
+            daily_limit,
+            count_today,
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_workflow_daily_limit(
+    app: AppHandle,
+    workflow_id: String,
+    limit: Option<i64>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_daily_limit(&pool, &workflow_id, limit).await
+}
+
+/// 前端执行引擎创建执行记录前调用
+#[tauri::command]
+pub async fn ensure_workflow_within_daily_limit(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<(), CommandError> {
+    let pool = db::pool(&app).await?;
+    ensure_within_daily_limit(&pool, &workflow_id, 1).await
+}
+
+#[tauri::command]
+pub async fn get_workflow_invocation_count_today(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<InvocationStats, String> {
+    let pool = db::pool(&app).await?;
+    stats(&pool, &workflow_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn counts_todays_executions_against_limit() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '雾城');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p1', '润色');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let now = db::now();
+        let yesterday = db::to_iso(chrono::Utc::now() - chrono::Duration::days(1));
+        for (id, workflow_id, status, started_at) in [
+            ("e1", "w1", "completed", &now),
+            ("e2", "w1", "queued", &now),
+            ("e3", "w1", "simulated", &now),
+            ("e4", "w1", "completed", &yesterday),
+            ("e5", "w2", "completed", &now),
+        ] {
+            sqlx::query(
+                "INSERT INTO executions (id, workflow_id, status, started_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(workflow_id)
+            .bind(status)
+            .bind(started_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            stats(&pool, "w1").await.unwrap(),
+            InvocationStats {
+                count_today: 2,
+                daily_limit: None,
+                limit_remaining: None,
+            }
+        );
+        ensure_within_daily_limit(&pool, "w1", 1).await.unwrap();
+        set_daily_limit(&pool, "w1", Some(5)).await.unwrap();
+        assert_eq!(stats(&pool, "w1").await.unwrap().limit_remaining, Some(3));
+        ensure_within_daily_limit(&pool, "w1", 3).await.unwrap();
+        // 批量入队时按即将开始的次数检查
+        assert_eq!(
+            ensure_within_daily_limit(&pool, "w1", 4).await,
+            Err(CommandError::DailyLimitExceeded {
+                daily_limit: 5,
+                count_today: 2,
+            })
+        );
+        set_daily_limit(&pool, "w1", Some(1)).await.unwrap();
+        assert_eq!(stats(&pool, "w1").await.unwrap().limit_remaining, Some(0));
+        assert!(ensure_within_daily_limit(&pool, "w1", 1).await.is_err());
+        set_daily_limit(&pool, "w1", None).await.unwrap();
+        ensure_within_daily_limit(&pool, "w1", 1).await.unwrap();
+
+        assert!(set_daily_limit(&pool, "w1", Some(0)).await.is_err());
+        assert!(set_daily_limit(&pool, "missing", Some(1)).await.is_err());
+        assert!(stats(&pool, "missing").await.is_err());
+    }
+}
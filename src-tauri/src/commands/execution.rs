@@ -0,0 +1,1238 @@
+// 执行记录查询与整理
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::models::{Execution, VariableSnapshot};
+
+/// 单次搜索返回的最大条数
+const MAX_SEARCH_LIMIT: i64 = 500;
+
+/// 首页动态流返回的最大条数
+const MAX_RECENT_LIMIT: i64 = 50;
+
+/// 批量删除时每个事务处理的条数, 避免长事务阻塞其他操作
+const BULK_DELETE_BATCH_SIZE: i64 = 500;
+
+/// 自动标题的最大字符数
+const TITLE_MAX_CHARS: usize = 30;
+
+/// 允许批量删除的执行状态
+pub const BULK_DELETABLE_STATUSES: &[&str] = &["failed", "cancelled", "dry_run"];
+
+// 执行记录搜索条件, 未填写的字段不参与过滤
+#[derive(Debug, Default, Deserialize)]
+pub struct ExecutionSearchFilter {
+    pub workflow_id: Option<String>,
+    pub status: Option<String>,
+    /// 在标题、输入与最终输出中模糊匹配
+    pub query: Option<String>,
+    pub label: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// 批量删除条件; 带标签的执行记录视为已保留, 默认不删除
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkDeleteFilter {
+    pub statuses: Vec<String>,
+    pub project_id: Option<String>,
+    pub workflow_id: Option<String>,
+    /// 开始时间下界 (含), ISO 8601
+    pub started_after: Option<String>,
+    /// 开始时间上界 (不含), ISO 8601
+    pub started_before: Option<String>,
+    pub include_labeled: Option<bool>,
+}
+
+// 批量删除预览, 需携带 confirm_token 才能真正删除
+#[derive(Debug, Serialize)]
+pub struct BulkDeletePreview {
+    pub matched: i64,
+    pub estimated_bytes: i64,
+    pub confirm_token: String,
+}
+
+// 批量删除结果
+#[derive(Debug, Serialize)]
+pub struct BulkDeleteResult {
+    pub deleted: i64,
+    pub estimated_bytes: i64,
+    pub vacuumed: bool,
+}
+
+// 活动热力图的一格: 星期 (0 为周日) 与小时 (以 started_at 的时间计, 即 UTC)
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct HeatmapCell {
+    pub day_of_week: i64,
+    pub hour_of_day: i64,
+    pub count: i64,
+}
+
+/// 输出长度分布的区间下界, 最后一个区间不设上界
+pub const LENGTH_BUCKET_BOUNDS: &[i64] = &[0, 100, 500, 1000];
+
+// 输出长度区间, 含下界不含上界
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DistributionBucket {
+    pub min_words: i64,
+    pub max_words: Option<i64>,
+    pub count: i64,
+}
+
+// 已完成执行的最终输出长度分布, 以字符数 (`LENGTH(final_output)`) 近似字数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LengthDistribution {
+    pub min_words: i64,
+    pub max_words: i64,
+    pub avg_words: f64,
+    pub median_words: f64,
+    pub buckets: Vec<DistributionBucket>,
+}
+
+// 输入完全相同的一组执行, `input_hash` 为输入的 SHA-256 (十六进制), 成员按开始时间排序
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateExecutionGroup {
+    pub input_hash: String,
+    pub execution_ids: Vec<String>,
+}
+
+/// 模型定价 (每百万 token, USD): (模型, 输入, 输出), 与前端 `MODEL_PRICING` 保持一致
+pub const MODEL_PRICING: &[(&str, f64, f64)] = &[
+    ("gpt-5.1", 5.0, 15.0),
+    ("gpt-5", 3.0, 12.0),
+    ("gpt-5-mini", 0.3, 1.2),
+    ("gemini-3-pro-preview", 2.5, 10.0),
+    ("gemini-2.5-pro", 2.5, 10.0),
+    ("gemini-2.5-flash", 0.15, 0.6),
+    ("claude-opus-4-5-20251101", 15.0, 75.0),
+    ("claude-sonnet-4-5-20250929", 3.0, 15.0),
+    ("claude-haiku-4-5-20251001", 0.8, 4.0),
+];
+
+// 单个节点的费用, 循环中多次运行的节点合并为一项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeCost {
+    pub node_id: String,
+    /// 节点已删除时为节点 ID
+    pub node_name: String,
+    pub model: Option<String>,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+    /// 使用的模型均有定价; 否则 cost_usd 只含有定价的部分
+    pub priced: bool,
+}
+
+// 执行的费用明细, 按节点结果记录的 token 用量与模型定价计算
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionCostReport {
+    pub execution_id: String,
+    pub total_cost_usd: f64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub by_node: Vec<NodeCost>,
+}
+
+// 首页动态流中的执行记录, 附带所属项目与工作流名称
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RecentExecution {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub execution: Execution,
+    pub project_name: String,
+    pub workflow_name: String,
+}
+
+// 批量删除进度, 通过 `cleanup:progress` 事件推送
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupProgress {
+    pub deleted: i64,
+    pub total: i64,
+}
+
+/// 设置执行记录的标签, 空字符串表示清除标签
+pub async fn set_label(pool: &SqlitePool, execution_id: &str, label: &str) -> Result<(), String> {
+    let label = label.trim();
+    let label = (!label.is_empty()).then_some(label);
+
+    let result = sqlx::query("UPDATE executions SET label = ? WHERE id = ?")
+        .bind(label)
+        .bind(execution_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("执行记录不存在: {}", execution_id));
+    }
+    Ok(())
+}
+
+// 去掉 Markdown 标题标记并截断到指定字符数
+fn clean_title_line(line: &str) -> String {
+    line.trim()
+        .trim_start_matches('#')
+        .trim()
+        .chars()
+        .take(TITLE_MAX_CHARS)
+        .collect()
+}
+
+// 是否为 "第…章" 形式的章节标题
+fn is_chapter_heading(line: &str) -> bool {
+    line.find('第').is_some_and(|start| {
+        let rest = &line[start + '第'.len_utf8()..];
+        rest.char_indices().any(|(i, c)| c == '章' && i > 0)
+    })
+}
+
+/// 由最终输出推断标题: 优先取 "第…章" 章节标题, 否则取第一行非空文本, 均截断到 30 字
+pub fn derive_title(final_output: &str) -> Option<String> {
+    let lines: Vec<&str> = final_output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !clean_title_line(l).is_empty())
+        .collect();
+    lines
+        .iter()
+        .find(|l| is_chapter_heading(l))
+        .or(lines.first())
+        .map(|l| clean_title_line(l))
+}
+
+/// 为尚无标题的执行记录自动生成标题, 返回当前标题
+pub async fn auto_title(pool: &SqlitePool, execution_id: &str) -> Result<Option<String>, String> {
+    let row: Option<(Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT title, final_output FROM executions WHERE id = ?")
+            .bind(execution_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (title, final_output) = row.ok_or_else(|| format!("执行记录不存在: {}", execution_id))?;
+    if title.is_some() {
+        return Ok(title);
+    }
+
+    let title = final_output.as_deref().and_then(derive_title);
+    if title.is_some() {
+        sqlx::query("UPDATE executions SET title = ? WHERE id = ?")
+            .bind(&title)
+            .bind(execution_id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(title)
+}
+
+/// 手动修改执行记录标题, 空字符串表示清除
+pub async fn rename(pool: &SqlitePool, execution_id: &str, title: &str) -> Result<(), String> {
+    let title = title.trim();
+    let title = (!title.is_empty()).then_some(title);
+
+    let result = sqlx::query("UPDATE executions SET title = ? WHERE id = ?")
+        .bind(title)
+        .bind(execution_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("执行记录不存在: {}", execution_id));
+    }
+    Ok(())
+}
+
+/// 列出工作流下已使用的标签
+pub async fn list_labels(pool: &SqlitePool, workflow_id: &str) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(
+        "SELECT DISTINCT label FROM executions
+         WHERE workflow_id = ? AND label IS NOT NULL ORDER BY label",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按条件搜索执行记录, 按开始时间倒序
+pub async fn search(
+    pool: &SqlitePool,
+    filter: &ExecutionSearchFilter,
+) -> Result<Vec<Execution>, String> {
+    let mut sql: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT * FROM executions WHERE 1 = 1");
+
+    if let Some(workflow_id) = &filter.workflow_id {
+        sql.push(" AND workflow_id = ").push_bind(workflow_id);
+    }
+    if let Some(status) = &filter.status {
+        sql.push(" AND status = ").push_bind(status);
+    }
+    if let Some(label) = &filter.label {
+        sql.push(" AND label = ").push_bind(label);
+    }
+    if let Some(query) = filter.query.as_deref().filter(|q| !q.is_empty()) {
+        let pattern = format!("%{}%", query);
+        sql.push(" AND (title LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR input LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR final_output LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    let limit = filter.limit.unwrap_or(100).clamp(1, MAX_SEARCH_LIMIT);
+    sql.push(" ORDER BY started_at DESC LIMIT ")
+        .push_bind(limit);
+
+    sql.build_query_as::<Execution>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 全部项目最近开始的执行, 按开始时间倒序, 最多 50 条; limit 不大于 0 时返回空列表
+pub async fn recent_across_projects(
+    pool: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<RecentExecution>, String> {
+    if limit <= 0 {
+        return Ok(Vec::new());
+    }
+    sqlx::query_as::<_, RecentExecution>(
+        "SELECT e.*, p.name AS project_name, w.name AS workflow_name
+         FROM executions e
+         JOIN workflows w ON w.id = e.workflow_id
+         JOIN projects p ON p.id = w.project_id
+         ORDER BY e.started_at DESC, e.rowid DESC
+         LIMIT ?",
+    )
+    .bind(limit.min(MAX_RECENT_LIMIT))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按时间顺序列出执行过程中的变量快照
+pub async fn variable_trace(
+    pool: &SqlitePool,
+    execution_id: &str,
+) -> Result<Vec<VariableSnapshot>, String> {
+    sqlx::query_as::<_, VariableSnapshot>(
+        "SELECT s.node_id AS after_node_id, COALESCE(n.name, '') AS after_node_name,
+                s.variables, s.created_at
+         FROM execution_variable_snapshots s
+         LEFT JOIN nodes n ON n.id = s.node_id
+         WHERE s.execution_id = ?
+         ORDER BY s.created_at, s.rowid",
+    )
+    .bind(execution_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按星期与小时统计工作流的执行次数, 只返回有执行的格子
+pub async fn heatmap(pool: &SqlitePool, workflow_id: &str) -> Result<Vec<HeatmapCell>, String> {
+    sqlx::query_as::<_, HeatmapCell>(
+        "SELECT CAST(strftime('%w', started_at) AS INTEGER) AS day_of_week,
+                CAST(strftime('%H', started_at) AS INTEGER) AS hour_of_day,
+                COUNT(*) AS count
+         FROM executions
+         WHERE workflow_id = ? AND strftime('%w', started_at) IS NOT NULL
+         GROUP BY day_of_week, hour_of_day
+         ORDER BY day_of_week, hour_of_day",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 查找工作流中输入相同的执行 (无输入视为空字符串), 按组大小降序, 同样大小时先出现的组在前
+pub async fn duplicates(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<Vec<DuplicateExecutionGroup>, String> {
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT id, input FROM executions WHERE workflow_id = ? ORDER BY started_at, rowid",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<DuplicateExecutionGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (id, input) in rows {
+        let hash = hex::encode(Sha256::digest(input.unwrap_or_default().as_bytes()));
+        let i = *index.entry(hash.clone()).or_insert_with(|| {
+            groups.push(DuplicateExecutionGroup {
+                input_hash: hash,
+                execution_ids: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[i].execution_ids.push(id);
+    }
+    groups.retain(|g| g.execution_ids.len() > 1);
+    groups.sort_by_key(|g| std::cmp::Reverse(g.execution_ids.len()));
+    Ok(groups)
+}
+
+/// 按模型定价计算费用 (USD), 未知模型返回 None
+pub fn token_cost(model: &str, prompt_tokens: i64, completion_tokens: i64) -> Option<f64> {
+    MODEL_PRICING
+        .iter()
+        .find(|(id, _, _)| *id == model)
+        .map(|(_, input, output)| {
+            (prompt_tokens as f64 * input + completion_tokens as f64 * output) / 1_000_000.0
+        })
+}
+
+/// 执行的费用明细: 只统计记录了 token 用量的节点结果, 节点按首次运行的时间排序
+pub async fn cost_report(
+    pool: &SqlitePool,
+    execution_id: &str,
+) -> Result<ExecutionCostReport, String> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM executions WHERE id = ?")
+        .bind(execution_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("执行记录不存在: {}", execution_id));
+    }
+
+    let rows: Vec<(String, String, i64, i64, Option<String>)> = sqlx::query_as(
+        "SELECT r.node_id, COALESCE(n.name, r.node_id),
+                COALESCE(json_extract(r.token_usage, '$.promptTokens'), 0),
+                COALESCE(json_extract(r.token_usage, '$.completionTokens'), 0),
+                CASE WHEN json_valid(r.resolved_config)
+                     THEN json_extract(r.resolved_config, '$.model') END
+         FROM node_results r
+         LEFT JOIN nodes n ON n.id = r.node_id
+         WHERE r.execution_id = ? AND r.token_usage IS NOT NULL AND json_valid(r.token_usage)
+         ORDER BY r.started_at, r.rowid",
+    )
+    .bind(execution_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut by_node: Vec<NodeCost> = Vec::new();
+    for (node_id, node_name, prompt_tokens, completion_tokens, model) in rows {
+        let cost = model
+            .as_deref()
+            .and_then(|m| token_cost(m, prompt_tokens, completion_tokens));
+        let node = match by_node.iter_mut().find(|n| n.node_id == node_id) {
+            Some(node) => node,
+            None => {
+                by_node.push(NodeCost {
+                    node_id,
+                    node_name,
+                    model: None,
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    cost_usd: 0.0,
+                    priced: true,
+                });
+                by_node.last_mut().expect("刚插入的节点")
+            }
+        };
+        node.prompt_tokens += prompt_tokens;
+        node.completion_tokens += completion_tokens;
+        node.cost_usd += cost.unwrap_or_default();
+        node.priced &= cost.is_some();
+        if model.is_some() {
+            node.model = model;
+        }
+    }
+
+    Ok(ExecutionCostReport {
+        execution_id: execution_id.to_string(),
+        total_cost_usd: by_node.iter().map(|n| n.cost_usd).sum(),
+        prompt_tokens: by_node.iter().map(|n| n.prompt_tokens).sum(),
+        completion_tokens: by_node.iter().map(|n| n.completion_tokens).sum(),
+        by_node,
+    })
+}
+
+/// 统计工作流已完成执行的输出长度分布, 没有输出时各项为 0
+pub async fn output_length_distribution(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<LengthDistribution, String> {
+    // 后接工作流 ID 参数
+    const OUTPUTS: &str = "SELECT LENGTH(final_output) AS len FROM executions
+         WHERE status = 'completed' AND final_output IS NOT NULL AND workflow_id = ";
+
+    let mut sql: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT COUNT(*), COALESCE(MIN(len), 0), COALESCE(MAX(len), 0), COALESCE(AVG(len), 0.0)",
+    );
+    for (i, min) in LENGTH_BUCKET_BOUNDS.iter().enumerate() {
+        sql.push(", COALESCE(SUM(len >= ").push_bind(*min);
+        if let Some(max) = LENGTH_BUCKET_BOUNDS.get(i + 1) {
+            sql.push(" AND len < ").push_bind(*max);
+        }
+        sql.push("), 0)");
+    }
+    sql.push(" FROM (")
+        .push(OUTPUTS)
+        .push_bind(workflow_id)
+        .push(")");
+    let row = sql
+        .build()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let count: i64 = row.get(0);
+    // 中位数: 取排序后居中的一个 (奇数) 或两个 (偶数) 值求平均
+    let median: f64 = if count == 0 {
+        0.0
+    } else {
+        sqlx::query_scalar(&format!(
+            "SELECT AVG(len) FROM ({} ? ORDER BY len LIMIT ? OFFSET ?)",
+            OUTPUTS
+        ))
+        .bind(workflow_id)
+        .bind(2 - count % 2)
+        .bind((count - 1) / 2)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    Ok(LengthDistribution {
+        min_words: row.get(1),
+        max_words: row.get(2),
+        avg_words: row.get(3),
+        median_words: median,
+        buckets: LENGTH_BUCKET_BOUNDS
+            .iter()
+            .enumerate()
+            .map(|(i, min)| DistributionBucket {
+                min_words: *min,
+                max_words: LENGTH_BUCKET_BOUNDS.get(i + 1).copied(),
+                count: row.get(4 + i),
+            })
+            .collect(),
+    })
+}
+
+fn validate_bulk_filter(filter: &BulkDeleteFilter) -> Result<(), String> {
+    if filter.statuses.is_empty() {
+        return Err("请至少选择一种要删除的执行状态".to_string());
+    }
+    if let Some(status) = filter
+        .statuses
+        .iter()
+        .find(|s| !BULK_DELETABLE_STATUSES.contains(&s.as_str()))
+    {
+        return Err(format!("不支持批量删除该状态的执行记录: {}", status));
+    }
+    Ok(())
+}
+
+// 追加批量删除的过滤条件 (以 " AND ..." 形式)
+fn push_bulk_conditions<'a>(sql: &mut QueryBuilder<'a, Sqlite>, filter: &'a BulkDeleteFilter) {
+    sql.push(" AND status IN (");
+    let mut statuses = sql.separated(", ");
+    for status in &filter.statuses {
+        statuses.push_bind(status);
+    }
+    sql.push(")");
+
+    if let Some(workflow_id) = &filter.workflow_id {
+        sql.push(" AND workflow_id = ").push_bind(workflow_id);
+    }
+    if let Some(project_id) = &filter.project_id {
+        sql.push(" AND workflow_id IN (SELECT id FROM workflows WHERE project_id = ")
+            .push_bind(project_id)
+            .push(")");
+    }
+    if let Some(after) = &filter.started_after {
+        sql.push(" AND started_at >= ").push_bind(after);
+    }
+    if let Some(before) = &filter.started_before {
+        sql.push(" AND started_at < ").push_bind(before);
+    }
+    if !filter.include_labeled.unwrap_or(false) {
+        sql.push(" AND label IS NULL");
+    }
+}
+
+// 估算执行记录及其节点结果占用的文本字节数
+fn push_size_estimate(sql: &mut QueryBuilder<'_, Sqlite>) {
+    sql.push(
+        "SELECT COUNT(*), COALESCE(SUM(
+             COALESCE(LENGTH(e.input), 0) + COALESCE(LENGTH(e.final_output), 0)
+             + COALESCE(LENGTH(e.variables_snapshot), 0)
+             + (SELECT COALESCE(SUM(COALESCE(LENGTH(r.input), 0) + COALESCE(LENGTH(r.output), 0)
+                                    + COALESCE(LENGTH(r.resolved_config), 0)), 0)
+                FROM node_results r WHERE r.execution_id = e.id)
+         ), 0) FROM executions e WHERE e.id IN (SELECT id FROM executions WHERE 1 = 1",
+    );
+}
+
+// 由过滤条件与匹配数量生成确认令牌, 数据变化后令牌随之失效
+fn confirm_token(filter: &BulkDeleteFilter, matched: i64) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(filter)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    matched.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// 预览批量删除: 返回匹配条数、预计释放字节数与确认令牌
+pub async fn preview_bulk_delete(
+    pool: &SqlitePool,
+    filter: &BulkDeleteFilter,
+) -> Result<BulkDeletePreview, String> {
+    validate_bulk_filter(filter)?;
+
+    let mut sql: QueryBuilder<Sqlite> = QueryBuilder::new("");
+    push_size_estimate(&mut sql);
+    push_bulk_conditions(&mut sql, filter);
+    sql.push(")");
+    let (matched, estimated_bytes): (i64, i64) = sql
+        .build_query_as()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(BulkDeletePreview {
+        matched,
+        estimated_bytes,
+        confirm_token: confirm_token(filter, matched),
+    })
+}
+
+/// 分批删除匹配的执行记录, 每批独立事务; 可选在结束后执行 VACUUM
+pub async fn bulk_delete(
+    pool: &SqlitePool,
+    filter: &BulkDeleteFilter,
+    token: &str,
+    vacuum: bool,
+    on_progress: impl Fn(CleanupProgress),
+) -> Result<BulkDeleteResult, String> {
+    let preview = preview_bulk_delete(pool, filter).await?;
+    if preview.confirm_token != token {
+        return Err("确认令牌无效或数据已变化, 请重新预览".to_string());
+    }
+
+    let mut deleted = 0;
+    loop {
+        let mut sql: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT id FROM executions WHERE 1 = 1");
+        push_bulk_conditions(&mut sql, filter);
+        sql.push(" LIMIT ").push_bind(BULK_DELETE_BATCH_SIZE);
+        let ids: Vec<String> = sql
+            .build_query_scalar()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if ids.is_empty() {
+            break;
+        }
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        // 先删除关联的节点结果与变量快照, 再删除执行记录
+        for table_sql in [
+            "DELETE FROM node_results WHERE execution_id IN (",
+            "DELETE FROM execution_variable_snapshots WHERE execution_id IN (",
+            "DELETE FROM executions WHERE id IN (",
+        ] {
+            let mut delete: QueryBuilder<Sqlite> = QueryBuilder::new(table_sql);
+            let mut list = delete.separated(", ");
+            for id in &ids {
+                list.push_bind(id);
+            }
+            delete.push(")");
+            delete
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        deleted += ids.len() as i64;
+        on_progress(CleanupProgress {
+            deleted,
+            total: preview.matched,
+        });
+    }
+
+    if vacuum {
+        sqlx::query("VACUUM")
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(BulkDeleteResult {
+        deleted,
+        estimated_bytes: preview.estimated_bytes,
+        vacuumed: vacuum,
+    })
+}
+
+#[tauri::command]
+pub async fn set_execution_label(
+    app: AppHandle,
+    execution_id: String,
+    label: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_label(&pool, &execution_id, &label).await
+}
+
+#[tauri::command]
+pub async fn auto_title_execution(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<Option<String>, String> {
+    let pool = db::pool(&app).await?;
+    auto_title(&pool, &execution_id).await
+}
+
+#[tauri::command]
+pub async fn rename_execution(
+    app: AppHandle,
+    execution_id: String,
+    title: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    rename(&pool, &execution_id, &title).await
+}
+
+#[tauri::command]
+pub async fn list_execution_labels(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<String>, String> {
+    let pool = db::pool(&app).await?;
+    list_labels(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn search_executions(
+    app: AppHandle,
+    filter: ExecutionSearchFilter,
+) -> Result<Vec<Execution>, String> {
+    let pool = db::pool(&app).await?;
+    search(&pool, &filter).await
+}
+
+#[tauri::command]
+pub async fn list_recent_executions_across_all_projects(
+    app: AppHandle,
+    limit: i64,
+) -> Result<Vec<RecentExecution>, String> {
+    let pool = db::pool(&app).await?;
+    recent_across_projects(&pool, limit).await
+}
+
+#[tauri::command]
+pub async fn get_execution_variable_trace(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<Vec<VariableSnapshot>, String> {
+    let pool = db::pool(&app).await?;
+    variable_trace(&pool, &execution_id).await
+}
+
+#[tauri::command]
+pub async fn get_workflow_execution_heatmap(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<HeatmapCell>, String> {
+    let pool = db::pool(&app).await?;
+    heatmap(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn get_execution_output_length_distribution(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<LengthDistribution, String> {
+    let pool = db::pool(&app).await?;
+    output_length_distribution(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn get_execution_cost_report(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<ExecutionCostReport, String> {
+    let pool = db::pool(&app).await?;
+    cost_report(&pool, &execution_id).await
+}
+
+#[tauri::command]
+pub async fn detect_duplicate_executions(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<DuplicateExecutionGroup>, String> {
+    let pool = db::pool(&app).await?;
+    duplicates(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn preview_bulk_delete_executions(
+    app: AppHandle,
+    filter: BulkDeleteFilter,
+) -> Result<BulkDeletePreview, String> {
+    let pool = db::pool(&app).await?;
+    preview_bulk_delete(&pool, &filter).await
+}
+
+#[tauri::command]
+pub async fn bulk_delete_executions(
+    app: AppHandle,
+    filter: BulkDeleteFilter,
+    confirm_token: String,
+    vacuum: Option<bool>,
+) -> Result<BulkDeleteResult, String> {
+    let pool = db::pool(&app).await?;
+    bulk_delete(
+        &pool,
+        &filter,
+        &confirm_token,
+        vacuum.unwrap_or(false),
+        |progress| {
+            let _ = app.emit("cleanup:progress", progress);
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO executions (id, workflow_id, status, input, final_output, started_at)
+                VALUES ('e1', 'w1', 'completed', '第一章大纲', '林雪推开了门', '2026-01-01T00:00:00.000Z');
+            INSERT INTO executions (id, workflow_id, status, input, final_output, started_at)
+                VALUES ('e2', 'w1', 'failed', '第二章大纲', NULL, '2026-01-02T00:00:00.000Z');
+            INSERT INTO executions (id, workflow_id, status, input, final_output, started_at)
+                VALUES ('e3', 'w1', 'completed', '第二章大纲', '雨夜', '2026-01-03T00:00:00.000Z');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn labels_and_filters_executions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        set_label(&pool, "e1", " 第一章 ").await.unwrap();
+        set_label(&pool, "e2", "第二章").await.unwrap();
+        set_label(&pool, "e3", "第二章").await.unwrap();
+        assert_eq!(
+            list_labels(&pool, "w1").await.unwrap(),
+            vec!["第一章", "第二章"]
+        );
+
+        let filter = ExecutionSearchFilter {
+            label: Some("第二章".to_string()),
+            status: Some("completed".to_string()),
+            ..Default::default()
+        };
+        let found = search(&pool, &filter).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "e3");
+
+        set_label(&pool, "e1", "").await.unwrap();
+        assert_eq!(list_labels(&pool, "w1").await.unwrap(), vec!["第二章"]);
+    }
+
+    #[tokio::test]
+    async fn searches_text_newest_first() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let filter = ExecutionSearchFilter {
+            query: Some("第二章".to_string()),
+            ..Default::default()
+        };
+        let ids: Vec<String> = search(&pool, &filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["e3", "e2"]);
+    }
+
+    #[test]
+    fn derives_title_from_output() {
+        assert_eq!(
+            derive_title("\n林雪推开了门。\n\n## 第三章 雨夜来客\n正文").as_deref(),
+            Some("第三章 雨夜来客")
+        );
+        assert_eq!(
+            derive_title("  \n# 雨下得很急\n正文").as_deref(),
+            Some("雨下得很急")
+        );
+        assert_eq!(derive_title(&"长".repeat(40)).unwrap().chars().count(), 30);
+        assert_eq!(derive_title("第章").as_deref(), Some("第章"));
+        assert!(derive_title(" \n#\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn auto_titles_and_renames_executions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        assert_eq!(
+            auto_title(&pool, "e3").await.unwrap().as_deref(),
+            Some("雨夜")
+        );
+        assert!(auto_title(&pool, "e2").await.unwrap().is_none());
+
+        // 已有标题 (包括手动修改的) 不会被覆盖
+        rename(&pool, "e3", "第二章 初稿").await.unwrap();
+        sqlx::query("UPDATE executions SET final_output = '新的输出' WHERE id = 'e3'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            auto_title(&pool, "e3").await.unwrap().as_deref(),
+            Some("第二章 初稿")
+        );
+
+        let filter = ExecutionSearchFilter {
+            query: Some("初稿".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search(&pool, &filter).await.unwrap()[0].id, "e3");
+        assert!(rename(&pool, "missing", "标题").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_execution() {
+        let pool = db::test_pool().await;
+        assert!(set_label(&pool, "missing", "第一章").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn lists_recent_executions_across_projects() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p2', '雾城');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w2', 'p2', '润色');
+            INSERT INTO executions (id, workflow_id, status, started_at) VALUES
+                ('e4', 'w2', 'running', '2026-01-04T00:00:00.000Z'),
+                ('e5', 'w2', 'simulated', '2026-01-05T00:00:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let recent = recent_across_projects(&pool, 3).await.unwrap();
+        let rows: Vec<(&str, &str, &str)> = recent
+            .iter()
+            .map(|r| {
+                (
+                    r.execution.id.as_str(),
+                    r.project_name.as_str(),
+                    r.workflow_name.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("e5", "雾城", "润色"),
+                ("e4", "雾城", "润色"),
+                ("e3", "测试项目", "正文生成"),
+            ]
+        );
+        assert!(recent_across_projects(&pool, 0).await.unwrap().is_empty());
+        assert!(recent_across_projects(&pool, -1).await.unwrap().is_empty());
+        assert_eq!(recent_across_projects(&pool, 1000).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn traces_variables_in_order() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO nodes (id, workflow_id, type, name, order_index) VALUES ('n1', 'w1', 'var_update', '记录主角', 0);
+            INSERT INTO execution_variable_snapshots (id, execution_id, node_id, variables, created_at) VALUES
+                ('v2', 'e1', 'n1', '{"主角":"林雪","章节":"2"}', '2026-01-01T00:00:02.000Z'),
+                ('v1', 'e1', 'n1', '{"主角":"林雪"}', '2026-01-01T00:00:01.000Z'),
+                ('v3', 'e2', 'n1', '{}', '2026-01-01T00:00:03.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let trace = variable_trace(&pool, "e1").await.unwrap();
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[0].after_node_name, "记录主角");
+        assert_eq!(trace[0].variables, serde_json::json!({ "主角": "林雪" }));
+        assert_eq!(trace[1].variables["章节"], "2");
+    }
+
+    #[tokio::test]
+    async fn bulk_deletes_in_batches_after_confirmation() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        set_label(&pool, "e2", "保留").await.unwrap();
+        for i in 0..600 {
+            sqlx::query(
+                "INSERT INTO executions (id, workflow_id, status, input, started_at)
+                 VALUES (?, 'w1', 'failed', '测试', '2026-01-04T00:00:00.000Z')",
+            )
+            .bind(format!("f{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::raw_sql(
+            "INSERT INTO node_results (id, execution_id, node_id, status, output) VALUES ('r1', 'f0', 'n1', 'failed', '错误');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let filter = BulkDeleteFilter {
+            statuses: vec!["failed".to_string()],
+            project_id: Some("p1".to_string()),
+            ..Default::default()
+        };
+        let preview = preview_bulk_delete(&pool, &filter).await.unwrap();
+        assert_eq!(preview.matched, 600);
+        assert!(preview.estimated_bytes > 0);
+        assert!(bulk_delete(&pool, &filter, "wrong", false, |_| {})
+            .await
+            .is_err());
+
+        let batches = std::cell::RefCell::new(Vec::new());
+        let result = bulk_delete(&pool, &filter, &preview.confirm_token, true, |p| {
+            batches.borrow_mut().push(p.deleted)
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.deleted, 600);
+        assert_eq!(batches.into_inner(), vec![500, 600]);
+
+        // 带标签的失败记录与其他状态保留
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM executions ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["e1", "e2", "e3"]);
+        let results: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM node_results")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(results, 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_unsafe_bulk_filters() {
+        let pool = db::test_pool().await;
+        let empty = BulkDeleteFilter::default();
+        assert!(preview_bulk_delete(&pool, &empty).await.is_err());
+
+        let completed = BulkDeleteFilter {
+            statuses: vec!["completed".to_string()],
+            ..Default::default()
+        };
+        assert!(preview_bulk_delete(&pool, &completed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn counts_executions_by_weekday_and_hour() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO workflows (id, project_id, name) VALUES ('w2', 'p1', '其他');
+            INSERT INTO executions (id, workflow_id, status, started_at) VALUES
+                ('e4', 'w1', 'completed', '2026-01-08T00:45:00.000Z'),
+                ('e5', 'w1', 'completed', '2026-01-08T21:05:00.000Z'),
+                ('e6', 'w2', 'completed', '2026-01-08T21:05:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let cells: Vec<(i64, i64, i64)> = heatmap(&pool, "w1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.day_of_week, c.hour_of_day, c.count))
+            .collect();
+        // 2026-01-01 与 01-08 为周四, 01-02 为周五, 01-03 为周六
+        assert_eq!(cells, [(4, 0, 2), (4, 21, 1), (5, 0, 1), (6, 0, 1)]);
+        assert!(heatmap(&pool, "missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn summarizes_output_lengths() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let empty = output_length_distribution(&pool, "missing").await.unwrap();
+        assert_eq!((empty.min_words, empty.max_words), (0, 0));
+        assert_eq!(empty.median_words, 0.0);
+        assert!(empty.buckets.iter().all(|b| b.count == 0));
+
+        sqlx::query(
+            "INSERT INTO executions (id, workflow_id, status, final_output) VALUES
+                ('e4', 'w1', 'completed', ?), ('e5', 'w1', 'completed', ?),
+                ('e6', 'w1', 'failed', ?)",
+        )
+        .bind("字".repeat(100))
+        .bind("字".repeat(1200))
+        .bind("字".repeat(5000))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // 已完成的输出长度: 6 ("林雪推开了门"), 2 ("雨夜"), 100, 1200
+        let found = output_length_distribution(&pool, "w1").await.unwrap();
+        assert_eq!((found.min_words, found.max_words), (2, 1200));
+        assert_eq!(found.avg_words, 327.0);
+        assert_eq!(found.median_words, 53.0);
+        let counts: Vec<(i64, Option<i64>, i64)> = found
+            .buckets
+            .iter()
+            .map(|b| (b.min_words, b.max_words, b.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                (0, Some(100), 2),
+                (100, Some(500), 1),
+                (500, Some(1000), 0),
+                (1000, None, 1)
+            ]
+        );
+
+        sqlx::query("DELETE FROM executions WHERE id = 'e5'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let found = output_length_distribution(&pool, "w1").await.unwrap();
+        assert_eq!(found.median_words, 6.0);
+    }
+
+    #[tokio::test]
+    async fn groups_executions_with_identical_inputs() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO executions (id, workflow_id, status, input, started_at) VALUES
+                ('e4', 'w1', 'completed', '第一章大纲', '2026-01-04T00:00:00.000Z'),
+                ('e5', 'w1', 'completed', '第二章大纲', '2026-01-05T00:00:00.000Z'),
+                ('e6', 'w1', 'completed', '第一章大纲 ', '2026-01-06T00:00:00.000Z'),
+                ('e7', 'w1', 'completed', NULL, '2026-01-07T00:00:00.000Z'),
+                ('e8', 'w1', 'completed', '', '2026-01-08T00:00:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let groups = duplicates(&pool, "w1").await.unwrap();
+        let ids: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|g| g.execution_ids.iter().map(String::as_str).collect())
+            .collect();
+        // 空白不同的输入不算重复, 无输入与空输入算作重复
+        assert_eq!(
+            ids,
+            [vec!["e2", "e3", "e5"], vec!["e1", "e4"], vec!["e7", "e8"]]
+        );
+        assert_eq!(
+            groups[0].input_hash,
+            hex::encode(Sha256::digest("第二章大纲".as_bytes()))
+        );
+        assert!(duplicates(&pool, "missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_execution_costs_by_node() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'ai_chat', '撰写正文', '{}', 0),
+                ('n2', 'w1', 'ai_chat', '润色', '{}', 1);
+            INSERT INTO node_results (id, execution_id, node_id, status, started_at, token_usage, resolved_config) VALUES
+                ('r1', 'e1', 'n1', 'completed', '2026-01-01T00:00:01.000Z',
+                 '{"promptTokens":1000,"completionTokens":2000,"totalTokens":3000}', '{"model":"gpt-5"}'),
+                ('r2', 'e1', 'n2', 'completed', '2026-01-01T00:00:02.000Z',
+                 '{"promptTokens":500,"completionTokens":0,"totalTokens":500}', '{"model":"local-model"}'),
+                ('r3', 'e1', 'n1', 'completed', '2026-01-01T00:00:03.000Z',
+                 '{"promptTokens":1000,"completionTokens":0,"totalTokens":1000}', '{"model":"gpt-5"}'),
+                ('r4', 'e1', 'gone', 'completed', '2026-01-01T00:00:04.000Z',
+                 '{"promptTokens":0,"completionTokens":1000,"totalTokens":1000}', 'not json'),
+                ('r5', 'e1', 'n2', 'completed', '2026-01-01T00:00:05.000Z', NULL, NULL);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = cost_report(&pool, "e1").await.unwrap();
+        assert_eq!(
+            (report.prompt_tokens, report.completion_tokens),
+            (2500, 3000)
+        );
+        let nodes: Vec<(&str, &str, i64, bool)> = report
+            .by_node
+            .iter()
+            .map(|n| {
+                (
+                    n.node_id.as_str(),
+                    n.node_name.as_str(),
+                    n.prompt_tokens,
+                    n.priced,
+                )
+            })
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                ("n1", "撰写正文", 2000, true),
+                ("n2", "润色", 500, false),
+                ("gone", "gone", 0, false)
+            ]
+        );
+        // gpt-5: (2000 * 3 + 2000 * 12) / 1e6
+        assert!((report.by_node[0].cost_usd - 0.03).abs() < 1e-12);
+        assert!((report.total_cost_usd - 0.03).abs() < 1e-12);
+
+        let empty = cost_report(&pool, "e2").await.unwrap();
+        assert!(empty.by_node.is_empty() && empty.total_cost_usd == 0.0);
+        assert!(cost_report(&pool, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,489 @@
+// 导出: 执行记录的结构化 JSON (供调试与外部分析脚本使用), 以及汇编后的全书草稿 Markdown
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+use tauri_plugin_fs::{FsExt, OpenOptions};
+
+use crate::commands::analytics;
+use crate::commands::annotation::{self, Annotation, AnnotationTarget};
+use crate::commands::chapter::{self, FullDraft};
+use crate::commands::chapter_length::{self, ChapterLengthIssue};
+use crate::db;
+use crate::models::{Execution, NodeResult};
+
+/// 导出格式版本, 字段只增不改; 不兼容的变更需要提升版本号
+pub const EXECUTION_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// 导出时需要清除的敏感字段 (小写, `-` 视为 `_`)
+pub(crate) const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "x_api_key",
+    "authorization",
+    "access_token",
+    "secret",
+    "password",
+];
+
+pub(crate) const SCRUBBED: &str = "[已移除]";
+
+// 执行记录元数据
+#[derive(Debug, Serialize)]
+pub struct ExportedExecutionMeta {
+    pub id: String,
+    pub workflow_id: String,
+    pub workflow_name: Option<String>,
+    pub status: String,
+    pub label: Option<String>,
+    pub title: Option<String>,
+    pub input: Option<String>,
+    pub final_output: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+// 单个节点结果; 同一节点同一轮次的重复执行按开始时间编号为 attempt
+#[derive(Debug, Serialize)]
+pub struct ExportedNodeResult {
+    pub id: String,
+    pub node_id: String,
+    pub node_name: Option<String>,
+    pub node_type: Option<String>,
+    pub iteration: i64,
+    pub attempt: i64,
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub token_usage: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_guard: Option<Value>,
+}
+
+// 执行记录导出文档
+#[derive(Debug, Serialize)]
+pub struct ExecutionExport {
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub execution: ExportedExecutionMeta,
+    pub node_results: Vec<ExportedNodeResult>,
+    /// 最终输出上的批注, 仅在导出时选择包含批注时输出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
+}
+
+// 两个 ISO 8601 时间之间的毫秒数, 无法解析时返回 None
+pub(crate) fn duration_ms(started_at: &str, finished_at: Option<&str>) -> Option<i64> {
+    let start = chrono::DateTime::parse_from_rfc3339(started_at).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(finished_at?).ok()?;
+    Some((end - start).num_milliseconds())
+}
+
+/// 递归清除 JSON 中的 API Key 等敏感字段
+pub fn scrub_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let normalized = key.to_lowercase().replace('-', "_");
+                if SECRET_KEYS.contains(&normalized.as_str()) {
+                    *child = Value::String(SCRUBBED.to_string());
+                } else {
+                    scrub_secrets(child);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub_secrets),
+        _ => {}
+    }
+}
+
+/// 构建执行记录导出文档
+pub async fn build_execution_export(
+    pool: &SqlitePool,
+    execution_id: &str,
+    include_resolved_configs: bool,
+) -> Result<ExecutionExport, String> {
+    let execution = sqlx::query_as::<_, Execution>("SELECT * FROM executions WHERE id = ?")
+        .bind(execution_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("执行记录不存在: {}", execution_id))?;
+    let workflow_name: Option<String> =
+        sqlx::query_scalar("SELECT name FROM workflows WHERE id = ?")
+            .bind(&execution.workflow_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let results = sqlx::query_as::<_, NodeResult>(
+        "SELECT * FROM node_results WHERE execution_id = ? ORDER BY started_at, rowid",
+    )
+    .bind(execution_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let nodes: HashMap<String, (String, String)> = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT id, name, type FROM nodes WHERE workflow_id = ?",
+    )
+    .bind(&execution.workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(id, name, node_type)| (id, (name, node_type)))
+    .collect();
+
+    let mut attempts: HashMap<(String, i64), i64> = HashMap::new();
+    let node_results = results
+        .into_iter()
+        .map(|result| {
+            let attempt = attempts
+                .entry((result.node_id.clone(), result.iteration))
+                .or_insert(0);
+            *attempt += 1;
+            let node = nodes.get(&result.node_id);
+            let resolved_config = result
+                .resolved_config
+                .filter(|_| include_resolved_configs)
+                .map(|mut config| {
+                    scrub_secrets(&mut config);
+                    config
+                });
+            ExportedNodeResult {
+                duration_ms: duration_ms(&result.started_at, result.finished_at.as_deref()),
+                id: result.id,
+                node_name: node.map(|(name, _)| name.clone()),
+                node_type: node.map(|(_, node_type)| node_type.clone()),
+                node_id: result.node_id,
+                iteration: result.iteration,
+                attempt: *attempt,
+                status: result.status,
+                started_at: result.started_at,
+                finished_at: result.finished_at,
+                input: result.input,
+                output: result.output,
+                token_usage: result.token_usage,
+                resolved_config,
+                output_guard: result.output_guard,
+            }
+        })
+        .collect();
+
+    Ok(ExecutionExport {
+        schema_version: EXECUTION_EXPORT_SCHEMA_VERSION,
+        exported_at: db::now(),
+        execution: ExportedExecutionMeta {
+            duration_ms: duration_ms(&execution.started_at, execution.finished_at.as_deref()),
+            id: execution.id,
+            workflow_id: execution.workflow_id,
+            workflow_name,
+            status: execution.status,
+            label: execution.label,
+            title: execution.title,
+            input: execution.input,
+            final_output: execution.final_output,
+            started_at: execution.started_at,
+            finished_at: execution.finished_at,
+        },
+        node_results,
+        annotations: None,
+    })
+}
+
+// 全书草稿导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    /// 正文字数, 与项目字数口径一致按字符计算, 不含 front matter 与章节标题
+    pub word_count: i64,
+    pub chapter_count: i64,
+    /// 以脚注输出的批注数
+    pub footnote_count: i64,
+    /// 正文字数超出项目章节字数范围的章节
+    pub out_of_range_chapters: Vec<ChapterLengthIssue>,
+}
+
+/// 全书草稿导出时批注的处理方式
+pub const ANNOTATION_MODES: &[&str] = &["skip", "footnotes"];
+
+/// 渲染全书草稿: YAML front matter (title / author / date) 后每章一个 `## 标题` 小节;
+/// `annotations` 为各章的批注 (以章节 ID 为键), 以 Markdown 脚注附在该章末尾, 编号全书连续
+pub fn render_full_draft_markdown(
+    draft: &FullDraft,
+    date: &str,
+    annotations: &HashMap<String, Vec<Annotation>>,
+) -> String {
+    // JSON 字符串同时是合法的 YAML 标量, 可安全转义引号与换行
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let mut output = format!("---\ntitle: {}\n", quote(&draft.title));
+    if let Some(author) = draft.author.as_deref().filter(|a| !a.trim().is_empty()) {
+        output.push_str(&format!("author: {}\n", quote(author)));
+    }
+    output.push_str(&format!("date: {}\n---\n", date));
+    let mut footnotes = 0;
+    for chapter in &draft.chapters {
+        output.push_str(&format!("\n## {}\n\n", chapter.title));
+        let (content, definitions) = match annotations.get(&chapter.chapter_id) {
+            Some(list) => annotation::with_footnotes(&chapter.content, list, footnotes + 1),
+            None => (chapter.content.clone(), Vec::new()),
+        };
+        if !content.is_empty() {
+            output.push_str(&content);
+            output.push('\n');
+        }
+        if !definitions.is_empty() {
+            footnotes += definitions.len();
+            output.push('\n');
+            output.push_str(&definitions.join("\n"));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[tauri::command]
+pub async fn export_full_draft_to_markdown(
+    app: AppHandle,
+    project_id: String,
+    destination_path: String,
+    annotation_mode: Option<String>,
+) -> Result<ExportReport, String> {
+    let mode = annotation_mode.as_deref().unwrap_or("skip");
+    if !ANNOTATION_MODES.contains(&mode) {
+        return Err(format!("不支持的批注导出方式: {}", mode));
+    }
+    let pool = db::pool(&app).await?;
+    let draft = chapter::full_draft(&pool, &project_id).await?;
+    let length_range = chapter_length::get_range(&pool, &project_id).await?;
+    let mut annotations = HashMap::new();
+    if mode == "footnotes" {
+        for chapter in &draft.chapters {
+            let target = AnnotationTarget::Chapter {
+                id: chapter.chapter_id.clone(),
+            };
+            annotations.insert(
+                chapter.chapter_id.clone(),
+                annotation::list(&pool, &target).await?,
+            );
+        }
+    }
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let markdown = render_full_draft_markdown(&draft, &date, &annotations);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    let mut file = app
+        .fs()
+        .open(PathBuf::from(&destination_path), options)
+        .map_err(|e| format!("无法写入文件 {}: {}", destination_path, e))?;
+    file.write_all(markdown.as_bytes())
+        .map_err(|e| format!("写入文件失败: {}", e))?;
+
+    let payload = serde_json::json!({ "target": "full_draft" });
+    if let Err(e) = analytics::record(&pool, "export_used", Some(&payload)).await {
+        eprintln!("记录使用统计失败: {}", e);
+    }
+    Ok(ExportReport {
+        word_count: draft
+            .chapters
+            .iter()
+            .map(|c| c.content.chars().count() as i64)
+            .sum(),
+        chapter_count: draft.chapters.len() as i64,
+        footnote_count: annotations
+            .values()
+            .flatten()
+            .filter(|a| !a.orphaned)
+            .count() as i64,
+        out_of_range_chapters: chapter_length::issues(&draft, &length_range),
+    })
+}
+
+#[tauri::command]
+pub async fn export_execution_json(
+    app: AppHandle,
+    execution_id: String,
+    path: String,
+    include_resolved_configs: bool,
+    include_annotations: Option<bool>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    let mut export = build_execution_export(&pool, &execution_id, include_resolved_configs).await?;
+    if include_annotations.unwrap_or(false) {
+        let target = AnnotationTarget::Execution {
+            id: execution_id.clone(),
+        };
+        export.annotations = Some(annotation::list(&pool, &target).await?);
+    }
+    let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("写入文件失败: {}", e))?;
+
+    let payload = serde_json::json!({ "target": "execution" });
+    if let Err(e) = analytics::record(&pool, "export_used", Some(&payload)).await {
+        eprintln!("记录使用统计失败: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::chapter::ChapterDraft;
+
+    #[test]
+    fn renders_full_draft_with_front_matter() {
+        let draft = FullDraft {
+            title: "雪夜\"驿站\"".to_string(),
+            author: Some("沈舟".to_string()),
+            chapters: vec![
+                ChapterDraft {
+                    chapter_id: "c1".to_string(),
+                    title: "雨夜".to_string(),
+                    content: "雨下得很急。".to_string(),
+                },
+                ChapterDraft {
+                    chapter_id: "c2".to_string(),
+                    title: "鬼市".to_string(),
+                    content: String::new(),
+                },
+            ],
+        };
+        assert_eq!(
+            render_full_draft_markdown(&draft, "2026-01-01", &HashMap::new()),
+            r#"---
+title: "雪夜\"驿站\""
+author: "沈舟"
+date: 2026-01-01
+---
+
+## 雨夜
+
+雨下得很急。
+
+## 鬼市
+
+"#
+        );
+
+        let anonymous = FullDraft {
+            author: None,
+            chapters: Vec::new(),
+            ..draft
+        };
+        assert!(
+            !render_full_draft_markdown(&anonymous, "2026-01-01", &HashMap::new())
+                .contains("author")
+        );
+    }
+
+    #[test]
+    fn renders_annotations_as_footnotes() {
+        let annotation =
+            |chapter_id: &str, start: i64, end: i64, note: &str, orphaned: bool| Annotation {
+                id: db::new_id(),
+                execution_id: None,
+                chapter_id: Some(chapter_id.to_string()),
+                start_offset: start,
+                end_offset: end,
+                anchor_text: String::new(),
+                note: note.to_string(),
+                kind: "issue".to_string(),
+                orphaned,
+                created_at: String::new(),
+                updated_at: String::new(),
+            };
+        let draft = FullDraft {
+            title: "雪夜驿站".to_string(),
+            author: None,
+            chapters: ["c1", "c2"]
+                .into_iter()
+                .map(|id| ChapterDraft {
+                    chapter_id: id.to_string(),
+                    title: id.to_string(),
+                    content: "林雪推开门。".to_string(),
+                })
+                .collect(),
+        };
+        let annotations = HashMap::from([
+            (
+                "c1".to_string(),
+                vec![
+                    annotation("c1", 0, 6, "节奏\n太快", false),
+                    annotation("c1", 0, 2, "已删除的句子", true),
+                ],
+            ),
+            (
+                "c2".to_string(),
+                vec![annotation("c2", 0, 2, "人设", false)],
+            ),
+        ]);
+        let markdown = render_full_draft_markdown(&draft, "2026-01-01", &annotations);
+        assert!(markdown.contains(
+            "## c1\n\n林雪推开门。[^1]\n\n[^1]: [问题] 节奏 太快\n\n## c2\n\n林雪[^2]推开门。\n\n[^2]: [问题] 人设\n"
+        ));
+        assert!(!markdown.contains("已删除的句子"));
+    }
+
+    #[test]
+    fn scrubs_nested_secrets() {
+        let mut config = serde_json::json!({
+            "provider": "openai",
+            "api_key": "sk-secret",
+            "headers": [{ "Authorization": "Bearer sk-secret", "X-Trace": "1" }],
+            "max_tokens": 2000
+        });
+        scrub_secrets(&mut config);
+        assert_eq!(config["api_key"], SCRUBBED);
+        assert_eq!(config["headers"][0]["Authorization"], SCRUBBED);
+        assert_eq!(config["headers"][0]["X-Trace"], "1");
+        assert_eq!(config["max_tokens"], 2000);
+    }
+
+    #[tokio::test]
+    async fn exports_ordered_results_with_attempts() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO nodes (id, workflow_id, type, name, order_index) VALUES ('n1', 'w1', 'ai_chat', '撰写正文', 0);
+            INSERT INTO executions (id, workflow_id, status, started_at, finished_at)
+                VALUES ('e1', 'w1', 'completed', '2026-01-01T00:00:00.000Z', '2026-01-01T00:00:05.000Z');
+            INSERT INTO node_results (id, execution_id, node_id, iteration, output, resolved_config, status, started_at, finished_at) VALUES
+                ('r2', 'e1', 'n1', 1, '正文', '{"api_key":"sk-secret","model":"gpt"}', 'completed', '2026-01-01T00:00:02.000Z', '2026-01-01T00:00:04.500Z'),
+                ('r1', 'e1', 'n1', 1, NULL, NULL, 'failed', '2026-01-01T00:00:01.000Z', '2026-01-01T00:00:02.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let export = build_execution_export(&pool, "e1", true).await.unwrap();
+        assert_eq!(export.schema_version, 1);
+        assert_eq!(export.execution.duration_ms, Some(5000));
+        assert_eq!(export.execution.workflow_name.as_deref(), Some("正文生成"));
+        let ids: Vec<&str> = export.node_results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["r1", "r2"]);
+        assert_eq!(export.node_results[1].attempt, 2);
+        assert_eq!(export.node_results[1].duration_ms, Some(2500));
+        let config = export.node_results[1].resolved_config.as_ref().unwrap();
+        assert_eq!(config["api_key"], SCRUBBED);
+        assert_eq!(config["model"], "gpt");
+
+        let without = build_execution_export(&pool, "e1", false).await.unwrap();
+        assert!(without.node_results[1].resolved_config.is_none());
+        assert!(!serde_json::to_string(&without)
+            .unwrap()
+            .contains("resolved_config"));
+    }
+}
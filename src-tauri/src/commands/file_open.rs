@@ -0,0 +1,228 @@
+// 通过文件关联打开的项目备份: 启动参数 (Windows/Linux) 或系统打开文件事件 (macOS) 传入路径,
+// 校验后发出 `import:suggestion` 事件由前端确认导入. 同一时间只有一个待确认的导入,
+// 其余排队, 前端处理完当前导入后调用 finish_import_suggestion 取得下一个
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::review;
+
+/// 有待确认的导入时发出的事件
+pub const IMPORT_SUGGESTION_EVENT: &str = "import:suggestion";
+
+/// 关联到应用的文件扩展名: 项目备份为 .chbak, 另接受导出的项目 JSON
+pub const IMPORT_EXTENSIONS: &[&str] = &["chbak", "json"];
+
+// 待确认的导入, 作为 `import:suggestion` 事件负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportSuggestion {
+    pub path: String,
+    pub project_name: String,
+    pub exported_at: Option<String>,
+    /// 只读审阅副本, 经 import_review_copy 导入
+    pub read_only: bool,
+    /// 加密的审阅副本, 导入前须输入密码
+    pub encrypted: bool,
+}
+
+#[derive(Default)]
+struct PendingImports {
+    active: Option<ImportSuggestion>,
+    queued: VecDeque<ImportSuggestion>,
+}
+
+/// 导入队列, 作为应用状态管理
+#[derive(Default)]
+pub struct ImportQueue(Mutex<PendingImports>);
+
+impl ImportQueue {
+    // 入队; 当前没有待确认的导入时直接成为当前导入并返回它
+    fn push(&self, suggestion: ImportSuggestion) -> Option<ImportSuggestion> {
+        let mut pending = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let duplicate =
+            pending.active.as_ref() == Some(&suggestion) || pending.queued.contains(&suggestion);
+        if duplicate {
+            return None;
+        }
+        if pending.active.is_some() {
+            pending.queued.push_back(suggestion);
+            return None;
+        }
+        pending.active = Some(suggestion.clone());
+        Some(suggestion)
+    }
+
+    // 结束当前导入, 返回下一个待确认的导入
+    fn finish(&self) -> Option<ImportSuggestion> {
+        let mut pending = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        pending.active = pending.queued.pop_front();
+        pending.active.clone()
+    }
+
+    fn active(&self) -> Option<ImportSuggestion> {
+        let pending = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        pending.active.clone()
+    }
+}
+
+/// 校验项目备份文件: 扩展名受支持, 内容为含 version、project、workflows、settings 的 JSON,
+/// 或审阅副本 (加密的审阅副本无法读取项目名)
+pub fn inspect(path: &Path) -> Result<ImportSuggestion, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    if !IMPORT_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("不支持的文件类型: {}", path.display()));
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let data: serde_json::Value =
+        serde_json::from_str(&content).map_err(|_| "无效的项目备份文件格式".to_string())?;
+    let exported_at = data
+        .get("exported_at")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    if let Some(encrypted) = review::detect(&data) {
+        let project_name = data
+            .pointer("/project/name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("加密的审阅副本");
+        return Ok(ImportSuggestion {
+            path: path.to_string_lossy().into_owned(),
+            project_name: project_name.to_string(),
+            exported_at,
+            read_only: true,
+            encrypted,
+        });
+    }
+
+    let valid = ["version", "project", "workflows", "settings"]
+        .iter()
+        .all(|key| data.get(key).is_some_and(|v| !v.is_null()));
+    let project_name = data
+        .pointer("/project/name")
+        .and_then(|v| v.as_str())
+        .filter(|_| valid)
+        .ok_or_else(|| "无效的项目备份文件格式".to_string())?;
+    Ok(ImportSuggestion {
+        path: path.to_string_lossy().into_owned(),
+        project_name: project_name.to_string(),
+        exported_at,
+        read_only: false,
+        encrypted: false,
+    })
+}
+
+/// 处理打开的文件路径 (启动参数或单实例转发的参数均可), 跳过非文件参数与校验失败的文件
+pub fn offer_paths<I, P>(app: &AppHandle, paths: I)
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let queue = app.state::<ImportQueue>();
+    for path in paths {
+        let path = path.as_ref();
+        if !path.is_file() {
+            continue;
+        }
+        match inspect(path) {
+            Ok(suggestion) => {
+                if let Some(active) = queue.push(suggestion) {
+                    let _ = app.emit(IMPORT_SUGGESTION_EVENT, active);
+                }
+            }
+            Err(e) => eprintln!("忽略打开的文件 {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// 当前待确认的导入; 前端启动时调用, 以免错过监听注册前发出的事件
+#[tauri::command]
+pub fn get_pending_import_suggestion(app: AppHandle) -> Option<ImportSuggestion> {
+    app.state::<ImportQueue>().active()
+}
+
+/// 当前导入已完成或被取消, 有排队的导入时发出下一个 `import:suggestion` 事件
+#[tauri::command]
+pub fn finish_import_suggestion(app: AppHandle) -> Option<ImportSuggestion> {
+    let next = app.state::<ImportQueue>().finish();
+    if let Some(next) = &next {
+        let _ = app.emit(IMPORT_SUGGESTION_EVENT, next);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn suggestion(path: &str) -> ImportSuggestion {
+        ImportSuggestion {
+            path: path.to_string(),
+            project_name: "测试项目".to_string(),
+            exported_at: None,
+            read_only: false,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn inspects_backup_files() {
+        let dir = std::env::temp_dir().join(format!("chouann-file-open-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let backup = r#"{"version":"1.0","exported_at":"2026-01-01","project":{"name":"雨夜"},"workflows":[],"settings":[]}"#;
+
+        let found = inspect(&write(&dir, "雨夜.CHBAK", backup)).unwrap();
+        assert_eq!(found.project_name, "雨夜");
+        assert_eq!(found.exported_at.as_deref(), Some("2026-01-01"));
+        assert!(inspect(&write(&dir, "雨夜.json", backup)).is_ok());
+        assert!(inspect(&write(&dir, "雨夜.txt", backup)).is_err());
+        assert!(inspect(&write(&dir, "坏.chbak", "not json")).is_err());
+        assert!(inspect(&write(
+            &dir,
+            "缺字段.chbak",
+            r#"{"version":"1.0","project":{"name":"雨夜"}}"#
+        ))
+        .is_err());
+        assert!(inspect(&dir.join("missing.chbak")).is_err());
+
+        let review_copy = r#"{"format":"chouann-review-copy","read_only":true,"encrypted":{}}"#;
+        let found = inspect(&write(&dir, "审阅.chbak", review_copy)).unwrap();
+        assert!(found.read_only && found.encrypted);
+        assert_eq!(found.project_name, "加密的审阅副本");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn queues_imports_one_at_a_time() {
+        let queue = ImportQueue::default();
+        assert_eq!(
+            queue.push(suggestion("a.chbak")),
+            Some(suggestion("a.chbak"))
+        );
+        assert_eq!(queue.push(suggestion("b.chbak")), None);
+        // 重复打开同一文件不会重复排队
+        assert_eq!(queue.push(suggestion("a.chbak")), None);
+        assert_eq!(queue.push(suggestion("b.chbak")), None);
+        assert_eq!(queue.active(), Some(suggestion("a.chbak")));
+
+        assert_eq!(queue.finish(), Some(suggestion("b.chbak")));
+        assert_eq!(queue.finish(), None);
+        assert_eq!(queue.active(), None);
+        assert_eq!(
+            queue.push(suggestion("c.chbak")),
+            Some(suggestion("c.chbak"))
+        );
+    }
+}
@@ -0,0 +1,906 @@
+// 全局查找替换: 在设定、章节与节点提示词中查找文本, 确认后在单个事务中替换并写入撤销记录
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::setting;
+use crate::db;
+
+/// 节点配置中参与替换的提示词字段
+pub const PROMPT_KEYS: &[&str] = &[
+    "system_prompt",
+    "user_prompt",
+    "system_prompt_manual",
+    "user_prompt_manual",
+    "prompt",
+];
+
+/// 每个字段返回的匹配示例数
+const MAX_SAMPLES: usize = 3;
+
+/// 匹配示例前后保留的字符数
+const SAMPLE_CONTEXT_CHARS: usize = 20;
+
+/// 替换历史列表返回的条数
+const HISTORY_LIMIT: i64 = 50;
+
+/// 查找范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaceScope {
+    /// 设定名称
+    SettingNames,
+    /// 设定内容与摘要
+    SettingContent,
+    /// 设定关键词 (自动注入时作为别名匹配)
+    SettingAliases,
+    /// 关联到章节的执行记录最终输出, 即章节正文
+    ChapterContent,
+    /// 章节标题与梗概
+    OutlineSummaries,
+    /// 节点配置中的提示词
+    NodePrompts,
+}
+
+// 匹配选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplaceOptions {
+    /// 匹配前后不能紧邻 ASCII 字母、数字或下划线
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// find 按正则解析, replace 中可用 `$1` / `${name}` 引用分组
+    #[serde(default)]
+    pub regex: bool,
+}
+
+// 一次查找替换的完整条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceQuery {
+    pub find: String,
+    pub replace: String,
+    pub scopes: Vec<ReplaceScope>,
+    #[serde(default)]
+    pub options: ReplaceOptions,
+}
+
+/// 可被替换的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaceField {
+    SettingName,
+    SettingContent,
+    SettingSummary,
+    SettingKeywords,
+    ChapterTitle,
+    ChapterSynopsis,
+    ExecutionOutput,
+    NodeConfig,
+}
+
+impl ReplaceField {
+    // 字段所在的表、列, 以及该表是否有 updated_at
+    fn column(self) -> (&'static str, &'static str, bool) {
+        match self {
+            ReplaceField::SettingName => ("settings", "name", true),
+            ReplaceField::SettingContent => ("settings", "content", true),
+            ReplaceField::SettingSummary => ("settings", "summary", true),
+            ReplaceField::SettingKeywords => ("settings", "keywords", true),
+            ReplaceField::ChapterTitle => ("chapters", "title", true),
+            ReplaceField::ChapterSynopsis => ("chapters", "synopsis", true),
+            ReplaceField::ExecutionOutput => ("executions", "final_output", false),
+            ReplaceField::NodeConfig => ("nodes", "config", true),
+        }
+    }
+
+    fn is_setting(self) -> bool {
+        self.column().0 == "settings"
+    }
+}
+
+// 匹配处及其上下文
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchSample {
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+// 单个实体字段的匹配情况
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityMatches {
+    pub entity_id: String,
+    pub field: ReplaceField,
+    pub label: String,
+    pub count: usize,
+    pub samples: Vec<MatchSample>,
+}
+
+// 查找结果, 需携带 token 才能执行替换
+#[derive(Debug, Serialize)]
+pub struct FindReplacePreview {
+    pub total: usize,
+    pub entities: Vec<EntityMatches>,
+    pub token: String,
+}
+
+// 替换结果
+#[derive(Debug, Serialize)]
+pub struct ReplaceResult {
+    pub history_id: String,
+    pub replaced: usize,
+    pub entities: usize,
+    /// 被修改的设定, 便于前端刷新
+    pub setting_ids: Vec<String>,
+}
+
+// 撤销结果
+#[derive(Debug, Serialize)]
+pub struct UndoReplaceResult {
+    pub restored: usize,
+    /// 替换后又被修改过而未恢复的字段 (实体标签)
+    pub skipped: Vec<String>,
+    pub setting_ids: Vec<String>,
+}
+
+// 替换历史
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReplaceHistoryEntry {
+    pub id: String,
+    pub find: String,
+    pub replace_with: String,
+    pub replaced: i64,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+// 撤销记录中单个字段替换前后的值 (数据库中的原始文本)
+#[derive(Debug, Serialize, Deserialize)]
+struct FieldChange {
+    entity_id: String,
+    field: ReplaceField,
+    label: String,
+    before: String,
+    after: String,
+}
+
+// 字段内容; 关键词与节点配置只在其中的字符串上匹配
+enum FieldValue {
+    Text(String),
+    Keywords(Vec<String>),
+    Config(Map<String, Value>),
+}
+
+impl FieldValue {
+    fn texts(&self) -> Vec<&str> {
+        match self {
+            FieldValue::Text(text) => vec![text.as_str()],
+            FieldValue::Keywords(keywords) => keywords.iter().map(String::as_str).collect(),
+            FieldValue::Config(config) => PROMPT_KEYS
+                .iter()
+                .filter_map(|key| config.get(*key).and_then(Value::as_str))
+                .collect(),
+        }
+    }
+
+    // 替换后写回数据库的文本
+    fn replaced(&self, matcher: &Matcher) -> Result<String, String> {
+        match self {
+            FieldValue::Text(text) => Ok(matcher.replace(text)),
+            FieldValue::Keywords(keywords) => {
+                let keywords: Vec<String> = keywords
+                    .iter()
+                    .map(|k| matcher.replace(k))
+                    .filter(|k| !k.trim().is_empty())
+                    .collect();
+                serde_json::to_string(&keywords).map_err(|e| e.to_string())
+            }
+            FieldValue::Config(config) => {
+                let mut config = config.clone();
+                for key in PROMPT_KEYS {
+                    if let Some(Value::String(text)) = config.get_mut(*key) {
+                        *text = matcher.replace(text);
+                    }
+                }
+                serde_json::to_string(&config).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+// 设定的 id、名称、内容、摘要与关键词
+type SettingRow = (String, String, String, Option<String>, Option<String>);
+
+// 待查找的实体字段
+struct Target {
+    entity_id: String,
+    field: ReplaceField,
+    label: String,
+    raw: String,
+    value: FieldValue,
+}
+
+impl Target {
+    fn text(entity_id: &str, field: ReplaceField, label: &str, text: String) -> Self {
+        Target {
+            entity_id: entity_id.to_string(),
+            field,
+            label: label.to_string(),
+            raw: text.clone(),
+            value: FieldValue::Text(text),
+        }
+    }
+}
+
+struct Matcher {
+    regex: Regex,
+    whole_word: bool,
+    expand: bool,
+    replace: String,
+}
+
+fn is_ascii_word(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Matcher {
+    fn new(query: &ReplaceQuery) -> Result<Self, String> {
+        if query.find.is_empty() {
+            return Err("查找内容不能为空".to_string());
+        }
+        let pattern = if query.options.regex {
+            query.find.clone()
+        } else {
+            regex::escape(&query.find)
+        };
+        let pattern = if query.options.case_sensitive {
+            pattern
+        } else {
+            format!("(?i){}", pattern)
+        };
+        let regex = Regex::new(&pattern).map_err(|e| format!("正则表达式无效: {}", e))?;
+        Ok(Matcher {
+            regex,
+            whole_word: query.options.whole_word,
+            expand: query.options.regex,
+            replace: query.replace.clone(),
+        })
+    }
+
+    // 非空且满足整词条件的匹配
+    fn matches<'t>(&'t self, text: &'t str) -> impl Iterator<Item = Captures<'t>> + 't {
+        self.regex.captures_iter(text).filter(move |caps| {
+            let m = caps.get(0).expect("group 0 always matches");
+            !m.is_empty()
+                && (!self.whole_word
+                    || (!is_ascii_word(text[..m.start()].chars().next_back())
+                        && !is_ascii_word(text[m.end()..].chars().next())))
+        })
+    }
+
+    fn replace(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for caps in self.matches(text) {
+            let m = caps.get(0).expect("group 0 always matches");
+            out.push_str(&text[last..m.start()]);
+            if self.expand {
+                caps.expand(&self.replace, &mut out);
+            } else {
+                out.push_str(&self.replace);
+            }
+            last = m.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+fn sample(text: &str, start: usize, end: usize) -> MatchSample {
+    let before: Vec<char> = text[..start].chars().collect();
+    let after: String = text[end..].chars().take(SAMPLE_CONTEXT_CHARS).collect();
+    MatchSample {
+        before: before[before.len().saturating_sub(SAMPLE_CONTEXT_CHARS)..]
+            .iter()
+            .collect(),
+        matched: text[start..end].to_string(),
+        after,
+    }
+}
+
+// 按范围读取项目中可替换的字段
+async fn collect(
+    pool: &SqlitePool,
+    project_id: &str,
+    scopes: &HashSet<ReplaceScope>,
+) -> Result<Vec<Target>, String> {
+    let mut targets = Vec::new();
+
+    if scopes.contains(&ReplaceScope::SettingNames)
+        || scopes.contains(&ReplaceScope::SettingContent)
+        || scopes.contains(&ReplaceScope::SettingAliases)
+    {
+        let settings: Vec<SettingRow> = sqlx::query_as(
+            "SELECT id, name, content, summary, keywords FROM settings
+                 WHERE project_id = ? ORDER BY category, order_index, name",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (id, name, content, summary, keywords) in settings {
+            if scopes.contains(&ReplaceScope::SettingNames) {
+                targets.push(Target::text(
+                    &id,
+                    ReplaceField::SettingName,
+                    &name,
+                    name.clone(),
+                ));
+            }
+            if scopes.contains(&ReplaceScope::SettingContent) {
+                targets.push(Target::text(
+                    &id,
+                    ReplaceField::SettingContent,
+                    &name,
+                    content,
+                ));
+                if let Some(summary) = summary {
+                    targets.push(Target::text(
+                        &id,
+                        ReplaceField::SettingSummary,
+                        &name,
+                        summary,
+                    ));
+                }
+            }
+            if scopes.contains(&ReplaceScope::SettingAliases) {
+                let parsed = keywords
+                    .as_deref()
+                    .and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok());
+                if let (Some(raw), Some(list)) = (keywords, parsed) {
+                    targets.push(Target {
+                        entity_id: id,
+                        field: ReplaceField::SettingKeywords,
+                        label: name,
+                        raw,
+                        value: FieldValue::Keywords(list),
+                    });
+                }
+            }
+        }
+    }
+
+    if scopes.contains(&ReplaceScope::OutlineSummaries) {
+        let chapters: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, title, synopsis FROM chapters WHERE project_id = ? ORDER BY order_index, created_at",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (id, title, synopsis) in chapters {
+            targets.push(Target::text(
+                &id,
+                ReplaceField::ChapterTitle,
+                &title,
+                title.clone(),
+            ));
+            if let Some(synopsis) = synopsis {
+                targets.push(Target::text(
+                    &id,
+                    ReplaceField::ChapterSynopsis,
+                    &title,
+                    synopsis,
+                ));
+            }
+        }
+    }
+
+    if scopes.contains(&ReplaceScope::ChapterContent) {
+        let outputs: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT e.id, c.title, e.final_output FROM executions e
+             JOIN chapters c ON c.id = e.chapter_id
+             WHERE c.project_id = ? AND e.final_output IS NOT NULL
+             ORDER BY c.order_index, e.started_at",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (id, title, output) in outputs {
+            targets.push(Target::text(
+                &id,
+                ReplaceField::ExecutionOutput,
+                &title,
+                output,
+            ));
+        }
+    }
+
+    if scopes.contains(&ReplaceScope::NodePrompts) {
+        let nodes: Vec<(String, String, String, String)> = sqlx::query_as(
+            "SELECT n.id, w.name, n.name, n.config FROM nodes n
+             JOIN workflows w ON w.id = n.workflow_id
+             WHERE w.project_id = ? ORDER BY w.name, n.order_index",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (id, workflow_name, node_name, config) in nodes {
+            if let Ok(Value::Object(map)) = serde_json::from_str(&config) {
+                targets.push(Target {
+                    entity_id: id,
+                    field: ReplaceField::NodeConfig,
+                    label: format!("{} / {}", workflow_name, node_name),
+                    raw: config,
+                    value: FieldValue::Config(map),
+                });
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+// 查找并保留有匹配的字段
+async fn scan(
+    pool: &SqlitePool,
+    project_id: &str,
+    query: &ReplaceQuery,
+) -> Result<(Matcher, Vec<(Target, EntityMatches)>), String> {
+    let matcher = Matcher::new(query)?;
+    let scopes: HashSet<ReplaceScope> = query.scopes.iter().copied().collect();
+    if scopes.is_empty() {
+        return Err("请选择查找范围".to_string());
+    }
+
+    let mut found = Vec::new();
+    for target in collect(pool, project_id, &scopes).await? {
+        let mut count = 0;
+        let mut samples = Vec::new();
+        for text in target.value.texts() {
+            for caps in matcher.matches(text) {
+                let m = caps.get(0).expect("group 0 always matches");
+                count += 1;
+                if samples.len() < MAX_SAMPLES {
+                    samples.push(sample(text, m.start(), m.end()));
+                }
+            }
+        }
+        if count > 0 {
+            let matches = EntityMatches {
+                entity_id: target.entity_id.clone(),
+                field: target.field,
+                label: target.label.clone(),
+                count,
+                samples,
+            };
+            found.push((target, matches));
+        }
+    }
+    Ok((matcher, found))
+}
+
+// 由条件与匹配到的原始内容生成令牌, 内容变化后令牌随之失效
+fn replace_token(
+    project_id: &str,
+    query: &ReplaceQuery,
+    found: &[(Target, EntityMatches)],
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    project_id.hash(&mut hasher);
+    serde_json::to_string(query)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    for (target, _) in found {
+        target.entity_id.hash(&mut hasher);
+        target.raw.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+fn setting_ids<'a>(fields: impl Iterator<Item = (&'a str, ReplaceField)>) -> Vec<String> {
+    let mut ids: Vec<String> = fields
+        .filter(|(_, field)| field.is_setting())
+        .map(|(id, _)| id.to_string())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// 查找: 返回每个实体字段的匹配数与示例, 以及执行替换所需的令牌
+pub async fn find(
+    pool: &SqlitePool,
+    project_id: &str,
+    query: &ReplaceQuery,
+) -> Result<FindReplacePreview, String> {
+    let (_, found) = scan(pool, project_id, query).await?;
+    let token = replace_token(project_id, query, &found);
+    let entities: Vec<EntityMatches> = found.into_iter().map(|(_, matches)| matches).collect();
+    Ok(FindReplacePreview {
+        total: entities.iter().map(|e| e.count).sum(),
+        entities,
+        token,
+    })
+}
+
+/// 替换: 重新查找并核对令牌, 在同一事务中写回全部字段并记录替换前后的值
+pub async fn apply(
+    pool: &SqlitePool,
+    project_id: &str,
+    query: &ReplaceQuery,
+    token: &str,
+) -> Result<ReplaceResult, String> {
+    let (matcher, found) = scan(pool, project_id, query).await?;
+    if replace_token(project_id, query, &found) != token {
+        return Err("确认令牌无效或内容已变化, 请重新查找".to_string());
+    }
+    if found.is_empty() {
+        return Err("没有可替换的内容".to_string());
+    }
+
+    let mut changes = Vec::new();
+    let mut replaced = 0;
+    for (target, matches) in found {
+        let after = target.value.replaced(&matcher)?;
+        replaced += matches.count;
+        if after != target.raw {
+            changes.push(FieldChange {
+                entity_id: target.entity_id,
+                field: target.field,
+                label: target.label,
+                before: target.raw,
+                after,
+            });
+        }
+    }
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for change in &changes {
+        let (table, column, has_updated_at) = change.field.column();
+        let sql = if has_updated_at {
+            format!(
+                "UPDATE {} SET {} = ?, updated_at = ? WHERE id = ?",
+                table, column
+            )
+        } else {
+            format!("UPDATE {} SET {} = ? WHERE id = ?", table, column)
+        };
+        let mut statement = sqlx::query(&sql).bind(&change.after);
+        if has_updated_at {
+            statement = statement.bind(&now);
+        }
+        statement
+            .bind(&change.entity_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let history_id = db::new_id();
+    sqlx::query(
+        "INSERT INTO replace_history (id, project_id, find, replace_with, options, changes, replaced, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&history_id)
+    .bind(project_id)
+    .bind(&query.find)
+    .bind(&query.replace)
+    .bind(serde_json::to_string(&(&query.scopes, &query.options)).map_err(|e| e.to_string())?)
+    .bind(serde_json::to_string(&changes).map_err(|e| e.to_string())?)
+    .bind(replaced as i64)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(ReplaceResult {
+        history_id,
+        replaced,
+        entities: changes.len(),
+        setting_ids: setting_ids(changes.iter().map(|c| (c.entity_id.as_str(), c.field))),
+    })
+}
+
+/// 撤销一次替换; 替换后又被修改过的字段保持现状并在结果中列出
+pub async fn undo(pool: &SqlitePool, history_id: &str) -> Result<UndoReplaceResult, String> {
+    let (changes, undone_at): (String, Option<String>) =
+        sqlx::query_as("SELECT changes, undone_at FROM replace_history WHERE id = ?")
+            .bind(history_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("替换记录不存在: {}", history_id))?;
+    if undone_at.is_some() {
+        return Err("该替换已撤销".to_string());
+    }
+    let changes: Vec<FieldChange> = serde_json::from_str(&changes).map_err(|e| e.to_string())?;
+
+    let now = db::now();
+    let mut restored = Vec::new();
+    let mut skipped = Vec::new();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for change in &changes {
+        let (table, column, has_updated_at) = change.field.column();
+        let current: Option<Option<String>> =
+            sqlx::query_scalar(&format!("SELECT {} FROM {} WHERE id = ?", column, table))
+                .bind(&change.entity_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        if current.flatten().as_deref() != Some(change.after.as_str()) {
+            skipped.push(change.label.clone());
+            continue;
+        }
+        let sql = if has_updated_at {
+            format!(
+                "UPDATE {} SET {} = ?, updated_at = ? WHERE id = ?",
+                table, column
+            )
+        } else {
+            format!("UPDATE {} SET {} = ? WHERE id = ?", table, column)
+        };
+        let mut statement = sqlx::query(&sql).bind(&change.before);
+        if has_updated_at {
+            statement = statement.bind(&now);
+        }
+        statement
+            .bind(&change.entity_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        restored.push(change);
+    }
+    sqlx::query("UPDATE replace_history SET undone_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(history_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(UndoReplaceResult {
+        restored: restored.len(),
+        skipped,
+        setting_ids: setting_ids(restored.iter().map(|c| (c.entity_id.as_str(), c.field))),
+    })
+}
+
+/// 项目最近的替换记录, 新的在前
+pub async fn history(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<ReplaceHistoryEntry>, String> {
+    sqlx::query_as::<_, ReplaceHistoryEntry>(
+        "SELECT id, find, replace_with, replaced, created_at, undone_at FROM replace_history
+         WHERE project_id = ? ORDER BY created_at DESC, rowid DESC LIMIT ?",
+    )
+    .bind(project_id)
+    .bind(HISTORY_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_replace(
+    app: AppHandle,
+    project_id: String,
+    find: String,
+    replace: String,
+    scopes: Vec<ReplaceScope>,
+    options: Option<ReplaceOptions>,
+) -> Result<FindReplacePreview, String> {
+    let pool = db::pool(&app).await?;
+    let query = ReplaceQuery {
+        find,
+        replace,
+        scopes,
+        options: options.unwrap_or_default(),
+    };
+    self::find(&pool, &project_id, &query).await
+}
+
+#[tauri::command]
+pub async fn apply_replace(
+    app: AppHandle,
+    project_id: String,
+    find: String,
+    replace: String,
+    scopes: Vec<ReplaceScope>,
+    options: Option<ReplaceOptions>,
+    token: String,
+) -> Result<ReplaceResult, String> {
+    let pool = db::pool(&app).await?;
+    let query = ReplaceQuery {
+        find,
+        replace,
+        scopes,
+        options: options.unwrap_or_default(),
+    };
+    let result = apply(&pool, &project_id, &query, &token).await?;
+    setting::notify_changed(&app, &pool, &result.setting_ids, "updated").await;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn undo_replace(app: AppHandle, history_id: String) -> Result<UndoReplaceResult, String> {
+    let pool = db::pool(&app).await?;
+    let result = undo(&pool, &history_id).await?;
+    setting::notify_changed(&app, &pool, &result.setting_ids, "updated").await;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_replace_history(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<ReplaceHistoryEntry>, String> {
+    let pool = db::pool(&app).await?;
+    history(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '其他项目');
+            INSERT INTO settings (id, project_id, category, name, content, keywords) VALUES
+                ('s1', 'p1', 'character', '林雪', '林雪, 又名 Lin, 与 Lina 是旧识。', '["林雪","小雪"]'),
+                ('s2', 'p2', 'character', '林雪', '别的项目', NULL);
+            INSERT INTO chapters (id, project_id, title, synopsis, order_index, status) VALUES
+                ('c1', 'p1', '雨夜', '林雪推开木门', 0, 'draft');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO executions (id, workflow_id, status, final_output, chapter_id) VALUES
+                ('e1', 'w1', 'completed', '林雪说: "走吧。"', 'c1'),
+                ('e2', 'w1', 'completed', '未归档的林雪', NULL);
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'ai_chat', '续写', '{"system_prompt":"主角是林雪","model":"林雪"}', 0);
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn query(find: &str, replace: &str, scopes: &[ReplaceScope]) -> ReplaceQuery {
+        ReplaceQuery {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            scopes: scopes.to_vec(),
+            options: ReplaceOptions::default(),
+        }
+    }
+
+    const ALL_SCOPES: &[ReplaceScope] = &[
+        ReplaceScope::SettingNames,
+        ReplaceScope::SettingContent,
+        ReplaceScope::SettingAliases,
+        ReplaceScope::ChapterContent,
+        ReplaceScope::OutlineSummaries,
+        ReplaceScope::NodePrompts,
+    ];
+
+    #[tokio::test]
+    async fn finds_matches_per_entity_and_scope() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let preview = find(&pool, "p1", &query("林雪", "苏晴", ALL_SCOPES))
+            .await
+            .unwrap();
+        let fields: Vec<(&str, ReplaceField, usize)> = preview
+            .entities
+            .iter()
+            .map(|e| (e.entity_id.as_str(), e.field, e.count))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("s1", ReplaceField::SettingName, 1),
+                ("s1", ReplaceField::SettingContent, 1),
+                ("s1", ReplaceField::SettingKeywords, 1),
+                ("c1", ReplaceField::ChapterSynopsis, 1),
+                ("e1", ReplaceField::ExecutionOutput, 1),
+                ("n1", ReplaceField::NodeConfig, 1),
+            ]
+        );
+        assert_eq!(preview.total, 6);
+        assert_eq!(preview.entities[3].samples[0].after, "推开木门");
+        assert_eq!(preview.entities[5].label, "正文生成 / 续写");
+
+        let only_names = find(
+            &pool,
+            "p1",
+            &query("林雪", "", &[ReplaceScope::SettingNames]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(only_names.total, 1);
+
+        let mut word = query("lin", "Su", &[ReplaceScope::SettingContent]);
+        assert_eq!(find(&pool, "p1", &word).await.unwrap().total, 2);
+        word.options.whole_word = true;
+        assert_eq!(find(&pool, "p1", &word).await.unwrap().total, 1);
+        word.options.case_sensitive = true;
+        assert_eq!(find(&pool, "p1", &word).await.unwrap().total, 0);
+
+        let mut bad = query("(", "", ALL_SCOPES);
+        bad.options.regex = true;
+        assert!(find(&pool, "p1", &bad).await.is_err());
+        assert!(find(&pool, "p1", &query("", "", ALL_SCOPES)).await.is_err());
+        assert!(find(&pool, "p1", &query("林雪", "", &[])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn applies_and_undoes_replacements() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let mut q = query("林(雪)", "苏$1", ALL_SCOPES);
+        q.options.regex = true;
+        let preview = find(&pool, "p1", &q).await.unwrap();
+        assert!(apply(&pool, "p1", &q, "stale").await.is_err());
+
+        let result = apply(&pool, "p1", &q, &preview.token).await.unwrap();
+        assert_eq!(result.replaced, 6);
+        assert_eq!(result.entities, 6);
+        assert_eq!(result.setting_ids, vec!["s1"]);
+
+        let (name, keywords): (String, String) =
+            sqlx::query_as("SELECT name, keywords FROM settings WHERE id = 's1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(name, "苏雪");
+        assert_eq!(keywords, r#"["苏雪","小雪"]"#);
+        let config: String = sqlx::query_scalar("SELECT config FROM nodes WHERE id = 'n1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let config: Value = serde_json::from_str(&config).unwrap();
+        assert_eq!(config["system_prompt"], "主角是苏雪");
+        assert_eq!(config["model"], "林雪");
+        let untouched: Vec<String> = sqlx::query_scalar(
+            "SELECT final_output FROM executions WHERE id = 'e2'
+                                UNION ALL SELECT name FROM settings WHERE id = 's2'",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(untouched, vec!["未归档的林雪", "林雪"]);
+
+        // 替换后手动修改的字段不会被撤销覆盖
+        sqlx::query("UPDATE chapters SET synopsis = '手动修改' WHERE id = 'c1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let undone = undo(&pool, &result.history_id).await.unwrap();
+        assert_eq!(undone.restored, 5);
+        assert_eq!(undone.skipped, vec!["雨夜"]);
+        let content: String = sqlx::query_scalar("SELECT content FROM settings WHERE id = 's1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(content, "林雪, 又名 Lin, 与 Lina 是旧识。");
+        assert!(undo(&pool, &result.history_id).await.is_err());
+
+        let entries = history(&pool, "p1").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].replaced, 6);
+        assert!(entries[0].undone_at.is_some());
+    }
+}
@@ -0,0 +1,237 @@
+// 微调数据导入: 把 OpenAI 微调格式的 JSONL 训练样本导入为工作流的模拟执行记录,
+// 每行 `{"messages": [...]}` 的用户消息作为输入, 助手消息作为最终输出
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 导入的执行记录状态
+pub const SIMULATED_STATUS: &str = "simulated";
+
+/// JSONL 文件大小上限
+const MAX_JSONL_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    role: String,
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Example {
+    messages: Vec<Message>,
+}
+
+// 未导入的行
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedLine {
+    /// 行号, 从 1 开始
+    pub line: usize,
+    pub reason: String,
+}
+
+// 微调数据导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub execution_ids: Vec<String>,
+    pub skipped: Vec<SkippedLine>,
+}
+
+/// 解析一行训练样本, 返回 (输入, 输出): 输出取最后一条助手消息, 输入取其之前最近的用户消息
+fn parse_example(line: &str) -> Result<(String, String), String> {
+    let example: Example =
+        serde_json::from_str(line).map_err(|e| format!("JSON 格式错误: {}", e))?;
+    let content = |message: &Message| {
+        message
+            .content
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+            .map(str::to_string)
+    };
+    let assistant = example
+        .messages
+        .iter()
+        .rposition(|m| m.role == "assistant" && content(m).is_some())
+        .ok_or_else(|| "缺少助手消息".to_string())?;
+    let input = example.messages[..assistant]
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .and_then(content)
+        .ok_or_else(|| "缺少用户消息".to_string())?;
+    let output = content(&example.messages[assistant]).unwrap_or_default();
+    Ok((input, output))
+}
+
+/// 导入 JSONL 训练样本为工作流的模拟执行记录; 空行忽略, 格式不符的行跳过并记录原因
+pub async fn import_jsonl(
+    pool: &SqlitePool,
+    project_id: &str,
+    workflow_id: &str,
+    jsonl_path: &str,
+) -> Result<ImportReport, String> {
+    let owner: Option<String> = sqlx::query_scalar("SELECT project_id FROM workflows WHERE id = ?")
+        .bind(workflow_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if owner.as_deref() != Some(project_id) {
+        return Err(format!("项目中不存在工作流: {}", workflow_id));
+    }
+
+    let size = std::fs::metadata(jsonl_path)
+        .map_err(|e| format!("读取文件失败: {}", e))?
+        .len();
+    if size > MAX_JSONL_BYTES {
+        return Err(format!(
+            "JSONL 文件过大 ({} 字节), 上限为 {} 字节",
+            size, MAX_JSONL_BYTES
+        ));
+    }
+    let text = std::fs::read_to_string(jsonl_path)
+        .map_err(|e| format!("无法读取文件 {}: {}", jsonl_path, e))?;
+
+    let mut examples = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_example(line) {
+            Ok(example) => examples.push(example),
+            Err(reason) => skipped.push(SkippedLine {
+                line: index + 1,
+                reason,
+            }),
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let now = db::now();
+    let mut execution_ids = Vec::with_capacity(examples.len());
+    for (input, output) in &examples {
+        let id = db::new_id();
+        sqlx::query(
+            "INSERT INTO executions (id, workflow_id, status, input, final_output, started_at, finished_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(workflow_id)
+        .bind(SIMULATED_STATUS)
+        .bind(input)
+        .bind(output)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        execution_ids.push(id);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(ImportReport {
+        imported: execution_ids.len(),
+        execution_ids,
+        skipped,
+    })
+}
+
+#[tauri::command]
+pub async fn import_openai_fine_tune_data(
+    app: AppHandle,
+    project_id: String,
+    workflow_id: String,
+    jsonl_path: String,
+) -> Result<ImportReport, String> {
+    let pool = db::pool(&app).await?;
+    import_jsonl(&pool, &project_id, &workflow_id, &jsonl_path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_last_assistant_reply_and_preceding_user_message() {
+        let line = r#"{"messages": [
+            {"role": "system", "content": "你是小说作者"},
+            {"role": "user", "content": "写开头"},
+            {"role": "assistant", "content": "雨夜。"},
+            {"role": "user", "content": "续写"},
+            {"role": "assistant", "content": "驿站的灯灭了。"}
+        ]}"#;
+        assert_eq!(
+            parse_example(line).unwrap(),
+            ("续写".to_string(), "驿站的灯灭了。".to_string())
+        );
+        assert!(parse_example(r#"{"messages": [{"role": "user", "content": "写开头"}]}"#).is_err());
+        assert!(
+            parse_example(r#"{"messages": [{"role": "assistant", "content": "雨夜。"}]}"#).is_err()
+        );
+        assert!(parse_example("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn imports_examples_as_simulated_executions() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '雾城'), ('p2', '潮汐');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("fine-tune-{}.jsonl", db::new_id()));
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"messages": [{"role": "user", "content": "第一章大纲"}, {"role": "assistant", "content": "雨夜。"}]}"#,
+                "\n\n",
+                r#"{"messages": []}"#,
+                "\n",
+                r#"{"messages": [{"role": "user", "content": "第二章大纲"}, {"role": "assistant", "content": "进城。"}]}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let path_str = path.to_str().unwrap();
+
+        assert!(import_jsonl(&pool, "p2", "w1", path_str).await.is_err());
+        assert!(import_jsonl(&pool, "p1", "w1", "/nonexistent/data.jsonl")
+            .await
+            .is_err());
+
+        let report = import_jsonl(&pool, "p1", "w1", path_str).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(
+            report.skipped,
+            [SkippedLine {
+                line: 3,
+                reason: "缺少助手消息".to_string()
+            }]
+        );
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT status, input, final_output FROM executions WHERE workflow_id = 'w1' ORDER BY input",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            [
+                (
+                    "simulated".to_string(),
+                    "第一章大纲".to_string(),
+                    "雨夜。".to_string()
+                ),
+                (
+                    "simulated".to_string(),
+                    "第二章大纲".to_string(),
+                    "进城。".to_string()
+                ),
+            ]
+        );
+    }
+}
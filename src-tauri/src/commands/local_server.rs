@@ -0,0 +1,441 @@
+// 本地 HTTP API: 供外部脚本自动化调用, 只监听 127.0.0.1. 每个请求须通过
+// `Authorization: Bearer <key>` 或 `X-Api-Key` 请求头携带 API Key, 并具有路由要求的访问范围.
+// 执行本身仍由前端引擎完成, `POST /projects/{id}/execute` 只把执行加入队列, 由前端的队列消费在引擎空闲时运行
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::extract::{Path, Request, State as ServerState};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::commands::{api_key, budget, daily_limit, queue};
+use crate::db;
+use crate::error::CommandError;
+use crate::models::{Execution, Project};
+
+/// 本地 HTTP 服务句柄, 作为应用状态管理; 未启动时为空
+#[derive(Default)]
+pub struct LocalServerHandle(Mutex<Option<RunningServer>>);
+
+struct RunningServer {
+    port: u16,
+    started_at: String,
+    /// 本次启动以来处理的请求数 (含鉴权失败的请求)
+    requests: Arc<AtomicU64>,
+    shutdown: oneshot::Sender<()>,
+}
+
+// 本地 HTTP 服务状态, 显示在高级设置中
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub requests_served: u64,
+    pub started_at: Option<String>,
+}
+
+impl LocalServerHandle {
+    fn server(&self) -> MutexGuard<'_, Option<RunningServer>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// 错误响应, 正文为 `{ "error": 信息 }`
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<CommandError> for ApiError {
+    fn from(error: CommandError) -> Self {
+        let status = match error {
+            CommandError::BudgetExceeded { .. }
+            | CommandError::DailyLimitExceeded { .. }
+            | CommandError::ReadOnlyProject
+            | CommandError::Locked(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+fn internal(message: String) -> ApiError {
+    ApiError(StatusCode::INTERNAL_SERVER_ERROR, message)
+}
+
+// 请求携带的原始密钥
+fn raw_key(headers: &HeaderMap) -> Option<&str> {
+    let value = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    value(header::AUTHORIZATION.as_str())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| value("x-api-key"))
+}
+
+/// 校验请求的 API Key 是否授予了指定访问范围
+async fn authorize(pool: &SqlitePool, headers: &HeaderMap, scope: &str) -> Result<(), ApiError> {
+    let key = raw_key(headers)
+        .ok_or_else(|| ApiError(StatusCode::UNAUTHORIZED, "缺少 API Key".to_string()))?;
+    let scopes = api_key::validate(pool, key)
+        .await
+        .map_err(|e| ApiError(StatusCode::UNAUTHORIZED, e))?;
+    if !scopes.iter().any(|s| s == scope) {
+        return Err(ApiError(
+            StatusCode::FORBIDDEN,
+            format!("API Key 未授予访问范围: {}", scope),
+        ));
+    }
+    Ok(())
+}
+
+async fn list_projects(
+    ServerState(pool): ServerState<SqlitePool>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Project>>, ApiError> {
+    authorize(&pool, &headers, "projects:read").await?;
+    let projects =
+        sqlx::query_as::<_, Project>("SELECT * FROM projects ORDER BY created_at, rowid")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| internal(e.to_string()))?;
+    Ok(Json(projects))
+}
+
+// `POST /projects/{id}/execute` 的请求正文
+#[derive(Debug, Deserialize)]
+struct ExecuteRequest {
+    workflow_id: String,
+    input: Option<String>,
+    /// high / normal / low, 默认 normal
+    priority: Option<String>,
+}
+
+async fn execute_project(
+    ServerState(pool): ServerState<SqlitePool>,
+    Path(project_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<ExecuteRequest>,
+) -> Result<(StatusCode, Json<Execution>), ApiError> {
+    authorize(&pool, &headers, "executions:write").await?;
+    let owner: Option<String> = sqlx::query_scalar("SELECT project_id FROM workflows WHERE id = ?")
+        .bind(&request.workflow_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+    if owner.as_deref() != Some(project_id.as_str()) {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!("项目中不存在工作流: {}", request.workflow_id),
+        ));
+    }
+    budget::ensure_workflow_within_budget(&pool, &request.workflow_id).await?;
+    daily_limit::ensure_within_daily_limit(&pool, &request.workflow_id, 1).await?;
+    let execution = queue::enqueue(
+        &pool,
+        &request.workflow_id,
+        request.input.as_deref(),
+        request.priority.as_deref().unwrap_or("normal"),
+    )
+    .await
+    .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::ACCEPTED, Json(execution)))
+}
+
+async fn get_execution(
+    ServerState(pool): ServerState<SqlitePool>,
+    Path(execution_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Execution>, ApiError> {
+    authorize(&pool, &headers, "executions:read").await?;
+    sqlx::query_as::<_, Execution>("SELECT * FROM executions WHERE id = ?")
+        .bind(&execution_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| internal(e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError(
+                StatusCode::NOT_FOUND,
+                format!("执行记录不存在: {}", execution_id),
+            )
+        })
+}
+
+fn router(pool: SqlitePool, requests: Arc<AtomicU64>) -> Router {
+    Router::new()
+        .route("/projects", get(list_projects))
+        .route("/projects/{id}/execute", post(execute_project))
+        .route("/executions/{id}", get(get_execution))
+        .with_state(pool)
+        .layer(middleware::from_fn(move |request: Request, next: Next| {
+            requests.fetch_add(1, Ordering::Relaxed);
+            next.run(request)
+        }))
+}
+
+/// 在已绑定的监听器上提供服务, 直到收到关闭信号
+async fn serve(
+    listener: TcpListener,
+    pool: SqlitePool,
+    requests: Arc<AtomicU64>,
+    shutdown: oneshot::Receiver<()>,
+) {
+    let result = axum::serve(listener, router(pool, requests))
+        .with_graceful_shutdown(async {
+            let _ = shutdown.await;
+        })
+        .await;
+    if let Err(e) = result {
+        eprintln!("本地 HTTP 服务异常退出: {}", e);
+    }
+}
+
+/// 启动本地 HTTP 服务; 已在运行或端口被占用时报错
+pub async fn start(handle: &LocalServerHandle, pool: SqlitePool, port: u16) -> Result<(), String> {
+    if port == 0 {
+        return Err("端口不能为 0".to_string());
+    }
+    if handle.server().is_some() {
+        return Err("本地 HTTP 服务已在运行".to_string());
+    }
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("无法监听端口 {}: {}", port, e))?;
+
+    let mut server = handle.server();
+    if server.is_some() {
+        return Err("本地 HTTP 服务已在运行".to_string());
+    }
+    let (shutdown, receiver) = oneshot::channel();
+    let requests = Arc::new(AtomicU64::new(0));
+    tauri::async_runtime::spawn(serve(listener, pool, requests.clone(), receiver));
+    *server = Some(RunningServer {
+        port,
+        started_at: db::now(),
+        requests,
+        shutdown,
+    });
+    Ok(())
+}
+
+/// 停止本地 HTTP 服务, 返回此前监听的端口; 未运行时报错
+pub fn stop(handle: &LocalServerHandle) -> Result<u16, String> {
+    let server = handle
+        .server()
+        .take()
+        .ok_or_else(|| "本地 HTTP 服务未运行".to_string())?;
+    // 服务任务已退出时接收端已释放, 无需处理发送失败
+    let _ = server.shutdown.send(());
+    Ok(server.port)
+}
+
+/// 服务当前状态; 未运行时端口与启动时间为空, 请求数为 0
+pub fn status(handle: &LocalServerHandle) -> LocalServerStatus {
+    match handle.server().as_ref() {
+        Some(server) => LocalServerStatus {
+            running: true,
+            port: Some(server.port),
+            requests_served: server.requests.load(Ordering::Relaxed),
+            started_at: Some(server.started_at.clone()),
+        },
+        None => LocalServerStatus {
+            running: false,
+            port: None,
+            requests_served: 0,
+            started_at: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn start_local_http_server(
+    app: AppHandle,
+    handle: State<'_, LocalServerHandle>,
+    port: u16,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    start(&handle, pool, port).await
+}
+
+#[tauri::command]
+pub fn stop_local_http_server(handle: State<'_, LocalServerHandle>) -> Result<(), String> {
+    stop(&handle).map(|_| ())
+}
+
+#[tauri::command]
+pub fn get_local_server_status(
+    handle: State<'_, LocalServerHandle>,
+) -> Result<LocalServerStatus, String> {
+    Ok(status(&handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        response: tauri_plugin_http::reqwest::Response,
+    ) -> T {
+        serde_json::from_str(&response.text().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_routes_behind_api_key_scopes() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '雾城'), ('p2', '潮汐');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let reader = api_key::create(&pool, "只读", &["projects:read".to_string()], None)
+            .await
+            .unwrap()
+            .key;
+        let writer = api_key::create(
+            &pool,
+            "脚本",
+            &[
+                "executions:read".to_string(),
+                "executions:write".to_string(),
+            ],
+            None,
+        )
+        .await
+        .unwrap()
+        .key;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown, receiver) = oneshot::channel();
+        let requests = Arc::new(AtomicU64::new(0));
+        let server = tokio::spawn(serve(listener, pool.clone(), requests.clone(), receiver));
+        let client = tauri_plugin_http::reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/projects", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 401);
+        let response = client
+            .get(format!("{}/projects", base))
+            .bearer_auth("chn_wrong")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 401);
+
+        let response = client
+            .get(format!("{}/projects", base))
+            .bearer_auth(&reader)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let projects: Vec<serde_json::Value> = json(response).await;
+        assert_eq!(projects.len(), 2);
+
+        // 只读密钥不能创建执行
+        let body = serde_json::json!({ "workflow_id": "w1", "input": "第十二章大纲" });
+        let response = client
+            .post(format!("{}/projects/p1/execute", base))
+            .bearer_auth(&reader)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 403);
+        let response = client
+            .post(format!("{}/projects/p2/execute", base))
+            .header("X-Api-Key", &writer)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+
+        let response = client
+            .post(format!("{}/projects/p1/execute", base))
+            .header("X-Api-Key", &writer)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 202);
+        let execution: Execution = json(response).await;
+        assert_eq!(execution.status, "queued");
+
+        let response = client
+            .get(format!("{}/executions/{}", base, execution.id))
+            .header("X-Api-Key", &writer)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let fetched: Execution = json(response).await;
+        assert_eq!(fetched.input.as_deref(), Some("第十二章大纲"));
+        let response = client
+            .get(format!("{}/executions/missing", base))
+            .header("X-Api-Key", &writer)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+
+        assert_eq!(requests.load(Ordering::Relaxed), 8);
+
+        shutdown.send(()).unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_second_start_and_stop_when_idle() {
+        let pool = db::test_pool().await;
+        let handle = LocalServerHandle::default();
+        assert!(stop(&handle).is_err());
+        assert!(start(&handle, pool.clone(), 0).await.is_err());
+        assert!(!status(&handle).running);
+
+        // 借用空闲端口启动
+        let port = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        start(&handle, pool.clone(), port).await.unwrap();
+        let running = status(&handle);
+        assert!(running.running);
+        assert_eq!(running.port, Some(port));
+        assert_eq!(running.requests_served, 0);
+        assert!(running.started_at.is_some());
+        assert!(start(&handle, pool, port).await.is_err());
+        assert_eq!(stop(&handle).unwrap(), port);
+        assert!(stop(&handle).is_err());
+        assert_eq!(
+            status(&handle),
+            LocalServerStatus {
+                running: false,
+                port: None,
+                requests_served: 0,
+                started_at: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,48 @@
+// Tauri 命令, 按业务领域划分子模块
+pub mod analytics;
+pub mod annotation;
+pub mod api_key;
+pub mod attachment;
+pub mod background_job;
+pub mod batch_report;
+pub mod budget;
+pub mod chapter;
+pub mod chapter_length;
+pub mod chapter_lock;
+pub mod context_preview;
+pub mod daily_limit;
+pub mod execution;
+pub mod export;
+pub mod file_open;
+pub mod find_replace;
+pub mod fine_tune;
+pub mod local_server;
+pub mod network;
+pub mod node;
+pub mod note;
+pub mod outline;
+pub mod overview;
+pub mod post_processing;
+pub mod preset;
+pub mod prose_analysis;
+pub mod provider;
+pub mod provider_health;
+pub mod queue;
+pub mod recovery;
+pub mod review;
+pub mod sample;
+pub mod sampling;
+pub mod scene;
+pub mod setting;
+pub mod snapshot;
+pub mod sql_console;
+pub mod structured_output;
+pub mod synopsis;
+pub mod text_format;
+pub mod timeline;
+pub mod token_limit;
+pub mod tts;
+pub mod ui_prefs;
+pub mod watchdog;
+pub mod webhook;
+pub mod workflow;
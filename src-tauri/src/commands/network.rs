@@ -0,0 +1,101 @@
+// 离线模式: 开启后所有对外网络请求 (AI 请求、Webhook、额度查询、TTS、更新检查等) 在发出前被拦截.
+// 新增联网功能须在请求前调用 guard, 前端请求经 ensure_network_allowed 检查
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CommandError;
+
+/// 请求被拦截时发出的事件
+pub const NETWORK_BLOCKED_EVENT: &str = "network:blocked";
+
+// 被拦截的请求, 作为 `network:blocked` 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkBlocked {
+    pub target: String,
+    pub blocked_at: String,
+}
+
+/// 离线模式是否已开启 (默认关闭)
+pub async fn is_offline(pool: &SqlitePool) -> Result<bool, String> {
+    let offline: Option<Option<bool>> =
+        sqlx::query_scalar("SELECT offline_mode FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(offline.flatten().unwrap_or(false))
+}
+
+/// 开启或关闭离线模式
+pub async fn set_offline(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    sqlx::query("UPDATE global_config SET offline_mode = ? WHERE id = 1")
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 检查是否允许访问网络, 离线时返回 OfflineMode 错误
+pub async fn check(pool: &SqlitePool, target: &str) -> Result<(), CommandError> {
+    if is_offline(pool).await? {
+        return Err(CommandError::OfflineMode(target.to_string()));
+    }
+    Ok(())
+}
+
+/// 同 check, 拦截时额外发出 `network:blocked` 事件
+pub async fn guard(app: &AppHandle, pool: &SqlitePool, target: &str) -> Result<(), CommandError> {
+    let result = check(pool, target).await;
+    if result.is_err() {
+        let _ = app.emit(
+            NETWORK_BLOCKED_EVENT,
+            NetworkBlocked {
+                target: target.to_string(),
+                blocked_at: db::now(),
+            },
+        );
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn get_offline_mode(app: AppHandle) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    is_offline(&pool).await
+}
+
+#[tauri::command]
+pub async fn set_offline_mode(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_offline(&pool, enabled).await
+}
+
+/// 前端发起网络请求 (AI 请求、检查更新) 前调用
+#[tauri::command]
+pub async fn ensure_network_allowed(app: AppHandle, target: String) -> Result<(), CommandError> {
+    let pool = db::pool(&app).await?;
+    guard(&app, &pool, &target).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn blocks_requests_while_offline() {
+        let pool = db::test_pool().await;
+        assert!(!is_offline(&pool).await.unwrap());
+        assert!(check(&pool, "webhook").await.is_ok());
+
+        set_offline(&pool, true).await.unwrap();
+        assert!(is_offline(&pool).await.unwrap());
+        let err = check(&pool, "webhook").await.unwrap_err();
+        assert_eq!(err, CommandError::OfflineMode("webhook".to_string()));
+        assert_eq!(err.kind(), "OfflineMode");
+
+        set_offline(&pool, false).await.unwrap();
+        assert!(check(&pool, "webhook").await.is_ok());
+    }
+}
@@ -0,0 +1,668 @@
+// 节点查询与编辑
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{Node, NodeSummary};
+
+// 工作流中的节点分组, 供编辑器渲染可折叠分区
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct NodeGroup {
+    pub label: String,
+    pub color: Option<String>,
+    pub node_count: i64,
+    /// 组内第一个节点的位置, 分组按此排序
+    pub first_order_index: i64,
+}
+
+/// 列出图结构工作流中没有任何连接 (既非起点也非终点) 的节点
+///
+/// 线性工作流没有连接记录, 此时直接返回空列表
+pub async fn list_orphaned(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<Vec<NodeSummary>, String> {
+    let has_connections: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM node_connections WHERE workflow_id = ?)")
+            .bind(workflow_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if !has_connections {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, NodeSummary>(
+        "SELECT id, type, name, order_index FROM nodes n
+         WHERE n.workflow_id = ?
+           AND NOT EXISTS (
+               SELECT 1 FROM node_connections c
+               WHERE c.source_node_id = n.id OR c.target_node_id = n.id
+           )
+         ORDER BY n.order_index",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 设置节点说明, 空字符串表示清除
+pub async fn set_description(
+    pool: &SqlitePool,
+    node_id: &str,
+    description: &str,
+) -> Result<(), String> {
+    let description = description.trim();
+    let description = (!description.is_empty()).then_some(description);
+
+    let result = sqlx::query("UPDATE nodes SET description = ?, updated_at = ? WHERE id = ?")
+        .bind(description)
+        .bind(db::now())
+        .bind(node_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("节点不存在: {}", node_id));
+    }
+    Ok(())
+}
+
+/// 原地复制节点, 插入到原节点 (或整个块) 之后, 返回新节点 ID
+///
+/// 复制控制结构节点时复制整个块: `with_children` 为 true 时包含块内全部节点
+/// (嵌套块的 block_id 重新生成), 否则只复制开始/分支/结束节点得到一个空块
+pub async fn duplicate(
+    pool: &SqlitePool,
+    node_id: &str,
+    with_children: bool,
+) -> Result<Vec<String>, String> {
+    let node = sqlx::query_as::<_, Node>("SELECT * FROM nodes WHERE id = ?")
+        .bind(node_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("节点不存在: {}", node_id))?;
+
+    let sources = match &node.block_id {
+        Some(block_id) => {
+            // 块在线性列表中连续排列, 取开始到结束节点之间的全部节点
+            let (first, last): (i64, i64) = sqlx::query_as(
+                "SELECT MIN(order_index), MAX(order_index) FROM nodes
+                 WHERE workflow_id = ? AND block_id = ?",
+            )
+            .bind(&node.workflow_id)
+            .bind(block_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            let nodes = sqlx::query_as::<_, Node>(
+                "SELECT * FROM nodes WHERE workflow_id = ? AND order_index BETWEEN ? AND ?
+                 ORDER BY order_index",
+            )
+            .bind(&node.workflow_id)
+            .bind(first)
+            .bind(last)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            nodes
+                .into_iter()
+                .filter(|n| with_children || n.block_id.as_deref() == Some(block_id))
+                .collect()
+        }
+        None => vec![node.clone()],
+    };
+    let insert_at = sources
+        .last()
+        .map(|n| n.order_index + 1)
+        .unwrap_or_default();
+
+    // 块内出现的 block_id 全部重新生成, 指向块外的 parent_block_id 保持不变
+    let block_ids: HashMap<&str, String> = sources
+        .iter()
+        .filter_map(|n| n.block_id.as_deref())
+        .map(|id| (id, db::new_id()))
+        .collect();
+    let remap = |id: &Option<String>| -> Option<String> {
+        id.as_deref()
+            .map(|id| block_ids.get(id).cloned().unwrap_or_else(|| id.to_string()))
+    };
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE nodes SET order_index = order_index + ? WHERE workflow_id = ? AND order_index >= ?",
+    )
+    .bind(sources.len() as i64)
+    .bind(&node.workflow_id)
+    .bind(insert_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut new_ids = Vec::with_capacity(sources.len());
+    for (offset, source) in sources.iter().enumerate() {
+        let id = db::new_id();
+        let name = if offset == 0 {
+            format!("{}（副本）", source.name)
+        } else {
+            source.name.clone()
+        };
+        sqlx::query(
+            "INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, description, group_label, group_color, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&source.workflow_id)
+        .bind(&source.node_type)
+        .bind(name)
+        .bind(source.config.to_string())
+        .bind(insert_at + offset as i64)
+        .bind(remap(&source.block_id))
+        .bind(remap(&source.parent_block_id))
+        .bind(&source.description)
+        .bind(&source.group_label)
+        .bind(&source.group_color)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        new_ids.push(id);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(new_ids)
+}
+
+/// 将节点加入分组 (已在其他分组中的节点会移出原分组), 节点须属于同一工作流
+///
+/// 分组以标签区分, 颜色对整个分组生效; 空颜色表示不设颜色
+pub async fn set_group(
+    pool: &SqlitePool,
+    node_ids: &[String],
+    label: &str,
+    color: Option<&str>,
+) -> Result<(), String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("分组标签不能为空".to_string());
+    }
+    if node_ids.is_empty() {
+        return Err("未选择节点".to_string());
+    }
+    let color = color.map(str::trim).filter(|c| !c.is_empty());
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut workflow_id: Option<String> = None;
+    for node_id in node_ids {
+        let node_workflow: String =
+            sqlx::query_scalar("SELECT workflow_id FROM nodes WHERE id = ?")
+                .bind(node_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("节点不存在: {}", node_id))?;
+        if workflow_id.get_or_insert_with(|| node_workflow.clone()) != &node_workflow {
+            return Err("分组中的节点须属于同一工作流".to_string());
+        }
+        sqlx::query(
+            "UPDATE nodes SET group_label = ?, group_color = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(label)
+        .bind(color)
+        .bind(&now)
+        .bind(node_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    sqlx::query(
+        "UPDATE nodes SET group_color = ?, updated_at = ?
+         WHERE workflow_id = ? AND group_label = ? AND group_color IS NOT ?",
+    )
+    .bind(color)
+    .bind(&now)
+    .bind(workflow_id)
+    .bind(label)
+    .bind(color)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 将节点移出所在分组
+pub async fn clear_group(pool: &SqlitePool, node_ids: &[String]) -> Result<(), String> {
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for node_id in node_ids {
+        let result = sqlx::query(
+            "UPDATE nodes SET group_label = NULL, group_color = NULL, updated_at = ? WHERE id = ?",
+        )
+        .bind(&now)
+        .bind(node_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err(format!("节点不存在: {}", node_id));
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 工作流中的分组及成员数量, 按组内第一个节点的位置排序
+pub async fn groups(pool: &SqlitePool, workflow_id: &str) -> Result<Vec<NodeGroup>, String> {
+    sqlx::query_as::<_, NodeGroup>(
+        "SELECT group_label AS label, MAX(group_color) AS color, COUNT(*) AS node_count,
+                MIN(order_index) AS first_order_index
+         FROM nodes WHERE workflow_id = ? AND group_label IS NOT NULL
+         GROUP BY group_label
+         ORDER BY first_order_index",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按类型列出工作流中的节点
+pub async fn list_by_type(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    node_type: &str,
+) -> Result<Vec<Node>, String> {
+    sqlx::query_as::<_, Node>(
+        "SELECT * FROM nodes WHERE workflow_id = ? AND type = ? ORDER BY order_index",
+    )
+    .bind(workflow_id)
+    .bind(node_type)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按名称查找工作流中的节点, 重名时返回排在最前的一个
+pub async fn find_by_name(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    name: &str,
+) -> Result<Option<Node>, String> {
+    sqlx::query_as::<_, Node>(
+        "SELECT * FROM nodes WHERE workflow_id = ? AND name = ? ORDER BY order_index LIMIT 1",
+    )
+    .bind(workflow_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 按 LIKE 模式 (`%` 匹配任意字符串, `_` 匹配单个字符, ASCII 不区分大小写) 匹配节点名称
+pub async fn list_matching_name(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    pattern: &str,
+) -> Result<Vec<Node>, String> {
+    sqlx::query_as::<_, Node>(
+        "SELECT * FROM nodes WHERE workflow_id = ? AND name LIKE ? ORDER BY order_index",
+    )
+    .bind(workflow_id)
+    .bind(pattern)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 统计工作流中各类型节点的数量
+pub async fn count_by_type(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<HashMap<String, i64>, String> {
+    let rows: Vec<(String, i64)> =
+        sqlx::query_as("SELECT type, COUNT(*) FROM nodes WHERE workflow_id = ? GROUP BY type")
+            .bind(workflow_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().collect())
+}
+
+#[tauri::command]
+pub async fn list_orphaned_nodes(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<NodeSummary>, String> {
+    let pool = db::pool(&app).await?;
+    list_orphaned(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn set_node_description(
+    app: AppHandle,
+    node_id: String,
+    description: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_description(&pool, &node_id, &description).await
+}
+
+#[tauri::command]
+pub async fn duplicate_node(
+    app: AppHandle,
+    node_id: String,
+    with_children: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let pool = db::pool(&app).await?;
+    duplicate(&pool, &node_id, with_children.unwrap_or(true)).await
+}
+
+#[tauri::command]
+pub async fn set_node_group(
+    app: AppHandle,
+    node_ids: Vec<String>,
+    label: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_group(&pool, &node_ids, &label, color.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn clear_node_group(app: AppHandle, node_ids: Vec<String>) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    clear_group(&pool, &node_ids).await
+}
+
+#[tauri::command]
+pub async fn get_workflow_groups(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<NodeGroup>, String> {
+    let pool = db::pool(&app).await?;
+    groups(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn get_nodes_by_type(
+    app: AppHandle,
+    workflow_id: String,
+    node_type: String,
+) -> Result<Vec<Node>, String> {
+    let pool = db::pool(&app).await?;
+    list_by_type(&pool, &workflow_id, &node_type).await
+}
+
+#[tauri::command]
+pub async fn get_node_by_name_in_workflow(
+    app: AppHandle,
+    workflow_id: String,
+    name: String,
+) -> Result<Option<Node>, String> {
+    let pool = db::pool(&app).await?;
+    find_by_name(&pool, &workflow_id, &name).await
+}
+
+#[tauri::command]
+pub async fn list_nodes_matching_name_pattern(
+    app: AppHandle,
+    workflow_id: String,
+    pattern: String,
+) -> Result<Vec<Node>, String> {
+    let pool = db::pool(&app).await?;
+    list_matching_name(&pool, &workflow_id, &pattern).await
+}
+
+#[tauri::command]
+pub async fn count_nodes_by_type(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<HashMap<String, i64>, String> {
+    let pool = db::pool(&app).await?;
+    count_by_type(&pool, &workflow_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '图结构'), ('w2', 'p1', '线性');
+            INSERT INTO nodes (id, workflow_id, type, name, order_index) VALUES
+                ('n1', 'w1', 'start', '开始流程', 0),
+                ('n2', 'w1', 'ai_chat', '生成大纲', 1),
+                ('n3', 'w1', 'output', '孤立输出', 2),
+                ('n4', 'w2', 'start', '开始流程', 0);
+            INSERT INTO node_connections (id, workflow_id, source_node_id, target_node_id)
+                VALUES ('c1', 'w1', 'n1', 'n2');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_nodes_without_connections() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let orphaned = list_orphaned(&pool, "w1").await.unwrap();
+        assert_eq!(orphaned.len(), 1);
+        assert_eq!(orphaned[0].id, "n3");
+
+        assert!(list_orphaned(&pool, "w2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn sets_and_clears_description() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        set_description(&pool, "n2", " 根据输入生成三段式大纲 ")
+            .await
+            .unwrap();
+        let node = sqlx::query_as::<_, crate::models::Node>("SELECT * FROM nodes WHERE id = 'n2'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(node.description.as_deref(), Some("根据输入生成三段式大纲"));
+
+        set_description(&pool, "n2", "").await.unwrap();
+        let description: Option<String> =
+            sqlx::query_scalar("SELECT description FROM nodes WHERE id = 'n2'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(description.is_none());
+        assert!(set_description(&pool, "missing", "说明").await.is_err());
+    }
+
+    async fn seed_block(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '章节生成');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id) VALUES
+                ('n1', 'w1', 'start', '开始流程', '{}', 0, NULL, NULL),
+                ('n2', 'w1', 'loop_start', '循环', '{"max_iterations":3}', 1, 'b1', NULL),
+                ('n3', 'w1', 'ai_chat', '撰写正文', '{}', 2, NULL, 'b1'),
+                ('n4', 'w1', 'loop_end', '循环结束', '{}', 3, 'b1', NULL),
+                ('n5', 'w1', 'output', '输出', '{}', 4, NULL, NULL);
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn ordered(pool: &SqlitePool) -> Vec<Node> {
+        sqlx::query_as::<_, Node>(
+            "SELECT * FROM nodes WHERE workflow_id = 'w1' ORDER BY order_index",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn duplicates_single_node_in_place() {
+        let pool = db::test_pool().await;
+        seed_block(&pool).await;
+
+        let ids = duplicate(&pool, "n3", true).await.unwrap();
+        assert_eq!(ids.len(), 1);
+
+        let nodes = ordered(&pool).await;
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "开始流程",
+                "循环",
+                "撰写正文",
+                "撰写正文（副本）",
+                "循环结束",
+                "输出"
+            ]
+        );
+        assert_eq!(nodes[3].parent_block_id.as_deref(), Some("b1"));
+        assert_eq!(
+            nodes.iter().map(|n| n.order_index).collect::<Vec<_>>(),
+            (0..6).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn duplicates_block_with_remapped_ids() {
+        let pool = db::test_pool().await;
+        seed_block(&pool).await;
+
+        let ids = duplicate(&pool, "n4", true).await.unwrap();
+        assert_eq!(ids.len(), 3);
+
+        let nodes = ordered(&pool).await;
+        assert_eq!(nodes.len(), 8);
+        let (start, child, end) = (&nodes[4], &nodes[5], &nodes[6]);
+        assert_eq!(start.name, "循环（副本）");
+        assert_eq!(start.config["max_iterations"], 3);
+        assert_ne!(start.block_id.as_deref(), Some("b1"));
+        assert_eq!(start.block_id, end.block_id);
+        assert_eq!(child.parent_block_id, start.block_id);
+        assert_eq!(nodes[7].id, "n5");
+
+        // 不含子节点时只复制块的结构节点
+        assert_eq!(duplicate(&pool, "n2", false).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn filters_and_counts_by_type() {
+        let pool = db::test_pool().await;
+        seed_block(&pool).await;
+        duplicate(&pool, "n3", true).await.unwrap();
+
+        let chats = list_by_type(&pool, "w1", "ai_chat").await.unwrap();
+        assert_eq!(chats.len(), 2);
+        assert_eq!(chats[0].id, "n3");
+
+        let counts = count_by_type(&pool, "w1").await.unwrap();
+        assert_eq!(counts["ai_chat"], 2);
+        assert_eq!(counts["loop_start"], 1);
+        assert!(!counts.contains_key("condition_if"));
+    }
+
+    #[tokio::test]
+    async fn finds_nodes_by_name() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let found = find_by_name(&pool, "w1", "生成大纲")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, "n2");
+        assert_eq!(
+            find_by_name(&pool, "w2", "开始流程")
+                .await
+                .unwrap()
+                .unwrap()
+                .id,
+            "n4"
+        );
+        assert!(find_by_name(&pool, "w2", "生成大纲")
+            .await
+            .unwrap()
+            .is_none());
+
+        let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(list_matching_name(&pool, "w1", "%流程%").await.unwrap()),
+            ["n1"]
+        );
+        assert_eq!(
+            ids(list_matching_name(&pool, "w1", "__大纲").await.unwrap()),
+            ["n2"]
+        );
+        assert_eq!(
+            ids(list_matching_name(&pool, "w1", "%").await.unwrap()).len(),
+            3
+        );
+        assert!(list_matching_name(&pool, "w1", "大纲")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn groups_nodes_transactionally() {
+        let pool = db::test_pool().await;
+        seed_block(&pool).await;
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        set_group(
+            &pool,
+            &ids(&["n2", "n3", "n4"]),
+            " 循环体 ",
+            Some("#f59e0b"),
+        )
+        .await
+        .unwrap();
+        set_group(&pool, &ids(&["n5"]), "收尾", None).await.unwrap();
+        // 加入已有分组时颜色对整个分组生效
+        set_group(&pool, &ids(&["n1"]), "循环体", Some("#3b82f6"))
+            .await
+            .unwrap();
+        let found = groups(&pool, "w1").await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].label, "循环体");
+        assert_eq!(found[0].color.as_deref(), Some("#3b82f6"));
+        assert_eq!((found[0].node_count, found[0].first_order_index), (4, 0));
+        assert_eq!((found[1].label.as_str(), found[1].node_count), ("收尾", 1));
+
+        // 任一节点无效时整批回滚
+        assert!(set_group(&pool, &ids(&["n5", "missing"]), "新组", None)
+            .await
+            .is_err());
+        assert!(clear_group(&pool, &ids(&["n5", "missing"])).await.is_err());
+        assert_eq!(groups(&pool, "w1").await.unwrap(), found);
+        assert!(set_group(&pool, &ids(&["n5"]), " ", None).await.is_err());
+
+        clear_group(&pool, &ids(&["n1", "n5"])).await.unwrap();
+        let found = groups(&pool, "w1").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].node_count, found[0].first_order_index), (3, 1));
+
+        // 复制节点保留分组
+        duplicate(&pool, "n3", true).await.unwrap();
+        assert_eq!(groups(&pool, "w1").await.unwrap()[0].node_count, 4);
+    }
+}
@@ -0,0 +1,438 @@
+// 项目笔记: 项目级的自由记事 (头脑风暴、大纲等), 与设定库相互独立
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::ProjectNote;
+
+/// 笔记可关联的资源类型
+const LINKABLE_RESOURCES: &[&str] = &["workflow", "node", "setting", "execution"];
+
+/// 搜索结果上限
+const MAX_SEARCH_HITS: i64 = 20;
+
+/// 摘要长度 (字符数)
+const SNIPPET_CHARS: usize = 50;
+
+// 笔记搜索命中
+#[derive(Debug, Serialize)]
+pub struct NoteSearchHit {
+    pub note_id: String,
+    pub title: String,
+    /// 匹配位置前后共约 50 个字符; 仅标题匹配时取内容开头
+    pub snippet: String,
+}
+
+/// 获取单条笔记
+pub async fn get(pool: &SqlitePool, note_id: &str) -> Result<ProjectNote, String> {
+    sqlx::query_as::<_, ProjectNote>("SELECT * FROM project_notes WHERE id = ?")
+        .bind(note_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("笔记不存在: {}", note_id))
+}
+
+/// 列出项目笔记, 最近修改的在前
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<ProjectNote>, String> {
+    sqlx::query_as::<_, ProjectNote>(
+        "SELECT * FROM project_notes WHERE project_id = ? ORDER BY updated_at DESC, rowid DESC",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 创建笔记, 标题去除首尾空白
+pub async fn create(
+    pool: &SqlitePool,
+    project_id: &str,
+    title: &str,
+    content: &str,
+) -> Result<ProjectNote, String> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+
+    let now = db::now();
+    let note = ProjectNote {
+        id: db::new_id(),
+        project_id: project_id.to_string(),
+        title: title.trim().to_string(),
+        content: content.to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO project_notes (id, project_id, title, content, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&note.id)
+    .bind(&note.project_id)
+    .bind(&note.title)
+    .bind(&note.content)
+    .bind(&note.created_at)
+    .bind(&note.updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(note)
+}
+
+/// 更新笔记标题与内容
+pub async fn update(
+    pool: &SqlitePool,
+    note_id: &str,
+    title: &str,
+    content: &str,
+) -> Result<ProjectNote, String> {
+    let mut note = get(pool, note_id).await?;
+    note.title = title.trim().to_string();
+    note.content = content.to_string();
+    note.updated_at = db::now();
+
+    sqlx::query("UPDATE project_notes SET title = ?, content = ?, updated_at = ? WHERE id = ?")
+        .bind(&note.title)
+        .bind(&note.content)
+        .bind(&note.updated_at)
+        .bind(note_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(note)
+}
+
+/// 删除笔记, 关联记录随之删除
+pub async fn delete(pool: &SqlitePool, note_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM note_links WHERE note_id = ?")
+        .bind(note_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let result = sqlx::query("DELETE FROM project_notes WHERE id = ?")
+        .bind(note_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("笔记不存在: {}", note_id));
+    }
+    Ok(())
+}
+
+/// 关联笔记与资源, 重复关联不报错
+pub async fn link(
+    pool: &SqlitePool,
+    note_id: &str,
+    resource_type: &str,
+    resource_id: &str,
+) -> Result<(), String> {
+    if !LINKABLE_RESOURCES.contains(&resource_type) {
+        return Err(format!("不支持关联的资源类型: {}", resource_type));
+    }
+    get(pool, note_id).await?;
+    sqlx::query(
+        "INSERT OR IGNORE INTO note_links (note_id, resource_type, resource_id, created_at)
+         VALUES (?, ?, ?, ?)",
+    )
+    .bind(note_id)
+    .bind(resource_type)
+    .bind(resource_id)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 取消笔记与资源的关联
+pub async fn unlink(pool: &SqlitePool, note_id: &str, resource_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM note_links WHERE note_id = ? AND resource_id = ?")
+        .bind(note_id)
+        .bind(resource_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 列出关联到资源的笔记, 最近修改的在前
+pub async fn list_for_resource(
+    pool: &SqlitePool,
+    resource_type: &str,
+    resource_id: &str,
+) -> Result<Vec<ProjectNote>, String> {
+    sqlx::query_as::<_, ProjectNote>(
+        "SELECT n.* FROM project_notes n
+         JOIN note_links l ON l.note_id = n.id
+         WHERE l.resource_type = ? AND l.resource_id = ?
+         ORDER BY n.updated_at DESC, n.rowid DESC",
+    )
+    .bind(resource_type)
+    .bind(resource_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// 不区分大小写查找 needle 在 haystack 中的字符下标
+fn find_chars(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    (0..=haystack.len() - needle.len()).find(|&start| {
+        haystack[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+    })
+}
+
+/// 截取匹配位置附近的摘要, 匹配内容尽量居中
+pub fn snippet(content: &str, query: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let needle: Vec<char> = query.chars().collect();
+    let start = match find_chars(&chars, &needle) {
+        Some(index) => {
+            let before = SNIPPET_CHARS.saturating_sub(needle.len()) / 2;
+            let end = (index.saturating_sub(before) + SNIPPET_CHARS).min(chars.len());
+            end.saturating_sub(SNIPPET_CHARS)
+        }
+        None => 0,
+    };
+    chars[start..(start + SNIPPET_CHARS).min(chars.len())]
+        .iter()
+        .collect()
+}
+
+/// 按标题与内容搜索项目笔记, 最近修改的在前, 最多返回 20 条
+pub async fn search(
+    pool: &SqlitePool,
+    project_id: &str,
+    query: &str,
+) -> Result<Vec<NoteSearchHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = format!("%{}%", query);
+    let notes = sqlx::query_as::<_, ProjectNote>(
+        "SELECT * FROM project_notes
+         WHERE project_id = ? AND (title LIKE ? OR content LIKE ?)
+         ORDER BY updated_at DESC, rowid DESC LIMIT ?",
+    )
+    .bind(project_id)
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(MAX_SEARCH_HITS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(notes
+        .into_iter()
+        .map(|note| NoteSearchHit {
+            snippet: snippet(&note.content, query),
+            note_id: note.id,
+            title: note.title,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_project_note(
+    app: AppHandle,
+    project_id: String,
+    title: String,
+    content: String,
+) -> Result<ProjectNote, String> {
+    let pool = db::pool(&app).await?;
+    create(&pool, &project_id, &title, &content).await
+}
+
+#[tauri::command]
+pub async fn update_project_note(
+    app: AppHandle,
+    note_id: String,
+    title: String,
+    content: String,
+) -> Result<ProjectNote, String> {
+    let pool = db::pool(&app).await?;
+    update(&pool, &note_id, &title, &content).await
+}
+
+#[tauri::command]
+pub async fn delete_project_note(app: AppHandle, note_id: String) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    delete(&pool, &note_id).await
+}
+
+#[tauri::command]
+pub async fn list_project_notes(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<ProjectNote>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn get_project_note(app: AppHandle, id: String) -> Result<ProjectNote, String> {
+    let pool = db::pool(&app).await?;
+    get(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn search_project_notes(
+    app: AppHandle,
+    project_id: String,
+    query: String,
+) -> Result<Vec<NoteSearchHit>, String> {
+    let pool = db::pool(&app).await?;
+    search(&pool, &project_id, &query).await
+}
+
+#[tauri::command]
+pub async fn link_note_to_resource(
+    app: AppHandle,
+    note_id: String,
+    resource_type: String,
+    resource_id: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    link(&pool, &note_id, &resource_type, &resource_id).await
+}
+
+#[tauri::command]
+pub async fn unlink_note_from_resource(
+    app: AppHandle,
+    note_id: String,
+    resource_id: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    unlink(&pool, &note_id, &resource_id).await
+}
+
+#[tauri::command]
+pub async fn get_notes_for_resource(
+    app: AppHandle,
+    resource_type: String,
+    resource_id: String,
+) -> Result<Vec<ProjectNote>, String> {
+    let pool = db::pool(&app).await?;
+    list_for_resource(&pool, &resource_type, &resource_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn creates_updates_and_deletes_notes() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql("INSERT INTO projects (id, name) VALUES ('p1', '测试项目');")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let outline = create(&pool, "p1", " 大纲 ", "第一卷: 驿站").await.unwrap();
+        assert_eq!(outline.title, "大纲");
+        let ideas = create(&pool, "p1", "灵感", "").await.unwrap();
+        assert!(create(&pool, "missing", "大纲", "").await.is_err());
+
+        // 确保更新时间晚于后创建的笔记
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let updated = update(&pool, &outline.id, "大纲", "第一卷: 驿站\n第二卷: 京城")
+            .await
+            .unwrap();
+        assert_eq!(
+            get(&pool, &outline.id).await.unwrap().content,
+            updated.content
+        );
+
+        let notes = list(&pool, "p1").await.unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].id, outline.id);
+
+        delete(&pool, &ideas.id).await.unwrap();
+        assert!(get(&pool, &ideas.id).await.is_err());
+        assert!(delete(&pool, &ideas.id).await.is_err());
+    }
+
+    #[test]
+    fn centers_snippet_on_match() {
+        let content = format!("{}林雪{}", "前".repeat(60), "后".repeat(60));
+        let snippet = snippet(&content, "林雪");
+        assert_eq!(snippet.chars().count(), 50);
+        assert_eq!(
+            snippet,
+            format!("{}林雪{}", "前".repeat(24), "后".repeat(24))
+        );
+
+        assert_eq!(super::snippet("Hello World", "world"), "Hello World");
+        assert_eq!(super::snippet("短内容", "标题"), "短内容");
+    }
+
+    #[tokio::test]
+    async fn searches_title_and_content() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '其他项目');
+            INSERT INTO project_notes (id, project_id, title, content, updated_at) VALUES
+                ('n1', 'p1', '人物', '林雪是驿站掌柜的女儿', '2026-01-01T00:00:00.000Z'),
+                ('n2', 'p1', '林雪的身世', '待定', '2026-01-02T00:00:00.000Z'),
+                ('n3', 'p1', '大纲', '第一卷', '2026-01-03T00:00:00.000Z'),
+                ('n4', 'p2', '人物', '林雪', '2026-01-04T00:00:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let hits = search(&pool, "p1", "林雪").await.unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.note_id.as_str()).collect();
+        assert_eq!(ids, vec!["n2", "n1"]);
+        assert_eq!(hits[0].snippet, "待定");
+        assert_eq!(hits[1].snippet, "林雪是驿站掌柜的女儿");
+        assert!(search(&pool, "p1", "  ").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn links_notes_to_workflows() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql("INSERT INTO projects (id, name) VALUES ('p1', '测试项目');")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let note = create(&pool, "p1", "第一章备忘", "结尾留悬念")
+            .await
+            .unwrap();
+
+        link(&pool, &note.id, "workflow", "w1").await.unwrap();
+        link(&pool, &note.id, "workflow", "w1").await.unwrap();
+        assert!(link(&pool, &note.id, "project", "p1").await.is_err());
+        assert!(link(&pool, "missing", "workflow", "w1").await.is_err());
+
+        let notes = list_for_resource(&pool, "workflow", "w1").await.unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].id, note.id);
+        assert!(list_for_resource(&pool, "setting", "w1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        unlink(&pool, &note.id, "w1").await.unwrap();
+        assert!(list_for_resource(&pool, "workflow", "w1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+// 大纲导入: 解析 XMind / Workflowy 导出的 OPML, 一级条目作为章节, 下级条目汇总为章节梗概
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::chapter::CHAPTER_STATUSES;
+use crate::db;
+
+/// OPML 文件大小上限
+const MAX_OPML_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 大纲嵌套层数上限
+const MAX_DEPTH: usize = 64;
+
+// OPML 中的大纲条目
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlineItem {
+    pub text: String,
+    pub children: Vec<OutlineItem>,
+}
+
+// 导入预览中的章节: 下级条目按层级缩进汇总为梗概
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineChapter {
+    pub title: String,
+    pub synopsis: Option<String>,
+    pub items: Vec<OutlineItem>,
+}
+
+// OPML 导入结果, 预览时 chapter_ids 为空
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineImport {
+    pub title: Option<String>,
+    pub chapters: Vec<OutlineChapter>,
+    pub chapter_ids: Vec<String>,
+}
+
+fn outline_text(element: &BytesStart) -> Result<String, String> {
+    for attr in element.attributes() {
+        let attr = attr.map_err(|e| format!("OPML 属性格式错误: {}", e))?;
+        if attr.key.as_ref() == b"text" {
+            let value = attr
+                .unescape_value()
+                .map_err(|e| format!("OPML 属性格式错误: {}", e))?;
+            return Ok(value.trim().to_string());
+        }
+    }
+    Ok(String::new())
+}
+
+fn element_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+/// 解析 OPML 文本, 返回文档标题与 body 下的大纲树
+///
+/// 拒绝 DOCTYPE 声明, 且只解析 XML 预定义实体与字符引用, 自定义实体一律报错, 不会发生实体展开.
+pub fn parse_opml(xml: &str) -> Result<(Option<String>, Vec<OutlineItem>), String> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut root_seen = false;
+    let mut body_seen = false;
+    let mut in_body = false;
+    let mut in_title = false;
+    let mut title = String::new();
+    let mut stack: Vec<OutlineItem> = Vec::new();
+    let mut roots: Vec<OutlineItem> = Vec::new();
+
+    fn attach(stack: &mut [OutlineItem], roots: &mut Vec<OutlineItem>, item: OutlineItem) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(item),
+            None => roots.push(item),
+        }
+    }
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("OPML 格式错误 (位置 {}): {}", reader.error_position(), e))?;
+        match event {
+            Event::DocType(_) => return Err("OPML 文件不能包含 DOCTYPE 声明".to_string()),
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let name = element_name(element);
+                let is_empty = matches!(event, Event::Empty(_));
+                if !root_seen {
+                    if name != "opml" {
+                        return Err(format!("不是 OPML 文件: 根元素为 <{}>", name));
+                    }
+                    root_seen = true;
+                    continue;
+                }
+                match name.as_str() {
+                    "body" => {
+                        body_seen = true;
+                        in_body = !is_empty;
+                    }
+                    "title" if !in_body => in_title = !is_empty,
+                    "outline" if in_body => {
+                        let item = OutlineItem {
+                            text: outline_text(element)?,
+                            children: Vec::new(),
+                        };
+                        if is_empty {
+                            attach(&mut stack, &mut roots, item);
+                        } else {
+                            if stack.len() >= MAX_DEPTH {
+                                return Err(format!("大纲嵌套超过 {} 层", MAX_DEPTH));
+                            }
+                            stack.push(item);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref element) => match element.local_name().as_ref() {
+                b"outline" if in_body => {
+                    if let Some(item) = stack.pop() {
+                        attach(&mut stack, &mut roots, item);
+                    }
+                }
+                b"body" => in_body = false,
+                b"title" => in_title = false,
+                _ => {}
+            },
+            Event::Text(text) if in_title => {
+                title.push_str(&text.decode().map_err(|e| e.to_string())?);
+            }
+            Event::GeneralRef(reference) => {
+                let resolved = if reference.is_char_ref() {
+                    reference
+                        .resolve_char_ref()
+                        .map_err(|e| format!("OPML 字符引用无效: {}", e))?
+                        .map(String::from)
+                } else {
+                    let name = reference.decode().map_err(|e| e.to_string())?;
+                    match resolve_predefined_entity(&name) {
+                        Some(value) => Some(value.to_string()),
+                        None => return Err(format!("OPML 包含不支持的实体引用: &{};", name)),
+                    }
+                };
+                if in_title {
+                    title.push_str(&resolved.unwrap_or_default());
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !root_seen {
+        return Err("不是 OPML 文件: 缺少 <opml> 根元素".to_string());
+    }
+    if !body_seen {
+        return Err("OPML 文件缺少 <body>".to_string());
+    }
+    if !stack.is_empty() {
+        return Err("OPML 格式错误: 存在未闭合的 <outline>".to_string());
+    }
+    let title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+    Ok((title, roots))
+}
+
+fn summarize(items: &[OutlineItem], depth: usize, lines: &mut Vec<String>) {
+    for item in items {
+        if !item.text.is_empty() {
+            lines.push(format!("{}- {}", "  ".repeat(depth), item.text));
+        }
+        summarize(&item.children, depth + 1, lines);
+    }
+}
+
+/// 一级条目转为章节, 其下所有条目按层级缩进汇总为梗概
+pub fn to_chapters(items: Vec<OutlineItem>) -> Vec<OutlineChapter> {
+    items
+        .into_iter()
+        .map(|item| {
+            let mut lines = Vec::new();
+            summarize(&item.children, 0, &mut lines);
+            OutlineChapter {
+                title: if item.text.is_empty() {
+                    "未命名章节".to_string()
+                } else {
+                    item.text
+                },
+                synopsis: Some(lines.join("\n")).filter(|s| !s.is_empty()),
+                items: item.children,
+            }
+        })
+        .collect()
+}
+
+/// 导入 OPML 大纲: `dry_run` 为 true 时只返回预览, 否则把章节追加到项目末尾
+pub async fn import_opml(
+    pool: &SqlitePool,
+    project_id: &str,
+    path: &str,
+    dry_run: bool,
+) -> Result<OutlineImport, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("读取文件失败: {}", e))?
+        .len();
+    if size > MAX_OPML_BYTES {
+        return Err(format!(
+            "OPML 文件过大 ({} 字节), 上限为 {} 字节",
+            size, MAX_OPML_BYTES
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let xml = String::from_utf8(bytes).map_err(|_| "OPML 文件不是 UTF-8 编码".to_string())?;
+    let (title, items) = parse_opml(&xml)?;
+    let chapters = to_chapters(items);
+    if chapters.is_empty() {
+        return Err("OPML 中没有大纲条目".to_string());
+    }
+    if dry_run {
+        return Ok(OutlineImport {
+            title,
+            chapters,
+            chapter_ids: Vec::new(),
+        });
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let start: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(order_index) + 1, 0) FROM chapters WHERE project_id = ?",
+    )
+    .bind(project_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let now = db::now();
+    let mut chapter_ids = Vec::with_capacity(chapters.len());
+    for (offset, chapter) in chapters.iter().enumerate() {
+        let id = db::new_id();
+        sqlx::query(
+            "INSERT INTO chapters (id, project_id, title, synopsis, order_index, status, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(project_id)
+        .bind(&chapter.title)
+        .bind(&chapter.synopsis)
+        .bind(start + offset as i64)
+        .bind(CHAPTER_STATUSES[0])
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        chapter_ids.push(id);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(OutlineImport {
+        title,
+        chapters,
+        chapter_ids,
+    })
+}
+
+#[tauri::command]
+pub async fn import_outline_opml(
+    app: AppHandle,
+    project_id: String,
+    path: String,
+    dry_run: bool,
+) -> Result<OutlineImport, String> {
+    let pool = db::pool(&app).await?;
+    import_opml(&pool, &project_id, &path, dry_run).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::chapter;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>青云城 &amp; 玉佩</title></head>
+  <body>
+    <outline text="第一章 雨夜">
+      <outline text="林雪在驿站遇到陌生人">
+        <outline text="陌生人左臂受伤"/>
+      </outline>
+      <outline text="陌生人留下玉佩"/>
+    </outline>
+    <outline text="第二章 &quot;进城&quot;"/>
+  </body>
+</opml>"#;
+
+    #[test]
+    fn maps_top_level_outlines_to_chapters() {
+        let (title, items) = parse_opml(SAMPLE).unwrap();
+        assert_eq!(title.as_deref(), Some("青云城 & 玉佩"));
+        let chapters = to_chapters(items);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "第一章 雨夜");
+        assert_eq!(
+            chapters[0].synopsis.as_deref(),
+            Some("- 林雪在驿站遇到陌生人\n  - 陌生人左臂受伤\n- 陌生人留下玉佩")
+        );
+        assert_eq!(chapters[0].items[0].children[0].text, "陌生人左臂受伤");
+        assert_eq!(chapters[1].title, "第二章 \"进城\"");
+        assert!(chapters[1].synopsis.is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_and_unsafe_documents() {
+        let not_opml = parse_opml("<html><body></body></html>").unwrap_err();
+        assert!(not_opml.contains("不是 OPML 文件"));
+        assert!(parse_opml("纯文本").is_err());
+        assert!(parse_opml(r#"<opml><body><outline text="a"></body></opml>"#).is_err());
+        assert!(parse_opml("<opml><head></head></opml>")
+            .unwrap_err()
+            .contains("<body>"));
+
+        let bomb = r#"<?xml version="1.0"?>
+<!DOCTYPE opml [<!ENTITY a "aaaaaaaaaa"><!ENTITY b "&a;&a;&a;&a;&a;">]>
+<opml><body><outline text="&b;"/></body></opml>"#;
+        assert!(parse_opml(bomb).unwrap_err().contains("DOCTYPE"));
+        let undefined = r#"<opml><body><outline text="&b;"/></body></opml>"#;
+        assert!(parse_opml(undefined).is_err());
+    }
+
+    #[tokio::test]
+    async fn previews_before_importing_chapters() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql("INSERT INTO projects (id, name) VALUES ('p1', '测试项目');")
+            .execute(&pool)
+            .await
+            .unwrap();
+        chapter::create(&pool, "p1", "楔子", None, None)
+            .await
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("outline-{}.opml", db::new_id()));
+        std::fs::write(&path, SAMPLE).unwrap();
+        let path = path.to_str().unwrap();
+
+        let preview = import_opml(&pool, "p1", path, true).await.unwrap();
+        assert_eq!(preview.chapters.len(), 2);
+        assert!(preview.chapter_ids.is_empty());
+        assert_eq!(chapter::list(&pool, "p1").await.unwrap().len(), 1);
+
+        let imported = import_opml(&pool, "p1", path, false).await.unwrap();
+        let chapters = chapter::list(&pool, "p1").await.unwrap();
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[1].id, imported.chapter_ids[0]);
+        assert_eq!(chapters[1].order_index, 1);
+        assert_eq!(chapters[2].title, "第二章 \"进城\"");
+        assert!(import_opml(&pool, "missing", path, false).await.is_err());
+        std::fs::remove_file(path).unwrap();
+
+        assert!(import_opml(&pool, "p1", "/nonexistent/outline.opml", true)
+            .await
+            .is_err());
+    }
+}
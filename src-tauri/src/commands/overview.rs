@@ -0,0 +1,175 @@
+// 跨项目总览: 首页仪表盘一次调用取得全部汇总数据
+//
+// 字数与 token 均来自执行记录 (字数为最终输出的字符数), 日期按 UTC 计算, 周从周一开始.
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+// 本月最活跃的项目
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ActiveProject {
+    pub project_id: String,
+    pub name: String,
+    pub execution_count: i64,
+}
+
+// 跨项目总览
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalOverview {
+    pub project_count: i64,
+    pub workflow_count: i64,
+    pub chapter_count: i64,
+    pub chars_this_week: i64,
+    pub chars_this_month: i64,
+    pub tokens_this_month: i64,
+    pub most_active_project: Option<ActiveProject>,
+    // 截至今天 (今天尚无产出时截至昨天) 连续有产出的天数
+    pub writing_streak_days: i64,
+}
+
+async fn scalar(pool: &SqlitePool, sql: &str, since: Option<NaiveDate>) -> Result<i64, String> {
+    let mut query = sqlx::query_scalar::<_, i64>(sql);
+    if let Some(since) = since {
+        query = query.bind(since.to_string());
+    }
+    query.fetch_one(pool).await.map_err(|e| e.to_string())
+}
+
+/// 由有产出的日期 (倒序) 计算连续写作天数
+pub fn streak(days: &[NaiveDate], today: NaiveDate) -> i64 {
+    let mut expected = match days.first() {
+        Some(day) if *day == today || *day == today - Duration::days(1) => *day,
+        _ => return 0,
+    };
+    let mut count = 0;
+    for day in days {
+        if *day != expected {
+            break;
+        }
+        count += 1;
+        expected -= Duration::days(1);
+    }
+    count
+}
+
+/// 汇总全部项目的统计数据
+pub async fn global_overview(
+    pool: &SqlitePool,
+    today: NaiveDate,
+) -> Result<GlobalOverview, String> {
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap_or(today);
+
+    let project_count = scalar(pool, "SELECT COUNT(*) FROM projects", None).await?;
+    let workflow_count = scalar(pool, "SELECT COUNT(*) FROM workflows", None).await?;
+    let chapter_count = scalar(pool, "SELECT COUNT(*) FROM chapters", None).await?;
+    let chars_since = "SELECT COALESCE(SUM(LENGTH(final_output)), 0) FROM executions
+                       WHERE date(started_at) >= date(?)";
+    let chars_this_week = scalar(pool, chars_since, Some(week_start)).await?;
+    let chars_this_month = scalar(pool, chars_since, Some(month_start)).await?;
+    let tokens_this_month = scalar(
+        pool,
+        "SELECT COALESCE(SUM(json_extract(token_usage, '$.totalTokens')), 0) FROM node_results
+         WHERE token_usage IS NOT NULL AND json_valid(token_usage) AND date(started_at) >= date(?)",
+        Some(month_start),
+    )
+    .await?;
+
+    let most_active_project = sqlx::query_as::<_, ActiveProject>(
+        "SELECT p.id AS project_id, p.name, COUNT(e.id) AS execution_count
+         FROM executions e
+         JOIN workflows w ON w.id = e.workflow_id
+         JOIN projects p ON p.id = w.project_id
+         WHERE date(e.started_at) >= date(?)
+         GROUP BY p.id
+         ORDER BY execution_count DESC, MAX(e.started_at) DESC
+         LIMIT 1",
+    )
+    .bind(month_start.to_string())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let days: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT date(started_at) AS day FROM executions
+         WHERE final_output IS NOT NULL AND final_output != '' AND date(started_at) <= date(?)
+         ORDER BY day DESC",
+    )
+    .bind(today.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let days: Vec<NaiveDate> = days.iter().filter_map(|d| d.parse().ok()).collect();
+
+    Ok(GlobalOverview {
+        project_count,
+        workflow_count,
+        chapter_count,
+        chars_this_week,
+        chars_this_month,
+        tokens_this_month,
+        most_active_project,
+        writing_streak_days: streak(&days, today),
+    })
+}
+
+#[tauri::command]
+pub async fn get_global_overview(app: AppHandle) -> Result<GlobalOverview, String> {
+    let pool = db::pool(&app).await?;
+    global_overview(&pool, Utc::now().date_naive()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn counts_consecutive_writing_days() {
+        let today = day("2026-03-11");
+        let days = [day("2026-03-10"), day("2026-03-09"), day("2026-03-07")];
+        assert_eq!(streak(&days, today), 2);
+        assert_eq!(streak(&[day("2026-03-11"), day("2026-03-10")], today), 2);
+        assert_eq!(streak(&[day("2026-03-08")], today), 0);
+        assert_eq!(streak(&[], today), 0);
+    }
+
+    #[tokio::test]
+    async fn aggregates_across_projects() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '长篇'), ('p2', '短篇');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文'), ('w2', 'p2', '正文');
+            INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                ('e1', 'w1', 'completed', '雨下得很急', '2026-03-11T08:00:00.000Z'),
+                ('e2', 'w1', 'completed', '林雪推门', '2026-03-10T08:00:00.000Z'),
+                ('e3', 'w2', 'completed', '旧稿', '2026-03-02T08:00:00.000Z'),
+                ('e4', 'w2', 'completed', '上月', '2026-02-27T08:00:00.000Z');
+            INSERT INTO node_results (id, execution_id, node_id, status, started_at, token_usage) VALUES
+                ('r1', 'e1', 'n1', 'completed', '2026-03-11T08:00:00.000Z', '{"totalTokens": 120}'),
+                ('r2', 'e4', 'n1', 'completed', '2026-02-27T08:00:00.000Z', '{"totalTokens": 80}');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // 2026-03-11 为周三, 本周从 03-09 开始
+        let overview = global_overview(&pool, day("2026-03-11")).await.unwrap();
+        assert_eq!(overview.project_count, 2);
+        assert_eq!(overview.workflow_count, 2);
+        assert_eq!(overview.chapter_count, 0);
+        assert_eq!(overview.chars_this_week, 9);
+        assert_eq!(overview.chars_this_month, 11);
+        assert_eq!(overview.tokens_this_month, 120);
+        assert_eq!(overview.most_active_project.unwrap().project_id, "p1");
+        assert_eq!(overview.writing_streak_days, 2);
+    }
+}
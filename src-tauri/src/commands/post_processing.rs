@@ -0,0 +1,379 @@
+// 输出后处理: 按工作流配置的规则清理模型输出 (开场白、代码围栏、标点与空行)
+//
+// 规则由前端引擎在节点完成时执行 (src/lib/post-processing.ts), 此处实现保持一致, 用于保存前校验与预览.
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 未配置匹配模式时去除的开场白: 以客套话开头、以冒号结尾的首行
+pub const DEFAULT_META_PATTERNS: &[&str] =
+    &[r"(?:好的|当然|没问题|以下是)[^\n]{0,40}[:：][ \t]*(?:\n|$)"];
+
+// 后处理作用范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessingScope {
+    /// AI 节点的输出
+    NodeOutputs,
+    /// 输出节点的最终输出
+    FinalOutput,
+    #[default]
+    Both,
+}
+
+// 后处理规则, 按列表顺序执行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostProcessingRule {
+    /// 反复去除开头匹配任一模式的开场白, 模式自动锚定到开头
+    StripMetaPrefix {
+        #[serde(default)]
+        patterns: Vec<String>,
+    },
+    /// 去除 Markdown 代码围栏行, 保留其中内容
+    StripCodeFences,
+    /// 将 `...`、`。。。` 与单个 `…` 统一为 `……`
+    NormalizeEllipsis,
+    /// 将每行中成对的直双引号替换为 `“”`
+    NormalizeQuotes,
+    /// 超过两个的连续空行压缩为两个
+    CollapseBlankLines,
+}
+
+impl PostProcessingRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StripMetaPrefix { .. } => "strip_meta_prefix",
+            Self::StripCodeFences => "strip_code_fences",
+            Self::NormalizeEllipsis => "normalize_ellipsis",
+            Self::NormalizeQuotes => "normalize_quotes",
+            Self::CollapseBlankLines => "collapse_blank_lines",
+        }
+    }
+}
+
+// 工作流的后处理配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostProcessingConfig {
+    #[serde(default)]
+    pub scope: PostProcessingScope,
+    #[serde(default)]
+    pub rules: Vec<PostProcessingRule>,
+}
+
+// 实际改动了输出的规则; 替换使输出变长时删除字符数为负
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleApplication {
+    pub rule: String,
+    pub chars_removed: i64,
+}
+
+// 后处理结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PostProcessingResult {
+    pub output: String,
+    pub applied: Vec<RuleApplication>,
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    let patterns: Vec<String> = if patterns.is_empty() {
+        DEFAULT_META_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    } else {
+        patterns.to_vec()
+    };
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(&format!("^(?:{})", p)).map_err(|e| format!("无效的匹配模式 {}: {}", p, e))
+        })
+        .collect()
+}
+
+fn strip_meta_prefix(text: &str, patterns: &[Regex]) -> String {
+    let mut rest = text.trim_start();
+    let mut stripped = false;
+    while let Some(len) = patterns
+        .iter()
+        .filter_map(|re| re.find(rest))
+        .map(|m| m.end())
+        .find(|len| *len > 0)
+    {
+        rest = rest[len..].trim_start();
+        stripped = true;
+    }
+    if stripped {
+        rest.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn is_code_fence(line: &str) -> bool {
+    line.trim().strip_prefix("```").is_some_and(|lang| {
+        lang.chars()
+            .all(|c| c.is_alphanumeric() || "-_+".contains(c))
+    })
+}
+
+fn normalize_quotes(text: &str) -> String {
+    let mut open = true;
+    text.chars()
+        .map(|c| match c {
+            '"' => {
+                open = !open;
+                if open {
+                    '”'
+                } else {
+                    '“'
+                }
+            }
+            '\n' => {
+                open = true;
+                c
+            }
+            _ => c,
+        })
+        .collect()
+}
+
+fn apply_rule(rule: &PostProcessingRule, text: &str) -> Result<String, String> {
+    Ok(match rule {
+        PostProcessingRule::StripMetaPrefix { patterns } => {
+            strip_meta_prefix(text, &compile_patterns(patterns)?)
+        }
+        PostProcessingRule::StripCodeFences => text
+            .split('\n')
+            .filter(|line| !is_code_fence(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        PostProcessingRule::NormalizeEllipsis => Regex::new(r"\.{3,}|。{3,}|…+")
+            .expect("省略号模式有效")
+            .replace_all(text, "……")
+            .into_owned(),
+        PostProcessingRule::NormalizeQuotes => normalize_quotes(text),
+        PostProcessingRule::CollapseBlankLines => Regex::new(r"\n(?:[ \t]*\n){3,}")
+            .expect("空行模式有效")
+            .replace_all(text, "\n\n\n")
+            .into_owned(),
+    })
+}
+
+/// 校验配置中的匹配模式
+pub fn validate(config: &PostProcessingConfig) -> Result<(), String> {
+    for rule in &config.rules {
+        if let PostProcessingRule::StripMetaPrefix { patterns } = rule {
+            compile_patterns(patterns)?;
+        }
+    }
+    Ok(())
+}
+
+/// 按顺序执行全部规则, 记录改动了输出的规则
+pub fn apply(config: &PostProcessingConfig, text: &str) -> Result<PostProcessingResult, String> {
+    let mut output = text.to_string();
+    let mut applied = Vec::new();
+    for rule in &config.rules {
+        let next = apply_rule(rule, &output)?;
+        if next != output {
+            applied.push(RuleApplication {
+                rule: rule.name().to_string(),
+                chars_removed: output.chars().count() as i64 - next.chars().count() as i64,
+            });
+            output = next;
+        }
+    }
+    Ok(PostProcessingResult { output, applied })
+}
+
+/// 读取工作流的后处理配置, 未配置时返回 None
+pub async fn load(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<Option<PostProcessingConfig>, String> {
+    let raw: Option<Option<String>> =
+        sqlx::query_scalar("SELECT post_processing FROM workflows WHERE id = ?")
+            .bind(workflow_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    match raw.ok_or_else(|| format!("工作流不存在: {}", workflow_id))? {
+        Some(raw) => serde_json::from_str(&raw)
+            .map(Some)
+            .map_err(|e| format!("后处理配置格式错误: {}", e)),
+        None => Ok(None),
+    }
+}
+
+/// 保存工作流的后处理配置; 没有规则时清空
+pub async fn save(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    config: Option<&PostProcessingConfig>,
+) -> Result<(), String> {
+    let config = config.filter(|c| !c.rules.is_empty());
+    let raw = match config {
+        Some(config) => {
+            validate(config)?;
+            Some(serde_json::to_string(config).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    let result =
+        sqlx::query("UPDATE workflows SET post_processing = ?, updated_at = ? WHERE id = ?")
+            .bind(raw)
+            .bind(db::now())
+            .bind(workflow_id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+    Ok(())
+}
+
+/// 预览后处理效果: 传入配置时使用未保存的配置, 否则使用已保存的; 预览忽略作用范围
+pub async fn preview(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    sample_text: &str,
+    config: Option<PostProcessingConfig>,
+) -> Result<PostProcessingResult, String> {
+    let config = match config {
+        Some(config) => config,
+        None => load(pool, workflow_id).await?.unwrap_or_default(),
+    };
+    apply(&config, sample_text)
+}
+
+#[tauri::command]
+pub async fn get_workflow_post_processing(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Option<PostProcessingConfig>, String> {
+    let pool = db::pool(&app).await?;
+    load(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn set_workflow_post_processing(
+    app: AppHandle,
+    workflow_id: String,
+    config: Option<PostProcessingConfig>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    save(&pool, &workflow_id, config.as_ref()).await
+}
+
+#[tauri::command]
+pub async fn preview_post_processing(
+    app: AppHandle,
+    workflow_id: String,
+    sample_text: String,
+    config: Option<PostProcessingConfig>,
+) -> Result<PostProcessingResult, String> {
+    let pool = db::pool(&app).await?;
+    preview(&pool, &workflow_id, &sample_text, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(rules: Vec<PostProcessingRule>) -> PostProcessingConfig {
+        PostProcessingConfig {
+            scope: PostProcessingScope::Both,
+            rules,
+        }
+    }
+
+    #[test]
+    fn strips_meta_phrases_and_normalizes_text() {
+        let config = config(vec![
+            PostProcessingRule::StripMetaPrefix { patterns: vec![] },
+            PostProcessingRule::StripCodeFences,
+            PostProcessingRule::NormalizeEllipsis,
+            PostProcessingRule::NormalizeQuotes,
+            PostProcessingRule::CollapseBlankLines,
+        ]);
+        let text = "好的，以下是润色后的文本：\n```markdown\n\"雨下得很急...\"\n\n\n\n\n林雪推开门。。。\n```";
+        let result = apply(&config, text).unwrap();
+        assert_eq!(result.output, "“雨下得很急……”\n\n\n林雪推开门……");
+        let fired: Vec<&str> = result.applied.iter().map(|a| a.rule.as_str()).collect();
+        assert_eq!(
+            fired,
+            vec![
+                "strip_meta_prefix",
+                "strip_code_fences",
+                "normalize_ellipsis",
+                "normalize_quotes",
+                "collapse_blank_lines"
+            ]
+        );
+        assert_eq!(result.applied[0].chars_removed, 14);
+        assert_eq!(result.applied[2].chars_removed, 2);
+        assert_eq!(result.applied[3].chars_removed, 0);
+    }
+
+    #[test]
+    fn keeps_dialogue_and_unmatched_text() {
+        let config = config(vec![
+            PostProcessingRule::StripMetaPrefix { patterns: vec![] },
+            PostProcessingRule::NormalizeEllipsis,
+        ]);
+        // 冒号不在行尾, 不视为开场白
+        let text = "当然，他说：“走吧。”";
+        let result = apply(&config, text).unwrap();
+        assert_eq!(result.output, text);
+        assert!(result.applied.is_empty());
+
+        let custom = self::config(vec![PostProcessingRule::StripMetaPrefix {
+            patterns: vec!["Sure[^\n]*\n".to_string(), "(".to_string()],
+        }]);
+        assert!(validate(&custom).is_err());
+    }
+
+    #[tokio::test]
+    async fn saves_and_previews_workflow_rules() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(load(&pool, "w1").await.unwrap().is_none());
+        let saved = PostProcessingConfig {
+            scope: PostProcessingScope::FinalOutput,
+            rules: vec![PostProcessingRule::StripMetaPrefix {
+                patterns: vec![r"Sure[^\n]*\n".to_string()],
+            }],
+        };
+        save(&pool, "w1", Some(&saved)).await.unwrap();
+        assert_eq!(load(&pool, "w1").await.unwrap(), Some(saved));
+
+        let result = preview(&pool, "w1", "Sure, here it is:\n雨夜", None)
+            .await
+            .unwrap();
+        assert_eq!(result.output, "雨夜");
+        // 未保存的配置优先
+        let draft = config(vec![PostProcessingRule::NormalizeEllipsis]);
+        let result = preview(&pool, "w1", "Sure...", Some(draft)).await.unwrap();
+        assert_eq!(result.output, "Sure……");
+
+        save(&pool, "w1", Some(&config(vec![]))).await.unwrap();
+        assert!(load(&pool, "w1").await.unwrap().is_none());
+        assert!(save(&pool, "missing", None).await.is_err());
+        assert!(preview(&pool, "missing", "", None).await.is_err());
+    }
+}
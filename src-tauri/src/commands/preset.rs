@@ -0,0 +1,305 @@
+// 执行输入预设: 保存工作流常用的输入文本, 执行时可直接引用预设
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::InputPreset;
+
+/// 获取单个预设
+pub async fn get(pool: &SqlitePool, preset_id: &str) -> Result<InputPreset, String> {
+    sqlx::query_as::<_, InputPreset>("SELECT * FROM input_presets WHERE id = ?")
+        .bind(preset_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("输入预设不存在: {}", preset_id))
+}
+
+/// 列出工作流的预设, 默认预设在前
+pub async fn list(pool: &SqlitePool, workflow_id: &str) -> Result<Vec<InputPreset>, String> {
+    sqlx::query_as::<_, InputPreset>(
+        "SELECT * FROM input_presets WHERE workflow_id = ?
+         ORDER BY is_default DESC, created_at, rowid",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// 设为默认时取消同一工作流中其他预设的默认标记
+async fn clear_default(
+    tx: &mut Transaction<'_, Sqlite>,
+    workflow_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE input_presets SET is_default = 0 WHERE workflow_id = ?")
+        .bind(workflow_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// 在事务中写入预设, 供创建与工作流导入、复制共用
+pub async fn insert(
+    tx: &mut Transaction<'_, Sqlite>,
+    workflow_id: &str,
+    name: &str,
+    content: &str,
+    is_default: bool,
+) -> Result<InputPreset, sqlx::Error> {
+    if is_default {
+        clear_default(tx, workflow_id).await?;
+    }
+    let preset = InputPreset {
+        id: db::new_id(),
+        workflow_id: workflow_id.to_string(),
+        name: name.trim().to_string(),
+        content: content.to_string(),
+        is_default,
+        created_at: db::now(),
+    };
+    sqlx::query(
+        "INSERT INTO input_presets (id, workflow_id, name, content, is_default, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&preset.id)
+    .bind(&preset.workflow_id)
+    .bind(&preset.name)
+    .bind(&preset.content)
+    .bind(preset.is_default)
+    .bind(&preset.created_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(preset)
+}
+
+/// 创建预设, 名称不能为空
+pub async fn create(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    name: &str,
+    content: &str,
+    is_default: bool,
+) -> Result<InputPreset, String> {
+    if name.trim().is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM workflows WHERE id = ?)")
+        .bind(workflow_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let preset = insert(&mut tx, workflow_id, name, content, is_default)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(preset)
+}
+
+/// 更新预设名称、内容与默认标记
+pub async fn update(
+    pool: &SqlitePool,
+    preset_id: &str,
+    name: &str,
+    content: &str,
+    is_default: bool,
+) -> Result<InputPreset, String> {
+    if name.trim().is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+    let mut preset = get(pool, preset_id).await?;
+    preset.name = name.trim().to_string();
+    preset.content = content.to_string();
+    preset.is_default = is_default;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    if is_default {
+        clear_default(&mut tx, &preset.workflow_id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    sqlx::query("UPDATE input_presets SET name = ?, content = ?, is_default = ? WHERE id = ?")
+        .bind(&preset.name)
+        .bind(&preset.content)
+        .bind(preset.is_default)
+        .bind(preset_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(preset)
+}
+
+/// 删除预设
+pub async fn delete(pool: &SqlitePool, preset_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM input_presets WHERE id = ?")
+        .bind(preset_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 工作流默认预设的内容, 未设置默认预设时返回 None
+pub async fn default_input(pool: &SqlitePool, workflow_id: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar(
+        "SELECT content FROM input_presets WHERE workflow_id = ? AND is_default = 1 LIMIT 1",
+    )
+    .bind(workflow_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 确定执行实际使用的输入: 指定预设时使用预设内容 (预设须属于该工作流), 否则使用原始输入
+pub async fn resolve_input(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    input: Option<&str>,
+    preset_id: Option<&str>,
+) -> Result<Option<String>, String> {
+    let Some(preset_id) = preset_id else {
+        return Ok(input.map(str::to_string));
+    };
+    let preset = get(pool, preset_id).await?;
+    if preset.workflow_id != workflow_id {
+        return Err(format!("输入预设不属于该工作流: {}", preset_id));
+    }
+    Ok(Some(preset.content))
+}
+
+#[tauri::command]
+pub async fn create_input_preset(
+    app: AppHandle,
+    workflow_id: String,
+    name: String,
+    content: String,
+    is_default: bool,
+) -> Result<InputPreset, String> {
+    let pool = db::pool(&app).await?;
+    create(&pool, &workflow_id, &name, &content, is_default).await
+}
+
+#[tauri::command]
+pub async fn update_input_preset(
+    app: AppHandle,
+    preset_id: String,
+    name: String,
+    content: String,
+    is_default: bool,
+) -> Result<InputPreset, String> {
+    let pool = db::pool(&app).await?;
+    update(&pool, &preset_id, &name, &content, is_default).await
+}
+
+#[tauri::command]
+pub async fn delete_input_preset(app: AppHandle, preset_id: String) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    delete(&pool, &preset_id).await
+}
+
+#[tauri::command]
+pub async fn list_input_presets(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<InputPreset>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn get_default_input(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Option<String>, String> {
+    let pool = db::pool(&app).await?;
+    default_input(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn resolve_execution_input(
+    app: AppHandle,
+    workflow_id: String,
+    input: Option<String>,
+    preset_id: Option<String>,
+) -> Result<Option<String>, String> {
+    let pool = db::pool(&app).await?;
+    resolve_input(&pool, &workflow_id, input.as_deref(), preset_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p1', '润色');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_single_default_preset() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let style = create(&pool, "w1", "文风", "古风, 白描", true)
+            .await
+            .unwrap();
+        let pacing = create(&pool, "w1", "节奏", "快节奏", true).await.unwrap();
+        assert!(create(&pool, "w1", " ", "", false).await.is_err());
+        assert!(create(&pool, "missing", "文风", "", false).await.is_err());
+
+        assert_eq!(
+            default_input(&pool, "w1").await.unwrap().as_deref(),
+            Some("快节奏")
+        );
+        update(&pool, &style.id, "文风", "古风", true)
+            .await
+            .unwrap();
+        let presets = list(&pool, "w1").await.unwrap();
+        assert_eq!(presets[0].id, style.id);
+        assert!(!presets[1].is_default);
+
+        delete(&pool, &pacing.id).await.unwrap();
+        assert_eq!(list(&pool, "w1").await.unwrap().len(), 1);
+        assert!(default_input(&pool, "w2").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn resolves_preset_or_raw_input() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let preset = create(&pool, "w1", "文风", "古风, 白描", false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            resolve_input(&pool, "w1", Some("原始输入"), None)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("原始输入")
+        );
+        assert_eq!(
+            resolve_input(&pool, "w1", Some("原始输入"), Some(&preset.id))
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("古风, 白描")
+        );
+        assert!(resolve_input(&pool, "w2", None, Some(&preset.id))
+            .await
+            .is_err());
+    }
+}
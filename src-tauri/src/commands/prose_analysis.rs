@@ -0,0 +1,429 @@
+// 文本分析: 编辑工具面板与输出分析界面使用的启发式检查, 只做本地文本处理, 不调用 AI
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 支持的叙述视角
+pub const POV_MODES: &[&str] = &["first_person", "third_limited", "omniscient"];
+
+/// 叙述中的第一人称 (对白已去除)
+const FIRST_PERSON_PATTERN: &str =
+    r"\b(?:I|[Ww]e|[Mm]e|[Uu]s|[Mm]y|[Oo]ur|[Mm]ine|[Oo]urs|[Mm]yself|[Oo]urselves)\b|我";
+
+/// 直接描写某人内心的句式, 第一个非空捕获组为被描写的人物
+const THOUGHT_PATTERN: &str = r"\b([Hh]e|[Ss]he|[Tt]hey|[A-Z][a-z]+)\s+(?:thought|wondered|felt|knew|realized|realised|wished|feared|hoped)\b|(他们|她们|他|她)(?:心想|暗想|心里想|心中暗想|想道|暗自思忖)";
+
+/// 超过该词数的句子计为长句
+const LONG_SENTENCE_WORDS: usize = 25;
+
+/// 被动语态: be 动词 + (可选副词) + 过去分词, 或中文 "被"
+const PASSIVE_PATTERN: &str =
+    r"(?i)\b(?:am|is|are|was|were|be|been|being)\s+(?:\w+ly\s+)?\w+(?:ed|en)\b|被";
+
+/// 词频统计时可排除的常用词 (英文虚词与常见汉字虚词)
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "if", "of", "to", "in", "on", "at", "by", "for", "with",
+    "from", "as", "is", "are", "was", "were", "be", "been", "it", "its", "this", "that", "he",
+    "she", "they", "i", "you", "we", "his", "her", "their", "my", "your", "him", "them", "me",
+    "not", "no", "so", "had", "has", "have", "do", "did", "s", "t", "的", "了", "是", "在", "着",
+    "和", "也", "就", "都", "而", "与", "之", "一", "不", "这", "那", "他", "她", "我", "你", "它",
+    "们", "个", "地", "得", "上", "中", "里", "说",
+];
+
+// 视角问题
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PovViolation {
+    /// 句子序号, 从 0 开始
+    pub sentence_index: i64,
+    pub sentence: String,
+    pub reason: String,
+}
+
+/// 按句末标点 (`。！？.!?`) 与换行切分句子, 句末紧跟的右引号归入前一句
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            sentences.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+        if matches!(c, '。' | '！' | '？' | '.' | '!' | '?') {
+            while let Some(&next) = chars.peek() {
+                if !matches!(
+                    next,
+                    '”' | '」' | '"' | '\'' | '’' | '。' | '！' | '？' | '.' | '!' | '?'
+                ) {
+                    break;
+                }
+                current.push(next);
+                chars.next();
+            }
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+// 去除引号中的对白, 只保留叙述部分
+fn narration(sentence: &str) -> String {
+    let mut result = String::new();
+    let mut closing: Option<char> = None;
+    for c in sentence.chars() {
+        match closing {
+            Some(end) if c == end => closing = None,
+            Some(_) => {}
+            None => match c {
+                '“' => closing = Some('”'),
+                '「' => closing = Some('」'),
+                '"' => closing = Some('"'),
+                _ => result.push(c),
+            },
+        }
+    }
+    result
+}
+
+/// 检查文本是否符合预期的叙述视角 (启发式):
+/// - 第三人称 (限知/全知) 的叙述中出现第一人称
+/// - 第一人称叙述直接描写他人内心
+/// - 第三人称限知中内心描写的人物发生切换 (视角跳跃), 以第一个被描写内心的人物为视角人物
+pub fn check_pov(text: &str, expected_pov: &str) -> Result<Vec<PovViolation>, String> {
+    if !POV_MODES.contains(&expected_pov) {
+        return Err(format!("不支持的叙述视角: {}", expected_pov));
+    }
+    let first_person = Regex::new(FIRST_PERSON_PATTERN).map_err(|e| e.to_string())?;
+    let thought = Regex::new(THOUGHT_PATTERN).map_err(|e| e.to_string())?;
+
+    let mut violations = Vec::new();
+    let mut viewpoint: Option<String> = None;
+    for (index, sentence) in split_sentences(text).into_iter().enumerate() {
+        let narration = narration(&sentence);
+        let thinker = thought.captures(&narration).and_then(|captures| {
+            captures
+                .get(1)
+                .or_else(|| captures.get(2))
+                .map(|m| m.as_str().to_lowercase())
+        });
+        let reason = match expected_pov {
+            "first_person" => thinker.map(|t| format!("第一人称叙述直接描写了 {} 的内心", t)),
+            _ if first_person.is_match(&narration) => {
+                Some("第三人称叙述中出现了第一人称".to_string())
+            }
+            "third_limited" => match thinker {
+                Some(t) if viewpoint.is_none() => {
+                    viewpoint = Some(t);
+                    None
+                }
+                Some(t) if viewpoint.as_deref() != Some(t.as_str()) => Some(format!(
+                    "视角跳跃: 描写了 {} 的内心, 视角人物为 {}",
+                    t,
+                    viewpoint.as_deref().unwrap_or_default()
+                )),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            violations.push(PovViolation {
+                sentence_index: index as i64,
+                sentence,
+                reason,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+// 可读性指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReadabilityReport {
+    pub flesch_kincaid_grade: f64,
+    /// 每句平均词数
+    pub avg_sentence_length: f64,
+    /// 每词平均字符数
+    pub avg_word_length: f64,
+    pub long_sentence_count: i64,
+    pub passive_voice_count: i64,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}')
+}
+
+/// 切分单词: 英文按字母/数字/撇号连续片段, 每个汉字单独计为一个词
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if !is_cjk(c) && (c.is_alphanumeric() || (c == '\'' && !current.is_empty())) {
+            current.push(c);
+            continue;
+        }
+        if !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if is_cjk(c) {
+            words.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// 估算音节数: 英文按元音组计数, 词尾不发音的 e 不计, 每词至少一个音节; 汉字为一个音节
+fn syllables(word: &str) -> usize {
+    if word.chars().any(is_cjk) {
+        return word.chars().count();
+    }
+    let word = word.to_lowercase();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// 计算可读性指标: Flesch-Kincaid 年级 = 0.39 × 每句词数 + 11.8 × 每词音节数 − 15.59.
+/// 句子按句末标点切分, 文本中没有单词时报错
+pub fn readability(text: &str) -> Result<ReadabilityReport, String> {
+    let sentences: Vec<Vec<String>> = split_sentences(text)
+        .iter()
+        .map(|sentence| words(sentence))
+        .filter(|words| !words.is_empty())
+        .collect();
+    let word_count: usize = sentences.iter().map(Vec::len).sum();
+    if word_count == 0 {
+        return Err("文本中没有可分析的单词".to_string());
+    }
+    let syllable_count: usize = sentences.iter().flatten().map(|w| syllables(w)).sum();
+    let char_count: usize = sentences.iter().flatten().map(|w| w.chars().count()).sum();
+    let passive = Regex::new(PASSIVE_PATTERN).map_err(|e| e.to_string())?;
+
+    let words_per_sentence = word_count as f64 / sentences.len() as f64;
+    let syllables_per_word = syllable_count as f64 / word_count as f64;
+    Ok(ReadabilityReport {
+        flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+        avg_sentence_length: words_per_sentence,
+        avg_word_length: char_count as f64 / word_count as f64,
+        long_sentence_count: sentences
+            .iter()
+            .filter(|words| words.len() > LONG_SENTENCE_WORDS)
+            .count() as i64,
+        passive_voice_count: passive.find_iter(text).count() as i64,
+    })
+}
+
+// 词频
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordFrequency {
+    pub word: String,
+    pub count: i64,
+    /// 每千词出现次数 (按全部词数计算, 含被排除的常用词)
+    pub frequency_per_1000: f64,
+}
+
+/// 统计出现最多的词: 英文词转为小写, 每个汉字计为一个词; 次数相同时按词排序
+pub fn repetitive_words(
+    text: &str,
+    top_n: i64,
+    exclude_common: bool,
+) -> Result<Vec<WordFrequency>, String> {
+    if top_n <= 0 {
+        return Err("top_n 必须大于 0".to_string());
+    }
+    let words = words(text);
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for word in &words {
+        let word = word.to_lowercase();
+        if exclude_common && STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut frequencies: Vec<WordFrequency> = counts
+        .into_iter()
+        .map(|(word, count)| WordFrequency {
+            word,
+            count,
+            frequency_per_1000: count as f64 * 1000.0 / words.len() as f64,
+        })
+        .collect();
+    frequencies.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    frequencies.truncate(top_n as usize);
+    Ok(frequencies)
+}
+
+async fn final_output(pool: &SqlitePool, execution_id: &str) -> Result<String, String> {
+    let output: Option<Option<String>> =
+        sqlx::query_scalar("SELECT final_output FROM executions WHERE id = ?")
+            .bind(execution_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    output
+        .ok_or_else(|| format!("执行记录不存在: {}", execution_id))?
+        .ok_or_else(|| "执行记录没有最终输出".to_string())
+}
+
+/// 检查执行最终输出的叙述视角
+pub async fn detect_pov(
+    pool: &SqlitePool,
+    execution_id: &str,
+    expected_pov: &str,
+) -> Result<Vec<PovViolation>, String> {
+    check_pov(&final_output(pool, execution_id).await?, expected_pov)
+}
+
+#[tauri::command]
+pub async fn detect_pov_violations(
+    app: AppHandle,
+    execution_id: String,
+    expected_pov: String,
+) -> Result<Vec<PovViolation>, String> {
+    let pool = db::pool(&app).await?;
+    detect_pov(&pool, &execution_id, &expected_pov).await
+}
+
+#[tauri::command]
+pub fn compute_readability_score(text: String) -> Result<ReadabilityReport, String> {
+    readability(&text)
+}
+
+#[tauri::command]
+pub fn detect_repetitive_words(
+    text: String,
+    top_n: i64,
+    exclude_common: bool,
+) -> Result<Vec<WordFrequency>, String> {
+    repetitive_words(&text, top_n, exclude_common)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sentences_at_terminal_punctuation() {
+        assert_eq!(
+            split_sentences("雨夜。“你来了？”她问。\nHe left. Then silence"),
+            ["雨夜。", "“你来了？”", "她问。", "He left.", "Then silence"]
+        );
+    }
+
+    #[test]
+    fn detects_pov_violations_by_mode() {
+        let text = "She opened the door. “I know,” she said. She thought of the sea. \
+                    He wondered why she came. I watched them leave.";
+        let limited = check_pov(text, "third_limited").unwrap();
+        let reasons: Vec<(i64, &str)> = limited
+            .iter()
+            .map(|v| (v.sentence_index, v.reason.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                (3, "视角跳跃: 描写了 he 的内心, 视角人物为 she"),
+                (4, "第三人称叙述中出现了第一人称"),
+            ]
+        );
+        assert_eq!(limited[0].sentence, "He wondered why she came.");
+
+        let omniscient = check_pov(text, "omniscient").unwrap();
+        assert_eq!(omniscient.len(), 1);
+        assert_eq!(omniscient[0].sentence_index, 4);
+
+        let first = check_pov("我推开门。她心想, 他终于来了。我笑了。", "first_person").unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].sentence_index, 1);
+
+        assert!(check_pov(text, "second_person").is_err());
+    }
+
+    #[test]
+    fn computes_flesch_kincaid_grade() {
+        assert_eq!(syllables("the"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("reading"), 2);
+        assert_eq!(syllables("rhythm"), 1);
+
+        // 2 句 6 词 6 音节: 0.39 × 3 + 11.8 × 1 − 15.59 = −2.62
+        let report = readability("The cat sat. The dog ran!").unwrap();
+        assert!((report.flesch_kincaid_grade - -2.62).abs() < 1e-9);
+        assert_eq!(report.avg_sentence_length, 3.0);
+        assert_eq!(report.avg_word_length, 3.0);
+        assert_eq!(report.long_sentence_count, 0);
+        assert_eq!(report.passive_voice_count, 0);
+
+        let passive = readability("The letter was quickly written. 玉佩被偷走了。").unwrap();
+        assert_eq!(passive.passive_voice_count, 2);
+        let long = readability(&"word ".repeat(30)).unwrap();
+        assert_eq!(long.long_sentence_count, 1);
+        assert!(readability("…… !").is_err());
+    }
+
+    #[test]
+    fn counts_most_frequent_words() {
+        let text = "The rain fell. The rain, the RAIN! Night fell.";
+        let top = repetitive_words(text, 2, false).unwrap();
+        let counts: Vec<(&str, i64)> = top.iter().map(|f| (f.word.as_str(), f.count)).collect();
+        assert_eq!(counts, [("rain", 3), ("the", 3)]);
+        // 共 9 个词, 3 / 9 × 1000
+        assert!((top[0].frequency_per_1000 - 333.333).abs() < 1e-3);
+
+        let filtered = repetitive_words(text, 2, true).unwrap();
+        let counts: Vec<(&str, i64)> = filtered
+            .iter()
+            .map(|f| (f.word.as_str(), f.count))
+            .collect();
+        assert_eq!(counts, [("rain", 3), ("fell", 2)]);
+
+        let chinese = repetitive_words("雨的声音, 雨的影子", 1, true).unwrap();
+        assert_eq!(chinese[0].word, "雨");
+        assert_eq!(chinese[0].count, 2);
+        assert!(repetitive_words(text, 0, false).is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_execution_final_output() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '雾城');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                 ('e1', 'w1', 'completed', '她推开门。我听见了雨声。', '2026-01-01T00:00:00.000Z'),
+                 ('e2', 'w1', 'running', NULL, '2026-01-01T00:00:00.000Z');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let violations = detect_pov(&pool, "e1", "third_limited").await.unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].sentence, "我听见了雨声。");
+        assert!(detect_pov(&pool, "e2", "third_limited").await.is_err());
+        assert!(detect_pov(&pool, "missing", "third_limited").await.is_err());
+    }
+}
@@ -0,0 +1,439 @@
+// AI 提供商配置: 展示时只返回遮盖后的 API Key, 完整 Key 需显式调用获取
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::execution::token_cost;
+use crate::commands::network;
+use crate::db;
+use crate::error::CommandError;
+
+// 遮盖 Key 后的提供商配置, 其余字段 (enabled、base_url、模型列表等) 原样返回
+#[derive(Debug, Serialize)]
+pub struct MaskedProvider {
+    pub id: String,
+    pub has_key: bool,
+    pub masked_key: String,
+    #[serde(flatten)]
+    pub config: Map<String, Value>,
+}
+
+/// 估算批量执行花费时参考的最近完成执行数
+const COST_HISTORY_RUNS: i64 = 10;
+
+/// 额度不足时发出的事件
+pub const QUOTA_INSUFFICIENT_EVENT: &str = "provider:quota_insufficient";
+
+/// 未配置 `quota_path` 时依次尝试的余额字段
+const DEFAULT_QUOTA_PATHS: &[&str] = &[
+    "remaining",
+    "balance",
+    "total_available",
+    "data.remaining",
+    "data.balance",
+    "balance_infos.0.total_balance",
+];
+
+// 提供商剩余额度; 未配置额度接口时 status 为 "unknown"
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderQuota {
+    pub provider_id: String,
+    pub status: String,
+    pub remaining: Option<f64>,
+    pub checked_at: String,
+}
+
+// 批量执行前的额度检查结果, 额度未知时 sufficient 为 None
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaPreflight {
+    pub quota: ProviderQuota,
+    pub estimated_cost: f64,
+    pub sufficient: Option<bool>,
+}
+
+/// 读取全局配置中的提供商配置
+pub async fn load_providers(pool: &SqlitePool) -> Result<Map<String, Value>, String> {
+    let raw: Option<String> =
+        sqlx::query_scalar("SELECT ai_providers FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    match raw {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("提供商配置格式错误: {}", e)),
+        None => Ok(Map::new()),
+    }
+}
+
+/// 遮盖 API Key: 保留形如 `sk-` 的前缀与末尾 4 位, 过短的 Key 不显示任何字符
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.trim().chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    if chars.len() <= 8 {
+        return "…".to_string();
+    }
+
+    let prefix: String = match chars.iter().position(|c| *c == '-') {
+        Some(dash) if dash <= 3 => chars[..=dash].iter().collect(),
+        _ => String::new(),
+    };
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", prefix, suffix)
+}
+
+fn api_key(config: &Value) -> &str {
+    config.get("api_key").and_then(Value::as_str).unwrap_or("")
+}
+
+/// 列出提供商配置, API Key 替换为遮盖值
+pub async fn list_masked(pool: &SqlitePool) -> Result<Vec<MaskedProvider>, String> {
+    let providers = load_providers(pool).await?;
+    Ok(providers
+        .into_iter()
+        .map(|(id, config)| {
+            let key = api_key(&config).trim().to_string();
+            let mut config = match config {
+                Value::Object(map) => map,
+                _ => Map::new(),
+            };
+            config.remove("api_key");
+            MaskedProvider {
+                id,
+                has_key: !key.is_empty(),
+                masked_key: mask_key(&key),
+                config,
+            }
+        })
+        .collect())
+}
+
+/// 获取指定提供商的完整 API Key
+pub async fn reveal_key(pool: &SqlitePool, provider_id: &str) -> Result<String, String> {
+    let providers = load_providers(pool).await?;
+    let config = providers
+        .get(provider_id)
+        .ok_or_else(|| format!("提供商不存在: {}", provider_id))?;
+    let key = api_key(config).trim();
+    if key.is_empty() {
+        return Err(format!("提供商未配置 API Key: {}", provider_id));
+    }
+    Ok(key.to_string())
+}
+
+/// 按点分路径 (数字段表示数组下标) 从额度接口响应中取出剩余额度, 兼容数字字符串
+pub fn parse_quota(body: &Value, path: Option<&str>) -> Option<f64> {
+    let lookup = |path: &str| {
+        path.split('.')
+            .try_fold(body, |value, segment| match segment.parse::<usize>() {
+                Ok(index) => value.get(index),
+                Err(_) => value.get(segment),
+            })
+            .and_then(|value| match value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            })
+    };
+    match path.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => lookup(path),
+        None => DEFAULT_QUOTA_PATHS.iter().find_map(|path| lookup(path)),
+    }
+}
+
+/// 查询提供商剩余额度: 读取配置中的 `quota_url` 与可选的 `quota_path`
+pub async fn fetch_quota(pool: &SqlitePool, provider_id: &str) -> Result<ProviderQuota, String> {
+    let providers = load_providers(pool).await?;
+    let config = providers
+        .get(provider_id)
+        .ok_or_else(|| format!("提供商不存在: {}", provider_id))?;
+    let unknown = ProviderQuota {
+        provider_id: provider_id.to_string(),
+        status: "unknown".to_string(),
+        remaining: None,
+        checked_at: db::now(),
+    };
+    let Some(url) = config
+        .get("quota_url")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+    else {
+        return Ok(unknown);
+    };
+
+    let mut request = tauri_plugin_http::reqwest::Client::new().get(url);
+    let key = api_key(config).trim();
+    if !key.is_empty() {
+        request = request.bearer_auth(key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("查询额度失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("查询额度失败: HTTP {}", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("查询额度失败: {}", e))?;
+    let body: Value =
+        serde_json::from_str(&text).map_err(|e| format!("额度接口返回的不是 JSON: {}", e))?;
+
+    let path = config.get("quota_path").and_then(Value::as_str);
+    Ok(match parse_quota(&body, path) {
+        Some(remaining) => ProviderQuota {
+            status: "ok".to_string(),
+            remaining: Some(remaining),
+            ..unknown
+        },
+        None => unknown,
+    })
+}
+
+/// 比较剩余额度与批量执行的预估花费
+pub fn preflight(quota: ProviderQuota, estimated_cost: f64) -> QuotaPreflight {
+    QuotaPreflight {
+        sufficient: quota.remaining.map(|remaining| remaining >= estimated_cost),
+        quota,
+        estimated_cost,
+    }
+}
+
+/// 按工作流最近完成的执行估算单次执行在各提供商上的平均花费 (USD), 按提供商排序;
+/// 没有可计价的历史 (未记录 token 用量或模型未定价) 时为空
+pub async fn estimate_run_cost(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<Vec<(String, f64)>, String> {
+    let runs: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT id FROM executions WHERE workflow_id = ? AND status = 'completed'
+             ORDER BY started_at DESC LIMIT ?
+         )",
+    )
+    .bind(workflow_id)
+    .bind(COST_HISTORY_RUNS)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if runs == 0 {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<(Option<String>, Option<String>, i64, i64)> = sqlx::query_as(
+        "SELECT json_extract(r.resolved_config, '$.provider'),
+                json_extract(r.resolved_config, '$.model'),
+                COALESCE(json_extract(r.token_usage, '$.promptTokens'), 0),
+                COALESCE(json_extract(r.token_usage, '$.completionTokens'), 0)
+         FROM node_results r
+         WHERE r.execution_id IN (
+                 SELECT id FROM executions WHERE workflow_id = ? AND status = 'completed'
+                 ORDER BY started_at DESC LIMIT ?
+             )
+           AND r.token_usage IS NOT NULL AND json_valid(r.token_usage)
+           AND json_valid(r.resolved_config)",
+    )
+    .bind(workflow_id)
+    .bind(COST_HISTORY_RUNS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for (provider, model, prompt_tokens, completion_tokens) in rows {
+        let (Some(provider), Some(model)) = (provider, model) else {
+            continue;
+        };
+        if let Some(cost) = token_cost(&model, prompt_tokens, completion_tokens) {
+            *totals.entry(provider).or_default() += cost;
+        }
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(provider, total)| (provider, total / runs as f64))
+        .collect())
+}
+
+// 额度不足时通知前端
+fn emit_if_insufficient(app: &AppHandle, result: &QuotaPreflight) -> Result<(), String> {
+    if result.sufficient == Some(false) {
+        app.emit(QUOTA_INSUFFICIENT_EVENT, result)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 批量执行前的额度预检: 按历史平均花费估算整批在各提供商上的花费并查询额度,
+/// 额度不足的提供商发出 `provider:quota_insufficient` 事件. 离线模式下或没有历史时不检查,
+/// 额度接口请求失败的提供商视为额度未知
+pub async fn batch_preflight(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    workflow_id: &str,
+    runs: i64,
+) -> Result<Vec<QuotaPreflight>, String> {
+    if network::is_offline(pool).await? {
+        return Ok(Vec::new());
+    }
+    let mut results = Vec::new();
+    for (provider_id, cost_per_run) in estimate_run_cost(pool, workflow_id).await? {
+        let quota = match fetch_quota(pool, &provider_id).await {
+            Ok(quota) => quota,
+            Err(e) => {
+                eprintln!("批量执行额度预检失败 ({}): {}", provider_id, e);
+                continue;
+            }
+        };
+        let result = preflight(quota, cost_per_run * runs as f64);
+        emit_if_insufficient(app, &result)?;
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn get_providers_masked(app: AppHandle) -> Result<Vec<MaskedProvider>, String> {
+    let pool = db::pool(&app).await?;
+    list_masked(&pool).await
+}
+
+#[tauri::command]
+pub async fn reveal_provider_key(app: AppHandle, provider_id: String) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    reveal_key(&pool, &provider_id).await
+}
+
+#[tauri::command]
+pub async fn get_provider_quota(
+    app: AppHandle,
+    provider_id: String,
+) -> Result<ProviderQuota, CommandError> {
+    let pool = db::pool(&app).await?;
+    network::guard(&app, &pool, "provider_quota").await?;
+    Ok(fetch_quota(&pool, &provider_id).await?)
+}
+
+/// 批量执行前检查额度, 不足时发出 `provider:quota_insufficient` 事件, 由前端决定是否中止
+#[tauri::command]
+pub async fn check_provider_quota_preflight(
+    app: AppHandle,
+    provider_id: String,
+    estimated_cost: f64,
+) -> Result<QuotaPreflight, CommandError> {
+    let pool = db::pool(&app).await?;
+    network::guard(&app, &pool, "provider_quota").await?;
+    let result = preflight(fetch_quota(&pool, &provider_id).await?, estimated_cost);
+    emit_if_insufficient(&app, &result)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_keys_without_leaking_body() {
+        assert_eq!(mask_key("sk-proj-1234567890abcd"), "sk-…abcd");
+        assert_eq!(mask_key("AIzaSyA1234567890wxyz"), "…wxyz");
+        assert_eq!(mask_key("short"), "…");
+        assert_eq!(mask_key("  "), "");
+    }
+
+    #[tokio::test]
+    async fn lists_masked_providers_and_reveals_on_request() {
+        let pool = db::test_pool().await;
+        let providers = serde_json::json!({
+            "openai": { "api_key": "sk-1234567890abcd", "enabled": true, "base_url": "https://relay.example" },
+            "claude": { "api_key": "", "enabled": false }
+        });
+        sqlx::query("UPDATE global_config SET ai_providers = ? WHERE id = 1")
+            .bind(providers.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let masked = list_masked(&pool).await.unwrap();
+        let openai = masked.iter().find(|p| p.id == "openai").unwrap();
+        assert!(openai.has_key);
+        assert_eq!(openai.masked_key, "sk-…abcd");
+        assert_eq!(openai.config["base_url"], "https://relay.example");
+        assert!(!openai.config.contains_key("api_key"));
+        assert!(!serde_json::to_string(&masked)
+            .unwrap()
+            .contains("1234567890"));
+
+        assert_eq!(
+            reveal_key(&pool, "openai").await.unwrap(),
+            "sk-1234567890abcd"
+        );
+        assert!(reveal_key(&pool, "claude").await.is_err());
+        assert!(reveal_key(&pool, "gemini").await.is_err());
+    }
+
+    #[test]
+    fn parses_quota_with_hint_or_defaults() {
+        let body = serde_json::json!({
+            "balance_infos": [{ "currency": "CNY", "total_balance": "12.50" }],
+            "data": { "credits": 3 }
+        });
+        assert_eq!(parse_quota(&body, None), Some(12.5));
+        assert_eq!(parse_quota(&body, Some("data.credits")), Some(3.0));
+        assert_eq!(parse_quota(&body, Some("data.missing")), None);
+    }
+
+    #[tokio::test]
+    async fn reports_unknown_without_quota_url() {
+        let pool = db::test_pool().await;
+        sqlx::query("UPDATE global_config SET ai_providers = ? WHERE id = 1")
+            .bind(r#"{"openai":{"api_key":"sk-1234567890abcd","enabled":true}}"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let quota = fetch_quota(&pool, "openai").await.unwrap();
+        assert_eq!(quota.status, "unknown");
+        assert!(preflight(quota, 5.0).sufficient.is_none());
+
+        let known = ProviderQuota {
+            remaining: Some(3.0),
+            status: "ok".to_string(),
+            ..fetch_quota(&pool, "openai").await.unwrap()
+        };
+        assert_eq!(preflight(known, 5.0).sufficient, Some(false));
+    }
+
+    #[tokio::test]
+    async fn estimates_run_cost_from_recent_executions() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '雾城');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p1', '空');
+            INSERT INTO executions (id, workflow_id, status, started_at) VALUES
+                ('e1', 'w1', 'completed', '2026-01-01T00:00:00.000Z'),
+                ('e2', 'w1', 'completed', '2026-01-02T00:00:00.000Z'),
+                ('e3', 'w1', 'failed', '2026-01-03T00:00:00.000Z');
+            INSERT INTO node_results (id, execution_id, node_id, status, resolved_config, token_usage) VALUES
+                ('r1', 'e1', 'n1', 'completed', '{"provider":"openai","model":"gpt-5"}', '{"promptTokens":1000000,"completionTokens":0}'),
+                ('r2', 'e2', 'n1', 'completed', '{"provider":"openai","model":"gpt-5"}', '{"promptTokens":0,"completionTokens":1000000}'),
+                ('r3', 'e2', 'n2', 'completed', '{"provider":"relay","model":"unpriced"}', '{"promptTokens":5,"completionTokens":5}'),
+                ('r4', 'e3', 'n1', 'failed', '{"provider":"openai","model":"gpt-5"}', '{"promptTokens":9000000,"completionTokens":0}');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // (3 + 12) / 2 次完成的执行; 未定价的模型与失败的执行不计入
+        assert_eq!(
+            estimate_run_cost(&pool, "w1").await.unwrap(),
+            vec![("openai".to_string(), 7.5)]
+        );
+        assert!(estimate_run_cost(&pool, "w2").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,595 @@
+// 提供商健康统计: 前端每次 AI 请求结束后上报结果, 内存中按小时窗口累计并定期写入 provider_health 表;
+// 最近 10 次请求中提供商侧错误比例超过阈值时发出 `provider:degraded` 事件
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db;
+
+/// 提供商最近请求错误率过高时发出的事件, 负载为 [`ProviderDegraded`]
+pub const PROVIDER_DEGRADED_EVENT: &str = "provider:degraded";
+
+/// 允许上报的错误类型
+pub const ERROR_CLASSES: &[&str] = &[
+    "timeout",
+    "rate_limit",
+    "server",
+    "network",
+    "auth",
+    "bad_request",
+    "other",
+];
+
+/// 计入提供商健康的错误类型; auth 与 bad_request 通常是配置或提示词问题
+pub const PROVIDER_ERROR_CLASSES: &[&str] =
+    &["timeout", "rate_limit", "server", "network", "other"];
+
+/// 判断降级时考察的最近请求数
+pub const RECENT_REQUESTS: usize = 10;
+
+/// 最近请求不足该数量时不判断降级
+const MIN_RECENT_REQUESTS: usize = 5;
+
+/// 最近请求中提供商侧错误比例超过该值视为降级
+pub const DEGRADED_ERROR_RATE: f64 = 0.5;
+
+/// 内存中的统计写入数据库的最短间隔
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 每个窗口保留的延迟样本数, 超出时丢弃最早的样本
+const MAX_LATENCY_SAMPLES: usize = 1000;
+
+/// 统计保留天数
+const RETENTION_DAYS: i64 = 30;
+
+/// get_provider_health 允许查询的最长小时数
+const MAX_HOURS: i64 = RETENTION_DAYS * 24;
+
+// 一个小时窗口内的原始统计, 以 JSON 存入 provider_health.stats_json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowStats {
+    pub requests: i64,
+    #[serde(default)]
+    pub errors: BTreeMap<String, i64>,
+    #[serde(default)]
+    pub latencies_ms: Vec<i64>,
+    #[serde(default)]
+    pub tokens: i64,
+}
+
+impl WindowStats {
+    fn add(&mut self, sample: &RequestSample) {
+        self.requests += 1;
+        if let Some(class) = &sample.error_class {
+            *self.errors.entry(class.clone()).or_default() += 1;
+        }
+        self.latencies_ms.push(sample.latency_ms.max(0));
+        self.tokens += sample.tokens.max(0);
+        self.trim();
+    }
+
+    fn merge(&mut self, other: &WindowStats) {
+        self.requests += other.requests;
+        for (class, count) in &other.errors {
+            *self.errors.entry(class.clone()).or_default() += count;
+        }
+        self.latencies_ms.extend(&other.latencies_ms);
+        self.tokens += other.tokens;
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let excess = self.latencies_ms.len().saturating_sub(MAX_LATENCY_SAMPLES);
+        self.latencies_ms.drain(..excess);
+    }
+}
+
+// 一次 AI 请求的结果
+#[derive(Debug, Clone)]
+pub struct RequestSample {
+    pub provider_id: String,
+    pub latency_ms: i64,
+    /// 成功时为 None
+    pub error_class: Option<String>,
+    pub tokens: i64,
+}
+
+// 汇总后的健康指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthStats {
+    pub requests: i64,
+    pub errors: i64,
+    pub error_rate: f64,
+    pub errors_by_class: BTreeMap<String, i64>,
+    pub p50_latency_ms: Option<i64>,
+    pub p95_latency_ms: Option<i64>,
+    /// 统计时段内平均每分钟消耗的 token
+    pub tokens_per_minute: f64,
+}
+
+// 单个小时窗口的健康指标
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthWindow {
+    pub window_start: String,
+    #[serde(flatten)]
+    pub stats: HealthStats,
+}
+
+// 提供商健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub provider_id: String,
+    pub hours: i64,
+    pub summary: HealthStats,
+    pub windows: Vec<HealthWindow>,
+    /// 最近请求中提供商侧错误的比例, 本次启动后没有请求时为 None
+    pub recent_error_rate: Option<f64>,
+    pub degraded: bool,
+}
+
+// 提供商降级通知
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderDegraded {
+    pub provider_id: String,
+    pub error_rate: f64,
+    pub recent_requests: usize,
+    /// 最近请求中最多的错误类型
+    pub top_error_class: Option<String>,
+}
+
+#[derive(Default)]
+struct Tracker {
+    // 尚未写入数据库的增量, 以窗口起始时间为键
+    pending: BTreeMap<String, WindowStats>,
+    // 最近请求的错误类型, 成功为 None
+    recent: VecDeque<Option<String>>,
+    degraded: bool,
+}
+
+impl Tracker {
+    fn recent_error_rate(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let errors = self
+            .recent
+            .iter()
+            .flatten()
+            .filter(|class| PROVIDER_ERROR_CLASSES.contains(&class.as_str()))
+            .count();
+        Some(errors as f64 / self.recent.len() as f64)
+    }
+}
+
+/// 提供商健康统计, 作为应用状态管理
+#[derive(Default)]
+pub struct ProviderHealthState {
+    trackers: Mutex<HashMap<String, Tracker>>,
+    last_persist: Mutex<Option<Instant>>,
+    persisting: AtomicBool,
+}
+
+impl ProviderHealthState {
+    fn trackers(&self) -> std::sync::MutexGuard<'_, HashMap<String, Tracker>> {
+        self.trackers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录一次请求, 提供商由健康转为降级时返回通知
+    pub fn observe(&self, sample: &RequestSample, window_start: &str) -> Option<ProviderDegraded> {
+        let mut trackers = self.trackers();
+        let tracker = trackers.entry(sample.provider_id.clone()).or_default();
+        tracker
+            .pending
+            .entry(window_start.to_string())
+            .or_default()
+            .add(sample);
+        tracker.recent.push_back(sample.error_class.clone());
+        if tracker.recent.len() > RECENT_REQUESTS {
+            tracker.recent.pop_front();
+        }
+
+        let error_rate = tracker.recent_error_rate().unwrap_or_default();
+        let degraded =
+            tracker.recent.len() >= MIN_RECENT_REQUESTS && error_rate > DEGRADED_ERROR_RATE;
+        let became_degraded = degraded && !tracker.degraded;
+        tracker.degraded = degraded;
+        if !became_degraded {
+            return None;
+        }
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for class in tracker.recent.iter().flatten() {
+            *counts.entry(class).or_default() += 1;
+        }
+        let top_error_class = counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(class, _)| class.to_string());
+        Some(ProviderDegraded {
+            provider_id: sample.provider_id.clone(),
+            error_rate,
+            recent_requests: tracker.recent.len(),
+            top_error_class,
+        })
+    }
+
+    fn recent(&self, provider_id: &str) -> (Option<f64>, bool) {
+        self.trackers()
+            .get(provider_id)
+            .map(|t| (t.recent_error_rate(), t.degraded))
+            .unwrap_or((None, false))
+    }
+
+    /// 按健康程度排序候选提供商: 未降级的在前, 其次按最近错误率升序, 同等时保持原顺序
+    pub fn rank(&self, candidates: &[String]) -> Vec<String> {
+        let mut ranked: Vec<(bool, f64, &String)> = candidates
+            .iter()
+            .map(|id| {
+                let (rate, degraded) = self.recent(id);
+                (degraded, rate.unwrap_or_default(), id)
+            })
+            .collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        ranked.into_iter().map(|(_, _, id)| id.clone()).collect()
+    }
+
+    fn take_pending(&self) -> Vec<(String, String, WindowStats)> {
+        let mut trackers = self.trackers();
+        trackers
+            .iter_mut()
+            .flat_map(|(provider_id, tracker)| {
+                std::mem::take(&mut tracker.pending)
+                    .into_iter()
+                    .map(move |(window, stats)| (provider_id.clone(), window, stats))
+            })
+            .collect()
+    }
+
+    // 写入失败时放回内存, 下次再试
+    fn restore_pending(&self, pending: Vec<(String, String, WindowStats)>) {
+        let mut trackers = self.trackers();
+        for (provider_id, window, stats) in pending {
+            trackers
+                .entry(provider_id)
+                .or_default()
+                .pending
+                .entry(window)
+                .or_default()
+                .merge(&stats);
+        }
+    }
+
+    fn persist_due(&self) -> bool {
+        let last = self.last_persist.lock().unwrap_or_else(|e| e.into_inner());
+        last.is_none_or(|at| at.elapsed() >= PERSIST_INTERVAL)
+    }
+}
+
+/// 时间所在小时窗口的起始时间
+pub fn window_start(time: DateTime<Utc>) -> String {
+    let hour = time
+        .with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time);
+    db::to_iso(hour)
+}
+
+// 最近秩法求百分位
+fn percentile(sorted: &[i64], q: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// 由原始统计计算健康指标, `minutes` 为统计时段长度
+pub fn summarize(stats: &WindowStats, minutes: f64) -> HealthStats {
+    let errors = stats
+        .errors
+        .iter()
+        .filter(|(class, _)| PROVIDER_ERROR_CLASSES.contains(&class.as_str()))
+        .map(|(_, count)| count)
+        .sum();
+    let mut latencies = stats.latencies_ms.clone();
+    latencies.sort_unstable();
+    HealthStats {
+        requests: stats.requests,
+        errors,
+        error_rate: if stats.requests > 0 {
+            errors as f64 / stats.requests as f64
+        } else {
+            0.0
+        },
+        errors_by_class: stats.errors.clone(),
+        p50_latency_ms: percentile(&latencies, 0.5),
+        p95_latency_ms: percentile(&latencies, 0.95),
+        tokens_per_minute: if minutes > 0.0 {
+            stats.tokens as f64 / minutes
+        } else {
+            0.0
+        },
+    }
+}
+
+/// 将内存中的统计并入数据库, 同时清理过期窗口; 已有写入在进行时直接返回
+pub async fn persist(pool: &SqlitePool, state: &ProviderHealthState) -> Result<(), String> {
+    if state.persisting.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+    let pending = state.take_pending();
+    let result = write_pending(pool, &pending).await;
+    match &result {
+        Ok(()) => {
+            *state.last_persist.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+        }
+        Err(_) => state.restore_pending(pending),
+    }
+    state.persisting.store(false, Ordering::Release);
+    result
+}
+
+async fn write_pending(
+    pool: &SqlitePool,
+    pending: &[(String, String, WindowStats)],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (provider_id, window, stats) in pending {
+        let existing: Option<String> = sqlx::query_scalar(
+            "SELECT stats_json FROM provider_health WHERE provider_id = ? AND window_start = ?",
+        )
+        .bind(provider_id)
+        .bind(window)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        // 无法解析的旧记录直接覆盖
+        let mut merged: WindowStats = existing
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        merged.merge(stats);
+        sqlx::query(
+            "INSERT INTO provider_health (provider_id, window_start, stats_json) VALUES (?, ?, ?)
+             ON CONFLICT(provider_id, window_start) DO UPDATE SET stats_json = excluded.stats_json",
+        )
+        .bind(provider_id)
+        .bind(window)
+        .bind(serde_json::to_string(&merged).map_err(|e| e.to_string())?)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    sqlx::query("DELETE FROM provider_health WHERE window_start < ?")
+        .bind(db::to_iso(
+            Utc::now() - chrono::Duration::days(RETENTION_DAYS),
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 记录一次请求, 距上次写入超过间隔时写入数据库; 提供商转为降级时返回通知
+pub async fn record(
+    pool: &SqlitePool,
+    state: &ProviderHealthState,
+    sample: &RequestSample,
+) -> Result<Option<ProviderDegraded>, String> {
+    if sample.provider_id.trim().is_empty() {
+        return Err("提供商不能为空".to_string());
+    }
+    if let Some(class) = &sample.error_class {
+        if !ERROR_CLASSES.contains(&class.as_str()) {
+            return Err(format!("未知的错误类型: {}", class));
+        }
+    }
+    let degraded = state.observe(sample, &window_start(Utc::now()));
+    if state.persist_due() {
+        persist(pool, state).await?;
+    }
+    Ok(degraded)
+}
+
+/// 提供商最近若干小时的健康状态, 查询前先写入内存中的统计
+pub async fn health(
+    pool: &SqlitePool,
+    state: &ProviderHealthState,
+    provider_id: &str,
+    hours: i64,
+) -> Result<ProviderHealth, String> {
+    if !(1..=MAX_HOURS).contains(&hours) {
+        return Err(format!("小时数须在 1 到 {} 之间", MAX_HOURS));
+    }
+    persist(pool, state).await?;
+
+    let since = window_start(Utc::now() - chrono::Duration::hours(hours - 1));
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT window_start, stats_json FROM provider_health
+         WHERE provider_id = ? AND window_start >= ? ORDER BY window_start",
+    )
+    .bind(provider_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut total = WindowStats::default();
+    let mut windows = Vec::with_capacity(rows.len());
+    for (window_start, raw) in rows {
+        let stats: WindowStats =
+            serde_json::from_str(&raw).map_err(|e| format!("健康统计格式错误: {}", e))?;
+        total.merge(&stats);
+        windows.push(HealthWindow {
+            window_start,
+            stats: summarize(&stats, 60.0),
+        });
+    }
+    let (recent_error_rate, degraded) = state.recent(provider_id);
+    Ok(ProviderHealth {
+        provider_id: provider_id.to_string(),
+        hours,
+        summary: summarize(&total, hours as f64 * 60.0),
+        windows,
+        recent_error_rate,
+        degraded,
+    })
+}
+
+#[tauri::command]
+pub async fn record_provider_request(
+    app: AppHandle,
+    state: State<'_, ProviderHealthState>,
+    provider_id: String,
+    latency_ms: i64,
+    error_class: Option<String>,
+    tokens: Option<i64>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    let sample = RequestSample {
+        provider_id,
+        latency_ms,
+        error_class,
+        tokens: tokens.unwrap_or_default(),
+    };
+    if let Some(degraded) = record(&pool, &state, &sample).await? {
+        let _ = app.emit(PROVIDER_DEGRADED_EVENT, degraded);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_provider_health(
+    app: AppHandle,
+    state: State<'_, ProviderHealthState>,
+    provider_id: String,
+    hours: Option<i64>,
+) -> Result<ProviderHealth, String> {
+    let pool = db::pool(&app).await?;
+    health(&pool, &state, &provider_id, hours.unwrap_or(24)).await
+}
+
+/// 供故障转移选择提供商: 返回按健康程度排序的候选列表
+#[tauri::command]
+pub fn rank_providers_by_health(
+    state: State<'_, ProviderHealthState>,
+    provider_ids: Vec<String>,
+) -> Vec<String> {
+    state.rank(&provider_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(provider_id: &str, latency_ms: i64, error_class: Option<&str>) -> RequestSample {
+        RequestSample {
+            provider_id: provider_id.to_string(),
+            latency_ms,
+            error_class: error_class.map(str::to_string),
+            tokens: 100,
+        }
+    }
+
+    #[test]
+    fn flags_degraded_providers_once() {
+        let state = ProviderHealthState::default();
+        let window = "2026-01-01T10:00:00.000Z";
+        for _ in 0..4 {
+            assert!(state
+                .observe(&sample("openai", 800, Some("server")), window)
+                .is_none());
+        }
+        // 第 5 次请求后才判断, 由健康转为降级时只通知一次
+        let degraded = state
+            .observe(&sample("openai", 800, Some("rate_limit")), window)
+            .unwrap();
+        assert_eq!(degraded.error_rate, 1.0);
+        assert_eq!(degraded.top_error_class.as_deref(), Some("server"));
+        assert!(state
+            .observe(&sample("openai", 800, Some("server")), window)
+            .is_none());
+
+        // 提示词问题不计入提供商错误
+        for _ in 0..6 {
+            state.observe(&sample("claude", 500, Some("bad_request")), window);
+        }
+        assert_eq!(state.recent("claude"), (Some(0.0), false));
+
+        // 恢复后可以再次通知
+        for _ in 0..RECENT_REQUESTS {
+            state.observe(&sample("openai", 300, None), window);
+        }
+        assert_eq!(state.recent("openai"), (Some(0.0), false));
+
+        let candidates = vec!["openai".to_string(), "gemini".to_string()];
+        assert_eq!(state.rank(&candidates), candidates);
+        for _ in 0..RECENT_REQUESTS {
+            state.observe(&sample("openai", 300, Some("timeout")), window);
+        }
+        assert_eq!(state.rank(&candidates), ["gemini", "openai"]);
+    }
+
+    #[test]
+    fn summarizes_window_stats() {
+        let mut stats = WindowStats::default();
+        for latency in 1..=20 {
+            stats.add(&sample("openai", latency * 100, None));
+        }
+        stats.add(&sample("openai", 5000, Some("timeout")));
+        stats.add(&sample("openai", 100, Some("auth")));
+        let summary = summarize(&stats, 60.0);
+        assert_eq!((summary.requests, summary.errors), (22, 1));
+        assert_eq!(summary.errors_by_class.get("auth"), Some(&1));
+        assert_eq!(summary.p50_latency_ms, Some(1000));
+        assert_eq!(summary.p95_latency_ms, Some(2000));
+        assert!((summary.tokens_per_minute - 2200.0 / 60.0).abs() < 1e-9);
+        assert_eq!(
+            summarize(&WindowStats::default(), 60.0).p50_latency_ms,
+            None
+        );
+        assert_eq!(
+            window_start("2026-01-01T10:42:13.500Z".parse().unwrap()),
+            "2026-01-01T10:00:00.000Z"
+        );
+    }
+
+    #[tokio::test]
+    async fn persists_and_merges_windows() {
+        let pool = db::test_pool().await;
+        let state = ProviderHealthState::default();
+
+        record(&pool, &state, &sample("openai", 400, None))
+            .await
+            .unwrap();
+        record(&pool, &state, &sample("openai", 600, Some("server")))
+            .await
+            .unwrap();
+        assert!(record(&pool, &state, &sample("openai", 1, Some("unknown")))
+            .await
+            .is_err());
+
+        // 未到写入间隔的请求留在内存中, 手动写入后由重启后的新状态与同一窗口合并
+        persist(&pool, &state).await.unwrap();
+        let restarted = ProviderHealthState::default();
+        record(&pool, &restarted, &sample("openai", 500, None))
+            .await
+            .unwrap();
+        let found = health(&pool, &restarted, "openai", 24).await.unwrap();
+        assert_eq!(found.windows.len(), 1);
+        assert_eq!((found.summary.requests, found.summary.errors), (3, 1));
+        assert_eq!(found.summary.p50_latency_ms, Some(500));
+        assert_eq!(found.recent_error_rate, Some(0.0));
+        assert!(!found.degraded);
+
+        assert!(health(&pool, &state, "openai", 0).await.is_err());
+        let empty = health(&pool, &state, "gemini", 1).await.unwrap();
+        assert!(empty.windows.is_empty() && empty.recent_error_rate.is_none());
+    }
+}
@@ -0,0 +1,476 @@
+// 执行队列: 排队的执行按优先级 (高 > 普通 > 低) 依次启动, 同级先进先出.
+// 执行本身由前端引擎完成, 前端空闲时调用 claim_next_execution 取得下一条; 运行中的执行不会被抢占
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::{budget, daily_limit, provider};
+use crate::db;
+use crate::error::CommandError;
+use crate::models::Execution;
+
+/// 优先级名称与存储值, 按从高到低排列
+pub const PRIORITIES: &[(&str, i64)] = &[("high", 2), ("normal", 1), ("low", 0)];
+
+// 各优先级排队的执行数
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PriorityCounts {
+    pub high: i64,
+    pub normal: i64,
+    pub low: i64,
+}
+
+// 队列状态
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStatus {
+    /// 运行中 (含暂停) 的执行数, 不为 0 时排队的执行需等待
+    pub running: i64,
+    pub queued: i64,
+    pub by_priority: PriorityCounts,
+}
+
+// 一次加入队列的一批执行
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionBatch {
+    pub batch_id: String,
+    pub executions: Vec<Execution>,
+}
+
+fn priority_value(priority: &str) -> Result<i64, String> {
+    PRIORITIES
+        .iter()
+        .find(|(name, _)| *name == priority)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| format!("不支持的优先级: {}", priority))
+}
+
+fn priority_name(value: i64) -> &'static str {
+    PRIORITIES
+        .iter()
+        .find(|(_, v)| *v == value)
+        .map_or("normal", |(name, _)| name)
+}
+
+async fn ensure_workflow(pool: &SqlitePool, workflow_id: &str) -> Result<(), String> {
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM workflows WHERE id = ?")
+        .bind(workflow_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+    Ok(())
+}
+
+/// 工作流最新保存的版本号, 从未保存版本时为 None
+async fn latest_version(pool: &SqlitePool, workflow_id: &str) -> Result<Option<i64>, String> {
+    sqlx::query_scalar("SELECT MAX(version_number) FROM workflow_versions WHERE workflow_id = ?")
+        .bind(workflow_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn insert_queued(
+    conn: &mut sqlx::SqliteConnection,
+    workflow_id: &str,
+    input: Option<&str>,
+    priority: i64,
+    batch_id: Option<&str>,
+    workflow_version: Option<i64>,
+) -> Result<String, String> {
+    let id = db::new_id();
+    let now = db::now();
+    // started_at 在出队时改为实际开始时间
+    sqlx::query(
+        "INSERT INTO executions
+             (id, workflow_id, status, input, started_at, queued_at, priority, batch_id, workflow_version)
+         VALUES (?, ?, 'queued', ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(workflow_id)
+    .bind(input)
+    .bind(&now)
+    .bind(&now)
+    .bind(priority)
+    .bind(batch_id)
+    .bind(workflow_version)
+    .execute(conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// 把执行加入队列, 状态为 queued; 记录工作流当前的版本号
+pub async fn enqueue(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    input: Option<&str>,
+    priority: &str,
+) -> Result<Execution, String> {
+    let priority = priority_value(priority)?;
+    ensure_workflow(pool, workflow_id).await?;
+    let version = latest_version(pool, workflow_id).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let id = insert_queued(&mut conn, workflow_id, input, priority, None, version).await?;
+    drop(conn);
+    get(pool, &id).await
+}
+
+/// 以同一批次把多条输入加入队列 (单个事务), 按输入顺序排队
+pub async fn enqueue_batch(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    inputs: &[String],
+    priority: &str,
+) -> Result<ExecutionBatch, String> {
+    let priority = priority_value(priority)?;
+    if inputs.is_empty() {
+        return Err("批次至少包含一条输入".to_string());
+    }
+    ensure_workflow(pool, workflow_id).await?;
+    let version = latest_version(pool, workflow_id).await?;
+    let batch_id = db::new_id();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut ids = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let id = insert_queued(
+            &mut tx,
+            workflow_id,
+            Some(input),
+            priority,
+            Some(&batch_id),
+            version,
+        )
+        .await?;
+        ids.push(id);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let mut executions = Vec::with_capacity(ids.len());
+    for id in &ids {
+        executions.push(get(pool, id).await?);
+    }
+    Ok(ExecutionBatch {
+        batch_id,
+        executions,
+    })
+}
+
+async fn get(pool: &SqlitePool, execution_id: &str) -> Result<Execution, String> {
+    sqlx::query_as::<_, Execution>("SELECT * FROM executions WHERE id = ?")
+        .bind(execution_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 启动时把上次退出 (崩溃、强制退出或运行中关闭窗口) 时仍在运行或暂停的执行标记为失败,
+/// 这些执行已没有引擎在运行, 不处理会一直阻塞队列; 返回处理的执行数
+pub async fn recover_interrupted(pool: &SqlitePool) -> Result<u64, String> {
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE node_results SET status = 'failed', finished_at = ?
+         WHERE status IN ('running', 'paused')
+           AND execution_id IN (SELECT id FROM executions WHERE status IN ('running', 'paused'))",
+    )
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let result = sqlx::query(
+        "UPDATE executions SET status = 'failed', finished_at = ?
+         WHERE status IN ('running', 'paused')",
+    )
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(result.rows_affected())
+}
+
+/// 取出下一条执行并标记为 running; 有执行正在运行或队列为空时返回 None
+pub async fn claim_next(pool: &SqlitePool) -> Result<Option<Execution>, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let running: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM executions WHERE status IN ('running', 'paused')")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    if running > 0 {
+        return Ok(None);
+    }
+
+    let next: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM executions WHERE status = 'queued'
+         ORDER BY COALESCE(priority, 1) DESC, queued_at, rowid
+         LIMIT 1",
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let Some(id) = next else {
+        return Ok(None);
+    };
+    sqlx::query("UPDATE executions SET status = 'running', started_at = ? WHERE id = ?")
+        .bind(db::now())
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get(pool, &id).await.map(Some)
+}
+
+/// 把排队中的执行提升一级, 返回新的优先级; 已开始或已是最高优先级时报错
+pub async fn bump(pool: &SqlitePool, execution_id: &str) -> Result<String, String> {
+    let row: Option<(String, Option<i64>)> =
+        sqlx::query_as("SELECT status, priority FROM executions WHERE id = ?")
+            .bind(execution_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (status, priority) = row.ok_or_else(|| format!("执行记录不存在: {}", execution_id))?;
+    if status != "queued" {
+        return Err("只能调整排队中的执行的优先级".to_string());
+    }
+    let priority = priority.unwrap_or(1);
+    if priority >= PRIORITIES[0].1 {
+        return Err("已是最高优先级".to_string());
+    }
+
+    sqlx::query("UPDATE executions SET priority = ? WHERE id = ?")
+        .bind(priority + 1)
+        .bind(execution_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(priority_name(priority + 1).to_string())
+}
+
+/// 队列状态: 运行中的执行数与各优先级排队数
+pub async fn status(pool: &SqlitePool) -> Result<QueueStatus, String> {
+    let running: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM executions WHERE status IN ('running', 'paused')")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT COALESCE(priority, 1), COUNT(*) FROM executions
+         WHERE status = 'queued' GROUP BY COALESCE(priority, 1)",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut by_priority = PriorityCounts::default();
+    for (priority, count) in rows {
+        match priority_name(priority) {
+            "high" => by_priority.high += count,
+            "low" => by_priority.low += count,
+            _ => by_priority.normal += count,
+        }
+    }
+    Ok(QueueStatus {
+        running,
+        queued: by_priority.high + by_priority.normal + by_priority.low,
+        by_priority,
+    })
+}
+
+#[tauri::command]
+pub async fn enqueue_execution(
+    app: AppHandle,
+    workflow_id: String,
+    input: Option<String>,
+    priority: Option<String>,
+) -> Result<Execution, CommandError> {
+    let pool = db::pool(&app).await?;
+    budget::ensure_workflow_within_budget(&pool, &workflow_id).await?;
+    daily_limit::ensure_within_daily_limit(&pool, &workflow_id, 1).await?;
+    Ok(enqueue(
+        &pool,
+        &workflow_id,
+        input.as_deref(),
+        priority.as_deref().unwrap_or("normal"),
+    )
+    .await?)
+}
+
+/// 入队前按历史花费预检提供商额度, 不足时发出 `provider:quota_insufficient` 事件;
+/// `abort_on_insufficient_quota` 为 true 时不入队并返回 QuotaInsufficient
+#[tauri::command]
+pub async fn enqueue_execution_batch(
+    app: AppHandle,
+    workflow_id: String,
+    inputs: Vec<String>,
+    priority: Option<String>,
+    abort_on_insufficient_quota: Option<bool>,
+) -> Result<ExecutionBatch, CommandError> {
+    let pool = db::pool(&app).await?;
+    budget::ensure_workflow_within_budget(&pool, &workflow_id).await?;
+    daily_limit::ensure_within_daily_limit(&pool, &workflow_id, inputs.len() as i64).await?;
+    let checks = provider::batch_preflight(&app, &pool, &workflow_id, inputs.len() as i64).await?;
+    if abort_on_insufficient_quota.unwrap_or(false) {
+        let insufficient = checks.into_iter().find(|c| c.sufficient == Some(false));
+        if let Some(check) = insufficient {
+            return Err(CommandError::QuotaInsufficient {
+                provider_id: check.quota.provider_id,
+                remaining: check.quota.remaining.unwrap_or_default(),
+                estimated_cost: check.estimated_cost,
+            });
+        }
+    }
+    Ok(enqueue_batch(
+        &pool,
+        &workflow_id,
+        &inputs,
+        priority.as_deref().unwrap_or("normal"),
+    )
+    .await?)
+}
+
+#[tauri::command]
+pub async fn claim_next_execution(app: AppHandle) -> Result<Option<Execution>, String> {
+    let pool = db::pool(&app).await?;
+    claim_next(&pool).await
+}
+
+#[tauri::command]
+pub async fn bump_execution_priority(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    bump(&pool, &execution_id).await
+}
+
+#[tauri::command]
+pub async fn get_execution_queue_status(app: AppHandle) -> Result<QueueStatus, String> {
+    let pool = db::pool(&app).await?;
+    status(&pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn finish(pool: &SqlitePool, execution_id: &str) {
+        sqlx::query("UPDATE executions SET status = 'completed' WHERE id = ?")
+            .bind(execution_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn recovers_executions_interrupted_by_exit() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        // 上次退出时仍在运行的执行与其节点结果
+        sqlx::raw_sql(
+            "INSERT INTO executions (id, workflow_id, status, started_at)
+                 VALUES ('stale', 'w1', 'running', '2026-01-01T00:00:00.000Z'),
+                        ('done', 'w1', 'completed', '2026-01-01T00:00:00.000Z');
+             INSERT INTO node_results (id, execution_id, node_id, status)
+                 VALUES ('r1', 'stale', 'n1', 'running');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let queued = enqueue(&pool, "w1", Some("排队"), "normal").await.unwrap();
+        assert!(claim_next(&pool).await.unwrap().is_none());
+
+        assert_eq!(recover_interrupted(&pool).await.unwrap(), 1);
+        let statuses: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, status FROM executions ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert!(statuses.contains(&("stale".to_string(), "failed".to_string())));
+        assert!(statuses.contains(&("done".to_string(), "completed".to_string())));
+        let node_status: String =
+            sqlx::query_scalar("SELECT status FROM node_results WHERE id = 'r1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(node_status, "failed");
+
+        assert_eq!(claim_next(&pool).await.unwrap().unwrap().id, queued.id);
+    }
+
+    #[tokio::test]
+    async fn claims_by_priority_then_fifo() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let low = enqueue(&pool, "w1", Some("低"), "low").await.unwrap();
+        let first = enqueue(&pool, "w1", Some("普通一"), "normal")
+            .await
+            .unwrap();
+        let second = enqueue(&pool, "w1", Some("普通二"), "normal")
+            .await
+            .unwrap();
+        let urgent = enqueue(&pool, "w1", Some("急"), "high").await.unwrap();
+        assert_eq!(low.status, "queued");
+        assert!(enqueue(&pool, "w1", None, "urgent").await.is_err());
+        assert!(enqueue(&pool, "missing", None, "normal").await.is_err());
+
+        let queue = status(&pool).await.unwrap();
+        assert_eq!(queue.queued, 4);
+        assert_eq!(
+            queue.by_priority,
+            PriorityCounts {
+                high: 1,
+                normal: 2,
+                low: 1
+            }
+        );
+
+        let claimed = claim_next(&pool).await.unwrap().unwrap();
+        assert_eq!(claimed.id, urgent.id);
+        assert_eq!(claimed.status, "running");
+        // 运行中的执行不会被抢占
+        assert!(claim_next(&pool).await.unwrap().is_none());
+        assert_eq!(status(&pool).await.unwrap().running, 1);
+
+        let mut order = Vec::new();
+        finish(&pool, &urgent.id).await;
+        while let Some(next) = claim_next(&pool).await.unwrap() {
+            finish(&pool, &next.id).await;
+            order.push(next.id);
+        }
+        assert_eq!(order, vec![first.id, second.id, low.id]);
+    }
+
+    #[tokio::test]
+    async fn bumps_queued_executions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        enqueue(&pool, "w1", None, "normal").await.unwrap();
+        let low = enqueue(&pool, "w1", None, "low").await.unwrap();
+        assert_eq!(bump(&pool, &low.id).await.unwrap(), "normal");
+        assert_eq!(bump(&pool, &low.id).await.unwrap(), "high");
+        assert!(bump(&pool, &low.id).await.is_err());
+        assert_eq!(claim_next(&pool).await.unwrap().unwrap().id, low.id);
+        assert!(bump(&pool, &low.id).await.is_err());
+
+        assert_eq!(status(&pool).await.unwrap().by_priority.normal, 1);
+        assert!(bump(&pool, "missing").await.is_err());
+    }
+}
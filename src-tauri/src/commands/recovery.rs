@@ -0,0 +1,199 @@
+// 编辑器恢复草稿: 前端在编辑过程中防抖保存未提交的状态, webview 崩溃后下次打开可恢复
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+/// 单个草稿的最大字节数
+pub const MAX_DRAFT_BYTES: usize = 1024 * 1024;
+
+/// 全部草稿的总字节数上限, 超出时删除最早更新的草稿
+pub const MAX_TOTAL_BYTES: i64 = 16 * 1024 * 1024;
+
+/// 草稿保留天数, 过期草稿在启动时清理
+pub const DRAFT_TTL_DAYS: i64 = 7;
+
+// 恢复草稿, payload 为前端序列化的 JSON
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RecoveryDraft {
+    pub scope_key: String,
+    pub payload: String,
+    pub size_bytes: i64,
+    pub updated_at: String,
+}
+
+fn cutoff() -> String {
+    db::to_iso(chrono::Utc::now() - chrono::Duration::days(DRAFT_TTL_DAYS))
+}
+
+/// 保存草稿 (同一作用域覆盖), 超出总量上限时删除最早更新的其他草稿
+pub async fn save(pool: &SqlitePool, scope_key: &str, payload: &str) -> Result<(), String> {
+    let scope_key = scope_key.trim();
+    if scope_key.is_empty() {
+        return Err("草稿作用域不能为空".to_string());
+    }
+    if payload.len() > MAX_DRAFT_BYTES {
+        return Err(format!(
+            "草稿过大: {} 字节, 上限为 {} 字节",
+            payload.len(),
+            MAX_DRAFT_BYTES
+        ));
+    }
+    serde_json::from_str::<serde_json::Value>(payload)
+        .map_err(|e| format!("草稿不是有效的 JSON: {}", e))?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO recovery_drafts (scope_key, payload, size_bytes, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(scope_key) DO UPDATE SET
+             payload = excluded.payload, size_bytes = excluded.size_bytes, updated_at = excluded.updated_at",
+    )
+    .bind(scope_key)
+    .bind(payload)
+    .bind(payload.len() as i64)
+    .bind(db::now())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let others: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT scope_key, size_bytes FROM recovery_drafts
+         WHERE scope_key != ? ORDER BY updated_at DESC, rowid DESC",
+    )
+    .bind(scope_key)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut total = payload.len() as i64;
+    for (key, size) in others {
+        total += size;
+        if total > MAX_TOTAL_BYTES {
+            sqlx::query("DELETE FROM recovery_drafts WHERE scope_key = ?")
+                .bind(key)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 读取未过期的草稿
+pub async fn get(pool: &SqlitePool, scope_key: &str) -> Result<Option<RecoveryDraft>, String> {
+    sqlx::query_as::<_, RecoveryDraft>(
+        "SELECT * FROM recovery_drafts WHERE scope_key = ? AND updated_at >= ?",
+    )
+    .bind(scope_key.trim())
+    .bind(cutoff())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 删除草稿, 返回是否存在
+pub async fn clear(pool: &SqlitePool, scope_key: &str) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM recovery_drafts WHERE scope_key = ?")
+        .bind(scope_key.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 删除过期草稿, 启动时调用
+pub async fn purge_expired(pool: &SqlitePool) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM recovery_drafts WHERE updated_at < ?")
+        .bind(cutoff())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected())
+}
+
+#[tauri::command]
+pub async fn save_recovery_draft(
+    app: AppHandle,
+    scope_key: String,
+    payload_json: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    save(&pool, &scope_key, &payload_json).await
+}
+
+#[tauri::command]
+pub async fn get_recovery_draft(
+    app: AppHandle,
+    scope_key: String,
+) -> Result<Option<RecoveryDraft>, String> {
+    let pool = db::pool(&app).await?;
+    get(&pool, &scope_key).await
+}
+
+#[tauri::command]
+pub async fn clear_recovery_draft(app: AppHandle, scope_key: String) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    clear(&pool, &scope_key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn saves_and_clears_drafts() {
+        let pool = db::test_pool().await;
+
+        save(&pool, "node-config:n1", r#"{"name":"撰写"}"#)
+            .await
+            .unwrap();
+        save(&pool, "node-config:n1", r#"{"name":"撰写正文"}"#)
+            .await
+            .unwrap();
+        let draft = get(&pool, "node-config:n1").await.unwrap().unwrap();
+        assert_eq!(draft.payload, r#"{"name":"撰写正文"}"#);
+        assert_eq!(draft.size_bytes, draft.payload.len() as i64);
+
+        assert!(save(&pool, " ", "{}").await.is_err());
+        assert!(save(&pool, "node-config:n1", "not json").await.is_err());
+        let oversized = format!("\"{}\"", "字".repeat(MAX_DRAFT_BYTES / 3));
+        assert!(save(&pool, "node-config:n1", &oversized).await.is_err());
+
+        assert!(clear(&pool, "node-config:n1").await.unwrap());
+        assert!(!clear(&pool, "node-config:n1").await.unwrap());
+        assert!(get(&pool, "node-config:n1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn prunes_expired_and_oldest_drafts() {
+        let pool = db::test_pool().await;
+        let expired = db::to_iso(chrono::Utc::now() - chrono::Duration::days(DRAFT_TTL_DAYS + 1));
+        sqlx::query(
+            "INSERT INTO recovery_drafts (scope_key, payload, size_bytes, updated_at) VALUES ('old', '{}', 2, ?)",
+        )
+        .bind(&expired)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(get(&pool, "old").await.unwrap().is_none());
+        assert_eq!(purge_expired(&pool).await.unwrap(), 1);
+
+        // 写满总量上限后再保存, 最早更新的草稿被删除
+        let payload = format!("\"{}\"", "a".repeat(MAX_DRAFT_BYTES - 2));
+        let count = MAX_TOTAL_BYTES as usize / MAX_DRAFT_BYTES;
+        for i in 0..count {
+            save(&pool, &format!("draft-{}", i), &payload)
+                .await
+                .unwrap();
+        }
+        save(&pool, "latest", "{}").await.unwrap();
+        assert!(get(&pool, "draft-0").await.unwrap().is_none());
+        assert!(get(&pool, "draft-1").await.unwrap().is_some());
+        assert!(get(&pool, "latest").await.unwrap().is_some());
+        let total: i64 = sqlx::query_scalar("SELECT SUM(size_bytes) FROM recovery_drafts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(total <= MAX_TOTAL_BYTES);
+    }
+}
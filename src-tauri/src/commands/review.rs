@@ -0,0 +1,567 @@
+// 只读审阅副本: 交给试读者的项目副本, 只含章节正文、大纲 (章节梗概与场景) 与选定的设定,
+// 不含工作流与提示词. 可设密码: PBKDF2-HMAC-SHA256 派生密钥, HMAC-SHA256 计数器模式加密并校验完整性.
+// 导入后项目为只读, 写入由 v38 的触发器拒绝 (ReadOnlyProject), convert_to_editable 解除限制
+use std::io::Write;
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+use tauri_plugin_fs::{FsExt, OpenOptions};
+
+use crate::commands::{chapter, scene};
+use crate::db;
+use crate::error::CommandError;
+use crate::models::Project;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 审阅副本的格式标识
+pub const REVIEW_COPY_FORMAT: &str = "chouann-review-copy";
+
+const REVIEW_COPY_VERSION: &str = "1.0";
+
+/// 密钥派生的迭代次数, 写入副本, 导入时按副本中的值派生
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// 导入时接受的迭代次数上限, 防止构造的副本长时间占用
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// 导入后存放章节正文的工作流 (不含节点)
+const CONTENT_WORKFLOW_NAME: &str = "审阅副本正文";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewProject {
+    pub name: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewScene {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewChapter {
+    pub title: String,
+    pub synopsis: Option<String>,
+    pub target_word_count: Option<i64>,
+    pub status: String,
+    /// 已完成执行的最终输出, 与全书草稿一致
+    pub content: String,
+    pub scenes: Vec<ReviewScene>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReviewSetting {
+    pub category: String,
+    pub name: String,
+    pub content: String,
+}
+
+// 审阅副本内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewCopy {
+    pub format: String,
+    pub version: String,
+    pub exported_at: String,
+    pub read_only: bool,
+    pub project: ReviewProject,
+    pub chapters: Vec<ReviewChapter>,
+    pub settings: Vec<ReviewSetting>,
+}
+
+// 加密后的内容, 各字段为 base64
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedPayload {
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+    mac: String,
+}
+
+// 加密的审阅副本: 只有格式信息为明文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedReviewCopy {
+    format: String,
+    version: String,
+    exported_at: String,
+    read_only: bool,
+    encrypted: EncryptedPayload,
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+// PBKDF2-HMAC-SHA256 (RFC 8018) 的前两个输出块, 分别作为加密密钥与校验密钥
+fn derive_keys(password: &str, salt: &[u8], iterations: u32) -> ([u8; 32], [u8; 32]) {
+    let block = |index: u32| {
+        let mut u = hmac(password.as_bytes(), &[salt, &index.to_be_bytes()]);
+        let mut output = u;
+        for _ in 1..iterations {
+            u = hmac(password.as_bytes(), &[&u]);
+            output.iter_mut().zip(u.iter()).for_each(|(o, b)| *o ^= b);
+        }
+        output
+    };
+    (block(1), block(2))
+}
+
+// 计数器模式: 第 i 块密钥流为 HMAC(key, nonce || i), 加密与解密相同
+fn apply_keystream(key: &[u8], nonce: &[u8], data: &mut [u8]) {
+    for (counter, chunk) in data.chunks_mut(32).enumerate() {
+        let stream = hmac(key, &[nonce, &(counter as u64).to_be_bytes()]);
+        chunk
+            .iter_mut()
+            .zip(stream.iter())
+            .for_each(|(b, s)| *b ^= s);
+    }
+}
+
+fn random_bytes() -> [u8; 16] {
+    *uuid::Uuid::new_v4().as_bytes()
+}
+
+fn decode(field: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(field)
+        .map_err(|_| "审阅副本已损坏".to_string())
+}
+
+/// 汇编审阅副本; `setting_ids` 为要附带的设定, 须属于该项目
+pub async fn build(
+    pool: &SqlitePool,
+    project_id: &str,
+    setting_ids: &[String],
+) -> Result<ReviewCopy, String> {
+    let draft = chapter::full_draft(pool, project_id).await?;
+    let description: Option<String> =
+        sqlx::query_scalar("SELECT description FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    // full_draft 与 list 的章节顺序一致
+    let mut chapters = Vec::new();
+    for (outline, text) in chapter::list(pool, project_id)
+        .await?
+        .into_iter()
+        .zip(draft.chapters)
+    {
+        let scenes = scene::list(pool, &outline.id)
+            .await?
+            .into_iter()
+            .map(|s| ReviewScene {
+                title: s.title,
+                description: s.description,
+                status: s.status,
+            })
+            .collect();
+        chapters.push(ReviewChapter {
+            title: outline.title,
+            synopsis: outline.synopsis,
+            target_word_count: outline.target_word_count,
+            status: outline.status,
+            content: text.content,
+            scenes,
+        });
+    }
+
+    let mut settings = Vec::new();
+    for setting_id in setting_ids {
+        let setting: Option<ReviewSetting> = sqlx::query_as(
+            "SELECT category, name, content FROM settings WHERE id = ? AND project_id = ?",
+        )
+        .bind(setting_id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        settings.push(setting.ok_or_else(|| format!("设定不属于该项目: {}", setting_id))?);
+    }
+
+    Ok(ReviewCopy {
+        format: REVIEW_COPY_FORMAT.to_string(),
+        version: REVIEW_COPY_VERSION.to_string(),
+        exported_at: db::now(),
+        read_only: true,
+        project: ReviewProject {
+            name: draft.title,
+            description,
+            author: draft.author,
+        },
+        chapters,
+        settings,
+    })
+}
+
+/// 序列化审阅副本; 提供密码时加密内容
+pub fn seal(copy: &ReviewCopy, password: Option<&str>) -> Result<String, String> {
+    let Some(password) = password.filter(|p| !p.is_empty()) else {
+        return serde_json::to_string_pretty(copy).map_err(|e| e.to_string());
+    };
+
+    let salt = random_bytes();
+    let nonce = random_bytes();
+    let (key, mac_key) = derive_keys(password, &salt, PBKDF2_ITERATIONS);
+    let mut data = serde_json::to_vec(copy).map_err(|e| e.to_string())?;
+    apply_keystream(&key, &nonce, &mut data);
+    let mac = hmac(&mac_key, &[&nonce, &data]);
+
+    let sealed = EncryptedReviewCopy {
+        format: copy.format.clone(),
+        version: copy.version.clone(),
+        exported_at: copy.exported_at.clone(),
+        read_only: true,
+        encrypted: EncryptedPayload {
+            iterations: PBKDF2_ITERATIONS,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(&data),
+            mac: STANDARD.encode(mac),
+        },
+    };
+    serde_json::to_string_pretty(&sealed).map_err(|e| e.to_string())
+}
+
+/// 是否为审阅副本, 以及是否加密
+pub fn detect(value: &serde_json::Value) -> Option<bool> {
+    (value.get("format")?.as_str()? == REVIEW_COPY_FORMAT).then(|| value.get("encrypted").is_some())
+}
+
+/// 解析审阅副本; 加密的副本须提供密码, 密码错误或内容被改动时报错
+pub fn open(content: &str, password: Option<&str>) -> Result<ReviewCopy, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|_| "无效的审阅副本格式".to_string())?;
+    let encrypted = detect(&value).ok_or_else(|| "无效的审阅副本格式".to_string())?;
+    if !encrypted {
+        return serde_json::from_value(value).map_err(|_| "无效的审阅副本格式".to_string());
+    }
+
+    let password = password
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| "审阅副本已加密, 请输入密码".to_string())?;
+    let sealed: EncryptedReviewCopy =
+        serde_json::from_value(value).map_err(|_| "审阅副本已损坏".to_string())?;
+    let payload = sealed.encrypted;
+    if payload.iterations == 0 || payload.iterations > MAX_PBKDF2_ITERATIONS {
+        return Err("审阅副本已损坏".to_string());
+    }
+    let salt = decode(&payload.salt)?;
+    let nonce = decode(&payload.nonce)?;
+    let mut data = decode(&payload.ciphertext)?;
+    let expected = decode(&payload.mac)?;
+
+    let (key, mac_key) = derive_keys(password, &salt, payload.iterations);
+    let mut mac = HmacSha256::new_from_slice(&mac_key).expect("HMAC 接受任意长度的密钥");
+    mac.update(&nonce);
+    mac.update(&data);
+    mac.verify_slice(&expected)
+        .map_err(|_| "密码错误或审阅副本已损坏".to_string())?;
+    apply_keystream(&key, &nonce, &mut data);
+    serde_json::from_slice(&data).map_err(|_| "无效的审阅副本格式".to_string())
+}
+
+async fn get_project(pool: &SqlitePool, project_id: &str) -> Result<Project, String> {
+    sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("项目不存在: {}", project_id))
+}
+
+/// 把审阅副本导入为新的只读项目; 章节正文存为不含节点的工作流下的已完成执行记录
+pub async fn import(pool: &SqlitePool, copy: &ReviewCopy) -> Result<Project, String> {
+    let project_id = db::new_id();
+    let workflow_id = db::new_id();
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO projects (id, name, description, author, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&project_id)
+    .bind(&copy.project.name)
+    .bind(&copy.project.description)
+    .bind(&copy.project.author)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO workflows (id, project_id, name, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&workflow_id)
+    .bind(&project_id)
+    .bind(CONTENT_WORKFLOW_NAME)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (index, item) in copy.chapters.iter().enumerate() {
+        let chapter_id = db::new_id();
+        sqlx::query(
+            "INSERT INTO chapters (id, project_id, title, synopsis, target_word_count, order_index, status, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&chapter_id)
+        .bind(&project_id)
+        .bind(&item.title)
+        .bind(&item.synopsis)
+        .bind(item.target_word_count)
+        .bind(index as i64)
+        .bind(&item.status)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (scene_index, scene) in item.scenes.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO scenes (id, chapter_id, title, description, order_index, status, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(db::new_id())
+            .bind(&chapter_id)
+            .bind(&scene.title)
+            .bind(&scene.description)
+            .bind(scene_index as i64)
+            .bind(&scene.status)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        if item.content.is_empty() {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO executions (id, workflow_id, status, final_output, started_at, finished_at, title, chapter_id)
+             VALUES (?, ?, 'completed', ?, ?, ?, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(&workflow_id)
+        .bind(&item.content)
+        .bind(&now)
+        .bind(&now)
+        .bind(&item.title)
+        .bind(&chapter_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for (index, setting) in copy.settings.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO settings (id, project_id, category, name, content, order_index, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(&project_id)
+        .bind(&setting.category)
+        .bind(&setting.name)
+        .bind(&setting.content)
+        .bind(index as i64)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    // 内容写入后再设为只读, 此后的写入都会被触发器拒绝
+    sqlx::query("UPDATE projects SET read_only = 1 WHERE id = ?")
+        .bind(&project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_project(pool, &project_id).await
+}
+
+/// 解除项目的只读限制
+pub async fn convert(pool: &SqlitePool, project_id: &str) -> Result<Project, String> {
+    let result = sqlx::query("UPDATE projects SET read_only = 0, updated_at = ? WHERE id = ?")
+        .bind(db::now())
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+    get_project(pool, project_id).await
+}
+
+#[tauri::command]
+pub async fn export_review_copy(
+    app: AppHandle,
+    project_id: String,
+    path: String,
+    password: Option<String>,
+    setting_ids: Option<Vec<String>>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    let copy = build(&pool, &project_id, &setting_ids.unwrap_or_default()).await?;
+    let content = seal(&copy, password.as_deref())?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    let mut file = app
+        .fs()
+        .open(PathBuf::from(&path), options)
+        .map_err(|e| format!("无法写入文件 {}: {}", path, e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("写入文件失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn import_review_copy(
+    app: AppHandle,
+    path: String,
+    password: Option<String>,
+) -> Result<Project, CommandError> {
+    let pool = db::pool(&app).await?;
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取文件 {}: {}", path, e))?;
+    let copy = open(&content, password.as_deref())?;
+    Ok(import(&pool, &copy).await?)
+}
+
+#[tauri::command]
+pub async fn convert_to_editable(app: AppHandle, project_id: String) -> Result<Project, String> {
+    let pool = db::pool(&app).await?;
+    convert(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name, author) VALUES ('p1', '雨夜', '沈舟');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES ('n1', 'w1', 'ai_chat', '写作', '{\"system_prompt\":\"秘密提示词\"}', 0);
+             INSERT INTO chapters (id, project_id, title, synopsis, order_index) VALUES
+                 ('c1', 'p1', '第一章', '林雪救下沈舟', 0), ('c2', 'p1', '第二章', NULL, 1);
+             INSERT INTO scenes (id, chapter_id, title, order_index) VALUES ('s1', 'c1', '街头', 0);
+             INSERT INTO executions (id, workflow_id, status, final_output, chapter_id) VALUES
+                 ('e1', 'w1', 'completed', '雨下得很大。', 'c1');
+             INSERT INTO settings (id, project_id, category, name, content) VALUES
+                 ('st1', 'p1', 'character', '林雪', '医女'), ('st2', 'p1', 'character', '沈舟', '剑客');",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn builds_and_opens_review_copies() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let copy = build(&pool, "p1", &["st2".to_string()]).await.unwrap();
+        assert!(copy.read_only);
+        assert_eq!(copy.project.author.as_deref(), Some("沈舟"));
+        assert_eq!(copy.chapters.len(), 2);
+        assert_eq!(copy.chapters[0].content, "雨下得很大。");
+        assert_eq!(copy.chapters[0].scenes[0].title, "街头");
+        assert_eq!(copy.settings.len(), 1);
+        assert_eq!(copy.settings[0].name, "沈舟");
+        assert!(build(&pool, "p1", &["missing".to_string()]).await.is_err());
+
+        let plain = seal(&copy, None).unwrap();
+        assert!(!plain.contains("秘密提示词"));
+        assert_eq!(open(&plain, None).unwrap(), copy);
+
+        let sealed = seal(&copy, Some("试读")).unwrap();
+        assert!(!sealed.contains("雨下得很大"));
+        assert!(open(&sealed, None).is_err());
+        assert!(open(&sealed, Some("错误密码")).is_err());
+        assert_eq!(open(&sealed, Some("试读")).unwrap(), copy);
+        assert!(open(r#"{"version":"1.0","project":{}}"#, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn imported_copies_reject_writes_until_converted() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let copy = build(&pool, "p1", &["st1".to_string()]).await.unwrap();
+
+        let project = import(&pool, &copy).await.unwrap();
+        assert!(project.read_only);
+        let draft = chapter::full_draft(&pool, &project.id).await.unwrap();
+        assert_eq!(draft.chapters[0].content, "雨下得很大。");
+        let chapters = chapter::list(&pool, &project.id).await.unwrap();
+        assert_eq!(scene::list(&pool, &chapters[0].id).await.unwrap().len(), 1);
+
+        let err = chapter::create(&pool, &project.id, "第三章", None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(CommandError::from(err), CommandError::ReadOnlyProject);
+        assert!(scene::create(&pool, &chapters[0].id, "医馆", None)
+            .await
+            .is_err());
+        let rename = sqlx::query("UPDATE projects SET name = '改名' WHERE id = ?")
+            .bind(&project.id)
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert_eq!(CommandError::from(rename), CommandError::ReadOnlyProject);
+        assert!(sqlx::query("DELETE FROM settings WHERE project_id = ?")
+            .bind(&project.id)
+            .execute(&pool)
+            .await
+            .is_err());
+        // 原项目不受影响
+        assert!(chapter::create(&pool, "p1", "第三章", None, None)
+            .await
+            .is_ok());
+
+        let editable = convert(&pool, &project.id).await.unwrap();
+        assert!(!editable.read_only);
+        assert!(chapter::create(&pool, &project.id, "第三章", None, None)
+            .await
+            .is_ok());
+        assert!(convert(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_only_projects_can_be_deleted() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let copy = build(&pool, "p1", &[]).await.unwrap();
+        let project = import(&pool, &copy).await.unwrap();
+
+        sqlx::query("DELETE FROM projects WHERE id = ?")
+            .bind(&project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM chapters")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 2);
+    }
+}
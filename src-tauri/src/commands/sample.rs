@@ -0,0 +1,461 @@
+// 示例数据: 首次启动时生成演示项目, 所有数据带 is_sample 标记以便一键清除
+use serde_json::json;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::AppHandle;
+
+use crate::commands::setting;
+use crate::db;
+
+/// 示例数据涉及的表, 按删除顺序排列 (子表在前)
+const SAMPLE_TABLES: &[&str] = &[
+    "node_results",
+    "executions",
+    "nodes",
+    "workflows",
+    "setting_prompts",
+    "settings",
+    "projects",
+];
+
+const SAMPLE_OUTLINE: &str = "1. 雨夜, 林雪在青云城外的驿站遇到一位受伤的陌生人\n2. 陌生人留下一枚刻有古纹的玉佩后消失\n3. 林雪决定进城打听玉佩的来历";
+
+const SAMPLE_CHAPTER: &str = "雨下得很急。\n\n林雪推开驿站的木门, 潮气裹着泥土的味道扑面而来。角落里坐着一个浑身湿透的男人, 左臂缠着渗血的布条, 目光却比炉火还亮。\n\n\"姑娘, 可否借个火?\" 他低声问。\n\n林雪没有回答, 只是把油灯往他那边推了推。等她再抬头时, 座位上只剩一枚温润的玉佩, 上面的古纹在灯下若隐若现。";
+
+/// 首次启动时生成示例数据: 仅在从未生成过且没有任何项目时执行, 返回是否生成
+pub async fn seed_on_first_launch(pool: &SqlitePool) -> Result<bool, String> {
+    let seeded: Option<bool> =
+        sqlx::query_scalar("SELECT sample_data_seeded FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten();
+    if seeded.unwrap_or(false) {
+        return Ok(false);
+    }
+
+    let project_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if project_count == 0 {
+        seed(pool).await?;
+    }
+
+    // 无论是否生成都只检查一次, 用户删除示例后不会再次出现
+    sqlx::query("UPDATE global_config SET sample_data_seeded = 1 WHERE id = 1")
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(project_count == 0)
+}
+
+/// 生成示例项目, 返回项目 ID
+pub async fn seed(pool: &SqlitePool) -> Result<String, String> {
+    let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects WHERE is_sample = 1")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if existing > 0 {
+        return Err("示例数据已存在".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let project_id = insert_sample(&mut tx).await.map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(project_id)
+}
+
+async fn insert_sample(tx: &mut Transaction<'_, Sqlite>) -> Result<String, sqlx::Error> {
+    let now = db::now();
+    let project_id = db::new_id();
+    let workflow_id = db::new_id();
+
+    sqlx::query(
+        "INSERT INTO projects (id, name, description, is_sample, created_at, updated_at)
+         VALUES (?, ?, ?, 1, ?, ?)",
+    )
+    .bind(&project_id)
+    .bind("示例项目: 雨夜来客")
+    .bind("用于熟悉工作流、设定库与执行历史的演示项目, 可在设置中一键删除")
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut **tx)
+    .await?;
+
+    // 设定库
+    let settings = [
+        (
+            "character",
+            "林雪",
+            "十七岁, 驿站掌柜的女儿, 沉默寡言但观察力敏锐, 擅长辨认古物",
+            "high",
+        ),
+        (
+            "character",
+            "陌生人",
+            "身份不明的受伤男子, 左臂有伤, 言谈举止像是出身世家",
+            "medium",
+        ),
+        (
+            "worldview",
+            "青云城",
+            "群山环绕的古城, 城中古玩行当兴盛, 城外官道旁设有驿站",
+            "medium",
+        ),
+        (
+            "style",
+            "文风",
+            "短句为主, 注重环境与细节描写, 对话克制, 避免直白的心理独白",
+            "high",
+        ),
+    ];
+    let mut character_ids = Vec::new();
+    for (index, (category, name, content, priority)) in settings.iter().enumerate() {
+        let id = db::new_id();
+        sqlx::query(
+            "INSERT INTO settings (id, project_id, category, name, content, enabled, order_index,
+                                   injection_mode, priority, is_sample, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, 1, ?, 'manual', ?, 1, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&project_id)
+        .bind(category)
+        .bind(name)
+        .bind(content)
+        .bind(index as i64)
+        .bind(priority)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+        if *category == "character" || *category == "style" {
+            character_ids.push(id);
+        }
+    }
+
+    let prompts = [
+        (
+            "character",
+            "【角色设定】\n{{#each items}}- {{name}}：{{content}}\n{{/each}}",
+        ),
+        ("style", "【写作要求】请遵循以下文风：\n{{items}}"),
+    ];
+    for (category, template) in prompts {
+        sqlx::query(
+            "INSERT INTO setting_prompts (id, project_id, category, prompt_template, enabled, is_sample)
+             VALUES (?, ?, ?, ?, 1, 1)",
+        )
+        .bind(db::new_id())
+        .bind(&project_id)
+        .bind(category)
+        .bind(template)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    // 工作流: 大纲 → (循环: 正文 → 润色) → 输出
+    sqlx::query(
+        "INSERT INTO workflows (id, project_id, name, description, loop_max_count, timeout_seconds,
+                                is_sample, created_at, updated_at)
+         VALUES (?, ?, ?, ?, 10, 300, 1, ?, ?)",
+    )
+    .bind(&workflow_id)
+    .bind(&project_id)
+    .bind("章节生成")
+    .bind("根据用户输入的情节要点生成大纲, 再循环撰写并润色正文")
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut **tx)
+    .await?;
+
+    let block_id = db::new_id();
+    let start_id = db::new_id();
+    let outline_id = db::new_id();
+    let loop_start_id = db::new_id();
+    let draft_id = db::new_id();
+    let polish_id = db::new_id();
+    let loop_end_id = db::new_id();
+    let output_id = db::new_id();
+
+    let ai_config = |system: &str, user: String| {
+        json!({
+            "provider": "openai",
+            "model": "gpt-4o-mini",
+            "system_prompt": system,
+            "user_prompt": user,
+            "temperature": 0.8,
+            "enable_history": false,
+            "history_count": 0,
+            "setting_ids": character_ids,
+        })
+    };
+    let nodes = [
+        (&start_id, "start", "开始流程", json!({}), None, None),
+        (
+            &outline_id,
+            "ai_chat",
+            "生成大纲",
+            ai_config(
+                "你是一位经验丰富的小说策划。",
+                "请根据以下情节要点写出本章大纲, 分条列出:\n{{用户问题}}".to_string(),
+            ),
+            None,
+            None,
+        ),
+        (
+            &loop_start_id,
+            "loop_start",
+            "正文打磨",
+            json!({ "loop_type": "count", "max_iterations": 2 }),
+            Some(&block_id),
+            None,
+        ),
+        (
+            &draft_id,
+            "ai_chat",
+            "撰写正文",
+            ai_config(
+                "你是一位擅长古风悬疑的小说作者。",
+                format!("请根据大纲撰写本章正文:\n{{{{@{} > 大纲}}}}", outline_id),
+            ),
+            None,
+            Some(&block_id),
+        ),
+        (
+            &polish_id,
+            "ai_chat",
+            "润色",
+            ai_config(
+                "你是一位严谨的文字编辑。",
+                format!(
+                    "请润色以下正文, 保持情节不变:\n{{{{@{} > 正文}}}}",
+                    draft_id
+                ),
+            ),
+            None,
+            Some(&block_id),
+        ),
+        (
+            &loop_end_id,
+            "loop_end",
+            "end for",
+            json!({ "loop_start_id": block_id }),
+            Some(&block_id),
+            None,
+        ),
+        (
+            &output_id,
+            "output",
+            "输出",
+            json!({ "format": "markdown" }),
+            None,
+            None,
+        ),
+    ];
+    for (index, (id, node_type, name, config, node_block_id, parent_block_id)) in
+        nodes.iter().enumerate()
+    {
+        sqlx::query(
+            "INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id,
+                                parent_block_id, is_sample, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)",
+        )
+        .bind(id.as_str())
+        .bind(&workflow_id)
+        .bind(node_type)
+        .bind(name)
+        .bind(config.to_string())
+        .bind(index as i64)
+        .bind(node_block_id.map(String::as_str))
+        .bind(parent_block_id.map(String::as_str))
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    // 一次已完成的执行记录
+    let execution_id = db::new_id();
+    let input = "林雪在雨夜的驿站遇到神秘来客, 来客留下玉佩后消失";
+    sqlx::query(
+        "INSERT INTO executions (id, workflow_id, status, input, final_output, variables_snapshot,
+                                 started_at, finished_at, is_sample)
+         VALUES (?, ?, 'completed', ?, ?, ?, ?, ?, 1)",
+    )
+    .bind(&execution_id)
+    .bind(&workflow_id)
+    .bind(input)
+    .bind(SAMPLE_CHAPTER)
+    .bind(json!({ "用户问题": input }).to_string())
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut **tx)
+    .await?;
+
+    let results = [
+        (&start_id, 1, input, input),
+        (&outline_id, 1, input, SAMPLE_OUTLINE),
+        (&draft_id, 1, SAMPLE_OUTLINE, SAMPLE_CHAPTER),
+        (&polish_id, 1, SAMPLE_CHAPTER, SAMPLE_CHAPTER),
+        (&draft_id, 2, SAMPLE_OUTLINE, SAMPLE_CHAPTER),
+        (&polish_id, 2, SAMPLE_CHAPTER, SAMPLE_CHAPTER),
+        (&output_id, 1, SAMPLE_CHAPTER, SAMPLE_CHAPTER),
+    ];
+    for (node_id, iteration, node_input, output) in results {
+        let token_usage = json!({
+            "promptTokens": node_input.chars().count(),
+            "completionTokens": output.chars().count(),
+            "totalTokens": node_input.chars().count() + output.chars().count(),
+        });
+        sqlx::query(
+            "INSERT INTO node_results (id, execution_id, node_id, iteration, input, output, status,
+                                       token_usage, is_sample, started_at, finished_at)
+             VALUES (?, ?, ?, ?, ?, ?, 'completed', ?, 1, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(&execution_id)
+        .bind(node_id.as_str())
+        .bind(iteration)
+        .bind(node_input)
+        .bind(output)
+        .bind(token_usage.to_string())
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(project_id)
+}
+
+/// 删除全部示例数据, 返回删除的行数
+pub async fn delete_sample(pool: &SqlitePool) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut deleted = 0;
+    for table in SAMPLE_TABLES {
+        let result = sqlx::query(&format!("DELETE FROM {} WHERE is_sample = 1", table))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        deleted += result.rows_affected();
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// 示例设定的 ID, 用于生成与删除后的变更通知
+pub async fn sample_setting_ids(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    sqlx::query_scalar("SELECT id FROM settings WHERE is_sample = 1 ORDER BY rowid")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn seed_sample_data(app: AppHandle) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    let project_id = seed(&pool).await?;
+    let setting_ids = sample_setting_ids(&pool).await?;
+    setting::notify_changed(&app, &pool, &setting_ids, "imported").await;
+    Ok(project_id)
+}
+
+#[tauri::command]
+pub async fn delete_sample_data(app: AppHandle) -> Result<u64, String> {
+    let pool = db::pool(&app).await?;
+    let setting_ids = sample_setting_ids(&pool).await?;
+    let deleted = delete_sample(&pool).await?;
+    setting::notify_changed(&app, &pool, &setting_ids, "deleted").await;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn seeds_only_once_on_empty_database() {
+        let pool = db::test_pool().await;
+
+        assert!(seed_on_first_launch(&pool).await.unwrap());
+        assert_eq!(count(&pool, "projects").await, 1);
+        assert_eq!(count(&pool, "nodes").await, 7);
+        assert_eq!(count(&pool, "node_results").await, 7);
+
+        // 删除后再次启动不会重新生成
+        delete_sample(&pool).await.unwrap();
+        assert!(!seed_on_first_launch(&pool).await.unwrap());
+        assert_eq!(count(&pool, "projects").await, 0);
+    }
+
+    #[tokio::test]
+    async fn skips_seeding_when_projects_exist() {
+        let pool = db::test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name) VALUES ('p1', '我的小说')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(!seed_on_first_launch(&pool).await.unwrap());
+        assert_eq!(count(&pool, "projects").await, 1);
+    }
+
+    #[tokio::test]
+    async fn delete_keeps_user_data() {
+        let pool = db::test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name) VALUES ('p1', '我的小说')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        seed(&pool).await.unwrap();
+        assert!(seed(&pool).await.is_err());
+
+        assert!(delete_sample(&pool).await.unwrap() > 0);
+        for table in SAMPLE_TABLES {
+            let remaining: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {} WHERE is_sample = 1",
+                table
+            ))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(remaining, 0);
+        }
+        assert_eq!(count(&pool, "projects").await, 1);
+    }
+
+    #[tokio::test]
+    async fn reports_setting_changes_for_import_and_delete() {
+        let pool = db::test_pool().await;
+        let project_id = seed(&pool).await.unwrap();
+        let setting_ids = sample_setting_ids(&pool).await.unwrap();
+        assert!(!setting_ids.is_empty());
+
+        let imported = setting::changes_for(&pool, &setting_ids, "imported")
+            .await
+            .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].project_id, project_id);
+        assert_eq!(imported[0].setting_ids, setting_ids);
+
+        // 删除后依据删除记录仍能定位所属项目
+        delete_sample(&pool).await.unwrap();
+        let deleted = setting::changes_for(&pool, &setting_ids, "deleted")
+            .await
+            .unwrap();
+        assert_eq!(deleted[0].project_id, project_id);
+        assert_eq!(deleted[0].change_kind, "deleted");
+        let delta = setting::changed_since(&pool, &project_id, "2000-01-01T00:00:00Z")
+            .await
+            .unwrap();
+        assert!(delta.changed.is_empty());
+        assert_eq!(delta.deleted_ids.len(), setting_ids.len());
+    }
+}
@@ -0,0 +1,153 @@
+// AI 节点采样参数: 节点配置的 sampling 对象覆盖旧版顶层参数, 按提供商校验取值范围.
+// 范围与前端 src/lib/ai/sampling.ts 保持一致
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 采样参数, 未设置的字段使用模型默认值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub max_tokens: Option<i64>,
+    pub presence_penalty: Option<f64>,
+    pub frequency_penalty: Option<f64>,
+}
+
+// 提供商支持的取值范围; penalties 为 None 表示不支持 presence / frequency penalty
+struct ProviderLimits {
+    name: &'static str,
+    temperature: (f64, f64),
+    penalties: Option<(f64, f64)>,
+}
+
+fn provider_limits(provider: &str) -> Option<ProviderLimits> {
+    match provider {
+        "openai" => Some(ProviderLimits {
+            name: "OpenAI",
+            temperature: (0.0, 2.0),
+            penalties: Some((-2.0, 2.0)),
+        }),
+        "gemini" => Some(ProviderLimits {
+            name: "Gemini",
+            temperature: (0.0, 2.0),
+            penalties: Some((-2.0, 2.0)),
+        }),
+        "claude" => Some(ProviderLimits {
+            name: "Claude",
+            temperature: (0.0, 1.0),
+            penalties: None,
+        }),
+        _ => None,
+    }
+}
+
+/// 从节点配置解析生效的采样参数: `sampling` 中的字段优先, 其余沿用顶层的
+/// temperature / top_p / max_tokens
+pub fn resolve(config: &Value) -> Result<SamplingParams, String> {
+    let mut params: SamplingParams = match config.get("sampling") {
+        None | Some(Value::Null) => SamplingParams::default(),
+        Some(sampling) => serde_json::from_value(sampling.clone())
+            .map_err(|e| format!("采样参数格式无效: {}", e))?,
+    };
+    params.temperature = params
+        .temperature
+        .or_else(|| config.get("temperature").and_then(Value::as_f64));
+    params.top_p = params
+        .top_p
+        .or_else(|| config.get("top_p").and_then(Value::as_f64));
+    params.max_tokens = params
+        .max_tokens
+        .or_else(|| config.get("max_tokens").and_then(Value::as_i64));
+    Ok(params)
+}
+
+fn check_range(issues: &mut Vec<String>, field: &str, value: Option<f64>, (min, max): (f64, f64)) {
+    if let Some(value) = value {
+        if !(min..=max).contains(&value) {
+            issues.push(format!(
+                "{} 须在 {} 到 {} 之间, 当前为 {}",
+                field, min, max, value
+            ));
+        }
+    }
+}
+
+/// 按提供商校验采样参数, 返回问题说明; 全部有效时为空
+pub fn validate(provider: &str, params: &SamplingParams) -> Vec<String> {
+    let Some(limits) = provider_limits(provider) else {
+        return vec![format!("不支持的 AI 提供商: {}", provider)];
+    };
+    let mut issues = Vec::new();
+    check_range(
+        &mut issues,
+        "temperature",
+        params.temperature,
+        limits.temperature,
+    );
+    check_range(&mut issues, "top_p", params.top_p, (0.0, 1.0));
+    if params.max_tokens.is_some_and(|n| n < 1) {
+        issues.push("max_tokens 须为正整数".to_string());
+    }
+    for (field, value) in [
+        ("presence_penalty", params.presence_penalty),
+        ("frequency_penalty", params.frequency_penalty),
+    ] {
+        match limits.penalties {
+            Some(range) => check_range(&mut issues, field, value, range),
+            None if value.is_some() => {
+                issues.push(format!("{} 不支持 {}", limits.name, field));
+            }
+            None => {}
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sampling_overrides_legacy_fields() {
+        let config = json!({
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "max_tokens": 1000,
+            "sampling": { "temperature": 1.2, "presence_penalty": 0.5 },
+        });
+        let params = resolve(&config).unwrap();
+        assert_eq!(params.temperature, Some(1.2));
+        assert_eq!(params.top_p, Some(0.9));
+        assert_eq!(params.max_tokens, Some(1000));
+        assert_eq!(params.presence_penalty, Some(0.5));
+        assert!(resolve(&json!({ "sampling": { "temperature": "hot" } })).is_err());
+        assert_eq!(resolve(&json!({})).unwrap(), SamplingParams::default());
+    }
+
+    #[test]
+    fn validates_ranges_per_provider() {
+        let params = SamplingParams {
+            temperature: Some(1.5),
+            frequency_penalty: Some(0.2),
+            ..Default::default()
+        };
+        assert!(validate("openai", &params).is_empty());
+        assert_eq!(
+            validate("claude", &params),
+            vec![
+                "temperature 须在 0 到 1 之间, 当前为 1.5",
+                "Claude 不支持 frequency_penalty",
+            ]
+        );
+
+        let invalid = SamplingParams {
+            top_p: Some(1.5),
+            max_tokens: Some(0),
+            presence_penalty: Some(-3.0),
+            ..Default::default()
+        };
+        assert_eq!(validate("gemini", &invalid).len(), 3);
+        assert_eq!(validate("mistral", &invalid).len(), 1);
+    }
+}
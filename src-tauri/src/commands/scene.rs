@@ -0,0 +1,454 @@
+// 场景: 章节之下按 order_index 排列的场景细分, 状态取值与章节一致
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::chapter::{self, CHAPTER_STATUSES};
+use crate::db;
+use crate::models::Scene;
+
+// 场景正文: 关联到场景的执行记录的最终输出
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneContent {
+    pub scene_title: String,
+    pub chapter_title: String,
+    pub content: String,
+    /// 各条最终输出的字符数之和, 与章节字数口径一致
+    pub word_count: i64,
+}
+
+fn validate(title: &str, status: &str) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("场景标题不能为空".to_string());
+    }
+    if !CHAPTER_STATUSES.contains(&status) {
+        return Err(format!("不支持的场景状态: {}", status));
+    }
+    Ok(())
+}
+
+/// 获取单个场景
+pub async fn get(pool: &SqlitePool, scene_id: &str) -> Result<Scene, String> {
+    sqlx::query_as::<_, Scene>("SELECT * FROM scenes WHERE id = ?")
+        .bind(scene_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("场景不存在: {}", scene_id))
+}
+
+/// 按顺序列出章节的场景
+pub async fn list(pool: &SqlitePool, chapter_id: &str) -> Result<Vec<Scene>, String> {
+    sqlx::query_as::<_, Scene>(
+        "SELECT * FROM scenes WHERE chapter_id = ? ORDER BY order_index, created_at",
+    )
+    .bind(chapter_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 创建场景, 排在章节末尾, 初始状态为 draft
+pub async fn create(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    title: &str,
+    description: Option<&str>,
+) -> Result<Scene, String> {
+    validate(title, CHAPTER_STATUSES[0])?;
+    chapter::get(pool, chapter_id).await?;
+
+    let order_index: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(order_index) + 1, 0) FROM scenes WHERE chapter_id = ?",
+    )
+    .bind(chapter_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let now = db::now();
+    let scene = Scene {
+        id: db::new_id(),
+        chapter_id: chapter_id.to_string(),
+        title: title.trim().to_string(),
+        description: description.map(str::to_string),
+        order_index,
+        status: CHAPTER_STATUSES[0].to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO scenes (id, chapter_id, title, description, order_index, status, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&scene.id)
+    .bind(&scene.chapter_id)
+    .bind(&scene.title)
+    .bind(&scene.description)
+    .bind(scene.order_index)
+    .bind(&scene.status)
+    .bind(&scene.created_at)
+    .bind(&scene.updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(scene)
+}
+
+/// 更新场景标题、描述与状态
+pub async fn update(
+    pool: &SqlitePool,
+    scene_id: &str,
+    title: &str,
+    description: Option<&str>,
+    status: &str,
+) -> Result<Scene, String> {
+    validate(title, status)?;
+    let mut scene = get(pool, scene_id).await?;
+    scene.title = title.trim().to_string();
+    scene.description = description.map(str::to_string);
+    scene.status = status.to_string();
+    scene.updated_at = db::now();
+    sqlx::query(
+        "UPDATE scenes SET title = ?, description = ?, status = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&scene.title)
+    .bind(&scene.description)
+    .bind(&scene.status)
+    .bind(&scene.updated_at)
+    .bind(scene_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(scene)
+}
+
+/// 删除场景: 关联的执行记录只解除场景关联 (仍属于章节),
+/// 其余场景的排序号重新编为连续的 0..n
+pub async fn delete(pool: &SqlitePool, scene_id: &str) -> Result<(), String> {
+    let scene = get(pool, scene_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE executions SET scene_id = NULL WHERE scene_id = ?")
+        .bind(scene_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM scenes WHERE id = ?")
+        .bind(scene_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let remaining: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT id, order_index FROM scenes WHERE chapter_id = ? ORDER BY order_index, created_at, id",
+    )
+    .bind(&scene.chapter_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let now = db::now();
+    for (index, (id, order_index)) in remaining.iter().enumerate() {
+        if *order_index == index as i64 {
+            continue;
+        }
+        sqlx::query("UPDATE scenes SET order_index = ?, updated_at = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 按给定顺序重排场景; 列表须恰好包含章节的全部场景
+pub async fn reorder(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    ordered_ids: &[String],
+) -> Result<Vec<Scene>, String> {
+    let existing: HashSet<String> = list(pool, chapter_id)
+        .await?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    let foreign: Vec<&str> = ordered_ids
+        .iter()
+        .filter(|id| !existing.contains(*id))
+        .map(String::as_str)
+        .collect();
+    if !foreign.is_empty() {
+        return Err(format!("场景不属于该章节: {}", foreign.join(", ")));
+    }
+    let requested: HashSet<String> = ordered_ids.iter().cloned().collect();
+    if requested.len() != ordered_ids.len() {
+        return Err("场景列表中有重复的场景".to_string());
+    }
+    if requested != existing {
+        return Err("场景列表须包含章节的全部场景".to_string());
+    }
+
+    let now = db::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (index, id) in ordered_ids.iter().enumerate() {
+        sqlx::query("UPDATE scenes SET order_index = ?, updated_at = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    list(pool, chapter_id).await
+}
+
+/// 把执行记录关联到场景, 同时关联到场景所属章节 (须与执行记录属于同一项目)
+pub async fn link_execution(
+    pool: &SqlitePool,
+    execution_id: &str,
+    scene_id: &str,
+) -> Result<(), String> {
+    let scene = get(pool, scene_id).await?;
+    chapter::link_execution(pool, execution_id, &scene.chapter_id).await?;
+    sqlx::query("UPDATE executions SET scene_id = ? WHERE id = ?")
+        .bind(scene_id)
+        .bind(execution_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 场景正文: 已完成执行的最终输出按开始时间拼接, 各条之间空一行
+pub async fn content(pool: &SqlitePool, scene_id: &str) -> Result<SceneContent, String> {
+    let scene = get(pool, scene_id).await?;
+    let chapter = chapter::get(pool, &scene.chapter_id).await?;
+    let outputs: Vec<String> = sqlx::query_scalar(
+        "SELECT final_output FROM executions
+         WHERE scene_id = ? AND status = 'completed' AND final_output IS NOT NULL
+         ORDER BY started_at",
+    )
+    .bind(scene_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let outputs: Vec<&str> = outputs.iter().map(|o| o.trim()).collect();
+    Ok(SceneContent {
+        scene_title: scene.title,
+        chapter_title: chapter.title,
+        content: outputs.join("\n\n"),
+        word_count: outputs.iter().map(|o| o.chars().count() as i64).sum(),
+    })
+}
+
+#[tauri::command]
+pub async fn create_scene(
+    app: AppHandle,
+    chapter_id: String,
+    title: String,
+    description: Option<String>,
+) -> Result<Scene, String> {
+    let pool = db::pool(&app).await?;
+    create(&pool, &chapter_id, &title, description.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_scene(app: AppHandle, scene_id: String) -> Result<Scene, String> {
+    let pool = db::pool(&app).await?;
+    get(&pool, &scene_id).await
+}
+
+#[tauri::command]
+pub async fn update_scene(
+    app: AppHandle,
+    scene_id: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+) -> Result<Scene, String> {
+    let pool = db::pool(&app).await?;
+    update(&pool, &scene_id, &title, description.as_deref(), &status).await
+}
+
+#[tauri::command]
+pub async fn delete_scene(app: AppHandle, scene_id: String) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    delete(&pool, &scene_id).await
+}
+
+#[tauri::command]
+pub async fn list_scenes_for_chapter(
+    app: AppHandle,
+    chapter_id: String,
+) -> Result<Vec<Scene>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &chapter_id).await
+}
+
+#[tauri::command]
+pub async fn reorder_scenes(
+    app: AppHandle,
+    chapter_id: String,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<Scene>, String> {
+    let pool = db::pool(&app).await?;
+    reorder(&pool, &chapter_id, &ordered_ids).await
+}
+
+#[tauri::command]
+pub async fn link_execution_to_scene(
+    app: AppHandle,
+    execution_id: String,
+    scene_id: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    link_execution(&pool, &execution_id, &scene_id).await
+}
+
+#[tauri::command]
+pub async fn get_scene_execution_content(
+    app: AppHandle,
+    scene_id: String,
+) -> Result<SceneContent, String> {
+    let pool = db::pool(&app).await?;
+    content(&pool, &scene_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Execution;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '另一个项目');
+             INSERT INTO chapters (id, project_id, title) VALUES ('c1', 'p1', '雨夜'), ('c2', 'p1', '玉佩');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成'), ('w2', 'p2', '正文生成');
+             INSERT INTO executions (id, workflow_id, status) VALUES ('e1', 'w1', 'completed'), ('e2', 'w2', 'completed');",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn manages_scenes_within_chapter() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let first = create(&pool, "c1", "街头", Some("林雪撞见沈舟"))
+            .await
+            .unwrap();
+        let second = create(&pool, "c1", "医馆", None).await.unwrap();
+        let third = create(&pool, "c1", "雨停", None).await.unwrap();
+        assert_eq!(
+            (first.order_index, second.order_index, third.order_index),
+            (0, 1, 2)
+        );
+        assert_eq!(first.status, "draft");
+        assert_eq!(
+            create(&pool, "c2", "开篇", None).await.unwrap().order_index,
+            0
+        );
+        assert!(create(&pool, "c1", " ", None).await.is_err());
+        assert!(create(&pool, "missing", "街头", None).await.is_err());
+
+        let updated = update(&pool, &second.id, "药铺", Some("包扎伤口"), "in_progress")
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &second.id).await.unwrap().title, updated.title);
+        assert!(update(&pool, &second.id, "药铺", None, "published")
+            .await
+            .is_err());
+
+        let order = vec![third.id.clone(), first.id.clone(), second.id.clone()];
+        let reordered = reorder(&pool, "c1", &order).await.unwrap();
+        let ids: Vec<String> = reordered.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, order);
+        assert!(reorder(&pool, "c1", &order[..2]).await.is_err());
+        assert!(reorder(&pool, "c2", &order).await.is_err());
+
+        delete(&pool, &third.id).await.unwrap();
+        let remaining = list(&pool, "c1").await.unwrap();
+        assert_eq!(
+            remaining
+                .iter()
+                .map(|s| (s.title.as_str(), s.order_index))
+                .collect::<Vec<_>>(),
+            vec![("街头", 0), ("药铺", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn links_execution_to_scene_and_chapter() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let scene = create(&pool, "c1", "街头", None).await.unwrap();
+
+        link_execution(&pool, "e1", &scene.id).await.unwrap();
+        let execution: Execution = sqlx::query_as("SELECT * FROM executions WHERE id = 'e1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(execution.chapter_id.as_deref(), Some("c1"));
+        assert_eq!(execution.scene_id.as_deref(), Some(scene.id.as_str()));
+        assert!(link_execution(&pool, "e2", &scene.id).await.is_err());
+        assert!(link_execution(&pool, "e1", "missing").await.is_err());
+
+        // 改关联到其他章节时解除场景关联
+        chapter::link_execution(&pool, "e1", "c2").await.unwrap();
+        let execution: Execution = sqlx::query_as("SELECT * FROM executions WHERE id = 'e1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(execution.scene_id.is_none());
+        link_execution(&pool, "e1", &scene.id).await.unwrap();
+
+        // 删除场景后执行记录仍属于章节
+        delete(&pool, &scene.id).await.unwrap();
+        let execution: Execution = sqlx::query_as("SELECT * FROM executions WHERE id = 'e1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(execution.chapter_id.as_deref(), Some("c1"));
+        assert!(execution.scene_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn concatenates_scene_outputs() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let scene = create(&pool, "c1", "街头", None).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO executions (id, workflow_id, status, final_output, started_at) VALUES
+                 ('e3', 'w1', 'completed', '  雨停了。', '2026-01-02'),
+                 ('e4', 'w1', 'failed', '半截', '2026-01-03'),
+                 ('e5', 'w1', 'completed', NULL, '2026-01-04');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let empty = content(&pool, &scene.id).await.unwrap();
+        assert_eq!((empty.content.as_str(), empty.word_count), ("", 0));
+
+        sqlx::query("UPDATE executions SET final_output = '林雪撑伞。', started_at = '2026-01-01' WHERE id = 'e1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for id in ["e3", "e1", "e4", "e5"] {
+            link_execution(&pool, id, &scene.id).await.unwrap();
+        }
+
+        let found = content(&pool, &scene.id).await.unwrap();
+        assert_eq!(found.scene_title, "街头");
+        assert_eq!(found.chapter_title, "雨夜");
+        assert_eq!(found.content, "林雪撑伞。\n\n雨停了。");
+        assert_eq!(found.word_count, 9);
+        assert!(content(&pool, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,1223 @@
+// 设定库查询
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{Setting, SettingPrompt};
+use crate::{db, injection, tokens};
+
+/// 世界观条目允许的分类
+pub const WORLD_BUILDING_CATEGORIES: &[&str] =
+    &["character", "location", "event", "faction", "item"];
+
+/// 设定正文模板允许的占位符
+pub const CONTENT_TEMPLATE_PLACEHOLDERS: &[&str] = &["name"];
+
+/// 设定写入提交后发出的事件, 负载为 [`SettingsChanged`]
+pub const SETTINGS_CHANGED_EVENT: &str = "settings:changed";
+
+// 设定变更通知: 同一项目的变更合并为一条
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingsChanged {
+    pub project_id: String,
+    pub setting_ids: Vec<String>,
+    // created / updated / deleted / imported
+    pub change_kind: String,
+}
+
+// 某时刻之后的设定变更, 供增量刷新
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsDelta {
+    pub changed: Vec<Setting>,
+    pub deleted_ids: Vec<String>,
+    // 查询时刻, 下次增量刷新时作为 since 传入
+    pub as_of: String,
+}
+
+// 世界观条目: 以固定分类写入 settings 的类型化包装
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldBuildingEntry {
+    pub name: String,
+    pub category: String,
+    pub content: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// 单条设定的 token 开销
+#[derive(Debug, Serialize)]
+pub struct SettingTokenCost {
+    pub setting_id: String,
+    pub name: String,
+    /// 设定内容本身
+    pub raw_tokens: u64,
+    /// 按分类注入模板渲染后
+    pub rendered_tokens: u64,
+}
+
+// 人物关系图中的人物
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterNode {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+}
+
+// 人物关系边: from 的设定内容中提到 to 的次数
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterRelationship {
+    pub from_id: String,
+    pub to_id: String,
+    pub mention_count: i64,
+}
+
+// 设定之间的矛盾或重复
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Inconsistency {
+    // duplicate_character / location_conflict / cross_category_duplicate
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub description: String,
+    pub setting_id_a: String,
+    pub setting_id_b: String,
+}
+
+// 项目人物关系图
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterNetwork {
+    pub characters: Vec<CharacterNode>,
+    pub relationships: Vec<CharacterRelationship>,
+}
+
+// 地点层级树节点
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationNode {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    pub children: Vec<LocationNode>,
+}
+
+/// 按分类与名称查找项目内的设定
+pub async fn find_by_name(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: &str,
+    name: &str,
+) -> Result<Option<Setting>, String> {
+    sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? AND category = ? AND name = ?
+         ORDER BY order_index LIMIT 1",
+    )
+    .bind(project_id)
+    .bind(category)
+    .bind(name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 项目内该分类下是否存在同名设定
+pub async fn exists(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: &str,
+    name: &str,
+) -> Result<bool, String> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM settings WHERE project_id = ? AND category = ? AND name = ?)",
+    )
+    .bind(project_id)
+    .bind(category)
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 创建设定, 排在该分类末尾, 返回设定 ID
+pub async fn create(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: &str,
+    name: &str,
+    content: &str,
+    enabled: bool,
+) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("设定名称不能为空".to_string());
+    }
+    let project_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)")
+            .bind(project_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if !project_exists {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+
+    let order_index: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(order_index) + 1, 0) FROM settings WHERE project_id = ? AND category = ?",
+    )
+    .bind(project_id)
+    .bind(category)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let id = db::new_id();
+    let now = db::now();
+    sqlx::query(
+        "INSERT INTO settings (id, project_id, category, name, content, enabled, order_index, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(project_id)
+    .bind(category)
+    .bind(name)
+    .bind(content)
+    .bind(enabled)
+    .bind(order_index)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// 按分类的正文模板创建设定, `{{name}}` 替换为设定名称; 未配置模板时使用分类默认模板
+pub async fn create_from_template(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: &str,
+    name: &str,
+) -> Result<String, String> {
+    let template: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT content_template FROM setting_prompts WHERE project_id = ? AND category = ?",
+    )
+    .bind(project_id)
+    .bind(category)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let template = template
+        .flatten()
+        .unwrap_or_else(|| injection::default_content_template(category).to_string());
+    let content = template.replace("{{name}}", name.trim());
+    create(pool, project_id, category, name, &content, true).await
+}
+
+/// 设置分类的正文模板, 传入空内容时清除 (恢复默认模板)
+pub async fn update_content_template(
+    pool: &SqlitePool,
+    category_id: &str,
+    template: Option<&str>,
+) -> Result<(), String> {
+    let template = template.filter(|t| !t.trim().is_empty());
+    if let Some(template) = template {
+        injection::validate_placeholders(template, CONTENT_TEMPLATE_PLACEHOLDERS)?;
+    }
+    let result = sqlx::query("UPDATE setting_prompts SET content_template = ? WHERE id = ?")
+        .bind(template)
+        .bind(category_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("设定分类不存在: {}", category_id));
+    }
+    Ok(())
+}
+
+/// 创建世界观条目, 分类须在白名单内
+pub async fn create_world_entry(
+    pool: &SqlitePool,
+    project_id: &str,
+    entry: &WorldBuildingEntry,
+) -> Result<String, String> {
+    if !WORLD_BUILDING_CATEGORIES.contains(&entry.category.as_str()) {
+        return Err(format!("不支持的世界观分类: {}", entry.category));
+    }
+    create(
+        pool,
+        project_id,
+        &entry.category,
+        &entry.name,
+        &entry.content,
+        entry.enabled,
+    )
+    .await
+}
+
+/// 列出世界观条目, 可按分类筛选; 按分类与排序号排列
+pub async fn list_world_entries(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: Option<&str>,
+) -> Result<Vec<Setting>, String> {
+    let categories: Vec<&str> = match category {
+        Some(category) if WORLD_BUILDING_CATEGORIES.contains(&category) => vec![category],
+        Some(category) => return Err(format!("不支持的世界观分类: {}", category)),
+        None => WORLD_BUILDING_CATEGORIES.to_vec(),
+    };
+    let mut sql: QueryBuilder<Sqlite> =
+        QueryBuilder::new("SELECT * FROM settings WHERE project_id = ");
+    sql.push_bind(project_id).push(" AND category IN (");
+    let mut separated = sql.separated(", ");
+    for category in categories {
+        separated.push_bind(category);
+    }
+    sql.push(") ORDER BY category, order_index");
+    sql.build_query_as::<Setting>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 由人物设定构建关系图: 在每个人物的内容中匹配其他人物的名字, 按出现次数连边
+pub fn build_character_network(characters: &[Setting]) -> CharacterNetwork {
+    let mut relationships = Vec::new();
+    for from in characters {
+        for to in characters {
+            let name = to.name.trim();
+            if from.id == to.id || name.is_empty() {
+                continue;
+            }
+            let mention_count = from.content.matches(name).count() as i64;
+            if mention_count > 0 {
+                relationships.push(CharacterRelationship {
+                    from_id: from.id.clone(),
+                    to_id: to.id.clone(),
+                    mention_count,
+                });
+            }
+        }
+    }
+    CharacterNetwork {
+        characters: characters
+            .iter()
+            .map(|c| CharacterNode {
+                id: c.id.clone(),
+                name: c.name.clone(),
+                enabled: c.enabled,
+            })
+            .collect(),
+        relationships,
+    }
+}
+
+/// 项目的人物关系图
+pub async fn character_network(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<CharacterNetwork, String> {
+    let characters = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? AND category = 'character' ORDER BY order_index",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(build_character_network(&characters))
+}
+
+fn location_subtree(
+    location: &Setting,
+    children: &HashMap<&str, Vec<&Setting>>,
+    visited: &mut HashSet<String>,
+) -> LocationNode {
+    visited.insert(location.id.clone());
+    let mut node = LocationNode {
+        id: location.id.clone(),
+        name: location.name.clone(),
+        content: location.content.clone(),
+        children: Vec::new(),
+    };
+    for child in children.get(location.id.as_str()).into_iter().flatten() {
+        if !visited.contains(&child.id) {
+            node.children
+                .push(location_subtree(child, children, visited));
+        }
+    }
+    node
+}
+
+/// 由 parent_id 构建地点树: 父级不是本项目地点的条目作为根; 成环的条目从环中首个条目断开
+pub fn build_location_tree(locations: &[Setting]) -> Vec<LocationNode> {
+    let ids: HashSet<&str> = locations.iter().map(|l| l.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&Setting>> = HashMap::new();
+    let mut roots = Vec::new();
+    for location in locations {
+        match location.parent_id.as_deref().filter(|p| ids.contains(p)) {
+            Some(parent) => children.entry(parent).or_default().push(location),
+            None => roots.push(location),
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut tree: Vec<LocationNode> = roots
+        .into_iter()
+        .map(|root| location_subtree(root, &children, &mut visited))
+        .collect();
+    for location in locations {
+        if !visited.contains(&location.id) {
+            tree.push(location_subtree(location, &children, &mut visited));
+        }
+    }
+    tree
+}
+
+/// 项目的地点层级
+pub async fn location_hierarchy(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<LocationNode>, String> {
+    let locations = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? AND category = 'location' ORDER BY order_index",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(build_location_tree(&locations))
+}
+
+// 比较描述时忽略空白差异
+fn normalize_description(content: &str) -> String {
+    content.split_whitespace().collect()
+}
+
+// 两个同名地点的矛盾: 同一事实取值不同, 或没有可比较的事实时描述不同
+fn location_conflict(a: &Setting, b: &Setting) -> Option<String> {
+    let (facts_a, facts_b) = (a.facts.as_ref(), b.facts.as_ref());
+    let conflicting: Vec<String> = facts_a
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let other = facts_b?.get(key)?;
+            (other.trim() != value.trim()).then(|| format!("{}: {} / {}", key, value, other))
+        })
+        .collect();
+    if !conflicting.is_empty() {
+        return Some(format!(
+            "地点 \"{}\" 的事实不一致 ({})",
+            a.name.trim(),
+            conflicting.join(", ")
+        ));
+    }
+    (normalize_description(&a.content) != normalize_description(&b.content))
+        .then(|| format!("地点 \"{}\" 有两条描述不同的设定", a.name.trim()))
+}
+
+/// 连贯性检查: 同名人物设定、描述矛盾的同名地点、跨分类重名,
+/// 每对设定最多报告一条
+pub fn find_inconsistencies(settings: &[Setting]) -> Vec<Inconsistency> {
+    let mut found = Vec::new();
+    for (i, a) in settings.iter().enumerate() {
+        let name = a.name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        for b in &settings[i + 1..] {
+            if b.name.trim() != name {
+                continue;
+            }
+            let (type_, description) = if a.category != b.category {
+                (
+                    "cross_category_duplicate",
+                    format!(
+                        "\"{}\" 同时出现在 {} 与 {} 分类中",
+                        name, a.category, b.category
+                    ),
+                )
+            } else if a.category == "character" {
+                (
+                    "duplicate_character",
+                    format!("人物 \"{}\" 有重复的设定", name),
+                )
+            } else if a.category == "location" {
+                match location_conflict(a, b) {
+                    Some(description) => ("location_conflict", description),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+            found.push(Inconsistency {
+                type_: type_.to_string(),
+                description,
+                setting_id_a: a.id.clone(),
+                setting_id_b: b.id.clone(),
+            });
+        }
+    }
+    found
+}
+
+/// 检查项目设定的连贯性
+pub async fn inconsistencies(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<Inconsistency>, String> {
+    let settings = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? ORDER BY category, order_index, created_at",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(find_inconsistencies(&settings))
+}
+
+/// 读取设定的结构化事实
+pub async fn facts(
+    pool: &SqlitePool,
+    setting_id: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let raw: Option<Option<String>> = sqlx::query_scalar("SELECT facts FROM settings WHERE id = ?")
+        .bind(setting_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    match raw.ok_or_else(|| format!("设定不存在: {}", setting_id))? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("设定事实格式错误: {}", e)),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+async fn save_facts(
+    pool: &SqlitePool,
+    setting_id: &str,
+    facts: &BTreeMap<String, String>,
+) -> Result<(), String> {
+    let raw = if facts.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(facts).map_err(|e| e.to_string())?)
+    };
+    sqlx::query("UPDATE settings SET facts = ?, updated_at = ? WHERE id = ?")
+        .bind(raw)
+        .bind(db::now())
+        .bind(setting_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 设置一条事实 (已存在的键会被覆盖), 返回更新后的全部事实
+pub async fn set_fact(
+    pool: &SqlitePool,
+    setting_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("事实名称不能为空".to_string());
+    }
+    let mut facts = facts(pool, setting_id).await?;
+    facts.insert(key.to_string(), value.to_string());
+    save_facts(pool, setting_id, &facts).await?;
+    Ok(facts)
+}
+
+/// 删除一条事实, 返回更新后的全部事实
+pub async fn remove_fact(
+    pool: &SqlitePool,
+    setting_id: &str,
+    key: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut facts = facts(pool, setting_id).await?;
+    if facts.remove(key.trim()).is_some() {
+        save_facts(pool, setting_id, &facts).await?;
+    }
+    Ok(facts)
+}
+
+/// 用项目设定渲染模板中的事实引用, 供编辑器预览
+pub async fn render_references(
+    pool: &SqlitePool,
+    project_id: &str,
+    template: &str,
+) -> Result<String, String> {
+    let settings = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? ORDER BY category, order_index",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(injection::render_setting_refs(template, &settings))
+}
+
+/// 按所属项目归并变更的设定; 已删除的设定从删除记录中查找项目
+pub async fn changes_for(
+    pool: &SqlitePool,
+    setting_ids: &[String],
+    change_kind: &str,
+) -> Result<Vec<SettingsChanged>, String> {
+    if setting_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut sql: QueryBuilder<Sqlite> =
+        QueryBuilder::new("SELECT id, project_id FROM settings WHERE id IN (");
+    let mut separated = sql.separated(", ");
+    for id in setting_ids {
+        separated.push_bind(id);
+    }
+    sql.push(") UNION SELECT setting_id, project_id FROM setting_tombstones WHERE setting_id IN (");
+    let mut separated = sql.separated(", ");
+    for id in setting_ids {
+        separated.push_bind(id);
+    }
+    sql.push(")");
+    let projects: HashMap<String, String> = sql
+        .build_query_as::<(String, String)>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    let mut grouped: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for id in setting_ids {
+        if let Some(project_id) = projects.get(id) {
+            let ids = grouped.entry(project_id).or_default();
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+    }
+    Ok(grouped
+        .into_iter()
+        .map(|(project_id, setting_ids)| SettingsChanged {
+            project_id: project_id.to_string(),
+            setting_ids,
+            change_kind: change_kind.to_string(),
+        })
+        .collect())
+}
+
+/// 写入提交后发出 `settings:changed` 事件; 通知失败不影响已提交的写入
+pub async fn notify_changed(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    setting_ids: &[String],
+    change_kind: &str,
+) {
+    if let Ok(changes) = changes_for(pool, setting_ids, change_kind).await {
+        for change in changes {
+            let _ = app.emit(SETTINGS_CHANGED_EVENT, &change);
+        }
+    }
+}
+
+/// 项目中在 `since` 之后 (含) 更新或删除的设定; 边界上的变更可能重复返回, 前端按 ID 覆盖即可
+pub async fn changed_since(
+    pool: &SqlitePool,
+    project_id: &str,
+    since: &str,
+) -> Result<SettingsDelta, String> {
+    let since = chrono::DateTime::parse_from_rfc3339(since)
+        .map_err(|_| format!("时间格式无效: {}", since))?;
+    let since = db::to_iso(since.with_timezone(&chrono::Utc));
+    let as_of = db::now();
+
+    let changed = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? AND updated_at >= ?
+         ORDER BY category, order_index",
+    )
+    .bind(project_id)
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let deleted_ids = sqlx::query_scalar(
+        "SELECT setting_id FROM setting_tombstones WHERE project_id = ? AND deleted_at >= ?
+         ORDER BY deleted_at",
+    )
+    .bind(project_id)
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(SettingsDelta {
+        changed,
+        deleted_ids,
+        as_of,
+    })
+}
+
+/// 计算分类下每条设定的 token 开销, 按渲染后 token 数倒序
+pub async fn token_budget(
+    pool: &SqlitePool,
+    project_id: &str,
+    category: &str,
+    provider_name: &str,
+) -> Result<Vec<SettingTokenCost>, String> {
+    let settings = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? AND category = ? ORDER BY order_index",
+    )
+    .bind(project_id)
+    .bind(category)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let prompts = sqlx::query_as::<_, SettingPrompt>(
+        "SELECT * FROM setting_prompts WHERE project_id = ? AND category = ?",
+    )
+    .bind(project_id)
+    .bind(category)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let template = injection::template_for(category, &prompts);
+
+    let mut costs = settings
+        .iter()
+        .map(|setting| {
+            let rendered = injection::render_category(template, &[setting]);
+            Ok(SettingTokenCost {
+                setting_id: setting.id.clone(),
+                name: setting.name.clone(),
+                raw_tokens: tokens::count_tokens(&setting.content, provider_name)?,
+                rendered_tokens: tokens::count_tokens(&rendered, provider_name)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    costs.sort_by_key(|c| std::cmp::Reverse(c.rendered_tokens));
+    Ok(costs)
+}
+
+#[tauri::command]
+pub async fn get_setting_by_name(
+    app: AppHandle,
+    project_id: String,
+    category: String,
+    name: String,
+) -> Result<Option<Setting>, String> {
+    let pool = db::pool(&app).await?;
+    find_by_name(&pool, &project_id, &category, &name).await
+}
+
+#[tauri::command]
+pub async fn setting_exists(
+    app: AppHandle,
+    project_id: String,
+    category: String,
+    name: String,
+) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    exists(&pool, &project_id, &category, &name).await
+}
+
+#[tauri::command]
+pub async fn compute_setting_token_budget(
+    app: AppHandle,
+    project_id: String,
+    category: String,
+    provider_name: String,
+) -> Result<Vec<SettingTokenCost>, String> {
+    let pool = db::pool(&app).await?;
+    token_budget(&pool, &project_id, &category, &provider_name).await
+}
+
+#[tauri::command]
+pub async fn create_setting_from_template(
+    app: AppHandle,
+    project_id: String,
+    category: String,
+    name: String,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    let id = create_from_template(&pool, &project_id, &category, &name).await?;
+    notify_changed(&app, &pool, std::slice::from_ref(&id), "created").await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_category_template(
+    app: AppHandle,
+    category_id: String,
+    template: Option<String>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    update_content_template(&pool, &category_id, template.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn create_world_building_entry(
+    app: AppHandle,
+    project_id: String,
+    entry: WorldBuildingEntry,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    let id = create_world_entry(&pool, &project_id, &entry).await?;
+    notify_changed(&app, &pool, std::slice::from_ref(&id), "created").await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn list_world_building_entries(
+    app: AppHandle,
+    project_id: String,
+    category: Option<String>,
+) -> Result<Vec<Setting>, String> {
+    let pool = db::pool(&app).await?;
+    list_world_entries(&pool, &project_id, category.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_character_network(
+    app: AppHandle,
+    project_id: String,
+) -> Result<CharacterNetwork, String> {
+    let pool = db::pool(&app).await?;
+    character_network(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn set_setting_fact(
+    app: AppHandle,
+    setting_id: String,
+    key: String,
+    value: String,
+) -> Result<BTreeMap<String, String>, String> {
+    let pool = db::pool(&app).await?;
+    let facts = set_fact(&pool, &setting_id, &key, &value).await?;
+    notify_changed(&app, &pool, &[setting_id], "updated").await;
+    Ok(facts)
+}
+
+#[tauri::command]
+pub async fn remove_setting_fact(
+    app: AppHandle,
+    setting_id: String,
+    key: String,
+) -> Result<BTreeMap<String, String>, String> {
+    let pool = db::pool(&app).await?;
+    let facts = remove_fact(&pool, &setting_id, &key).await?;
+    notify_changed(&app, &pool, &[setting_id], "updated").await;
+    Ok(facts)
+}
+
+#[tauri::command]
+pub async fn preview_setting_references(
+    app: AppHandle,
+    project_id: String,
+    template: String,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    render_references(&pool, &project_id, &template).await
+}
+
+#[tauri::command]
+pub async fn get_location_hierarchy(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<LocationNode>, String> {
+    let pool = db::pool(&app).await?;
+    location_hierarchy(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn get_settings_changed_since(
+    app: AppHandle,
+    project_id: String,
+    timestamp: String,
+) -> Result<SettingsDelta, String> {
+    let pool = db::pool(&app).await?;
+    changed_since(&pool, &project_id, &timestamp).await
+}
+
+#[tauri::command]
+pub async fn detect_setting_inconsistencies(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<Inconsistency>, String> {
+    let pool = db::pool(&app).await?;
+    inconsistencies(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn finds_setting_by_category_and_name() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('s1', 'p1', 'character', '林雪', '驿站掌柜的女儿');
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('s2', 'p1', 'worldview', '林雪', '同名地名');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let found = find_by_name(&pool, "p1", "worldview", "林雪")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, "s2");
+        assert!(find_by_name(&pool, "p1", "style", "林雪")
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(exists(&pool, "p1", "character", "林雪").await.unwrap());
+        assert!(!exists(&pool, "p1", "character", "陌生人").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn ranks_settings_by_rendered_tokens() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content) VALUES
+                ('s1', 'p1', 'character', '林雪', '少女'),
+                ('s2', 'p1', 'character', '陌生人', '身份不明的受伤男子');
+            INSERT INTO setting_prompts (id, project_id, category, prompt_template, enabled)
+                VALUES ('sp1', 'p1', 'character', '角色: {{#each items}}{{name}}{{/each}}', 1);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let costs = token_budget(&pool, "p1", "character", "claude")
+            .await
+            .unwrap();
+        assert_eq!(costs[0].setting_id, "s2");
+        assert_eq!(costs[0].raw_tokens, 14);
+        // "角色: 陌生人" → 5 个汉字 + 符号 ":" 计为一个单词
+        assert_eq!(costs[0].rendered_tokens, 9);
+        assert_eq!(costs[1].raw_tokens, 3);
+
+        assert!(token_budget(&pool, "p1", "character", "unknown")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn creates_settings_from_category_templates() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO setting_prompts (id, project_id, category, prompt_template, enabled)
+                VALUES ('sp1', 'p1', 'worldview', '{{items}}', 1);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let content = |id: String| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, String>("SELECT content FROM settings WHERE id = ?")
+                    .bind(id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let character = create_from_template(&pool, "p1", "character", "林雪")
+            .await
+            .unwrap();
+        assert_eq!(
+            content(character).await,
+            "外貌：\n\n性格：\n\n背景：\n\n目标："
+        );
+
+        update_content_template(&pool, "sp1", Some("【{{name}}】\n地理："))
+            .await
+            .unwrap();
+        let world = create_from_template(&pool, "p1", "worldview", "北境")
+            .await
+            .unwrap();
+        assert_eq!(content(world).await, "【北境】\n地理：");
+
+        assert!(update_content_template(&pool, "sp1", Some("{{title}}"))
+            .await
+            .is_err());
+        assert!(update_content_template(&pool, "sp1", Some("{{name"))
+            .await
+            .is_err());
+        assert!(update_content_template(&pool, "missing", Some("{{name}}"))
+            .await
+            .is_err());
+
+        update_content_template(&pool, "sp1", Some(" "))
+            .await
+            .unwrap();
+        let blank = create_from_template(&pool, "p1", "worldview", "南疆")
+            .await
+            .unwrap();
+        assert_eq!(content(blank).await, "");
+    }
+
+    #[tokio::test]
+    async fn creates_world_entries_within_whitelist() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('s1', 'p1', 'style', '文风', '古风');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let entry = |name: &str, category: &str| WorldBuildingEntry {
+            name: name.to_string(),
+            category: category.to_string(),
+            content: String::new(),
+            enabled: true,
+        };
+        let station = create_world_entry(&pool, "p1", &entry("驿站", "location"))
+            .await
+            .unwrap();
+        create_world_entry(&pool, "p1", &entry("京城", "location"))
+            .await
+            .unwrap();
+        create_world_entry(&pool, "p1", &entry("林雪", "character"))
+            .await
+            .unwrap();
+        assert!(create_world_entry(&pool, "p1", &entry("文风", "style"))
+            .await
+            .is_err());
+        assert!(create_world_entry(&pool, "p1", &entry(" ", "item"))
+            .await
+            .is_err());
+
+        let locations = list_world_entries(&pool, "p1", Some("location"))
+            .await
+            .unwrap();
+        assert_eq!(locations[0].id, station);
+        assert_eq!(locations[1].order_index, 1);
+        let all = list_world_entries(&pool, "p1", None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(list_world_entries(&pool, "p1", Some("style"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn builds_character_network_from_mentions() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content, order_index) VALUES
+                ('c1', 'p1', 'character', '林雪', '驿站女掌柜, 救下沈舟; 沈舟欠她一条命', 0),
+                ('c2', 'p1', 'character', '沈舟', '受伤的剑客, 被林雪所救', 1),
+                ('c3', 'p1', 'character', '老周', '驿站伙计', 2),
+                ('w1', 'p1', 'worldview', '驿站', '林雪经营的驿站', 0);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let network = character_network(&pool, "p1").await.unwrap();
+        assert_eq!(network.characters.len(), 3);
+        let edge = |from: &str, to: &str| {
+            network
+                .relationships
+                .iter()
+                .find(|r| r.from_id == from && r.to_id == to)
+                .map(|r| r.mention_count)
+        };
+        assert_eq!(edge("c1", "c2"), Some(2));
+        assert_eq!(edge("c2", "c1"), Some(1));
+        assert_eq!(network.relationships.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sets_and_removes_setting_facts() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('c1', 'p1', 'character', '林雪', '驿站女掌柜');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        set_fact(&pool, "c1", "年龄", "17").await.unwrap();
+        let facts = set_fact(&pool, "c1", " 瞳色 ", "蓝").await.unwrap();
+        assert_eq!(facts.get("瞳色").map(String::as_str), Some("蓝"));
+        assert!(set_fact(&pool, "c1", " ", "x").await.is_err());
+        assert!(set_fact(&pool, "missing", "年龄", "17").await.is_err());
+
+        let setting = find_by_name(&pool, "p1", "character", "林雪")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(setting.facts.unwrap().len(), 2);
+        assert_eq!(
+            render_references(&pool, "p1", "瞳色: {{setting:林雪.facts.瞳色}}")
+                .await
+                .unwrap(),
+            "瞳色: 蓝"
+        );
+
+        remove_fact(&pool, "c1", "年龄").await.unwrap();
+        let facts = remove_fact(&pool, "c1", "瞳色").await.unwrap();
+        assert!(facts.is_empty());
+        let raw: Option<String> = sqlx::query_scalar("SELECT facts FROM settings WHERE id = 'c1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(raw.is_none());
+    }
+
+    #[tokio::test]
+    async fn builds_location_tree_from_parent_ids() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content, parent_id, order_index) VALUES
+                ('l1', 'p1', 'location', '大梁', '王朝', NULL, 0),
+                ('l2', 'p1', 'location', '京城', '都城', 'l1', 1),
+                ('l3', 'p1', 'location', '驿站', '京郊驿站', 'l2', 2),
+                ('l4', 'p1', 'location', '北境', '边关', 'c1', 3),
+                ('l5', 'p1', 'location', '雾谷', '', 'l6', 4),
+                ('l6', 'p1', 'location', '雾岭', '', 'l5', 5),
+                ('c1', 'p1', 'character', '林雪', '', NULL, 0);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let tree = location_hierarchy(&pool, "p1").await.unwrap();
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["大梁", "北境", "雾谷"]);
+        assert_eq!(tree[0].children[0].name, "京城");
+        assert_eq!(tree[0].children[0].children[0].name, "驿站");
+        assert_eq!(tree[2].children[0].name, "雾岭");
+        assert!(tree[2].children[0].children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tracks_setting_changes_since_timestamp() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '另一项目');
+            INSERT INTO settings (id, project_id, category, name, content, updated_at) VALUES
+                ('old', 'p1', 'character', '陌生人', '', '2026-01-01T00:00:00.000Z'),
+                ('gone', 'p1', 'character', '路人', '', '2026-01-01T00:00:00.000Z'),
+                ('other', 'p2', 'character', '旁人', '', '2026-01-01T00:00:00.000Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let since = "2026-02-01T08:00:00+08:00";
+
+        let entry = WorldBuildingEntry {
+            name: "林雪".to_string(),
+            category: "character".to_string(),
+            content: "驿站掌柜的女儿".to_string(),
+            enabled: true,
+        };
+        let created = create_world_entry(&pool, "p1", &entry).await.unwrap();
+        let changes = changes_for(&pool, std::slice::from_ref(&created), "created")
+            .await
+            .unwrap();
+        assert_eq!(changes[0].project_id, "p1");
+        assert_eq!(changes[0].setting_ids, vec![created.clone()]);
+
+        set_fact(&pool, "old", "年龄", "三十").await.unwrap();
+        remove_fact(&pool, "old", "年龄").await.unwrap();
+        let ids = ["old".to_string(), "other".to_string(), "old".to_string()];
+        let changes = changes_for(&pool, &ids, "updated").await.unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].setting_ids, vec!["old".to_string()]);
+        assert_eq!(changes[1].project_id, "p2");
+        assert!(changes_for(&pool, &[], "updated").await.unwrap().is_empty());
+
+        // 前端直接删除的设定同样写入删除记录
+        sqlx::query("DELETE FROM settings WHERE id = 'gone'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let delta = changed_since(&pool, "p1", since).await.unwrap();
+        let changed: Vec<&str> = delta.changed.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(changed, vec!["old", created.as_str()]);
+        assert_eq!(delta.deleted_ids, vec!["gone".to_string()]);
+
+        assert!(changed_since(&pool, "p1", "昨天").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn detects_setting_inconsistencies() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO settings (id, project_id, category, name, content, order_index)
+                VALUES ('c1', 'p1', 'character', '林雪', '驿站女掌柜', 0),
+                       ('c2', 'p1', 'character', ' 林雪 ', '剑客', 1),
+                       ('l1', 'p1', 'location', '雁门驿', '边关 驿站', 0),
+                       ('l2', 'p1', 'location', '雁门驿', '边关驿站', 1),
+                       ('l3', 'p1', 'location', '青石镇', '江南小镇', 2),
+                       ('l4', 'p1', 'location', '青石镇', '塞北小镇', 3),
+                       ('l5', 'p1', 'location', '落霞谷', '山谷', 4),
+                       ('l6', 'p1', 'location', '落霞谷', '谷地', 5),
+                       ('w1', 'p1', 'worldview', '雁门驿', '驿站的历史', 0);
+            UPDATE settings SET facts = '{"气候":"湿冷"}' WHERE id = 'l5';
+            UPDATE settings SET facts = '{"气候":"干热"}' WHERE id = 'l6';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let found = inconsistencies(&pool, "p1").await.unwrap();
+        let pairs: Vec<(&str, &str, &str)> = found
+            .iter()
+            .map(|i| {
+                (
+                    i.type_.as_str(),
+                    i.setting_id_a.as_str(),
+                    i.setting_id_b.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(pairs.len(), 5);
+        assert!(pairs.contains(&("duplicate_character", "c1", "c2")));
+        // 仅空白不同的描述不算矛盾
+        assert!(!pairs.iter().any(|p| p.1 == "l1" && p.2 == "l2"));
+        assert!(pairs.contains(&("location_conflict", "l3", "l4")));
+        assert!(pairs.contains(&("location_conflict", "l5", "l6")));
+        assert!(pairs.contains(&("cross_category_duplicate", "l1", "w1")));
+        assert!(pairs.contains(&("cross_category_duplicate", "l2", "w1")));
+        let facts_conflict = found.iter().find(|i| i.setting_id_a == "l5").unwrap();
+        assert!(facts_conflict.description.contains("气候: 湿冷 / 干热"));
+    }
+}
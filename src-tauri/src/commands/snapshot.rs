@@ -0,0 +1,238 @@
+// 项目快照: 保存项目完整数据, 用于项目历史与回滚
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{Node, Project, Setting, SettingPrompt, Workflow};
+
+/// 快照格式版本号
+const SNAPSHOT_VERSION: &str = "1.0.0";
+
+// 快照中的单个工作流
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowSnapshot {
+    pub workflow: Workflow,
+    pub nodes: Vec<Node>,
+}
+
+// 项目快照内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    pub version: String,
+    pub project: Project,
+    pub workflows: Vec<WorkflowSnapshot>,
+    pub settings: Vec<Setting>,
+    pub setting_prompts: Vec<SettingPrompt>,
+}
+
+// 快照列表项 (不含快照正文)
+#[derive(Debug, Serialize)]
+pub struct ProjectSnapshotMeta {
+    pub id: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub workflow_count: i64,
+    pub setting_count: i64,
+}
+
+/// 读取项目当前的完整数据
+pub async fn build_project_snapshot(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<ProjectSnapshot, String> {
+    let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("项目不存在: {}", project_id))?;
+
+    let workflow_rows = sqlx::query_as::<_, Workflow>(
+        "SELECT * FROM workflows WHERE project_id = ? ORDER BY created_at ASC",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut workflows = Vec::with_capacity(workflow_rows.len());
+    for workflow in workflow_rows {
+        let nodes = sqlx::query_as::<_, Node>(
+            "SELECT * FROM nodes WHERE workflow_id = ? ORDER BY order_index ASC",
+        )
+        .bind(&workflow.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        workflows.push(WorkflowSnapshot { workflow, nodes });
+    }
+
+    let settings = sqlx::query_as::<_, Setting>(
+        "SELECT * FROM settings WHERE project_id = ? ORDER BY category, order_index, name",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let setting_prompts = sqlx::query_as::<_, SettingPrompt>(
+        "SELECT * FROM setting_prompts WHERE project_id = ? ORDER BY category",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(ProjectSnapshot {
+        version: SNAPSHOT_VERSION.to_string(),
+        project,
+        workflows,
+        settings,
+        setting_prompts,
+    })
+}
+
+/// 为项目创建快照, 返回快照 ID
+pub async fn create_snapshot(
+    pool: &SqlitePool,
+    project_id: &str,
+    description: Option<&str>,
+) -> Result<String, String> {
+    let snapshot = build_project_snapshot(pool, project_id).await?;
+    let snapshot = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    let id = db::new_id();
+
+    sqlx::query(
+        "INSERT INTO project_snapshots (id, project_id, snapshot, description, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(project_id)
+    .bind(snapshot)
+    .bind(description)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(id)
+}
+
+/// 列出项目的全部快照, 按创建时间倒序
+pub async fn list_snapshots(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<ProjectSnapshotMeta>, String> {
+    let rows: Vec<(String, Option<String>, String, String)> = sqlx::query_as(
+        "SELECT id, description, created_at, snapshot FROM project_snapshots
+         WHERE project_id = ? ORDER BY created_at DESC",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, description, created_at, snapshot)| {
+            let (workflow_count, setting_count) = count_snapshot_items(&snapshot);
+            ProjectSnapshotMeta {
+                id,
+                description,
+                created_at,
+                workflow_count,
+                setting_count,
+            }
+        })
+        .collect())
+}
+
+// 统计快照中的工作流与设定数量, 快照损坏时计为 0
+fn count_snapshot_items(snapshot: &str) -> (i64, i64) {
+    let value: serde_json::Value = match serde_json::from_str(snapshot) {
+        Ok(value) => value,
+        Err(_) => return (0, 0),
+    };
+    let count = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map_or(0, |items| items.len() as i64)
+    };
+    (count("workflows"), count("settings"))
+}
+
+#[tauri::command]
+pub async fn create_project_snapshot(
+    app: AppHandle,
+    project_id: String,
+    description: Option<String>,
+) -> Result<String, String> {
+    let pool = db::pool(&app).await?;
+    create_snapshot(&pool, &project_id, description.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_project_snapshots(
+    app: AppHandle,
+    project_id: String,
+) -> Result<Vec<ProjectSnapshotMeta>, String> {
+    let pool = db::pool(&app).await?;
+    list_snapshots(&pool, &project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed_project(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '第一章');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index)
+                VALUES ('n1', 'w1', 'start', '开始流程', '{}', 0);
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('s1', 'p1', 'character', '林雪', '女主角');
+            INSERT INTO settings (id, project_id, category, name, content)
+                VALUES ('s2', 'p1', 'worldview', '青云城', '故事发生地');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_snapshots_with_counts_newest_first() {
+        let pool = db::test_pool().await;
+        seed_project(&pool).await;
+
+        create_snapshot(&pool, "p1", Some("初稿")).await.unwrap();
+        sqlx::query("UPDATE project_snapshots SET created_at = '2026-01-01T00:00:00.000Z'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let newest = create_snapshot(&pool, "p1", None).await.unwrap();
+
+        let snapshots = list_snapshots(&pool, "p1").await.unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, newest);
+        assert_eq!(snapshots[1].description.as_deref(), Some("初稿"));
+        assert_eq!(snapshots[0].workflow_count, 1);
+        assert_eq!(snapshots[0].setting_count, 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_project() {
+        let pool = db::test_pool().await;
+        assert!(create_snapshot(&pool, "missing", None).await.is_err());
+    }
+
+    #[test]
+    fn corrupted_snapshot_counts_as_empty() {
+        assert_eq!(count_snapshot_items("not json"), (0, 0));
+        assert_eq!(count_snapshot_items(r#"{"workflows": [{}]}"#), (1, 0));
+    }
+}
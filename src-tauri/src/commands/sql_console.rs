@@ -0,0 +1,417 @@
+// SQL 控制台: 供高级用户执行临时查询, 需在全局配置中开启 enable_sql_console
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqliteConnection, SqliteRow};
+use sqlx::{Arguments, Column, Connection, Row, SqlitePool, TypeInfo, ValueRef};
+use tauri::{AppHandle, Manager};
+
+use crate::db;
+
+/// 只读查询默认与最大返回行数
+const DEFAULT_ROW_LIMIT: i64 = 200;
+const MAX_ROW_LIMIT: i64 = 1000;
+
+/// 单条语句的执行时间上限
+const STATEMENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 只读查询每执行多少条 SQLite 虚拟机指令检查一次是否超时
+const PROGRESS_CHECK_OPS: i32 = 1000;
+
+// 只读查询结果
+#[derive(Debug, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// 结果超过行数上限被截断
+    pub truncated: bool,
+}
+
+// 写入语句预览, 需携带 confirm_token 才能执行
+#[derive(Debug, Serialize)]
+pub struct WriteStatementPreview {
+    pub confirm_token: String,
+}
+
+// 写入语句执行结果
+#[derive(Debug, Serialize)]
+pub struct WriteStatementResult {
+    pub rows_affected: u64,
+    pub backup_path: String,
+}
+
+async fn ensure_enabled(pool: &SqlitePool) -> Result<(), String> {
+    let enabled: Option<bool> =
+        sqlx::query_scalar("SELECT enable_sql_console FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten();
+    if enabled != Some(true) {
+        return Err("SQL 控制台未开启, 请先在设置中启用".to_string());
+    }
+    Ok(())
+}
+
+// 结构化日志, 每次执行输出一行 JSON
+fn log_statement(kind: &str, sql: &str, outcome: &Result<u64, String>) {
+    let entry = json!({
+        "target": "sql_console",
+        "kind": kind,
+        "sql": sql,
+        "rows": outcome.as_ref().ok(),
+        "error": outcome.as_ref().err(),
+        "at": db::now(),
+    });
+    eprintln!("{}", entry);
+}
+
+/// 去除末尾分号并确认只有一条语句; 字符串中含分号的语句同样会被拒绝
+pub fn single_statement(sql: &str) -> Result<&str, String> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if sql.is_empty() {
+        return Err("SQL 语句不能为空".to_string());
+    }
+    if sql.contains(';') {
+        return Err("一次只能执行一条 SQL 语句".to_string());
+    }
+    Ok(sql)
+}
+
+/// 只读查询必须以 SELECT 或 WITH 开头
+pub fn validate_select(sql: &str) -> Result<&str, String> {
+    let sql = single_statement(sql)?;
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase();
+    if keyword != "SELECT" && keyword != "WITH" {
+        return Err("只读查询只支持 SELECT 语句".to_string());
+    }
+    Ok(sql)
+}
+
+fn bind_params(params: &[Value]) -> Result<SqliteArguments<'static>, String> {
+    let mut args = SqliteArguments::default();
+    for param in params {
+        let bound = match param {
+            Value::Null => args.add(None::<String>),
+            Value::Bool(b) => args.add(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => args.add(i),
+                None => args.add(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => args.add(s.clone()),
+            other => args.add(other.to_string()),
+        };
+        bound.map_err(|e| e.to_string())?;
+    }
+    Ok(args)
+}
+
+// 按值的实际类型转为 JSON, BLOB 使用 Base64
+fn column_value(row: &SqliteRow, index: usize) -> Value {
+    let Ok(raw) = row.try_get_raw(index) else {
+        return Value::Null;
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+    match raw.type_info().name() {
+        "INTEGER" => row.try_get::<i64, _>(index).map(Value::from),
+        "REAL" => row.try_get::<f64, _>(index).map(Value::from),
+        "BLOB" => row
+            .try_get::<Vec<u8>, _>(index)
+            .map(|bytes| Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))),
+        _ => row.try_get::<String, _>(index).map(Value::String),
+    }
+    .unwrap_or(Value::Null)
+}
+
+// 只读查询使用独立的只读连接打开同一个数据库文件, 不改动应用连接池中连接的状态
+async fn open_readonly(pool: &SqlitePool) -> Result<SqliteConnection, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(pool.connect_options().get_filename())
+        .read_only(true);
+    SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("打开只读连接失败: {}", e))
+}
+
+async fn readonly_query(
+    pool: &SqlitePool,
+    sql: &str,
+    params: &[Value],
+    limit: i64,
+    timeout: Duration,
+) -> Result<QueryResult, String> {
+    let sql = validate_select(sql)?;
+    let limit = limit.clamp(1, MAX_ROW_LIMIT);
+    let mut args = bind_params(params)?;
+    args.add(limit + 1).map_err(|e| e.to_string())?;
+    let wrapped = format!("SELECT * FROM ({}) LIMIT ?", sql);
+
+    let mut conn = open_readonly(pool).await?;
+    // 超时后进度回调返回 false, SQLite 中断正在执行的语句
+    let deadline = Instant::now() + timeout;
+    conn.lock_handle()
+        .await
+        .map_err(|e| e.to_string())?
+        .set_progress_handler(PROGRESS_CHECK_OPS, move || Instant::now() < deadline);
+    let rows = sqlx::query_with(&wrapped, args).fetch_all(&mut conn).await;
+    // 只读连接不会留下未提交的改动, 关闭失败无需处理
+    let _ = conn.close().await;
+    let mut rows = rows.map_err(|e| {
+        if Instant::now() >= deadline {
+            format!("查询超过 {} 秒, 已中止", timeout.as_secs_f64())
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    let truncated = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let columns = match rows.first() {
+        Some(row) => row.columns().iter().map(|c| c.name().to_string()).collect(),
+        None => Vec::new(),
+    };
+    let rows = rows
+        .iter()
+        .map(|row| (0..row.len()).map(|i| column_value(row, i)).collect())
+        .collect();
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+    })
+}
+
+/// 执行只读查询, 结果受行数与时间限制
+pub async fn run_readonly(
+    pool: &SqlitePool,
+    sql: &str,
+    params: &[Value],
+    limit: Option<i64>,
+) -> Result<QueryResult, String> {
+    ensure_enabled(pool).await?;
+    let result = readonly_query(
+        pool,
+        sql,
+        params,
+        limit.unwrap_or(DEFAULT_ROW_LIMIT),
+        STATEMENT_TIMEOUT,
+    )
+    .await;
+    log_statement(
+        "read",
+        sql,
+        &result
+            .as_ref()
+            .map(|r| r.rows.len() as u64)
+            .map_err(Clone::clone),
+    );
+    result
+}
+
+fn write_confirm_token(sql: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    "sql_console_write".hash(&mut hasher);
+    sql.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// 预览写入语句, 返回执行所需的确认令牌
+pub async fn prepare_write(pool: &SqlitePool, sql: &str) -> Result<WriteStatementPreview, String> {
+    ensure_enabled(pool).await?;
+    let sql = single_statement(sql)?;
+    Ok(WriteStatementPreview {
+        confirm_token: write_confirm_token(sql),
+    })
+}
+
+/// 执行写入语句: 校验确认令牌, 先将整个数据库备份到 backup_path
+pub async fn run_write(
+    pool: &SqlitePool,
+    sql: &str,
+    token: &str,
+    backup_path: &Path,
+) -> Result<WriteStatementResult, String> {
+    ensure_enabled(pool).await?;
+    let statement = single_statement(sql)?;
+    if write_confirm_token(statement) != token {
+        return Err("确认令牌无效, 请重新预览语句".to_string());
+    }
+
+    let backup = backup_path.to_string_lossy().to_string();
+    sqlx::query("VACUUM INTO ?")
+        .bind(&backup)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("执行前备份失败: {}", e))?;
+
+    let result = tokio::time::timeout(STATEMENT_TIMEOUT, sqlx::query(statement).execute(pool))
+        .await
+        .map_err(|_| format!("语句超过 {} 秒, 已中止", STATEMENT_TIMEOUT.as_secs()))
+        .and_then(|r| r.map(|r| r.rows_affected()).map_err(|e| e.to_string()));
+    log_statement("write", statement, &result);
+    Ok(WriteStatementResult {
+        rows_affected: result?,
+        backup_path: backup,
+    })
+}
+
+#[tauri::command]
+pub async fn run_readonly_query(
+    app: AppHandle,
+    sql: String,
+    params: Option<Vec<Value>>,
+    limit: Option<i64>,
+) -> Result<QueryResult, String> {
+    let pool = db::pool(&app).await?;
+    run_readonly(&pool, &sql, &params.unwrap_or_default(), limit).await
+}
+
+#[tauri::command]
+pub async fn prepare_write_statement(
+    app: AppHandle,
+    sql: String,
+) -> Result<WriteStatementPreview, String> {
+    let pool = db::pool(&app).await?;
+    prepare_write(&pool, &sql).await
+}
+
+/// 危险操作: 执行任意写入语句, 执行前自动备份到应用数据目录的 backups 下
+#[tauri::command]
+pub async fn run_write_statement(
+    app: AppHandle,
+    sql: String,
+    confirm_token: String,
+) -> Result<WriteStatementResult, String> {
+    let pool = db::pool(&app).await?;
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("backups");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let file = format!(
+        "sql-console-{}.db",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S%3f")
+    );
+    run_write(&pool, &sql, &confirm_token, &dir.join(file)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn enable(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            UPDATE global_config SET enable_sql_console = 1 WHERE id = 1;
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目'), ('p2', '第二个项目');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn accepts_only_single_select() {
+        assert_eq!(
+            validate_select("select * from projects;  ").unwrap(),
+            "select * from projects"
+        );
+        assert!(validate_select("WITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(validate_select("DELETE FROM projects").is_err());
+        assert!(validate_select("SELECT 1; DROP TABLE projects").is_err());
+    }
+
+    #[tokio::test]
+    async fn runs_readonly_queries_with_limits() {
+        let dir = std::env::temp_dir().join(format!("sql-console-test-{}", db::new_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pool = db::test_file_pool(&dir.join("main.db")).await;
+        assert!(run_readonly(&pool, "SELECT 1", &[], None).await.is_err());
+        enable(&pool).await;
+
+        let result = run_readonly(
+            &pool,
+            "SELECT id, name, 1.5 AS ratio, NULL AS empty FROM projects WHERE id LIKE ? ORDER BY id",
+            &[json!("p%")],
+            Some(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.columns, vec!["id", "name", "ratio", "empty"]);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                json!("p1"),
+                json!("测试项目"),
+                json!(1.5),
+                Value::Null
+            ]]
+        );
+        assert!(result.truncated);
+
+        // 只读连接拒绝写入, 应用连接池不受影响
+        assert!(run_readonly(
+            &pool,
+            "WITH x AS (DELETE FROM projects) SELECT 1",
+            &[],
+            None
+        )
+        .await
+        .is_err());
+        sqlx::query("UPDATE projects SET name = '改名' WHERE id = 'p2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // 超时的查询在 SQLite 内部被中断
+        let started = Instant::now();
+        let error = readonly_query(
+            &pool,
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT COUNT(*) FROM c",
+            &[],
+            1,
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.contains("已中止"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn requires_token_and_backs_up_before_writes() {
+        let dir = std::env::temp_dir().join(format!("sql-console-test-{}", db::new_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pool = db::test_file_pool(&dir.join("main.db")).await;
+        enable(&pool).await;
+        let backup = dir.join("backup.db");
+
+        let sql = "DELETE FROM projects WHERE id = 'p2'";
+        assert!(run_write(&pool, sql, "bad", &backup).await.is_err());
+        assert!(!backup.exists());
+
+        let preview = prepare_write(&pool, sql).await.unwrap();
+        let result = run_write(&pool, sql, &preview.confirm_token, &backup)
+            .await
+            .unwrap();
+        assert_eq!(result.rows_affected, 1);
+        assert!(backup.exists());
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
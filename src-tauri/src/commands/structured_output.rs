@@ -0,0 +1,217 @@
+// 结构化输出: 从混有说明文字的 AI 输出中提取 JSON 并按 JSON Schema 校验, 供输出节点使用.
+// 只支持 Schema 的常用子集: type、enum、required、properties、items
+use serde_json::{Map, Value};
+
+// 从 start 处的 `{` 或 `[` 开始按括号深度扫描 (忽略字符串中的括号), 返回配对结束处之后的字节位置;
+// 括号不匹配或未闭合时返回 None
+fn matching_end(text: &str, start: usize) -> Option<usize> {
+    let mut closers: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                if closers.pop() != Some(c) {
+                    return None;
+                }
+                if closers.is_empty() {
+                    return Some(start + offset + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 按出现顺序返回第一个能解析的 JSON 对象或数组; 找不到时报告最靠前的疑似 JSON 片段的位置 (字符序号)
+pub fn extract_json(raw_output: &str) -> Result<Value, String> {
+    let mut nearest: Option<(usize, String)> = None;
+    for (start, c) in raw_output.char_indices() {
+        if c != '{' && c != '[' {
+            continue;
+        }
+        let error = match matching_end(raw_output, start) {
+            Some(end) => match serde_json::from_str(&raw_output[start..end]) {
+                Ok(value) => return Ok(value),
+                Err(e) => e.to_string(),
+            },
+            None => "括号未闭合或不匹配".to_string(),
+        };
+        if nearest.is_none() {
+            nearest = Some((raw_output[..start].chars().count(), error));
+        }
+    }
+    Err(match nearest {
+        Some((position, error)) => format!(
+            "输出中没有有效的 JSON, 最近的疑似片段位于第 {} 个字符: {}",
+            position, error
+        ),
+        None => "输出中没有 JSON 对象或数组".to_string(),
+    })
+}
+
+fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+/// 按 Schema 校验值, 错误信息带 `$.a[0].b` 形式的路径
+pub fn validate_schema(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(true) => return Ok(()),
+        _ => return Err(format!("不支持的 Schema: {}", schema)),
+    };
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => return Err(format!("{}: Schema 的 type 无效", path)),
+        };
+        if !types.iter().any(|t| type_matches(value, t)) {
+            return Err(format!("{}: 类型应为 {}", path, types.join(" / ")));
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return Err(format!("{}: 不是允许的取值", path));
+        }
+    }
+    if let Value::Object(object) = value {
+        check_object(object, schema, path)?;
+    }
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            validate_schema(item, item_schema, &format!("{}[{}]", path, index))?;
+        }
+    }
+    Ok(())
+}
+
+fn check_object(
+    object: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        if let Some(missing) = required
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|key| !object.contains_key(*key))
+        {
+            return Err(format!("{}: 缺少必填字段 {}", path, missing));
+        }
+    }
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        for (key, property) in properties {
+            if let Some(field) = object.get(key) {
+                validate_schema(field, property, &format!("{}.{}", path, key))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 提取第一个有效的 JSON 并按 Schema 校验
+pub fn parse_structured(raw_output: &str, schema: &Value) -> Result<Value, String> {
+    let value = extract_json(raw_output)?;
+    validate_schema(&value, schema, "$")?;
+    Ok(value)
+}
+
+#[tauri::command]
+pub fn parse_structured_ai_output(raw_output: String, schema: Value) -> Result<Value, String> {
+    parse_structured(&raw_output, &schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_first_parsable_json() {
+        let raw = "好的, 以下是结果:\n```json\n{\"title\": \"雨夜 {上}\", \"tags\": [\"悬疑\"]}\n```\n希望有帮助 [1]";
+        assert_eq!(
+            extract_json(raw).unwrap(),
+            json!({ "title": "雨夜 {上}", "tags": ["悬疑"] })
+        );
+        // 外层无法解析时尝试其中的片段
+        assert_eq!(extract_json("{broken: [1, 2]}").unwrap(), json!([1, 2]));
+
+        let error = extract_json("结果: {\"a\": 1,}").unwrap_err();
+        assert!(error.contains("第 4 个字符"), "{}", error);
+        let error = extract_json("结果: {\"a\": [1}").unwrap_err();
+        assert!(error.contains("括号"), "{}", error);
+        assert_eq!(
+            extract_json("没有结构化内容").unwrap_err(),
+            "输出中没有 JSON 对象或数组"
+        );
+    }
+
+    #[test]
+    fn validates_against_schema_subset() {
+        let schema = json!({
+            "type": "object",
+            "required": ["title", "chapters"],
+            "properties": {
+                "title": { "type": "string" },
+                "mood": { "enum": ["dark", "light"] },
+                "chapters": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["index"],
+                        "properties": { "index": { "type": "integer" } }
+                    }
+                }
+            }
+        });
+        let raw = r#"{"title": "雾城", "mood": "dark", "chapters": [{"index": 1}]}"#;
+        assert!(parse_structured(raw, &schema).is_ok());
+        assert!(parse_structured(raw, &json!({})).is_ok());
+
+        assert_eq!(
+            parse_structured(r#"{"title": "雾城"}"#, &schema).unwrap_err(),
+            "$: 缺少必填字段 chapters"
+        );
+        assert_eq!(
+            parse_structured(
+                r#"{"title": "雾城", "chapters": [{"index": 1.5}]}"#,
+                &schema
+            )
+            .unwrap_err(),
+            "$.chapters[0].index: 类型应为 integer"
+        );
+        assert_eq!(
+            parse_structured(
+                r#"{"title": "雾城", "mood": "grey", "chapters": []}"#,
+                &schema
+            )
+            .unwrap_err(),
+            "$.mood: 不是允许的取值"
+        );
+        assert!(parse_structured("[1]", &json!({ "type": ["object", "null"] })).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+// 项目滚动梗概: 每章一段摘要, 按章节顺序拼成全书梗概注入到生成的提示词中 (`{{synopsis}}`).
+// 超出字数预算时先把最早的摘要压缩为首句, 仍超出则移除最早的章节
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{chapter, tts};
+use crate::db;
+
+/// 梗概更新后发出的事件, 负载为 [`ProjectSynopsis`]
+pub const SYNOPSIS_UPDATED_EVENT: &str = "synopsis:updated";
+
+/// 未配置时的字数预算
+const DEFAULT_CHAR_BUDGET: i64 = 4000;
+
+// 单章摘要
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynopsisSection {
+    pub chapter_id: String,
+    pub title: String,
+    pub summary: String,
+    /// 已因超出预算压缩为首句
+    #[serde(default)]
+    pub compressed: bool,
+}
+
+// 项目梗概
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ProjectSynopsis {
+    pub project_id: String,
+    pub content: String,
+    #[sqlx(json)]
+    pub sections: Vec<SynopsisSection>,
+    pub covers_up_to_chapter_id: Option<String>,
+    pub char_budget: i64,
+    pub auto_update: bool,
+    pub updated_at: Option<String>,
+}
+
+fn render_section(section: &SynopsisSection) -> String {
+    format!("【{}】{}", section.title, section.summary)
+}
+
+/// 按章节顺序渲染梗概, 每章一行
+pub fn render(sections: &[SynopsisSection]) -> String {
+    sections
+        .iter()
+        .map(render_section)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 压缩到字数预算内: 依次把最早的未压缩摘要缩为首句, 全部压缩后仍超出则移除最早的章节
+pub fn compress(mut sections: Vec<SynopsisSection>, budget: usize) -> Vec<SynopsisSection> {
+    let length = |sections: &[SynopsisSection]| render(sections).chars().count();
+    while length(&sections) > budget {
+        if let Some(section) = sections.iter_mut().find(|s| !s.compressed) {
+            let first = tts::split_sentences(&section.summary)
+                .into_iter()
+                .map(|sentence| sentence.trim().to_string())
+                .find(|sentence| !sentence.is_empty())
+                .unwrap_or_default();
+            section.summary = first;
+            section.compressed = true;
+        } else if !sections.is_empty() {
+            sections.remove(0);
+        } else {
+            break;
+        }
+    }
+    sections
+}
+
+/// 项目梗概, 尚未生成时返回空梗概与默认配置
+pub async fn get(pool: &SqlitePool, project_id: &str) -> Result<ProjectSynopsis, String> {
+    let synopsis =
+        sqlx::query_as::<_, ProjectSynopsis>("SELECT * FROM project_synopsis WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(synopsis.unwrap_or_else(|| ProjectSynopsis {
+        project_id: project_id.to_string(),
+        content: String::new(),
+        sections: Vec::new(),
+        covers_up_to_chapter_id: None,
+        char_budget: DEFAULT_CHAR_BUDGET,
+        auto_update: false,
+        updated_at: None,
+    }))
+}
+
+/// 压缩并保存各章摘要, 同时更新渲染后的梗概与覆盖到的最后一章
+async fn save(
+    pool: &SqlitePool,
+    mut synopsis: ProjectSynopsis,
+    sections: Vec<SynopsisSection>,
+) -> Result<ProjectSynopsis, String> {
+    synopsis.sections = compress(sections, synopsis.char_budget.max(0) as usize);
+    synopsis.content = render(&synopsis.sections);
+    synopsis.covers_up_to_chapter_id = synopsis.sections.last().map(|s| s.chapter_id.clone());
+    synopsis.updated_at = Some(db::now());
+    let sections = serde_json::to_string(&synopsis.sections).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO project_synopsis
+             (project_id, content, sections, covers_up_to_chapter_id, char_budget, auto_update, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(project_id) DO UPDATE SET
+             content = excluded.content,
+             sections = excluded.sections,
+             covers_up_to_chapter_id = excluded.covers_up_to_chapter_id,
+             char_budget = excluded.char_budget,
+             auto_update = excluded.auto_update,
+             updated_at = excluded.updated_at",
+    )
+    .bind(&synopsis.project_id)
+    .bind(&synopsis.content)
+    .bind(sections)
+    .bind(&synopsis.covers_up_to_chapter_id)
+    .bind(synopsis.char_budget)
+    .bind(synopsis.auto_update)
+    .bind(&synopsis.updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(synopsis)
+}
+
+/// 设置自动更新与字数预算, 预算调小时立即按新预算压缩
+pub async fn configure(
+    pool: &SqlitePool,
+    project_id: &str,
+    auto_update: bool,
+    char_budget: i64,
+) -> Result<ProjectSynopsis, String> {
+    if char_budget < 1 {
+        return Err("梗概字数预算须为正整数".to_string());
+    }
+    let mut synopsis = get(pool, project_id).await?;
+    synopsis.auto_update = auto_update;
+    synopsis.char_budget = char_budget;
+    let sections = std::mem::take(&mut synopsis.sections);
+    save(pool, synopsis, sections).await
+}
+
+/// 写入一章的摘要 (已有则替换), 按章节顺序排列; 未提供摘要时取章节已完成执行的前两句
+pub async fn append_chapter(
+    pool: &SqlitePool,
+    chapter_id: &str,
+    summary: Option<&str>,
+) -> Result<ProjectSynopsis, String> {
+    let target = chapter::get(pool, chapter_id).await?;
+    let summary = match summary {
+        Some(summary) => summary.trim().to_string(),
+        None => chapter::generate_synopsis(pool, chapter_id).await?,
+    };
+    let mut synopsis = get(pool, &target.project_id).await?;
+    let mut sections = std::mem::take(&mut synopsis.sections);
+    sections.retain(|s| s.chapter_id != chapter_id);
+    if !summary.is_empty() {
+        sections.push(SynopsisSection {
+            chapter_id: target.id.clone(),
+            title: target.title.clone(),
+            summary,
+            compressed: false,
+        });
+    }
+
+    // 按章节当前顺序排列, 已删除的章节排在最前, 优先被压缩
+    let order: Vec<String> = chapter::list(pool, &target.project_id)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    sections.sort_by_key(|s| order.iter().position(|id| *id == s.chapter_id));
+    save(pool, synopsis, sections).await
+}
+
+/// 从指定章节起重新生成梗概 (未指定时全部重建): 之前章节的摘要保留, 之后按章节执行输出重新摘取
+pub async fn rebuild(
+    pool: &SqlitePool,
+    project_id: &str,
+    from_chapter: Option<&str>,
+) -> Result<ProjectSynopsis, String> {
+    let chapters = chapter::list(pool, project_id).await?;
+    let start = match from_chapter {
+        Some(id) => chapters
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| format!("章节不属于该项目: {}", id))?,
+        None => 0,
+    };
+
+    let mut synopsis = get(pool, project_id).await?;
+    let kept: Vec<&str> = chapters[..start].iter().map(|c| c.id.as_str()).collect();
+    let mut sections: Vec<SynopsisSection> = std::mem::take(&mut synopsis.sections)
+        .into_iter()
+        .filter(|s| kept.contains(&s.chapter_id.as_str()))
+        .collect();
+    for chapter in &chapters[start..] {
+        let summary = chapter::generate_synopsis(pool, &chapter.id).await?;
+        if !summary.is_empty() {
+            sections.push(SynopsisSection {
+                chapter_id: chapter.id.clone(),
+                title: chapter.title.clone(),
+                summary,
+                compressed: false,
+            });
+        }
+    }
+    save(pool, synopsis, sections).await
+}
+
+/// 章节关联执行记录后调用: 开启自动更新时追加该章摘要并发出 `synopsis:updated`
+pub async fn on_chapter_accepted(app: &AppHandle, pool: &SqlitePool, chapter_id: &str) {
+    let result = async {
+        let project_id = chapter::get(pool, chapter_id).await?.project_id;
+        if !get(pool, &project_id).await?.auto_update {
+            return Ok(None);
+        }
+        append_chapter(pool, chapter_id, None).await.map(Some)
+    }
+    .await;
+    match result {
+        Ok(Some(synopsis)) => {
+            let _ = app.emit(SYNOPSIS_UPDATED_EVENT, &synopsis);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("更新项目梗概失败: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_project_synopsis(
+    app: AppHandle,
+    project_id: String,
+) -> Result<ProjectSynopsis, String> {
+    let pool = db::pool(&app).await?;
+    get(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn configure_project_synopsis(
+    app: AppHandle,
+    project_id: String,
+    auto_update: bool,
+    char_budget: i64,
+) -> Result<ProjectSynopsis, String> {
+    let pool = db::pool(&app).await?;
+    configure(&pool, &project_id, auto_update, char_budget).await
+}
+
+/// 写入由摘要工作流等生成的章节摘要, 未提供时取章节执行输出的前两句
+#[tauri::command]
+pub async fn update_chapter_synopsis(
+    app: AppHandle,
+    chapter_id: String,
+    summary: Option<String>,
+) -> Result<ProjectSynopsis, String> {
+    let pool = db::pool(&app).await?;
+    let synopsis = append_chapter(&pool, &chapter_id, summary.as_deref()).await?;
+    let _ = app.emit(SYNOPSIS_UPDATED_EVENT, &synopsis);
+    Ok(synopsis)
+}
+
+#[tauri::command]
+pub async fn rebuild_synopsis(
+    app: AppHandle,
+    project_id: String,
+    from_chapter: Option<String>,
+) -> Result<ProjectSynopsis, String> {
+    let pool = db::pool(&app).await?;
+    let synopsis = rebuild(&pool, &project_id, from_chapter.as_deref()).await?;
+    let _ = app.emit(SYNOPSIS_UPDATED_EVENT, &synopsis);
+    Ok(synopsis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(chapter_id: &str, summary: &str) -> SynopsisSection {
+        SynopsisSection {
+            chapter_id: chapter_id.to_string(),
+            title: chapter_id.to_string(),
+            summary: summary.to_string(),
+            compressed: false,
+        }
+    }
+
+    #[test]
+    fn compresses_oldest_material_first() {
+        let sections = vec![
+            section("一", "雨夜驿站。林雪救下沈舟。"),
+            section("二", "玉佩碎了。沈舟不告而别。"),
+        ];
+        // "【一】雨夜驿站。林雪救下沈舟。\n【二】玉佩碎了。沈舟不告而别。" 共 31 字
+        assert_eq!(compress(sections.clone(), 31), sections);
+
+        let compressed = compress(sections.clone(), 25);
+        assert_eq!(
+            render(&compressed),
+            "【一】雨夜驿站。\n【二】玉佩碎了。沈舟不告而别。"
+        );
+        assert!(compressed[0].compressed && !compressed[1].compressed);
+
+        let dropped = compress(sections, 10);
+        assert_eq!(render(&dropped), "【二】玉佩碎了。");
+        assert!(compress(Vec::new(), 0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn maintains_synopsis_in_chapter_order() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            "INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+             INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+             INSERT INTO executions (id, workflow_id, status, final_output) VALUES
+                 ('e1', 'w1', 'completed', '雨下得很急。林雪推开木门。沈舟倒在门外。'),
+                 ('e2', 'w1', 'completed', '玉佩碎了。');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let first = chapter::create(&pool, "p1", "雨夜", None, None)
+            .await
+            .unwrap();
+        let second = chapter::create(&pool, "p1", "玉佩", None, None)
+            .await
+            .unwrap();
+        chapter::link_execution(&pool, "e1", &first.id)
+            .await
+            .unwrap();
+        chapter::link_execution(&pool, "e2", &second.id)
+            .await
+            .unwrap();
+
+        let synopsis = append_chapter(&pool, &second.id, Some("沈舟不告而别。"))
+            .await
+            .unwrap();
+        assert_eq!(
+            synopsis.covers_up_to_chapter_id.as_deref(),
+            Some(second.id.as_str())
+        );
+        let synopsis = append_chapter(&pool, &first.id, None).await.unwrap();
+        assert_eq!(
+            synopsis.content,
+            "【雨夜】雨下得很急。林雪推开木门。\n【玉佩】沈舟不告而别。"
+        );
+        assert_eq!(get(&pool, "p1").await.unwrap().content, synopsis.content);
+
+        let rebuilt = rebuild(&pool, "p1", Some(&second.id)).await.unwrap();
+        assert_eq!(
+            rebuilt.content,
+            "【雨夜】雨下得很急。林雪推开木门。\n【玉佩】玉佩碎了。"
+        );
+        assert!(rebuild(&pool, "p1", Some("missing")).await.is_err());
+
+        let configured = configure(&pool, "p1", true, 20).await.unwrap();
+        assert_eq!(
+            configured.content,
+            "【雨夜】雨下得很急。\n【玉佩】玉佩碎了。"
+        );
+        let stored = get(&pool, "p1").await.unwrap();
+        assert!(stored.auto_update);
+        assert_eq!(stored.char_budget, 20);
+        assert!(configure(&pool, "p1", true, 0).await.is_err());
+    }
+}
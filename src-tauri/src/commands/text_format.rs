@@ -0,0 +1,191 @@
+// 输出格式化: 纯文本转换, 不访问数据库也不调用 AI, 供输出格式化面板与工作流的输出后处理使用
+use regex::Regex;
+
+/// 剧本页面宽度 (显示列数), 角色名在此宽度内居中
+const PAGE_WIDTH: usize = 60;
+
+/// 台词缩进列数
+const DIALOGUE_INDENT: usize = 10;
+
+/// 行首 `角色名: 台词` (支持全角冒号); 角色名不超过 20 个字符, 且不含句读标点
+const DIALOGUE_PATTERN: &str = r#"^\s*([^\s:：。，,.!?！？“”"「」]{1,20}(?: [^\s:：。，,.!?！？“”"「」]+){0,2})\s*[:：]\s*(\S.*)$"#;
+
+/// 支持的文本转换, 按请求中的顺序依次执行
+pub const TEXT_TRANSFORMS: &[&str] = &[
+    "trim_whitespace",
+    "normalize_quotes",
+    "remove_duplicate_newlines",
+    "capitalize_sentences",
+    "fix_ellipsis",
+];
+
+// 显示宽度: 非 ASCII 字符 (中文、全角标点) 按两列计
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+/// 把 `角色名: 台词` 形式的对白转换为剧本格式: 角色名居中, 台词缩进, 各段之间空一行;
+/// 其他非空行作为动作描写原样保留. 文本中没有对白时报错
+pub fn format_dialogue(text: &str) -> Result<String, String> {
+    let dialogue_line = Regex::new(DIALOGUE_PATTERN).map_err(|e| e.to_string())?;
+    let mut blocks: Vec<String> = Vec::new();
+    let mut found = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+        match dialogue_line.captures(line) {
+            Some(captures) => {
+                found = true;
+                let name = captures[1].to_uppercase();
+                let padding = PAGE_WIDTH.saturating_sub(display_width(&name)) / 2;
+                blocks.push(format!(
+                    "{}{}\n{}{}",
+                    " ".repeat(padding),
+                    name,
+                    " ".repeat(DIALOGUE_INDENT),
+                    captures[2].trim()
+                ));
+            }
+            None => blocks.push(line.trim().to_string()),
+        }
+    }
+    if !found {
+        return Err("未找到对白, 对白应为每行 `角色名: 台词` 的形式".to_string());
+    }
+    let mut formatted = blocks.join("\n\n");
+    formatted.push('\n');
+    Ok(formatted)
+}
+
+// 句首字母大写: 文本开头与句末标点 (`.!?`) 后空白之后的第一个字母
+fn capitalize_sentences(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    let mut after_terminal = false;
+    for c in text.chars() {
+        if sentence_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            sentence_start = false;
+            after_terminal = false;
+            continue;
+        }
+        if c.is_whitespace() {
+            sentence_start |= after_terminal;
+        } else if !matches!(c, '"' | '\'' | '(' | '[') {
+            sentence_start = false;
+        }
+        after_terminal = matches!(c, '.' | '!' | '?') || (after_terminal && c.is_whitespace());
+        result.push(c);
+    }
+    result
+}
+
+fn apply_transform(transform: &str, text: &str) -> String {
+    match transform {
+        "trim_whitespace" => text
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string(),
+        "normalize_quotes" => text
+            .chars()
+            .map(|c| match c {
+                '“' | '”' | '„' | '‟' => '"',
+                '‘' | '’' | '‚' | '‛' => '\'',
+                _ => c,
+            })
+            .collect(),
+        "remove_duplicate_newlines" => Regex::new(r"\n(?:[ \t]*\n)+")
+            .expect("换行模式有效")
+            .replace_all(text, "\n")
+            .into_owned(),
+        "capitalize_sentences" => capitalize_sentences(text),
+        "fix_ellipsis" => Regex::new(r"\.{3,}|\.(?: \.){2,}")
+            .expect("省略号模式有效")
+            .replace_all(text, "…")
+            .into_owned(),
+        _ => text.to_string(),
+    }
+}
+
+/// 按顺序执行文本转换; 包含不支持的转换时报错, 不做任何修改
+pub fn transform_text(text: &str, transforms: &[String]) -> Result<String, String> {
+    if let Some(unknown) = transforms
+        .iter()
+        .find(|t| !TEXT_TRANSFORMS.contains(&t.as_str()))
+    {
+        return Err(format!("不支持的文本转换: {}", unknown));
+    }
+    Ok(transforms.iter().fold(text.to_string(), |text, transform| {
+        apply_transform(transform, &text)
+    }))
+}
+
+#[tauri::command]
+pub fn format_output_as_dialogue(text: String) -> Result<String, String> {
+    format_dialogue(&text)
+}
+
+#[tauri::command]
+pub fn apply_text_transform(text: String, transforms: Vec<String>) -> Result<String, String> {
+    transform_text(&text, &transforms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_dialogue_lines_as_screenplay() {
+        let text = "雨夜, 驿站。\n林雪：你来了。\n\nJohn Smith: Sorry, the road was flooded.\n";
+        let formatted = format_dialogue(text).unwrap();
+        let lines: Vec<&str> = formatted.lines().collect();
+        assert_eq!(lines[0], "雨夜, 驿站。");
+        // "林雪" 显示宽度为 4, (60 - 4) / 2 = 28
+        assert_eq!(lines[2], format!("{}林雪", " ".repeat(28)));
+        assert_eq!(lines[3], format!("{}你来了。", " ".repeat(10)));
+        assert_eq!(lines[5], format!("{}JOHN SMITH", " ".repeat(25)));
+        assert_eq!(
+            lines[6],
+            format!("{}Sorry, the road was flooded.", " ".repeat(10))
+        );
+
+        // 含句读标点的前缀不是角色名
+        assert!(format_dialogue("她说了一句话，大意是: 快走").is_err());
+        assert!(format_dialogue("林雪:").is_err());
+        assert!(format_dialogue("").is_err());
+    }
+
+    fn transforms(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn applies_text_transforms_in_order() {
+        let text = "  “hello,” she said... wait!  \n\n\n\nit’s late. . . go home?  ";
+        assert_eq!(
+            transform_text(
+                text,
+                &transforms(&[
+                    "trim_whitespace",
+                    "normalize_quotes",
+                    "remove_duplicate_newlines",
+                    "fix_ellipsis",
+                    "capitalize_sentences",
+                ])
+            )
+            .unwrap(),
+            "\"Hello,\" she said… wait!\nIt's late… go home?"
+        );
+        assert_eq!(
+            transform_text("a. b", &transforms(&["capitalize_sentences"])).unwrap(),
+            "A. B"
+        );
+        assert_eq!(transform_text("原文", &[]).unwrap(), "原文");
+        assert!(transform_text(text, &transforms(&["trim_whitespace", "shout"])).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+// 执行时间线: 把节点结果换算为相对执行开始的偏移量, 前端直接按比例绘制甘特图
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db;
+
+// 时间线中的单个节点结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    pub node_result_id: String,
+    pub node_id: String,
+    pub node_name: Option<String>,
+    pub node_type: Option<String>,
+    pub iteration: i64,
+    pub status: String,
+    // 状态颜色提示: green / red / blue / orange / gray
+    pub color: String,
+    pub start_offset_ms: i64,
+    pub end_offset_ms: i64,
+    // 按执行总时长归一化到 0..=1
+    pub start_ratio: f64,
+    pub end_ratio: f64,
+    pub duration_ms: i64,
+    // 有首个片段时间时, 拆分为等待提供商与流式输出两段
+    pub provider_wait_ms: Option<i64>,
+    pub streaming_ms: Option<i64>,
+    // 尚未结束的节点按当前时间计算
+    pub running: bool,
+}
+
+// 执行时间线
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionTimeline {
+    pub execution_id: String,
+    pub status: String,
+    pub total_ms: i64,
+    // 进入队列到开始执行的等待, 未排队时为 None
+    pub queue_wait_ms: Option<i64>,
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TimelineRow {
+    id: String,
+    node_id: String,
+    node_name: Option<String>,
+    node_type: Option<String>,
+    iteration: i64,
+    status: String,
+    started_at: String,
+    finished_at: Option<String>,
+    first_token_at: Option<String>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok()
+}
+
+/// 节点状态对应的颜色提示
+pub fn status_color(status: &str) -> &'static str {
+    match status {
+        "completed" => "green",
+        "failed" => "red",
+        "running" => "blue",
+        "timeout" => "orange",
+        _ => "gray",
+    }
+}
+
+/// 构建执行时间线; `now` 用于计算仍在运行的执行与节点
+pub async fn build_timeline(
+    pool: &SqlitePool,
+    execution_id: &str,
+    now: DateTime<Utc>,
+) -> Result<ExecutionTimeline, String> {
+    let execution: Option<(String, String, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT status, started_at, finished_at, queued_at FROM executions WHERE id = ?",
+    )
+    .bind(execution_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (status, started_at, finished_at, queued_at) =
+        execution.ok_or_else(|| format!("执行记录不存在: {}", execution_id))?;
+    let start =
+        parse_time(&started_at).ok_or_else(|| format!("执行开始时间无效: {}", started_at))?;
+    let now = now.fixed_offset();
+    let offset = |time: DateTime<FixedOffset>| (time - start).num_milliseconds().max(0);
+
+    let rows = sqlx::query_as::<_, TimelineRow>(
+        "SELECT r.id, r.node_id, n.name AS node_name, n.type AS node_type, r.iteration, r.status,
+                r.started_at, r.finished_at, r.first_token_at
+         FROM node_results r
+         LEFT JOIN nodes n ON n.id = r.node_id
+         WHERE r.execution_id = ?
+         ORDER BY r.started_at, r.rowid",
+    )
+    .bind(execution_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut entries: Vec<TimelineEntry> = rows
+        .into_iter()
+        .map(|row| {
+            let node_start = parse_time(&row.started_at).unwrap_or(start);
+            let node_end = row.finished_at.as_deref().and_then(parse_time);
+            let end = node_end.unwrap_or(now).max(node_start);
+            let first_token = row
+                .first_token_at
+                .as_deref()
+                .and_then(parse_time)
+                .map(|t| t.clamp(node_start, end));
+            TimelineEntry {
+                node_result_id: row.id,
+                node_id: row.node_id,
+                node_name: row.node_name,
+                node_type: row.node_type,
+                iteration: row.iteration,
+                color: status_color(&row.status).to_string(),
+                status: row.status,
+                start_offset_ms: offset(node_start),
+                end_offset_ms: offset(end),
+                start_ratio: 0.0,
+                end_ratio: 0.0,
+                duration_ms: (end - node_start).num_milliseconds(),
+                provider_wait_ms: first_token.map(|t| (t - node_start).num_milliseconds()),
+                streaming_ms: first_token.map(|t| (end - t).num_milliseconds()),
+                running: node_end.is_none(),
+            }
+        })
+        .collect();
+
+    let execution_end = finished_at.as_deref().and_then(parse_time).unwrap_or(now);
+    let total_ms = entries
+        .iter()
+        .map(|e| e.end_offset_ms)
+        .fold(offset(execution_end), i64::max);
+    for entry in &mut entries {
+        if total_ms > 0 {
+            entry.start_ratio = entry.start_offset_ms as f64 / total_ms as f64;
+            entry.end_ratio = entry.end_offset_ms as f64 / total_ms as f64;
+        }
+    }
+
+    Ok(ExecutionTimeline {
+        execution_id: execution_id.to_string(),
+        status,
+        total_ms,
+        queue_wait_ms: queued_at
+            .as_deref()
+            .and_then(parse_time)
+            .map(|queued| (start - queued).num_milliseconds().max(0)),
+        entries,
+    })
+}
+
+#[tauri::command]
+pub async fn get_execution_timeline(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<ExecutionTimeline, String> {
+    let pool = db::pool(&app).await?;
+    build_timeline(&pool, &execution_id, Utc::now()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn offsets_node_results_from_execution_start() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'start', '开始', '{}', 0),
+                ('n2', 'w1', 'ai_chat', '正文', '{}', 1);
+            INSERT INTO executions (id, workflow_id, status, started_at, finished_at, queued_at)
+                VALUES ('e1', 'w1', 'completed', '2026-01-01T00:00:00Z', '2026-01-01T00:00:10Z', '2025-12-31T23:59:58Z');
+            INSERT INTO node_results (id, execution_id, node_id, status, started_at, finished_at, first_token_at) VALUES
+                ('r1', 'e1', 'n1', 'completed', '2026-01-01T00:00:00Z', '2026-01-01T00:00:01Z', NULL),
+                ('r2', 'e1', 'n2', 'failed', '2026-01-01T00:00:01Z', '2026-01-01T00:00:09Z', '2026-01-01T00:00:04Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let timeline = build_timeline(&pool, "e1", Utc::now()).await.unwrap();
+        assert_eq!(timeline.total_ms, 10_000);
+        assert_eq!(timeline.queue_wait_ms, Some(2_000));
+        let ai = &timeline.entries[1];
+        assert_eq!(ai.node_name.as_deref(), Some("正文"));
+        assert_eq!((ai.start_offset_ms, ai.end_offset_ms), (1_000, 9_000));
+        assert_eq!(ai.color, "red");
+        assert_eq!(ai.provider_wait_ms, Some(3_000));
+        assert_eq!(ai.streaming_ms, Some(5_000));
+        assert!((ai.end_ratio - 0.9).abs() < 1e-9);
+        assert!(timeline.entries[0].provider_wait_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn extends_running_nodes_to_now() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO executions (id, workflow_id, status, started_at)
+                VALUES ('e1', 'w1', 'running', '2026-01-01T00:00:00Z');
+            INSERT INTO node_results (id, execution_id, node_id, status, started_at)
+                VALUES ('r1', 'e1', 'n1', 'running', '2026-01-01T00:00:02Z');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2026-01-01T00:00:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let timeline = build_timeline(&pool, "e1", now).await.unwrap();
+        assert_eq!(timeline.total_ms, 5_000);
+        assert!(timeline.queue_wait_ms.is_none());
+        let entry = &timeline.entries[0];
+        assert!(entry.running);
+        assert_eq!(entry.duration_ms, 3_000);
+        assert!(entry.node_name.is_none());
+    }
+}
@@ -0,0 +1,331 @@
+// 按 token 上限处理文本: 调用 AI 前截断过长的提示词, 以及把长文档切分为带重叠的分块;
+// token 数按 `tokens::count_tokens` 估算
+use serde::Serialize;
+
+use crate::tokens;
+
+/// 支持的截断方式: 从末尾删除、从开头删除、删除中间部分
+pub const TRUNCATION_STRATEGIES: &[&str] = &["end", "start", "middle"];
+
+/// 删除中间部分时, 在保留的首尾之间插入的标记
+const MIDDLE_MARKER: &str = "\n……\n";
+
+// 长文档分块
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chunk {
+    pub index: i64,
+    pub content: String,
+    pub token_count: i64,
+}
+
+// 截断结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TruncationResult {
+    pub truncated_text: String,
+    pub tokens_removed: i64,
+    pub was_truncated: bool,
+}
+
+// 各字符的起始字节位置, 末尾追加文本长度; 第 k 个元素即前 k 个字符的结束位置
+fn char_bounds(text: &str) -> Vec<usize> {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect()
+}
+
+// 在 0..=n 中二分查找满足 fits 的最大值; fits 随参数增大由真变假, 0 视为总是满足
+fn max_fitting(n: usize, fits: impl Fn(usize) -> Result<bool, String>) -> Result<usize, String> {
+    let (mut low, mut high) = (0, n);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if fits(mid)? {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(low)
+}
+
+fn fits(text: &str, max_tokens: u64, provider_name: &str) -> Result<bool, String> {
+    Ok(tokens::count_tokens(text, provider_name)? <= max_tokens)
+}
+
+/// 不超过 token 上限的最长前缀的结束字节位置
+fn longest_prefix(text: &str, max_tokens: u64, provider_name: &str) -> Result<usize, String> {
+    let bounds = char_bounds(text);
+    let kept = max_fitting(bounds.len() - 1, |k| {
+        fits(&text[..bounds[k]], max_tokens, provider_name)
+    })?;
+    Ok(bounds[kept])
+}
+
+/// 不超过 token 上限的最长后缀的起始字节位置
+fn longest_suffix(text: &str, max_tokens: u64, provider_name: &str) -> Result<usize, String> {
+    let bounds = char_bounds(text);
+    let n = bounds.len() - 1;
+    let kept = max_fitting(n, |k| {
+        fits(&text[bounds[n - k]..], max_tokens, provider_name)
+    })?;
+    Ok(bounds[n - kept])
+}
+
+/// 按策略把文本截断到 token 上限以内; 未超出上限时原样返回
+pub fn truncate(
+    text: &str,
+    max_tokens: i64,
+    provider_name: &str,
+    truncation_strategy: &str,
+) -> Result<TruncationResult, String> {
+    if !TRUNCATION_STRATEGIES.contains(&truncation_strategy) {
+        return Err(format!("不支持的截断方式: {}", truncation_strategy));
+    }
+    if max_tokens < 0 {
+        return Err("token 上限不能为负数".to_string());
+    }
+    let max_tokens = max_tokens as u64;
+    let total = tokens::count_tokens(text, provider_name)?;
+    if total <= max_tokens {
+        return Ok(TruncationResult {
+            truncated_text: text.to_string(),
+            tokens_removed: 0,
+            was_truncated: false,
+        });
+    }
+
+    let bounds = char_bounds(text);
+    let n = bounds.len() - 1;
+    let truncated_text = match truncation_strategy {
+        "end" => text[..longest_prefix(text, max_tokens, provider_name)?].to_string(),
+        "start" => text[longest_suffix(text, max_tokens, provider_name)?..].to_string(),
+        _ => {
+            // 保留首尾各约一半, 中间以标记连接
+            let middle = |k: usize| {
+                let head = k.div_ceil(2);
+                format!(
+                    "{}{}{}",
+                    &text[..bounds[head]],
+                    MIDDLE_MARKER,
+                    &text[bounds[n - (k - head)]..]
+                )
+            };
+            match max_fitting(n, |k| fits(&middle(k), max_tokens, provider_name))? {
+                0 => String::new(),
+                kept => middle(kept),
+            }
+        }
+    };
+    let remaining = tokens::count_tokens(&truncated_text, provider_name)?;
+    Ok(TruncationResult {
+        truncated_text,
+        tokens_removed: total.saturating_sub(remaining) as i64,
+        was_truncated: true,
+    })
+}
+
+// 按句末标点与换行切分, 句末的右引号与其后的空白归入前一句; 各片段依次拼接即为原文
+fn sentence_spans(text: &str) -> Vec<&str> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '\n' | '。' | '！' | '？' | '.' | '!' | '?') {
+            continue;
+        }
+        while let Some(&(_, next)) = chars.peek() {
+            if !matches!(
+                next,
+                '”' | '」' | '"' | '\'' | '’' | '。' | '！' | '？' | '.' | '!' | '?'
+            ) {
+                break;
+            }
+            chars.next();
+        }
+        while chars.peek().is_some_and(|&(_, next)| next.is_whitespace()) {
+            chars.next();
+        }
+        let end = chars.peek().map_or(text.len(), |&(i, _)| i);
+        spans.push(&text[start..end]);
+        start = end;
+    }
+    if start < text.len() {
+        spans.push(&text[start..]);
+    }
+    spans
+}
+
+/// 把长文本切分为不超过 token 上限的分块: 尽量在句子边界切分, 超长的句子按字符切开;
+/// 相邻分块之间重叠前一块末尾不超过 `overlap_tokens` 的内容 (优先整句)
+pub fn split_into_chunks(
+    text: &str,
+    max_tokens_per_chunk: i64,
+    overlap_tokens: i64,
+    provider_name: &str,
+) -> Result<Vec<Chunk>, String> {
+    if max_tokens_per_chunk <= 0 {
+        return Err("每块 token 上限必须大于 0".to_string());
+    }
+    if overlap_tokens < 0 || overlap_tokens >= max_tokens_per_chunk {
+        return Err("重叠 token 数须不小于 0 且小于每块上限".to_string());
+    }
+    let max_tokens = max_tokens_per_chunk as u64;
+    let overlap = overlap_tokens as u64;
+
+    // 超过上限的句子按字符切成不超过 (上限 - 重叠) 的片段, 为重叠内容留出空间
+    let piece_limit = max_tokens - overlap;
+    let mut pieces: Vec<&str> = Vec::new();
+    for span in sentence_spans(text) {
+        if fits(span, max_tokens, provider_name)? {
+            pieces.push(span);
+            continue;
+        }
+        let mut rest = span;
+        while !rest.is_empty() {
+            let first = rest.chars().next().map_or(0, char::len_utf8);
+            let end = longest_prefix(rest, piece_limit, provider_name)?.max(first);
+            pieces.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+
+    let fits_with = |parts: &[&str], piece: &str, limit: u64| {
+        fits(
+            &format!("{}{}", parts.concat(), piece),
+            limit,
+            provider_name,
+        )
+    };
+    let mut contents: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for piece in pieces {
+        if current.is_empty() || fits_with(&current, piece, max_tokens)? {
+            current.push(piece);
+            continue;
+        }
+        // 当前块已满: 取末尾不超过重叠上限的整句作为下一块开头, 一句都放不下时按字符截取
+        let mut carried: Vec<&str> = Vec::new();
+        for previous in current.iter().rev() {
+            if overlap == 0 || !fits_with(&[*previous], &carried.concat(), overlap)? {
+                break;
+            }
+            carried.insert(0, *previous);
+        }
+        if carried.is_empty() && overlap > 0 {
+            let last = current[current.len() - 1];
+            let start = longest_suffix(last, overlap, provider_name)?;
+            if start < last.len() {
+                carried.push(&last[start..]);
+            }
+        }
+        while !carried.is_empty() && !fits_with(&carried, piece, max_tokens)? {
+            carried.remove(0);
+        }
+        contents.push(current.concat());
+        carried.push(piece);
+        current = carried;
+    }
+    if !current.is_empty() {
+        contents.push(current.concat());
+    }
+
+    contents
+        .into_iter()
+        .enumerate()
+        .map(|(index, content)| {
+            Ok(Chunk {
+                index: index as i64,
+                token_count: tokens::count_tokens(&content, provider_name)? as i64,
+                content,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn truncate_to_token_limit(
+    text: String,
+    max_tokens: i64,
+    provider_name: String,
+    truncation_strategy: String,
+) -> Result<TruncationResult, String> {
+    truncate(&text, max_tokens, &provider_name, &truncation_strategy)
+}
+
+#[tauri::command]
+pub fn split_long_output_into_chunks(
+    text: String,
+    max_tokens_per_chunk: i64,
+    overlap_tokens: i64,
+    provider_name: String,
+) -> Result<Vec<Chunk>, String> {
+    split_into_chunks(&text, max_tokens_per_chunk, overlap_tokens, &provider_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_by_strategy() {
+        // 每个汉字 1.5 token, 10 个汉字共 15 token
+        let text = "一二三四五六七八九十";
+        let unchanged = truncate(text, 15, "openai", "end").unwrap();
+        assert!(!unchanged.was_truncated);
+        assert_eq!(unchanged.truncated_text, text);
+
+        let end = truncate(text, 6, "openai", "end").unwrap();
+        assert_eq!(end.truncated_text, "一二三四");
+        assert_eq!(end.tokens_removed, 9);
+        assert!(end.was_truncated);
+
+        let start = truncate(text, 6, "openai", "start").unwrap();
+        assert_eq!(start.truncated_text, "七八九十");
+
+        // 标记 "……" 计为一个词 (1.3), 首尾各两字: 6 + 1.3 → 8
+        let middle = truncate(text, 8, "openai", "middle").unwrap();
+        assert_eq!(middle.truncated_text, "一二\n……\n九十");
+        assert_eq!(middle.tokens_removed, 7);
+        assert_eq!(
+            truncate(text, 1, "openai", "middle")
+                .unwrap()
+                .truncated_text,
+            ""
+        );
+
+        assert!(truncate(text, 6, "openai", "random").is_err());
+        assert!(truncate(text, -1, "openai", "end").is_err());
+        assert!(truncate(text, 6, "unknown", "end").is_err());
+    }
+
+    fn contents(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|c| c.content.as_str()).collect()
+    }
+
+    #[test]
+    fn splits_at_sentence_boundaries_with_overlap() {
+        // 每句 3 个汉字加句号: 4.5 + 1.3 → 6 token, 两句 → 12 token
+        let text = "第一句。第二句。第三句。第四句。";
+        let chunks = split_into_chunks(text, 12, 0, "openai").unwrap();
+        assert_eq!(contents(&chunks), ["第一句。第二句。", "第三句。第四句。"]);
+        assert_eq!(chunks[1].index, 1);
+        assert_eq!(chunks[1].token_count, 12);
+
+        let overlapping = split_into_chunks(text, 12, 6, "openai").unwrap();
+        assert_eq!(
+            contents(&overlapping),
+            ["第一句。第二句。", "第二句。第三句。", "第三句。第四句。"]
+        );
+
+        // 没有句末标点的长句按字符切开, 片段为 (上限 - 重叠) 以内
+        let long = split_into_chunks("一二三四五六七八九十", 6, 3, "openai").unwrap();
+        assert_eq!(
+            contents(&long),
+            ["一二三四", "三四五六", "五六七八", "七八九十"]
+        );
+
+        assert!(split_into_chunks("", 12, 0, "openai").unwrap().is_empty());
+        assert!(split_into_chunks(text, 0, 0, "openai").is_err());
+        assert!(split_into_chunks(text, 12, 12, "openai").is_err());
+    }
+}
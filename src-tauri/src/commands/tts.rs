@@ -0,0 +1,420 @@
+// 章节朗读导出: 按句切分章节, 逐段调用 TTS 接口后拼接为单个 MP3
+//
+// 章节即执行记录的最终输出. 已完成的分段保存在临时目录中, 失败后重新调用只补齐缺失分段,
+// 全部拼接成功后才清理临时目录.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{network, provider};
+use crate::db;
+use crate::error::CommandError;
+
+/// 单个分段的最大字符数
+pub const MAX_SEGMENT_CHARS: usize = 4000;
+
+/// 句末标点, 分段只在这些字符之后断开
+const SENTENCE_ENDINGS: &[char] = &['。', '！', '？', '!', '?', '…', ';', '；', '\n'];
+
+/// 紧跟在句末标点后、应归入同一句的闭合符号
+// 半角引号无法区分开闭, 不计入
+const CLOSING_MARKS: &[char] = &['”', '’', '」', '』', ')', '）'];
+
+/// 未配置 base_url 时使用的 OpenAI 兼容地址
+const DEFAULT_TTS_BASE_URL: &str = "https://api.openai.com/v1";
+
+// 已请求取消的章节
+static CANCELLED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// 朗读配置; 未指定 url 时使用提供商配置中的 `tts_url`, 再退回 `{base_url}/audio/speech`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceConfig {
+    pub provider_id: String,
+    pub voice: String,
+    #[serde(default = "default_model")]
+    pub model: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub speed: Option<f64>,
+    // 两次请求之间的最小间隔, 用于限速
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+fn default_model() -> String {
+    "tts-1".to_string()
+}
+
+fn default_interval_ms() -> u64 {
+    500
+}
+
+// 进度事件负载 (`tts:progress`)
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioExportProgress {
+    pub chapter_id: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+// 导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioExportResult {
+    pub path: String,
+    pub segments: usize,
+    pub bytes: usize,
+}
+
+/// 按句末标点切分文本, 保留标点与紧随的闭合符号; 各句拼接后与原文一致
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        if SENTENCE_ENDINGS.contains(&c) {
+            while let Some(&next) = chars.peek() {
+                if !CLOSING_MARKS.contains(&next) {
+                    break;
+                }
+                current.push(next);
+                chars.next();
+            }
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences
+}
+
+/// 按句切分文本, 每段不超过 `max_chars` 个字符; 超长的单句按字符硬切
+pub fn split_segments(text: &str, max_chars: usize) -> Vec<String> {
+    let sentences = split_sentences(text);
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut segment_len = 0;
+    for sentence in sentences {
+        let len = sentence.chars().count();
+        if segment_len + len > max_chars && segment_len > 0 {
+            segments.push(std::mem::take(&mut segment));
+            segment_len = 0;
+        }
+        if len > max_chars {
+            let chars: Vec<char> = sentence.chars().collect();
+            for chunk in chars.chunks(max_chars) {
+                segments.push(chunk.iter().collect());
+            }
+            continue;
+        }
+        segment.push_str(&sentence);
+        segment_len += len;
+    }
+    segments.push(segment);
+    segments
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// 去掉 MP3 开头的 ID3v2 标签, 拼接时只保留第一段的标签
+pub fn strip_id3v2(data: &[u8]) -> &[u8] {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return data;
+    }
+    // 标签长度为 4 字节 syncsafe 整数, 不含 10 字节头部; 标志位 0x10 表示带 10 字节尾部
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |acc, b| (acc << 7) | (*b as usize & 0x7f));
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    data.get(10 + size + footer..).unwrap_or(&[])
+}
+
+/// 拼接各分段的 MP3 数据
+pub fn concat_mp3(segments: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        if index == 0 {
+            out.extend_from_slice(segment);
+        } else {
+            out.extend_from_slice(strip_id3v2(segment));
+        }
+    }
+    out
+}
+
+/// 分段缓存目录: 章节内容或朗读配置变化时使用新目录, 避免拼入旧音频
+pub fn segment_dir(chapter_id: &str, text: &str, voice: &VoiceConfig) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    chapter_id.hash(&mut hasher);
+    text.hash(&mut hasher);
+    serde_json::to_string(voice)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    std::env::temp_dir().join("chouann-tts").join(format!(
+        "{}-{:016x}",
+        chapter_id,
+        hasher.finish()
+    ))
+}
+
+fn segment_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{:04}.mp3", index))
+}
+
+/// 读取章节正文 (执行记录的最终输出)
+pub async fn chapter_text(pool: &SqlitePool, chapter_id: &str) -> Result<String, String> {
+    let row: Option<Option<String>> =
+        sqlx::query_scalar("SELECT final_output FROM executions WHERE id = ?")
+            .bind(chapter_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    match row {
+        None => Err(format!("章节不存在: {}", chapter_id)),
+        Some(text) => text
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| format!("章节没有正文: {}", chapter_id)),
+    }
+}
+
+/// 解析 TTS 接口地址与 API Key
+pub async fn resolve_endpoint(
+    pool: &SqlitePool,
+    voice: &VoiceConfig,
+) -> Result<(String, String), String> {
+    let providers = provider::load_providers(pool).await?;
+    let config = providers
+        .get(&voice.provider_id)
+        .ok_or_else(|| format!("提供商不存在: {}", voice.provider_id))?;
+    let field = |key: &str| {
+        config
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let url = voice
+        .url
+        .clone()
+        .filter(|u| !u.trim().is_empty())
+        .or_else(|| field("tts_url"))
+        .unwrap_or_else(|| {
+            let base = field("base_url").unwrap_or_else(|| DEFAULT_TTS_BASE_URL.to_string());
+            let base = base.trim_end_matches('/');
+            if base.ends_with("/v1") {
+                format!("{}/audio/speech", base)
+            } else {
+                format!("{}/v1/audio/speech", base)
+            }
+        });
+    Ok((url, field("api_key").unwrap_or_default()))
+}
+
+async fn synthesize(
+    url: &str,
+    api_key: &str,
+    voice: &VoiceConfig,
+    text: &str,
+) -> Result<Vec<u8>, String> {
+    let mut body = serde_json::json!({
+        "model": voice.model,
+        "voice": voice.voice,
+        "input": text,
+        "response_format": "mp3",
+    });
+    if let Some(speed) = voice.speed {
+        body["speed"] = serde_json::json!(speed);
+    }
+    let mut request = tauri_plugin_http::reqwest::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+    if !api_key.is_empty() {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| e.to_string())
+}
+
+fn take_cancelled(chapter_id: &str) -> bool {
+    let mut cancelled = CANCELLED.lock().unwrap_or_else(|e| e.into_inner());
+    match cancelled.iter().position(|id| id == chapter_id) {
+        Some(index) => {
+            cancelled.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 请求取消章节朗读导出, 在下一个分段开始前生效
+pub fn request_cancel(chapter_id: &str) {
+    let mut cancelled = CANCELLED.lock().unwrap_or_else(|e| e.into_inner());
+    if !cancelled.iter().any(|id| id == chapter_id) {
+        cancelled.push(chapter_id.to_string());
+    }
+}
+
+#[tauri::command]
+pub async fn export_chapter_audio(
+    app: AppHandle,
+    chapter_id: String,
+    path: String,
+    voice_config: VoiceConfig,
+) -> Result<AudioExportResult, CommandError> {
+    let pool = db::pool(&app).await?;
+    let text = chapter_text(&pool, &chapter_id).await?;
+    let (url, api_key) = resolve_endpoint(&pool, &voice_config).await?;
+    let segments = split_segments(&text, MAX_SEGMENT_CHARS);
+    let dir = segment_dir(&chapter_id, &text, &voice_config);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    // 清除上一次导出遗留的取消标记
+    take_cancelled(&chapter_id);
+
+    let total = segments.len();
+    let interval = Duration::from_millis(voice_config.interval_ms);
+    let mut requested = false;
+    for (index, segment) in segments.iter().enumerate() {
+        let file = segment_path(&dir, index);
+        if !file.exists() {
+            if take_cancelled(&chapter_id) {
+                return Err(CommandError::Other("朗读导出已取消".to_string()));
+            }
+            // 已缓存的分段不需要联网, 只在实际请求前检查离线模式
+            network::guard(&app, &pool, "tts").await?;
+            if requested {
+                tokio::time::sleep(interval).await;
+            }
+            requested = true;
+            let audio = synthesize(&url, &api_key, &voice_config, segment)
+                .await
+                .map_err(|e| format!("第 {} 段合成失败, 可重试继续: {}", index + 1, e))?;
+            // 先写入临时文件再改名, 中断时不会留下不完整的分段
+            let partial = file.with_extension("part");
+            std::fs::write(&partial, &audio).map_err(|e| format!("写入分段失败: {}", e))?;
+            std::fs::rename(&partial, &file).map_err(|e| format!("写入分段失败: {}", e))?;
+        }
+        let progress = AudioExportProgress {
+            chapter_id: chapter_id.clone(),
+            completed: index + 1,
+            total,
+        };
+        app.emit("tts:progress", &progress)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let parts = (0..total)
+        .map(|index| std::fs::read(segment_path(&dir, index)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("读取分段失败: {}", e))?;
+    let audio = concat_mp3(&parts);
+    std::fs::write(&path, &audio).map_err(|e| format!("写入文件失败: {}", e))?;
+    if let Err(e) = std::fs::remove_dir_all(&dir) {
+        eprintln!("清理朗读临时目录失败: {}", e);
+    }
+    Ok(AudioExportResult {
+        path,
+        segments: total,
+        bytes: audio.len(),
+    })
+}
+
+#[tauri::command]
+pub async fn cancel_chapter_audio_export(chapter_id: String) -> Result<(), String> {
+    request_cancel(&chapter_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_sentence_boundaries() {
+        let text = "雨下得很急。林雪推开木门。“姑娘, 可否借个火?”他问。";
+        let segments = split_segments(text, 15);
+        assert_eq!(
+            segments,
+            vec!["雨下得很急。林雪推开木门。", "“姑娘, 可否借个火?”他问。"]
+        );
+        assert!(segments.iter().all(|s| s.chars().count() <= 15));
+
+        let long = "啊".repeat(9);
+        assert_eq!(split_segments(&long, 4), vec!["啊啊啊啊", "啊啊啊啊", "啊"]);
+        assert!(split_segments("  \n ", 10).is_empty());
+    }
+
+    #[test]
+    fn concatenates_without_repeating_id3_tags() {
+        let tagged = |frame: &[u8]| {
+            let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x02".to_vec();
+            data.extend_from_slice(b"tg");
+            data.extend_from_slice(frame);
+            data
+        };
+        assert_eq!(strip_id3v2(&tagged(b"\xff\xfb")), b"\xff\xfb");
+        assert_eq!(strip_id3v2(b"\xff\xfb"), b"\xff\xfb");
+
+        let audio = concat_mp3(&[tagged(b"A"), tagged(b"B"), b"C".to_vec()]);
+        let mut expected = tagged(b"A");
+        expected.extend_from_slice(b"BC");
+        assert_eq!(audio, expected);
+    }
+
+    #[tokio::test]
+    async fn resolves_tts_endpoint_from_provider_config() {
+        let pool = db::test_pool().await;
+        sqlx::query("UPDATE global_config SET ai_providers = ? WHERE id = 1")
+            .bind(
+                r#"{"openai":{"api_key":"sk-1","base_url":"https://relay.example/v1/"},
+                    "custom":{"api_key":"k","tts_url":"https://tts.example/speak"}}"#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        let voice: VoiceConfig =
+            serde_json::from_str(r#"{"provider_id":"openai","voice":"alloy"}"#).unwrap();
+
+        let (url, key) = resolve_endpoint(&pool, &voice).await.unwrap();
+        assert_eq!(url, "https://relay.example/v1/audio/speech");
+        assert_eq!(key, "sk-1");
+        let custom = VoiceConfig {
+            provider_id: "custom".to_string(),
+            ..voice.clone()
+        };
+        assert_eq!(
+            resolve_endpoint(&pool, &custom).await.unwrap().0,
+            "https://tts.example/speak"
+        );
+        let missing = VoiceConfig {
+            provider_id: "missing".to_string(),
+            ..voice
+        };
+        assert!(resolve_endpoint(&pool, &missing).await.is_err());
+    }
+
+    #[test]
+    fn cancel_flag_is_consumed_once() {
+        request_cancel("c1");
+        request_cancel("c1");
+        assert!(take_cancelled("c1"));
+        assert!(!take_cancelled("c1"));
+    }
+}
@@ -0,0 +1,292 @@
+// 界面偏好 (面板尺寸、上次打开的标签页、编辑器字号等): 保存在数据库中, 随数据库备份一起迁移.
+// 写入先合并到内存, 延迟 FLUSH_DELAY 后批量落库, 拖动调整尺寸等连续写入同一键时只写最后一次
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db;
+
+/// 写入合并的时间窗口
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+/// 偏好导出文件格式版本
+pub const EXPORT_VERSION: i64 = 1;
+
+// 偏好导出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiPrefsExport {
+    pub version: i64,
+    pub exported_at: String,
+    pub preferences: BTreeMap<String, Value>,
+}
+
+/// 待写入的偏好, 作为应用状态管理
+#[derive(Default)]
+pub struct UiPrefsState {
+    /// 键 -> (JSON 值, 修改时间)
+    pending: Mutex<HashMap<String, (String, String)>>,
+    flush_scheduled: AtomicBool,
+}
+
+impl UiPrefsState {
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<String, (String, String)>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录一次写入, 同一键只保留最新值
+    pub fn set(&self, key: &str, value_json: &str) -> Result<(), String> {
+        if key.trim().is_empty() {
+            return Err("偏好键不能为空".to_string());
+        }
+        serde_json::from_str::<Value>(value_json).map_err(|e| format!("无效的 JSON: {}", e))?;
+        self.pending()
+            .insert(key.to_string(), (value_json.to_string(), db::now()));
+        Ok(())
+    }
+
+    fn take(&self) -> HashMap<String, (String, String)> {
+        std::mem::take(&mut *self.pending())
+    }
+
+    // 写入失败时放回, 不覆盖期间的新写入
+    fn restore(&self, entries: HashMap<String, (String, String)>) {
+        let mut pending = self.pending();
+        for (key, entry) in entries {
+            pending.entry(key).or_insert(entry);
+        }
+    }
+}
+
+/// 把待写入的偏好落库
+pub async fn flush(pool: &SqlitePool, state: &UiPrefsState) -> Result<(), String> {
+    let entries = state.take();
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let result = write(pool, &entries).await;
+    if result.is_err() {
+        state.restore(entries);
+    }
+    result
+}
+
+async fn write(
+    pool: &SqlitePool,
+    entries: &HashMap<String, (String, String)>,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (key, (value, updated_at)) in entries {
+        sqlx::query(
+            "INSERT INTO ui_preferences (key, value, updated_at) VALUES (?, ?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 以 prefix 开头的全部偏好 (含尚未落库的写入), 按键排序
+pub async fn get_prefs(
+    pool: &SqlitePool,
+    state: &UiPrefsState,
+    prefix: &str,
+) -> Result<BTreeMap<String, Value>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT key, value FROM ui_preferences WHERE substr(key, 1, length(?1)) = ?1",
+    )
+    .bind(prefix)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let pending: Vec<(String, String)> = state
+        .pending()
+        .iter()
+        .filter(|(key, _)| key.starts_with(prefix))
+        .map(|(key, (value, _))| (key.clone(), value.clone()))
+        .collect();
+
+    let mut prefs = BTreeMap::new();
+    for (key, value) in rows.into_iter().chain(pending) {
+        // 无法解析的旧值跳过, 前端按默认值处理
+        if let Ok(value) = serde_json::from_str(&value) {
+            prefs.insert(key, value);
+        }
+    }
+    Ok(prefs)
+}
+
+/// 导出全部偏好
+pub async fn export(pool: &SqlitePool, state: &UiPrefsState) -> Result<UiPrefsExport, String> {
+    Ok(UiPrefsExport {
+        version: EXPORT_VERSION,
+        exported_at: db::now(),
+        preferences: get_prefs(pool, state, "").await?,
+    })
+}
+
+/// 导入偏好, 覆盖同名键, 返回导入的数量
+pub async fn import(
+    pool: &SqlitePool,
+    state: &UiPrefsState,
+    data: &UiPrefsExport,
+) -> Result<usize, String> {
+    if data.version > EXPORT_VERSION {
+        return Err(format!("不支持的偏好文件版本: {}", data.version));
+    }
+    // 先落库已有的写入, 避免稍后覆盖导入的值
+    flush(pool, state).await?;
+    let now = db::now();
+    let entries: HashMap<String, (String, String)> = data
+        .preferences
+        .iter()
+        .filter(|(key, _)| !key.trim().is_empty())
+        .map(|(key, value)| (key.clone(), (value.to_string(), now.clone())))
+        .collect();
+    write(pool, &entries).await?;
+    Ok(entries.len())
+}
+
+async fn flush_app(app: &AppHandle) -> Result<(), String> {
+    let pool = db::pool(app).await?;
+    flush(&pool, &app.state::<UiPrefsState>()).await
+}
+
+/// 窗口关闭时调用, 立即落库尚未写入的偏好
+pub fn flush_now(app: &AppHandle) {
+    if let Err(e) = tauri::async_runtime::block_on(flush_app(app)) {
+        eprintln!("保存界面偏好失败: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn set_ui_pref(
+    app: AppHandle,
+    state: State<'_, UiPrefsState>,
+    key: String,
+    value_json: String,
+) -> Result<(), String> {
+    state.set(&key, &value_json)?;
+    if !state.flush_scheduled.swap(true, Ordering::AcqRel) {
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(FLUSH_DELAY).await;
+            app.state::<UiPrefsState>()
+                .flush_scheduled
+                .store(false, Ordering::Release);
+            if let Err(e) = flush_app(&app).await {
+                eprintln!("保存界面偏好失败: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_ui_prefs(
+    app: AppHandle,
+    state: State<'_, UiPrefsState>,
+    prefix: Option<String>,
+) -> Result<BTreeMap<String, Value>, String> {
+    let pool = db::pool(&app).await?;
+    get_prefs(&pool, &state, prefix.as_deref().unwrap_or("")).await
+}
+
+#[tauri::command]
+pub async fn export_ui_prefs(
+    app: AppHandle,
+    state: State<'_, UiPrefsState>,
+    path: String,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    let data = export(&pool, &state).await?;
+    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    std::fs::write(Path::new(&path), json).map_err(|e| format!("写入文件失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn import_ui_prefs(
+    app: AppHandle,
+    state: State<'_, UiPrefsState>,
+    path: String,
+) -> Result<usize, String> {
+    let pool = db::pool(&app).await?;
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取文件 {}: {}", path, e))?;
+    let data: UiPrefsExport =
+        serde_json::from_str(&content).map_err(|_| "无效的偏好文件格式".to_string())?;
+    import(&pool, &state, &data).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn coalesces_writes_until_flushed() {
+        let pool = db::test_pool().await;
+        let state = UiPrefsState::default();
+
+        state.set("layout.sidebar_width", "240").unwrap();
+        state.set("layout.sidebar_width", "260").unwrap();
+        state.set("editor.font_size", "16").unwrap();
+        assert!(state.set("layout.sidebar_width", "{").is_err());
+        assert!(state.set(" ", "1").is_err());
+
+        // 未落库的写入也能读到
+        let layout = get_prefs(&pool, &state, "layout.").await.unwrap();
+        assert_eq!(layout.len(), 1);
+        assert_eq!(layout["layout.sidebar_width"], 260);
+
+        flush(&pool, &state).await.unwrap();
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ui_preferences")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 2);
+
+        state.set("layout.sidebar_width", "300").unwrap();
+        let all = get_prefs(&pool, &state, "").await.unwrap();
+        assert_eq!(all["layout.sidebar_width"], 300);
+        assert_eq!(all["editor.font_size"], 16);
+    }
+
+    #[tokio::test]
+    async fn exports_and_imports_prefs() {
+        let pool = db::test_pool().await;
+        let state = UiPrefsState::default();
+        state
+            .set("layout.tabs", r#"["outline","chapters"]"#)
+            .unwrap();
+        state.set("editor.font_size", "16").unwrap();
+        let data = export(&pool, &state).await.unwrap();
+        assert_eq!(data.preferences.len(), 2);
+
+        let restored = db::test_pool().await;
+        let restored_state = UiPrefsState::default();
+        restored_state.set("editor.font_size", "20").unwrap();
+        assert_eq!(import(&restored, &restored_state, &data).await.unwrap(), 2);
+        let prefs = get_prefs(&restored, &restored_state, "").await.unwrap();
+        assert_eq!(prefs["editor.font_size"], 16);
+        assert_eq!(
+            prefs["layout.tabs"],
+            serde_json::json!(["outline", "chapters"])
+        );
+
+        let newer = UiPrefsExport {
+            version: EXPORT_VERSION + 1,
+            ..data
+        };
+        assert!(import(&restored, &restored_state, &newer).await.is_err());
+    }
+}
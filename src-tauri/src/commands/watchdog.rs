@@ -0,0 +1,231 @@
+// 执行看门狗: 运行中的执行超过设定时长没有心跳时通知前端引擎中止当前节点.
+// 巡检时同时写入应用存活心跳, 供命令行判断是否有正在运行的应用实例
+use std::time::Duration;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+
+/// 看门狗巡检间隔, 也是应用存活心跳的间隔
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+// 卡住的执行, 作为 `execution:watchdog_triggered` 事件负载
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct StalledExecution {
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub node_id: Option<String>,
+    pub node_name: Option<String>,
+    pub last_heartbeat_at: String,
+}
+
+/// 记录执行心跳, 同时清除上一次的触发标记
+pub async fn heartbeat(
+    pool: &SqlitePool,
+    execution_id: &str,
+    node_id: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO execution_runtime_state (execution_id, node_id, last_heartbeat_at)
+         VALUES (?, ?, ?)
+         ON CONFLICT(execution_id) DO UPDATE SET
+             node_id = excluded.node_id,
+             last_heartbeat_at = excluded.last_heartbeat_at,
+             watchdog_triggered_at = NULL",
+    )
+    .bind(execution_id)
+    .bind(node_id)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 记录应用存活心跳
+pub async fn record_app_alive(pool: &SqlitePool) -> Result<(), String> {
+    sqlx::query("UPDATE global_config SET app_heartbeat_at = ? WHERE id = 1")
+        .bind(db::now())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 应用是否在运行: 最近一次存活心跳距 now 不超过三个巡检间隔
+pub async fn app_running(
+    pool: &SqlitePool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, String> {
+    let heartbeat: Option<Option<String>> =
+        sqlx::query_scalar("SELECT app_heartbeat_at FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let Some(heartbeat) = heartbeat
+        .flatten()
+        .and_then(|at| chrono::DateTime::parse_from_rfc3339(&at).ok())
+    else {
+        return Ok(false);
+    };
+    let window = chrono::Duration::from_std(CHECK_INTERVAL * 3).map_err(|e| e.to_string())?;
+    Ok(now.signed_duration_since(heartbeat) <= window)
+}
+
+/// 无心跳超时秒数, 0 表示关闭看门狗
+pub async fn timeout_seconds(pool: &SqlitePool) -> Result<i64, String> {
+    let timeout: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT watchdog_timeout_seconds FROM global_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(timeout.flatten().unwrap_or(120))
+}
+
+/// 设置无心跳超时秒数
+pub async fn set_timeout_seconds(pool: &SqlitePool, seconds: i64) -> Result<(), String> {
+    if seconds < 0 {
+        return Err("超时时间不能为负数".to_string());
+    }
+    sqlx::query("UPDATE global_config SET watchdog_timeout_seconds = ? WHERE id = 1")
+        .bind(seconds)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 找出仍在运行但心跳已超时、且尚未触发过的执行
+pub async fn find_stalled(
+    pool: &SqlitePool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<StalledExecution>, String> {
+    let timeout = timeout_seconds(pool).await?;
+    if timeout == 0 {
+        return Ok(Vec::new());
+    }
+    let threshold = db::to_iso(now - chrono::Duration::seconds(timeout));
+
+    sqlx::query_as::<_, StalledExecution>(
+        "SELECT s.execution_id, e.workflow_id, s.node_id, n.name AS node_name, s.last_heartbeat_at
+         FROM execution_runtime_state s
+         JOIN executions e ON e.id = s.execution_id
+         LEFT JOIN nodes n ON n.id = s.node_id
+         WHERE e.status = 'running'
+           AND s.watchdog_triggered_at IS NULL
+           AND s.last_heartbeat_at < ?
+         ORDER BY s.last_heartbeat_at",
+    )
+    .bind(threshold)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 标记已触发, 直到下一次心跳前不会重复通知
+pub async fn mark_triggered(pool: &SqlitePool, execution_id: &str) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE execution_runtime_state SET watchdog_triggered_at = ? WHERE execution_id = ?",
+    )
+    .bind(db::now())
+    .bind(execution_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn check(app: &AppHandle) -> Result<(), String> {
+    let pool = db::pool(app).await?;
+    record_app_alive(&pool).await?;
+    for stalled in find_stalled(&pool, chrono::Utc::now()).await? {
+        mark_triggered(&pool, &stalled.execution_id).await?;
+        // 前端引擎收到事件后中止当前节点请求, 并按重试设置决定重试或使执行失败
+        app.emit("execution:watchdog_triggered", &stalled)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 启动后台巡检任务; 启动时立即巡检一次, 使命令行尽快得知应用已运行
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = check(&app).await {
+                eprintln!("执行看门狗巡检失败: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn report_execution_heartbeat(
+    app: AppHandle,
+    execution_id: String,
+    node_id: Option<String>,
+) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    heartbeat(&pool, &execution_id, node_id.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn set_watchdog_timeout(app: AppHandle, seconds: i64) -> Result<(), String> {
+    let pool = db::pool(&app).await?;
+    set_timeout_seconds(&pool, seconds).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn detects_stalled_running_executions_once() {
+        let pool = db::test_pool().await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO nodes (id, workflow_id, type, name, order_index) VALUES ('n1', 'w1', 'ai_chat', '撰写正文', 0);
+            INSERT INTO executions (id, workflow_id, status) VALUES ('e1', 'w1', 'running'), ('e2', 'w1', 'completed');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        heartbeat(&pool, "e1", Some("n1")).await.unwrap();
+        heartbeat(&pool, "e2", Some("n1")).await.unwrap();
+
+        let now = chrono::Utc::now();
+        assert!(find_stalled(&pool, now).await.unwrap().is_empty());
+
+        let later = now + chrono::Duration::seconds(121);
+        let stalled = find_stalled(&pool, later).await.unwrap();
+        assert_eq!(stalled.len(), 1);
+        assert_eq!(stalled[0].execution_id, "e1");
+        assert_eq!(stalled[0].node_name.as_deref(), Some("撰写正文"));
+
+        mark_triggered(&pool, "e1").await.unwrap();
+        assert!(find_stalled(&pool, later).await.unwrap().is_empty());
+
+        // 新的心跳会重新启用检测; 超时设为 0 则关闭
+        heartbeat(&pool, "e1", Some("n1")).await.unwrap();
+        assert_eq!(find_stalled(&pool, later).await.unwrap().len(), 1);
+        set_timeout_seconds(&pool, 0).await.unwrap();
+        assert!(find_stalled(&pool, later).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tracks_whether_the_app_is_running() {
+        let pool = db::test_pool().await;
+        let now = chrono::Utc::now();
+        assert!(!app_running(&pool, now).await.unwrap());
+
+        record_app_alive(&pool).await.unwrap();
+        assert!(app_running(&pool, now).await.unwrap());
+        let later = now + chrono::Duration::from_std(CHECK_INTERVAL * 4).unwrap();
+        assert!(!app_running(&pool, later).await.unwrap());
+    }
+}
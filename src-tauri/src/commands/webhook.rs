@@ -0,0 +1,1169 @@
+// Webhook: 工作流 Webhook 在执行结束后向配置的地址 POST 通知; 项目 Webhook 按订阅的事件类型
+// 在执行状态变化后 POST 完整的执行记录. 投递失败不影响执行记录本身
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::commands::network;
+use crate::db;
+use crate::error::CommandError;
+use crate::keychain;
+use crate::models::{Execution, WebhookDelivery};
+
+/// 首次投递失败后的重试次数
+const MAX_RETRIES: u32 = 3;
+
+/// 重试间隔基数, 依次等待 1、2、4 倍
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// 触发通知的执行状态
+const NOTIFY_STATUSES: &[&str] = &["completed", "failed", "timeout"];
+
+/// 签名请求头, 值为 `sha256=<hex>`
+const SIGNATURE_HEADER: &str = "X-Chouann-Signature";
+
+/// 项目 Webhook 可订阅的事件类型, 对应执行状态 (running 为 started)
+pub const EVENT_TYPES: &[&str] = &[
+    "execution.started",
+    "execution.completed",
+    "execution.failed",
+    "execution.cancelled",
+    "execution.timeout",
+];
+
+// Webhook 请求体; 默认不含正文, 仅在开启 include_output 时附带
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload {
+    pub event: String,
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub status: String,
+    pub title: Option<String>,
+    pub input_chars: usize,
+    pub output_chars: usize,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+// 项目 Webhook
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub project_id: String,
+    pub url: String,
+    #[sqlx(json)]
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+// 注册 Webhook 的结果; 签名密钥只在注册时返回
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+// 项目 Webhook 请求体: 事件类型与完整的执行记录
+#[derive(Debug, Serialize)]
+pub struct ProjectWebhookPayload<'a> {
+    pub event: &'a str,
+    pub project_id: &'a str,
+    pub execution: &'a Execution,
+}
+
+/// 投递日志单次返回的最大条数
+const MAX_LOG_LIMIT: i64 = 500;
+
+/// 测试请求的事件类型
+const TEST_EVENT: &str = "test";
+
+/// 测试结果中响应正文预览的字符数
+const RESPONSE_PREVIEW_CHARS: usize = 500;
+
+// 投递失败的原因; 网络错误与 5xx 响应会重试, 其他失败直接返回
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookError {
+    /// 连接失败、超时等网络错误
+    Network(String),
+    /// 非 2xx 响应
+    Status(u16),
+    /// Webhook 不存在、写入投递记录失败等
+    Other(String),
+}
+
+impl WebhookError {
+    /// 是否为可重试的临时失败
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WebhookError::Network(_) => true,
+            WebhookError::Status(code) => (500..600).contains(code),
+            WebhookError::Other(_) => false,
+        }
+    }
+
+    // 写入投递记录的状态码与错误信息
+    fn record(&self) -> (Option<u16>, String) {
+        match self {
+            WebhookError::Status(code) => (Some(*code), format!("HTTP {}", code)),
+            WebhookError::Network(message) | WebhookError::Other(message) => {
+                (None, message.clone())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.record().1)
+    }
+}
+
+impl From<String> for WebhookError {
+    fn from(message: String) -> Self {
+        WebhookError::Other(message)
+    }
+}
+
+// 测试请求体, 不关联执行记录
+#[derive(Debug, Serialize)]
+pub struct WebhookTestPayload<'a> {
+    pub event_type: &'a str,
+    pub webhook_id: &'a str,
+    pub project_id: &'a str,
+    pub sent_at: String,
+}
+
+// 测试 Webhook 的结果; 非 2xx 响应同样返回, 由配置面板展示
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookTestResult {
+    pub http_status: u16,
+    pub response_body_preview: String,
+    pub latency_ms: u64,
+}
+
+// 一次投递的目标
+struct Target<'a> {
+    url: &'a str,
+    event: &'a str,
+    secret: &'a str,
+    /// 项目 Webhook 的 ID, 工作流 Webhook 为 None
+    webhook_id: Option<&'a str>,
+}
+
+// 工作流的 Webhook 配置; 签名密钥保存在系统钥匙串中, webhook_secret 只存有尚未迁移的旧密钥
+#[derive(sqlx::FromRow)]
+struct WebhookConfig {
+    webhook_url: Option<String>,
+    webhook_include_output: bool,
+    has_webhook_secret: bool,
+    webhook_secret: Option<String>,
+}
+
+/// 对请求体做 HMAC-SHA256 签名, 返回请求头的值
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC 接受任意长度的密钥");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn new_secret() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+// 工作流 Webhook 签名密钥在系统钥匙串中的账户名
+fn secret_account(workflow_id: &str) -> String {
+    format!("workflow-webhook:{}", workflow_id)
+}
+
+// 工作流 Webhook 的签名密钥: 已迁入钥匙串的从钥匙串读取, 否则使用尚未迁移的旧密钥
+fn load_secret(workflow_id: &str, config: &WebhookConfig) -> Result<Option<String>, String> {
+    if config.has_webhook_secret {
+        keychain::get(&secret_account(workflow_id))
+    } else {
+        Ok(config.webhook_secret.clone())
+    }
+}
+
+/// 设置工作流的 Webhook 地址, 返回签名密钥 (首次配置时生成); 地址为空表示关闭.
+/// 密钥写入系统钥匙串, 数据库只记录是否已生成
+pub async fn configure(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    url: Option<&str>,
+    include_output: bool,
+) -> Result<Option<String>, String> {
+    let config = load_config(pool, workflow_id).await?;
+    let url = url.map(str::trim).filter(|u| !u.is_empty());
+    if let Some(url) = url {
+        validate_url(url)?;
+    }
+    // 钥匙串中的密钥丢失时重新生成
+    let secret = match (url, load_secret(workflow_id, &config)?) {
+        (Some(_), Some(secret)) => Some(secret),
+        (Some(_), None) => Some(new_secret()),
+        (None, secret) => secret,
+    };
+    if let Some(secret) = &secret {
+        keychain::set(&secret_account(workflow_id), secret)?;
+    }
+
+    sqlx::query(
+        "UPDATE workflows SET webhook_url = ?, webhook_include_output = ?, has_webhook_secret = ?,
+         webhook_secret = NULL, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(url)
+    .bind(include_output)
+    .bind(secret.is_some())
+    .bind(db::now())
+    .bind(workflow_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(url.and(secret))
+}
+
+async fn load_config(pool: &SqlitePool, workflow_id: &str) -> Result<WebhookConfig, String> {
+    sqlx::query_as::<_, WebhookConfig>(
+        "SELECT webhook_url, webhook_include_output, has_webhook_secret, webhook_secret
+         FROM workflows WHERE id = ?",
+    )
+    .bind(workflow_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("工作流不存在: {}", workflow_id))
+}
+
+/// 把保存在数据库中的旧版工作流 Webhook 签名密钥迁入系统钥匙串并清空, 返回迁移数量
+pub async fn migrate_legacy_secrets(pool: &SqlitePool) -> Result<usize, String> {
+    let legacy: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, webhook_secret FROM workflows WHERE webhook_secret IS NOT NULL")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    for (workflow_id, secret) in &legacy {
+        keychain::set(&secret_account(workflow_id), secret)?;
+        sqlx::query(
+            "UPDATE workflows SET has_webhook_secret = 1, webhook_secret = NULL WHERE id = ?",
+        )
+        .bind(workflow_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(legacy.len())
+}
+
+/// 全部工作流 Webhook 的签名密钥 (含尚未迁移的旧密钥)
+pub async fn workflow_secrets(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    let configs: Vec<(String, bool, Option<String>)> = sqlx::query_as(
+        "SELECT id, has_webhook_secret, webhook_secret FROM workflows
+         WHERE has_webhook_secret = 1 OR webhook_secret IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut secrets = Vec::new();
+    for (workflow_id, has_webhook_secret, legacy) in configs {
+        if has_webhook_secret {
+            secrets.extend(keychain::get(&secret_account(&workflow_id))?);
+        } else {
+            secrets.extend(legacy);
+        }
+    }
+    Ok(secrets)
+}
+
+/// 根据执行记录构建请求体
+pub fn build_payload(execution: &Execution, include_output: bool) -> WebhookPayload {
+    let output = execution.final_output.as_deref().unwrap_or("");
+    let duration_ms = execution.finished_at.as_deref().and_then(|finished_at| {
+        let start = chrono::DateTime::parse_from_rfc3339(&execution.started_at).ok()?;
+        let end = chrono::DateTime::parse_from_rfc3339(finished_at).ok()?;
+        Some((end - start).num_milliseconds())
+    });
+    WebhookPayload {
+        event: format!("execution.{}", execution.status),
+        execution_id: execution.id.clone(),
+        workflow_id: execution.workflow_id.clone(),
+        status: execution.status.clone(),
+        title: execution.title.clone(),
+        input_chars: execution.input.as_deref().unwrap_or("").chars().count(),
+        output_chars: output.chars().count(),
+        started_at: execution.started_at.clone(),
+        finished_at: execution.finished_at.clone(),
+        duration_ms,
+        output: include_output.then(|| output.to_string()),
+    }
+}
+
+/// 执行状态对应的项目 Webhook 事件类型
+pub fn event_type(status: &str) -> String {
+    match status {
+        "running" => "execution.started".to_string(),
+        status => format!("execution.{}", status),
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!(
+            "Webhook 地址必须以 http:// 或 https:// 开头: {}",
+            url
+        ));
+    }
+    Ok(())
+}
+
+/// 注册项目 Webhook; 未提供签名密钥时生成
+pub async fn register(
+    pool: &SqlitePool,
+    project_id: &str,
+    url: &str,
+    event_types: &[String],
+    secret: Option<&str>,
+) -> Result<RegisteredWebhook, String> {
+    let url = url.trim();
+    validate_url(url)?;
+    if event_types.is_empty() {
+        return Err("至少订阅一种事件".to_string());
+    }
+    if let Some(unknown) = event_types
+        .iter()
+        .find(|t| !EVENT_TYPES.contains(&t.as_str()))
+    {
+        return Err(format!("不支持的事件类型: {}", unknown));
+    }
+    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("项目不存在: {}", project_id));
+    }
+
+    let mut event_types = event_types.to_vec();
+    event_types.sort_by_key(|t| EVENT_TYPES.iter().position(|e| e == t));
+    event_types.dedup();
+    let secret = secret
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map_or_else(new_secret, str::to_string);
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO webhooks (id, project_id, url, event_types, secret, enabled, created_at)
+         VALUES (?, ?, ?, ?, ?, 1, ?)",
+    )
+    .bind(&id)
+    .bind(project_id)
+    .bind(url)
+    .bind(serde_json::to_string(&event_types).map_err(|e| e.to_string())?)
+    .bind(&secret)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let webhook = sqlx::query_as::<_, Webhook>(
+        "SELECT id, project_id, url, event_types, enabled, created_at FROM webhooks WHERE id = ?",
+    )
+    .bind(&id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(RegisteredWebhook { webhook, secret })
+}
+
+/// 删除项目 Webhook, 返回是否存在
+pub async fn delete(pool: &SqlitePool, webhook_id: &str) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(webhook_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 列出项目 Webhook, 按注册时间排序
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<Webhook>, String> {
+    sqlx::query_as::<_, Webhook>(
+        "SELECT id, project_id, url, event_types, enabled, created_at FROM webhooks
+         WHERE project_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn record_attempt(
+    pool: &SqlitePool,
+    execution: &Execution,
+    webhook_id: Option<&str>,
+    attempt: u32,
+    status_code: Option<u16>,
+    error_message: Option<String>,
+) -> Result<(), String> {
+    let now = db::now();
+    let delivered_at = error_message.is_none().then(|| now.clone());
+    sqlx::query(
+        "INSERT INTO webhook_deliveries (id, workflow_id, execution_id, webhook_id, attempt, status_code, success, error_message, delivered_at, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(db::new_id())
+    .bind(&execution.workflow_id)
+    .bind(&execution.id)
+    .bind(webhook_id)
+    .bind(attempt as i64)
+    .bind(status_code.map(i64::from))
+    .bind(error_message.is_none())
+    .bind(error_message)
+    .bind(delivered_at)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn load_execution(pool: &SqlitePool, execution_id: &str) -> Result<Execution, String> {
+    sqlx::query_as::<_, Execution>("SELECT * FROM executions WHERE id = ?")
+        .bind(execution_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("执行记录不存在: {}", execution_id))
+}
+
+// 发送一次签名后的请求体, 返回 2xx 响应的状态码
+async fn send(
+    client: &tauri_plugin_http::reqwest::Client,
+    target: &Target<'_>,
+    signature: &str,
+    body: &[u8],
+) -> Result<u16, WebhookError> {
+    let response = client
+        .post(target.url)
+        .header("Content-Type", "application/json")
+        .header("X-Chouann-Event", target.event)
+        .header(SIGNATURE_HEADER, signature)
+        .body(body.to_vec())
+        .send()
+        .await
+        .map_err(|e| WebhookError::Network(e.to_string()))?;
+    let status = response.status().as_u16();
+    if !response.status().is_success() {
+        return Err(WebhookError::Status(status));
+    }
+    Ok(status)
+}
+
+// 从第 attempt 次尝试开始投递, 临时失败时按指数退避重试 (共最多 MAX_RETRIES + 1 次),
+// 每次尝试写入投递记录
+async fn post(
+    pool: &SqlitePool,
+    execution: &Execution,
+    target: &Target<'_>,
+    body: &[u8],
+    attempt: u32,
+    backoff: Duration,
+) -> Result<(), WebhookError> {
+    let signature = sign(target.secret, body);
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| WebhookError::Other(e.to_string()))?;
+
+    let mut attempt = attempt.max(1);
+    loop {
+        if attempt > 1 {
+            tokio::time::sleep(backoff * 2u32.pow(attempt - 2)).await;
+        }
+        let result = send(&client, target, &signature, body).await;
+        let (status_code, error) = match &result {
+            Ok(status) => (Some(*status), None),
+            Err(e) => {
+                let (status_code, error) = e.record();
+                (status_code, Some(error))
+            }
+        };
+        record_attempt(
+            pool,
+            execution,
+            target.webhook_id,
+            attempt,
+            status_code,
+            error,
+        )
+        .await?;
+        match result {
+            Ok(_) => return Ok(()),
+            Err(e) if !e.is_transient() || attempt > MAX_RETRIES => return Err(e),
+            Err(_) => attempt += 1,
+        }
+    }
+}
+
+// 投递结果转为是否成功; 投递本身之外的错误 (如写入投递记录失败) 向上返回
+fn delivered(result: Result<(), WebhookError>) -> Result<bool, String> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(WebhookError::Other(e)) => Err(e),
+        Err(_) => Ok(false),
+    }
+}
+
+/// 向项目 Webhook 投递请求体, 从第 attempt 次尝试开始计数;
+/// 网络错误与 5xx 响应按指数退避重试, 每次尝试写入 webhook_deliveries
+pub async fn deliver_webhook(
+    pool: &SqlitePool,
+    webhook_id: &str,
+    execution: &Execution,
+    payload: &str,
+    attempt: u32,
+    backoff: Duration,
+) -> Result<(), WebhookError> {
+    let hook: Option<(String, Option<String>)> =
+        sqlx::query_as("SELECT url, secret FROM webhooks WHERE id = ?")
+            .bind(webhook_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (url, secret) = hook.ok_or_else(|| format!("Webhook 不存在: {}", webhook_id))?;
+    let event = event_type(&execution.status);
+    let target = Target {
+        url: &url,
+        event: &event,
+        secret: secret.as_deref().unwrap_or(""),
+        webhook_id: Some(webhook_id),
+    };
+    post(
+        pool,
+        execution,
+        &target,
+        payload.as_bytes(),
+        attempt,
+        backoff,
+    )
+    .await
+}
+
+/// 投递执行结束通知, 失败时按指数退避重试; 无需投递时返回 None, 否则返回是否投递成功
+pub async fn deliver(
+    pool: &SqlitePool,
+    execution_id: &str,
+    backoff: Duration,
+) -> Result<Option<bool>, String> {
+    let execution = load_execution(pool, execution_id).await?;
+    let config = load_config(pool, &execution.workflow_id).await?;
+    let Some(url) = config.webhook_url else {
+        return Ok(None);
+    };
+    // 只通知完成与失败 (含超时), 取消与未结束的执行不投递
+    if !NOTIFY_STATUSES.contains(&execution.status.as_str()) {
+        return Ok(None);
+    }
+
+    let secret = load_secret(&execution.workflow_id, &config)?
+        .ok_or("系统钥匙串中没有该工作流的 Webhook 签名密钥, 请重新保存 Webhook 配置")?;
+    let payload = build_payload(&execution, config.webhook_include_output);
+    let body = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+    let target = Target {
+        url: &url,
+        event: &payload.event,
+        secret: &secret,
+        webhook_id: None,
+    };
+    delivered(post(pool, &execution, &target, &body, 1, backoff).await).map(Some)
+}
+
+/// 向订阅了执行当前状态对应事件的已启用项目 Webhook 投递执行记录, 返回各 Webhook 是否投递成功
+pub async fn deliver_project(
+    pool: &SqlitePool,
+    execution_id: &str,
+    backoff: Duration,
+) -> Result<Vec<(String, bool)>, String> {
+    let execution = load_execution(pool, execution_id).await?;
+    let project_id: String = sqlx::query_scalar("SELECT project_id FROM workflows WHERE id = ?")
+        .bind(&execution.workflow_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let event = event_type(&execution.status);
+    let hooks: Vec<String> = sqlx::query_scalar(
+        "SELECT w.id FROM webhooks w
+         WHERE w.project_id = ? AND w.enabled = 1
+           AND EXISTS (SELECT 1 FROM json_each(w.event_types) WHERE value = ?)
+         ORDER BY w.created_at, w.rowid",
+    )
+    .bind(&project_id)
+    .bind(&event)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if hooks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let payload = ProjectWebhookPayload {
+        event: &event,
+        project_id: &project_id,
+        execution: &execution,
+    };
+    let body = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
+    let mut results = Vec::new();
+    for id in hooks {
+        let result = deliver_webhook(pool, &id, &execution, &body, 1, backoff).await;
+        results.push((id, delivered(result)?));
+    }
+    Ok(results)
+}
+
+/// 列出工作流的 Webhook 投递记录, 最新的在前
+pub async fn list_deliveries(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<Vec<WebhookDelivery>, String> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        "SELECT * FROM webhook_deliveries WHERE workflow_id = ? ORDER BY created_at DESC, rowid DESC",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 向项目 Webhook 发送签名的测试请求, 验证地址可达; 不写入投递记录, 连接失败时报错
+pub async fn test(pool: &SqlitePool, webhook_id: &str) -> Result<WebhookTestResult, String> {
+    let hook: Option<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT project_id, url, secret FROM webhooks WHERE id = ?")
+            .bind(webhook_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (project_id, url, secret) =
+        hook.ok_or_else(|| format!("Webhook 不存在: {}", webhook_id))?;
+    let payload = WebhookTestPayload {
+        event_type: TEST_EVENT,
+        webhook_id,
+        project_id: &project_id,
+        sent_at: db::now(),
+    };
+    let body = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let started = std::time::Instant::now();
+    let response = client
+        .post(&url)
+        .header("Content-Type", "application/json")
+        .header("X-Chouann-Event", TEST_EVENT)
+        .header(
+            SIGNATURE_HEADER,
+            sign(secret.as_deref().unwrap_or(""), &body),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("无法连接 Webhook 地址 {}: {}", url, e))?;
+    let http_status = response.status().as_u16();
+    let text = response.text().await.unwrap_or_default();
+    Ok(WebhookTestResult {
+        http_status,
+        response_body_preview: text.chars().take(RESPONSE_PREVIEW_CHARS).collect(),
+        latency_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// 项目 Webhook 最近的投递记录 (每次尝试一条), 最新的在前
+pub async fn delivery_log(
+    pool: &SqlitePool,
+    webhook_id: &str,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, String> {
+    if limit <= 0 {
+        return Err("条数须大于 0".to_string());
+    }
+    sqlx::query_as::<_, WebhookDelivery>(
+        "SELECT * FROM webhook_deliveries WHERE webhook_id = ?
+         ORDER BY created_at DESC, rowid DESC LIMIT ?",
+    )
+    .bind(webhook_id)
+    .bind(limit.min(MAX_LOG_LIMIT))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_workflow_webhook(
+    app: AppHandle,
+    workflow_id: String,
+    url: Option<String>,
+    include_output: bool,
+) -> Result<Option<String>, String> {
+    let pool = db::pool(&app).await?;
+    configure(&pool, &workflow_id, url.as_deref(), include_output).await
+}
+
+/// 执行状态变化后调用: 在后台投递工作流与项目 Webhook, 立即返回; 投递结果只写入 webhook_deliveries.
+/// 离线模式下不投递
+#[tauri::command]
+pub async fn deliver_execution_webhook(
+    app: AppHandle,
+    execution_id: String,
+) -> Result<(), CommandError> {
+    let pool = db::pool(&app).await?;
+    network::guard(&app, &pool, "webhook").await?;
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = deliver(&pool, &execution_id, RETRY_BACKOFF).await {
+            eprintln!("Webhook 投递失败: {}", e);
+        }
+        if let Err(e) = deliver_project(&pool, &execution_id, RETRY_BACKOFF).await {
+            eprintln!("项目 Webhook 投递失败: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_webhook(
+    app: AppHandle,
+    project_id: String,
+    url: String,
+    event_types: Vec<String>,
+    secret: Option<String>,
+) -> Result<RegisteredWebhook, String> {
+    let pool = db::pool(&app).await?;
+    register(&pool, &project_id, &url, &event_types, secret.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn delete_webhook(app: AppHandle, webhook_id: String) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    delete(&pool, &webhook_id).await
+}
+
+#[tauri::command]
+pub async fn list_webhooks(app: AppHandle, project_id: String) -> Result<Vec<Webhook>, String> {
+    let pool = db::pool(&app).await?;
+    list(&pool, &project_id).await
+}
+
+#[tauri::command]
+pub async fn list_webhook_deliveries(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<WebhookDelivery>, String> {
+    let pool = db::pool(&app).await?;
+    list_deliveries(&pool, &workflow_id).await
+}
+
+/// Webhook 配置面板的 "发送测试"; 离线模式下不发送
+#[tauri::command]
+pub async fn test_webhook(
+    app: AppHandle,
+    webhook_id: String,
+) -> Result<WebhookTestResult, CommandError> {
+    let pool = db::pool(&app).await?;
+    network::guard(&app, &pool, "webhook").await?;
+    Ok(test(&pool, &webhook_id).await?)
+}
+
+#[tauri::command]
+pub async fn get_webhook_delivery_log(
+    app: AppHandle,
+    webhook_id: String,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, String> {
+    let pool = db::pool(&app).await?;
+    delivery_log(&pool, &webhook_id, limit).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '测试项目');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '正文生成');
+            INSERT INTO executions (id, workflow_id, status, input, final_output, title, started_at, finished_at)
+                VALUES ('e1', 'w1', 'completed', '驿站', '第一章 风雪夜', '风雪夜',
+                        '2026-01-01T00:00:00.000Z', '2026-01-01T00:00:03.000Z');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    // 本地 HTTP 服务, 依次以 statuses 中的状态码响应
+    fn serve(statuses: &'static [u16]) -> String {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} X\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    status
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn signs_body_with_hmac_sha256() {
+        assert_eq!(
+            sign("secret", b"{}"),
+            "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13"
+        );
+        assert_ne!(sign("secret", b"{}"), sign("other", b"{}"));
+    }
+
+    #[tokio::test]
+    async fn omits_output_unless_enabled() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let execution = sqlx::query_as::<_, Execution>("SELECT * FROM executions WHERE id = 'e1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let payload = build_payload(&execution, false);
+        assert_eq!(payload.event, "execution.completed");
+        assert_eq!(payload.output_chars, 7);
+        assert_eq!(payload.duration_ms, Some(3000));
+        assert!(!serde_json::to_string(&payload)
+            .unwrap()
+            .contains("\"output\""));
+        assert!(payload.output.is_none());
+        assert_eq!(
+            build_payload(&execution, true).output.as_deref(),
+            Some("第一章 风雪夜")
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_and_logs_failed_deliveries() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        assert_eq!(deliver(&pool, "e1", Duration::ZERO).await.unwrap(), None);
+
+        let secret = configure(&pool, "w1", Some("http://127.0.0.1:9/hook"), false)
+            .await
+            .unwrap()
+            .unwrap();
+        let again = configure(&pool, "w1", Some("http://127.0.0.1:9/hook"), true)
+            .await
+            .unwrap();
+        assert_eq!(again.as_deref(), Some(secret.as_str()));
+        assert!(configure(&pool, "w1", Some("ftp://example"), false)
+            .await
+            .is_err());
+        // 密钥只保存在钥匙串中
+        let stored: (bool, Option<String>) = sqlx::query_as(
+            "SELECT has_webhook_secret, webhook_secret FROM workflows WHERE id = 'w1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, (true, None));
+        assert_eq!(
+            keychain::get(&secret_account("w1")).unwrap(),
+            Some(secret.clone())
+        );
+
+        assert_eq!(
+            deliver(&pool, "e1", Duration::ZERO).await.unwrap(),
+            Some(false)
+        );
+        let deliveries = list_deliveries(&pool, "w1").await.unwrap();
+        assert_eq!(deliveries.len(), 4);
+        assert!(deliveries
+            .iter()
+            .all(|d| !d.success && d.error_message.is_some() && d.delivered_at.is_none()));
+        let status: String = sqlx::query_scalar("SELECT status FROM executions WHERE id = 'e1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "completed");
+    }
+
+    #[tokio::test]
+    async fn moves_legacy_secrets_to_keychain() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::query(
+            "UPDATE workflows SET webhook_url = 'http://127.0.0.1:9/hook', webhook_secret = 'legacy-secret'
+             WHERE id = 'w1'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // 尚未迁移时沿用数据库中的旧密钥
+        assert_eq!(workflow_secrets(&pool).await.unwrap(), ["legacy-secret"]);
+
+        assert_eq!(migrate_legacy_secrets(&pool).await.unwrap(), 1);
+        assert_eq!(migrate_legacy_secrets(&pool).await.unwrap(), 0);
+        let stored: (bool, Option<String>) = sqlx::query_as(
+            "SELECT has_webhook_secret, webhook_secret FROM workflows WHERE id = 'w1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, (true, None));
+        assert_eq!(workflow_secrets(&pool).await.unwrap(), ["legacy-secret"]);
+        assert_eq!(
+            configure(&pool, "w1", Some("http://127.0.0.1:9/hook"), false)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("legacy-secret")
+        );
+    }
+
+    #[tokio::test]
+    async fn delivers_to_subscribed_project_webhooks() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let events = |types: &[&str]| types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert!(register(
+            &pool,
+            "p1",
+            "ftp://example",
+            &events(&["execution.completed"]),
+            None
+        )
+        .await
+        .is_err());
+        assert!(register(
+            &pool,
+            "p1",
+            "http://127.0.0.1:9/a",
+            &events(&["execution.paused"]),
+            None
+        )
+        .await
+        .is_err());
+        assert!(register(&pool, "p1", "http://127.0.0.1:9/a", &[], None)
+            .await
+            .is_err());
+        assert!(register(
+            &pool,
+            "missing",
+            "http://127.0.0.1:9/a",
+            &events(&["execution.failed"]),
+            None
+        )
+        .await
+        .is_err());
+
+        let done = register(
+            &pool,
+            "p1",
+            " http://127.0.0.1:9/done ",
+            &events(&[
+                "execution.failed",
+                "execution.completed",
+                "execution.failed",
+            ]),
+            Some("secret"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(done.secret, "secret");
+        assert_eq!(done.webhook.url, "http://127.0.0.1:9/done");
+        assert_eq!(
+            done.webhook.event_types,
+            ["execution.completed", "execution.failed"]
+        );
+        let started = register(
+            &pool,
+            "p1",
+            "http://127.0.0.1:9/start",
+            &events(&["execution.started"]),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(started.secret.len(), 64);
+        assert!(!serde_json::to_string(&list(&pool, "p1").await.unwrap())
+            .unwrap()
+            .contains("secret"));
+
+        // 只投递订阅了当前状态的 Webhook, 投递失败按次记录
+        assert_eq!(
+            deliver_project(&pool, "e1", Duration::ZERO).await.unwrap(),
+            [(done.webhook.id.clone(), false)]
+        );
+        let deliveries = list_deliveries(&pool, "w1").await.unwrap();
+        assert_eq!(deliveries.len(), 4);
+        assert!(deliveries
+            .iter()
+            .all(|d| d.webhook_id.as_deref() == Some(done.webhook.id.as_str())));
+
+        sqlx::query("UPDATE webhooks SET enabled = 0 WHERE id = ?")
+            .bind(&done.webhook.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(deliver_project(&pool, "e1", Duration::ZERO)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(delete(&pool, &started.webhook.id).await.unwrap());
+        assert!(!delete(&pool, &started.webhook.id).await.unwrap());
+        assert_eq!(list(&pool, "p1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_only_transient_failures() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let execution = load_execution(&pool, "e1").await.unwrap();
+        let events = vec!["execution.completed".to_string()];
+
+        let flaky = register(&pool, "p1", &serve(&[503, 200]), &events, None)
+            .await
+            .unwrap();
+        deliver_webhook(
+            &pool,
+            &flaky.webhook.id,
+            &execution,
+            "{}",
+            1,
+            Duration::ZERO,
+        )
+        .await
+        .unwrap();
+        let log = delivery_log(&pool, &flaky.webhook.id, 10).await.unwrap();
+        let attempts: Vec<(i64, Option<i64>, bool)> = log
+            .iter()
+            .map(|d| (d.attempt, d.status_code, d.success))
+            .collect();
+        assert_eq!(attempts, [(2, Some(200), true), (1, Some(503), false)]);
+        assert!(log[0].delivered_at.is_some() && log[0].error_message.is_none());
+        assert!(log[1].delivered_at.is_none());
+        assert_eq!(
+            delivery_log(&pool, &flaky.webhook.id, 1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(delivery_log(&pool, &flaky.webhook.id, 0).await.is_err());
+
+        // 4xx 不重试
+        let rejected = register(&pool, "p1", &serve(&[404]), &events, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            deliver_webhook(
+                &pool,
+                &rejected.webhook.id,
+                &execution,
+                "{}",
+                1,
+                Duration::ZERO
+            )
+            .await,
+            Err(WebhookError::Status(404))
+        );
+        let log = delivery_log(&pool, &rejected.webhook.id, 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].error_message.as_deref(), Some("HTTP 404"));
+
+        // 从最后一次尝试开始时不再重试
+        let down = register(&pool, "p1", "http://127.0.0.1:9/hook", &events, None)
+            .await
+            .unwrap();
+        let result = deliver_webhook(
+            &pool,
+            &down.webhook.id,
+            &execution,
+            "{}",
+            MAX_RETRIES + 1,
+            Duration::ZERO,
+        )
+        .await;
+        assert!(matches!(result, Err(WebhookError::Network(_))));
+        let log = delivery_log(&pool, &down.webhook.id, 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].attempt, i64::from(MAX_RETRIES + 1));
+
+        assert!(matches!(
+            deliver_webhook(&pool, "missing", &execution, "{}", 1, Duration::ZERO).await,
+            Err(WebhookError::Other(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sends_test_payload() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        let events = vec!["execution.completed".to_string()];
+
+        let hook = register(&pool, "p1", &serve(&[200, 404]), &events, None)
+            .await
+            .unwrap();
+        let result = test(&pool, &hook.webhook.id).await.unwrap();
+        assert_eq!(result.http_status, 200);
+        assert_eq!(result.response_body_preview, "ok");
+        // 非 2xx 响应也返回结果
+        assert_eq!(
+            test(&pool, &hook.webhook.id).await.unwrap().http_status,
+            404
+        );
+        assert!(delivery_log(&pool, &hook.webhook.id, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let down = register(&pool, "p1", "http://127.0.0.1:9/hook", &events, None)
+            .await
+            .unwrap();
+        assert!(test(&pool, &down.webhook.id).await.is_err());
+        assert!(test(&pool, "missing").await.is_err());
+    }
+}
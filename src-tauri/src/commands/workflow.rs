@@ -0,0 +1,973 @@
+// 工作流查询与管理
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::AppHandle;
+
+use crate::commands::{analytics, preset, sampling};
+use crate::db;
+use crate::error::CommandError;
+use crate::models::{InputPreset, Node, Workflow};
+
+/// 导出格式版本, 与前端 EXPORT_VERSION 保持一致
+const EXPORT_VERSION: &str = "1.0.0";
+
+// 名称冲突时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameResolve {
+    /// 返回 NameTaken 错误
+    #[default]
+    Error,
+    /// 自动追加 " (2)"、" (3)" 等后缀
+    Suffix,
+}
+
+// 导出的工作流基本信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedWorkflowInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub loop_max_count: i64,
+    pub timeout_seconds: i64,
+    #[serde(default = "default_context_mode")]
+    pub context_mode: String,
+    #[serde(default = "default_context_turn_limit")]
+    pub context_turn_limit: i64,
+    #[serde(default)]
+    pub context_token_budget: i64,
+    #[serde(default)]
+    pub post_processing: Option<serde_json::Value>,
+}
+
+fn default_context_mode() -> String {
+    "stitched".to_string()
+}
+
+fn default_context_turn_limit() -> i64 {
+    10
+}
+
+// 导出的节点, 不含 ID 与时间字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedNode {
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub name: String,
+    pub config: serde_json::Value,
+    pub order_index: i64,
+    pub block_id: Option<String>,
+    pub parent_block_id: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub group_label: Option<String>,
+    #[serde(default)]
+    pub group_color: Option<String>,
+}
+
+impl From<Node> for ExportedNode {
+    fn from(node: Node) -> Self {
+        ExportedNode {
+            node_type: node.node_type,
+            name: node.name,
+            config: node.config,
+            order_index: node.order_index,
+            block_id: node.block_id,
+            parent_block_id: node.parent_block_id,
+            description: node.description,
+            group_label: node.group_label,
+            group_color: node.group_color,
+        }
+    }
+}
+
+// 工作流版本快照, 与前端 `WorkflowSnapshot` 保持一致, 只需读取节点
+#[derive(Debug, Deserialize)]
+struct VersionSnapshot {
+    nodes: Vec<ExportedNode>,
+}
+
+// 两个版本之间的分组变化, 成员以节点名称表示
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupChange {
+    pub label: String,
+    // added / removed / changed
+    pub change_kind: String,
+    pub old_color: Option<String>,
+    pub new_color: Option<String>,
+    pub added_members: Vec<String>,
+    pub removed_members: Vec<String>,
+}
+
+// 导出的输入预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedInputPreset {
+    pub name: String,
+    pub content: String,
+    pub is_default: bool,
+}
+
+// 工作流导出格式, 与前端 `ExportedWorkflow` 保持一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedWorkflow {
+    pub version: String,
+    pub exported_at: String,
+    pub workflow: ExportedWorkflowInfo,
+    pub nodes: Vec<ExportedNode>,
+    #[serde(default)]
+    pub input_presets: Vec<ExportedInputPreset>,
+}
+
+// 同一项目内重名的一组工作流
+#[derive(Debug, Serialize)]
+pub struct DuplicateWorkflowName {
+    pub project_id: String,
+    pub project_name: String,
+    pub name: String,
+    pub workflow_ids: Vec<String>,
+}
+
+// 运行前检查发现的节点配置问题
+#[derive(Debug, Serialize)]
+pub struct WorkflowIssue {
+    pub node_id: String,
+    pub node_name: String,
+    pub message: String,
+}
+
+/// 按名称查找项目内的工作流
+pub async fn find_by_name(
+    pool: &SqlitePool,
+    project_id: &str,
+    name: &str,
+) -> Result<Option<Workflow>, String> {
+    sqlx::query_as::<_, Workflow>("SELECT * FROM workflows WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 项目内是否存在同名工作流
+pub async fn exists_by_name(
+    pool: &SqlitePool,
+    project_id: &str,
+    name: &str,
+) -> Result<bool, String> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM workflows WHERE project_id = ? AND name = ?)")
+        .bind(project_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按冲突处理方式确定最终名称; 比较时不区分大小写, 重命名时排除自身
+pub async fn resolve_name(
+    pool: &SqlitePool,
+    project_id: &str,
+    name: &str,
+    resolve: NameResolve,
+    exclude_id: Option<&str>,
+) -> Result<String, CommandError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(CommandError::Other("工作流名称不能为空".to_string()));
+    }
+
+    // SQLite 的 LOWER 只转换 ASCII 字母, 两侧都在 Rust 中按 Unicode 转为小写后比较
+    let taken: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT name FROM workflows WHERE project_id = ? AND id != ?",
+    )
+    .bind(project_id)
+    .bind(exclude_id.unwrap_or_default())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|taken| taken.to_lowercase())
+    .collect();
+
+    if !taken.contains(&name.to_lowercase()) {
+        return Ok(name.to_string());
+    }
+    match resolve {
+        NameResolve::Error => Err(CommandError::NameTaken(name.to_string())),
+        NameResolve::Suffix => Ok((2..)
+            .map(|n| format!("{} ({})", name, n))
+            .find(|candidate| !taken.contains(&candidate.to_lowercase()))
+            .expect("后缀序号无上限")),
+    }
+}
+
+async fn get(pool: &SqlitePool, workflow_id: &str) -> Result<Workflow, CommandError> {
+    sqlx::query_as::<_, Workflow>("SELECT * FROM workflows WHERE id = ?")
+        .bind(workflow_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| CommandError::Other(format!("工作流不存在: {}", workflow_id)))
+}
+
+async fn insert_workflow(
+    tx: &mut Transaction<'_, Sqlite>,
+    project_id: &str,
+    info: &ExportedWorkflowInfo,
+) -> Result<Workflow, sqlx::Error> {
+    let now = db::now();
+    let workflow = Workflow {
+        id: db::new_id(),
+        project_id: project_id.to_string(),
+        name: info.name.clone(),
+        description: info.description.clone(),
+        loop_max_count: info.loop_max_count,
+        timeout_seconds: info.timeout_seconds,
+        context_mode: info.context_mode.clone(),
+        context_turn_limit: info.context_turn_limit,
+        context_token_budget: info.context_token_budget,
+        webhook_url: None,
+        webhook_include_output: false,
+        post_processing: info.post_processing.clone(),
+        daily_execution_limit: None,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO workflows (id, project_id, name, description, loop_max_count, timeout_seconds,
+                                context_mode, context_turn_limit, context_token_budget, post_processing,
+                                created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&workflow.id)
+    .bind(&workflow.project_id)
+    .bind(&workflow.name)
+    .bind(&workflow.description)
+    .bind(workflow.loop_max_count)
+    .bind(workflow.timeout_seconds)
+    .bind(&workflow.context_mode)
+    .bind(workflow.context_turn_limit)
+    .bind(workflow.context_token_budget)
+    .bind(workflow.post_processing.as_ref().map(|v| v.to_string()))
+    .bind(&workflow.created_at)
+    .bind(&workflow.updated_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(workflow)
+}
+
+// 写入节点, 为块结构生成新的 block_id 并保持父子关系
+async fn insert_nodes(
+    tx: &mut Transaction<'_, Sqlite>,
+    workflow_id: &str,
+    nodes: &[ExportedNode],
+) -> Result<(), sqlx::Error> {
+    let now = db::now();
+    let mut block_ids: HashMap<&str, String> = HashMap::new();
+    for block_id in nodes.iter().filter_map(|n| n.block_id.as_deref()) {
+        block_ids.entry(block_id).or_insert_with(db::new_id);
+    }
+
+    for node in nodes {
+        let block_id = node.block_id.as_deref().and_then(|id| block_ids.get(id));
+        let parent_block_id = node
+            .parent_block_id
+            .as_deref()
+            .and_then(|id| block_ids.get(id));
+        sqlx::query(
+            "INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, description, group_label, group_color, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(workflow_id)
+        .bind(&node.node_type)
+        .bind(&node.name)
+        .bind(node.config.to_string())
+        .bind(node.order_index)
+        .bind(block_id)
+        .bind(parent_block_id)
+        .bind(&node.description)
+        .bind(&node.group_label)
+        .bind(&node.group_color)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// 创建工作流, 并自动创建固定在第一行的开始流程节点
+pub async fn create(
+    pool: &SqlitePool,
+    project_id: &str,
+    name: &str,
+    description: Option<&str>,
+    resolve: NameResolve,
+) -> Result<Workflow, CommandError> {
+    let name = resolve_name(pool, project_id, name, resolve, None).await?;
+    let info = ExportedWorkflowInfo {
+        name,
+        description: description.map(str::to_string),
+        loop_max_count: 10,
+        timeout_seconds: 300,
+        context_mode: default_context_mode(),
+        context_turn_limit: default_context_turn_limit(),
+        context_token_budget: 0,
+        post_processing: None,
+    };
+    let start = ExportedNode {
+        node_type: "start".to_string(),
+        name: "开始流程".to_string(),
+        config: serde_json::json!({}),
+        order_index: 0,
+        block_id: None,
+        parent_block_id: None,
+        description: None,
+        group_label: None,
+        group_color: None,
+    };
+
+    let mut tx = pool.begin().await?;
+    let workflow = insert_workflow(&mut tx, project_id, &info).await?;
+    insert_nodes(&mut tx, &workflow.id, &[start]).await?;
+    tx.commit().await?;
+    Ok(workflow)
+}
+
+/// 重命名工作流
+pub async fn rename(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    name: &str,
+    resolve: NameResolve,
+) -> Result<Workflow, CommandError> {
+    let mut workflow = get(pool, workflow_id).await?;
+    workflow.name =
+        resolve_name(pool, &workflow.project_id, name, resolve, Some(workflow_id)).await?;
+    workflow.updated_at = db::now();
+
+    sqlx::query("UPDATE workflows SET name = ?, updated_at = ? WHERE id = ?")
+        .bind(&workflow.name)
+        .bind(&workflow.updated_at)
+        .bind(workflow_id)
+        .execute(pool)
+        .await?;
+    Ok(workflow)
+}
+
+/// 导出工作流及其节点
+pub async fn export(
+    pool: &SqlitePool,
+    workflow_id: &str,
+) -> Result<ExportedWorkflow, CommandError> {
+    let workflow = get(pool, workflow_id).await?;
+    let nodes =
+        sqlx::query_as::<_, Node>("SELECT * FROM nodes WHERE workflow_id = ? ORDER BY order_index")
+            .bind(workflow_id)
+            .fetch_all(pool)
+            .await?;
+    let presets = sqlx::query_as::<_, InputPreset>(
+        "SELECT * FROM input_presets WHERE workflow_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ExportedWorkflow {
+        version: EXPORT_VERSION.to_string(),
+        exported_at: db::now(),
+        workflow: ExportedWorkflowInfo {
+            name: workflow.name,
+            description: workflow.description,
+            loop_max_count: workflow.loop_max_count,
+            timeout_seconds: workflow.timeout_seconds,
+            context_mode: workflow.context_mode,
+            context_turn_limit: workflow.context_turn_limit,
+            context_token_budget: workflow.context_token_budget,
+            post_processing: workflow.post_processing,
+        },
+        nodes: nodes.into_iter().map(ExportedNode::from).collect(),
+        input_presets: presets
+            .into_iter()
+            .map(|p| ExportedInputPreset {
+                name: p.name,
+                content: p.content,
+                is_default: p.is_default,
+            })
+            .collect(),
+    })
+}
+
+/// 导入工作流到指定项目, 未指定新名称时沿用导出时的名称
+pub async fn import(
+    pool: &SqlitePool,
+    project_id: &str,
+    data: &ExportedWorkflow,
+    new_name: Option<&str>,
+    resolve: NameResolve,
+) -> Result<Workflow, CommandError> {
+    let name = new_name.unwrap_or(&data.workflow.name);
+    let info = ExportedWorkflowInfo {
+        name: resolve_name(pool, project_id, name, resolve, None).await?,
+        ..data.workflow.clone()
+    };
+
+    let mut tx = pool.begin().await?;
+    let workflow = insert_workflow(&mut tx, project_id, &info).await?;
+    insert_nodes(&mut tx, &workflow.id, &data.nodes).await?;
+    for p in &data.input_presets {
+        preset::insert(&mut tx, &workflow.id, &p.name, &p.content, p.is_default).await?;
+    }
+    tx.commit().await?;
+    Ok(workflow)
+}
+
+/// 复制工作流到同一项目, 默认名称为 "原名称 (副本)"
+pub async fn duplicate(
+    pool: &SqlitePool,
+    workflow_id: &str,
+    new_name: Option<&str>,
+    resolve: NameResolve,
+) -> Result<Workflow, CommandError> {
+    let project_id = get(pool, workflow_id).await?.project_id;
+    let data = export(pool, workflow_id).await?;
+    let name = new_name
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} (副本)", data.workflow.name));
+    import(pool, &project_id, &data, Some(&name), resolve).await
+}
+
+/// 列出各项目内重名 (不区分大小写) 的工作流, 供用户清理历史数据
+pub async fn find_duplicate_names(pool: &SqlitePool) -> Result<Vec<DuplicateWorkflowName>, String> {
+    // 按 Unicode 规则分组 (与 resolve_name 一致), 不使用只转换 ASCII 的 LOWER
+    let rows: Vec<(String, String, String, String)> = sqlx::query_as(
+        "SELECT w.project_id, p.name, w.name, w.id
+         FROM workflows w JOIN projects p ON p.id = w.project_id
+         ORDER BY p.name, w.project_id, w.name, w.id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<DuplicateWorkflowName> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    for (project_id, project_name, name, id) in rows {
+        let key = (project_id.clone(), name.to_lowercase());
+        match index.get(&key) {
+            Some(&i) => groups[i].workflow_ids.push(id),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(DuplicateWorkflowName {
+                    project_id,
+                    project_name,
+                    name,
+                    workflow_ids: vec![id],
+                });
+            }
+        }
+    }
+    groups.retain(|group| group.workflow_ids.len() > 1);
+    groups.sort_by(|a, b| (&a.project_name, &a.name).cmp(&(&b.project_name, &b.name)));
+    Ok(groups)
+}
+
+/// 运行前检查工作流: 目前校验 AI 节点的提供商与采样参数, 避免批量运行到一半才被 API 拒绝
+pub async fn validate(pool: &SqlitePool, workflow_id: &str) -> Result<Vec<WorkflowIssue>, String> {
+    let workflow_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM workflows WHERE id = ?)")
+            .bind(workflow_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if !workflow_exists {
+        return Err(format!("工作流不存在: {}", workflow_id));
+    }
+
+    let nodes: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT id, name, config FROM nodes WHERE workflow_id = ? AND type = 'ai_chat' ORDER BY order_index",
+    )
+    .bind(workflow_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut issues = Vec::new();
+    for (node_id, node_name, config) in nodes {
+        let messages = match serde_json::from_str::<serde_json::Value>(&config) {
+            Ok(config) => {
+                let provider = config
+                    .get("provider")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                match sampling::resolve(&config) {
+                    Ok(params) => sampling::validate(provider, &params),
+                    Err(e) => vec![e],
+                }
+            }
+            Err(e) => vec![format!("节点配置不是有效的 JSON: {}", e)],
+        };
+        issues.extend(messages.into_iter().map(|message| WorkflowIssue {
+            node_id: node_id.clone(),
+            node_name: node_name.clone(),
+            message,
+        }));
+    }
+    Ok(issues)
+}
+
+// 以标签汇总分组: 颜色与成员名称
+fn summarize_groups(nodes: &[ExportedNode]) -> BTreeMap<&str, (Option<&str>, BTreeSet<&str>)> {
+    let mut groups: BTreeMap<&str, (Option<&str>, BTreeSet<&str>)> = BTreeMap::new();
+    for node in nodes {
+        if let Some(label) = node.group_label.as_deref() {
+            let group = groups.entry(label).or_default();
+            group.0 = group.0.or(node.group_color.as_deref());
+            group.1.insert(&node.name);
+        }
+    }
+    groups
+}
+
+/// 比较两组节点的分组: 新增、删除, 以及颜色或成员变化的分组
+pub fn diff_groups(old: &[ExportedNode], new: &[ExportedNode]) -> Vec<GroupChange> {
+    let (old, new) = (summarize_groups(old), summarize_groups(new));
+    let labels: BTreeSet<&str> = old.keys().chain(new.keys()).copied().collect();
+    let empty = (None, BTreeSet::new());
+    labels
+        .into_iter()
+        .filter_map(|label| {
+            let (before, after) = (old.get(label), new.get(label));
+            let change_kind = match (before, after) {
+                (None, _) => "added",
+                (_, None) => "removed",
+                (Some(before), Some(after)) if before != after => "changed",
+                _ => return None,
+            };
+            let (old_color, old_members) = before.unwrap_or(&empty);
+            let (new_color, new_members) = after.unwrap_or(&empty);
+            let names = |a: &BTreeSet<&str>, b: &BTreeSet<&str>| {
+                a.difference(b).map(|n| n.to_string()).collect()
+            };
+            Some(GroupChange {
+                label: label.to_string(),
+                change_kind: change_kind.to_string(),
+                old_color: old_color.map(str::to_string),
+                new_color: new_color.map(str::to_string),
+                added_members: names(new_members, old_members),
+                removed_members: names(old_members, new_members),
+            })
+        })
+        .collect()
+}
+
+// 读取版本快照中的节点与所属工作流
+async fn version_nodes(
+    pool: &SqlitePool,
+    version_id: &str,
+) -> Result<(String, Vec<ExportedNode>), String> {
+    let (workflow_id, snapshot): (String, String) =
+        sqlx::query_as("SELECT workflow_id, snapshot FROM workflow_versions WHERE id = ?")
+            .bind(version_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("版本不存在: {}", version_id))?;
+    let snapshot: VersionSnapshot =
+        serde_json::from_str(&snapshot).map_err(|e| format!("版本快照格式错误: {}", e))?;
+    Ok((workflow_id, snapshot.nodes))
+}
+
+/// 两个版本之间的分组变化; 未指定 `to_version_id` 时与工作流当前节点比较
+pub async fn version_group_changes(
+    pool: &SqlitePool,
+    from_version_id: &str,
+    to_version_id: Option<&str>,
+) -> Result<Vec<GroupChange>, String> {
+    let (workflow_id, old) = version_nodes(pool, from_version_id).await?;
+    let new = match to_version_id {
+        Some(id) => version_nodes(pool, id).await?.1,
+        None => sqlx::query_as::<_, Node>(
+            "SELECT * FROM nodes WHERE workflow_id = ? ORDER BY order_index",
+        )
+        .bind(&workflow_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(ExportedNode::from)
+        .collect(),
+    };
+    Ok(diff_groups(&old, &new))
+}
+
+#[tauri::command]
+pub async fn get_workflow_by_name(
+    app: AppHandle,
+    project_id: String,
+    name: String,
+) -> Result<Option<Workflow>, String> {
+    let pool = db::pool(&app).await?;
+    find_by_name(&pool, &project_id, &name).await
+}
+
+#[tauri::command]
+pub async fn workflow_exists_by_name(
+    app: AppHandle,
+    project_id: String,
+    name: String,
+) -> Result<bool, String> {
+    let pool = db::pool(&app).await?;
+    exists_by_name(&pool, &project_id, &name).await
+}
+
+#[tauri::command]
+pub async fn create_workflow(
+    app: AppHandle,
+    project_id: String,
+    name: String,
+    description: Option<String>,
+    resolve: Option<NameResolve>,
+) -> Result<Workflow, CommandError> {
+    let pool = db::pool(&app).await?;
+    create(
+        &pool,
+        &project_id,
+        &name,
+        description.as_deref(),
+        resolve.unwrap_or_default(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn rename_workflow(
+    app: AppHandle,
+    workflow_id: String,
+    name: String,
+    resolve: Option<NameResolve>,
+) -> Result<Workflow, CommandError> {
+    let pool = db::pool(&app).await?;
+    rename(&pool, &workflow_id, &name, resolve.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn duplicate_workflow(
+    app: AppHandle,
+    workflow_id: String,
+    new_name: Option<String>,
+    resolve: Option<NameResolve>,
+) -> Result<Workflow, CommandError> {
+    let pool = db::pool(&app).await?;
+    duplicate(
+        &pool,
+        &workflow_id,
+        new_name.as_deref(),
+        resolve.unwrap_or_default(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn import_workflow(
+    app: AppHandle,
+    project_id: String,
+    data: ExportedWorkflow,
+    new_name: Option<String>,
+    resolve: Option<NameResolve>,
+) -> Result<Workflow, CommandError> {
+    let pool = db::pool(&app).await?;
+    let workflow = import(
+        &pool,
+        &project_id,
+        &data,
+        new_name.as_deref(),
+        resolve.unwrap_or_default(),
+    )
+    .await?;
+    let payload = serde_json::json!({ "target": "workflow" });
+    if let Err(e) = analytics::record(&pool, "import_used", Some(&payload)).await {
+        eprintln!("记录使用统计失败: {}", e);
+    }
+    Ok(workflow)
+}
+
+#[tauri::command]
+pub async fn find_duplicate_workflow_names(
+    app: AppHandle,
+) -> Result<Vec<DuplicateWorkflowName>, String> {
+    let pool = db::pool(&app).await?;
+    find_duplicate_names(&pool).await
+}
+
+#[tauri::command]
+pub async fn validate_workflow(
+    app: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<WorkflowIssue>, String> {
+    let pool = db::pool(&app).await?;
+    validate(&pool, &workflow_id).await
+}
+
+#[tauri::command]
+pub async fn get_workflow_version_group_changes(
+    app: AppHandle,
+    from_version_id: String,
+    to_version_id: Option<String>,
+) -> Result<Vec<GroupChange>, String> {
+    let pool = db::pool(&app).await?;
+    version_group_changes(&pool, &from_version_id, to_version_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO projects (id, name) VALUES ('p1', '项目一'), ('p2', '项目二');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w1', 'p1', '第一章');
+            INSERT INTO workflows (id, project_id, name) VALUES ('w2', 'p2', '第一章');
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn finds_workflow_within_project() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let found = find_by_name(&pool, "p2", "第一章").await.unwrap().unwrap();
+        assert_eq!(found.id, "w2");
+        assert!(find_by_name(&pool, "p1", "第二章").await.unwrap().is_none());
+
+        assert!(exists_by_name(&pool, "p1", "第一章").await.unwrap());
+        assert!(!exists_by_name(&pool, "p1", "第二章").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_or_suffixes_taken_names() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+
+        let err = create(&pool, "p1", "第一章", None, NameResolve::Error)
+            .await
+            .unwrap_err();
+        assert_eq!(err, CommandError::NameTaken("第一章".to_string()));
+
+        let second = create(&pool, "p1", "第一章", None, NameResolve::Suffix)
+            .await
+            .unwrap();
+        assert_eq!(second.name, "第一章 (2)");
+        let third = duplicate(&pool, "w1", Some("第一章"), NameResolve::Suffix)
+            .await
+            .unwrap();
+        assert_eq!(third.name, "第一章 (3)");
+
+        // 重命名为自身名称不算冲突, 大小写不同视为重名
+        create(&pool, "p1", "Prologue", None, NameResolve::Error)
+            .await
+            .unwrap();
+        assert!(rename(&pool, "w1", "第一章", NameResolve::Error)
+            .await
+            .is_ok());
+        assert!(rename(&pool, "w1", "prologue", NameResolve::Error)
+            .await
+            .is_err());
+        // 非 ASCII 字母同样不区分大小写
+        create(&pool, "p1", "Ärger", None, NameResolve::Error)
+            .await
+            .unwrap();
+        assert_eq!(
+            create(&pool, "p1", "äRGER", None, NameResolve::Error)
+                .await
+                .unwrap_err(),
+            CommandError::NameTaken("äRGER".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn duplicates_nodes_with_new_block_ids() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id)
+                VALUES ('n1', 'w1', 'loop_start', '循环', '{"max_iterations":3}', 0, 'b1');
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index, parent_block_id)
+                VALUES ('n2', 'w1', 'ai_chat', '撰写', '{}', 1, 'b1');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let copy = duplicate(&pool, "w1", None, NameResolve::Error)
+            .await
+            .unwrap();
+        assert_eq!(copy.name, "第一章 (副本)");
+
+        let nodes = export(&pool, &copy.id).await.unwrap().nodes;
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].config["max_iterations"], 3);
+        assert_ne!(nodes[0].block_id.as_deref(), Some("b1"));
+        assert_eq!(nodes[0].block_id, nodes[1].parent_block_id);
+    }
+
+    #[tokio::test]
+    async fn reports_group_changes_between_versions() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        // 旧版本快照由前端写入, 不含分组字段
+        sqlx::raw_sql(
+            r##"
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index, group_label, group_color) VALUES
+                ('n1', 'w1', 'ai_chat', '大纲', '{}', 0, '构思', '#3b82f6'),
+                ('n2', 'w1', 'ai_chat', '正文', '{}', 1, '写作', NULL),
+                ('n3', 'w1', 'ai_chat', '润色', '{}', 2, '写作', NULL);
+            INSERT INTO workflow_versions (id, workflow_id, version_number, snapshot) VALUES
+                ('v1', 'w1', 1, '{"workflow":{},"nodes":[
+                    {"type":"ai_chat","name":"大纲","config":{},"order_index":0,"block_id":null,"parent_block_id":null},
+                    {"type":"ai_chat","name":"正文","config":{},"order_index":1,"block_id":null,"parent_block_id":null}]}'),
+                ('v2', 'w1', 2, '{"workflow":{},"nodes":[
+                    {"type":"ai_chat","name":"大纲","config":{},"order_index":0,"group_label":"构思","group_color":"#f59e0b"},
+                    {"type":"ai_chat","name":"正文","config":{},"order_index":1,"group_label":"写作"},
+                    {"type":"ai_chat","name":"校对","config":{},"order_index":2,"group_label":"收尾"}]}');
+            "##,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let changes = version_group_changes(&pool, "v1", Some("v2"))
+            .await
+            .unwrap();
+        let kinds: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|c| (c.label.as_str(), c.change_kind.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [("写作", "added"), ("收尾", "added"), ("构思", "added")]
+        );
+
+        let changes = version_group_changes(&pool, "v2", None).await.unwrap();
+        assert_eq!(changes.len(), 3);
+        let writing = changes.iter().find(|c| c.label == "写作").unwrap();
+        assert_eq!(writing.change_kind, "changed");
+        assert_eq!(writing.added_members, ["润色"]);
+        let outline = changes.iter().find(|c| c.label == "构思").unwrap();
+        assert_eq!(
+            (outline.old_color.as_deref(), outline.new_color.as_deref()),
+            (Some("#f59e0b"), Some("#3b82f6"))
+        );
+        assert!(outline.added_members.is_empty() && outline.removed_members.is_empty());
+        let finishing = changes.iter().find(|c| c.label == "收尾").unwrap();
+        assert_eq!(finishing.change_kind, "removed");
+        assert_eq!(finishing.removed_members, ["校对"]);
+
+        // 导出与复制保留分组
+        let copy = duplicate(&pool, "w1", None, NameResolve::Error)
+            .await
+            .unwrap();
+        let nodes = export(&pool, &copy.id).await.unwrap().nodes;
+        assert_eq!(nodes[0].group_label.as_deref(), Some("构思"));
+        assert_eq!(nodes[0].group_color.as_deref(), Some("#3b82f6"));
+        assert!(version_group_changes(&pool, "missing", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn carries_input_presets_through_duplicate() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        preset::create(&pool, "w1", "文风", "古风, 白描", true)
+            .await
+            .unwrap();
+
+        let copy = duplicate(&pool, "w1", None, NameResolve::Error)
+            .await
+            .unwrap();
+        let presets = preset::list(&pool, &copy.id).await.unwrap();
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets[0].content, "古风, 白描");
+        assert!(presets[0].is_default);
+        assert_eq!(preset::list(&pool, "w1").await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn fills_context_defaults_for_old_exports() {
+        let info: ExportedWorkflowInfo = serde_json::from_value(serde_json::json!({
+            "name": "第一章",
+            "description": null,
+            "loop_max_count": 10,
+            "timeout_seconds": 300
+        }))
+        .unwrap();
+        assert_eq!(info.context_mode, "stitched");
+        assert_eq!(info.context_turn_limit, 10);
+        assert_eq!(info.context_token_budget, 0);
+    }
+
+    #[tokio::test]
+    async fn reports_existing_duplicates() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            "INSERT INTO workflows (id, project_id, name) VALUES ('w3', 'p1', '第一章'), ('w4', 'p2', '第二章'),
+                 ('w5', 'p2', 'Ärger'), ('w6', 'p2', 'ärger');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let duplicates = find_duplicate_names(&pool).await.unwrap();
+        assert_eq!(duplicates.len(), 2);
+        let unicode = duplicates.iter().find(|d| d.project_id == "p2").unwrap();
+        assert_eq!(unicode.name, "Ärger");
+        assert_eq!(unicode.workflow_ids, ["w5", "w6"]);
+        let duplicates: Vec<_> = duplicates
+            .into_iter()
+            .filter(|d| d.project_id == "p1")
+            .collect();
+        assert_eq!(duplicates[0].project_id, "p1");
+        let mut ids = duplicates[0].workflow_ids.clone();
+        ids.sort();
+        assert_eq!(ids, vec!["w1", "w3"]);
+    }
+
+    #[tokio::test]
+    async fn validates_ai_node_sampling_before_run() {
+        let pool = db::test_pool().await;
+        seed(&pool).await;
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO nodes (id, workflow_id, type, name, config, order_index) VALUES
+                ('n1', 'w1', 'ai_chat', '续写', '{"provider":"openai","sampling":{"frequency_penalty":0.5}}', 0),
+                ('n2', 'w1', 'ai_chat', '润色', '{"provider":"claude","temperature":1.5,"sampling":{"presence_penalty":0.1}}', 1),
+                ('n3', 'w1', 'text_concat', '拼接', '{"temperature":9}', 2);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let issues = validate(&pool, "w1").await.unwrap();
+        let messages: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|i| (i.node_name.as_str(), i.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("润色", "temperature 须在 0 到 1 之间, 当前为 1.5"),
+                ("润色", "Claude 不支持 presence_penalty"),
+            ]
+        );
+        assert!(validate(&pool, "w2").await.unwrap().is_empty());
+        assert!(validate(&pool, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// 数据库访问: 复用 tauri-plugin-sql 已建立的连接池, 与前端共享同一个 SQLite 文件
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+/// 数据库连接地址, 需与前端 `src/lib/db/index.ts` 中的 DB_PATH 保持一致
+pub const DB_URL: &str = "sqlite:chouann_novel.db";
+
+/// 获取插件持有的 SQLite 连接池
+pub async fn pool(app: &AppHandle) -> Result<SqlitePool, String> {
+    let instances = app.state::<DbInstances>();
+    let instances = instances.0.read().await;
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err("数据库尚未加载".to_string()),
+    }
+}
+
+/// 生成 UUID, 与前端 `crypto.randomUUID()` 格式一致
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// 当前时间的 ISO 8601 字符串, 与前端 `new Date().toISOString()` 格式一致
+pub fn now() -> String {
+    to_iso(chrono::Utc::now())
+}
+
+/// 将时间格式化为与 `now()` 相同的 ISO 8601 字符串, 便于按字符串比较
+pub fn to_iso(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// 测试用内存数据库, 按顺序执行全部迁移
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("创建内存数据库失败");
+    run_test_migrations(&pool).await;
+    pool
+}
+
+/// 测试用文件数据库, 用于依赖真实文件的场景 (如 VACUUM INTO)
+#[cfg(test)]
+pub async fn test_file_pool(path: &std::path::Path) -> SqlitePool {
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("创建测试数据库文件失败");
+    run_test_migrations(&pool).await;
+    pool
+}
+
+#[cfg(test)]
+async fn run_test_migrations(pool: &SqlitePool) {
+    for migration in crate::migrations::migrations() {
+        sqlx::raw_sql(migration.sql)
+            .execute(pool)
+            .await
+            .unwrap_or_else(|e| panic!("迁移 v{} 执行失败: {}", migration.version, e));
+    }
+}
@@ -0,0 +1,152 @@
+// 命令错误: 需要前端区分处理的错误使用独立类型, 其余统一归入 Other
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// 只读项目的写入触发器抛出的错误信息
+pub const READ_ONLY_PROJECT: &str = "ReadOnlyProject";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// 同一项目内名称已被占用 (不区分大小写)
+    NameTaken(String),
+    /// 离线模式已开启, 网络请求被阻止; 携带被阻止的请求目标
+    OfflineMode(String),
+    /// 项目为只读审阅副本, 写入被拒绝
+    ReadOnlyProject,
+    /// 章节已被其他窗口锁定; 携带持有者名称
+    Locked(String),
+    /// 项目已花费的 AI 费用达到预算, 不能开始新的执行
+    BudgetExceeded {
+        budget_usd: f64,
+        spend_usd: f64,
+    },
+    /// 工作流当天的执行次数达到每日上限
+    DailyLimitExceeded {
+        daily_limit: i64,
+        count_today: i64,
+    },
+    /// 批量执行的预估花费超过提供商剩余额度, 且要求额度不足时中止
+    QuotaInsufficient {
+        provider_id: String,
+        remaining: f64,
+        estimated_cost: f64,
+    },
+    Other(String),
+}
+
+impl CommandError {
+    /// 供前端判断的错误类型标识
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::NameTaken(_) => "NameTaken",
+            CommandError::OfflineMode(_) => "OfflineMode",
+            CommandError::ReadOnlyProject => READ_ONLY_PROJECT,
+            CommandError::Locked(_) => "Locked",
+            CommandError::BudgetExceeded { .. } => "BudgetExceeded",
+            CommandError::DailyLimitExceeded { .. } => "DailyLimitExceeded",
+            CommandError::QuotaInsufficient { .. } => "QuotaInsufficient",
+            CommandError::Other(_) => "Other",
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::NameTaken(name) => write!(f, "名称已被占用: {}", name),
+            CommandError::OfflineMode(target) => {
+                write!(f, "离线模式已开启, 已阻止网络请求: {}", target)
+            }
+            CommandError::ReadOnlyProject => {
+                write!(f, "项目为只读审阅副本, 请先转换为可编辑项目")
+            }
+            CommandError::Locked(holder) => write!(f, "章节正在被 {} 编辑", holder),
+            CommandError::BudgetExceeded {
+                budget_usd,
+                spend_usd,
+            } => write!(
+                f,
+                "项目 AI 费用已达预算: 已花费 ${:.2}, 预算 ${:.2}",
+                spend_usd, budget_usd
+            ),
+            CommandError::DailyLimitExceeded {
+                daily_limit,
+                count_today,
+            } => write!(
+                f,
+                "工作流今日已执行 {} 次, 达到每日上限 {} 次",
+                count_today, daily_limit
+            ),
+            CommandError::QuotaInsufficient {
+                provider_id,
+                remaining,
+                estimated_cost,
+            } => write!(
+                f,
+                "提供商 {} 剩余额度 {:.2} 不足以完成本批执行 (预估花费 {:.2})",
+                provider_id, remaining, estimated_cost
+            ),
+            CommandError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+// 已转为字符串的数据库错误仍可按触发器信息识别出只读项目
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        if message.contains(READ_ONLY_PROJECT) {
+            return CommandError::ReadOnlyProject;
+        }
+        CommandError::Other(message)
+    }
+}
+
+impl From<sqlx::Error> for CommandError {
+    fn from(error: sqlx::Error) -> Self {
+        CommandError::from(error.to_string())
+    }
+}
+
+// 序列化为 { kind, message }, 前端 getErrorMessage 可直接读取 message;
+// Locked 另带 holder, BudgetExceeded 另带 budget_usd 与 spend_usd,
+// DailyLimitExceeded 另带 daily_limit 与 count_today,
+// QuotaInsufficient 另带 provider_id、remaining 与 estimated_cost
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 5)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let CommandError::Locked(holder) = self {
+            state.serialize_field("holder", holder)?;
+        }
+        if let CommandError::BudgetExceeded {
+            budget_usd,
+            spend_usd,
+        } = self
+        {
+            state.serialize_field("budget_usd", budget_usd)?;
+            state.serialize_field("spend_usd", spend_usd)?;
+        }
+        if let CommandError::DailyLimitExceeded {
+            daily_limit,
+            count_today,
+        } = self
+        {
+            state.serialize_field("daily_limit", daily_limit)?;
+            state.serialize_field("count_today", count_today)?;
+        }
+        if let CommandError::QuotaInsufficient {
+            provider_id,
+            remaining,
+            estimated_cost,
+        } = self
+        {
+            state.serialize_field("provider_id", provider_id)?;
+            state.serialize_field("remaining", remaining)?;
+            state.serialize_field("estimated_cost", estimated_cost)?;
+        }
+        state.end()
+    }
+}
@@ -0,0 +1,282 @@
+// 设定注入的选取与文本渲染, 规则与前端 `src/lib/settings-injection.ts` 保持一致
+use crate::models::{Setting, SettingPrompt};
+use crate::tokens;
+
+/// 智能注入档位的 token 预算, 未知档位不限制
+pub fn injection_budget(level: &str) -> Option<u64> {
+    match level {
+        "minimal" => Some(500),
+        "balanced" => Some(1500),
+        "full" => Some(3000),
+        _ => None,
+    }
+}
+
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "high" => 0,
+        "low" => 2,
+        _ => 1,
+    }
+}
+
+/// 分类默认模板, 未配置启用的注入提示词时使用
+pub fn default_template(category: &str) -> &'static str {
+    match category {
+        "character" => "【角色设定】\n{{items}}",
+        "worldview" => "【世界观设定】\n{{items}}",
+        "style" => "【笔触风格】\n{{items}}",
+        "outline" => "【故事大纲】\n{{items}}",
+        _ => "{{items}}",
+    }
+}
+
+/// 分类默认的设定正文模板, 未配置 content_template 时新建设定使用
+pub fn default_content_template(category: &str) -> &'static str {
+    match category {
+        "character" => "外貌：\n\n性格：\n\n背景：\n\n目标：",
+        _ => "",
+    }
+}
+
+/// 校验模板中的 `{{...}}` 占位符: 须闭合且在 `allowed` 之内
+pub fn validate_placeholders(template: &str, allowed: &[&str]) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let body = &rest[start + 2..];
+        let Some(len) = body.find("}}") else {
+            return Err("模板中存在未闭合的占位符".to_string());
+        };
+        let placeholder = body[..len].trim();
+        if !allowed.contains(&placeholder) {
+            return Err(format!("不支持的占位符: {{{{{}}}}}", placeholder));
+        }
+        rest = &body[len + 2..];
+    }
+    Ok(())
+}
+
+/// 分类实际使用的模板: 优先取已启用的注入提示词
+pub fn template_for<'a>(category: &str, prompts: &'a [SettingPrompt]) -> &'a str {
+    prompts
+        .iter()
+        .find(|p| p.category == category && p.enabled && !p.prompt_template.is_empty())
+        .map(|p| p.prompt_template.as_str())
+        .unwrap_or_else(|| default_template(category))
+}
+
+/// 用模板渲染同一分类下的设定, 支持 `{{items}}` 与 `{{#each items}}...{{/each}}`
+pub fn render_category(template: &str, settings: &[&Setting]) -> String {
+    const EACH_START: &str = "{{#each items}}";
+    const EACH_END: &str = "{{/each}}";
+
+    if let Some(start) = template.find(EACH_START) {
+        let body_start = start + EACH_START.len();
+        if let Some(len) = template[body_start..].find(EACH_END) {
+            let item_template = &template[body_start..body_start + len];
+            let items: String = settings
+                .iter()
+                .map(|s| {
+                    item_template
+                        .replace("{{name}}", &s.name)
+                        .replace("{{content}}", &s.content)
+                })
+                .collect();
+            return format!(
+                "{}{}{}",
+                &template[..start],
+                items,
+                &template[body_start + len + EACH_END.len()..]
+            );
+        }
+        // 与前端一致: 未闭合的 each 块原样保留
+        return template.to_string();
+    }
+
+    let items = settings
+        .iter()
+        .map(|s| format!("{}：{}", s.name, s.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    template.replace("{{items}}", &items)
+}
+
+/// 选出要注入的设定: 已启用的自动注入设定在前, 其后是手动选择的设定;
+/// 指定注入档位时按优先级在 token 预算内选取, 正文超出预算时改用摘要
+pub fn select_settings(
+    settings: &[Setting],
+    manual_ids: &[String],
+    injection_level: Option<&str>,
+) -> Vec<Setting> {
+    let mut candidates: Vec<Setting> = settings
+        .iter()
+        .filter(|s| s.enabled && s.injection_mode == "auto")
+        .cloned()
+        .collect();
+    for setting in settings
+        .iter()
+        .filter(|s| s.enabled && manual_ids.contains(&s.id))
+    {
+        if !candidates.iter().any(|c| c.id == setting.id) {
+            candidates.push(setting.clone());
+        }
+    }
+    let Some(budget) = injection_level.and_then(injection_budget) else {
+        return candidates;
+    };
+
+    candidates.sort_by_key(|s| priority_rank(&s.priority));
+    let mut used = 0;
+    let mut selected = Vec::new();
+    for mut setting in candidates {
+        let cost = tokens::estimate_tokens(&setting.content);
+        if used + cost <= budget {
+            used += cost;
+            selected.push(setting);
+            continue;
+        }
+        let Some(summary) = setting.summary.clone().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let cost = tokens::estimate_tokens(&summary);
+        if used + cost <= budget {
+            used += cost;
+            setting.content = summary;
+            selected.push(setting);
+        }
+    }
+    selected
+}
+
+/// 按分类 (首次出现的顺序) 渲染注入文本, 各分类之间空一行
+pub fn render_injection(settings: &[Setting], prompts: &[SettingPrompt]) -> String {
+    let mut categories: Vec<&str> = Vec::new();
+    for setting in settings {
+        if !categories.contains(&setting.category.as_str()) {
+            categories.push(&setting.category);
+        }
+    }
+    categories
+        .into_iter()
+        .map(|category| {
+            let members: Vec<&Setting> =
+                settings.iter().filter(|s| s.category == category).collect();
+            render_category(template_for(category, prompts), &members)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 渲染设定事实为 Markdown 表格, 没有事实时返回空字符串
+pub fn render_facts_table(setting: &Setting) -> String {
+    let Some(facts) = setting.facts.as_ref().filter(|f| !f.is_empty()) else {
+        return String::new();
+    };
+    let rows: Vec<String> = facts
+        .iter()
+        .map(|(key, value)| format!("| {} | {} |", key, value))
+        .collect();
+    format!("| 属性 | 值 |\n| --- | --- |\n{}", rows.join("\n"))
+}
+
+/// 替换 `{{setting:名称.facts.键}}` 与 `{{setting:名称.facts_table}}`, 无法解析的占位符原样保留
+pub fn render_setting_refs(template: &str, settings: &[Setting]) -> String {
+    const REF_START: &str = "{{setting:";
+    const REF_END: &str = "}}";
+
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(REF_START) {
+        let body_start = start + REF_START.len();
+        let Some(len) = rest[body_start..].find(REF_END) else {
+            break;
+        };
+        let reference = rest[body_start..body_start + len].trim();
+        let end = body_start + len + REF_END.len();
+        let find = |name: &str| settings.iter().find(|s| s.name == name.trim());
+        let resolved = if let Some(name) = reference.strip_suffix(".facts_table") {
+            find(name).map(render_facts_table)
+        } else if let Some((name, key)) = reference.split_once(".facts.") {
+            find(name)
+                .and_then(|s| s.facts.as_ref())
+                .and_then(|facts| facts.get(key.trim()))
+                .cloned()
+        } else {
+            None
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(resolved.as_deref().unwrap_or(&rest[start..end]));
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(name: &str, content: &str) -> Setting {
+        Setting {
+            id: name.to_string(),
+            project_id: "p1".to_string(),
+            category: "character".to_string(),
+            name: name.to_string(),
+            content: content.to_string(),
+            enabled: true,
+            parent_id: None,
+            order_index: 0,
+            injection_mode: "manual".to_string(),
+            priority: "medium".to_string(),
+            keywords: None,
+            summary: None,
+            facts: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn renders_items_and_each_templates() {
+        let a = setting("林雪", "驿站掌柜的女儿");
+        let b = setting("陌生人", "左臂有伤");
+        let settings = [&a, &b];
+
+        assert_eq!(
+            render_category(default_template("character"), &settings),
+            "【角色设定】\n林雪：驿站掌柜的女儿\n\n陌生人：左臂有伤"
+        );
+        assert_eq!(
+            render_category("角色:\n{{#each items}}- {{name}}\n{{/each}}完", &settings),
+            "角色:\n- 林雪\n- 陌生人\n完"
+        );
+    }
+
+    #[test]
+    fn renders_setting_fact_references() {
+        let mut lin = setting("林雪", "驿站掌柜的女儿");
+        lin.facts = Some(
+            [("年龄", "17"), ("瞳色", "蓝")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        let settings = [lin, setting("陌生人", "左臂有伤")];
+
+        assert_eq!(
+            render_setting_refs("她的眼睛是{{setting:林雪.facts.瞳色}}色的", &settings),
+            "她的眼睛是蓝色的"
+        );
+        assert_eq!(
+            render_setting_refs("{{setting:林雪.facts_table}}", &settings),
+            "| 属性 | 值 |\n| --- | --- |\n| 年龄 | 17 |\n| 瞳色 | 蓝 |"
+        );
+        assert_eq!(
+            render_setting_refs(
+                "{{setting:林雪.facts.身高}} {{setting:陌生人.facts_table}}",
+                &settings
+            ),
+            "{{setting:林雪.facts.身高}} "
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// 系统钥匙串: 签名密钥等敏感值保存在操作系统的凭据存储中 (macOS 钥匙串、Windows 凭据管理器、
+// Linux Secret Service), 数据库只记录是否存在. 测试时使用线程内的内存存储, 互不影响
+
+/// 钥匙串条目的服务名
+#[cfg(not(test))]
+const SERVICE: &str = "chouannnovel";
+
+#[cfg(not(test))]
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| format!("无法访问系统钥匙串: {}", e))
+}
+
+/// 读取条目, 不存在时返回 None
+#[cfg(not(test))]
+pub fn get(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("读取系统钥匙串失败: {}", e)),
+    }
+}
+
+/// 写入条目, 已存在时覆盖
+#[cfg(not(test))]
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| format!("写入系统钥匙串失败: {}", e))
+}
+
+#[cfg(test)]
+thread_local! {
+    static ENTRIES: std::cell::RefCell<std::collections::HashMap<String, String>> =
+        Default::default();
+}
+
+#[cfg(test)]
+pub fn get(account: &str) -> Result<Option<String>, String> {
+    Ok(ENTRIES.with(|entries| entries.borrow().get(account).cloned()))
+}
+
+#[cfg(test)]
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    ENTRIES.with(|entries| {
+        entries
+            .borrow_mut()
+            .insert(account.to_string(), secret.to_string())
+    });
+    Ok(())
+}
@@ -1,231 +1,83 @@
 // 了解更多关于 Tauri 命令的信息: https://tauri.app/develop/calling-rust/
-use tauri_plugin_sql::{Builder, Migration, MigrationKind};
+use tauri::Manager;
+use tauri_plugin_sql::Builder;
+
+mod cli;
+mod commands;
+mod db;
+mod error;
+mod injection;
+mod keychain;
+mod migrations;
+mod models;
+mod tokens;
 
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("你好, {}! 来自 Rust 的问候!", name)
 }
 
+/// 命令行模式: 启动参数是命令行子命令时执行并返回退出码, 否则返回 None 按界面模式启动
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    cli::run(&args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // 数据库初始化
-    let migrations = vec![Migration {
-        version: 1,
-        description: "init_database",
-        sql: r#"
-            -- 项目表
-            CREATE TABLE IF NOT EXISTS projects (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            );
-
-            -- 工作流表
-            CREATE TABLE IF NOT EXISTS workflows (
-                id TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                name TEXT NOT NULL,
-                description TEXT,
-                loop_max_count INTEGER DEFAULT 10,
-                timeout_seconds INTEGER DEFAULT 300,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
-            );
-
-            -- 节点表
-            CREATE TABLE IF NOT EXISTS nodes (
-                id TEXT PRIMARY KEY,
-                workflow_id TEXT NOT NULL,
-                type TEXT NOT NULL,
-                name TEXT NOT NULL,
-                config TEXT NOT NULL DEFAULT '{}',
-                order_index INTEGER NOT NULL,
-                block_id TEXT,
-                parent_block_id TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
-            );
-
-            -- 设定库表
-            CREATE TABLE IF NOT EXISTS settings (
-                id TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                category TEXT NOT NULL,
-                name TEXT NOT NULL,
-                content TEXT NOT NULL,
-                enabled INTEGER DEFAULT 1,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
-            );
-
-            -- 设定注入提示词表
-            CREATE TABLE IF NOT EXISTS setting_prompts (
-                id TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                category TEXT NOT NULL,
-                prompt_template TEXT NOT NULL,
-                enabled INTEGER DEFAULT 1,
-                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
-            );
-
-            -- 全局配置表
-            CREATE TABLE IF NOT EXISTS global_config (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                ai_providers TEXT NOT NULL DEFAULT '{}',
-                theme TEXT DEFAULT 'system',
-                default_loop_max INTEGER DEFAULT 10,
-                default_timeout INTEGER DEFAULT 300
-            );
-
-            -- 执行记录表
-            CREATE TABLE IF NOT EXISTS executions (
-                id TEXT PRIMARY KEY,
-                workflow_id TEXT NOT NULL,
-                status TEXT NOT NULL,
-                input TEXT,
-                final_output TEXT,
-                variables_snapshot TEXT,
-                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                finished_at DATETIME,
-                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
-            );
-
-            -- 节点执行结果表
-            CREATE TABLE IF NOT EXISTS node_results (
-                id TEXT PRIMARY KEY,
-                execution_id TEXT NOT NULL,
-                node_id TEXT NOT NULL,
-                iteration INTEGER DEFAULT 1,
-                input TEXT,
-                output TEXT,
-                resolved_config TEXT,
-                status TEXT NOT NULL,
-                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                finished_at DATETIME,
-                FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
-            );
-
-            -- 工作流版本历史表
-            CREATE TABLE IF NOT EXISTS workflow_versions (
-                id TEXT PRIMARY KEY,
-                workflow_id TEXT NOT NULL,
-                version_number INTEGER NOT NULL,
-                snapshot TEXT NOT NULL,
-                description TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
-            );
-
-            -- 插入默认全局配置
-            INSERT OR IGNORE INTO global_config (id, ai_providers, theme)
-            VALUES (1, '{}', 'system');
-
-            -- 性能优化索引：工作流表
-            CREATE INDEX IF NOT EXISTS idx_workflows_project_id ON workflows(project_id);
-            CREATE INDEX IF NOT EXISTS idx_workflows_updated_at ON workflows(updated_at DESC);
-
-            -- 性能优化索引：节点表
-            CREATE INDEX IF NOT EXISTS idx_nodes_workflow_id ON nodes(workflow_id);
-            CREATE INDEX IF NOT EXISTS idx_nodes_order_index ON nodes(workflow_id, order_index);
-
-            -- 性能优化索引：设定库表
-            CREATE INDEX IF NOT EXISTS idx_settings_project_id ON settings(project_id);
-            CREATE INDEX IF NOT EXISTS idx_settings_project_category ON settings(project_id, category);
-            CREATE INDEX IF NOT EXISTS idx_settings_name ON settings(name);
-
-            -- 性能优化索引：设定提示词表
-            CREATE INDEX IF NOT EXISTS idx_setting_prompts_project_id ON setting_prompts(project_id);
-            CREATE INDEX IF NOT EXISTS idx_setting_prompts_project_category ON setting_prompts(project_id, category);
-
-            -- 性能优化索引：执行记录表
-            CREATE INDEX IF NOT EXISTS idx_executions_workflow_id ON executions(workflow_id);
-            CREATE INDEX IF NOT EXISTS idx_executions_started_at ON executions(started_at DESC);
-            CREATE INDEX IF NOT EXISTS idx_executions_workflow_started ON executions(workflow_id, started_at DESC);
-
-            -- 性能优化索引：节点结果表
-            CREATE INDEX IF NOT EXISTS idx_node_results_execution_id ON node_results(execution_id);
-            CREATE INDEX IF NOT EXISTS idx_node_results_node_id ON node_results(node_id);
-            CREATE INDEX IF NOT EXISTS idx_node_results_started_at ON node_results(started_at);
-
-            -- 性能优化索引：工作流版本历史表
-            CREATE INDEX IF NOT EXISTS idx_workflow_versions_workflow_id ON workflow_versions(workflow_id);
-            CREATE INDEX IF NOT EXISTS idx_workflow_versions_number ON workflow_versions(workflow_id, version_number DESC);
-        "#,
-        kind: MigrationKind::Up,
-    },
-    Migration {
-        version: 2,
-        description: "add_settings_hierarchy",
-        sql: r#"
-            ALTER TABLE settings ADD COLUMN parent_id TEXT DEFAULT NULL;
-            ALTER TABLE settings ADD COLUMN order_index INTEGER DEFAULT 0;
-            CREATE INDEX IF NOT EXISTS idx_settings_parent_id ON settings(parent_id);
-        "#,
-        kind: MigrationKind::Up,
-    },
-    Migration {
-        version: 3,
-        description: "add_token_usage_to_node_results",
-        sql: r#"
-            ALTER TABLE node_results ADD COLUMN token_usage TEXT;
-        "#,
-        kind: MigrationKind::Up,
-    },
-    Migration {
-        version: 4,
-        description: "add_settings_injection_fields",
-        sql: r#"
-            ALTER TABLE settings ADD COLUMN injection_mode TEXT DEFAULT 'manual';
-            ALTER TABLE settings ADD COLUMN priority TEXT DEFAULT 'medium';
-            ALTER TABLE settings ADD COLUMN keywords TEXT DEFAULT NULL;
-            ALTER TABLE settings ADD COLUMN summary TEXT DEFAULT NULL;
-        "#,
-        kind: MigrationKind::Up,
-    },
-    Migration {
-        version: 5,
-        description: "add_setting_relations_table",
-        sql: r#"
-            CREATE TABLE IF NOT EXISTS setting_relations (
-                id TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                source_id TEXT NOT NULL,
-                target_id TEXT NOT NULL,
-                label TEXT DEFAULT NULL,
-                description TEXT DEFAULT NULL,
-                bidirectional INTEGER DEFAULT 1,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
-                FOREIGN KEY (source_id) REFERENCES settings(id) ON DELETE CASCADE,
-                FOREIGN KEY (target_id) REFERENCES settings(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_relations_source ON setting_relations(source_id);
-            CREATE INDEX IF NOT EXISTS idx_relations_target ON setting_relations(target_id);
-            CREATE INDEX IF NOT EXISTS idx_relations_project ON setting_relations(project_id);
-        "#,
-        kind: MigrationKind::Up,
-    },
-    Migration {
-        version: 6,
-        description: "add_setting_assistant_config",
-        sql: r#"
-            ALTER TABLE global_config ADD COLUMN setting_assistant TEXT DEFAULT NULL;
-        "#,
-        kind: MigrationKind::Up,
-    }];
-
     tauri::Builder::default()
         .setup(|app| {
             #[cfg(desktop)]
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
+
+            // 首次启动时生成示例数据, 清理过期的恢复草稿, 把上次退出时中断的执行标记为失败,
+            // 并把旧版的 Webhook 签名密钥迁入系统钥匙串; 失败不影响应用启动
+            let handle = app.handle().clone();
+            tauri::async_runtime::block_on(async move {
+                let pool = match db::pool(&handle).await {
+                    Ok(pool) => pool,
+                    Err(e) => return eprintln!("打开数据库失败: {}", e),
+                };
+                if let Err(e) = commands::sample::seed_on_first_launch(&pool).await {
+                    eprintln!("生成示例数据失败: {}", e);
+                }
+                if let Err(e) = commands::recovery::purge_expired(&pool).await {
+                    eprintln!("清理过期恢复草稿失败: {}", e);
+                }
+                if let Err(e) = commands::queue::recover_interrupted(&pool).await {
+                    eprintln!("恢复中断的执行失败: {}", e);
+                }
+                if let Err(e) = commands::webhook::migrate_legacy_secrets(&pool).await {
+                    eprintln!("迁移 Webhook 签名密钥失败: {}", e);
+                }
+            });
+
+            commands::watchdog::spawn(app.handle().clone());
+            // 继续执行未完成的数据回填任务
+            commands::background_job::spawn(app.handle().clone());
+
+            // 双击关联文件启动时, 文件路径作为启动参数传入
+            commands::file_open::offer_paths(app.handle(), std::env::args_os().skip(1));
             Ok(())
         })
+        .manage(commands::file_open::ImportQueue::default())
+        .manage(commands::provider_health::ProviderHealthState::default())
+        .manage(commands::chapter_lock::ChapterLocks::default())
+        .manage(commands::ui_prefs::UiPrefsState::default())
+        .manage(commands::local_server::LocalServerHandle::default())
+        // 窗口关闭时释放其持有的章节锁, 并保存尚未落库的界面偏好
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                window
+                    .state::<commands::chapter_lock::ChapterLocks>()
+                    .release_window(window.label());
+                commands::ui_prefs::flush_now(window.app_handle());
+            }
+        })
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_http::init())
@@ -233,10 +85,215 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(
             Builder::default()
-                .add_migrations("sqlite:chouann_novel.db", migrations)
+                .add_migrations(db::DB_URL, migrations::migrations())
                 .build(),
         )
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("运行 Tauri 应用时出错");
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            commands::analytics::set_local_analytics_enabled,
+            commands::analytics::record_app_event,
+            commands::analytics::get_app_activity_summary,
+            commands::analytics::purge_app_events,
+            commands::snapshot::create_project_snapshot,
+            commands::snapshot::get_project_snapshots,
+            commands::execution::set_execution_label,
+            commands::execution::list_execution_labels,
+            commands::execution::search_executions,
+            commands::sample::seed_sample_data,
+            commands::sample::delete_sample_data,
+            commands::workflow::get_workflow_by_name,
+            commands::workflow::workflow_exists_by_name,
+            commands::workflow::create_workflow,
+            commands::workflow::rename_workflow,
+            commands::workflow::duplicate_workflow,
+            commands::workflow::import_workflow,
+            commands::workflow::find_duplicate_workflow_names,
+            commands::setting::get_setting_by_name,
+            commands::setting::setting_exists,
+            commands::execution::preview_bulk_delete_executions,
+            commands::execution::bulk_delete_executions,
+            commands::node::list_orphaned_nodes,
+            commands::watchdog::report_execution_heartbeat,
+            commands::watchdog::set_watchdog_timeout,
+            commands::node::set_node_description,
+            commands::node::duplicate_node,
+            commands::node::get_nodes_by_type,
+            commands::node::count_nodes_by_type,
+            commands::provider::get_providers_masked,
+            commands::provider::reveal_provider_key,
+            commands::execution::get_execution_variable_trace,
+            commands::export::export_execution_json,
+            commands::setting::compute_setting_token_budget,
+            commands::execution::auto_title_execution,
+            commands::execution::rename_execution,
+            commands::attachment::add_execution_attachment,
+            commands::attachment::get_execution_attachments,
+            commands::provider::get_provider_quota,
+            commands::provider::check_provider_quota_preflight,
+            commands::attachment::get_execution_attachment_preview,
+            commands::note::create_project_note,
+            commands::note::update_project_note,
+            commands::note::delete_project_note,
+            commands::note::list_project_notes,
+            commands::note::get_project_note,
+            commands::webhook::set_workflow_webhook,
+            commands::webhook::deliver_execution_webhook,
+            commands::webhook::list_webhook_deliveries,
+            commands::note::search_project_notes,
+            commands::sql_console::run_readonly_query,
+            commands::sql_console::prepare_write_statement,
+            commands::sql_console::run_write_statement,
+            commands::note::link_note_to_resource,
+            commands::note::unlink_note_from_resource,
+            commands::note::get_notes_for_resource,
+            commands::preset::create_input_preset,
+            commands::preset::update_input_preset,
+            commands::preset::delete_input_preset,
+            commands::preset::list_input_presets,
+            commands::preset::get_default_input,
+            commands::preset::resolve_execution_input,
+            commands::setting::create_world_building_entry,
+            commands::setting::list_world_building_entries,
+            commands::tts::export_chapter_audio,
+            commands::tts::cancel_chapter_audio_export,
+            commands::setting::get_character_network,
+            commands::setting::set_setting_fact,
+            commands::setting::remove_setting_fact,
+            commands::setting::preview_setting_references,
+            commands::setting::get_location_hierarchy,
+            commands::timeline::get_execution_timeline,
+            commands::chapter::create_chapter_outline,
+            commands::chapter::get_chapter,
+            commands::chapter::update_chapter,
+            commands::chapter::delete_chapter,
+            commands::chapter::reorder_chapters,
+            commands::chapter::get_chapter_by_index,
+            commands::chapter::get_project_chapter_list,
+            commands::chapter::renumber_chapters,
+            commands::chapter::link_execution_to_chapter,
+            commands::chapter::get_chapter_executions,
+            commands::chapter::compute_project_word_count,
+            commands::overview::get_global_overview,
+            commands::chapter::get_chapter_word_count,
+            commands::setting::get_settings_changed_since,
+            commands::chapter::get_all_chapters_progress,
+            commands::post_processing::get_workflow_post_processing,
+            commands::post_processing::set_workflow_post_processing,
+            commands::post_processing::preview_post_processing,
+            commands::chapter::set_chapter_status,
+            commands::chapter::get_chapters_by_status,
+            commands::outline::import_outline_opml,
+            commands::chapter::get_next_chapter,
+            commands::chapter::get_previous_chapter,
+            commands::find_replace::find_replace,
+            commands::find_replace::apply_replace,
+            commands::find_replace::undo_replace,
+            commands::find_replace::list_replace_history,
+            commands::workflow::validate_workflow,
+            commands::chapter::duplicate_chapter,
+            commands::network::get_offline_mode,
+            commands::network::set_offline_mode,
+            commands::network::ensure_network_allowed,
+            commands::chapter::generate_chapter_synopsis,
+            commands::queue::enqueue_execution,
+            commands::queue::claim_next_execution,
+            commands::queue::bump_execution_priority,
+            commands::queue::get_execution_queue_status,
+            commands::chapter::get_project_full_draft,
+            commands::setting::create_setting_from_template,
+            commands::setting::update_category_template,
+            commands::export::export_full_draft_to_markdown,
+            commands::synopsis::get_project_synopsis,
+            commands::synopsis::configure_project_synopsis,
+            commands::synopsis::update_chapter_synopsis,
+            commands::synopsis::rebuild_synopsis,
+            commands::scene::create_scene,
+            commands::scene::get_scene,
+            commands::scene::update_scene,
+            commands::scene::delete_scene,
+            commands::scene::list_scenes_for_chapter,
+            commands::scene::reorder_scenes,
+            commands::scene::link_execution_to_scene,
+            commands::file_open::get_pending_import_suggestion,
+            commands::file_open::finish_import_suggestion,
+            commands::scene::get_scene_execution_content,
+            commands::review::export_review_copy,
+            commands::review::import_review_copy,
+            commands::review::convert_to_editable,
+            commands::setting::detect_setting_inconsistencies,
+            commands::node::set_node_group,
+            commands::node::clear_node_group,
+            commands::node::get_workflow_groups,
+            commands::workflow::get_workflow_version_group_changes,
+            commands::execution::get_workflow_execution_heatmap,
+            commands::recovery::save_recovery_draft,
+            commands::recovery::get_recovery_draft,
+            commands::recovery::clear_recovery_draft,
+            commands::execution::get_execution_output_length_distribution,
+            commands::provider_health::record_provider_request,
+            commands::provider_health::get_provider_health,
+            commands::provider_health::rank_providers_by_health,
+            commands::execution::detect_duplicate_executions,
+            commands::chapter_lock::acquire_chapter_lock,
+            commands::chapter_lock::release_chapter_lock,
+            commands::chapter_lock::list_active_locks,
+            commands::chapter_lock::set_chapter_lock_idle_timeout,
+            commands::execution::get_execution_cost_report,
+            commands::annotation::create_annotation,
+            commands::annotation::update_annotation,
+            commands::annotation::delete_annotation,
+            commands::annotation::list_annotations,
+            commands::budget::set_project_budget,
+            commands::budget::get_project_spend,
+            commands::budget::ensure_project_within_budget,
+            commands::ui_prefs::set_ui_pref,
+            commands::ui_prefs::get_ui_prefs,
+            commands::ui_prefs::export_ui_prefs,
+            commands::ui_prefs::import_ui_prefs,
+            commands::budget::get_budget_status,
+            commands::chapter_length::get_chapter_length_range,
+            commands::chapter_length::set_chapter_length_range,
+            commands::webhook::register_webhook,
+            commands::webhook::delete_webhook,
+            commands::webhook::list_webhooks,
+            commands::batch_report::export_batch_report,
+            commands::queue::enqueue_execution_batch,
+            commands::webhook::get_webhook_delivery_log,
+            commands::webhook::test_webhook,
+            commands::background_job::get_background_jobs,
+            commands::background_job::cancel_background_job,
+            commands::api_key::create_api_key,
+            commands::api_key::list_api_keys,
+            commands::api_key::revoke_api_key,
+            commands::api_key::validate_api_key,
+            commands::local_server::start_local_http_server,
+            commands::local_server::stop_local_http_server,
+            commands::local_server::get_local_server_status,
+            commands::fine_tune::import_openai_fine_tune_data,
+            commands::text_format::format_output_as_dialogue,
+            commands::prose_analysis::detect_pov_violations,
+            commands::prose_analysis::compute_readability_score,
+            commands::prose_analysis::detect_repetitive_words,
+            commands::text_format::apply_text_transform,
+            commands::structured_output::parse_structured_ai_output,
+            commands::token_limit::truncate_to_token_limit,
+            commands::token_limit::split_long_output_into_chunks,
+            commands::daily_limit::get_workflow_invocation_count_today,
+            commands::daily_limit::set_workflow_daily_limit,
+            commands::daily_limit::ensure_workflow_within_daily_limit,
+            commands::context_preview::get_workflow_settings_context_preview,
+            commands::execution::list_recent_executions_across_all_projects,
+            commands::node::get_node_by_name_in_workflow,
+            commands::node::list_nodes_matching_name_pattern,
+        ])
+        .build(tauri::generate_context!())
+        .expect("运行 Tauri 应用时出错")
+        .run(|_app, _event| {
+            // macOS 通过系统事件传入打开的文件
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                let paths = urls.iter().filter_map(|url| url.to_file_path().ok());
+                commands::file_open::offer_paths(_app, paths);
+            }
+        });
 }
@@ -0,0 +1,1044 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 数据库迁移列表, 新增迁移只能在末尾追加, 已发布的迁移不可修改
+#[rustfmt::skip]
+pub fn migrations() -> Vec<Migration> {
+    vec![Migration {
+        version: 1,
+        description: "init_database",
+        sql: r#"
+            -- 项目表
+            CREATE TABLE IF NOT EXISTS projects (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- 工作流表
+            CREATE TABLE IF NOT EXISTS workflows (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                loop_max_count INTEGER DEFAULT 10,
+                timeout_seconds INTEGER DEFAULT 300,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- 节点表
+            CREATE TABLE IF NOT EXISTS nodes (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                config TEXT NOT NULL DEFAULT '{}',
+                order_index INTEGER NOT NULL,
+                block_id TEXT,
+                parent_block_id TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+            );
+
+            -- 设定库表
+            CREATE TABLE IF NOT EXISTS settings (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                category TEXT NOT NULL,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                enabled INTEGER DEFAULT 1,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- 设定注入提示词表
+            CREATE TABLE IF NOT EXISTS setting_prompts (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                category TEXT NOT NULL,
+                prompt_template TEXT NOT NULL,
+                enabled INTEGER DEFAULT 1,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- 全局配置表
+            CREATE TABLE IF NOT EXISTS global_config (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                ai_providers TEXT NOT NULL DEFAULT '{}',
+                theme TEXT DEFAULT 'system',
+                default_loop_max INTEGER DEFAULT 10,
+                default_timeout INTEGER DEFAULT 300
+            );
+
+            -- 执行记录表
+            CREATE TABLE IF NOT EXISTS executions (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                input TEXT,
+                final_output TEXT,
+                variables_snapshot TEXT,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                finished_at DATETIME,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+            );
+
+            -- 节点执行结果表
+            CREATE TABLE IF NOT EXISTS node_results (
+                id TEXT PRIMARY KEY,
+                execution_id TEXT NOT NULL,
+                node_id TEXT NOT NULL,
+                iteration INTEGER DEFAULT 1,
+                input TEXT,
+                output TEXT,
+                resolved_config TEXT,
+                status TEXT NOT NULL,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                finished_at DATETIME,
+                FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+            );
+
+            -- 工作流版本历史表
+            CREATE TABLE IF NOT EXISTS workflow_versions (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                version_number INTEGER NOT NULL,
+                snapshot TEXT NOT NULL,
+                description TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+            );
+
+            -- 插入默认全局配置
+            INSERT OR IGNORE INTO global_config (id, ai_providers, theme)
+            VALUES (1, '{}', 'system');
+
+            -- 性能优化索引：工作流表
+            CREATE INDEX IF NOT EXISTS idx_workflows_project_id ON workflows(project_id);
+            CREATE INDEX IF NOT EXISTS idx_workflows_updated_at ON workflows(updated_at DESC);
+
+            -- 性能优化索引：节点表
+            CREATE INDEX IF NOT EXISTS idx_nodes_workflow_id ON nodes(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_nodes_order_index ON nodes(workflow_id, order_index);
+
+            -- 性能优化索引：设定库表
+            CREATE INDEX IF NOT EXISTS idx_settings_project_id ON settings(project_id);
+            CREATE INDEX IF NOT EXISTS idx_settings_project_category ON settings(project_id, category);
+            CREATE INDEX IF NOT EXISTS idx_settings_name ON settings(name);
+
+            -- 性能优化索引：设定提示词表
+            CREATE INDEX IF NOT EXISTS idx_setting_prompts_project_id ON setting_prompts(project_id);
+            CREATE INDEX IF NOT EXISTS idx_setting_prompts_project_category ON setting_prompts(project_id, category);
+
+            -- 性能优化索引：执行记录表
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow_id ON executions(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_executions_started_at ON executions(started_at DESC);
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow_started ON executions(workflow_id, started_at DESC);
+
+            -- 性能优化索引：节点结果表
+            CREATE INDEX IF NOT EXISTS idx_node_results_execution_id ON node_results(execution_id);
+            CREATE INDEX IF NOT EXISTS idx_node_results_node_id ON node_results(node_id);
+            CREATE INDEX IF NOT EXISTS idx_node_results_started_at ON node_results(started_at);
+
+            -- 性能优化索引：工作流版本历史表
+            CREATE INDEX IF NOT EXISTS idx_workflow_versions_workflow_id ON workflow_versions(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_workflow_versions_number ON workflow_versions(workflow_id, version_number DESC);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 2,
+        description: "add_settings_hierarchy",
+        sql: r#"
+            ALTER TABLE settings ADD COLUMN parent_id TEXT DEFAULT NULL;
+            ALTER TABLE settings ADD COLUMN order_index INTEGER DEFAULT 0;
+            CREATE INDEX IF NOT EXISTS idx_settings_parent_id ON settings(parent_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 3,
+        description: "add_token_usage_to_node_results",
+        sql: r#"
+            ALTER TABLE node_results ADD COLUMN token_usage TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 4,
+        description: "add_settings_injection_fields",
+        sql: r#"
+            ALTER TABLE settings ADD COLUMN injection_mode TEXT DEFAULT 'manual';
+            ALTER TABLE settings ADD COLUMN priority TEXT DEFAULT 'medium';
+            ALTER TABLE settings ADD COLUMN keywords TEXT DEFAULT NULL;
+            ALTER TABLE settings ADD COLUMN summary TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 5,
+        description: "add_setting_relations_table",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS setting_relations (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                label TEXT DEFAULT NULL,
+                description TEXT DEFAULT NULL,
+                bidirectional INTEGER DEFAULT 1,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (source_id) REFERENCES settings(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_id) REFERENCES settings(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_relations_source ON setting_relations(source_id);
+            CREATE INDEX IF NOT EXISTS idx_relations_target ON setting_relations(target_id);
+            CREATE INDEX IF NOT EXISTS idx_relations_project ON setting_relations(project_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 6,
+        description: "add_setting_assistant_config",
+        sql: r#"
+            ALTER TABLE global_config ADD COLUMN setting_assistant TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 7,
+        description: "add_project_snapshots_table",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS project_snapshots (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                snapshot TEXT NOT NULL,
+                description TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_project_snapshots_project ON project_snapshots(project_id, created_at DESC);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 8,
+        description: "add_local_analytics",
+        sql: r#"
+            ALTER TABLE global_config ADD COLUMN local_analytics_enabled INTEGER DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS app_events (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                payload TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_app_events_created_at ON app_events(created_at);
+            CREATE INDEX IF NOT EXISTS idx_app_events_kind ON app_events(kind);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 9,
+        description: "add_execution_label",
+        sql: r#"
+            ALTER TABLE executions ADD COLUMN label TEXT DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow_label ON executions(workflow_id, label);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 10,
+        description: "add_sample_data_flags",
+        sql: r#"
+            ALTER TABLE projects ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE workflows ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE nodes ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE settings ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE setting_prompts ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE executions ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE node_results ADD COLUMN is_sample INTEGER DEFAULT 0;
+            ALTER TABLE global_config ADD COLUMN sample_data_seeded INTEGER DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 11,
+        description: "add_node_connections_table",
+        sql: r#"
+            -- 节点连接表 (图结构工作流), 线性工作流不写入任何连接
+            CREATE TABLE IF NOT EXISTS node_connections (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                source_node_id TEXT NOT NULL,
+                target_node_id TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE,
+                FOREIGN KEY (source_node_id) REFERENCES nodes(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_node_connections_workflow ON node_connections(workflow_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 12,
+        description: "add_execution_runtime_state",
+        sql: r#"
+            -- 执行运行时状态: 引擎在每个输出片段/节点边界上报心跳, 供看门狗检测卡住的执行
+            CREATE TABLE IF NOT EXISTS execution_runtime_state (
+                execution_id TEXT PRIMARY KEY,
+                node_id TEXT,
+                last_heartbeat_at TEXT NOT NULL,
+                watchdog_triggered_at TEXT,
+                FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+            );
+            ALTER TABLE global_config ADD COLUMN watchdog_timeout_seconds INTEGER DEFAULT 120;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 13,
+        description: "add_node_description",
+        sql: r#"
+            ALTER TABLE nodes ADD COLUMN description TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 14,
+        description: "add_execution_variable_snapshots",
+        sql: r#"
+            -- 执行过程中的变量快照: 每个写入变量的节点完成后保存一次, 用于变量追踪/调试
+            CREATE TABLE IF NOT EXISTS execution_variable_snapshots (
+                id TEXT PRIMARY KEY,
+                execution_id TEXT NOT NULL,
+                node_id TEXT NOT NULL,
+                variables TEXT NOT NULL DEFAULT '{}',
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_execution_variable_snapshots_execution ON execution_variable_snapshots(execution_id, created_at);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 15,
+        description: "add_execution_title",
+        sql: r#"
+            ALTER TABLE executions ADD COLUMN title TEXT DEFAULT NULL;
+            ALTER TABLE global_config ADD COLUMN ai_execution_title INTEGER DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 16,
+        description: "add_execution_attachments",
+        sql: r#"
+            -- 执行附件: 封面、大纲、参考图等与执行结果关联的本地文件
+            CREATE TABLE IF NOT EXISTS execution_attachments (
+                id TEXT PRIMARY KEY,
+                execution_id TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                mime_type TEXT,
+                description TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_execution_attachments_execution ON execution_attachments(execution_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 17,
+        description: "add_workflow_context_mode",
+        sql: r#"
+            -- 上下文模式: stitched 为拼接提示词, chat_history 为将前序 AI 节点作为对话历史
+            ALTER TABLE workflows ADD COLUMN context_mode TEXT NOT NULL DEFAULT 'stitched';
+            -- chat_history 模式下保留的最大轮数与 token 预算 (0 表示不限制)
+            ALTER TABLE workflows ADD COLUMN context_turn_limit INTEGER NOT NULL DEFAULT 10;
+            ALTER TABLE workflows ADD COLUMN context_token_budget INTEGER NOT NULL DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 18,
+        description: "add_project_notes",
+        sql: r#"
+            -- 项目笔记: 项目级的自由记事 (头脑风暴、大纲等), 与设定库分开
+            CREATE TABLE IF NOT EXISTS project_notes (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                title TEXT NOT NULL DEFAULT '',
+                content TEXT NOT NULL DEFAULT '',
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_project_notes_project ON project_notes(project_id, updated_at DESC);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 19,
+        description: "add_workflow_webhooks",
+        sql: r#"
+            -- 工作流 Webhook: 执行完成或失败时 POST 通知, 请求体使用 webhook_secret 做 HMAC-SHA256 签名
+            ALTER TABLE workflows ADD COLUMN webhook_url TEXT DEFAULT NULL;
+            ALTER TABLE workflows ADD COLUMN webhook_include_output INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE workflows ADD COLUMN webhook_secret TEXT DEFAULT NULL;
+
+            -- 每次投递尝试一条记录
+            CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                execution_id TEXT NOT NULL,
+                attempt INTEGER NOT NULL,
+                status_code INTEGER,
+                success INTEGER NOT NULL DEFAULT 0,
+                error TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_workflow ON webhook_deliveries(workflow_id, created_at DESC);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 20,
+        description: "add_sql_console_flag",
+        sql: r#"
+            -- SQL 控制台开关, 默认关闭
+            ALTER TABLE global_config ADD COLUMN enable_sql_console INTEGER DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 21,
+        description: "add_note_links",
+        sql: r#"
+            -- 笔记关联: 将项目笔记关联到工作流、设定等资源
+            CREATE TABLE IF NOT EXISTS note_links (
+                note_id TEXT NOT NULL,
+                resource_type TEXT NOT NULL,
+                resource_id TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (note_id, resource_type, resource_id),
+                FOREIGN KEY (note_id) REFERENCES project_notes(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_note_links_resource ON note_links(resource_type, resource_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 22,
+        description: "add_input_presets",
+        sql: r#"
+            -- 执行输入预设: 每个工作流可保存多段常用输入, 至多一个默认预设
+            CREATE TABLE IF NOT EXISTS input_presets (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL DEFAULT '',
+                is_default INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_input_presets_workflow ON input_presets(workflow_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 23,
+        description: "add_setting_facts",
+        sql: r#"
+            -- 设定结构化事实: JSON 对象, 键值均为字符串, 如 {"年龄": "17", "瞳色": "蓝"}
+            ALTER TABLE settings ADD COLUMN facts TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 24,
+        description: "add_timeline_timestamps",
+        sql: r#"
+            -- 节点收到首个流式片段的时间, 用于区分等待提供商与流式输出的耗时
+            ALTER TABLE node_results ADD COLUMN first_token_at TEXT DEFAULT NULL;
+            -- 执行进入队列的时间, 直接开始的执行为空
+            ALTER TABLE executions ADD COLUMN queued_at TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 25,
+        description: "create_chapters",
+        sql: r#"
+            -- 章节规划: 标题、梗概、目标字数与写作状态
+            CREATE TABLE IF NOT EXISTS chapters (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                synopsis TEXT DEFAULT NULL,
+                target_word_count INTEGER DEFAULT NULL,
+                order_index INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'draft',
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_chapters_project_order ON chapters(project_id, order_index);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 26,
+        description: "add_execution_chapter",
+        sql: r#"
+            -- 执行记录所属章节
+            ALTER TABLE executions ADD COLUMN chapter_id TEXT DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS idx_executions_chapter ON executions(chapter_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 27,
+        description: "create_setting_tombstones",
+        sql: r#"
+            -- 已删除设定的记录, 供增量刷新得知哪些设定已不存在
+            CREATE TABLE IF NOT EXISTS setting_tombstones (
+                setting_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                deleted_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_setting_tombstones_project ON setting_tombstones(project_id, deleted_at);
+            -- 任意途径 (含删除项目的级联) 删除设定时记录, 时间格式与 updated_at 一致
+            CREATE TRIGGER IF NOT EXISTS trg_settings_tombstone AFTER DELETE ON settings
+            BEGIN
+                INSERT OR REPLACE INTO setting_tombstones (setting_id, project_id, deleted_at)
+                VALUES (OLD.id, OLD.project_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 28,
+        description: "add_post_processing",
+        sql: r#"
+            -- 工作流的输出后处理规则 (JSON), 为空表示不处理
+            ALTER TABLE workflows ADD COLUMN post_processing TEXT DEFAULT NULL;
+            -- 节点输出实际触发的后处理规则与删除的字符数 (JSON)
+            ALTER TABLE node_results ADD COLUMN post_processing TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 29,
+        description: "rename_chapter_statuses",
+        sql: r#"
+            -- 章节状态改为看板列: draft / in_progress / review / complete
+            UPDATE chapters SET status = CASE status
+                WHEN 'planned' THEN 'draft'
+                WHEN 'drafting' THEN 'in_progress'
+                WHEN 'revising' THEN 'review'
+                WHEN 'done' THEN 'complete'
+                ELSE status
+            END;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 30,
+        description: "add_node_results_output_guard",
+        sql: r#"
+            -- 节点结果记录输出守卫的评估与重试 (JSON)
+            ALTER TABLE node_results ADD COLUMN output_guard TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 31,
+        description: "create_replace_history",
+        sql: r#"
+            -- 全局查找替换的撤销记录
+            CREATE TABLE IF NOT EXISTS replace_history (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                find TEXT NOT NULL,
+                replace_with TEXT NOT NULL,
+                options TEXT NOT NULL,                  -- 查找范围与选项 (JSON)
+                changes TEXT NOT NULL,                  -- 每个字段替换前后的值 (JSON)
+                replaced INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                undone_at DATETIME DEFAULT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_replace_history_project ON replace_history(project_id, created_at);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 32,
+        description: "add_offline_mode",
+        sql: r#"
+            -- 离线模式: 开启后阻止所有对外网络请求
+            ALTER TABLE global_config ADD COLUMN offline_mode INTEGER DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 33,
+        description: "add_execution_priority",
+        sql: r#"
+            -- 排队执行的优先级: 2 高 / 1 普通 / 0 低, 直接开始的执行为空
+            ALTER TABLE executions ADD COLUMN priority INTEGER DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 34,
+        description: "add_project_author",
+        sql: r#"
+            -- 作者署名, 用于汇编全书草稿与导出
+            ALTER TABLE projects ADD COLUMN author TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 35,
+        description: "add_setting_content_templates",
+        sql: r#"
+            -- 分类的设定正文模板, 新建设定时以此为初始内容, {{name}} 替换为设定名称
+            ALTER TABLE setting_prompts ADD COLUMN content_template TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 36,
+        description: "create_project_synopsis",
+        sql: r#"
+            -- 项目滚动梗概: 每章一段摘要, 超出字数预算时先压缩、再移除最早的章节
+            CREATE TABLE IF NOT EXISTS project_synopsis (
+                project_id TEXT PRIMARY KEY,
+                content TEXT NOT NULL DEFAULT '',
+                -- 各章摘要 [{ chapter_id, title, summary, compressed }], content 由其渲染
+                sections TEXT NOT NULL DEFAULT '[]',
+                covers_up_to_chapter_id TEXT DEFAULT NULL,
+                char_budget INTEGER NOT NULL DEFAULT 4000,
+                -- 章节关联执行记录后自动追加摘要
+                auto_update INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT DEFAULT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 37,
+        description: "create_scenes",
+        sql: r#"
+            -- 场景: 章节内按 order_index 排列的场景细分, 状态与章节一致
+            CREATE TABLE IF NOT EXISTS scenes (
+                id TEXT PRIMARY KEY,
+                chapter_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT DEFAULT NULL,
+                order_index INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'draft',
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (chapter_id) REFERENCES chapters(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_scenes_chapter_order ON scenes(chapter_id, order_index);
+            -- 执行记录所属场景, 关联场景时同时关联其章节
+            ALTER TABLE executions ADD COLUMN scene_id TEXT DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS idx_executions_scene ON executions(scene_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 38,
+        description: "add_project_read_only",
+        sql: r#"
+            -- 只读审阅副本: 导入的审阅副本不可编辑或运行, convert_to_editable 解除限制
+            ALTER TABLE projects ADD COLUMN read_only INTEGER NOT NULL DEFAULT 0;
+            -- 只读项目的写入由触发器拦截, 前端直接写库与 Rust 命令一并生效; 删除项目时的级联删除不受影响
+            CREATE TRIGGER IF NOT EXISTS trg_projects_read_only BEFORE UPDATE ON projects
+            WHEN OLD.read_only = 1 AND NEW.read_only = 1
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_workflows_read_only_insert BEFORE INSERT ON workflows
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_workflows_read_only_update BEFORE UPDATE ON workflows
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_workflows_read_only_delete BEFORE DELETE ON workflows
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_settings_read_only_insert BEFORE INSERT ON settings
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_settings_read_only_update BEFORE UPDATE ON settings
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_settings_read_only_delete BEFORE DELETE ON settings
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_prompts_read_only_insert BEFORE INSERT ON setting_prompts
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_prompts_read_only_update BEFORE UPDATE ON setting_prompts
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_prompts_read_only_delete BEFORE DELETE ON setting_prompts
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_relations_read_only_insert BEFORE INSERT ON setting_relations
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_relations_read_only_update BEFORE UPDATE ON setting_relations
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_setting_relations_read_only_delete BEFORE DELETE ON setting_relations
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_notes_read_only_insert BEFORE INSERT ON project_notes
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_notes_read_only_update BEFORE UPDATE ON project_notes
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_notes_read_only_delete BEFORE DELETE ON project_notes
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_chapters_read_only_insert BEFORE INSERT ON chapters
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_chapters_read_only_update BEFORE UPDATE ON chapters
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_chapters_read_only_delete BEFORE DELETE ON chapters
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_synopsis_read_only_insert BEFORE INSERT ON project_synopsis
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = NEW.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_synopsis_read_only_update BEFORE UPDATE ON project_synopsis
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_project_synopsis_read_only_delete BEFORE DELETE ON project_synopsis
+            WHEN EXISTS (SELECT 1 FROM projects WHERE id = OLD.project_id AND read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_nodes_read_only_insert BEFORE INSERT ON nodes
+            WHEN EXISTS (SELECT 1 FROM workflows x JOIN projects p ON p.id = x.project_id WHERE x.id = NEW.workflow_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_nodes_read_only_update BEFORE UPDATE ON nodes
+            WHEN EXISTS (SELECT 1 FROM workflows x JOIN projects p ON p.id = x.project_id WHERE x.id = OLD.workflow_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_nodes_read_only_delete BEFORE DELETE ON nodes
+            WHEN EXISTS (SELECT 1 FROM workflows x JOIN projects p ON p.id = x.project_id WHERE x.id = OLD.workflow_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_scenes_read_only_insert BEFORE INSERT ON scenes
+            WHEN EXISTS (SELECT 1 FROM chapters x JOIN projects p ON p.id = x.project_id WHERE x.id = NEW.chapter_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_scenes_read_only_update BEFORE UPDATE ON scenes
+            WHEN EXISTS (SELECT 1 FROM chapters x JOIN projects p ON p.id = x.project_id WHERE x.id = OLD.chapter_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_scenes_read_only_delete BEFORE DELETE ON scenes
+            WHEN EXISTS (SELECT 1 FROM chapters x JOIN projects p ON p.id = x.project_id WHERE x.id = OLD.chapter_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+            -- 只读项目不能运行工作流
+            CREATE TRIGGER IF NOT EXISTS trg_executions_read_only_insert BEFORE INSERT ON executions
+            WHEN EXISTS (SELECT 1 FROM workflows w JOIN projects p ON p.id = w.project_id WHERE w.id = NEW.workflow_id AND p.read_only = 1)
+            BEGIN
+                SELECT RAISE(ABORT, 'ReadOnlyProject');
+            END;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 39,
+        description: "add_node_groups",
+        sql: r#"
+            -- 节点分组: 仅用于编辑器中的可折叠分区, 执行引擎不读取
+            ALTER TABLE nodes ADD COLUMN group_label TEXT DEFAULT NULL;
+            ALTER TABLE nodes ADD COLUMN group_color TEXT DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS idx_nodes_group ON nodes(workflow_id, group_label);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 40,
+        description: "add_recovery_drafts",
+        sql: r#"
+            -- 编辑器恢复草稿: 前端编辑中的状态按作用域键防抖保存, 超过 7 天的草稿在启动时清理
+            CREATE TABLE IF NOT EXISTS recovery_drafts (
+                scope_key TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_recovery_drafts_updated_at ON recovery_drafts(updated_at);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 41,
+        description: "add_provider_health",
+        sql: r#"
+            -- 提供商健康统计: 每个提供商每小时一行, stats_json 为请求数、各类错误数、延迟样本与 token 数
+            CREATE TABLE IF NOT EXISTS provider_health (
+                provider_id TEXT NOT NULL,
+                window_start TEXT NOT NULL,
+                stats_json TEXT NOT NULL,
+                PRIMARY KEY (provider_id, window_start)
+            );
+            CREATE INDEX IF NOT EXISTS idx_provider_health_window ON provider_health(window_start);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 42,
+        description: "add_chapter_lock_idle_timeout",
+        sql: r#"
+            -- 章节锁空闲多久后自动失效 (秒)
+            ALTER TABLE global_config ADD COLUMN chapter_lock_idle_seconds INTEGER DEFAULT 300;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 43,
+        description: "add_output_annotations",
+        sql: r#"
+            -- 锚定在执行输出或章节正文文本区间上的批注, 偏移量按字符计; anchor_text 为创建时区间内的文本,
+            -- 正文变化后据此重新定位, 找不到时标记 orphaned
+            CREATE TABLE IF NOT EXISTS output_annotations (
+                id TEXT PRIMARY KEY,
+                execution_id TEXT REFERENCES executions(id) ON DELETE CASCADE,
+                chapter_id TEXT REFERENCES chapters(id) ON DELETE CASCADE,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                anchor_text TEXT NOT NULL,
+                note TEXT NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'note',
+                orphaned INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                CHECK ((execution_id IS NULL) <> (chapter_id IS NULL))
+            );
+            CREATE INDEX IF NOT EXISTS idx_output_annotations_execution ON output_annotations(execution_id);
+            CREATE INDEX IF NOT EXISTS idx_output_annotations_chapter ON output_annotations(chapter_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 44,
+        description: "add_budget_to_projects",
+        sql: r#"
+            ALTER TABLE projects ADD COLUMN budget_usd REAL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 45,
+        description: "create_ui_preferences",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS ui_preferences (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 46,
+        description: "add_chapter_length_range_to_projects",
+        sql: r#"
+            ALTER TABLE projects ADD COLUMN min_chapter_chars INTEGER;
+            ALTER TABLE projects ADD COLUMN max_chapter_chars INTEGER;
+            ALTER TABLE projects ADD COLUMN chapter_length_enforcement TEXT NOT NULL DEFAULT 'off';
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 47,
+        description: "create_project_webhooks",
+        sql: r#"
+            -- 项目 Webhook: 按订阅的事件类型 (JSON 数组) 在执行状态变化后 POST 执行记录
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                url TEXT NOT NULL,
+                event_types TEXT NOT NULL,
+                secret TEXT,
+                enabled INTEGER DEFAULT 1,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_webhooks_project ON webhooks(project_id);
+
+            -- 项目 Webhook 的投递记录关联 Webhook, 工作流 Webhook 为 NULL
+            ALTER TABLE webhook_deliveries ADD COLUMN webhook_id TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 48,
+        description: "add_execution_batch_and_workflow_version",
+        sql: r#"
+            -- 批量执行: 同一批次的执行共享 batch_id, 用于批量报告; workflow_version 记录加入队列时的工作流版本号
+            ALTER TABLE executions ADD COLUMN batch_id TEXT DEFAULT NULL;
+            ALTER TABLE executions ADD COLUMN workflow_version INTEGER DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS idx_executions_batch ON executions(batch_id);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 49,
+        description: "add_webhook_deliveries_webhook_index",
+        sql: r#"
+            -- 按项目 Webhook 查询投递日志
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at DESC);
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 50,
+        description: "create_background_jobs",
+        sql: r#"
+            -- 后台任务: 数据回填等耗时处理分批执行, cursor 为任务类型自定义的断点 (JSON), 重启后继续
+            CREATE TABLE IF NOT EXISTS background_jobs (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                progress INTEGER NOT NULL DEFAULT 0,
+                total INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'pending',
+                cancellable INTEGER NOT NULL DEFAULT 1,
+                cursor TEXT,
+                error TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- 回填 v48 之前执行记录的 workflow_version
+            INSERT OR IGNORE INTO background_jobs (id, kind)
+            VALUES ('v50-backfill_execution_workflow_version', 'backfill_execution_workflow_version');
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 51,
+        description: "create_api_keys",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS api_keys (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL UNIQUE,
+                scopes TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                last_used_at TEXT,
+                expires_at TEXT
+            );
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 52,
+        description: "add_daily_execution_limit_to_workflows",
+        sql: r#"
+            ALTER TABLE workflows ADD COLUMN daily_execution_limit INTEGER;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 53,
+        description: "add_has_webhook_secret_to_workflows",
+        sql: r#"
+            -- 工作流 Webhook 签名密钥改存系统钥匙串, 数据库只记录是否已生成;
+            -- webhook_secret 中的旧密钥在应用启动时迁入钥匙串后清空
+            ALTER TABLE workflows ADD COLUMN has_webhook_secret INTEGER NOT NULL DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 54,
+        description: "add_app_heartbeat_to_global_config",
+        sql: r#"
+            -- 应用运行期间定期写入的存活时间, 命令行据此判断是否有应用实例会运行排队的执行
+            ALTER TABLE global_config ADD COLUMN app_heartbeat_at TEXT DEFAULT NULL;
+        "#,
+        kind: MigrationKind::Up,
+    },
+    Migration {
+        version: 55,
+        description: "add_delivered_at_to_webhook_deliveries",
+        sql: r#"
+            -- 投递日志: error 更名为 error_message, delivered_at 只在投递成功时记录
+            ALTER TABLE webhook_deliveries RENAME COLUMN error TO error_message;
+            ALTER TABLE webhook_deliveries ADD COLUMN delivered_at TEXT DEFAULT NULL;
+            UPDATE webhook_deliveries SET delivered_at = created_at WHERE success = 1;
+        "#,
+        kind: MigrationKind::Up,
+    }]
+}
@@ -0,0 +1,247 @@
+// 数据模型, 字段与前端 `src/types/index.ts` 保持一致
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// 项目
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// 作者署名
+    pub author: Option<String>,
+    /// 只读审阅副本, 写入被触发器拒绝
+    pub read_only: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 工作流
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Workflow {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub loop_max_count: i64,
+    pub timeout_seconds: i64,
+    pub context_mode: String,
+    pub context_turn_limit: i64,
+    pub context_token_budget: i64,
+    pub webhook_url: Option<String>,
+    pub webhook_include_output: bool,
+    /// 输出后处理规则, 未配置时为空
+    #[sqlx(json(nullable))]
+    pub post_processing: Option<serde_json::Value>,
+    /// 每日执行上限, None 表示不限制
+    pub daily_execution_limit: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 工作流节点
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Node {
+    pub id: String,
+    pub workflow_id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub node_type: String,
+    pub name: String,
+    #[sqlx(json)]
+    pub config: serde_json::Value,
+    pub order_index: i64,
+    pub block_id: Option<String>,
+    pub parent_block_id: Option<String>,
+    /// 节点说明, 显示为画布提示
+    pub description: Option<String>,
+    /// 编辑器分组标签, 执行时忽略
+    pub group_label: Option<String>,
+    pub group_color: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 设定库条目
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Setting {
+    pub id: String,
+    pub project_id: String,
+    pub category: String,
+    pub name: String,
+    pub content: String,
+    pub enabled: bool,
+    pub parent_id: Option<String>,
+    pub order_index: i64,
+    pub injection_mode: String,
+    pub priority: String,
+    #[sqlx(json(nullable))]
+    pub keywords: Option<Vec<String>>,
+    pub summary: Option<String>,
+    /// 结构化事实, 键值均为字符串
+    #[sqlx(json(nullable))]
+    pub facts: Option<BTreeMap<String, String>>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 设定注入提示词
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SettingPrompt {
+    pub id: String,
+    pub project_id: String,
+    pub category: String,
+    pub prompt_template: String,
+    pub enabled: bool,
+    /// 新建设定的正文模板, 为空时使用分类默认模板
+    pub content_template: Option<String>,
+}
+
+// 执行记录
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Execution {
+    pub id: String,
+    pub workflow_id: String,
+    pub status: String,
+    pub input: Option<String>,
+    pub final_output: Option<String>,
+    #[sqlx(json(nullable))]
+    pub variables_snapshot: Option<serde_json::Value>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub label: Option<String>,
+    pub title: Option<String>,
+    /// 所属章节
+    pub chapter_id: Option<String>,
+    /// 所属场景
+    pub scene_id: Option<String>,
+    /// 所属批次, 见 enqueue_execution_batch
+    pub batch_id: Option<String>,
+    /// 加入队列时工作流的最新版本号
+    pub workflow_version: Option<i64>,
+}
+
+// 节点摘要, 用于列表与检查结果
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NodeSummary {
+    pub id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub node_type: String,
+    pub name: String,
+    pub order_index: i64,
+}
+
+// 执行过程中某个节点完成后的变量快照
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VariableSnapshot {
+    pub after_node_id: String,
+    pub after_node_name: String,
+    #[sqlx(json)]
+    pub variables: serde_json::Value,
+    pub created_at: String,
+}
+
+// 节点执行结果
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NodeResult {
+    pub id: String,
+    pub execution_id: String,
+    pub node_id: String,
+    pub iteration: i64,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    #[sqlx(json(nullable))]
+    pub token_usage: Option<serde_json::Value>,
+    #[sqlx(json(nullable))]
+    pub resolved_config: Option<serde_json::Value>,
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// 收到首个流式片段的时间, 仅 AI 节点记录
+    pub first_token_at: Option<String>,
+    /// 触发的后处理规则与删除的字符数
+    #[sqlx(json(nullable))]
+    pub post_processing: Option<serde_json::Value>,
+    /// 输出守卫的评估与重试记录, 未配置守卫时为空
+    #[sqlx(json(nullable))]
+    pub output_guard: Option<serde_json::Value>,
+}
+
+// 执行附件
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: String,
+    pub execution_id: String,
+    pub file_path: String,
+    pub mime_type: Option<String>,
+    pub description: Option<String>,
+    pub created_at: String,
+}
+
+// 项目笔记
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ProjectNote {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Webhook 投递记录
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub workflow_id: String,
+    pub execution_id: String,
+    /// 项目 Webhook 的投递记录该 Webhook 的 ID, 工作流 Webhook 为 None
+    pub webhook_id: Option<String>,
+    pub attempt: i64,
+    pub status_code: Option<i64>,
+    pub success: bool,
+    pub error_message: Option<String>,
+    /// 投递成功的时间, 失败的尝试为 None
+    pub delivered_at: Option<String>,
+    pub created_at: String,
+}
+
+// 执行输入预设
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InputPreset {
+    pub id: String,
+    pub workflow_id: String,
+    pub name: String,
+    pub content: String,
+    pub is_default: bool,
+    pub created_at: String,
+}
+
+// 章节规划
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Chapter {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub synopsis: Option<String>,
+    pub target_word_count: Option<i64>,
+    pub order_index: i64,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 章节内的场景
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Scene {
+    pub id: String,
+    pub chapter_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub order_index: i64,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
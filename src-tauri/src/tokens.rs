@@ -0,0 +1,45 @@
+// Token 估算, 规则与前端 `src/lib/settings-injection.ts` 的 estimateTokens 保持一致
+
+/// 支持的 AI 提供商, 与前端 `AIProvider` 一致
+pub const PROVIDERS: &[&str] = &["openai", "gemini", "claude"];
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}')
+}
+
+/// 粗略估算文本 token 数: 中文字符 × 1.5 + 英文单词 × 1.3, 向上取整
+pub fn estimate_tokens(text: &str) -> u64 {
+    let chinese_chars = text.chars().filter(|c| is_cjk(*c)).count();
+    let remaining: String = text
+        .chars()
+        .map(|c| if is_cjk(c) { ' ' } else { c })
+        .collect();
+    let english_words = remaining.split_whitespace().count();
+    (chinese_chars as f64 * 1.5 + english_words as f64 * 1.3).ceil() as u64
+}
+
+/// 按提供商估算 token 数; 目前各提供商使用同一估算规则
+pub fn count_tokens(text: &str, provider_name: &str) -> Result<u64, String> {
+    if !PROVIDERS.contains(&provider_name) {
+        return Err(format!("不支持的提供商: {}", provider_name));
+    }
+    Ok(estimate_tokens(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_mixed_text_like_frontend() {
+        assert_eq!(estimate_tokens(""), 0);
+        // 4 个汉字 × 1.5 = 6
+        assert_eq!(estimate_tokens("雨夜来客"), 6);
+        // 2 个汉字 + 2 个单词: 3 + 2.6 = 5.6 → 6
+        assert_eq!(estimate_tokens("林雪 hello world"), 6);
+        // 标点与汉字相连时按一个单词计: 1.5 + 1.3 = 2.8 → 3
+        assert_eq!(estimate_tokens("雪。"), 3);
+        assert!(count_tokens("雨夜", "openai").is_ok());
+        assert!(count_tokens("雨夜", "unknown").is_err());
+    }
+}
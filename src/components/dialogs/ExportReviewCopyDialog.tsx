@@ -0,0 +1,125 @@
+// 导出只读审阅副本对话框：选择附带的设定并可设置密码
+import { useState, useEffect } from 'react'
+import { Button } from '@/components/ui/button'
+import { Checkbox } from '@/components/ui/checkbox'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog'
+import { exportReviewCopyToFile } from '@/lib/import-export'
+import { getErrorMessage, handleAppError } from '@/lib/errors'
+import { toast } from 'sonner'
+import type { Setting } from '@/types'
+
+interface ExportReviewCopyDialogProps {
+  open: boolean
+  onOpenChange: (open: boolean) => void
+  projectId: string
+  projectName: string
+  settings: Setting[]
+}
+
+export function ExportReviewCopyDialog({
+  open,
+  onOpenChange,
+  projectId,
+  projectName,
+  settings,
+}: ExportReviewCopyDialogProps) {
+  const [selectedIds, setSelectedIds] = useState<string[]>([])
+  const [password, setPassword] = useState('')
+  const [isExporting, setIsExporting] = useState(false)
+
+  // 对话框关闭时重置表单
+  useEffect(() => {
+    if (!open) {
+      setSelectedIds([])
+      setPassword('')
+      setIsExporting(false)
+    }
+  }, [open])
+
+  const toggleSetting = (id: string, checked: boolean) => {
+    setSelectedIds((prev) => (checked ? [...prev, id] : prev.filter((s) => s !== id)))
+  }
+
+  const handleExport = async () => {
+    setIsExporting(true)
+    try {
+      const success = await exportReviewCopyToFile(projectId, projectName, {
+        password,
+        settingIds: selectedIds,
+      })
+      if (success) {
+        toast.success('审阅副本导出成功')
+        onOpenChange(false)
+      }
+    } catch (error) {
+      handleAppError({
+        error,
+        context: '导出审阅副本',
+        toastMessage: `导出失败: ${getErrorMessage(error)}`,
+      })
+    } finally {
+      setIsExporting(false)
+    }
+  }
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[480px]">
+        <DialogHeader>
+          <DialogTitle>导出审阅副本</DialogTitle>
+          <DialogDescription>
+            审阅副本只包含章节正文、大纲与选定的设定，不含工作流与提示词；导入后为只读。
+          </DialogDescription>
+        </DialogHeader>
+        <div className="space-y-4 py-2">
+          <div className="space-y-2">
+            <Label>附带的设定</Label>
+            {settings.length === 0 ? (
+              <p className="text-sm text-muted-foreground">项目暂无设定</p>
+            ) : (
+              <ScrollArea className="h-40 rounded-md border p-2">
+                {settings.map((setting) => (
+                  <label key={setting.id} className="flex items-center gap-2 py-1 text-sm">
+                    <Checkbox
+                      checked={selectedIds.includes(setting.id)}
+                      onCheckedChange={(checked) => toggleSetting(setting.id, checked === true)}
+                    />
+                    {setting.name}
+                  </label>
+                ))}
+              </ScrollArea>
+            )}
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="review-copy-export-password">密码（可选）</Label>
+            <Input
+              id="review-copy-export-password"
+              type="password"
+              placeholder="留空则不加密"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+            />
+          </div>
+        </div>
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)} disabled={isExporting}>
+            取消
+          </Button>
+          <Button onClick={handleExport} disabled={isExporting}>
+            {isExporting ? '导出中...' : '导出'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
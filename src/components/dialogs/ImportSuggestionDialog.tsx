@@ -0,0 +1,121 @@
+// 通过文件关联打开项目备份时的导入确认对话框
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from '@/components/ui/alert-dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { useProjectStore } from '@/stores/project-store'
+import { importProjectFromPath } from '@/lib/import-export'
+import { getErrorMessage, handleAppError } from '@/lib/errors'
+import { toast } from 'sonner'
+import type { ImportSuggestion } from '@/types'
+
+interface ImportSuggestionDialogProps {
+  onNavigate: (path: string) => void
+}
+
+export function ImportSuggestionDialog({ onNavigate }: ImportSuggestionDialogProps) {
+  const { loadProjects } = useProjectStore()
+  const [suggestion, setSuggestion] = useState<ImportSuggestion | null>(null)
+  const [isImporting, setIsImporting] = useState(false)
+  const [password, setPassword] = useState('')
+
+  // 后端一次只发出一个待确认的导入，其余在后端排队
+  useEffect(() => {
+    if (!window.__TAURI_INTERNALS__) return
+    const unlisten = listen<ImportSuggestion>('import:suggestion', (event) => {
+      setSuggestion(event.payload)
+    })
+    // 启动时打开的文件可能早于监听注册
+    invoke<ImportSuggestion | null>('get_pending_import_suggestion').then((pending) => {
+      if (pending) setSuggestion(pending)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  // 结束当前导入，后端随后发出下一个排队的导入
+  const finish = async () => {
+    setSuggestion(null)
+    setPassword('')
+    await invoke('finish_import_suggestion')
+  }
+
+  const handleConfirm = async () => {
+    if (!suggestion) return
+    if (suggestion.encrypted && !password) {
+      toast.error('请输入审阅副本的密码')
+      return
+    }
+    setIsImporting(true)
+    try {
+      const project = await importProjectFromPath(suggestion.path, undefined, password)
+      loadProjects()
+      toast.success(`项目 "${project.name}" 导入成功`)
+      onNavigate(`/project/${project.id}`)
+      setIsImporting(false)
+      await finish()
+    } catch (error) {
+      handleAppError({
+        error,
+        context: '导入项目',
+        toastMessage: `导入失败: ${getErrorMessage(error)}`,
+      })
+      setIsImporting(false)
+      // 加密的副本保留对话框以便重新输入密码
+      if (!suggestion.encrypted) await finish()
+    }
+  }
+
+  return (
+    <AlertDialog
+      open={suggestion !== null}
+      onOpenChange={(open) => {
+        if (!open && !isImporting) finish()
+      }}
+    >
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>{suggestion?.read_only ? '导入审阅副本?' : '导入项目备份?'}</AlertDialogTitle>
+          <AlertDialogDescription>
+            将从 "{suggestion?.path}" 导入项目 "{suggestion?.project_name}"
+            {suggestion?.exported_at ? `（导出于 ${suggestion.exported_at}）` : ''}，作为新项目添加。
+            {suggestion?.read_only ? '审阅副本导入后为只读，不能编辑或运行。' : ''}
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        {suggestion?.encrypted && (
+          <div className="space-y-2">
+            <Label htmlFor="review-copy-password">密码</Label>
+            <Input
+              id="review-copy-password"
+              type="password"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              disabled={isImporting}
+            />
+          </div>
+        )}
+        <AlertDialogFooter>
+          <AlertDialogCancel disabled={isImporting}>取消</AlertDialogCancel>
+          <AlertDialogAction disabled={isImporting} onClick={(e) => {
+            e.preventDefault()
+            handleConfirm()
+          }}>
+            {isImporting ? '导入中...' : '导入'}
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  )
+}
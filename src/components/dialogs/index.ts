@@ -1,4 +1,6 @@
 // 对话框组件导出
 export { CreateProjectDialog } from './CreateProjectDialog'
 export { CreateWorkflowDialog } from './CreateWorkflowDialog'
+export { ExportReviewCopyDialog } from './ExportReviewCopyDialog'
+export { ImportSuggestionDialog } from './ImportSuggestionDialog'
 
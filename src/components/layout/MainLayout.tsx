@@ -2,7 +2,9 @@ import { useState, useCallback, useEffect, useMemo, useRef } from 'react'
 import { Sidebar } from './Sidebar'
 import { Toaster } from '@/components/ui/sonner'
 import { toast } from 'sonner'
+import { listen } from '@tauri-apps/api/event'
 import { ShortcutsDialog } from '@/components/help/ShortcutsDialog'
+import { ImportSuggestionDialog } from '@/components/dialogs'
 import { useHotkey } from '@/lib/hooks'
 
 // 页面组件
@@ -17,6 +19,17 @@ import { ExecutionHistoryPage } from '@/pages/ExecutionHistoryPage'
 import { EditWorkflowPage } from '@/pages/EditWorkflowPage'
 import { EditProjectPage } from '@/pages/EditProjectPage'
 import { getWorkflow } from '@/lib/db'
+import { ensureNetworkAllowed } from '@/lib/network'
+import { startExecutionQueueWorker } from '@/stores/execution-store'
+import {
+  cancelBackgroundJob,
+  formatJobProgress,
+  getBackgroundJobs,
+  isJobActive,
+  jobLabel,
+} from '@/lib/background-jobs'
+import { getErrorMessage } from '@/lib/errors'
+import type { BackgroundJob, ProviderDegraded } from '@/types'
 
 export function MainLayout() {
   const [currentPath, setCurrentPath] = useState('/')
@@ -72,6 +85,8 @@ export function MainLayout() {
     if (!window.__TAURI_INTERNALS__) return
     const timer = setTimeout(async () => {
       try {
+        // 离线模式下抛出错误，与检查失败一样静默跳过
+        await ensureNetworkAllowed('updater')
         const { check } = await import('@tauri-apps/plugin-updater')
         const update = await check()
         if (update) {
@@ -91,6 +106,76 @@ export function MainLayout() {
     return () => clearTimeout(timer)
   }, [])
 
+  // 执行队列：引擎空闲时运行排队的执行（本地 HTTP API、命令行等加入）
+  useEffect(() => {
+    if (!window.__TAURI_INTERNALS__) return
+    return startExecutionQueueWorker()
+  }, [])
+
+  // 提供商最近请求错误率过高时提醒（后端只在转为降级时发出一次）
+  useEffect(() => {
+    if (!window.__TAURI_INTERNALS__) return
+    const unlisten = listen<ProviderDegraded>('provider:degraded', (event) => {
+      const { provider_id, error_rate, recent_requests } = event.payload
+      toast.warning(`AI 提供商 ${provider_id} 当前不稳定`, {
+        description: `最近 ${recent_requests} 次请求中 ${Math.round(error_rate * 100)}% 失败`,
+        action: {
+          label: '查看设置',
+          onClick: () => setCurrentPath('/settings'),
+        },
+        duration: 10000,
+      })
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  // 启动时的后台数据回填任务：每个任务一个进度提示，结束后转为成功或失败提示
+  useEffect(() => {
+    if (!window.__TAURI_INTERNALS__) return
+    const shown = new Set<string>()
+    const showJob = (job: BackgroundJob) => {
+      const id = `background-job:${job.id}`
+      const title = jobLabel(job.kind)
+      if (isJobActive(job)) {
+        shown.add(job.id)
+        toast.loading(title, {
+          id,
+          description: `数据升级中：${formatJobProgress(job)}`,
+          duration: Infinity,
+          action: job.cancellable
+            ? {
+                label: '取消',
+                onClick: () => {
+                  cancelBackgroundJob(job.id).catch((error) => toast.error(getErrorMessage(error)))
+                },
+              }
+            : undefined,
+        })
+        return
+      }
+      // 上次启动已结束的任务不再提示
+      if (!shown.has(job.id)) return
+      shown.delete(job.id)
+      if (job.status === 'completed') {
+        toast.success(`${title}已完成`, { id, description: formatJobProgress(job), duration: 4000 })
+      } else if (job.status === 'cancelled') {
+        toast.info(`${title}已取消`, { id, description: '已处理的数据保留，下次启动不再继续', duration: 4000 })
+      } else {
+        toast.error(`${title}失败`, { id, description: job.error ?? undefined, duration: 10000 })
+      }
+    }
+
+    getBackgroundJobs()
+      .then((jobs) => jobs.forEach(showJob))
+      .catch((error) => console.error('读取后台任务失败:', error))
+    const unlisten = listen<BackgroundJob>('job:progress', (event) => showJob(event.payload))
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
   const navigate = useCallback((path: string) => {
     setCurrentPath(path)
   }, [])
@@ -248,6 +333,9 @@ export function MainLayout() {
         onOpenChange={setShowShortcuts}
         currentPath={currentPath}
       />
+
+      {/* 双击关联的项目备份文件时的导入确认 */}
+      <ImportSuggestionDialog onNavigate={navigate} />
     </div>
   )
 }
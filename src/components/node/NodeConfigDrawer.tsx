@@ -1,7 +1,7 @@
 // 节点配置抽屉组件
 
-import { useEffect, useState, useCallback } from 'react'
-import { Save, HelpCircle, AlertCircle, Plus, Trash2 } from 'lucide-react'
+import { useEffect, useState, useCallback, useMemo } from 'react'
+import { Save, HelpCircle, AlertCircle, Plus, Trash2, History } from 'lucide-react'
 import { Button } from '@/components/ui/button'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
@@ -31,7 +31,7 @@ import { LoopStartConfigForm } from './configs/LoopStartConfig'
 import { ParallelConfigForm } from './configs/ParallelConfig'
 import { ConditionIfConfigForm } from './configs/ConditionIfConfig'
 import { toast } from 'sonner'
-import { useHotkeys, HOTKEY_PRESETS } from '@/lib/hooks'
+import { useHotkeys, useRecoveryDraft, HOTKEY_PRESETS } from '@/lib/hooks'
 import * as db from '@/lib/db'
 import { handleAppError } from '@/lib/errors'
 import type { 
@@ -118,6 +118,28 @@ export function NodeConfigDrawer({
     }
   }, [node])
 
+  // 未保存的修改防抖写入恢复草稿，webview 崩溃后重新打开节点时可恢复
+  const editingState = useMemo(() => ({ name, config }), [name, config])
+  const isDirty = node !== null && open && (
+    name !== node.name || JSON.stringify(config) !== JSON.stringify(node.config)
+  )
+  const {
+    draft: recoveryDraft,
+    savedAt: recoverySavedAt,
+    clear: clearRecoveryDraft,
+  } = useRecoveryDraft<{ name: string; config: NodeConfig }>(
+    node ? `node-config:${node.id}` : null,
+    editingState,
+    isDirty
+  )
+
+  const handleRecover = () => {
+    if (!recoveryDraft) return
+    setName(recoveryDraft.name)
+    setConfig(recoveryDraft.config)
+    toast.success('已恢复未保存的修改')
+  }
+
   // 保存节点配置
   const handleSave = useCallback(async () => {
     if (!node) return
@@ -135,6 +157,7 @@ export function NodeConfigDrawer({
         config,
         updated_at: new Date().toISOString(),
       })
+      await clearRecoveryDraft()
 
       toast.success('节点配置已保存')
       onClose()
@@ -147,7 +170,7 @@ export function NodeConfigDrawer({
     } finally {
       setIsSaving(false)
     }
-  }, [node, name, config, onSave, onClose])
+  }, [node, name, config, onSave, onClose, clearRecoveryDraft])
 
   // 快捷键: Ctrl+S 保存, Escape 关闭
   useHotkeys([
@@ -554,6 +577,31 @@ export function NodeConfigDrawer({
 
         <ScrollArea className="flex-1 px-6 py-6">
           <div className="space-y-6 pb-20">
+            {/* 恢复草稿提示 */}
+            {recoveryDraft && !isDirty && (
+              <div className="flex items-center justify-between gap-3 rounded-lg border bg-muted/50 px-3 py-2">
+                <div className="flex items-center gap-2 text-sm">
+                  <History className="h-4 w-4 text-muted-foreground" />
+                  <span>
+                    发现未保存的修改
+                    {recoverySavedAt && (
+                      <span className="text-muted-foreground">
+                        （{new Date(recoverySavedAt).toLocaleString()}）
+                      </span>
+                    )}
+                  </span>
+                </div>
+                <div className="flex gap-1">
+                  <Button size="sm" variant="ghost" onClick={clearRecoveryDraft}>
+                    丢弃
+                  </Button>
+                  <Button size="sm" variant="outline" onClick={handleRecover}>
+                    恢复未保存的修改
+                  </Button>
+                </div>
+              </div>
+            )}
+
             {/* 节点名称 */}
             <div className="space-y-2">
               <Label htmlFor="node-name">节点名称</Label>
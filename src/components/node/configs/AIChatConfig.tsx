@@ -18,6 +18,7 @@ import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent } from '@/components/ui/card'
 import { getAvailableModels, getModelConfig, type ModelConfig } from '@/lib/ai'
+import { SAMPLING_LIMITS } from '@/lib/ai/sampling'
 import { useSettingsStore } from '@/stores/settings-store'
 import type { AIChatConfig as AIChatConfigType, GlobalConfig, AIProvider, SettingCategory, WorkflowNode } from '@/types'
 import { cn } from '@/lib/utils'
@@ -150,6 +151,8 @@ export function AIChatConfigForm({ config, globalConfig, projectId, nodes = [],
   // 获取可用模型列表
   const availableModels = globalConfig ? getAvailableModels(globalConfig) : []
   const currentModelConfig = getModelConfig(currentConfig.model, globalConfig ?? undefined)
+  // 温度上限因提供商而异（Claude 为 1），预设超出时按上限取值
+  const maxTemperature = SAMPLING_LIMITS[currentConfig.provider]?.temperature[1] ?? 2
 
   // Task #8: 节点配置预验证
   const validationWarnings = useMemo(() => {
@@ -339,7 +342,8 @@ export function AIChatConfigForm({ config, globalConfig, projectId, nodes = [],
           <Label className="text-xs text-muted-foreground">参数预设</Label>
           <div className="grid grid-cols-3 gap-2">
             {Object.entries(AI_PRESETS).map(([key, preset]) => {
-              const isActive = currentConfig.temperature === preset.temperature && currentConfig.top_p === preset.top_p
+              const temperature = Math.min(preset.temperature, maxTemperature)
+              const isActive = currentConfig.temperature === temperature && currentConfig.top_p === preset.top_p
               return (
                 <button
                   key={key}
@@ -348,7 +352,7 @@ export function AIChatConfigForm({ config, globalConfig, projectId, nodes = [],
                     "flex flex-col items-center gap-1 rounded-lg border p-2.5 text-xs transition-all hover:bg-accent/50",
                     isActive && "border-primary bg-primary/5 ring-1 ring-primary/20"
                   )}
-                  onClick={() => updateConfig({ temperature: preset.temperature, top_p: preset.top_p })}
+                  onClick={() => updateConfig({ temperature, top_p: preset.top_p })}
                 >
                   <preset.icon className={cn("h-4 w-4", isActive ? "text-primary" : "text-muted-foreground")} />
                   <span className={cn("font-medium", isActive && "text-primary")}>{preset.label}</span>
@@ -563,7 +567,7 @@ export function AIChatConfigForm({ config, globalConfig, projectId, nodes = [],
                   <Slider
                     id="temperature"
                     min={0}
-                    max={2}
+                    max={maxTemperature}
                     step={0.1}
                     value={[currentConfig.temperature ?? 1]}
                     onValueChange={([value]) => updateConfig({ temperature: value })}
@@ -87,6 +87,7 @@ export function getNodeOutputVariables(node: WorkflowNode): OutputVariable[] {
       const variables: OutputVariable[] = [
         // 系统内置的全局变量
         { varName: '用户问题', description: '用户问题', type: 'global' },
+        { varName: 'synopsis', description: '项目梗概', type: 'global' },
       ]
       // 添加用户自定义的全局变量
       if (startConfig?.custom_variables) {
@@ -0,0 +1,43 @@
+/**
+ * background-jobs.ts 后台任务工具测试
+ */
+
+import { describe, it, expect } from 'vitest'
+import { formatJobProgress, isJobActive, jobLabel } from '../background-jobs'
+
+describe('jobLabel - 任务名称', () => {
+  it('已知任务类型应该返回中文名称', () => {
+    expect(jobLabel('backfill_execution_workflow_version')).toBe('回填执行记录的工作流版本')
+  })
+
+  it('未知任务类型应该原样返回', () => {
+    expect(jobLabel('rebuild_index')).toBe('rebuild_index')
+  })
+})
+
+describe('formatJobProgress - 进度文字', () => {
+  it('应该显示已处理数、总数与百分比', () => {
+    expect(formatJobProgress({ progress: 1500, total: 3000 })).toBe('1,500 / 3,000 (50%)')
+  })
+
+  it('百分比不应该超过 100', () => {
+    expect(formatJobProgress({ progress: 12, total: 10 })).toBe('12 / 10 (100%)')
+  })
+
+  it('总数为 0 时只显示已处理数', () => {
+    expect(formatJobProgress({ progress: 0, total: 0 })).toBe('0')
+  })
+})
+
+describe('isJobActive - 任务状态', () => {
+  it('等待与执行中的任务应该视为进行中', () => {
+    expect(isJobActive({ status: 'pending' })).toBe(true)
+    expect(isJobActive({ status: 'running' })).toBe(true)
+  })
+
+  it('结束的任务不应该视为进行中', () => {
+    expect(isJobActive({ status: 'completed' })).toBe(false)
+    expect(isJobActive({ status: 'failed' })).toBe(false)
+    expect(isJobActive({ status: 'cancelled' })).toBe(false)
+  })
+})
@@ -7,6 +7,7 @@ import {
   logError,
   handleAppError,
   handleUnexpectedError,
+  isReadOnlyProjectError,
 } from "../errors"
 
 // Mock sonner toast
@@ -141,6 +142,22 @@ describe("getErrorMessage - 提取错误信息", () => {
   })
 })
 
+// ========== isReadOnlyProjectError 测试 ==========
+
+describe("isReadOnlyProjectError - 识别只读审阅副本错误", () => {
+  it("应该识别后端错误与触发器错误", () => {
+    expect(isReadOnlyProjectError({ kind: "ReadOnlyProject", message: "只读" })).toBe(true)
+    expect(isReadOnlyProjectError("error returned from database: (code: 1811) ReadOnlyProject")).toBe(true)
+    expect(isReadOnlyProjectError(new Error("ReadOnlyProject"))).toBe(true)
+    expect(isReadOnlyProjectError({ kind: "Other", message: "失败" })).toBe(false)
+    expect(isReadOnlyProjectError(null)).toBe(false)
+  })
+
+  it("getErrorMessage 应该返回友好提示", () => {
+    expect(getErrorMessage("(code: 1811) ReadOnlyProject")).toBe("项目为只读审阅副本, 请先转换为可编辑项目")
+  })
+})
+
 // ========== logError 测试 ==========
 
 describe("logError - 记录错误日志", () => {
@@ -17,6 +17,11 @@ vi.mock('@tauri-apps/plugin-fs', () => ({
   writeTextFile: vi.fn(),
 }))
 
+// Mock Tauri 命令调用
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: vi.fn(),
+}))
+
 // Mock errors
 vi.mock('@/lib/errors', () => ({
   logError: vi.fn(),
@@ -29,7 +34,10 @@ import {
   exportSettingsToFile,
   importSettingsFromFile,
   exportProjectToFile,
+  exportProjectPartialToFile,
+  exportReviewCopyToFile,
   importProjectFromFile,
+  importProjectFromPath,
   validateExportedWorkflow,
   validateExportedSettings,
   validateExportedProject,
@@ -38,6 +46,7 @@ import {
 // 导入 mock 的模块以便在测试中控制
 import { open, save } from '@tauri-apps/plugin-dialog'
 import { readTextFile, writeTextFile } from '@tauri-apps/plugin-fs'
+import { invoke } from '@tauri-apps/api/core'
 import * as db from '@/lib/db'
 import { logError } from '@/lib/errors'
 
@@ -646,7 +655,7 @@ describe('exportProjectToFile - 导出项目备份到文件', () => {
 
   it('成功导出时应该返回 true', async () => {
     vi.mocked(db.exportProject).mockResolvedValueOnce(validExportedProject)
-    vi.mocked(save).mockResolvedValueOnce('/path/to/project.json')
+    vi.mocked(save).mockResolvedValueOnce('/path/to/project.chbak')
     vi.mocked(writeTextFile).mockResolvedValueOnce(undefined)
 
     const result = await exportProjectToFile('project-id')
@@ -654,11 +663,11 @@ describe('exportProjectToFile - 导出项目备份到文件', () => {
     expect(result).toBe(true)
     expect(save).toHaveBeenCalledWith({
       title: '导出项目备份',
-      defaultPath: `${validExportedProject.project.name}_备份.json`,
+      defaultPath: `${validExportedProject.project.name}_备份.chbak`,
       filters: expect.any(Array),
     })
     expect(writeTextFile).toHaveBeenCalledWith(
-      '/path/to/project.json',
+      '/path/to/project.chbak',
       JSON.stringify(validExportedProject, null, 2)
     )
   })
@@ -736,6 +745,148 @@ describe('importProjectFromFile - 从备份文件恢复项目', () => {
   })
 })
 
+// ========== importProjectFromPath 测试 ==========
+
+describe('importProjectFromPath - 从指定路径恢复项目', () => {
+  beforeEach(() => {
+    vi.clearAllMocks()
+  })
+
+  it('应该读取指定路径而不弹出选择对话框', async () => {
+    vi.mocked(readTextFile).mockResolvedValueOnce(JSON.stringify(validExportedProject))
+    vi.mocked(db.importProject).mockResolvedValueOnce({ id: 'new-id' } as any)
+
+    const project = await importProjectFromPath('/path/to/雨夜_备份.chbak')
+
+    expect(project).toEqual({ id: 'new-id' })
+    expect(open).not.toHaveBeenCalled()
+    expect(readTextFile).toHaveBeenCalledWith('/path/to/雨夜_备份.chbak')
+  })
+
+  it('提供目标项目时应该合并导入到该项目', async () => {
+    vi.mocked(readTextFile).mockResolvedValueOnce(JSON.stringify(validExportedProject))
+    vi.mocked(db.importProject).mockResolvedValueOnce({ id: 'target-id' } as any)
+
+    const project = await importProjectFromPath('/path/to/部分导出.chbak', undefined, undefined, 'target-id')
+
+    expect(project).toEqual({ id: 'target-id' })
+    expect(db.importProject).toHaveBeenCalledWith(validExportedProject, undefined, 'target-id')
+  })
+
+  it('文件格式无效时应该抛出错误', async () => {
+    vi.mocked(readTextFile).mockResolvedValueOnce(JSON.stringify({ version: '1.0.0' }))
+
+    await expect(importProjectFromPath('/path/to/bad.chbak')).rejects.toThrow('无效的项目备份文件格式')
+    expect(db.importProject).not.toHaveBeenCalled()
+  })
+
+  it('审阅副本应该交由后端导入为只读项目', async () => {
+    vi.mocked(readTextFile).mockResolvedValueOnce(
+      JSON.stringify({ format: 'chouann-review-copy', read_only: true, encrypted: {} })
+    )
+    vi.mocked(invoke).mockResolvedValueOnce({ id: 'review-id', read_only: true })
+
+    const project = await importProjectFromPath('/path/to/审阅副本.chbak', undefined, '试读')
+
+    expect(project).toEqual({ id: 'review-id', read_only: true })
+    expect(invoke).toHaveBeenCalledWith('import_review_copy', {
+      path: '/path/to/审阅副本.chbak',
+      password: '试读',
+    })
+    expect(db.importProject).not.toHaveBeenCalled()
+  })
+})
+
+// ========== exportProjectPartialToFile 测试 ==========
+
+describe('exportProjectPartialToFile - 部分导出项目', () => {
+  const selection = {
+    workflow_ids: ['w1'],
+    setting_categories: ['worldview' as const],
+    include_outlines: true,
+    include_chapters: false,
+    include_prompts: false,
+  }
+  const partial: ExportedProject = {
+    ...validExportedProject,
+    settings: [],
+    setting_prompts: [],
+    chapters: [],
+    manifest: {
+      partial: true,
+      selection,
+      warnings: ['工作流「工作流1」的节点「撰写」引用了未导出的角色设定「林雪」'],
+    },
+  }
+
+  beforeEach(() => {
+    vi.clearAllMocks()
+  })
+
+  it('项目不存在时应该抛出错误', async () => {
+    vi.mocked(db.exportProjectPartial).mockResolvedValueOnce(null)
+
+    await expect(exportProjectPartialToFile('non-existent-id', selection)).rejects.toThrow('项目不存在')
+    expect(logError).toHaveBeenCalled()
+  })
+
+  it('用户取消保存对话框时应该返回 null', async () => {
+    vi.mocked(db.exportProjectPartial).mockResolvedValueOnce(partial)
+    vi.mocked(save).mockResolvedValueOnce(null)
+
+    expect(await exportProjectPartialToFile('project-id', selection)).toBeNull()
+    expect(writeTextFile).not.toHaveBeenCalled()
+  })
+
+  it('成功导出时应该写入文件并返回清单中的警告', async () => {
+    vi.mocked(db.exportProjectPartial).mockResolvedValueOnce(partial)
+    vi.mocked(save).mockResolvedValueOnce('/path/to/partial.chbak')
+    vi.mocked(writeTextFile).mockResolvedValueOnce(undefined)
+
+    const result = await exportProjectPartialToFile('project-id', selection)
+
+    expect(result).toEqual({ warnings: partial.manifest!.warnings })
+    expect(db.exportProjectPartial).toHaveBeenCalledWith('project-id', selection)
+    expect(writeTextFile).toHaveBeenCalledWith('/path/to/partial.chbak', JSON.stringify(partial, null, 2))
+  })
+})
+
+// ========== exportReviewCopyToFile 测试 ==========
+
+describe('exportReviewCopyToFile - 导出审阅副本', () => {
+  beforeEach(() => {
+    vi.clearAllMocks()
+  })
+
+  it('用户取消保存对话框时应该返回 false', async () => {
+    vi.mocked(save).mockResolvedValueOnce(null)
+
+    const result = await exportReviewCopyToFile('project-id', '雨夜')
+
+    expect(result).toBe(false)
+    expect(invoke).not.toHaveBeenCalled()
+  })
+
+  it('应该把密码与选定设定传给后端', async () => {
+    vi.mocked(save).mockResolvedValueOnce('/path/to/雨夜_审阅副本.chbak')
+    vi.mocked(invoke).mockResolvedValueOnce(undefined)
+
+    const result = await exportReviewCopyToFile('project-id', '雨夜', {
+      password: '试读',
+      settingIds: ['s1'],
+    })
+
+    expect(result).toBe(true)
+    expect(save).toHaveBeenCalledWith(expect.objectContaining({ defaultPath: '雨夜_审阅副本.chbak' }))
+    expect(invoke).toHaveBeenCalledWith('export_review_copy', {
+      projectId: 'project-id',
+      path: '/path/to/雨夜_审阅副本.chbak',
+      password: '试读',
+      settingIds: ['s1'],
+    })
+  })
+})
+
 // ========== 边界情况测试 ==========
 
 describe('边界情况测试', () => {
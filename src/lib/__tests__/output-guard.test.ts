@@ -0,0 +1,57 @@
+import { describe, it, expect } from 'vitest'
+import { buildGuardNudge, chapterLengthChecks, cjkRatio, evaluateOutputGuard } from '../output-guard'
+import type { OutputGuardConfig } from '@/types'
+
+describe('cjkRatio', () => {
+  it('should ignore whitespace and punctuation', () => {
+    expect(cjkRatio('雨夜，林雪推开门。')).toBe(1)
+    expect(cjkRatio('林雪 said hi')).toBeCloseTo(2 / 8)
+    expect(cjkRatio('……')).toBe(0)
+  })
+})
+
+describe('evaluateOutputGuard', () => {
+  const config: OutputGuardConfig = {
+    checks: [
+      { type: 'min_cjk_ratio', value: 0.5 },
+      { type: 'forbid_prefix', value: '{' },
+      { type: 'min_length', value: 5 },
+    ],
+    action: 'retry',
+  }
+
+  it('should pass valid output', () => {
+    expect(evaluateOutputGuard(config.checks, '雨夜，林雪推开了门。')).toEqual([])
+  })
+
+  it('should report every failed check', () => {
+    expect(evaluateOutputGuard(config.checks, '  {"a"}')).toEqual([
+      '中文字符比例 0% 低于 50%',
+      '输出以 "{" 开头',
+    ])
+    expect(evaluateOutputGuard(config.checks, '雨夜')).toEqual(['输出 2 字，少于 5 字'])
+  })
+
+  it('should build a corrective nudge', () => {
+    const nudge = buildGuardNudge(config, ['输出以 "{" 开头'])
+    expect(nudge).toContain('输出以 "{" 开头')
+    expect(nudge).toContain('必须使用中文回答')
+    expect(nudge).toContain('正文不少于 5 字')
+    expect(buildGuardNudge({ ...config, nudge: '请用中文' }, [])).toBe('请用中文')
+  })
+})
+
+describe('chapterLengthChecks', () => {
+  it('should check the configured bounds only', () => {
+    const checks = chapterLengthChecks({ min_chapter_chars: 3, max_chapter_chars: 6, enforcement: 'retry' })
+    expect(checks).toEqual([
+      { type: 'min_length', value: 3 },
+      { type: 'max_length', value: 6 },
+    ])
+    expect(evaluateOutputGuard(checks, '林雪推开了门，外面在下雨')).toEqual(['输出 12 字，超过 6 字'])
+    expect(buildGuardNudge({ checks, action: 'retry' }, [])).toContain('正文不超过 6 字（超出时压缩）')
+    expect(chapterLengthChecks({ min_chapter_chars: null, max_chapter_chars: 6, enforcement: 'warn' })).toEqual([
+      { type: 'max_length', value: 6 },
+    ])
+  })
+})
@@ -0,0 +1,48 @@
+/**
+ * partial-export.ts 部分导出工具测试
+ */
+
+import { describe, it, expect } from 'vitest'
+import { remapSettingIds, selectionWarnings } from '../partial-export'
+
+const settings = [
+  { id: 's1', name: '林雪', category: 'character' as const },
+  { id: 's2', name: '雾城', category: 'worldview' as const },
+]
+
+const workflows = [
+  {
+    workflow: { name: '正文生成' },
+    nodes: [
+      { name: '开始', config: {} },
+      { name: '撰写', config: { setting_ids: ['s1', 's2', 'deleted'] } },
+    ],
+  },
+]
+
+describe('selectionWarnings - 引用一致性检查', () => {
+  it('引用未导出分类的设定时应该给出警告', () => {
+    expect(selectionWarnings(workflows, settings, ['worldview'])).toEqual([
+      '工作流「正文生成」的节点「撰写」引用了未导出的角色设定「林雪」',
+    ])
+  })
+
+  it('引用的设定全部导出时不应该给出警告', () => {
+    expect(selectionWarnings(workflows, settings, ['character', 'worldview'])).toEqual([])
+  })
+})
+
+describe('remapSettingIds - 设定 ID 重映射', () => {
+  it('应该替换已导入的设定 ID 并保留其他 ID', () => {
+    const config = { prompt: '写作', setting_ids: ['s1', 's2'] }
+    expect(remapSettingIds(config, new Map([['s1', 'n1']]))).toEqual({
+      prompt: '写作',
+      setting_ids: ['n1', 's2'],
+    })
+  })
+
+  it('没有设定引用的配置应该原样返回', () => {
+    const config = { prompt: '写作' }
+    expect(remapSettingIds(config, new Map([['s1', 'n1']]))).toBe(config)
+  })
+})
@@ -0,0 +1,69 @@
+import { describe, it, expect } from 'vitest'
+import {
+  appliesToNode,
+  applyPostProcessing,
+  parsePostProcessingConfig,
+} from '../post-processing'
+import type { PostProcessingConfig } from '@/types'
+
+const allRules: PostProcessingConfig = {
+  rules: [
+    { type: 'strip_meta_prefix' },
+    { type: 'strip_code_fences' },
+    { type: 'normalize_ellipsis' },
+    { type: 'normalize_quotes' },
+    { type: 'collapse_blank_lines' },
+  ],
+}
+
+describe('applyPostProcessing', () => {
+  it('should strip meta phrases and normalize text', () => {
+    const text = '好的，以下是润色后的文本：\n```markdown\n"雨下得很急..."\n\n\n\n\n林雪推开门。。。\n```'
+    const result = applyPostProcessing(allRules, text)
+    expect(result.output).toBe('“雨下得很急……”\n\n\n林雪推开门……')
+    expect(result.applied.map(a => a.rule)).toEqual([
+      'strip_meta_prefix',
+      'strip_code_fences',
+      'normalize_ellipsis',
+      'normalize_quotes',
+      'collapse_blank_lines',
+    ])
+    expect(result.applied[0].chars_removed).toBe(14)
+    expect(result.applied[2].chars_removed).toBe(2)
+  })
+
+  it('should keep dialogue that is not a meta phrase', () => {
+    const text = '当然，他说：“走吧。”'
+    const result = applyPostProcessing(allRules, text)
+    expect(result.output).toBe(text)
+    expect(result.applied).toEqual([])
+  })
+
+  it('should use custom patterns', () => {
+    const config: PostProcessingConfig = {
+      rules: [{ type: 'strip_meta_prefix', patterns: ['Sure[^\\n]*\\n'] }],
+    }
+    expect(applyPostProcessing(config, 'Sure, here it is:\n雨夜').output).toBe('雨夜')
+  })
+})
+
+describe('appliesToNode', () => {
+  it('should follow the configured scope', () => {
+    expect(appliesToNode(allRules, 'ai_chat')).toBe(true)
+    expect(appliesToNode(allRules, 'output')).toBe(true)
+    expect(appliesToNode(allRules, 'text_concat')).toBe(false)
+    expect(appliesToNode({ ...allRules, scope: 'final_output' }, 'ai_chat')).toBe(false)
+    expect(appliesToNode({ ...allRules, scope: 'node_outputs' }, 'output')).toBe(false)
+    expect(appliesToNode({ rules: [] }, 'ai_chat')).toBe(false)
+    expect(appliesToNode(null, 'ai_chat')).toBe(false)
+  })
+})
+
+describe('parsePostProcessingConfig', () => {
+  it('should parse stored JSON and ignore invalid values', () => {
+    expect(parsePostProcessingConfig(JSON.stringify(allRules))).toEqual(allRules)
+    expect(parsePostProcessingConfig(allRules)).toEqual(allRules)
+    expect(parsePostProcessingConfig('not json')).toBeNull()
+    expect(parsePostProcessingConfig(null)).toBeNull()
+  })
+})
@@ -0,0 +1,57 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest'
+import { invoke } from '@tauri-apps/api/core'
+import { classifyProviderError, trackProviderRequest } from '../provider-health'
+
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: vi.fn(),
+}))
+
+describe('classifyProviderError', () => {
+  it('should prefer the HTTP status code', () => {
+    expect(classifyProviderError(Object.assign(new Error('boom'), { statusCode: 401 }))).toBe('auth')
+    expect(classifyProviderError(Object.assign(new Error('boom'), { statusCode: 429 }))).toBe('rate_limit')
+    expect(classifyProviderError(Object.assign(new Error('boom'), { statusCode: 529 }))).toBe('server')
+    expect(classifyProviderError(Object.assign(new Error('boom'), { statusCode: 400 }))).toBe('bad_request')
+  })
+
+  it('should fall back to the error message', () => {
+    expect(classifyProviderError(new Error('401 Unauthorized - Invalid API key'))).toBe('auth')
+    expect(classifyProviderError(new Error('429 Rate limit exceeded'))).toBe('rate_limit')
+    expect(classifyProviderError(new Error('The operation was aborted due to timeout'))).toBe('timeout')
+    expect(classifyProviderError(new Error('503 Service Unavailable'))).toBe('server')
+    expect(classifyProviderError(new Error('fetch failed: ECONNREFUSED'))).toBe('network')
+    expect(classifyProviderError(new Error('404 Not Found - model does not exist'))).toBe('bad_request')
+    expect(classifyProviderError('Something unexpected happened')).toBe('other')
+  })
+})
+
+describe('trackProviderRequest', () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset()
+    vi.mocked(invoke).mockResolvedValue(undefined)
+    window.__TAURI_INTERNALS__ = {}
+  })
+
+  afterEach(() => {
+    delete window.__TAURI_INTERNALS__
+  })
+
+  it('should report successes and failures', () => {
+    trackProviderRequest('openai').succeed(120)
+    trackProviderRequest('claude').fail(new Error('429 Rate limit exceeded'))
+    expect(invoke).toHaveBeenCalledWith('record_provider_request', expect.objectContaining({
+      providerId: 'openai', errorClass: null, tokens: 120,
+    }))
+    expect(invoke).toHaveBeenCalledWith('record_provider_request', expect.objectContaining({
+      providerId: 'claude', errorClass: 'rate_limit',
+    }))
+  })
+
+  it('should skip requests cancelled by the user', () => {
+    const controller = new AbortController()
+    const tracker = trackProviderRequest('gemini', controller.signal)
+    controller.abort()
+    tracker.fail(new Error('This operation was aborted'))
+    expect(invoke).not.toHaveBeenCalled()
+  })
+})
@@ -0,0 +1,42 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest'
+import { invoke } from '@tauri-apps/api/core'
+import { getUiPrefs, setUiPref } from '../ui-prefs'
+
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: vi.fn(),
+}))
+
+describe('ui-prefs', () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset()
+    vi.mocked(invoke).mockResolvedValue(undefined)
+    localStorage.clear()
+  })
+
+  afterEach(() => {
+    delete window.__TAURI_INTERNALS__
+  })
+
+  it('should save through the backend in Tauri', async () => {
+    window.__TAURI_INTERNALS__ = {}
+    setUiPref('workflow.output_panel_collapsed', true)
+    expect(invoke).toHaveBeenCalledWith('set_ui_pref', {
+      key: 'workflow.output_panel_collapsed',
+      valueJson: 'true',
+    })
+
+    vi.mocked(invoke).mockResolvedValueOnce({ 'workflow.output_panel_collapsed': true })
+    await expect(getUiPrefs('workflow.')).resolves.toEqual({ 'workflow.output_panel_collapsed': true })
+    expect(invoke).toHaveBeenCalledWith('get_ui_prefs', { prefix: 'workflow.' })
+    expect(localStorage.length).toBe(0)
+  })
+
+  it('should fall back to localStorage outside Tauri', async () => {
+    setUiPref('editor.font_size', 16)
+    setUiPref('workflow.tabs', ['outline'])
+    localStorage.setItem('workflow.broken', '{')
+
+    await expect(getUiPrefs('workflow.')).resolves.toEqual({ 'workflow.tabs': ['outline'] })
+    expect(invoke).not.toHaveBeenCalled()
+  })
+})
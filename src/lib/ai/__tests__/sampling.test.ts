@@ -0,0 +1,43 @@
+// 节点采样参数解析测试
+
+import { describe, it, expect } from "vitest"
+import { resolveSampling } from "../sampling"
+import type { AIChatConfig } from "@/types"
+
+const baseConfig: AIChatConfig = {
+  provider: "openai",
+  model: "gpt-5",
+  system_prompt: "",
+  user_prompt: "写一段开头",
+  temperature: 0.7,
+  top_p: 0.9,
+  enable_history: false,
+  history_count: 0,
+}
+
+describe("resolveSampling", () => {
+  it("sampling 覆盖顶层参数，未覆盖的沿用顶层", () => {
+    const sampling = resolveSampling({
+      ...baseConfig,
+      sampling: { temperature: 1.2, max_tokens: 2000, frequency_penalty: 0.5 },
+    })
+    expect(sampling).toEqual({
+      temperature: 1.2,
+      top_p: 0.9,
+      max_tokens: 2000,
+      presence_penalty: undefined,
+      frequency_penalty: 0.5,
+    })
+  })
+
+  it("Claude 不发送 presence / frequency penalty", () => {
+    const sampling = resolveSampling({
+      ...baseConfig,
+      provider: "claude",
+      sampling: { presence_penalty: 0.3, frequency_penalty: 0.5 },
+    })
+    expect(sampling.presence_penalty).toBeUndefined()
+    expect(sampling.frequency_penalty).toBeUndefined()
+    expect(sampling.temperature).toBe(0.7)
+  })
+})
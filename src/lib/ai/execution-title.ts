@@ -0,0 +1,45 @@
+// AI 执行标题 - 执行完成后用一句话概括输出内容
+
+import type { GlobalConfig } from '@/types'
+import { chat } from './index'
+
+// 标题最大长度（与 Rust 端自动标题一致）
+const TITLE_MAX_LENGTH = 30
+
+// 参与概括的输出长度上限，避免长章节产生过多 token
+const OUTPUT_SAMPLE_LENGTH = 2000
+
+/**
+ * 使用设定助手配置的模型生成执行标题
+ * 未启用 AI 标题或未配置可用模型时返回 null
+ */
+export async function generateExecutionTitle(
+  output: string,
+  globalConfig: GlobalConfig
+): Promise<string | null> {
+  if (!globalConfig.ai_execution_title || !output.trim()) return null
+
+  const config = globalConfig.setting_assistant
+  if (!config) return null
+  const providerConfig = globalConfig.ai_providers[config.provider]
+  if (!providerConfig?.enabled || !providerConfig.api_key) return null
+
+  const response = await chat(
+    {
+      provider: config.provider,
+      model: config.model,
+      messages: [
+        {
+          role: 'system',
+          content: `请用一句话为下面的小说内容拟一个标题，不超过 ${TITLE_MAX_LENGTH} 个字，只输出标题本身，不要引号或解释。`,
+        },
+        { role: 'user', content: output.slice(0, OUTPUT_SAMPLE_LENGTH) },
+      ],
+      temperature: 0.3,
+    },
+    globalConfig
+  )
+
+  const title = response.content.split('\n')[0].trim().replace(/^["“《]|["”》]$/g, '')
+  return title ? title.slice(0, TITLE_MAX_LENGTH) : null
+}
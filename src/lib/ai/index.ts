@@ -6,6 +6,8 @@ import { createGoogleGenerativeAI } from '@ai-sdk/google'
 import { createAnthropic } from '@ai-sdk/anthropic'
 import type { AIRequestOptions, AIResponse, StreamChunk, Message, ThinkingConfig } from './types'
 import type { AIProvider, AIProviderConfig, GlobalConfig } from '@/types'
+import { ensureNetworkAllowed, OfflineModeError } from '@/lib/network'
+import { trackProviderRequest } from '@/lib/provider-health'
 
 // 导出类型
 export type { AIRequestOptions, AIResponse, StreamChunk, Message, ThinkingConfig }
@@ -159,6 +161,7 @@ export const MODELS = BUILTIN_MODELS
 
 // 模型定价（每百万 Token，单位: USD）
 // 注意: 价格为估算值，实际价格请参考各提供商官网
+// 修改时同步更新后端 execution::MODEL_PRICING（get_execution_cost_report 使用）
 const MODEL_PRICING: Record<string, { input: number; output: number }> = {
   // OpenAI
   'gpt-5.1': { input: 5, output: 15 },
@@ -308,6 +311,7 @@ async function createModel(
   if (!providerConfig?.enabled || !providerConfig.api_key) {
     throw new Error(`提供商 ${provider} 未配置或未启用`)
   }
+  await ensureNetworkAllowed('ai', globalConfig)
 
   const customFetch = await getCustomFetch()
 
@@ -425,26 +429,32 @@ export async function chat(
   // 构建提供商特定选项
   const providerOptions = buildProviderOptions(options.thinkingConfig, modelConfig)
 
+  const tracker = trackProviderRequest(options.provider, options.signal)
   const result = await generateText({
     model,
     messages: convertMessages(options.messages),
     temperature: modelConfig?.supportsTemperature ? options.temperature : undefined,
     maxOutputTokens: modelConfig?.supportsMaxTokens ? options.maxTokens : undefined,
     topP: modelConfig?.supportsTopP ? options.topP : undefined,
+    presencePenalty: options.presencePenalty,
+    frequencyPenalty: options.frequencyPenalty,
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     providerOptions: providerOptions as any,
+  }).catch((error: unknown) => {
+    tracker.fail(error)
+    throw error
   })
 
-  return {
-    content: result.text,
-    usage: result.usage
-      ? {
-          promptTokens: (await result.usage).inputTokens ?? 0,
-          completionTokens: (await result.usage).outputTokens ?? 0,
-          totalTokens: (await result.usage).totalTokens ?? 0,
-        }
-      : undefined,
-  }
+  const usage = result.usage
+    ? {
+        promptTokens: (await result.usage).inputTokens ?? 0,
+        completionTokens: (await result.usage).outputTokens ?? 0,
+        totalTokens: (await result.usage).totalTokens ?? 0,
+      }
+    : undefined
+  tracker.succeed(usage?.totalTokens)
+
+  return { content: result.text, usage }
 }
 
 /**
@@ -464,12 +474,15 @@ export async function chatStream(
   // 捕获流式过程中的错误（Vercel AI SDK 默认只 console.error 不抛出）
   let streamError: Error | null = null
 
+  const tracker = trackProviderRequest(options.provider, options.signal)
   const result = streamText({
     model,
     messages: convertMessages(options.messages),
     temperature: modelConfig?.supportsTemperature ? options.temperature : undefined,
     maxOutputTokens: modelConfig?.supportsMaxTokens ? options.maxTokens : undefined,
     topP: modelConfig?.supportsTopP ? options.topP : undefined,
+    presencePenalty: options.presencePenalty,
+    frequencyPenalty: options.frequencyPenalty,
     abortSignal: options.signal,
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     providerOptions: providerOptions as any,
@@ -478,28 +491,32 @@ export async function chatStream(
     },
   })
 
-  // 处理流式响应
-  for await (const textPart of result.textStream) {
-    onChunk({ content: textPart, done: false })
-  }
+  try {
+    // 处理流式响应
+    for await (const textPart of result.textStream) {
+      onChunk({ content: textPart, done: false })
+    }
 
-  // 如果流式过程中发生了错误，抛出而非静默忽略
-  if (streamError) {
-    throw streamError
+    // 如果流式过程中发生了错误，抛出而非静默忽略
+    if (streamError) {
+      throw streamError
+    }
+  } catch (error) {
+    tracker.fail(error)
+    throw error
   }
 
+  const usage = result.usage
+    ? {
+        promptTokens: (await result.usage).inputTokens ?? 0,
+        completionTokens: (await result.usage).outputTokens ?? 0,
+        totalTokens: (await result.usage).totalTokens ?? 0,
+      }
+    : undefined
+  tracker.succeed(usage?.totalTokens)
+
   // 流结束
-  onChunk({
-    content: '',
-    done: true,
-    usage: result.usage
-      ? {
-          promptTokens: (await result.usage).inputTokens ?? 0,
-          completionTokens: (await result.usage).outputTokens ?? 0,
-          totalTokens: (await result.usage).totalTokens ?? 0,
-        }
-      : undefined,
-  })
+  onChunk({ content: '', done: true, usage })
 }
 
 /**
@@ -518,12 +535,15 @@ export async function* chatStreamIterable(
   // 捕获流式过程中的错误（Vercel AI SDK 默认只 console.error 不抛出）
   let streamError: Error | null = null
 
+  const tracker = trackProviderRequest(options.provider, options.signal)
   const result = streamText({
     model,
     messages: convertMessages(options.messages),
     temperature: modelConfig?.supportsTemperature ? options.temperature : undefined,
     maxOutputTokens: modelConfig?.supportsMaxTokens ? options.maxTokens : undefined,
     topP: modelConfig?.supportsTopP ? options.topP : undefined,
+    presencePenalty: options.presencePenalty,
+    frequencyPenalty: options.frequencyPenalty,
     abortSignal: options.signal,
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     providerOptions: providerOptions as any,
@@ -532,26 +552,30 @@ export async function* chatStreamIterable(
     },
   })
 
-  for await (const textPart of result.textStream) {
-    yield { content: textPart, done: false }
-  }
+  try {
+    for await (const textPart of result.textStream) {
+      yield { content: textPart, done: false }
+    }
 
-  // 如果流式过程中发生了错误，抛出而非静默忽略
-  if (streamError) {
-    throw streamError
+    // 如果流式过程中发生了错误，抛出而非静默忽略
+    if (streamError) {
+      throw streamError
+    }
+  } catch (error) {
+    tracker.fail(error)
+    throw error
   }
 
-  yield {
-    content: '',
-    done: true,
-    usage: result.usage
-      ? {
-          promptTokens: (await result.usage).inputTokens ?? 0,
-          completionTokens: (await result.usage).outputTokens ?? 0,
-          totalTokens: (await result.usage).totalTokens ?? 0,
-        }
-      : undefined,
-  }
+  const usage = result.usage
+    ? {
+        promptTokens: (await result.usage).inputTokens ?? 0,
+        completionTokens: (await result.usage).outputTokens ?? 0,
+        totalTokens: (await result.usage).totalTokens ?? 0,
+      }
+    : undefined
+  tracker.succeed(usage?.totalTokens)
+
+  yield { content: '', done: true, usage }
 }
 
 // 每个提供商的默认 API 路径后缀
@@ -584,6 +608,7 @@ const DEFAULT_TEST_MODELS: Record<AIProvider, string> = {
  * 解析 API 错误为友好的中文提示
  */
 function parseConnectionError(error: unknown): string {
+  if (error instanceof OfflineModeError) return error.message
   const message = error instanceof Error ? error.message : String(error)
   const lowerMsg = message.toLowerCase()
 
@@ -628,6 +653,7 @@ export async function testProviderConnection(
   const startTime = Date.now()
 
   try {
+    await ensureNetworkAllowed('ai')
     const customFetch = await getCustomFetch()
 
     // 创建对应提供商的客户端
@@ -0,0 +1,35 @@
+/**
+ * 节点采样参数
+ * 节点配置的 `sampling` 覆盖旧版顶层的 temperature / top_p / max_tokens，并去掉提供商不支持的参数。
+ * 取值范围与 src-tauri/src/commands/sampling.rs 一致，后端 validate_workflow 在运行前校验。
+ */
+
+import type { AIChatConfig, AIProvider, SamplingParams } from '@/types'
+
+export interface SamplingLimits {
+  temperature: [number, number]
+  topP: [number, number]
+  // presence / frequency penalty 的范围，null 表示不支持
+  penalties: [number, number] | null
+}
+
+export const SAMPLING_LIMITS: Record<AIProvider, SamplingLimits> = {
+  openai: { temperature: [0, 2], topP: [0, 1], penalties: [-2, 2] },
+  gemini: { temperature: [0, 2], topP: [0, 1], penalties: [-2, 2] },
+  claude: { temperature: [0, 1], topP: [0, 1], penalties: null },
+}
+
+/**
+ * 节点实际发送的采样参数
+ */
+export function resolveSampling(config: AIChatConfig): SamplingParams {
+  const sampling = config.sampling ?? {}
+  const supportsPenalties = !!SAMPLING_LIMITS[config.provider]?.penalties
+  return {
+    temperature: sampling.temperature ?? config.temperature,
+    top_p: sampling.top_p ?? config.top_p,
+    max_tokens: sampling.max_tokens ?? config.max_tokens,
+    presence_penalty: supportsPenalties ? sampling.presence_penalty : undefined,
+    frequency_penalty: supportsPenalties ? sampling.frequency_penalty : undefined,
+  }
+}
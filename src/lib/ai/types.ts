@@ -44,6 +44,8 @@ export interface AIRequestOptions {
   temperature?: number
   maxTokens?: number
   topP?: number
+  presencePenalty?: number
+  frequencyPenalty?: number
   stream?: boolean
   signal?: AbortSignal
   /**
@@ -0,0 +1,26 @@
+/**
+ * 本地使用统计
+ * 事件只写入本地数据库（record_app_event），未开启统计时后端直接忽略。仅 Tauri 环境记录。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { logError } from '@/lib/errors'
+
+// 前端记录的事件类型，须在后端 EVENT_KINDS 中
+export type AppEventKind = 'execution_started' | 'execution_finished'
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+/**
+ * 记录一条本地事件，失败不影响调用方
+ */
+export async function recordAppEvent(kind: AppEventKind, payload?: Record<string, unknown>): Promise<void> {
+  if (!isTauri()) return
+  try {
+    await invoke('record_app_event', { kind, payload: payload ?? null })
+  } catch (error) {
+    logError({ error, context: '记录使用统计' })
+  }
+}
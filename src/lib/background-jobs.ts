@@ -0,0 +1,47 @@
+/**
+ * 后台数据回填任务
+ * 启动时由后端分批执行，并通过 job:progress 事件上报进度；仅 Tauri 环境存在
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import type { BackgroundJob } from '@/types'
+
+// 任务类型中文标签，与后端 background_job 中的任务类型一致
+const JOB_LABELS: Record<string, string> = {
+  backfill_execution_workflow_version: '回填执行记录的工作流版本',
+}
+
+/**
+ * 任务类型的中文名称，未知类型原样返回
+ */
+export function jobLabel(kind: string): string {
+  return JOB_LABELS[kind] ?? kind
+}
+
+/**
+ * 任务进度文字，如 "1,500 / 3,000 (50%)"；总数为 0 时只显示已处理数
+ */
+export function formatJobProgress(job: Pick<BackgroundJob, 'progress' | 'total'>): string {
+  const progress = job.progress.toLocaleString('en-US')
+  if (job.total <= 0) return progress
+  const percent = Math.min(100, Math.floor((job.progress / job.total) * 100))
+  return `${progress} / ${job.total.toLocaleString('en-US')} (${percent}%)`
+}
+
+/**
+ * 任务是否仍在等待或执行中
+ */
+export function isJobActive(job: Pick<BackgroundJob, 'status'>): boolean {
+  return job.status === 'pending' || job.status === 'running'
+}
+
+export async function getBackgroundJobs(): Promise<BackgroundJob[]> {
+  return invoke<BackgroundJob[]>('get_background_jobs')
+}
+
+/**
+ * 取消任务，已处理的批次保留；返回更新后的任务
+ */
+export async function cancelBackgroundJob(jobId: string): Promise<BackgroundJob> {
+  return invoke<BackgroundJob>('cancel_background_job', { jobId })
+}
@@ -0,0 +1,80 @@
+/**
+ * 项目预算与工作流每日执行上限
+ * 项目设置 AI 费用上限后，已花费达到预算时后端拒绝开始新的执行（{ kind: 'BudgetExceeded' }）；
+ * 工作流当天执行次数达到每日上限时同样拒绝（{ kind: 'DailyLimitExceeded' }）。
+ * 仅 Tauri 环境检查。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import type { BudgetStatus, InvocationStats } from '@/types'
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+/**
+ * 判断错误是否由项目预算用尽引起
+ */
+export function isBudgetExceededError(error: unknown): boolean {
+  return typeof error === 'object' && error !== null
+    && (error as { kind?: unknown }).kind === 'BudgetExceeded'
+}
+
+/**
+ * 开始执行前调用，项目已花费达到预算时抛出后端返回的 BudgetExceeded 错误
+ */
+export async function ensureWithinBudget(projectId: string): Promise<void> {
+  if (!isTauri()) return
+  await invoke('ensure_project_within_budget', { projectId })
+}
+
+/**
+ * 设置项目预算（USD，须大于 0），传 null 清除预算
+ */
+export async function setProjectBudget(projectId: string, budgetUsd: number | null): Promise<void> {
+  await invoke('set_project_budget', { projectId, budgetUsd })
+}
+
+/**
+ * 获取项目已花费的 AI 费用（USD）
+ */
+export async function getProjectSpend(projectId: string): Promise<number> {
+  return invoke<number>('get_project_spend', { projectId })
+}
+
+/**
+ * 获取项目预算使用情况，供预算进度条显示
+ */
+export async function getBudgetStatus(projectId: string): Promise<BudgetStatus> {
+  return invoke<BudgetStatus>('get_budget_status', { projectId })
+}
+
+/**
+ * 判断错误是否由工作流每日执行上限引起
+ */
+export function isDailyLimitExceededError(error: unknown): boolean {
+  return typeof error === 'object' && error !== null
+    && (error as { kind?: unknown }).kind === 'DailyLimitExceeded'
+}
+
+/**
+ * 开始执行前调用，工作流当天执行次数达到上限时抛出后端返回的 DailyLimitExceeded 错误
+ */
+export async function ensureWithinDailyLimit(workflowId: string): Promise<void> {
+  if (!isTauri()) return
+  await invoke('ensure_workflow_within_daily_limit', { workflowId })
+}
+
+/**
+ * 设置工作流每日执行上限（须大于 0），null 表示不限制
+ */
+export async function setWorkflowDailyLimit(workflowId: string, limit: number | null): Promise<void> {
+  await invoke('set_workflow_daily_limit', { workflowId, limit })
+}
+
+/**
+ * 获取工作流当天执行次数与剩余次数
+ */
+export async function getWorkflowInvocationCountToday(workflowId: string): Promise<InvocationStats> {
+  return invoke<InvocationStats>('get_workflow_invocation_count_today', { workflowId })
+}
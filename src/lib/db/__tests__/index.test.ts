@@ -450,6 +450,23 @@ describe("lib/db - 分支覆盖率增强", () => {
     expect(saved?.description).toBeNull()
   })
 
+  it("工作流名称在项目内应不区分大小写唯一", async () => {
+    const project = await db.createProject("项目")
+    const other = await db.createProject("其他项目")
+    const first = await db.createWorkflow(project.id, "Chapter")
+    const second = await db.createWorkflow(project.id, "润色")
+
+    await expect(db.createWorkflow(project.id, "chapter")).rejects.toMatchObject({ kind: "NameTaken" })
+    await db.createWorkflow(project.id, "Ärger")
+    await expect(db.createWorkflow(project.id, "ärger")).rejects.toMatchObject({ kind: "NameTaken" })
+    await expect(db.createWorkflow(other.id, "Chapter")).resolves.toBeDefined()
+
+    await expect(db.updateWorkflow(second.id, { name: "CHAPTER" })).rejects.toMatchObject({ kind: "NameTaken" })
+    // 名称未变化时仍可更新其他字段
+    await db.updateWorkflow(first.id, { name: "Chapter", description: "新描述" })
+    expect((await db.getWorkflow(first.id))?.description).toBe("新描述")
+  })
+
   it("updateNode 应支持单独更新各字段", async () => {
     const project = await db.createProject("项目")
     const workflow = await db.createWorkflow(project.id, "流程")
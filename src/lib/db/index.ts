@@ -1,4 +1,5 @@
 import Database from '@tauri-apps/plugin-sql'
+import { invoke } from '@tauri-apps/api/core'
 import type {
   Project,
   Workflow,
@@ -14,12 +15,19 @@ import type {
   ExportedWorkflow,
   ExportedSettings,
   ExportedProject,
+  ExportedSettingItem,
+  ExportedChapter,
+  ProjectExportSelection,
+  Chapter,
+  FullDraft,
   WorkflowVersion,
   WorkflowSnapshot,
 } from '@/types'
 import { EXPORT_VERSION } from '@/types'
 import type { SqlClient, SqlExecuteResult } from './types'
 import { createWebSqlClient } from './web-sqlite'
+import { parsePostProcessingConfig } from '@/lib/post-processing'
+import { remapSettingIds, selectionWarnings } from '@/lib/partial-export'
 
 const DB_PATH = 'sqlite:chouann_novel.db'
 
@@ -78,7 +86,8 @@ export function generateId(): string {
 
 export async function getProjects(): Promise<Project[]> {
   const db = await getDatabase()
-  return db.select<Project[]>('SELECT * FROM projects ORDER BY updated_at DESC')
+  const projects = await db.select<Project[]>('SELECT * FROM projects ORDER BY updated_at DESC')
+  return projects.map((p) => ({ ...p, read_only: Boolean(p.read_only) }))
 }
 
 export async function getProject(id: string): Promise<Project | null> {
@@ -87,7 +96,7 @@ export async function getProject(id: string): Promise<Project | null> {
     'SELECT * FROM projects WHERE id = ?',
     [id]
   )
-  return results[0] || null
+  return results[0] ? { ...results[0], read_only: Boolean(results[0].read_only) } : null
 }
 
 export async function createProject(
@@ -108,7 +117,7 @@ export async function createProject(
 
 export async function updateProject(
   id: string,
-  data: Partial<Pick<Project, 'name' | 'description'>>
+  data: Partial<Pick<Project, 'name' | 'description' | 'author'>>
 ): Promise<void> {
   const db = await getDatabase()
   const updates: string[] = []
@@ -122,6 +131,10 @@ export async function updateProject(
     updates.push('description = ?')
     values.push(data.description || null)
   }
+  if (data.author !== undefined) {
+    updates.push('author = ?')
+    values.push(data.author || null)
+  }
 
   updates.push('updated_at = ?')
   values.push(new Date().toISOString())
@@ -140,12 +153,21 @@ export async function deleteProject(id: string): Promise<void> {
 
 // ========== 工作流操作 ==========
 
+// 解析工作流中的 JSON 字段
+function parseWorkflow(workflow: Workflow): Workflow {
+  return {
+    ...workflow,
+    post_processing: parsePostProcessingConfig(workflow.post_processing),
+  }
+}
+
 export async function getWorkflows(projectId: string): Promise<Workflow[]> {
   const db = await getDatabase()
-  return db.select<Workflow[]>(
+  const results = await db.select<Workflow[]>(
     'SELECT * FROM workflows WHERE project_id = ? ORDER BY updated_at DESC',
     [projectId]
   )
+  return results.map(parseWorkflow)
 }
 
 export async function getWorkflow(id: string): Promise<Workflow | null> {
@@ -154,15 +176,46 @@ export async function getWorkflow(id: string): Promise<Workflow | null> {
     'SELECT * FROM workflows WHERE id = ?',
     [id]
   )
-  return results[0] || null
+  return results[0] ? parseWorkflow(results[0]) : null
 }
 
+// 项目内工作流名称不区分大小写唯一；Tauri 环境由后端检查，Web 环境在此按相同规则检查
+async function ensureWorkflowNameAvailable(
+  db: SqlClient,
+  projectId: string,
+  name: string,
+  excludeId?: string
+): Promise<void> {
+  // SQLite 的 LOWER 只转换 ASCII 字母，按 Unicode 规则在此比较（与后端一致）
+  const rows = await db.select<{ name: string }[]>(
+    'SELECT name FROM workflows WHERE project_id = ? AND id != ?',
+    [projectId, excludeId ?? '']
+  )
+  const lowered = name.trim().toLowerCase()
+  if (rows.some(row => row.name.toLowerCase() === lowered)) {
+    throw Object.assign(new Error(`名称已被占用: ${name.trim()}`), { kind: 'NameTaken' })
+  }
+}
+
+/**
+ * 创建工作流；项目内已有同名（不区分大小写）工作流时抛出 { kind: 'NameTaken' }
+ */
 export async function createWorkflow(
   projectId: string,
   name: string,
   description?: string
 ): Promise<Workflow> {
+  if (isTauriEnvironment()) {
+    const workflow = await invoke<Workflow>('create_workflow', {
+      projectId,
+      name,
+      description: description || null,
+    })
+    return parseWorkflow(workflow)
+  }
+
   const db = await getDatabase()
+  await ensureWorkflowNameAvailable(db, projectId, name)
   const workflowId = generateId()
   const now = new Date().toISOString()
 
@@ -192,19 +245,39 @@ export async function createWorkflow(
   }
 }
 
+/**
+ * 更新工作流；修改名称时与创建相同，名称已被占用时抛出 { kind: 'NameTaken' }
+ */
 export async function updateWorkflow(
   id: string,
   data: Partial<
-    Pick<Workflow, 'name' | 'description' | 'loop_max_count' | 'timeout_seconds'>
+    Pick<
+      Workflow,
+      | 'name'
+      | 'description'
+      | 'loop_max_count'
+      | 'timeout_seconds'
+      | 'context_mode'
+      | 'context_turn_limit'
+      | 'context_token_budget'
+      | 'post_processing'
+    >
   >
 ): Promise<void> {
   const db = await getDatabase()
   const updates: string[] = []
   const values: (string | number | null)[] = []
 
-  if (data.name !== undefined) {
-    updates.push('name = ?')
-    values.push(data.name)
+  // 名称未变化时不检查，已有的重名工作流仍可修改其他字段
+  const current = data.name !== undefined ? await getWorkflow(id) : null
+  if (current && data.name !== undefined && data.name !== current.name) {
+    if (isTauriEnvironment()) {
+      await invoke('rename_workflow', { workflowId: id, name: data.name })
+    } else {
+      await ensureWorkflowNameAvailable(db, current.project_id, data.name, id)
+      updates.push('name = ?')
+      values.push(data.name)
+    }
   }
   if (data.description !== undefined) {
     updates.push('description = ?')
@@ -218,6 +291,22 @@ export async function updateWorkflow(
     updates.push('timeout_seconds = ?')
     values.push(data.timeout_seconds)
   }
+  if (data.context_mode !== undefined) {
+    updates.push('context_mode = ?')
+    values.push(data.context_mode)
+  }
+  if (data.context_turn_limit !== undefined) {
+    updates.push('context_turn_limit = ?')
+    values.push(data.context_turn_limit)
+  }
+  if (data.context_token_budget !== undefined) {
+    updates.push('context_token_budget = ?')
+    values.push(data.context_token_budget)
+  }
+  if (data.post_processing !== undefined) {
+    updates.push('post_processing = ?')
+    values.push(data.post_processing?.rules.length ? JSON.stringify(data.post_processing) : null)
+  }
 
   updates.push('updated_at = ?')
   values.push(new Date().toISOString())
@@ -239,7 +328,15 @@ export async function deleteWorkflow(id: string): Promise<void> {
 export async function getNodes(workflowId: string): Promise<WorkflowNode[]> {
   const db = await getDatabase()
   const nodes = await db.select<
-    Array<Omit<WorkflowNode, 'config'> & { config: string; block_id: string | null; parent_block_id: string | null }>
+    Array<
+      Omit<WorkflowNode, 'config'> & {
+        config: string
+        block_id: string | null
+        parent_block_id: string | null
+        group_label: string | null
+        group_color: string | null
+      }
+    >
   >('SELECT * FROM nodes WHERE workflow_id = ? ORDER BY order_index ASC', [
     workflowId,
   ])
@@ -249,6 +346,8 @@ export async function getNodes(workflowId: string): Promise<WorkflowNode[]> {
     config: JSON.parse(node.config),
     block_id: node.block_id || undefined,
     parent_block_id: node.parent_block_id || undefined,
+    group_label: node.group_label || undefined,
+    group_color: node.group_color || undefined,
   }))
 }
 
@@ -413,8 +512,8 @@ export async function restoreNodes(
     const nodeId = generateId()
 
     await db.execute(
-      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, created_at, updated_at)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, group_label, group_color, created_at, updated_at)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
       [
         nodeId,
         workflowId,
@@ -424,6 +523,8 @@ export async function restoreNodes(
         node.order_index,
         node.block_id || null,
         node.parent_block_id || null,
+        node.group_label || null,
+        node.group_color || null,
         now,
         now,
       ]
@@ -438,6 +539,8 @@ export async function restoreNodes(
       order_index: node.order_index,
       block_id: node.block_id,
       parent_block_id: node.parent_block_id,
+      group_label: node.group_label,
+      group_color: node.group_color,
       created_at: now,
       updated_at: now,
     })
@@ -476,6 +579,7 @@ export async function getSettings(
     priority: s.priority ?? 'medium',
     keywords: s.keywords ? JSON.parse(s.keywords as unknown as string) : null,
     summary: s.summary ?? null,
+    facts: s.facts ? JSON.parse(s.facts as unknown as string) : null,
   }))
 }
 
@@ -520,6 +624,7 @@ export async function createSetting(
     priority: pri,
     keywords: keywords ?? null,
     summary: sum,
+    facts: null,
     created_at: now,
     updated_at: now,
   }
@@ -746,6 +851,9 @@ export async function getGlobalConfig(): Promise<GlobalConfig> {
   return {
     ...results[0],
     ai_providers: mergedProviders,
+    ai_execution_title: Boolean(results[0].ai_execution_title),
+    enable_sql_console: Boolean(results[0].enable_sql_console),
+    offline_mode: Boolean(results[0].offline_mode),
     setting_assistant: results[0].setting_assistant ? JSON.parse(results[0].setting_assistant) : null,
   }
 }
@@ -773,6 +881,18 @@ export async function updateGlobalConfig(
     updates.push('default_timeout = ?')
     values.push(data.default_timeout)
   }
+  if (data.ai_execution_title !== undefined) {
+    updates.push('ai_execution_title = ?')
+    values.push(data.ai_execution_title ? 1 : 0)
+  }
+  if (data.enable_sql_console !== undefined) {
+    updates.push('enable_sql_console = ?')
+    values.push(data.enable_sql_console ? 1 : 0)
+  }
+  if (data.offline_mode !== undefined) {
+    updates.push('offline_mode = ?')
+    values.push(data.offline_mode ? 1 : 0)
+  }
   if (data.setting_assistant !== undefined) {
     updates.push('setting_assistant = ?')
     values.push(data.setting_assistant ? JSON.stringify(data.setting_assistant) : 'null')
@@ -864,8 +984,9 @@ export async function updateExecution(
 
 export async function deleteExecution(id: string): Promise<void> {
   const db = await getDatabase()
-  // 先删除关联的节点结果
+  // 先删除关联的节点结果与变量快照
   await db.execute('DELETE FROM node_results WHERE execution_id = ?', [id])
+  await db.execute('DELETE FROM execution_variable_snapshots WHERE execution_id = ?', [id])
   // 再删除执行记录
   await db.execute('DELETE FROM executions WHERE id = ?', [id])
 }
@@ -874,14 +995,20 @@ export async function deleteExecution(id: string): Promise<void> {
 
 export async function getNodeResults(executionId: string): Promise<NodeResult[]> {
   const db = await getDatabase()
-  const results = await db.select<Array<NodeResult & { resolved_config?: string; token_usage?: string }>>(
+  const results = await db.select<Array<NodeResult & { resolved_config?: string; token_usage?: string; post_processing?: string; output_guard?: string }>>(
     'SELECT * FROM node_results WHERE execution_id = ? ORDER BY started_at ASC',
     [executionId]
   )
   
-  // 解析 resolved_config 与 token_usage JSON 字符串
+  // 解析 resolved_config、token_usage、post_processing 与 output_guard JSON 字符串
   return results.map(result => ({
     ...result,
+    output_guard: result.output_guard
+      ? JSON.parse(result.output_guard as string)
+      : undefined,
+    post_processing: result.post_processing
+      ? JSON.parse(result.post_processing as string)
+      : undefined,
     token_usage: result.token_usage
       ? JSON.parse(result.token_usage as string)
       : undefined,
@@ -919,7 +1046,7 @@ export async function createNodeResult(
 
 export async function updateNodeResult(
   id: string,
-  data: Partial<Pick<NodeResult, 'input' | 'output' | 'status' | 'finished_at' | 'resolved_config' | 'token_usage'>>
+  data: Partial<Pick<NodeResult, 'input' | 'output' | 'status' | 'finished_at' | 'resolved_config' | 'token_usage' | 'first_token_at' | 'post_processing' | 'output_guard'>>
 ): Promise<void> {
   const db = await getDatabase()
   const updates: string[] = []
@@ -949,6 +1076,18 @@ export async function updateNodeResult(
     updates.push('token_usage = ?')
     values.push(data.token_usage ? JSON.stringify(data.token_usage) : null)
   }
+  if (data.first_token_at !== undefined) {
+    updates.push('first_token_at = ?')
+    values.push(data.first_token_at || null)
+  }
+  if (data.post_processing !== undefined) {
+    updates.push('post_processing = ?')
+    values.push(data.post_processing?.length ? JSON.stringify(data.post_processing) : null)
+  }
+  if (data.output_guard !== undefined) {
+    updates.push('output_guard = ?')
+    values.push(data.output_guard ? JSON.stringify(data.output_guard) : null)
+  }
 
   values.push(id)
 
@@ -958,6 +1097,22 @@ export async function updateNodeResult(
   )
 }
 
+/**
+ * 保存写入变量节点完成后的变量快照
+ */
+export async function createVariableSnapshot(
+  executionId: string,
+  nodeId: string,
+  variables: Record<string, string>
+): Promise<void> {
+  const db = await getDatabase()
+  await db.execute(
+    `INSERT INTO execution_variable_snapshots (id, execution_id, node_id, variables, created_at)
+     VALUES (?, ?, ?, ?, ?)`,
+    [generateId(), executionId, nodeId, JSON.stringify(variables), new Date().toISOString()]
+  )
+}
+
 // ========== 统计操作 ==========
 
 export async function getGlobalStats(): Promise<GlobalStats> {
@@ -1033,6 +1188,11 @@ export async function exportWorkflow(workflowId: string): Promise<ExportedWorkfl
   if (!workflow) return null
 
   const nodes = await getNodes(workflowId)
+  const db = await getDatabase()
+  const presets = await db.select<{ name: string; content: string; is_default: number }[]>(
+    'SELECT name, content, is_default FROM input_presets WHERE workflow_id = ? ORDER BY created_at',
+    [workflowId]
+  )
 
   return {
     version: EXPORT_VERSION,
@@ -1050,6 +1210,13 @@ export async function exportWorkflow(workflowId: string): Promise<ExportedWorkfl
       order_index: node.order_index,
       block_id: node.block_id,
       parent_block_id: node.parent_block_id,
+      group_label: node.group_label,
+      group_color: node.group_color,
+    })),
+    input_presets: presets.map((preset) => ({
+      name: preset.name,
+      content: preset.content,
+      is_default: Boolean(preset.is_default),
     })),
   }
 }
@@ -1099,8 +1266,8 @@ export async function importWorkflow(
     const newParentBlockId = node.parent_block_id ? blockIdMap.get(node.parent_block_id) : null
 
     await db.execute(
-      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, created_at, updated_at)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, group_label, group_color, created_at, updated_at)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
       [
         nodeId,
         workflowId,
@@ -1110,12 +1277,23 @@ export async function importWorkflow(
         node.order_index,
         newBlockId,
         newParentBlockId,
+        node.group_label || null,
+        node.group_color || null,
         now,
         now,
       ]
     )
   }
 
+  // 创建输入预设
+  for (const preset of data.input_presets || []) {
+    await db.execute(
+      `INSERT INTO input_presets (id, workflow_id, name, content, is_default, created_at)
+       VALUES (?, ?, ?, ?, ?, ?)`,
+      [generateId(), workflowId, preset.name, preset.content, preset.is_default ? 1 : 0, now]
+    )
+  }
+
   return {
     id: workflowId,
     project_id: projectId,
@@ -1153,6 +1331,7 @@ export async function exportSettings(projectId: string): Promise<ExportedSetting
       category: p.category,
       prompt_template: p.prompt_template,
       enabled: p.enabled,
+      content_template: p.content_template ?? null,
     })),
   }
 }
@@ -1233,9 +1412,9 @@ export async function importSettings(
       
       const id = generateId()
       await db.execute(
-        `INSERT OR REPLACE INTO setting_prompts (id, project_id, category, prompt_template, enabled)
-         VALUES (?, ?, ?, ?, ?)`,
-        [id, projectId, prompt.category, prompt.prompt_template, prompt.enabled ? 1 : 0]
+        `INSERT OR REPLACE INTO setting_prompts (id, project_id, category, prompt_template, enabled, content_template)
+         VALUES (?, ?, ?, ?, ?, ?)`,
+        [id, projectId, prompt.category, prompt.prompt_template, prompt.enabled ? 1 : 0, prompt.content_template ?? null]
       )
     }
   }
@@ -1251,6 +1430,69 @@ export async function duplicateProject(projectId: string): Promise<Project> {
   return importProject(exported, `${originalName} (副本)`)
 }
 
+// 导出工作流及其节点（不含 ID）
+async function exportWorkflowWithNodes(workflow: Workflow): Promise<ExportedProject['workflows'][number]> {
+  const nodes = await getNodes(workflow.id)
+  return {
+    workflow: {
+      name: workflow.name,
+      description: workflow.description,
+      loop_max_count: workflow.loop_max_count,
+      timeout_seconds: workflow.timeout_seconds,
+    },
+    nodes: nodes.map((node) => ({
+      type: node.type,
+      name: node.name,
+      config: node.config,
+      order_index: node.order_index,
+      block_id: node.block_id,
+      parent_block_id: node.parent_block_id,
+      group_label: node.group_label,
+      group_color: node.group_color,
+    })),
+  }
+}
+
+function exportSettingItem(s: Setting): ExportedSettingItem {
+  return {
+    id: s.id,
+    category: s.category,
+    name: s.name,
+    content: s.content,
+    enabled: s.enabled,
+    parent_id: s.parent_id,
+    order_index: s.order_index,
+  }
+}
+
+function exportSettingPrompt(p: SettingPrompt): ExportedProject['setting_prompts'][number] {
+  return {
+    category: p.category,
+    prompt_template: p.prompt_template,
+    enabled: p.enabled,
+    content_template: p.content_template ?? null,
+  }
+}
+
+// 导出章节大纲，withContent 时附带章节正文；章节由后端管理，非 Tauri 环境下为空
+async function exportChapters(projectId: string, withContent: boolean): Promise<ExportedChapter[]> {
+  if (!isTauriEnvironment()) return []
+  const chapters = await invoke<Chapter[]>('get_project_chapter_list', { projectId })
+  const draft = withContent ? await invoke<FullDraft>('get_project_full_draft', { projectId }) : null
+  return chapters.map((chapter) => {
+    const exported: ExportedChapter = {
+      title: chapter.title,
+      synopsis: chapter.synopsis,
+      target_word_count: chapter.target_word_count,
+      status: chapter.status,
+    }
+    if (draft) {
+      exported.content = draft.chapters.find((c) => c.chapter_id === chapter.id)?.content ?? ''
+    }
+    return exported
+  })
+}
+
 /**
  * 导出完整项目数据
  */
@@ -1263,27 +1505,7 @@ export async function exportProject(projectId: string): Promise<ExportedProject
   const prompts = await getSettingPrompts(projectId)
 
   // 获取每个工作流的节点
-  const workflowsWithNodes = await Promise.all(
-    workflows.map(async (workflow) => {
-      const nodes = await getNodes(workflow.id)
-      return {
-        workflow: {
-          name: workflow.name,
-          description: workflow.description,
-          loop_max_count: workflow.loop_max_count,
-          timeout_seconds: workflow.timeout_seconds,
-        },
-        nodes: nodes.map((node) => ({
-          type: node.type,
-          name: node.name,
-          config: node.config,
-          order_index: node.order_index,
-          block_id: node.block_id,
-          parent_block_id: node.parent_block_id,
-        })),
-      }
-    })
-  )
+  const workflowsWithNodes = await Promise.all(workflows.map(exportWorkflowWithNodes))
 
   return {
     version: EXPORT_VERSION,
@@ -1291,96 +1513,93 @@ export async function exportProject(projectId: string): Promise<ExportedProject
     project: {
       name: project.name,
       description: project.description,
+      author: project.author,
     },
     workflows: workflowsWithNodes,
-    settings: settings.map((s) => ({
-      id: s.id,
-      category: s.category,
-      name: s.name,
-      content: s.content,
-      enabled: s.enabled,
-      parent_id: s.parent_id,
-      order_index: s.order_index,
-    })),
-    setting_prompts: prompts.map((p) => ({
-      category: p.category,
-      prompt_template: p.prompt_template,
-      enabled: p.enabled,
-    })),
+    settings: settings.map(exportSettingItem),
+    setting_prompts: prompts.map(exportSettingPrompt),
+  }
+}
+
+/**
+ * 按选择范围导出部分项目数据（选定的工作流与设定分类，可选章节、大纲与设定提示词）；
+ * 选中的工作流引用了未导出分类的设定时，清单中列出警告
+ */
+export async function exportProjectPartial(
+  projectId: string,
+  selection: ProjectExportSelection
+): Promise<ExportedProject | null> {
+  const project = await getProject(projectId)
+  if (!project) return null
+
+  const allWorkflows = await getWorkflows(projectId)
+  const missing = selection.workflow_ids.find((id) => !allWorkflows.some((w) => w.id === id))
+  if (missing) {
+    throw new Error(`工作流不属于该项目: ${missing}`)
+  }
+  const workflows = allWorkflows.filter((w) => selection.workflow_ids.includes(w.id))
+  const workflowsWithNodes = await Promise.all(workflows.map(exportWorkflowWithNodes))
+
+  const allSettings = await getSettings(projectId)
+  const settings = allSettings.filter((s) => selection.setting_categories.includes(s.category))
+  const prompts = selection.include_prompts ? await getSettingPrompts(projectId) : []
+  const chapters =
+    selection.include_outlines || selection.include_chapters
+      ? await exportChapters(projectId, selection.include_chapters)
+      : []
+
+  return {
+    version: EXPORT_VERSION,
+    exported_at: new Date().toISOString(),
+    project: {
+      name: project.name,
+      description: project.description,
+      author: project.author,
+    },
+    workflows: workflowsWithNodes,
+    settings: settings.map(exportSettingItem),
+    setting_prompts: prompts.map(exportSettingPrompt),
+    chapters,
+    manifest: {
+      partial: true,
+      selection,
+      warnings: selectionWarnings(workflowsWithNodes, allSettings, selection.setting_categories),
+    },
   }
 }
 
+// 合并导入时存放章节正文的工作流（不含节点）
+const IMPORTED_CONTENT_WORKFLOW_NAME = '导入的章节正文'
+
 /**
- * 从备份恢复项目（创建新项目）
+ * 从备份恢复项目：默认创建新项目；提供 mergeIntoProjectId 时把（部分）备份合并到已有项目，
+ * 合并时已存在同分类的设定提示词保持不变，章节追加在已有章节之后
  */
 export async function importProject(
   data: ExportedProject,
-  newName?: string
+  newName?: string,
+  mergeIntoProjectId?: string
 ): Promise<Project> {
   const db = await getDatabase()
-  const projectId = generateId()
   const now = new Date().toISOString()
+  let projectId: string
+  let merged: Project | null = null
 
-  // 创建项目
-  await db.execute(
-    `INSERT INTO projects (id, name, description, created_at, updated_at)
-     VALUES (?, ?, ?, ?, ?)`,
-    [projectId, newName || data.project.name, data.project.description || null, now, now]
-  )
-
-  // 导入工作流
-  for (const workflowData of data.workflows) {
-    const workflowId = generateId()
-    
+  if (mergeIntoProjectId) {
+    merged = await getProject(mergeIntoProjectId)
+    if (!merged) throw new Error('项目不存在')
+    projectId = merged.id
+  } else {
+    projectId = generateId()
+    // 创建项目
     await db.execute(
-      `INSERT INTO workflows (id, project_id, name, description, loop_max_count, timeout_seconds, created_at, updated_at)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
-      [
-        workflowId,
-        projectId,
-        workflowData.workflow.name,
-        workflowData.workflow.description || null,
-        workflowData.workflow.loop_max_count,
-        workflowData.workflow.timeout_seconds,
-        now,
-        now,
-      ]
+      `INSERT INTO projects (id, name, description, author, created_at, updated_at)
+       VALUES (?, ?, ?, ?, ?, ?)`,
+      [projectId, newName || data.project.name, data.project.description || null, data.project.author || null, now, now]
     )
-
-    // 创建 block_id 映射
-    const blockIdMap = new Map<string, string>()
-    for (const node of workflowData.nodes) {
-      if (node.block_id && !blockIdMap.has(node.block_id)) {
-        blockIdMap.set(node.block_id, generateId())
-      }
-    }
-
-    // 导入节点
-    for (const node of workflowData.nodes) {
-      const nodeId = generateId()
-      const newBlockId = node.block_id ? blockIdMap.get(node.block_id) : null
-      const newParentBlockId = node.parent_block_id ? blockIdMap.get(node.parent_block_id) : null
-
-      await db.execute(
-        `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
-        [
-          nodeId,
-          workflowId,
-          node.type,
-          node.name,
-          JSON.stringify(node.config),
-          node.order_index,
-          newBlockId,
-          newParentBlockId,
-          now,
-          now,
-        ]
-      )
-    }
   }
 
-  // 导入设定（两阶段：先插入，再更新 parent_id）
+  // 导入设定（两阶段：先插入，再更新 parent_id）；先于工作流导入，以便重映射节点引用的设定
   const settingIdMap = new Map<string, string>()
 
   for (const setting of data.settings) {
@@ -1428,14 +1647,118 @@ export async function importProject(
 
   // 导入设定提示词
   for (const prompt of data.setting_prompts) {
+    if (merged && (await getSettingPrompt(projectId, prompt.category))) {
+      // 合并时跳过已存在的提示词
+      continue
+    }
     const id = generateId()
     await db.execute(
-      `INSERT INTO setting_prompts (id, project_id, category, prompt_template, enabled)
-       VALUES (?, ?, ?, ?, ?)`,
-      [id, projectId, prompt.category, prompt.prompt_template, prompt.enabled ? 1 : 0]
+      `INSERT INTO setting_prompts (id, project_id, category, prompt_template, enabled, content_template)
+       VALUES (?, ?, ?, ?, ?, ?)`,
+      [id, projectId, prompt.category, prompt.prompt_template, prompt.enabled ? 1 : 0, prompt.content_template ?? null]
     )
   }
 
+  // 导入工作流
+  for (const workflowData of data.workflows) {
+    const workflowId = generateId()
+    
+    await db.execute(
+      `INSERT INTO workflows (id, project_id, name, description, loop_max_count, timeout_seconds, created_at, updated_at)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+      [
+        workflowId,
+        projectId,
+        workflowData.workflow.name,
+        workflowData.workflow.description || null,
+        workflowData.workflow.loop_max_count,
+        workflowData.workflow.timeout_seconds,
+        now,
+        now,
+      ]
+    )
+
+    // 创建 block_id 映射
+    const blockIdMap = new Map<string, string>()
+    for (const node of workflowData.nodes) {
+      if (node.block_id && !blockIdMap.has(node.block_id)) {
+        blockIdMap.set(node.block_id, generateId())
+      }
+    }
+
+    // 导入节点
+    for (const node of workflowData.nodes) {
+      const nodeId = generateId()
+      const newBlockId = node.block_id ? blockIdMap.get(node.block_id) : null
+      const newParentBlockId = node.parent_block_id ? blockIdMap.get(node.parent_block_id) : null
+
+      await db.execute(
+        `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, group_label, group_color, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+        [
+          nodeId,
+          workflowId,
+          node.type,
+          node.name,
+          JSON.stringify(remapSettingIds(node.config, settingIdMap)),
+          node.order_index,
+          newBlockId,
+          newParentBlockId,
+          node.group_label || null,
+          node.group_color || null,
+          now,
+          now,
+        ]
+      )
+    }
+  }
+
+  // 导入章节（部分导出时才包含）；正文写入为章节的已完成执行，与全书草稿的取值方式一致
+  if (data.chapters?.length) {
+    const [{ next_index }] = await db.select<Array<{ next_index: number }>>(
+      'SELECT COALESCE(MAX(order_index) + 1, 0) AS next_index FROM chapters WHERE project_id = ?',
+      [projectId]
+    )
+    let contentWorkflowId: string | null = null
+    for (const [index, chapter] of data.chapters.entries()) {
+      const chapterId = generateId()
+      await db.execute(
+        `INSERT INTO chapters (id, project_id, title, synopsis, target_word_count, order_index, status, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+        [
+          chapterId,
+          projectId,
+          chapter.title,
+          chapter.synopsis,
+          chapter.target_word_count,
+          next_index + index,
+          chapter.status,
+          now,
+          now,
+        ]
+      )
+      if (!chapter.content) continue
+
+      if (!contentWorkflowId) {
+        contentWorkflowId = generateId()
+        await db.execute(
+          `INSERT INTO workflows (id, project_id, name, created_at, updated_at) VALUES (?, ?, ?, ?, ?)`,
+          [contentWorkflowId, projectId, IMPORTED_CONTENT_WORKFLOW_NAME, now, now]
+        )
+      }
+      await db.execute(
+        `INSERT INTO executions (id, workflow_id, status, final_output, started_at, finished_at, title, chapter_id)
+         VALUES (?, ?, 'completed', ?, ?, ?, ?, ?)`,
+        [generateId(), contentWorkflowId, chapter.content, now, now, chapter.title, chapterId]
+      )
+    }
+  }
+
+  if (merged) {
+    await db.execute('UPDATE projects SET updated_at = ? WHERE id = ?', [now, projectId])
+    return { ...merged, updated_at: now }
+  }
+
   return {
     id: projectId,
     name: newName || data.project.name,
@@ -1503,6 +1826,8 @@ export async function createWorkflowVersion(
       order_index: node.order_index,
       block_id: node.block_id,
       parent_block_id: node.parent_block_id,
+      group_label: node.group_label,
+      group_color: node.group_color,
     })),
   }
 
@@ -1578,8 +1903,8 @@ export async function restoreWorkflowVersion(versionId: string): Promise<void> {
     const newParentBlockId = node.parent_block_id ? blockIdMap.get(node.parent_block_id) : null
 
     await db.execute(
-      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, created_at, updated_at)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+      `INSERT INTO nodes (id, workflow_id, type, name, config, order_index, block_id, parent_block_id, group_label, group_color, created_at, updated_at)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
       [
         nodeId,
         version.workflow_id,
@@ -1589,6 +1914,8 @@ export async function restoreWorkflowVersion(versionId: string): Promise<void> {
         node.order_index,
         newBlockId,
         newParentBlockId,
+        node.group_label || null,
+        node.group_color || null,
         now,
         now,
       ]
@@ -130,6 +130,8 @@ const MIGRATION_SQL = `
     id TEXT PRIMARY KEY,
     name TEXT NOT NULL,
     description TEXT,
+    author TEXT,
+    read_only INTEGER NOT NULL DEFAULT 0,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
   );
@@ -141,6 +143,7 @@ const MIGRATION_SQL = `
     description TEXT,
     loop_max_count INTEGER DEFAULT 10,
     timeout_seconds INTEGER DEFAULT 300,
+    post_processing TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
@@ -155,6 +158,8 @@ const MIGRATION_SQL = `
     order_index INTEGER NOT NULL,
     block_id TEXT,
     parent_block_id TEXT,
+    group_label TEXT DEFAULT NULL,
+    group_color TEXT DEFAULT NULL,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
@@ -173,6 +178,7 @@ const MIGRATION_SQL = `
     priority TEXT DEFAULT 'medium',
     keywords TEXT DEFAULT NULL,
     summary TEXT DEFAULT NULL,
+    facts TEXT DEFAULT NULL,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
@@ -184,6 +190,7 @@ const MIGRATION_SQL = `
     category TEXT NOT NULL,
     prompt_template TEXT NOT NULL,
     enabled INTEGER DEFAULT 1,
+    content_template TEXT,
     FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
   );
 
@@ -192,7 +199,8 @@ const MIGRATION_SQL = `
     ai_providers TEXT NOT NULL DEFAULT '{}',
     theme TEXT DEFAULT 'system',
     default_loop_max INTEGER DEFAULT 10,
-    default_timeout INTEGER DEFAULT 300
+    default_timeout INTEGER DEFAULT 300,
+    offline_mode INTEGER DEFAULT 0
   );
 
   INSERT OR IGNORE INTO global_config (id, ai_providers, theme)
@@ -222,6 +230,9 @@ const MIGRATION_SQL = `
     status TEXT NOT NULL,
     started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     finished_at DATETIME,
+    first_token_at DATETIME,
+    post_processing TEXT,
+    output_guard TEXT,
     FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
   );
 
@@ -275,6 +286,27 @@ const MIGRATION_SQL = `
   CREATE INDEX IF NOT EXISTS idx_relations_source ON setting_relations(source_id);
   CREATE INDEX IF NOT EXISTS idx_relations_target ON setting_relations(target_id);
   CREATE INDEX IF NOT EXISTS idx_relations_project ON setting_relations(project_id);
+
+  CREATE TABLE IF NOT EXISTS execution_variable_snapshots (
+    id TEXT PRIMARY KEY,
+    execution_id TEXT NOT NULL,
+    node_id TEXT NOT NULL,
+    variables TEXT NOT NULL DEFAULT '{}',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+  );
+  CREATE INDEX IF NOT EXISTS idx_execution_variable_snapshots_execution ON execution_variable_snapshots(execution_id, created_at);
+
+  CREATE TABLE IF NOT EXISTS input_presets (
+    id TEXT PRIMARY KEY,
+    workflow_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    content TEXT NOT NULL DEFAULT '',
+    is_default INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+  );
+  CREATE INDEX IF NOT EXISTS idx_input_presets_workflow ON input_presets(workflow_id);
 `
 
 
@@ -148,6 +148,22 @@ describe("ExecutionContext - 执行上下文", () => {
     it("获取不存在节点的历史应该返回空数组", () => {
       expect(ctx.getHistory("nonexistent")).toEqual([])
     })
+
+    it("工作流级对话历史应该按轮数与 token 预算裁剪", () => {
+      ctx.addChatTurn("写大纲", "大纲内容")
+      ctx.addChatTurn("写第一章", "第一章正文")
+      ctx.addChatTurn("润色", "润色后的正文")
+
+      const recent = ctx.getChatHistory(2, 0)
+      expect(recent).toHaveLength(4)
+      expect(recent[0]).toEqual({ role: "user", content: "写第一章" })
+      expect(recent[3]).toEqual({ role: "assistant", content: "润色后的正文" })
+
+      // "润色" + "润色后的正文" 约 12 token，预算只够保留最后一轮
+      const budgeted = ctx.getChatHistory(0, 20, 5)
+      expect(budgeted).toHaveLength(2)
+      expect(budgeted[0].content).toBe("润色")
+    })
   })
 
   // ========== 节点输出测试 ==========
@@ -1,7 +1,7 @@
 // lib/engine/executor.ts 执行器测试
 
 import { describe, it, expect, beforeEach, vi, afterEach } from "vitest"
-import { WorkflowExecutor, executorStatusToDbStatus } from "../executor"
+import { WorkflowExecutor, executorStatusToDbStatus, type ExecutionEvent } from "../executor"
 import type {
   Workflow,
   WorkflowNode,
@@ -182,7 +182,30 @@ describe("WorkflowExecutor - 工作流执行器", () => {
       expect(ctx.getVariable("用户问题")).toBe("用户输入的问题")
     })
 
-    it("没有输入时应该使用默认值", async () => {
+    it("应该提供项目梗概变量 synopsis", async () => {
+      const nodes = [
+        createTestNode("start", "开始"),
+        createTestNode("text_concat", "拼接", {
+          sources: [{ type: "custom", custom: "前情：{{synopsis}}" }],
+          separator: "",
+        } as TextConcatConfig),
+      ]
+      const executor = new WorkflowExecutor({
+        workflow,
+        nodes,
+        globalConfig,
+        synopsis: "【雨夜】林雪救下沈舟。",
+      })
+
+      const result = await executor.execute()
+
+      expect(result.output).toBe("前情：【雨夜】林雪救下沈舟。")
+
+      const withoutSynopsis = new WorkflowExecutor({ workflow, nodes, globalConfig })
+      expect(withoutSynopsis.getContext().getVariable("synopsis")).toBe("")
+    })
+
+        it("没有输入时应该使用默认值", async () => {
       const config: StartConfig = { default_value: "默认问题" }
       const nodes = [createTestNode("start", "开始", config)]
       const executor = new WorkflowExecutor({
@@ -1447,6 +1470,157 @@ describe("WorkflowExecutor - 工作流执行器", () => {
       expect(result.error).toContain("需要系统提示词或用户问题")
     })
 
+    it("应该按 sampling 覆盖采样参数并记录生效值", async () => {
+      let capturedOptions: any = null
+      mockChatStream.mockImplementation(async (options, _config, onChunk) => {
+        capturedOptions = options
+        onChunk({ content: "收到", done: false })
+        onChunk({ content: "", done: true })
+      })
+
+      const config: AIChatConfig = {
+        provider: "claude",
+        model: "claude-sonnet-4-5-20250929",
+        system_prompt: "",
+        user_prompt: "{{用户问题}}",
+        temperature: 0.7,
+        top_p: 0.9,
+        enable_history: false,
+        history_count: 0,
+        setting_ids: [],
+        sampling: { temperature: 0.3, frequency_penalty: 0.5 },
+      }
+      globalConfig.ai_providers.claude = { api_key: "test-key", enabled: true, enabled_models: [], custom_models: [] }
+      const events: ExecutionEvent[] = []
+      const executor = new WorkflowExecutor({
+        workflow,
+        nodes: [createTestNode("ai_chat", "AI对话", config)],
+        globalConfig,
+        initialInput: "你好",
+        onEvent: (e) => events.push(e),
+      })
+
+      await executor.execute()
+
+      expect(capturedOptions.temperature).toBe(0.3)
+      expect(capturedOptions.topP).toBe(0.9)
+      expect(capturedOptions.frequencyPenalty).toBeUndefined()
+      const resolved = events.find(e => e.type === "node_completed")?.resolvedConfig
+      expect(resolved?.temperature).toBe(0.3)
+      expect(resolved?.frequencyPenalty).toBeUndefined()
+    })
+
+    describe("输出守卫", () => {
+      const guardedConfig = (output_guard: AIChatConfig["output_guard"]): AIChatConfig => ({
+        provider: "openai",
+        model: "gpt-4",
+        system_prompt: "你是一个小说作者",
+        user_prompt: "{{用户问题}}",
+        enable_history: false,
+        history_count: 0,
+        setting_ids: [],
+        output_guard,
+      })
+
+      it("未通过时应该追加纠正提示重试并记录评估", async () => {
+        const outputs = ["Sure, here is the chapter.", "雨夜，林雪推开了门。"]
+        const capturedSystems: string[] = []
+        mockChatStream.mockImplementation(async (options, _config, onChunk) => {
+          capturedSystems.push(options.messages.find((m: any) => m.role === "system")?.content)
+          onChunk({ content: outputs[capturedSystems.length - 1], done: false })
+          onChunk({ content: "", done: true, usage: { promptTokens: 10, completionTokens: 5, totalTokens: 15 } })
+        })
+
+        const events: ExecutionEvent[] = []
+        const executor = new WorkflowExecutor({
+          workflow,
+          nodes: [
+            createTestNode("start", "开始", {}, { order_index: 0 }),
+            createTestNode("ai_chat", "AI对话", guardedConfig({
+              checks: [{ type: "min_cjk_ratio", value: 0.8 }],
+              action: "retry",
+            }), { order_index: 1 }),
+          ],
+          globalConfig,
+          initialInput: "写一段开头",
+          onEvent: (e) => events.push(e),
+        })
+
+        const result = await executor.execute()
+
+        expect(result.status).toBe("completed")
+        expect(result.output).toBe("雨夜，林雪推开了门。")
+        expect(capturedSystems[0]).toBe("你是一个小说作者")
+        expect(capturedSystems[1]).toContain("必须使用中文回答")
+        const completed = events.find(e => e.type === "node_completed" && e.nodeType === "ai_chat")
+        expect(completed?.usage?.totalTokens).toBe(30)
+        expect(completed?.outputGuard?.outcome).toBe("passed")
+        expect(completed?.outputGuard?.retries).toBe(1)
+        expect(completed?.outputGuard?.evaluations.map(e => e.passed)).toEqual([false, true])
+      })
+
+      it("warn 模式应该接受输出，fail 模式应该令节点失败", async () => {
+        mockChatStream.mockImplementation(async (_options, _config, onChunk) => {
+          onChunk({ content: "{\"text\": \"雨夜\"}", done: false })
+          onChunk({ content: "", done: true })
+        })
+        const checks = [{ type: "forbid_prefix" as const, value: "{" }]
+
+        const warnEvents: ExecutionEvent[] = []
+        const warned = await new WorkflowExecutor({
+          workflow,
+          nodes: [createTestNode("ai_chat", "AI对话", guardedConfig({ checks, action: "warn" }))],
+          globalConfig,
+          onEvent: (e) => warnEvents.push(e),
+        }).execute()
+        expect(warned.status).toBe("completed")
+        expect(warnEvents.find(e => e.type === "node_completed")?.outputGuard?.outcome).toBe("warned")
+
+        const failEvents: ExecutionEvent[] = []
+        const failed = await new WorkflowExecutor({
+          workflow,
+          nodes: [createTestNode("ai_chat", "AI对话", guardedConfig({ checks, action: "fail" }))],
+          globalConfig,
+          onEvent: (e) => failEvents.push(e),
+        }).execute()
+        expect(failed.status).toBe("failed")
+        expect(failed.error).toContain("输出未通过守卫检查")
+        expect(mockChatStream).toHaveBeenCalledTimes(2)
+        const nodeFailed = failEvents.find(e => e.type === "node_failed")
+        expect(nodeFailed?.outputGuard?.outcome).toBe("failed")
+        expect(nodeFailed?.outputGuard?.evaluations[0].failures).toEqual(['输出以 "{" 开头'])
+      })
+
+      it("开启章节字数检查时只在最后一个 AI 节点扩写或压缩", async () => {
+        const outputs = ["林雪推开了门", "雨夜的提纲", "雨夜，林雪推开了门。"]
+        const capturedSystems: string[] = []
+        mockChatStream.mockImplementation(async (options, _config, onChunk) => {
+          capturedSystems.push(options.messages.find((m: any) => m.role === "system")?.content)
+          onChunk({ content: outputs[capturedSystems.length - 1], done: false })
+          onChunk({ content: "", done: true })
+        })
+
+        const events: ExecutionEvent[] = []
+        const result = await new WorkflowExecutor({
+          workflow,
+          nodes: [
+            createTestNode("ai_chat", "提纲", guardedConfig(undefined), { order_index: 0 }),
+            createTestNode("ai_chat", "正文", guardedConfig(undefined), { order_index: 1 }),
+          ],
+          globalConfig,
+          chapterLength: { min_chapter_chars: 8, max_chapter_chars: 20, enforcement: "retry" },
+          onEvent: (e) => events.push(e),
+        }).execute()
+
+        expect(result.status).toBe("completed")
+        expect(result.output).toBe("雨夜，林雪推开了门。")
+        expect(capturedSystems).toHaveLength(3)
+        expect(capturedSystems[2]).toContain("正文不少于 8 字（不足时扩写）")
+        const guards = events.filter(e => e.type === "node_completed").map(e => e.outputGuard?.retries)
+        expect(guards).toEqual([undefined, 1])
+      })
+    })
+
     describe("对话历史功能", () => {
       it("enable_history=true 时应该保存对话历史", async () => {
         let callCount = 0
@@ -2503,6 +2677,84 @@ describe("WorkflowExecutor - 工作流执行器", () => {
       expect(result.error).toContain("重试 3 次后仍失败")
     })
 
+    it("看门狗触发时应中止当前请求并按重试设置重试", async () => {
+      let attempts = 0
+      let executor: WorkflowExecutor | null = null
+      const onHeartbeat = vi.fn()
+
+      mockChatStream.mockImplementation(async (options, _config, onChunk) => {
+        attempts++
+        if (attempts === 1) {
+          // 第一次请求卡住，直到被看门狗中止
+          await new Promise((_resolve, reject) => {
+            options.signal.addEventListener("abort", () => reject(new Error("aborted")))
+            executor!.abortStalled()
+          })
+        }
+        onChunk({ content: "恢复", done: false })
+        onChunk({ content: "", done: true })
+      })
+
+      const nodes = [
+        createTestNode("start", "开始", {}, { order_index: 0 }),
+        createTestNode("ai_chat", "AI对话", {
+          provider: "openai",
+          model: "gpt-4",
+          system_prompt: "",
+          user_prompt: "测试",
+          temperature: 0.7,
+          max_tokens: 100,
+          top_p: 1,
+          enable_history: false,
+          history_count: 0,
+          setting_ids: [],
+          retry_count: 1,
+        } as AIChatConfig, { order_index: 1, id: "ai-node" }),
+      ]
+
+      executor = new WorkflowExecutor({ workflow, nodes, globalConfig, onHeartbeat })
+      const result = await executor.execute()
+
+      expect(result.status).toBe("completed")
+      expect(result.output).toBe("恢复")
+      expect(attempts).toBe(2)
+      expect(onHeartbeat).toHaveBeenCalledWith("ai-node")
+    })
+
+    it("看门狗触发且重试用尽时执行应失败", async () => {
+      let executor: WorkflowExecutor | null = null
+
+      mockChatStream.mockImplementation(async (options) => {
+        await new Promise((_resolve, reject) => {
+          options.signal.addEventListener("abort", () => reject(new Error("aborted")))
+          executor!.abortStalled()
+        })
+      })
+
+      const nodes = [
+        createTestNode("start", "开始", {}, { order_index: 0 }),
+        createTestNode("ai_chat", "AI对话", {
+          provider: "openai",
+          model: "gpt-4",
+          system_prompt: "",
+          user_prompt: "测试",
+          temperature: 0.7,
+          max_tokens: 100,
+          top_p: 1,
+          enable_history: false,
+          history_count: 0,
+          setting_ids: [],
+          retry_count: 0,
+        } as AIChatConfig, { order_index: 1 }),
+      ]
+
+      executor = new WorkflowExecutor({ workflow, nodes, globalConfig })
+      const result = await executor.execute()
+
+      expect(result.status).toBe("failed")
+      expect(result.error).toContain("看门狗")
+    })
+
     it("未定义变量应触发严格插值失败", async () => {
       const nodes = [
         createTestNode("start", "开始", {}, { order_index: 0 }),
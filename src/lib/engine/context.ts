@@ -2,6 +2,7 @@
 
 import type { WorkflowNode } from '@/types'
 import type { Message } from '@/lib/ai/types'
+import { estimateTokens } from '@/lib/settings-injection'
 
 // 节点执行状态
 export interface NodeExecutionState {
@@ -23,6 +24,9 @@ export class ExecutionContext {
   
   // 对话历史（每个节点的对话历史）
   private conversationHistory: Map<string, Message[]> = new Map()
+
+  // 工作流级对话历史（chat_history 模式下各 AI 节点的问答轮次）
+  private chatTurns: [Message, Message][] = []
   
   // 节点输出存储（按节点ID索引）
   private nodeOutputs: Map<string, string> = new Map()
@@ -202,6 +206,39 @@ export class ExecutionContext {
     this.conversationHistory.delete(nodeId)
   }
 
+  /**
+   * 记录一轮工作流级对话（用户提示词 + AI 输出）
+   */
+  addChatTurn(userContent: string, assistantContent: string): void {
+    this.chatTurns.push([
+      { role: 'user', content: userContent },
+      { role: 'assistant', content: assistantContent },
+    ])
+  }
+
+  /**
+   * 获取工作流级对话历史，按轮次从旧到新裁剪
+   * @param turnLimit 最多保留的轮数（0 表示不限制）
+   * @param tokenBudget 历史与 reservedTokens 合计的 token 上限（0 表示不限制）
+   * @param reservedTokens 系统提示词与当前问题已占用的 token
+   */
+  getChatHistory(turnLimit: number, tokenBudget: number, reservedTokens: number = 0): Message[] {
+    let turns = turnLimit > 0 ? this.chatTurns.slice(-turnLimit) : [...this.chatTurns]
+    if (tokenBudget > 0) {
+      const turnTokens = turns.map(([user, assistant]) =>
+        estimateTokens(user.content) + estimateTokens(assistant.content)
+      )
+      let total = reservedTokens + turnTokens.reduce((sum, n) => sum + n, 0)
+      let dropped = 0
+      while (dropped < turns.length && total > tokenBudget) {
+        total -= turnTokens[dropped]
+        dropped++
+      }
+      turns = turns.slice(dropped)
+    }
+    return turns.flat()
+  }
+
   // ========== 输出操作 ==========
 
   /**
@@ -19,13 +19,40 @@ import type {
   SettingPrompt,
   ResolvedNodeConfig,
   TokenUsage,
+  PostProcessingApplication,
+  OutputGuardConfig,
+  OutputGuardReport,
+  ChapterLengthRange,
 } from '@/types'
 import type { Message } from '@/lib/ai/types'
 import { chatStream } from '@/lib/ai'
 import { getErrorMessage, logError } from '@/lib/errors'
-import { generateSettingsInjection } from '@/lib/settings-injection'
+import { estimateTokens, generateSettingsInjection, resolveSettingReferences } from '@/lib/settings-injection'
+import { appliesToNode, applyPostProcessing } from '@/lib/post-processing'
+import { DEFAULT_GUARD_RETRIES, buildGuardNudge, chapterLengthChecks, evaluateOutputGuard } from '@/lib/output-guard'
+import { resolveSampling } from '@/lib/ai/sampling'
 import { ExecutionContext, NodeExecutionState } from './context'
 
+// 看门狗中止请求时的错误信息
+const WATCHDOG_ERROR = '请求长时间无响应，已被看门狗中止'
+
+// AI 节点的单次请求参数
+interface AIChatRequestOptions {
+  provider: AIChatConfig['provider']
+  model: string
+  messages: Message[]
+  temperature?: number
+  maxTokens?: number
+  topP?: number
+  presencePenalty?: number
+  frequencyPenalty?: number
+  thinkingConfig?: {
+    thinkingLevel?: 'low' | 'high'
+    thinkingBudget?: number
+    effort?: 'low' | 'medium' | 'high'
+  }
+}
+
 // 执行器状态
 export type ExecutorStatus = 'idle' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled' | 'timeout'
 
@@ -52,8 +79,12 @@ export interface ExecutionEvent {
   nodeType?: string
   content?: string
   usage?: TokenUsage
+  firstTokenAt?: Date                  // 收到首个流式片段的时间（仅 AI 节点）
   error?: string
   resolvedConfig?: ResolvedNodeConfig  // 解析后的节点配置
+  variables?: Record<string, string>   // 写入变量的节点完成后的变量快照
+  postProcessing?: PostProcessingApplication[]  // 节点输出触发的后处理规则
+  outputGuard?: OutputGuardReport      // AI 节点输出守卫的评估与重试记录
   timestamp: Date
 }
 
@@ -77,7 +108,10 @@ export interface ExecutorOptions {
   initialInput?: string
   settings?: Setting[]           // 项目设定
   settingPrompts?: SettingPrompt[] // 设定注入提示词模板
+  synopsis?: string              // 项目滚动梗概，提示词中以 {{synopsis}} 引用
+  chapterLength?: ChapterLengthRange // 项目章节字数范围，开启检查时附加到最后一个 AI 节点的输出守卫
   onEvent?: ExecutionEventListener
+  onHeartbeat?: (nodeId: string) => void  // 节点开始/结束、每次请求与每个流式片段时调用，供看门狗检测卡住的执行
 }
 
 /**
@@ -91,10 +125,15 @@ export class WorkflowExecutor {
   private status: ExecutorStatus = 'idle'
   private currentNodeIndex: number = 0
   private onEvent?: ExecutionEventListener
+  private onHeartbeat?: (nodeId: string) => void
   
   // 设定库
   private settings: Setting[] = []
   private settingPrompts: SettingPrompt[] = []
+
+  // 章节字数范围，及其检查的最后一个 AI 节点
+  private chapterLength?: ChapterLengthRange
+  private finalAINodeId?: string
   
   // 暂停控制
   private pausePromise: Promise<void> | null = null
@@ -107,9 +146,18 @@ export class WorkflowExecutor {
   private abortController: AbortController | null = null
   // 最近完成节点的 token 使用量（仅 AI 节点）
   private lastNodeTokenUsage: TokenUsage | undefined
+  // 最近完成节点收到首个流式片段的时间（仅 AI 节点）
+  private lastNodeFirstTokenAt: Date | undefined
+  // 最近执行节点的输出守卫记录（仅配置了守卫的 AI 节点）
+  private lastNodeOutputGuard: OutputGuardReport | undefined
 
   // 暂停触发的流中断标记
   private isPauseAborting: boolean = false
+
+  // 看门狗触发的流中断标记
+  private isWatchdogAborting: boolean = false
+  // 看门狗触发时没有进行中的请求，下一个节点开始前使执行失败
+  private watchdogFailed: boolean = false
   
   // 流程控制
   private shouldEnd: boolean = false        // 是否结束工作流
@@ -122,13 +170,17 @@ export class WorkflowExecutor {
     this.nodes = options.nodes
     this.globalConfig = options.globalConfig
     this.onEvent = options.onEvent
+    this.onHeartbeat = options.onHeartbeat
     this.settings = options.settings || []
     this.settingPrompts = options.settingPrompts || []
+    this.chapterLength = options.chapterLength
+    this.finalAINodeId = [...this.nodes].reverse().find(node => node.type === 'ai_chat')?.id
     this.context = new ExecutionContext({
       initialInput: options.initialInput,
       maxLoopCount: this.workflow.loop_max_count,
       timeoutSeconds: this.workflow.timeout_seconds,
     })
+    this.context.setVariable('synopsis', options.synopsis ?? '')
     
     // 初始化所有节点状态
     this.nodes.forEach(node => {
@@ -207,6 +259,9 @@ export class WorkflowExecutor {
         const node = this.nodes[this.currentNodeIndex]
         
         try {
+          if (this.watchdogFailed) {
+            throw new Error(WATCHDOG_ERROR)
+          }
           await this.executeNode(node)
           
           // 检查是否需要结束工作流
@@ -267,6 +322,7 @@ export class WorkflowExecutor {
             nodeName: node.name,
             nodeType: node.type,
             error: errorMessage,
+            outputGuard: this.lastNodeOutputGuard,
           })
           
           this.status = 'failed'
@@ -323,10 +379,13 @@ export class WorkflowExecutor {
       nodeName: node.name,
       nodeType: node.type,
     })
+    this.onHeartbeat?.(node.id)
 
     let output: string = ''
     let resolvedConfig: ResolvedNodeConfig = {}
     this.lastNodeTokenUsage = undefined
+    this.lastNodeFirstTokenAt = undefined
+    this.lastNodeOutputGuard = undefined
 
     // 根据节点类型执行
     switch (node.type) {
@@ -425,6 +484,15 @@ export class WorkflowExecutor {
         return
     }
 
+    // 按工作流规则后处理 AI 节点与输出节点的输出
+    let postProcessing: PostProcessingApplication[] | undefined
+    const postProcessingConfig = this.workflow.post_processing
+    if (postProcessingConfig && appliesToNode(postProcessingConfig, node.type)) {
+      const processed = applyPostProcessing(postProcessingConfig, output)
+      output = processed.output
+      postProcessing = processed.applied
+    }
+
     // 更新节点状态为完成
     this.context.updateNodeState(node.id, {
       status: 'completed',
@@ -442,8 +510,13 @@ export class WorkflowExecutor {
       nodeType: node.type,
       content: output,
       usage: this.lastNodeTokenUsage,
+      firstTokenAt: this.lastNodeFirstTokenAt,
       resolvedConfig,
+      variables: node.type === 'var_update' ? this.context.getAllVariables() : undefined,
+      postProcessing,
+      outputGuard: this.lastNodeOutputGuard,
     })
+    this.onHeartbeat?.(node.id)
   }
 
   /**
@@ -499,7 +572,9 @@ export class WorkflowExecutor {
 
     // 构建系统提示词（变量插值），兼容旧版 prompt 字段
     const systemPromptRaw = config.system_prompt ?? legacyConfig.prompt ?? ''
-    let systemPrompt = systemPromptRaw ? this.context.interpolateStrict(systemPromptRaw) : ''
+    let systemPrompt = systemPromptRaw
+      ? this.context.interpolateStrict(resolveSettingReferences(systemPromptRaw, this.settings))
+      : ''
 
     // 注入设定到系统提示词（使用智能注入引擎）
     const settingsInjection = generateSettingsInjection(
@@ -513,7 +588,9 @@ export class WorkflowExecutor {
     }
 
     // 构建用户问题（变量插值）
-    const userPrompt = config.user_prompt ? this.context.interpolateStrict(config.user_prompt) : ''
+    const userPrompt = config.user_prompt
+      ? this.context.interpolateStrict(resolveSettingReferences(config.user_prompt, this.settings))
+      : ''
     
     // 更新节点输入（显示用户问题）
     this.context.updateNodeState(node.id, { input: userPrompt })
@@ -531,6 +608,17 @@ export class WorkflowExecutor {
     if (systemPrompt) {
       messages.push({ role: 'system', content: systemPrompt })
     }
+
+    // chat_history 模式：前序 AI 节点的问答作为对话历史，按轮数与 token 预算裁剪
+    const contextMode = this.workflow.context_mode ?? 'stitched'
+    if (contextMode === 'chat_history') {
+      const reservedTokens = estimateTokens(systemPrompt) + estimateTokens(userPrompt)
+      messages.push(...this.context.getChatHistory(
+        this.workflow.context_turn_limit ?? 10,
+        this.workflow.context_token_budget ?? 0,
+        reservedTokens,
+      ))
+    }
     
     // 添加用户消息
     if (userPrompt) {
@@ -540,23 +628,27 @@ export class WorkflowExecutor {
     }
 
     const retryCount = Math.max(0, config.retry_count ?? 3)
-    const aiResult = await this.executeAIChatWithRetry(
-      node,
-      {
-        provider: config.provider,
-        model: config.model,
-        messages,
-        temperature: config.temperature,
-        maxTokens: config.max_tokens,
-        topP: config.top_p,
-        thinkingConfig: {
-          thinkingLevel: config.thinking_level,
-          thinkingBudget: config.thinking_budget,
-          effort: config.effort,
-        },
+    const sampling = resolveSampling(config)
+    const requestOptions = {
+      provider: config.provider,
+      model: config.model,
+      messages,
+      temperature: sampling.temperature,
+      maxTokens: sampling.max_tokens,
+      topP: sampling.top_p,
+      presencePenalty: sampling.presence_penalty,
+      frequencyPenalty: sampling.frequency_penalty,
+      thinkingConfig: {
+        thinkingLevel: config.thinking_level,
+        thinkingBudget: config.thinking_budget,
+        effort: config.effort,
       },
-      retryCount
-    )
+    }
+    let aiResult = await this.executeAIChatWithRetry(node, requestOptions, retryCount)
+    const guard = this.resolveOutputGuard(node, config.output_guard)
+    if (guard) {
+      aiResult = await this.applyOutputGuard(node, guard, requestOptions, retryCount, aiResult)
+    }
 
     const fullOutput = aiResult.output
 
@@ -567,6 +659,9 @@ export class WorkflowExecutor {
       }
       this.context.addToHistory(node.id, { role: 'assistant', content: fullOutput })
     }
+    if (contextMode === 'chat_history') {
+      this.context.addChatTurn(userPrompt || systemPrompt, fullOutput)
+    }
 
     // 获取使用的设定名称
     const settingNames = (config.setting_ids || [])
@@ -583,43 +678,116 @@ export class WorkflowExecutor {
         // 提示词
         systemPrompt,
         userPrompt,
-        // 高级设置
-        temperature: config.temperature,
-        maxTokens: config.max_tokens,
-        topP: config.top_p,
+        // 高级设置（生效的采样参数）
+        temperature: sampling.temperature,
+        maxTokens: sampling.max_tokens,
+        topP: sampling.top_p,
+        presencePenalty: sampling.presence_penalty,
+        frequencyPenalty: sampling.frequency_penalty,
         tokenUsage: aiResult.usage,
         // 对话历史
         enableHistory: config.enable_history,
         historyCount: config.history_count,
+        contextMode,
+        messages: contextMode === 'chat_history' ? messages : undefined,
         // 使用的设定
         settingNames: settingNames.length > 0 ? settingNames : undefined,
       },
     }
   }
 
-  private async executeAIChatWithRetry(
+  /**
+   * 节点生效的输出守卫：最后一个 AI 节点在开启章节字数检查时追加字数检查项，
+   * 节点未配置守卫时按章节字数范围的处理方式重试或警告
+   */
+  private resolveOutputGuard(node: WorkflowNode, guard?: OutputGuardConfig): OutputGuardConfig | undefined {
+    const checks = guard?.checks ?? []
+    const range = this.chapterLength
+    if (range && range.enforcement !== 'off' && node.id === this.finalAINodeId) {
+      const lengthChecks = chapterLengthChecks(range)
+      if (lengthChecks.length > 0) {
+        return { ...guard, checks: [...checks, ...lengthChecks], action: guard?.action ?? range.enforcement }
+      }
+    }
+    return guard && checks.length > 0 ? guard : undefined
+  }
+
+  /**
+   * 按输出守卫检查 AI 输出：retry 模式在系统提示词末尾追加纠正提示后重新请求，
+   * warn 模式接受输出并记录警告，fail 模式与重试用尽时令节点失败。
+   * 每次评估都记录到 lastNodeOutputGuard，重试的 token 用量累加到节点用量。
+   */
+  private async applyOutputGuard(
     node: WorkflowNode,
-    requestOptions: {
-      provider: AIChatConfig['provider']
-      model: string
-      messages: Message[]
-      temperature?: number
-      maxTokens?: number
-      topP?: number
-      thinkingConfig?: {
-        thinkingLevel?: 'low' | 'high'
-        thinkingBudget?: number
-        effort?: 'low' | 'medium' | 'high'
+    guard: OutputGuardConfig,
+    requestOptions: AIChatRequestOptions,
+    retryCount: number,
+    firstResult: { output: string; usage?: TokenUsage }
+  ): Promise<{ output: string; usage?: TokenUsage }> {
+    const maxRetries = guard.action === 'retry'
+      ? Math.max(0, guard.max_retries ?? DEFAULT_GUARD_RETRIES)
+      : 0
+    const report: OutputGuardReport = { action: guard.action, evaluations: [], retries: 0, outcome: 'passed' }
+    this.lastNodeOutputGuard = report
+
+    let result = firstResult
+    let usage = firstResult.usage
+    for (let attempt = 1; ; attempt++) {
+      const failures = evaluateOutputGuard(guard.checks, result.output)
+      report.evaluations.push({ attempt, passed: failures.length === 0, failures })
+      if (failures.length === 0) {
+        return { output: result.output, usage }
       }
-    },
+      if (guard.action === 'warn') {
+        report.outcome = 'warned'
+        return { output: result.output, usage }
+      }
+      if (report.retries >= maxRetries) {
+        report.outcome = 'failed'
+        throw new Error(`输出未通过守卫检查: ${failures.join('；')}`)
+      }
+
+      report.retries++
+      const nudge = buildGuardNudge(guard, failures)
+      result = await this.executeAIChatWithRetry(
+        node,
+        { ...requestOptions, messages: this.withSystemNudge(requestOptions.messages, nudge) },
+        retryCount
+      )
+      usage = result.usage && usage
+        ? {
+            promptTokens: usage.promptTokens + result.usage.promptTokens,
+            completionTokens: usage.completionTokens + result.usage.completionTokens,
+            totalTokens: usage.totalTokens + result.usage.totalTokens,
+          }
+        : result.usage ?? usage
+    }
+  }
+
+  /**
+   * 将纠正提示追加到首条系统消息，没有系统消息时在开头插入一条
+   */
+  private withSystemNudge(messages: Message[], nudge: string): Message[] {
+    const index = messages.findIndex(m => m.role === 'system')
+    if (index === -1) {
+      return [{ role: 'system', content: nudge }, ...messages]
+    }
+    return messages.map((m, i) => i === index ? { ...m, content: `${m.content}\n\n${nudge}` } : m)
+  }
+
+  private async executeAIChatWithRetry(
+    node: WorkflowNode,
+    requestOptions: AIChatRequestOptions,
     retryCount: number
   ): Promise<{ output: string; usage?: TokenUsage }> {
     let lastError: unknown = null
 
     for (let attempt = 0; attempt <= retryCount; attempt++) {
       this.abortController = new AbortController()
+      this.onHeartbeat?.(node.id)
       let fullOutput = ''
       let usage: TokenUsage | undefined
+      this.lastNodeFirstTokenAt = undefined
 
       try {
         await chatStream({
@@ -640,7 +808,12 @@ export class WorkflowExecutor {
             throw new Error('执行超时')
           }
 
+          this.onHeartbeat?.(node.id)
+
           if (!chunk.done) {
+            if (!this.lastNodeFirstTokenAt) {
+              this.lastNodeFirstTokenAt = new Date()
+            }
             fullOutput += chunk.content
             this.emit({
               type: 'node_streaming',
@@ -667,7 +840,9 @@ export class WorkflowExecutor {
           throw new Error('执行已取消')
         }
 
-        const message = this.toErrorMessage(error)
+        // 看门狗中止的请求与普通失败一样按节点的重试设置处理
+        const message = this.isWatchdogAborting ? WATCHDOG_ERROR : this.toErrorMessage(error)
+        this.isWatchdogAborting = false
         const isLastAttempt = attempt >= retryCount
 
         if (isLastAttempt) {
@@ -1591,6 +1766,21 @@ ${input}`
     }
   }
 
+  /**
+   * 看门狗触发（执行长时间没有心跳）：中止进行中的 AI 请求，按节点的重试设置重试或失败；
+   * 没有进行中的请求时使执行失败。暂停中的执行不处理
+   */
+  abortStalled(): void {
+    if (this.status !== 'running') return
+
+    if (this.abortController) {
+      this.isWatchdogAborting = true
+      this.abortController.abort()
+    } else {
+      this.watchdogFailed = true
+    }
+  }
+
   /**
    * 修改节点输出（人工干预）
    */
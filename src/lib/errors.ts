@@ -12,11 +12,28 @@ interface HandleErrorOptions extends LogErrorOptions {
   silent?: boolean
 }
 
+// 只读项目的写入被数据库触发器拒绝时的错误标识
+const READ_ONLY_PROJECT = 'ReadOnlyProject'
+const READ_ONLY_PROJECT_MESSAGE = '项目为只读审阅副本, 请先转换为可编辑项目'
+
+/**
+ * 判断错误是否由只读审阅副本引起（后端 { kind: 'ReadOnlyProject' } 或前端写库时的触发器错误）
+ */
+export function isReadOnlyProjectError(error: unknown): boolean {
+  if (typeof error === 'object' && error !== null
+    && (error as { kind?: unknown }).kind === READ_ONLY_PROJECT) {
+    return true
+  }
+  const message = typeof error === 'string' ? error : error instanceof Error ? error.message : ''
+  return message.includes(READ_ONLY_PROJECT)
+}
+
 /**
  * 提取错误信息，统一兜底
  */
 export function getErrorMessage(error: unknown, fallback = '未知错误'): string {
   if (!error) return fallback
+  if (isReadOnlyProjectError(error)) return READ_ONLY_PROJECT_MESSAGE
   if (typeof error === 'string') return error
   if (error instanceof Error) return error.message || fallback
 
@@ -0,0 +1,81 @@
+// use-recovery-draft Hook 测试
+// 测试草稿的读取、防抖保存与清除
+
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest"
+import { renderHook, act, waitFor } from "@testing-library/react"
+import { invoke } from "@tauri-apps/api/core"
+import { useRecoveryDraft, RECOVERY_DRAFT_DELAY } from "../use-recovery-draft"
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}))
+
+describe("useRecoveryDraft", () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset()
+    vi.mocked(invoke).mockResolvedValue(null)
+    window.__TAURI_INTERNALS__ = {}
+  })
+
+  afterEach(() => {
+    vi.useRealTimers()
+    delete window.__TAURI_INTERNALS__
+  })
+
+  it("应该读取已有的草稿", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce({
+      scope_key: "node-config:n1",
+      payload: JSON.stringify({ name: "撰写正文" }),
+      size_bytes: 22,
+      updated_at: "2026-01-01T00:00:00.000Z",
+    })
+
+    const { result } = renderHook(() => useRecoveryDraft("node-config:n1", { name: "撰写" }, false))
+
+    await waitFor(() => expect(result.current.draft).toEqual({ name: "撰写正文" }))
+    expect(result.current.savedAt).toBe("2026-01-01T00:00:00.000Z")
+    expect(invoke).toHaveBeenCalledWith("get_recovery_draft", { scopeKey: "node-config:n1" })
+  })
+
+  it("应该只在有修改时防抖保存", async () => {
+    vi.useFakeTimers()
+    const { rerender } = renderHook(
+      ({ value, dirty }) => useRecoveryDraft("node-config:n1", value, dirty),
+      { initialProps: { value: { name: "撰写" }, dirty: false } }
+    )
+
+    act(() => {
+      vi.advanceTimersByTime(RECOVERY_DRAFT_DELAY)
+    })
+    expect(invoke).not.toHaveBeenCalledWith("save_recovery_draft", expect.anything())
+
+    rerender({ value: { name: "撰写正" }, dirty: true })
+    rerender({ value: { name: "撰写正文" }, dirty: true })
+    act(() => {
+      vi.advanceTimersByTime(RECOVERY_DRAFT_DELAY)
+    })
+    const saves = vi.mocked(invoke).mock.calls.filter(([cmd]) => cmd === "save_recovery_draft")
+    expect(saves).toEqual([
+      ["save_recovery_draft", { scopeKey: "node-config:n1", payloadJson: '{"name":"撰写正文"}' }],
+    ])
+  })
+
+  it("清除时应该取消待保存的草稿", async () => {
+    vi.useFakeTimers()
+    const { result } = renderHook(() => useRecoveryDraft("node-config:n1", { name: "撰写正文" }, true))
+
+    await act(async () => {
+      await result.current.clear()
+      vi.advanceTimersByTime(RECOVERY_DRAFT_DELAY)
+    })
+    expect(invoke).toHaveBeenCalledWith("clear_recovery_draft", { scopeKey: "node-config:n1" })
+    expect(invoke).not.toHaveBeenCalledWith("save_recovery_draft", expect.anything())
+    expect(result.current.draft).toBeNull()
+  })
+
+  it("非 Tauri 环境下不读写草稿", () => {
+    delete window.__TAURI_INTERNALS__
+    renderHook(() => useRecoveryDraft("node-config:n1", { name: "撰写" }, true))
+    expect(invoke).not.toHaveBeenCalled()
+  })
+})
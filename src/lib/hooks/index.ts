@@ -9,4 +9,6 @@ export { useNodeSelection } from './use-node-selection'
 export type { UseNodeSelectionReturn, SelectionMode } from './use-node-selection'
 
 export { useRunButtonState } from './use-run-button-state'
-export type { RunButtonState } from './use-run-button-state'
\ No newline at end of file
+export type { RunButtonState } from './use-run-button-state'
+export { useRecoveryDraft, RECOVERY_DRAFT_DELAY } from './use-recovery-draft'
+export type { UseRecoveryDraftReturn } from './use-recovery-draft'
@@ -0,0 +1,85 @@
+// 恢复草稿 Hook
+// 编辑中的状态防抖保存到数据库，webview 崩溃后下次打开同一作用域时可恢复
+
+import { useCallback, useEffect, useMemo, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { debounce } from '@/lib/utils'
+import { handleAppError } from '@/lib/errors'
+import type { RecoveryDraft } from '@/types'
+
+/** 停止编辑多久后保存草稿（毫秒） */
+export const RECOVERY_DRAFT_DELAY = 1000
+
+export interface UseRecoveryDraftReturn<T> {
+  /** 上次未保存的内容，没有草稿时为 null */
+  draft: T | null
+  /** 草稿保存时间 */
+  savedAt: string | null
+  /** 删除草稿（保存成功或放弃恢复时调用） */
+  clear: () => Promise<void>
+}
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+/**
+ * 为一个编辑作用域维护恢复草稿
+ * @param scopeKey 作用域键，如 `node-config:<节点 ID>`；为 null 时不读写
+ * @param value 当前编辑中的状态
+ * @param dirty 是否有未保存的修改，仅在有修改时保存草稿
+ */
+export function useRecoveryDraft<T>(
+  scopeKey: string | null,
+  value: T,
+  dirty: boolean
+): UseRecoveryDraftReturn<T> {
+  const [draft, setDraft] = useState<{ value: T; savedAt: string } | null>(null)
+
+  const save = useMemo(
+    () =>
+      debounce((key: string, payload: string) => {
+        invoke('save_recovery_draft', { scopeKey: key, payloadJson: payload }).catch((error) =>
+          handleAppError({ error, context: '保存恢复草稿', silent: true })
+        )
+      }, RECOVERY_DRAFT_DELAY),
+    []
+  )
+
+  useEffect(() => () => save.cancel(), [save])
+
+  // 切换作用域时读取已有的草稿
+  useEffect(() => {
+    setDraft(null)
+    save.cancel()
+    if (!scopeKey || !isTauri()) return
+    let cancelled = false
+    invoke<RecoveryDraft | null>('get_recovery_draft', { scopeKey })
+      .then((found) => {
+        if (cancelled || !found) return
+        setDraft({ value: JSON.parse(found.payload) as T, savedAt: found.updated_at })
+      })
+      .catch((error) => handleAppError({ error, context: '读取恢复草稿', silent: true }))
+    return () => {
+      cancelled = true
+    }
+  }, [scopeKey, save])
+
+  useEffect(() => {
+    if (!scopeKey || !dirty || !isTauri()) return
+    save(scopeKey, JSON.stringify(value))
+  }, [scopeKey, value, dirty, save])
+
+  const clear = useCallback(async () => {
+    save.cancel()
+    setDraft(null)
+    if (!scopeKey || !isTauri()) return
+    try {
+      await invoke('clear_recovery_draft', { scopeKey })
+    } catch (error) {
+      handleAppError({ error, context: '删除恢复草稿', silent: true })
+    }
+  }, [scopeKey, save])
+
+  return { draft: draft?.value ?? null, savedAt: draft?.savedAt ?? null, clear }
+}
@@ -2,9 +2,16 @@
  * 导入/导出工具函数
  * 封装文件选择和保存的逻辑
  */
+import { invoke } from '@tauri-apps/api/core'
 import { open, save } from '@tauri-apps/plugin-dialog'
 import { readTextFile, writeTextFile } from '@tauri-apps/plugin-fs'
-import type { ExportedWorkflow, ExportedSettings, ExportedProject } from '@/types'
+import type {
+  ExportedWorkflow,
+  ExportedSettings,
+  ExportedProject,
+  Project,
+  ProjectExportSelection,
+} from '@/types'
 import * as db from '@/lib/db'
 import { logError } from '@/lib/errors'
 
@@ -14,6 +21,15 @@ const JSON_FILTERS = [
   { name: '所有文件', extensions: ['*'] },
 ]
 
+// 审阅副本的格式标识（与后端 REVIEW_COPY_FORMAT 一致）
+const REVIEW_COPY_FORMAT = 'chouann-review-copy'
+
+// 项目备份文件过滤器，.chbak 与应用关联，双击即可打开导入
+const PROJECT_BACKUP_FILTERS = [
+  { name: '项目备份', extensions: ['chbak', 'json'] },
+  { name: '所有文件', extensions: ['*'] },
+]
+
 /**
  * 导出工作流到文件
  */
@@ -145,8 +161,8 @@ export async function exportProjectToFile(projectId: string): Promise<boolean> {
 
     const path = await save({
       title: '导出项目备份',
-      defaultPath: `${data.project.name}_备份.json`,
-      filters: JSON_FILTERS,
+      defaultPath: `${data.project.name}_备份.chbak`,
+      filters: PROJECT_BACKUP_FILTERS,
     })
 
     if (path) {
@@ -161,15 +177,79 @@ export async function exportProjectToFile(projectId: string): Promise<boolean> {
 }
 
 /**
- * 从备份文件恢复项目
+ * 按选择范围导出部分项目（选定的工作流、设定分类，可选章节、大纲与设定提示词），
+ * 返回清单中的引用一致性警告；用户取消保存时返回 null
+ */
+export async function exportProjectPartialToFile(
+  projectId: string,
+  selection: ProjectExportSelection
+): Promise<{ warnings: string[] } | null> {
+  try {
+    const data = await db.exportProjectPartial(projectId, selection)
+    if (!data) {
+      throw new Error('项目不存在')
+    }
+
+    const path = await save({
+      title: '导出部分项目',
+      defaultPath: `${data.project.name}_部分导出.chbak`,
+      filters: PROJECT_BACKUP_FILTERS,
+    })
+
+    if (path) {
+      await writeTextFile(path, JSON.stringify(data, null, 2))
+      return { warnings: data.manifest?.warnings ?? [] }
+    }
+    return null
+  } catch (error) {
+    logError({ error, context: '导出部分项目' })
+    throw error
+  }
+}
+
+/**
+ * 导出只读审阅副本（只含章节正文、大纲与选定设定，不含工作流与提示词），可设密码加密
+ */
+export async function exportReviewCopyToFile(
+  projectId: string,
+  projectName: string,
+  options: { password?: string; settingIds?: string[] } = {}
+): Promise<boolean> {
+  try {
+    const path = await save({
+      title: '导出审阅副本',
+      defaultPath: `${projectName}_审阅副本.chbak`,
+      filters: PROJECT_BACKUP_FILTERS,
+    })
+
+    if (!path) {
+      return false
+    }
+
+    await invoke('export_review_copy', {
+      projectId,
+      path,
+      password: options.password || null,
+      settingIds: options.settingIds ?? [],
+    })
+    return true
+  } catch (error) {
+    logError({ error, context: '导出审阅副本' })
+    throw error
+  }
+}
+
+/**
+ * 从备份文件恢复项目；提供 mergeIntoProjectId 时合并到已有项目
  */
 export async function importProjectFromFile(
-  newName?: string
+  newName?: string,
+  mergeIntoProjectId?: string
 ): Promise<{ success: boolean; project?: Awaited<ReturnType<typeof db.importProject>> }> {
   try {
     const path = await open({
       title: '导入项目备份',
-      filters: JSON_FILTERS,
+      filters: PROJECT_BACKUP_FILTERS,
       multiple: false,
     })
 
@@ -177,15 +257,7 @@ export async function importProjectFromFile(
       return { success: false }
     }
 
-    const content = await readTextFile(path as string)
-    const data = JSON.parse(content) as ExportedProject
-
-    // 验证数据格式
-    if (!data.version || !data.project || !data.workflows || !data.settings) {
-      throw new Error('无效的项目备份文件格式')
-    }
-
-    const project = await db.importProject(data, newName)
+    const project = await importProjectFromPath(path as string, newName, undefined, mergeIntoProjectId)
     return { success: true, project }
   } catch (error) {
     logError({ error, context: '导入项目' })
@@ -193,6 +265,34 @@ export async function importProjectFromFile(
   }
 }
 
+/**
+ * 从指定路径的备份文件恢复项目（文件关联打开的备份也经由此导入）；
+ * 审阅副本由后端导入为只读项目，加密的副本须提供密码；
+ * 提供 mergeIntoProjectId 时把备份（通常是部分导出）合并到已有项目
+ */
+export async function importProjectFromPath(
+  path: string,
+  newName?: string,
+  password?: string,
+  mergeIntoProjectId?: string
+): Promise<Project> {
+  const content = await readTextFile(path)
+  const data = JSON.parse(content) as ExportedProject & { format?: string }
+
+  if (data.format === REVIEW_COPY_FORMAT) {
+    return invoke<Project>('import_review_copy', { path, password: password || null })
+  }
+
+  // 验证数据格式
+  if (!data.version || !data.project || !data.workflows || !data.settings) {
+    throw new Error('无效的项目备份文件格式')
+  }
+
+  return mergeIntoProjectId
+    ? db.importProject(data, newName, mergeIntoProjectId)
+    : db.importProject(data, newName)
+}
+
 /**
  * 验证导出文件格式
  */
@@ -0,0 +1,49 @@
+/**
+ * 离线模式
+ * 开启后阻止所有对外网络请求。Tauri 环境由后端 ensure_network_allowed 判断并发出
+ * network:blocked 事件；Web 环境依据全局配置的 offline_mode 判断。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import type { GlobalConfig } from '@/types'
+
+// 被离线模式阻止的请求目标
+export type NetworkTarget = 'ai' | 'ai_execution' | 'updater'
+
+export class OfflineModeError extends Error {
+  readonly kind = 'OfflineMode'
+
+  constructor(readonly target: string) {
+    super(`离线模式已开启, 已阻止网络请求: ${target}`)
+    this.name = 'OfflineModeError'
+  }
+}
+
+/**
+ * 判断错误是否由离线模式引起（包括后端返回的 { kind: 'OfflineMode' }）
+ */
+export function isOfflineModeError(error: unknown): boolean {
+  return typeof error === 'object' && error !== null
+    && (error as { kind?: unknown }).kind === 'OfflineMode'
+}
+
+/**
+ * 发起网络请求前调用，离线模式下抛出 OfflineModeError
+ */
+export async function ensureNetworkAllowed(
+  target: NetworkTarget,
+  globalConfig?: Pick<GlobalConfig, 'offline_mode'>
+): Promise<void> {
+  if (typeof window !== 'undefined' && window.__TAURI_INTERNALS__) {
+    try {
+      await invoke('ensure_network_allowed', { target })
+    } catch (error) {
+      if (isOfflineModeError(error)) throw new OfflineModeError(target)
+      throw error
+    }
+    return
+  }
+  if (globalConfig?.offline_mode) {
+    throw new OfflineModeError(target)
+  }
+}
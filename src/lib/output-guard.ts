@@ -0,0 +1,93 @@
+/**
+ * 输出守卫
+ * 校验 AI 节点输出的语言与格式（中日韩字符比例、禁止前缀、最少字数），
+ * 由执行引擎根据结果追加纠正提示重试、接受并警告或令节点失败。
+ */
+
+import type { ChapterLengthRange, OutputGuardCheck, OutputGuardConfig } from '@/types'
+
+// retry 模式未配置 max_retries 时的重试次数
+export const DEFAULT_GUARD_RETRIES = 2
+
+const CJK_RE = /[\p{Script=Han}\p{Script=Hiragana}\p{Script=Katakana}\p{Script=Hangul}]/u
+const WORD_RE = /[\p{L}\p{N}]/u
+
+/**
+ * 中日韩字符占文字字符（字母与数字，不含空白和标点）的比例，无文字时为 0
+ */
+export function cjkRatio(text: string): number {
+  let words = 0
+  let cjk = 0
+  for (const c of text) {
+    if (!WORD_RE.test(c)) continue
+    words++
+    if (CJK_RE.test(c)) cjk++
+  }
+  return words === 0 ? 0 : cjk / words
+}
+
+function checkFailure(check: OutputGuardCheck, output: string): string | null {
+  switch (check.type) {
+    case 'min_cjk_ratio': {
+      const ratio = cjkRatio(output)
+      return ratio < check.value
+        ? `中文字符比例 ${Math.round(ratio * 100)}% 低于 ${Math.round(check.value * 100)}%`
+        : null
+    }
+    case 'forbid_prefix':
+      return check.value && output.trimStart().startsWith(check.value)
+        ? `输出以 "${check.value}" 开头`
+        : null
+    case 'min_length': {
+      const length = Array.from(output.trim()).length
+      return length < check.value ? `输出 ${length} 字，少于 ${check.value} 字` : null
+    }
+    case 'max_length': {
+      const length = Array.from(output.trim()).length
+      return length > check.value ? `输出 ${length} 字，超过 ${check.value} 字` : null
+    }
+    default:
+      return null
+  }
+}
+
+/**
+ * 依次执行全部检查，返回未通过项的说明，全部通过时为空数组
+ */
+export function evaluateOutputGuard(checks: OutputGuardCheck[], output: string): string[] {
+  return checks
+    .map(check => checkFailure(check, output))
+    .filter((failure): failure is string => failure !== null)
+}
+
+/**
+ * 生成重试时追加到系统提示词的纠正提示
+ */
+export function buildGuardNudge(config: OutputGuardConfig, failures: string[]): string {
+  if (config.nudge) return config.nudge
+  const requirements = config.checks.map(check => {
+    switch (check.type) {
+      case 'min_cjk_ratio':
+        return '必须使用中文回答'
+      case 'forbid_prefix':
+        return `不要以 "${check.value}" 开头`
+      case 'min_length':
+        return `正文不少于 ${check.value} 字（不足时扩写）`
+      case 'max_length':
+        return `正文不超过 ${check.value} 字（超出时压缩）`
+      default:
+        return ''
+    }
+  }).filter(Boolean)
+  return `上一次输出未通过检查（${failures.join('；')}）。请重新输出，要求：${requirements.join('；')}。只输出正文，不要解释。`
+}
+
+/**
+ * 项目章节字数范围对应的检查项，由执行引擎附加到最后一个 AI 节点
+ */
+export function chapterLengthChecks(range: ChapterLengthRange): OutputGuardCheck[] {
+  const checks: OutputGuardCheck[] = []
+  if (range.min_chapter_chars) checks.push({ type: 'min_length', value: range.min_chapter_chars })
+  if (range.max_chapter_chars) checks.push({ type: 'max_length', value: range.max_chapter_chars })
+  return checks
+}
@@ -0,0 +1,63 @@
+/**
+ * 项目部分导出工具
+ * 检查选中的工作流与设定分类之间的引用一致性，并在导入时重映射节点引用的设定 ID
+ */
+
+import type { Setting, SettingCategory } from '@/types'
+
+// 分类中文标签
+const CATEGORY_LABELS: Record<SettingCategory, string> = {
+  character: '角色',
+  worldview: '世界观',
+  style: '笔触风格',
+  outline: '大纲',
+}
+
+type WorkflowWithNodes = {
+  workflow: { name: string }
+  nodes: Array<{ name: string; config: unknown }>
+}
+
+// 节点配置中引用的设定 ID（AI 节点的 setting_ids）
+function referencedSettingIds(config: unknown): string[] {
+  const ids = (config as { setting_ids?: unknown } | null)?.setting_ids
+  return Array.isArray(ids) ? ids.filter((id): id is string => typeof id === 'string') : []
+}
+
+/**
+ * 列出选中的工作流中引用了未导出分类设定的节点，每处引用一条警告；
+ * 已不存在的设定不提示
+ */
+export function selectionWarnings(
+  workflows: WorkflowWithNodes[],
+  settings: Array<Pick<Setting, 'id' | 'name' | 'category'>>,
+  categories: SettingCategory[]
+): string[] {
+  const byId = new Map(settings.map((s) => [s.id, s]))
+  const warnings: string[] = []
+  for (const { workflow, nodes } of workflows) {
+    for (const node of nodes) {
+      for (const id of referencedSettingIds(node.config)) {
+        const setting = byId.get(id)
+        if (setting && !categories.includes(setting.category)) {
+          warnings.push(
+            `工作流「${workflow.name}」的节点「${node.name}」引用了未导出的${CATEGORY_LABELS[setting.category]}设定「${setting.name}」`
+          )
+        }
+      }
+    }
+  }
+  return warnings
+}
+
+/**
+ * 把节点配置中引用的设定 ID 替换为导入后的新 ID，未导入的设定保持原样
+ */
+export function remapSettingIds<T>(config: T, settingIdMap: Map<string, string>): T {
+  const ids = referencedSettingIds(config)
+  if (ids.length === 0) return config
+  return {
+    ...config,
+    setting_ids: ids.map((id) => settingIdMap.get(id) ?? id),
+  }
+}
@@ -0,0 +1,117 @@
+/**
+ * 输出后处理
+ * 按工作流配置的规则清理模型输出：去除开场白与代码围栏，规范省略号、引号与空行。
+ * 规则与 src-tauri/src/commands/post_processing.rs 保持一致，后端用于保存前校验与预览。
+ */
+
+import type {
+  NodeType,
+  PostProcessingApplication,
+  PostProcessingConfig,
+  PostProcessingRule,
+} from '@/types'
+
+// 未配置匹配模式时去除的开场白：以客套话开头、以冒号结尾的首行
+export const DEFAULT_META_PATTERNS = ['(?:好的|当然|没问题|以下是)[^\\n]{0,40}[:：][ \\t]*(?:\\n|$)']
+
+export interface PostProcessingResult {
+  output: string
+  applied: PostProcessingApplication[]
+}
+
+// 按码点计数，与后端的字符数一致
+function charCount(text: string): number {
+  return Array.from(text).length
+}
+
+function stripMetaPrefix(text: string, patterns: string[]): string {
+  const regexes = (patterns.length > 0 ? patterns : DEFAULT_META_PATTERNS)
+    .map(pattern => new RegExp(`^(?:${pattern})`, 'u'))
+  let rest = text.trimStart()
+  let stripped = false
+  for (;;) {
+    const length = regexes
+      .map(re => re.exec(rest)?.[0].length ?? 0)
+      .find(len => len > 0)
+    if (!length) break
+    rest = rest.slice(length).trimStart()
+    stripped = true
+  }
+  return stripped ? rest : text
+}
+
+function isCodeFence(line: string): boolean {
+  const trimmed = line.trim()
+  return trimmed.startsWith('```') && /^[\p{L}\p{N}_+-]*$/u.test(trimmed.slice(3))
+}
+
+function normalizeQuotes(text: string): string {
+  let open = true
+  return Array.from(text)
+    .map(c => {
+      if (c === '"') {
+        open = !open
+        return open ? '”' : '“'
+      }
+      if (c === '\n') open = true
+      return c
+    })
+    .join('')
+}
+
+function applyRule(rule: PostProcessingRule, text: string): string {
+  switch (rule.type) {
+    case 'strip_meta_prefix':
+      return stripMetaPrefix(text, rule.patterns ?? [])
+    case 'strip_code_fences':
+      return text.split('\n').filter(line => !isCodeFence(line)).join('\n')
+    case 'normalize_ellipsis':
+      return text.replace(/\.{3,}|。{3,}|…+/g, '……')
+    case 'normalize_quotes':
+      return normalizeQuotes(text)
+    case 'collapse_blank_lines':
+      return text.replace(/\n(?:[ \t]*\n){3,}/g, '\n\n\n')
+    default:
+      return text
+  }
+}
+
+/**
+ * 后处理是否作用于该类型节点：node_outputs 对应 AI 节点，final_output 对应输出节点
+ */
+export function appliesToNode(config: PostProcessingConfig | null | undefined, nodeType: NodeType): boolean {
+  if (!config || config.rules.length === 0) return false
+  const scope = config.scope ?? 'both'
+  if (nodeType === 'ai_chat') return scope !== 'final_output'
+  if (nodeType === 'output') return scope !== 'node_outputs'
+  return false
+}
+
+/**
+ * 按顺序执行全部规则，记录改动了输出的规则及删除的字符数
+ */
+export function applyPostProcessing(config: PostProcessingConfig, text: string): PostProcessingResult {
+  let output = text
+  const applied: PostProcessingApplication[] = []
+  for (const rule of config.rules) {
+    const next = applyRule(rule, output)
+    if (next !== output) {
+      applied.push({ rule: rule.type, chars_removed: charCount(output) - charCount(next) })
+      output = next
+    }
+  }
+  return { output, applied }
+}
+
+/**
+ * 解析数据库中的后处理配置，格式错误时视为未配置
+ */
+export function parsePostProcessingConfig(raw: unknown): PostProcessingConfig | null {
+  if (!raw) return null
+  try {
+    const config = typeof raw === 'string' ? JSON.parse(raw) : raw
+    return Array.isArray(config?.rules) ? (config as PostProcessingConfig) : null
+  } catch {
+    return null
+  }
+}
@@ -0,0 +1,98 @@
+/**
+ * 提供商健康统计
+ * AI 请求在前端发起，每次请求结束后上报延迟、错误类型与 token 数；后端累计滚动统计，
+ * 最近请求错误率过高时发出 provider:degraded 事件。仅 Tauri 环境统计。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { handleAppError } from '@/lib/errors'
+import type { AIProvider, ProviderHealth } from '@/types'
+
+// 上报的错误类型，与后端 provider_health::ERROR_CLASSES 一致
+export type ProviderErrorClass =
+  | 'timeout'
+  | 'rate_limit'
+  | 'server'
+  | 'network'
+  | 'auth'
+  | 'bad_request'
+  | 'other'
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+/**
+ * 按 HTTP 状态码与错误信息判断错误类型
+ */
+export function classifyProviderError(error: unknown): ProviderErrorClass {
+  const status = typeof error === 'object' && error !== null
+    ? (error as { statusCode?: unknown }).statusCode
+    : undefined
+  if (typeof status === 'number') {
+    if (status === 401 || status === 403) return 'auth'
+    if (status === 402 || status === 429) return 'rate_limit'
+    if (status === 408) return 'timeout'
+    if (status >= 500) return 'server'
+    if (status >= 400) return 'bad_request'
+  }
+
+  const message = (error instanceof Error ? error.message : String(error)).toLowerCase()
+  if (/\b40[13]\b/.test(message) || message.includes('unauthorized') || message.includes('forbidden') || message.includes('api key')) {
+    return 'auth'
+  }
+  if (/\b(402|429)\b/.test(message) || message.includes('rate limit') || message.includes('quota')) {
+    return 'rate_limit'
+  }
+  if (message.includes('timeout') || message.includes('timed out') || message.includes('aborted')) {
+    return 'timeout'
+  }
+  if (/\b5\d\d\b/.test(message) || message.includes('overloaded') || message.includes('internal server error') || message.includes('service unavailable') || message.includes('bad gateway')) {
+    return 'server'
+  }
+  if (message.includes('econnrefused') || message.includes('enotfound') || message.includes('econnreset') || message.includes('network') || message.includes('fetch failed') || message.includes('failed to fetch')) {
+    return 'network'
+  }
+  if (/\b4\d\d\b/.test(message) || message.includes('bad request') || message.includes('invalid')) {
+    return 'bad_request'
+  }
+  return 'other'
+}
+
+function report(provider: AIProvider, latencyMs: number, errorClass: ProviderErrorClass | null, tokens?: number) {
+  if (!isTauri()) return
+  invoke('record_provider_request', { providerId: provider, latencyMs, errorClass, tokens })
+    .catch((error) => handleAppError({ error, context: '记录提供商健康', silent: true }))
+}
+
+/**
+ * 开始统计一次 AI 请求，请求结束后调用 succeed 或 fail
+ * 用户主动取消的请求不计入统计
+ */
+export function trackProviderRequest(provider: AIProvider, signal?: AbortSignal) {
+  const startedAt = Date.now()
+  return {
+    succeed(tokens?: number) {
+      report(provider, Date.now() - startedAt, null, tokens)
+    },
+    fail(error: unknown) {
+      if (signal?.aborted) return
+      report(provider, Date.now() - startedAt, classifyProviderError(error))
+    },
+  }
+}
+
+/**
+ * 获取提供商最近若干小时的健康状态
+ */
+export async function getProviderHealth(provider: AIProvider, hours = 24): Promise<ProviderHealth> {
+  return invoke<ProviderHealth>('get_provider_health', { providerId: provider, hours })
+}
+
+/**
+ * 按健康程度排序候选提供商（未降级且最近错误率低的在前），供故障转移选择
+ */
+export async function rankProvidersByHealth(providers: AIProvider[]): Promise<AIProvider[]> {
+  if (!isTauri()) return providers
+  return invoke<AIProvider[]>('rank_providers_by_health', { providerIds: providers })
+}
@@ -142,6 +142,38 @@ function renderInjectionText(
   return parts.join('\n\n')
 }
 
+/**
+ * Render a setting's facts as a Markdown table (empty string when it has none)
+ */
+export function renderFactsTable(setting: Setting): string {
+  const entries = Object.entries(setting.facts ?? {})
+  if (entries.length === 0) return ''
+  const rows = entries.map(([key, value]) => `| ${key} | ${value} |`)
+  return ['| 属性 | 值 |', '| --- | --- |', ...rows].join('\n')
+}
+
+/**
+ * Replace {{setting:名称.facts.键}} and {{setting:名称.facts_table}} references.
+ * Unresolved references are left as-is (same rules as `injection::render_setting_refs`).
+ */
+export function resolveSettingReferences(template: string, settings: Setting[]): string {
+  return template.replace(/\{\{setting:([^}]+)\}\}/g, (match, reference: string) => {
+    const ref = reference.trim()
+    const find = (name: string) => settings.find((s) => s.name === name.trim())
+
+    if (ref.endsWith('.facts_table')) {
+      const setting = find(ref.slice(0, -'.facts_table'.length))
+      return setting ? renderFactsTable(setting) : match
+    }
+    const split = ref.indexOf('.facts.')
+    if (split >= 0) {
+      const value = find(ref.slice(0, split))?.facts?.[ref.slice(split + '.facts.'.length).trim()]
+      return value ?? match
+    }
+    return match
+  })
+}
+
 /**
  * Main injection function - generates settings injection text
  *
@@ -0,0 +1,69 @@
+/**
+ * 界面偏好
+ * 面板尺寸、上次打开的标签页、编辑器字号等。Tauri 环境保存在数据库中（后端合并短时间内的连续写入），
+ * 随数据库备份与 export_ui_prefs 导出迁移；Web 环境回退到 localStorage。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { handleAppError } from '@/lib/errors'
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+function readLocal(prefix: string): Record<string, unknown> {
+  const prefs: Record<string, unknown> = {}
+  try {
+    for (let i = 0; i < localStorage.length; i++) {
+      const key = localStorage.key(i)
+      if (!key?.startsWith(prefix)) continue
+      try {
+        prefs[key] = JSON.parse(localStorage.getItem(key) ?? 'null')
+      } catch {
+        // 无法解析的值按未设置处理
+      }
+    }
+  } catch {
+    // localStorage 不可用
+  }
+  return prefs
+}
+
+/**
+ * 读取以 prefix 开头的全部偏好
+ */
+export async function getUiPrefs(prefix = ''): Promise<Record<string, unknown>> {
+  if (!isTauri()) return readLocal(prefix)
+  return invoke<Record<string, unknown>>('get_ui_prefs', { prefix })
+}
+
+/**
+ * 保存偏好，可在拖动等连续事件中直接调用
+ */
+export function setUiPref(key: string, value: unknown): void {
+  const valueJson = JSON.stringify(value)
+  if (!isTauri()) {
+    try {
+      localStorage.setItem(key, valueJson)
+    } catch {
+      // ignore
+    }
+    return
+  }
+  invoke('set_ui_pref', { key, valueJson })
+    .catch((error) => handleAppError({ error, context: '保存界面偏好', silent: true }))
+}
+
+/**
+ * 导出全部偏好到 JSON 文件
+ */
+export async function exportUiPrefs(path: string): Promise<void> {
+  await invoke('export_ui_prefs', { path })
+}
+
+/**
+ * 从 JSON 文件导入偏好（覆盖同名键），返回导入的数量
+ */
+export async function importUiPrefs(path: string): Promise<number> {
+  return invoke<number>('import_ui_prefs', { path })
+}
@@ -0,0 +1,68 @@
+/**
+ * 执行看门狗
+ * 执行过程中上报心跳（report_execution_heartbeat），后端巡检发现运行中的执行超时无心跳时
+ * 发出 execution:watchdog_triggered 事件，由执行引擎中止当前请求。仅 Tauri 环境存在。
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { logError } from '@/lib/errors'
+
+// 看门狗触发事件负载，与后端 StalledExecution 一致
+export interface StalledExecution {
+  execution_id: string
+  workflow_id: string
+  node_id: string | null
+  node_name: string | null
+  last_heartbeat_at: string
+}
+
+function isTauri(): boolean {
+  return typeof window !== 'undefined' && Boolean(window.__TAURI_INTERNALS__)
+}
+
+/**
+ * 创建执行的心跳上报函数：上一次上报未完成时只保留最新一次，避免流式输出时请求堆积。
+ * 非 Tauri 环境不上报
+ */
+export function createHeartbeatReporter(executionId: string): (nodeId: string) => void {
+  if (!isTauri()) return () => {}
+  const report = (nodeId: string) => invoke('report_execution_heartbeat', { executionId, nodeId })
+  let inFlight = false
+  let pending: string | null = null
+
+  const send = (nodeId: string) => {
+    inFlight = true
+    report(nodeId)
+      .catch(error => logError({ error, context: '上报执行心跳' }))
+      .finally(() => {
+        inFlight = false
+        if (pending !== null) {
+          const next = pending
+          pending = null
+          send(next)
+        }
+      })
+  }
+
+  return (nodeId: string) => {
+    if (inFlight) {
+      pending = nodeId
+    } else {
+      send(nodeId)
+    }
+  }
+}
+
+/**
+ * 监听指定执行的看门狗触发事件，返回取消监听函数；非 Tauri 环境不监听
+ */
+export async function onWatchdogTriggered(
+  executionId: string,
+  handler: (stalled: StalledExecution) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri()) return () => {}
+  return listen<StalledExecution>('execution:watchdog_triggered', (event) => {
+    if (event.payload.execution_id === executionId) handler(event.payload)
+  })
+}
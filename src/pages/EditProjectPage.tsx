@@ -20,6 +20,7 @@ export function EditProjectPage({ projectId, onNavigate }: EditProjectPageProps)
   const { projects, loadProjects, updateProject } = useProjectStore()
   const [name, setName] = useState('')
   const [description, setDescription] = useState('')
+  const [author, setAuthor] = useState('')
   const [isSaving, setIsSaving] = useState(false)
   const [isLoading, setIsLoading] = useState(true)
 
@@ -34,6 +35,7 @@ export function EditProjectPage({ projectId, onNavigate }: EditProjectPageProps)
       if (project) {
         setName(project.name)
         setDescription(project.description || '')
+        setAuthor(project.author || '')
       } else {
         toast.error('项目不存在')
         onNavigate('/')
@@ -56,6 +58,7 @@ export function EditProjectPage({ projectId, onNavigate }: EditProjectPageProps)
       await updateProject(projectId, {
         name: name.trim(),
         description: description.trim() || undefined,
+        author: author.trim(),
       })
       toast.success('项目已更新')
       onNavigate(`/project/${projectId}`)
@@ -126,6 +129,17 @@ export function EditProjectPage({ projectId, onNavigate }: EditProjectPageProps)
                     />
                   </div>
 
+                  <div className="space-y-2">
+                    <Label htmlFor="author">作者署名</Label>
+                    <Input
+                      id="author"
+                      placeholder="汇编全书草稿时使用"
+                      value={author}
+                      onChange={(e) => setAuthor(e.target.value)}
+                      autoComplete="off"
+                    />
+                  </div>
+
                   <div className="flex justify-end gap-3">
                     <Button
                       type="button"
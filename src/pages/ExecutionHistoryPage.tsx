@@ -68,6 +68,7 @@ interface ExecutionHistoryPageProps {
 
 // 执行状态配置
 const statusConfig: Record<ExecutionStatus, { label: string; icon: React.ComponentType<{ className?: string }>; color: string }> = {
+  queued: { label: '排队中', icon: Clock, color: 'text-slate-500 bg-slate-500/10' },
   running: { label: '执行中', icon: Play, color: 'text-blue-500 bg-blue-500/10' },
   paused: { label: '已暂停', icon: StopCircle, color: 'text-yellow-500 bg-yellow-500/10' },
   completed: { label: '已完成', icon: CheckCircle2, color: 'text-green-500 bg-green-500/10' },
@@ -14,7 +14,9 @@ import {
   Download,
   Upload,
   MoreHorizontal,
+  Lock,
 } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
 import { Button } from '@/components/ui/button'
 import {
   Card,
@@ -54,8 +56,8 @@ import { useSettingsStore } from '@/stores/settings-store'
 import { exportProjectToFile, exportSettingsToFile, importSettingsFromFile } from '@/lib/import-export'
 import { getErrorMessage, handleAppError } from '@/lib/errors'
 import { toast } from 'sonner'
-import type { Workflow } from '@/types'
-import { CreateWorkflowDialog } from '@/components/dialogs'
+import type { Project, Workflow } from '@/types'
+import { CreateWorkflowDialog, ExportReviewCopyDialog } from '@/components/dialogs'
 
 interface ProjectPageProps {
   projectId: string
@@ -77,6 +79,7 @@ export function ProjectPage({ projectId, onNavigate }: ProjectPageProps) {
   const { settings, loadSettings } = useSettingsStore()
   const [workflowToDelete, setWorkflowToDelete] = useState<Workflow | null>(null)
   const [showCreateWorkflowDialog, setShowCreateWorkflowDialog] = useState(false)
+  const [showReviewCopyDialog, setShowReviewCopyDialog] = useState(false)
 
   useEffect(() => {
     // 加载项目和工作流
@@ -125,6 +128,25 @@ export function ProjectPage({ projectId, onNavigate }: ProjectPageProps) {
     }
   }
 
+  // 解除审阅副本的只读限制
+  const handleConvertToEditable = async () => {
+    if (!currentProject) return
+    try {
+      const project = await invoke<Project>('convert_to_editable', { projectId: currentProject.id })
+      useProjectStore.setState((state) => ({
+        projects: state.projects.map((p) => (p.id === project.id ? project : p)),
+        currentProject: state.currentProject?.id === project.id ? project : state.currentProject,
+      }))
+      toast.success('已转换为可编辑项目')
+    } catch (error) {
+      handleAppError({
+        error,
+        context: '转换为可编辑项目',
+        toastMessage: `转换失败: ${getErrorMessage(error)}`,
+      })
+    }
+  }
+
   // 导出设定库
   const handleExportSettings = async () => {
     if (!currentProject) return
@@ -217,6 +239,10 @@ export function ProjectPage({ projectId, onNavigate }: ProjectPageProps) {
               <Upload className="mr-2 h-4 w-4" />
               导出项目备份
             </DropdownMenuItem>
+            <DropdownMenuItem onClick={() => setShowReviewCopyDialog(true)}>
+              <Upload className="mr-2 h-4 w-4" />
+              导出审阅副本
+            </DropdownMenuItem>
             <DropdownMenuItem onClick={handleExportSettings}>
               <Upload className="mr-2 h-4 w-4" />
               导出设定库
@@ -244,6 +270,19 @@ export function ProjectPage({ projectId, onNavigate }: ProjectPageProps) {
 
       <div className="flex-1 overflow-auto">
         <div className="mx-auto max-w-6xl px-6 py-8">
+          {/* 审阅副本只读提示 */}
+          {currentProject.read_only && (
+            <div className="mb-6 flex items-center justify-between gap-4 rounded-lg border bg-muted/50 px-4 py-3">
+              <div className="flex items-center gap-2 text-sm">
+                <Lock className="h-4 w-4 text-muted-foreground" />
+                这是只读的审阅副本，不能编辑或运行
+              </div>
+              <Button size="sm" variant="outline" onClick={handleConvertToEditable}>
+                转换为可编辑项目
+              </Button>
+            </div>
+          )}
+
           {/* 概览仪表盘 */}
           <motion.div
             initial={{ opacity: 0, y: 20 }}
@@ -513,6 +552,15 @@ export function ProjectPage({ projectId, onNavigate }: ProjectPageProps) {
           onNavigate(`/project/${projectId}/workflow/${workflowId}`)
         }
       />
+
+      {/* 导出审阅副本 */}
+      <ExportReviewCopyDialog
+        open={showReviewCopyDialog}
+        onOpenChange={setShowReviewCopyDialog}
+        projectId={currentProject.id}
+        projectName={currentProject.name}
+        settings={settings}
+      />
     </div>
   )
 }
@@ -52,6 +52,7 @@ import type { GlobalConfig, AIProvider, CustomModel, Theme } from '@/types'
 import { getBuiltinModelsByProvider, getAvailableModels, testProviderConnection } from '@/lib/ai'
 import { cn } from '@/lib/utils'
 import { getErrorMessage, handleAppError } from '@/lib/errors'
+import { ensureNetworkAllowed } from '@/lib/network'
 import { useThemeStore } from '@/stores/theme-store'
 import { Tour } from '@/components/help/Tour'
 import { AI_CONFIG_TOUR_STEPS } from '@/tours'
@@ -263,6 +264,7 @@ export function SettingsPage({ onNavigate }: SettingsPageProps) {
     setUpdateChecking(true)
     setUpdateAvailable(null)
     try {
+      await ensureNetworkAllowed('updater')
       const update = await check()
       if (update) {
         setUpdateAvailable(update)
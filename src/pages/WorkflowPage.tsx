@@ -59,6 +59,7 @@ import { useHotkeys, HOTKEY_PRESETS, useWorkflowHistory, useRunButtonState } fro
 import { toast } from 'sonner'
 import { Tour } from '@/components/help/Tour'
 import { WORKFLOW_TOUR_STEPS } from '@/tours'
+import { getUiPrefs, setUiPref } from '@/lib/ui-prefs'
 import type { WorkflowNode, NodeType, GlobalConfig } from '@/types'
 import {
   AlertDialog,
@@ -90,6 +91,9 @@ interface WorkflowPageProps {
   onNavigate: (path: string) => void
 }
 
+// 输出面板折叠状态的界面偏好键
+const OUTPUT_PANEL_PREF = 'workflow.output_panel_collapsed'
+
 export function WorkflowPage({ projectId, workflowId, onNavigate }: WorkflowPageProps) {
   const {
     currentWorkflow,
@@ -132,15 +136,14 @@ export function WorkflowPage({ projectId, workflowId, onNavigate }: WorkflowPage
   const [showInputDialog, setShowInputDialog] = useState(false)
   const [nodeToDelete, setNodeToDelete] = useState<string | null>(null)
   
-  // 输出面板折叠状态（从 localStorage 读取初始值）
-  const [isOutputPanelCollapsed, setIsOutputPanelCollapsed] = useState(() => {
-    try {
-      const saved = localStorage.getItem('workflow-output-panel-collapsed')
-      return saved === 'true'
-    } catch {
-      return false
-    }
-  })
+  // 输出面板折叠状态（保存在界面偏好中）
+  const [isOutputPanelCollapsed, setIsOutputPanelCollapsed] = useState(false)
+
+  useEffect(() => {
+    getUiPrefs(OUTPUT_PANEL_PREF)
+      .then((prefs) => setIsOutputPanelCollapsed(prefs[OUTPUT_PANEL_PREF] === true))
+      .catch((error) => handleAppError({ error, context: '读取界面偏好', silent: true }))
+  }, [])
   
   // 版本历史状态
   const [versions, setVersions] = useState<WorkflowVersion[]>([])
@@ -243,11 +246,7 @@ export function WorkflowPage({ projectId, workflowId, onNavigate }: WorkflowPage
   const toggleOutputPanel = () => {
     setIsOutputPanelCollapsed(prev => {
       const newValue = !prev
-      try {
-        localStorage.setItem('workflow-output-panel-collapsed', String(newValue))
-      } catch {
-        // ignore
-      }
+      setUiPref(OUTPUT_PANEL_PREF, newValue)
       return newValue
     })
   }
@@ -256,11 +255,7 @@ export function WorkflowPage({ projectId, workflowId, onNavigate }: WorkflowPage
   useEffect(() => {
     if (isExecuting && isOutputPanelCollapsed) {
       setIsOutputPanelCollapsed(false)
-      try {
-        localStorage.setItem('workflow-output-panel-collapsed', 'false')
-      } catch {
-        // ignore
-      }
+      setUiPref(OUTPUT_PANEL_PREF, false)
     }
   }, [isExecuting, isOutputPanelCollapsed])
   
@@ -29,12 +29,28 @@ vi.mock("@/lib/engine", () => {
   }
 })
 
+// Mock Tauri 命令调用
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}))
+
+// Mock AI 执行标题
+vi.mock("@/lib/ai/execution-title", () => ({
+  generateExecutionTitle: vi.fn().mockResolvedValue(null),
+}))
+
 // Mock 数据库模块
 vi.mock("@/lib/db", () => ({
   createExecution: vi.fn(),
   updateExecution: vi.fn(),
   createNodeResult: vi.fn(),
   updateNodeResult: vi.fn(),
+  createVariableSnapshot: vi.fn(),
+  getWorkflow: vi.fn(),
+  getNodes: vi.fn(),
+  getGlobalConfig: vi.fn(),
+  getSettings: vi.fn(),
+  getSettingPrompts: vi.fn(),
 }))
 
 // 导入 mock 后的模块
@@ -164,6 +180,19 @@ describe("ExecutionStore - 执行控制", () => {
       expect(mockExecutorInstance.cancel).toHaveBeenCalled()
     })
 
+    it("离线模式下应拒绝启动包含 AI 节点的执行", async () => {
+      const workflow = createMockWorkflow()
+      const nodes = [createMockNode(), createMockNode({ type: "ai_chat", name: "AI 对话" })]
+      const globalConfig = { ...createMockGlobalConfig(), offline_mode: true }
+
+      await expect(
+        useExecutionStore.getState().startExecution(workflow, nodes, globalConfig)
+      ).rejects.toThrow("离线模式已开启")
+
+      expect(db.createExecution).not.toHaveBeenCalled()
+      expect(WorkflowExecutor).not.toHaveBeenCalled()
+    })
+
     it("应该重置执行状态", async () => {
       // 设置一些已存在的状态
       useExecutionStore.setState({
@@ -272,6 +301,60 @@ describe("ExecutionStore - 执行控制", () => {
     })
   })
 
+  describe("runQueuedExecution", () => {
+    it("应该以排队的执行记录运行工作流", async () => {
+      const workflow = createMockWorkflow({ id: "workflow-1" })
+      const nodes = [createMockNode()]
+      const execution = {
+        id: "execution-queued",
+        workflow_id: "workflow-1",
+        status: "running",
+        input: "排队输入",
+        started_at: new Date().toISOString(),
+      }
+
+      vi.mocked(db.getWorkflow).mockResolvedValue(workflow)
+      vi.mocked(db.getNodes).mockResolvedValue(nodes)
+      vi.mocked(db.getGlobalConfig).mockResolvedValue(createMockGlobalConfig())
+      vi.mocked(db.getSettings).mockResolvedValue([])
+      vi.mocked(db.getSettingPrompts).mockResolvedValue([])
+      mockExecutorInstance.execute.mockResolvedValue({
+        status: "completed",
+        output: "排队结果",
+        nodeStates: [],
+        elapsedSeconds: 1,
+      })
+
+      await useExecutionStore.getState().runQueuedExecution(execution as any)
+
+      expect(db.createExecution).not.toHaveBeenCalled()
+      expect(WorkflowExecutor).toHaveBeenCalledWith(
+        expect.objectContaining({ workflow, nodes, initialInput: "排队输入" })
+      )
+      expect(db.updateExecution).toHaveBeenCalledWith("execution-queued", expect.objectContaining({
+        status: "completed",
+        final_output: "排队结果",
+      }))
+      expect(useExecutionStore.getState().executionId).toBe("execution-queued")
+    })
+
+    it("工作流不存在时应把排队的执行标记为失败", async () => {
+      vi.mocked(db.getWorkflow).mockResolvedValue(null)
+
+      await useExecutionStore.getState().runQueuedExecution({
+        id: "execution-orphan",
+        workflow_id: "missing",
+        status: "running",
+        started_at: new Date().toISOString(),
+      })
+
+      expect(WorkflowExecutor).not.toHaveBeenCalled()
+      expect(db.updateExecution).toHaveBeenCalledWith("execution-orphan", expect.objectContaining({
+        status: "failed",
+      }))
+    })
+  })
+
   describe("pauseExecution", () => {
     it("应该暂停执行", () => {
       useExecutionStore.setState({ executor: mockExecutorInstance as any, status: "running" })
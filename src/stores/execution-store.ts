@@ -1,7 +1,7 @@
 // 执行状态管理
 
 import { create } from 'zustand'
-import type { WorkflowNode, Workflow, GlobalConfig, Setting, SettingPrompt } from '@/types'
+import type { WorkflowNode, Workflow, GlobalConfig, Setting, SettingPrompt, ChapterLengthRange, Execution } from '@/types'
 import {
   WorkflowExecutor,
   ExecutorStatus,
@@ -10,8 +10,14 @@ import {
   ResolvedNodeConfig,
   executorStatusToDbStatus,
 } from '@/lib/engine'
+import { invoke } from '@tauri-apps/api/core'
 import * as db from '@/lib/db'
+import { generateExecutionTitle } from '@/lib/ai/execution-title'
 import { logError } from '@/lib/errors'
+import { ensureNetworkAllowed, isOfflineModeError } from '@/lib/network'
+import { ensureWithinBudget, ensureWithinDailyLimit } from '@/lib/budget'
+import { createHeartbeatReporter, onWatchdogTriggered } from '@/lib/watchdog'
+import { recordAppEvent } from '@/lib/analytics'
 
 // 节点输出显示信息
 export interface NodeOutputInfo {
@@ -65,8 +71,10 @@ interface ExecutionState {
     globalConfig: GlobalConfig,
     initialInput?: string,
     settings?: Setting[],
-    settingPrompts?: SettingPrompt[]
+    settingPrompts?: SettingPrompt[],
+    presetId?: string
   ) => Promise<void>
+  runQueuedExecution: (execution: Execution) => Promise<void>
   pauseExecution: () => void
   resumeExecution: () => void
   cancelExecution: () => void
@@ -89,7 +97,16 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
   elapsedSeconds: 0,
 
   // 开始执行
-  startExecution: async (workflow, nodes, globalConfig, initialInput, settings, settingPrompts) => {
+  startExecution: async (workflow, nodes, globalConfig, initialInput, settings, settingPrompts, presetId) => {
+    // 离线模式下不启动包含 AI 节点的执行，也不创建执行记录
+    if (nodes.some(node => node.type === 'ai_chat')) {
+      await ensureNetworkAllowed('ai_execution', globalConfig)
+    }
+    // 项目 AI 费用达到预算时不启动执行
+    await ensureWithinBudget(workflow.project_id)
+    // 工作流当天执行次数达到每日上限时不启动执行
+    await ensureWithinDailyLimit(workflow.id)
+
     const { executor: existingExecutor } = get()
     
     // 如果已有执行器在运行，先取消
@@ -97,78 +114,59 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
       existingExecutor.cancel()
     }
 
+    // 使用输入预设时由后端解析为实际文本，执行记录保存实际使用的输入
+    if (presetId) {
+      initialInput = (await invoke<string | null>('resolve_execution_input', {
+        workflowId: workflow.id,
+        input: initialInput ?? null,
+        presetId,
+      })) ?? undefined
+    }
+
     // 创建执行记录
     const execution = await db.createExecution(workflow.id, initialInput)
+    notifyWebhook(execution.id)
 
-    // 重置状态
-    set({
-      executionId: execution.id,
-      status: 'running',
-      currentNodeIndex: null,
-      nodeStates: [],
-      nodeOutputs: [],
-      finalOutput: '',
-      streamingContent: '',
-      streamingNodeId: null,
-      error: null,
-      elapsedSeconds: 0,
-    })
-
-    // 清空节点结果 ID 映射
-    nodeResultIds.clear()
-
-    // 创建执行器
-    const executor = new WorkflowExecutor({
-      workflow,
-      nodes,
-      globalConfig,
-      initialInput,
-      settings,
-      settingPrompts,
-      onEvent: (event) => {
-        // 优先同步处理纯 UI 更新事件（减少延迟）
-        const handled = handleExecutionEventSync(event, get, set)
-        if (!handled) {
-          // 需要数据库操作的事件通过队列串行处理，避免竞态
-          enqueueEvent(() => handleExecutionEvent(event, get, set))
-        }
-      },
-    })
-
-    set({ executor })
+    await runExecution(execution.id, workflow, nodes, globalConfig, initialInput, settings, settingPrompts, get, set)
+  },
 
-    // 执行
+  // 运行从执行队列取出的执行（后端已将记录标记为 running）
+  runQueuedExecution: async (execution) => {
     try {
-      const result = await executor.execute()
-      
-      // 更新数据库记录
-      await db.updateExecution(execution.id, {
-        status: executorStatusToDbStatus(result.status),
-        final_output: result.output,
-        variables_snapshot: executor.getContext().createSnapshot(),
-        finished_at: new Date().toISOString(),
-      })
-
-      set({
-        status: result.status,
-        finalOutput: result.output || '',
-        error: result.error || null,
-        elapsedSeconds: result.elapsedSeconds,
-        nodeStates: result.nodeStates,
-      })
+      const workflow = await db.getWorkflow(execution.workflow_id)
+      if (!workflow) {
+        throw new Error(`工作流不存在: ${execution.workflow_id}`)
+      }
+      const [nodes, globalConfig, settings, settingPrompts] = await Promise.all([
+        db.getNodes(workflow.id),
+        db.getGlobalConfig(),
+        db.getSettings(workflow.project_id),
+        db.getSettingPrompts(workflow.project_id),
+      ])
+      if (nodes.some(node => node.type === 'ai_chat')) {
+        await ensureNetworkAllowed('ai_execution', globalConfig)
+      }
+      notifyWebhook(execution.id)
+
+      await runExecution(
+        execution.id,
+        workflow,
+        nodes,
+        globalConfig,
+        execution.input ?? undefined,
+        settings,
+        settingPrompts,
+        get,
+        set,
+      )
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : String(error)
-      
-      // 更新数据库记录
+      // 未能开始运行的排队执行标记为失败，避免一直占用队列
+      logError({ error, context: '运行排队的执行' })
       await db.updateExecution(execution.id, {
         status: 'failed',
         finished_at: new Date().toISOString(),
       })
-
-      set({
-        status: 'failed',
-        error: errorMessage,
-      })
+      notifyWebhook(execution.id)
     }
   },
 
@@ -202,7 +200,7 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
         db.updateExecution(executionId, {
           status: 'cancelled',
           finished_at: new Date().toISOString(),
-        })
+        }).then(() => notifyWebhook(executionId))
 
         // 清理孤儿节点结果：将所有未完成的 node_results 标记为 failed
         for (const [, resultId] of nodeResultIds) {
@@ -263,6 +261,200 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
 // 节点结果 ID 映射（用于追踪数据库记录）
 const nodeResultIds: Map<string, string> = new Map()
 
+/**
+ * 以已创建的执行记录运行工作流，结束后更新记录、生成标题并通知 Webhook
+ */
+async function runExecution(
+  executionId: string,
+  workflow: Workflow,
+  nodes: WorkflowNode[],
+  globalConfig: GlobalConfig,
+  initialInput: string | undefined,
+  settings: Setting[] | undefined,
+  settingPrompts: SettingPrompt[] | undefined,
+  get: () => ExecutionState,
+  set: (state: Partial<ExecutionState>) => void
+) {
+  // 项目滚动梗概，供提示词中的 {{synopsis}} 引用
+  const synopsis = await loadProjectSynopsis(workflow.project_id)
+  // 项目章节字数范围，开启检查时由最后一个 AI 节点的输出守卫执行
+  const chapterLength = await loadChapterLengthRange(workflow.project_id)
+
+  // 重置状态
+  set({
+    executionId,
+    status: 'running',
+    currentNodeIndex: null,
+    nodeStates: [],
+    nodeOutputs: [],
+    finalOutput: '',
+    streamingContent: '',
+    streamingNodeId: null,
+    error: null,
+    elapsedSeconds: 0,
+  })
+
+  // 清空节点结果 ID 映射
+  nodeResultIds.clear()
+
+  // 创建执行器
+  const executor = new WorkflowExecutor({
+    workflow,
+    nodes,
+    globalConfig,
+    initialInput,
+    settings,
+    settingPrompts,
+    synopsis,
+    chapterLength,
+    onHeartbeat: createHeartbeatReporter(executionId),
+    onEvent: (event) => {
+      // 优先同步处理纯 UI 更新事件（减少延迟）
+      const handled = handleExecutionEventSync(event, get, set)
+      if (!handled) {
+        // 需要数据库操作的事件通过队列串行处理，避免竞态
+        enqueueEvent(() => handleExecutionEvent(event, get, set))
+      }
+    },
+  })
+
+  set({ executor })
+
+  // 看门狗发现执行长时间没有心跳时，中止进行中的请求（按节点重试设置处理）
+  const unlistenWatchdog = await onWatchdogTriggered(executionId, () => executor.abortStalled())
+  recordAppEvent('execution_started', { workflow_id: workflow.id })
+
+  // 执行
+  try {
+    const result = await executor.execute()
+    
+    // 更新数据库记录
+    await db.updateExecution(executionId, {
+      status: executorStatusToDbStatus(result.status),
+      final_output: result.output,
+      variables_snapshot: executor.getContext().createSnapshot(),
+      finished_at: new Date().toISOString(),
+    })
+
+    // 生成执行标题：优先 AI 标题（需在全局配置中开启），否则使用 Rust 端的启发式标题
+    // 标题生成后再通知 Webhook，使通知中带上标题
+    titleExecution(executionId, result.output || '', globalConfig)
+      .then(() => notifyWebhook(executionId))
+
+    set({
+      status: result.status,
+      finalOutput: result.output || '',
+      error: result.error || null,
+      elapsedSeconds: result.elapsedSeconds,
+      nodeStates: result.nodeStates,
+    })
+    recordAppEvent('execution_finished', { workflow_id: workflow.id, status: executorStatusToDbStatus(result.status) })
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error)
+    
+    // 更新数据库记录
+    await db.updateExecution(executionId, {
+      status: 'failed',
+      finished_at: new Date().toISOString(),
+    })
+    notifyWebhook(executionId)
+
+    set({
+      status: 'failed',
+      error: errorMessage,
+    })
+    recordAppEvent('execution_finished', { workflow_id: workflow.id, status: 'failed' })
+  } finally {
+    unlistenWatchdog()
+  }
+}
+
+// 执行队列的轮询间隔：排队的执行可能来自本地 HTTP API 或命令行，需定期检查
+const QUEUE_POLL_INTERVAL_MS = 5000
+
+/**
+ * 启动执行队列消费：引擎空闲时取出优先级最高的排队执行（claim_next_execution）并运行，
+ * 运行结束后立即检查下一条。返回停止函数；仅 Tauri 环境使用
+ */
+export function startExecutionQueueWorker(): () => void {
+  let stopped = false
+  let claiming = false
+
+  const drain = async () => {
+    if (stopped || claiming) return
+    claiming = true
+    try {
+      while (!stopped) {
+        const { status } = useExecutionStore.getState()
+        if (status === 'running' || status === 'paused') break
+        const execution = await invoke<Execution | null>('claim_next_execution')
+        if (!execution) break
+        await useExecutionStore.getState().runQueuedExecution(execution)
+      }
+    } catch (error) {
+      logError({ error, context: '读取执行队列' })
+    } finally {
+      claiming = false
+    }
+  }
+
+  const timer = setInterval(drain, QUEUE_POLL_INTERVAL_MS)
+  drain()
+  return () => {
+    stopped = true
+    clearInterval(timer)
+  }
+}
+
+/**
+ * 为完成的执行生成标题，失败不影响执行结果
+ */
+async function titleExecution(executionId: string, output: string, globalConfig: GlobalConfig) {
+  try {
+    const aiTitle = await generateExecutionTitle(output, globalConfig)
+    if (aiTitle) {
+      await invoke('rename_execution', { executionId, title: aiTitle })
+    } else {
+      await invoke('auto_title_execution', { executionId })
+    }
+  } catch (error) {
+    logError({ error, context: '生成执行标题' })
+  }
+}
+
+// 加载项目滚动梗概，失败时按空梗概执行
+async function loadProjectSynopsis(projectId: string): Promise<string> {
+  try {
+    const synopsis = await invoke<{ content: string } | undefined>('get_project_synopsis', { projectId })
+    return synopsis?.content ?? ''
+  } catch (error) {
+    logError({ error, context: '加载项目梗概' })
+    return ''
+  }
+}
+
+// 加载项目章节字数范围，失败时不检查字数
+async function loadChapterLengthRange(projectId: string): Promise<ChapterLengthRange | undefined> {
+  try {
+    return await invoke<ChapterLengthRange | undefined>('get_chapter_length_range', { projectId })
+  } catch (error) {
+    logError({ error, context: '加载章节字数范围' })
+    return undefined
+  }
+}
+
+/**
+ * 执行状态变化后通知工作流与项目 Webhook（后端异步投递并记录结果），失败不影响执行结果
+ */
+async function notifyWebhook(executionId: string) {
+  try {
+    await invoke('deliver_execution_webhook', { executionId })
+  } catch (error) {
+    // 离线模式下后端拒绝投递，无需记录
+    if (!isOfflineModeError(error)) logError({ error, context: '通知 Webhook' })
+  }
+}
+
 // 处理执行事件
 async function handleExecutionEvent(
   event: ExecutionEvent,
@@ -332,12 +524,24 @@ async function handleExecutionEvent(
               finished_at: new Date().toISOString(),
               resolved_config: event.resolvedConfig,
               token_usage: event.usage,
+              first_token_at: event.firstTokenAt?.toISOString(),
+              post_processing: event.postProcessing,
+              output_guard: event.outputGuard,
             })
           } catch (error) {
             logError({ error, context: '更新节点结果' })
           }
         }
       }
+
+      // 保存变量快照（用于变量追踪）
+      if (executionId && event.nodeId && event.variables) {
+        try {
+          await db.createVariableSnapshot(executionId, event.nodeId, event.variables)
+        } catch (error) {
+          logError({ error, context: '保存变量快照' })
+        }
+      }
       break
     }
 
@@ -363,6 +567,7 @@ async function handleExecutionEvent(
               output: `错误: ${event.error}`,
               status: 'failed',
               finished_at: new Date().toISOString(),
+              output_guard: event.outputGuard,
             })
           } catch (error) {
             logError({ error, context: '更新节点结果' })
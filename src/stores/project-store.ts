@@ -48,7 +48,7 @@ interface ProjectState {
   loadProjects: () => Promise<void>
   loadGlobalStats: () => Promise<void>
   createProject: (name: string, description?: string) => Promise<Project>
-  updateProject: (id: string, data: Partial<Pick<Project, 'name' | 'description'>>) => Promise<void>
+  updateProject: (id: string, data: Partial<Pick<Project, 'name' | 'description' | 'author'>>) => Promise<void>
   deleteProject: (id: string) => Promise<void>
   setCurrentProject: (project: Project | null) => void
 
@@ -56,7 +56,7 @@ interface ProjectState {
   loadWorkflows: (projectId: string) => Promise<void>
   loadProjectStats: (projectId: string) => Promise<void>
   createWorkflow: (name: string, description?: string) => Promise<Workflow | null>
-  updateWorkflow: (id: string, data: Partial<Pick<Workflow, 'name' | 'description' | 'loop_max_count' | 'timeout_seconds'>>) => Promise<void>
+  updateWorkflow: (id: string, data: Partial<Pick<Workflow, 'name' | 'description' | 'loop_max_count' | 'timeout_seconds' | 'context_mode' | 'context_turn_limit' | 'context_token_budget'>>) => Promise<void>
   deleteWorkflow: (id: string) => Promise<void>
   setCurrentWorkflow: (workflow: Workflow | null) => void
 
@@ -77,6 +77,7 @@ vi.mock("@/lib/db", () => ({
   exportSettings: vi.fn().mockResolvedValue({ settings: [], setting_prompts: [] }),
   importSettings: vi.fn().mockResolvedValue(undefined),
   exportProject: vi.fn().mockResolvedValue(null),
+  exportProjectPartial: vi.fn().mockResolvedValue(null),
   importProject: vi.fn().mockResolvedValue({ id: "mock-id" }),
   
   // 版本历史
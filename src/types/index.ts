@@ -3,6 +3,9 @@ export interface Project {
   id: string
   name: string
   description?: string
+  author?: string | null  // 作者署名
+  read_only?: boolean     // 只读审阅副本，写入被拒绝（convert_to_editable 解除）
+  budget_usd?: number | null  // AI 费用上限（USD），已花费达到后不能开始新的执行
   created_at: string
   updated_at: string
 }
@@ -15,10 +18,50 @@ export interface Workflow {
   description?: string
   loop_max_count: number
   timeout_seconds: number
+  // 上下文模式：stitched 拼接提示词，chat_history 将前序 AI 节点作为对话历史
+  context_mode?: WorkflowContextMode
+  // chat_history 模式保留的最大轮数
+  context_turn_limit?: number
+  // chat_history 模式的 token 预算（0 表示不限制）
+  context_token_budget?: number
+  // 执行结束后通知的 Webhook 地址（签名密钥只保存在后端）
+  webhook_url?: string | null
+  // Webhook 请求体是否附带完整输出
+  webhook_include_output?: boolean
+  // 输出后处理规则
+  post_processing?: PostProcessingConfig | null
+  // 每日执行上限（null 表示不限制）
+  daily_execution_limit?: number | null
   created_at: string
   updated_at: string
 }
 
+// 工作流上下文模式
+export type WorkflowContextMode = 'stitched' | 'chat_history'
+
+// 后处理作用范围：node_outputs 为 AI 节点输出，final_output 为输出节点
+export type PostProcessingScope = 'node_outputs' | 'final_output' | 'both'
+
+// 后处理规则，按列表顺序执行
+export type PostProcessingRule =
+  | { type: 'strip_meta_prefix'; patterns?: string[] }  // 未填写模式时使用默认开场白模式
+  | { type: 'strip_code_fences' }
+  | { type: 'normalize_ellipsis' }
+  | { type: 'normalize_quotes' }
+  | { type: 'collapse_blank_lines' }
+
+// 工作流的输出后处理配置
+export interface PostProcessingConfig {
+  scope?: PostProcessingScope
+  rules: PostProcessingRule[]
+}
+
+// 实际改动了输出的规则（替换导致变长时 chars_removed 为负）
+export interface PostProcessingApplication {
+  rule: PostProcessingRule['type']
+  chars_removed: number
+}
+
 // 节点类型枚举
 export type NodeType =
   // 基础节点
@@ -54,6 +97,9 @@ export interface WorkflowNode {
   // 块结构支持
   block_id?: string          // 块 ID（用于关联开始和结束节点）
   parent_block_id?: string   // 父块 ID（用于嵌套结构）
+  description?: string       // 节点说明（画布提示）
+  group_label?: string       // 编辑器分组标签（执行时忽略）
+  group_color?: string       // 分组颜色
   created_at: string
   updated_at: string
 }
@@ -118,9 +164,51 @@ export interface ConditionEndConfig {
   condition_if_id: string
 }
 
+// 输出守卫检查项
+export type OutputGuardCheck =
+  | { type: 'min_cjk_ratio'; value: number }   // 中日韩字符占非空白字符的比例下限（0-1）
+  | { type: 'forbid_prefix'; value: string }   // 输出（忽略开头空白）不得以该前缀开头
+  | { type: 'min_length'; value: number }      // 输出最少字符数
+  | { type: 'max_length'; value: number }      // 输出最多字符数
+
+// 输出守卫未通过时的处理方式：追加纠正提示重试 / 接受并警告 / 节点失败
+export type OutputGuardAction = 'retry' | 'warn' | 'fail'
+
+// 输出守卫配置
+export interface OutputGuardConfig {
+  checks: OutputGuardCheck[]
+  action: OutputGuardAction
+  max_retries?: number  // retry 模式的最大重试次数，默认 2
+  nudge?: string        // 自定义纠正提示，未设置时根据未通过的检查项生成
+}
+
+// 单次输出的守卫评估
+export interface OutputGuardEvaluation {
+  attempt: number       // 第几次输出，从 1 开始
+  passed: boolean
+  failures: string[]    // 未通过的检查项说明
+}
+
+// 节点的守卫记录，写入 node_results.output_guard
+export interface OutputGuardReport {
+  action: OutputGuardAction
+  evaluations: OutputGuardEvaluation[]
+  retries: number
+  outcome: 'passed' | 'warned' | 'failed'
+}
+
 // AI 提供商
 export type AIProvider = 'openai' | 'gemini' | 'claude'
 
+// 节点采样参数，覆盖顶层的 temperature / top_p / max_tokens；取值范围因提供商而异
+export interface SamplingParams {
+  temperature?: number
+  top_p?: number
+  max_tokens?: number
+  presence_penalty?: number   // Claude 不支持
+  frequency_penalty?: number  // Claude 不支持
+}
+
 // AI 聊天节点配置
 export interface AIChatConfig {
   provider: AIProvider
@@ -137,6 +225,7 @@ export interface AIChatConfig {
   max_tokens?: number
   top_p?: number
   retry_count?: number
+  sampling?: SamplingParams  // 采样参数覆盖，运行前由后端 validate_workflow 校验范围
   /**
    * Gemini 3 Pro 使用的思考深度参数
    * - 'low': 浅层思考（快速响应）
@@ -159,6 +248,7 @@ export interface AIChatConfig {
   effort?: 'low' | 'medium' | 'high'
   enable_history: boolean
   history_count: number
+  output_guard?: OutputGuardConfig  // 输出守卫：校验语言与格式，不通过时重试、警告或失败
   setting_ids: string[]
   setting_injection_level?: 'minimal' | 'balanced' | 'full'
 }
@@ -302,6 +392,7 @@ export interface Setting {
   priority: SettingPriority
   keywords: string[] | null
   summary: string | null
+  facts?: Record<string, string> | null  // 结构化事实，如 { "瞳色": "蓝" }
   created_at: string
   updated_at: string
 }
@@ -325,6 +416,7 @@ export interface SettingPrompt {
   category: SettingCategory
   prompt_template: string
   enabled: boolean
+  content_template?: string | null  // 新建设定的正文模板，{{name}} 替换为设定名称
 }
 
 // 自定义模型配置
@@ -343,6 +435,12 @@ export interface AIProviderConfig {
   enabled_models?: string[]
   // 用户自定义模型
   custom_models?: CustomModel[]
+  // 余额查询接口, 未配置时额度显示为未知
+  quota_url?: string
+  // 余额字段的点分路径, 如 balance_infos.0.total_balance
+  quota_path?: string
+  // 自定义朗读接口, 未配置时使用 {base_url}/v1/audio/speech
+  tts_url?: string
 }
 
 // 全局配置
@@ -352,6 +450,9 @@ export interface GlobalConfig {
   theme: 'light' | 'dark' | 'system'
   default_loop_max: number
   default_timeout: number
+  ai_execution_title?: boolean   // 执行完成后使用 AI 生成标题
+  enable_sql_console?: boolean   // 启用 SQL 控制台（高级功能）
+  offline_mode?: boolean         // 离线模式：阻止所有对外网络请求
   setting_assistant: {
     provider: AIProvider
     model: string
@@ -365,12 +466,14 @@ export interface GlobalConfig {
 
 // 执行状态
 export type ExecutionStatus =
+  | 'queued'
   | 'running'
   | 'paused'
   | 'completed'
   | 'failed'
   | 'cancelled'
   | 'timeout'
+  | 'simulated'   // 由微调数据导入 (import_openai_fine_tune_data)
 
 // 执行记录
 export interface Execution {
@@ -382,6 +485,84 @@ export interface Execution {
   variables_snapshot?: Record<string, unknown>
   started_at: string
   finished_at?: string
+  label?: string
+  title?: string
+  chapter_id?: string | null  // 所属章节
+  scene_id?: string | null    // 所属场景
+  batch_id?: string | null    // 所属批次（enqueue_execution_batch）
+  workflow_version?: number | null  // 加入队列时工作流的最新版本号
+}
+
+// 场景正文（get_scene_execution_content），字数为各条最终输出的字符数之和
+export interface SceneContent {
+  scene_title: string
+  chapter_title: string
+  content: string
+  word_count: number
+}
+
+// 全书草稿（get_project_full_draft），章节按顺序排列
+export interface FullDraft {
+  title: string
+  author: string | null
+  chapters: Array<{ chapter_id: string; title: string; content: string }>
+}
+
+// 排队执行的优先级（高优先级先出队，同级先进先出）
+export type ExecutionPriority = 'high' | 'normal' | 'low'
+
+// 执行队列状态（get_execution_queue_status）
+export interface ExecutionQueueStatus {
+  running: number   // 运行中（含暂停）的执行数，不为 0 时排队的执行需等待
+  queued: number
+  by_priority: Record<ExecutionPriority, number>
+}
+
+// 执行活动热力图的一格（get_workflow_execution_heatmap），只包含有执行的格子
+export interface HeatmapCell {
+  day_of_week: number   // 0 为周日
+  hour_of_day: number   // 0–23（UTC）
+  count: number
+}
+
+// 输出长度分布（get_execution_output_length_distribution），以字符数近似字数
+export interface DistributionBucket {
+  min_words: number
+  max_words: number | null   // 最后一个区间没有上界
+  count: number
+}
+
+export interface LengthDistribution {
+  min_words: number
+  max_words: number
+  avg_words: number
+  median_words: number
+  buckets: DistributionBucket[]
+}
+
+// 执行的费用明细（get_execution_cost_report），按节点结果的 token 用量与模型定价计算
+export interface NodeCost {
+  node_id: string
+  node_name: string           // 节点已删除时为节点 ID
+  model: string | null
+  prompt_tokens: number
+  completion_tokens: number
+  cost_usd: number
+  priced: boolean             // 为 false 时部分用量的模型没有定价，未计入费用
+}
+
+export interface ExecutionCostReport {
+  execution_id: string
+  total_cost_usd: number
+  prompt_tokens: number
+  completion_tokens: number
+  by_node: NodeCost[]
+}
+
+// 输入完全相同的一组执行（detect_duplicate_executions），成员按开始时间排序
+export interface DuplicateExecutionGroup {
+  input_hash: string          // 输入的 SHA-256（十六进制）
+  execution_ids: string[]
 }
 
 // 解析后的节点配置（用于历史记录显示）
@@ -394,9 +575,13 @@ export interface ResolvedNodeConfig {
   temperature?: number      // 温度
   maxTokens?: number        // 最大 token 数
   topP?: number             // Top P
+  presencePenalty?: number  // 存在惩罚
+  frequencyPenalty?: number // 频率惩罚
   enableHistory?: boolean   // 是否启用对话历史
   historyCount?: number     // 对话历史轮数
   settingNames?: string[]   // 使用的设定名称列表
+  contextMode?: WorkflowContextMode  // 工作流上下文模式
+  messages?: { role: string; content: string }[]  // chat_history 模式下最终发送的消息
   
   // === 文本拼接节点 ===
   resolvedSources?: string[]  // 解析后的各个来源值
@@ -442,6 +627,9 @@ export interface NodeResult {
   status: 'pending' | 'running' | 'completed' | 'failed'
   started_at: string
   finished_at?: string
+  first_token_at?: string  // 收到首个流式片段的时间（仅 AI 节点）
+  post_processing?: PostProcessingApplication[]  // 触发的后处理规则
+  output_guard?: OutputGuardReport  // 输出守卫的评估与重试记录（仅配置了守卫的 AI 节点）
 }
 
 // 主题类型
@@ -479,6 +667,45 @@ export interface ExportedWorkflow {
   exported_at: string                  // 导出时间
   workflow: Omit<Workflow, 'id' | 'project_id' | 'created_at' | 'updated_at'>
   nodes: Array<Omit<WorkflowNode, 'id' | 'workflow_id' | 'created_at' | 'updated_at'>>
+  input_presets?: Array<Pick<InputPreset, 'name' | 'content' | 'is_default'>>
+}
+
+// 执行输入预设
+export interface InputPreset {
+  id: string
+  workflow_id: string
+  name: string
+  content: string
+  is_default: boolean
+  created_at: string
+}
+
+// 章节状态
+export type ChapterStatus = 'draft' | 'in_progress' | 'review' | 'complete'
+
+// 章节规划
+export interface Chapter {
+  id: string
+  project_id: string
+  title: string
+  synopsis: string | null
+  target_word_count: number | null
+  order_index: number
+  status: ChapterStatus
+  created_at: string
+  updated_at: string
+}
+
+// 章节内的场景，状态取值与章节一致
+export interface Scene {
+  id: string
+  chapter_id: string
+  title: string
+  description: string | null
+  order_index: number
+  status: ChapterStatus
+  created_at: string
+  updated_at: string
 }
 
 // 导出的设定数据格式
@@ -510,6 +737,42 @@ export interface ExportedProject {
   }>
   settings: ExportedSettingItem[]
   setting_prompts: Array<Omit<SettingPrompt, 'id' | 'project_id'>>
+  chapters?: ExportedChapter[]    // 仅部分导出时按选择包含
+  manifest?: ExportManifest       // 部分导出的清单，完整备份没有此字段
+}
+
+// 部分导出的选择范围
+export interface ProjectExportSelection {
+  workflow_ids: string[]
+  setting_categories: SettingCategory[]
+  include_outlines: boolean   // 章节大纲：标题、梗概、目标字数与状态
+  include_chapters: boolean   // 章节正文，同时包含章节大纲
+  include_prompts: boolean    // 设定提示词
+}
+
+// 导出的章节，content 仅在包含章节正文时存在
+export interface ExportedChapter {
+  title: string
+  synopsis: string | null
+  target_word_count: number | null
+  status: ChapterStatus
+  content?: string
+}
+
+// 部分导出的清单
+export interface ExportManifest {
+  partial: true
+  selection: ProjectExportSelection
+  warnings: string[]   // 引用一致性警告，如选中的工作流引用了未导出分类的设定
+}
+
+// 通过文件关联打开、等待确认导入的项目备份（import:suggestion 事件负载）
+export interface ImportSuggestion {
+  path: string
+  project_name: string
+  exported_at: string | null
+  read_only: boolean   // 只读审阅副本
+  encrypted: boolean   // 加密的审阅副本，导入前须输入密码
 }
 
 // 导出格式版本号
@@ -532,3 +795,280 @@ export interface WorkflowSnapshot {
   workflow: Omit<Workflow, 'id' | 'project_id' | 'created_at' | 'updated_at'>
   nodes: Array<Omit<WorkflowNode, 'id' | 'workflow_id' | 'created_at' | 'updated_at'>>
 }
+
+// 工作流节点分组（get_workflow_groups）
+export interface NodeGroup {
+  label: string
+  color: string | null
+  node_count: number
+  first_order_index: number          // 组内第一个节点的位置
+}
+
+// 版本间的分组变化（get_workflow_version_group_changes），成员为节点名称
+export interface WorkflowGroupChange {
+  label: string
+  change_kind: 'added' | 'removed' | 'changed'
+  old_color: string | null
+  new_color: string | null
+  added_members: string[]
+  removed_members: string[]
+}
+
+// 编辑器恢复草稿（get_recovery_draft），payload 为 JSON 字符串
+export interface RecoveryDraft {
+  scope_key: string
+  payload: string
+  size_bytes: number
+  updated_at: string
+}
+
+// 提供商健康指标，errors 只计提供商侧错误（不含 auth 与 bad_request）
+export interface ProviderHealthStats {
+  requests: number
+  errors: number
+  error_rate: number
+  errors_by_class: Record<string, number>
+  p50_latency_ms: number | null
+  p95_latency_ms: number | null
+  tokens_per_minute: number
+}
+
+// 提供商健康状态（get_provider_health），windows 为按小时的统计
+export interface ProviderHealth {
+  provider_id: string
+  hours: number
+  summary: ProviderHealthStats
+  windows: Array<ProviderHealthStats & { window_start: string }>
+  recent_error_rate: number | null   // 最近 10 次请求的错误率，本次启动后无请求时为 null
+  degraded: boolean
+}
+
+// provider:degraded 事件负载
+export interface ProviderDegraded {
+  provider_id: string
+  error_rate: number
+  recent_requests: number
+  top_error_class: string | null
+}
+
+// 章节锁（acquire_chapter_lock / list_active_locks），以获取锁的窗口为持有方
+export interface ChapterLock {
+  chapter_id: string
+  holder_label: string      // 显示给其他窗口的持有者名称
+  window_label: string
+  acquired_at: string
+  last_active_at: string
+  expires_at: string        // 空闲超过 chapter_lock_idle_seconds 后失效
+}
+
+// 批注目标：执行的最终输出或章节正文
+export type AnnotationTarget =
+  | { type: 'execution'; id: string }
+  | { type: 'chapter'; id: string }
+
+export type AnnotationKind = 'note' | 'issue' | 'suggestion'
+
+// 文本区间批注（list_annotations），偏移量按字符计
+export interface Annotation {
+  id: string
+  execution_id: string | null
+  chapter_id: string | null
+  start_offset: number
+  end_offset: number
+  anchor_text: string       // 创建时的区间文本，用于文本变化后重新定位
+  note: string
+  kind: AnnotationKind
+  orphaned: boolean         // 当前文本中已找不到 anchor_text
+  created_at: string
+  updated_at: string
+}
+
+// 项目预算使用情况（get_budget_status），未设置预算时相关字段为 null
+export interface BudgetStatus {
+  budget_usd: number | null
+  spent_usd: number
+  remaining_usd: number | null    // 超支时为 0
+  percentage_used: number | null  // 超支时可超过 100
+}
+
+// 章节字数超出范围时执行引擎的处理方式：不检查 / 在最后一个 AI 节点追加扩写或压缩提示重试 / 接受并警告
+export type ChapterLengthEnforcement = 'off' | 'retry' | 'warn'
+
+// 项目的章节字数范围（get_chapter_length_range），未设置的一端不限制
+export interface ChapterLengthRange {
+  min_chapter_chars: number | null
+  max_chapter_chars: number | null
+  enforcement: ChapterLengthEnforcement
+}
+
+// 章节列表项（get_project_chapter_list）
+export interface ChapterListItem extends Chapter {
+  chars: number           // 正文字数，与全书草稿一致
+  out_of_range: boolean   // 正文超出项目章节字数范围；尚未写入正文的章节为 false
+}
+
+// 项目 Webhook 可订阅的事件类型（running 状态为 started）
+export type WebhookEventType =
+  | 'execution.started'
+  | 'execution.completed'
+  | 'execution.failed'
+  | 'execution.cancelled'
+  | 'execution.timeout'
+
+// 项目 Webhook（list_webhooks），签名密钥只在 register_webhook 时返回
+export interface Webhook {
+  id: string
+  project_id: string
+  url: string
+  event_types: WebhookEventType[]
+  enabled: boolean
+  created_at: string
+}
+
+// Webhook 测试结果（test_webhook），非 2xx 响应同样返回
+export interface WebhookTestResult {
+  http_status: number
+  response_body_preview: string   // 响应正文前 500 字
+  latency_ms: number
+}
+
+// 同一批次加入队列的执行（enqueue_execution_batch）
+export interface ExecutionBatch {
+  batch_id: string
+  executions: Execution[]
+}
+
+// 批量执行报告汇总（export_batch_report），failed 含超时
+export interface BatchReportSummary {
+  batch_id: string
+  workflow_name: string | null
+  total: number
+  completed: number
+  failed: number
+  other: number
+  total_duration_ms: number
+  total_cost_usd: number
+}
+
+// 后台数据回填任务（get_background_jobs / job:progress 事件）
+export type BackgroundJobStatus = 'pending' | 'running' | 'completed' | 'failed' | 'cancelled'
+
+export interface BackgroundJob {
+  id: string
+  kind: string
+  progress: number
+  total: number
+  status: BackgroundJobStatus
+  cancellable: boolean   // 是否可以中途取消（已处理的批次保留）
+  error: string | null
+  created_at: string
+  updated_at: string
+}
+
+// API Key 访问范围，与后端 api_key::SCOPES 一致
+export type ApiKeyScope =
+  | 'projects:read'
+  | 'projects:write'
+  | 'executions:read'
+  | 'executions:write'
+  | 'settings:read'
+  | 'settings:write'
+
+// API Key（list_api_keys），不含密钥本身
+export interface ApiKey {
+  id: string
+  name: string
+  scopes: ApiKeyScope[]
+  created_at: string
+  last_used_at: string | null
+  expires_at: string | null   // 为空表示永不过期
+}
+
+// 创建 API Key 的结果（create_api_key），原始密钥只在创建时返回
+export interface ApiKeyCreated extends ApiKey {
+  key: string
+}
+
+// 本地 HTTP API 状态（get_local_server_status）
+export interface LocalServerStatus {
+  running: boolean
+  port: number | null
+  requests_served: number   // 本次启动以来处理的请求数
+  started_at: string | null
+}
+
+// 微调数据导入结果（import_openai_fine_tune_data）
+export interface ImportReport {
+  imported: number
+  execution_ids: string[]
+  skipped: { line: number; reason: string }[]   // 未导入的行 (行号从 1 开始) 与原因
+}
+
+// 叙述视角（detect_pov_violations）
+export type PovMode = 'first_person' | 'third_limited' | 'omniscient'
+
+// 视角问题（detect_pov_violations）
+export interface PovViolation {
+  sentence_index: number   // 句子序号，从 0 开始
+  sentence: string
+  reason: string
+}
+
+// 可读性指标（compute_readability_score）
+export interface ReadabilityReport {
+  flesch_kincaid_grade: number
+  avg_sentence_length: number   // 每句平均词数
+  avg_word_length: number       // 每词平均字符数
+  long_sentence_count: number
+  passive_voice_count: number
+}
+
+// 词频（detect_repetitive_words）
+export interface WordFrequency {
+  word: string
+  count: number
+  frequency_per_1000: number   // 每千词出现次数
+}
+
+// 输出文本转换（apply_text_transform），按顺序执行
+export type TextTransform =
+  | 'trim_whitespace'
+  | 'normalize_quotes'
+  | 'remove_duplicate_newlines'
+  | 'capitalize_sentences'
+  | 'fix_ellipsis'
+
+// 按 token 上限截断的结果（truncate_to_token_limit）
+export interface TruncationResult {
+  truncated_text: string
+  tokens_removed: number
+  was_truncated: boolean
+}
+
+// 长文档分块（split_long_output_into_chunks）
+export interface Chunk {
+  index: number
+  content: string
+  token_count: number
+}
+
+// 工作流当天执行次数（get_workflow_invocation_count_today），未设置上限时相关字段为 null
+export interface InvocationStats {
+  count_today: number
+  daily_limit: number | null
+  limit_remaining: number | null   // 已达上限时为 0
+}
+
+// 第一个 AI 节点的提示词预览（get_workflow_settings_context_preview），不调用 AI
+export interface WorkflowContextPreview {
+  system_prompt: string   // 含注入的设定
+  user_prompt: string
+  estimated_tokens: number
+  settings_used: string[] // 注入的设定名称
+}
+
+// 首页动态流中的执行记录（list_recent_executions_across_all_projects）
+export interface RecentExecution extends Execution {
+  project_name: string
+  workflow_name: string
+}